@@ -214,9 +214,17 @@ impl SvenAcpAgent {
         }
     }
 
-    /// Build the list of advertised [`SessionMode`]s.
-    fn advertised_modes() -> Vec<SessionMode> {
-        vec![
+    /// Build the list of advertised [`SessionMode`]s: the built-in modes
+    /// followed by the custom ones from `agent.modes`.
+    fn advertised_modes(&self) -> Vec<SessionMode> {
+        let custom = self.config.agent.modes.iter().map(|(name, mode)| {
+            let session_mode = SessionMode::new(SessionModeId::new(name.as_str()), name.as_str());
+            match &mode.description {
+                Some(desc) => session_mode.description(desc.clone()),
+                None => session_mode,
+            }
+        });
+        [
             SessionMode::new(SessionModeId::new("agent"), "Agent").description(
                 "Full agentic mode: reads, writes, executes tools autonomously".to_string(),
             ),
@@ -225,6 +233,9 @@ impl SvenAcpAgent {
             SessionMode::new(SessionModeId::new("research"), "Research")
                 .description("Research mode: reads and searches, no file writes".to_string()),
         ]
+        .into_iter()
+        .chain(custom)
+        .collect()
     }
 }
 
@@ -275,18 +286,25 @@ impl agent_client_protocol::Agent for SvenAcpAgent {
         };
 
         let mut runtime_ctx = RuntimeContext::auto_detect();
-        runtime_ctx.project_root = Some(args.cwd.clone());
+        runtime_ctx.set_project_root(args.cwd.clone());
 
         let permission_requester = Arc::new(AcpPermissionRequester {
             session_id: session_id.clone(),
             conn_tx: self.conn_tx.clone(),
         });
 
-        let agent = AgentBuilder::new(Arc::clone(&self.config))
+        let agent = match AgentBuilder::new(Arc::clone(&self.config))
             .with_runtime_context(runtime_ctx)
             .with_permission_requester(permission_requester)
             .build(initial_mode, model, profile)
-            .await;
+            .await
+        {
+            Ok(agent) => agent,
+            Err(e) => {
+                tracing::error!("ACP agent init error: {e:#}");
+                return Err(Error::internal_error());
+            }
+        };
 
         let mode_lock = agent.current_mode_lock().clone();
 
@@ -300,7 +318,7 @@ impl agent_client_protocol::Agent for SvenAcpAgent {
 
         let mode_state = SessionModeState::new(
             sven_mode_to_acp_mode_id(initial_mode),
-            Self::advertised_modes(),
+            self.advertised_modes(),
         );
 
         Ok(NewSessionResponse::new(session_id).modes(mode_state))
@@ -450,7 +468,8 @@ impl agent_client_protocol::Agent for SvenAcpAgent {
             .get_session(&session_id)
             .ok_or_else(Error::invalid_params)?;
 
-        let new_mode = acp_mode_id_to_sven_mode(&args.mode_id);
+        let new_mode = acp_mode_id_to_sven_mode(&self.config.agent, &args.mode_id)
+            .ok_or_else(Error::invalid_params)?;
         *entry.mode_lock.lock().await = new_mode;
 
         Ok(SetSessionModeResponse::new())
@@ -12,7 +12,7 @@ use agent_client_protocol::{
     PlanEntryStatus, SessionModeId, SessionUpdate, ToolCall as AcpToolCall, ToolCallStatus,
     ToolKind, UsageUpdate,
 };
-use sven_config::AgentMode;
+use sven_config::{AgentConfig, AgentMode};
 use sven_core::AgentEvent;
 use sven_tools::events::{TodoItem, TodoStatus};
 
@@ -20,22 +20,16 @@ use sven_tools::events::{TodoItem, TodoStatus};
 
 /// Convert a sven [`AgentMode`] to the matching ACP [`SessionModeId`].
 pub fn sven_mode_to_acp_mode_id(mode: AgentMode) -> SessionModeId {
-    match mode {
-        AgentMode::Research => SessionModeId::new("research"),
-        AgentMode::Plan => SessionModeId::new("plan"),
-        AgentMode::Agent => SessionModeId::new("agent"),
-    }
+    SessionModeId::new(mode.name())
 }
 
-/// Convert an ACP [`SessionModeId`] back to a sven [`AgentMode`].
-///
-/// Unknown mode IDs fall back to [`AgentMode::Agent`].
-pub fn acp_mode_id_to_sven_mode(mode_id: &SessionModeId) -> AgentMode {
-    match mode_id.0.as_ref() {
-        "research" => AgentMode::Research,
-        "plan" => AgentMode::Plan,
-        _ => AgentMode::Agent,
-    }
+/// Convert an ACP [`SessionModeId`] back to a sven [`AgentMode`] built in or
+/// defined in `config`.  Unknown mode IDs give `None`.
+pub fn acp_mode_id_to_sven_mode(
+    config: &AgentConfig,
+    mode_id: &SessionModeId,
+) -> Option<AgentMode> {
+    config.mode(mode_id.0.as_ref())
 }
 
 // ─── Event bridge ─────────────────────────────────────────────────────────────
@@ -131,7 +125,10 @@ pub fn agent_event_to_session_update(event: &AgentEvent) -> Option<SessionUpdate
         | AgentEvent::SubagentStarted { .. }
         | AgentEvent::SubagentEvent { .. }
         | AgentEvent::ModelChanged(_)
-        | AgentEvent::PeerList(_) => None,
+        | AgentEvent::ModelFallback { .. }
+        | AgentEvent::PeerList(_)
+        | AgentEvent::Paused
+        | AgentEvent::Resumed => None,
     }
 }
 
@@ -167,10 +164,13 @@ mod tests {
 
     #[test]
     fn round_trip_agent_mode() {
+        let config = AgentConfig::default();
         for mode in [AgentMode::Research, AgentMode::Plan, AgentMode::Agent] {
             let id = sven_mode_to_acp_mode_id(mode);
-            assert_eq!(acp_mode_id_to_sven_mode(&id), mode);
+            assert_eq!(acp_mode_id_to_sven_mode(&config, &id), Some(mode));
         }
+        let unknown = SessionModeId::new("undefined-mode");
+        assert_eq!(acp_mode_id_to_sven_mode(&config, &unknown), None);
     }
 
     #[test]
@@ -102,6 +102,7 @@ enum WsEvent {
 enum NodeSessionState {
     Idle,
     Running,
+    Paused,
     Completed,
     Failed,
     Cancelled,
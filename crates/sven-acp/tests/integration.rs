@@ -10,28 +10,32 @@ use agent_client_protocol::SessionUpdate;
 use sven_acp::bridge::{
     acp_mode_id_to_sven_mode, agent_event_to_session_update, sven_mode_to_acp_mode_id,
 };
-use sven_config::AgentMode;
+use sven_config::{AgentConfig, AgentMode};
 use sven_core::AgentEvent;
 
 // ─── Bridge unit tests ────────────────────────────────────────────────────────
 
 #[test]
 fn agent_mode_round_trip() {
+    let config = AgentConfig::default();
     for mode in [AgentMode::Agent, AgentMode::Plan, AgentMode::Research] {
         let id = sven_mode_to_acp_mode_id(mode);
         assert_eq!(
-            acp_mode_id_to_sven_mode(&id),
-            mode,
+            acp_mode_id_to_sven_mode(&config, &id),
+            Some(mode),
             "round-trip failed for {mode:?}"
         );
     }
 }
 
 #[test]
-fn unknown_mode_falls_back_to_agent() {
+fn unknown_mode_is_rejected() {
     use agent_client_protocol::SessionModeId;
     let unknown = SessionModeId::new("totally-unknown");
-    assert_eq!(acp_mode_id_to_sven_mode(&unknown), AgentMode::Agent);
+    assert_eq!(
+        acp_mode_id_to_sven_mode(&AgentConfig::default(), &unknown),
+        None
+    );
 }
 
 #[test]
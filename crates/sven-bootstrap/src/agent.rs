@@ -10,6 +10,7 @@
 use std::sync::Arc;
 use std::time::Duration;
 
+use anyhow::Context as _;
 use tokio::sync::{mpsc, Mutex};
 use tokio::time::Instant;
 use tracing::{info, warn};
@@ -18,7 +19,10 @@ use sven_config::{AgentMode, Config};
 use sven_core::{Agent, AgentNewParams, ModelResolver};
 use sven_mcp_client::{McpManager, McpTool};
 use sven_model::ModelProvider;
-use sven_tools::{events::ToolEvent, PermissionRequester, SharedToolDisplays, SharedTools};
+use sven_tools::{
+    events::{TodoItem, ToolEvent},
+    PermissionRequester, SharedToolDisplays, SharedTools, Tool,
+};
 
 use crate::context::{RuntimeContext, ToolSetProfile};
 use crate::registry::build_tool_registry;
@@ -49,6 +53,8 @@ pub struct AgentBuilder {
     /// available before building the registry. Used in headless mode so the
     /// conversation session gets tools from connecting MCP servers.
     wait_for_mcp_tools_ms: Option<u64>,
+    /// Caller-provided tools registered after the built-ins.
+    extra_tools: Vec<Arc<dyn Tool>>,
 }
 
 impl AgentBuilder {
@@ -64,6 +70,7 @@ impl AgentBuilder {
             permission_requester: None,
             allow_interactive_oauth: true,
             wait_for_mcp_tools_ms: None,
+            extra_tools: Vec::new(),
         }
     }
 
@@ -123,19 +130,37 @@ impl AgentBuilder {
         self
     }
 
+    /// Add a tool of the embedding application to the registry.
+    ///
+    /// Extra tools are registered after the built-in tools of the profile
+    /// and before MCP tools.  Building fails if an extra tool's name is
+    /// already taken.
+    pub fn with_extra_tool(mut self, tool: Box<dyn Tool>) -> Self {
+        self.extra_tools.push(Arc::from(tool));
+        self
+    }
+
+    /// Add several tools; see [`AgentBuilder::with_extra_tool`].
+    pub fn with_extra_tools(mut self, tools: impl IntoIterator<Item = Box<dyn Tool>>) -> Self {
+        self.extra_tools.extend(tools.into_iter().map(Arc::from));
+        self
+    }
+
     /// Build the [`Agent`] with the given mode, model, and tool-set profile.
     ///
     /// This method owns the creation of the shared mode lock and tool-event
     /// channel so that `SwitchModeTool` / `TodoTool` and the agent loop
     /// operate on **the same** instances.
+    ///
+    /// Fails when an extra tool collides with another tool's name.
     pub async fn build(
         self,
         mode: AgentMode,
         model: Arc<dyn ModelProvider>,
         profile: ToolSetProfile,
-    ) -> Agent {
-        let (agent, _mcp, _rx) = self.build_with_mcp(mode, model, profile).await;
-        agent
+    ) -> anyhow::Result<Agent> {
+        let (agent, _mcp, _rx) = self.build_with_mcp(mode, model, profile).await?;
+        Ok(agent)
     }
 
     /// Like [`build`] but also returns the [`McpManager`] and the MCP event
@@ -144,7 +169,8 @@ impl AgentBuilder {
     /// 1. Creates `mode_lock` (same Arc for both the registry and the Agent).
     /// 2. Creates `(tool_event_tx, tool_event_rx)` (tx → tools, rx → Agent).
     /// 3. Converts [`RuntimeContext`] → [`AgentRuntimeContext`].
-    /// 4. Builds a [`ToolRegistry`] via `build_tool_registry`.
+    /// 4. Builds a [`ToolRegistry`] via `build_tool_registry`, adds the
+    ///    extra and MCP tools and applies `tools.allow` / `tools.deny`.
     /// 5. Probes the provider for the actual context window (`GET /props`).
     /// 6. Constructs `Agent::new(...)`.
     pub async fn build_with_mcp(
@@ -152,22 +178,24 @@ impl AgentBuilder {
         mode: AgentMode,
         model: Arc<dyn ModelProvider>,
         profile: ToolSetProfile,
-    ) -> (
+    ) -> anyhow::Result<(
         Agent,
         Arc<McpManager>,
         mpsc::Receiver<sven_mcp_client::McpEvent>,
-    ) {
+    )> {
         // Shared mode lock: SwitchModeTool holds a clone; the agent owns it.
         let mode_lock = Arc::new(Mutex::new(mode));
         // Shared event channel: tools send, agent drains.
         let (tool_event_tx, tool_event_rx) = mpsc::channel::<ToolEvent>(64);
 
         // Convert RuntimeContext → AgentRuntimeContext (the sven-core type).
-        let mut runtime = self.runtime_ctx.to_agent_runtime();
+        let mut runtime_ctx = self.runtime_ctx;
+        runtime_ctx.add_recent_commits(self.config.agent.git_recent_commits);
+        let mut runtime = runtime_ctx.to_agent_runtime();
         // Preserve any append/override fields that may have been set on the
         // RuntimeContext before it was passed to the builder.
-        runtime.append_system_prompt = self.runtime_ctx.append_system_prompt;
-        runtime.system_prompt_override = self.runtime_ctx.system_prompt_override;
+        runtime.append_system_prompt = runtime_ctx.append_system_prompt;
+        runtime.system_prompt_override = runtime_ctx.system_prompt_override;
 
         let (mcp_event_tx, mcp_event_rx) = mpsc::channel::<sven_mcp_client::McpEvent>(64);
         let mcp_manager = McpManager::new(
@@ -206,6 +234,14 @@ impl AgentBuilder {
             }
         }
 
+        let profile = profile.with_preset(self.config.tools.profile);
+        if let (Some(path), Some(todos)) = (
+            self.config.tools.todo.persist_file.as_deref(),
+            profile.session_todos(),
+        ) {
+            restore_todos(std::path::Path::new(path), todos, &tool_event_tx).await;
+        }
+
         let mut registry = build_tool_registry(
             &self.config,
             model.clone(),
@@ -214,16 +250,33 @@ impl AgentBuilder {
             tool_event_tx,
             runtime.clone(),
         );
+        for tool in self.extra_tools {
+            let name = tool.name().to_string();
+            registry
+                .try_register(tool)
+                .with_context(|| format!("registering extra tool \"{name}\""))?;
+        }
 
         // Register MCP tools after core tools so that the Anthropic provider
-        // can place BP1 after core tools and BP2 after MCP tools.
-        let mcp_tools: Vec<McpTool> = mcp_manager.tools().await;
-        if !mcp_tools.is_empty() {
-            for tool in mcp_tools {
-                registry.register(tool);
+        // can place BP1 after core tools and BP2 after MCP tools.  Presets
+        // take none.
+        if registry.accepts_mcp_tools() {
+            let mcp_tools: Vec<McpTool> = mcp_manager.tools().await;
+            if !mcp_tools.is_empty() {
+                for tool in mcp_tools {
+                    registry.register(tool);
+                }
+            } else if !self.config.mcp_servers.is_empty() {
+                warn!("No MCP tools available yet (servers may still be connecting)");
+            }
+        }
+
+        // Apply tools.allow / tools.deny last so MCP tools are covered too.
+        let tools_cfg = &self.config.tools;
+        if tools_cfg.allow.is_some() || !tools_cfg.deny.is_empty() {
+            for name in registry.restrict(tools_cfg.allow.as_deref(), &tools_cfg.deny) {
+                warn!(tool = %name, "tools.allow/tools.deny names an unknown tool; ignoring it");
             }
-        } else if !self.config.mcp_servers.is_empty() {
-            warn!("No MCP tools available yet (servers may still be connecting)");
         }
 
         if let Some(req) = self.permission_requester {
@@ -272,6 +325,136 @@ impl AgentBuilder {
             model_resolver: Some(model_resolver),
         });
 
-        (agent, mcp_manager, mcp_event_rx)
+        Ok((agent, mcp_manager, mcp_event_rx))
+    }
+}
+
+/// Seed an empty session todo list from a file saved by a previous run and
+/// let the frontend know about it.  An unreadable file is logged and ignored.
+async fn restore_todos(
+    path: &std::path::Path,
+    todos: &Mutex<Vec<TodoItem>>,
+    tool_event_tx: &mpsc::Sender<ToolEvent>,
+) {
+    let saved = match sven_tools::load_todos(path).await {
+        Ok(saved) if !saved.is_empty() => saved,
+        Ok(_) => return,
+        Err(e) => {
+            warn!(path = %path.display(), error = %e, "ignoring unreadable todo file");
+            return;
+        }
+    };
+    let mut current = todos.lock().await;
+    if !current.is_empty() {
+        return;
+    }
+    info!(count = saved.len(), path = %path.display(), "restored todo list");
+    *current = saved.clone();
+    drop(current);
+    let _ = tool_event_tx.send(ToolEvent::TodoUpdate(saved)).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use serde_json::{json, Value};
+    use sven_tools::{ApprovalPolicy, OutputBufferStore, ToolCall, ToolOutput};
+
+    struct ShoutTool {
+        name: &'static str,
+    }
+
+    #[async_trait]
+    impl Tool for ShoutTool {
+        fn name(&self) -> &str {
+            self.name
+        }
+        fn description(&self) -> &str {
+            "Upper-cases its input"
+        }
+        fn parameters_schema(&self) -> Value {
+            json!({"type": "object", "properties": {"text": {"type": "string"}}})
+        }
+        fn default_policy(&self) -> ApprovalPolicy {
+            ApprovalPolicy::Auto
+        }
+        async fn execute(&self, call: &ToolCall) -> ToolOutput {
+            let text = call.args["text"].as_str().unwrap_or_default();
+            ToolOutput::ok(&call.id, text.to_uppercase())
+        }
+    }
+
+    fn profile() -> ToolSetProfile {
+        ToolSetProfile::Coding {
+            question_tx: None,
+            todos: Arc::new(Mutex::new(Vec::new())),
+            buffer_store: Arc::new(Mutex::new(OutputBufferStore::new())),
+        }
+    }
+
+    #[tokio::test]
+    async fn extra_tool_is_registered_and_callable() {
+        let agent = AgentBuilder::new(Arc::new(Config::default()))
+            .with_extra_tool(Box::new(ShoutTool { name: "shout" }))
+            .build(
+                AgentMode::Agent,
+                Arc::new(sven_model::MockProvider),
+                profile(),
+            )
+            .await
+            .unwrap();
+
+        let tools = agent.tools();
+        assert!(tools.names().contains(&"shout".to_string()));
+        assert!(tools.get("read_file").is_some(), "built-ins are kept");
+        let out = tools
+            .execute(&ToolCall {
+                id: "c1".into(),
+                name: "shout".into(),
+                args: json!({"text": "hi"}),
+            })
+            .await;
+        assert!(!out.is_error, "{}", out.content);
+        assert_eq!(out.content, "HI");
+    }
+
+    #[tokio::test]
+    async fn extra_tool_name_collision_is_an_error() {
+        let err = AgentBuilder::new(Arc::new(Config::default()))
+            .with_extra_tools([
+                Box::new(ShoutTool { name: "shout" }) as Box<dyn Tool>,
+                Box::new(ShoutTool { name: "read_file" }),
+            ])
+            .build(
+                AgentMode::Agent,
+                Arc::new(sven_model::MockProvider),
+                profile(),
+            )
+            .await
+            .err()
+            .expect("collision must fail the build");
+        let msg = format!("{err:#}");
+        assert!(msg.contains("\"read_file\" is already registered"), "{msg}");
+    }
+
+    #[tokio::test]
+    async fn tools_allow_and_deny_restrict_the_built_registry() {
+        let mut config = Config::default();
+        config.tools.allow = Some(vec!["read_file".into(), "grep".into(), "shout".into()]);
+        config.tools.deny = vec!["grep".into()];
+        let agent = AgentBuilder::new(Arc::new(config))
+            .with_extra_tool(Box::new(ShoutTool { name: "shout" }))
+            .build(
+                AgentMode::Agent,
+                Arc::new(sven_model::MockProvider),
+                profile(),
+            )
+            .await
+            .unwrap();
+
+        let mut names = agent.tools().names();
+        names.sort();
+        assert_eq!(names, ["read_file", "shout"]);
     }
 }
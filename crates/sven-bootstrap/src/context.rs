@@ -14,6 +14,7 @@ use std::sync::Arc;
 
 use tokio::sync::{mpsc, Mutex};
 
+use sven_config::ToolPreset;
 use sven_core::AgentRuntimeContext;
 use sven_runtime::{CiContext, GitContext, SharedAgents, SharedKnowledge, SharedSkills};
 use sven_tools::{events::TodoItem, OutputBufferStore, QuestionRequest};
@@ -29,6 +30,11 @@ use sven_tools::{events::TodoItem, OutputBufferStore, QuestionRequest};
 pub struct RuntimeContext {
     /// Absolute path to the project root (detected from `.git` walk-up).
     pub project_root: Option<PathBuf>,
+    /// Workspace root above the project (west, `.cursor`, …), detected from
+    /// `project_root`; equal to it outside a multi-repo layout.
+    pub workspace_root: Option<PathBuf>,
+    /// Build systems detected from marker files (`Cargo.toml`, `west.yml`, …).
+    pub build_systems: Vec<String>,
     /// Live git metadata (branch, commit, dirty state).
     pub git_context: Option<GitContext>,
     /// CI environment metadata.
@@ -57,9 +63,16 @@ impl RuntimeContext {
     /// Create with auto-detected project, git, CI context, skills, and knowledge.
     pub fn auto_detect() -> Self {
         let project_root = sven_runtime::find_project_root().ok();
+        let workspace_root = project_root
+            .as_deref()
+            .map(sven_runtime::find_workspace_root);
+        let build_systems = project_root
+            .as_deref()
+            .map(|r| sven_runtime::detect_build_systems(r, workspace_root.as_deref()))
+            .unwrap_or_default();
         let git_context = project_root
             .as_ref()
-            .map(|r| sven_runtime::collect_git_context(r));
+            .map(|r| sven_runtime::collect_git_context_cached(r));
         let ci_context = Some(sven_runtime::detect_ci_context());
         let project_context_file = project_root
             .as_ref()
@@ -77,6 +90,8 @@ impl RuntimeContext {
 
         Self {
             project_root,
+            workspace_root,
+            build_systems,
             git_context,
             ci_context,
             project_context_file,
@@ -89,6 +104,25 @@ impl RuntimeContext {
         }
     }
 
+    /// Use `root` as the project root (e.g. an ACP session's `cwd`) and
+    /// detect the workspace root above it again.
+    pub fn set_project_root(&mut self, root: PathBuf) {
+        self.workspace_root = Some(sven_runtime::find_workspace_root(&root));
+        self.project_root = Some(root);
+    }
+
+    /// Add the subjects of the last `n` commits to the git context (see
+    /// `agent.git_recent_commits`).  Does nothing when `n` is zero, there is
+    /// no git context or it already lists commits.
+    pub fn add_recent_commits(&mut self, n: usize) {
+        let (Some(root), Some(git)) = (&self.project_root, &mut self.git_context) else {
+            return;
+        };
+        if n > 0 && !git.is_empty() && git.recent_commits.is_empty() {
+            git.recent_commits = sven_runtime::collect_recent_commits(root, n);
+        }
+    }
+
     /// Create an empty context (no project/git/CI detection).
     pub fn empty() -> Self {
         Self {
@@ -107,6 +141,8 @@ impl RuntimeContext {
     pub fn to_agent_runtime(&self) -> AgentRuntimeContext {
         AgentRuntimeContext {
             project_root: self.project_root.clone(),
+            workspace_root: self.workspace_root.clone(),
+            build_systems: self.build_systems.clone(),
             git_context_note: self
                 .git_context
                 .as_ref()
@@ -170,6 +206,19 @@ pub enum ToolSetProfile {
         todos: Arc<Mutex<Vec<TodoItem>>>,
         buffer_store: Arc<Mutex<OutputBufferStore>>,
     },
+
+    /// Minimal preset: `read_file`, `find_file` and `grep`. 3 tools.
+    ///
+    /// Selected with `tools.profile = "minimal"`; for quick lookups where the
+    /// agent only needs to read the tree.
+    Minimal,
+
+    /// Review preset: the minimal tools plus `search_codebase` and
+    /// `read_lints`. 5 tools.
+    ///
+    /// Selected with `tools.profile = "review"`; nothing can edit files, run
+    /// commands or delegate.
+    Review,
 }
 
 impl ToolSetProfile {
@@ -214,6 +263,30 @@ impl ToolSetProfile {
         }
     }
 
+    /// The shared todo list of a top-level session, i.e. the one restored from
+    /// and saved to `tools.todo.persist_file`.  `None` for sub-agents, whose
+    /// todos are private to the sub-task.
+    pub fn session_todos(&self) -> Option<&Arc<Mutex<Vec<TodoItem>>>> {
+        match self {
+            ToolSetProfile::Full { todos, .. }
+            | ToolSetProfile::Coding { todos, .. }
+            | ToolSetProfile::Research { todos, .. } => Some(todos),
+            ToolSetProfile::SubAgent { .. } | ToolSetProfile::Minimal | ToolSetProfile::Review => {
+                None
+            }
+        }
+    }
+
+    /// Replace a top-level profile with the configured preset, if any.
+    /// Sub-agents keep their own tool set.
+    pub fn with_preset(self, preset: Option<ToolPreset>) -> Self {
+        match (preset, &self) {
+            (_, ToolSetProfile::SubAgent { .. }) | (None, _) => self,
+            (Some(ToolPreset::Minimal), _) => ToolSetProfile::Minimal,
+            (Some(ToolPreset::Review), _) => ToolSetProfile::Review,
+        }
+    }
+
     /// Returns a short name for the profile (for logging/display).
     pub fn name(&self) -> &'static str {
         match self {
@@ -221,6 +294,8 @@ impl ToolSetProfile {
             ToolSetProfile::Coding { .. } => "coding",
             ToolSetProfile::Research { .. } => "research",
             ToolSetProfile::SubAgent { .. } => "subagent",
+            ToolSetProfile::Minimal => "minimal",
+            ToolSetProfile::Review => "review",
         }
     }
 }
@@ -273,7 +348,7 @@ mod tests {
     use sven_tools::events::TodoItem;
     use sven_tools::OutputBufferStore;
 
-    use super::{has_gdb_config, ToolSetProfile};
+    use super::{has_gdb_config, RuntimeContext, ToolSetProfile};
 
     fn todos() -> Arc<Mutex<Vec<TodoItem>>> {
         Arc::new(Mutex::new(vec![]))
@@ -389,4 +464,42 @@ mod tests {
             "sub-agent flag must take priority"
         );
     }
+
+    #[test]
+    fn workspace_root_reaches_the_agent_runtime() {
+        let ctx = RuntimeContext {
+            project_root: Some("/work/app".into()),
+            workspace_root: Some("/work".into()),
+            ..RuntimeContext::empty()
+        };
+        let runtime = ctx.to_agent_runtime();
+        assert_eq!(runtime.project_root.as_deref(), Some("/work/app".as_ref()));
+        assert_eq!(runtime.workspace_root.as_deref(), Some("/work".as_ref()));
+    }
+
+    #[test]
+    fn set_project_root_detects_the_workspace_above_it() {
+        let tmp = tempfile::tempdir().unwrap();
+        let project = tmp.path().join("app");
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::create_dir_all(tmp.path().join(".west")).unwrap();
+
+        let mut ctx = RuntimeContext {
+            project_root: Some("/elsewhere".into()),
+            workspace_root: Some("/elsewhere".into()),
+            ..RuntimeContext::empty()
+        };
+        ctx.set_project_root(project.clone());
+        assert_eq!(ctx.project_root.as_deref(), Some(project.as_path()));
+        assert_eq!(ctx.workspace_root.as_deref(), Some(tmp.path()));
+    }
+
+    #[test]
+    fn build_systems_reach_the_agent_runtime() {
+        let ctx = RuntimeContext {
+            build_systems: vec!["cargo".into()],
+            ..RuntimeContext::empty()
+        };
+        assert_eq!(ctx.to_agent_runtime().build_systems, ["cargo"]);
+    }
 }
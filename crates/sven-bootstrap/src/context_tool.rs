@@ -153,6 +153,10 @@ impl Tool for ContextTool {
         &[AgentMode::Agent]
     }
 
+    fn path_args(&self) -> &[&str] {
+        &["path"]
+    }
+
     async fn execute(&self, call: &ToolCall) -> ToolOutput {
         let action = match call.args.get("action").and_then(|v| v.as_str()) {
             Some(a) => a.to_string(),
@@ -276,6 +280,23 @@ mod tests {
         ContextTool::new(store, provider, &cfg, None)
     }
 
+    #[tokio::test]
+    async fn open_outside_the_sandbox_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut reg = sven_tools::ToolRegistry::new();
+        reg.register(make_tool());
+        reg.set_sandbox(sven_tools::Sandbox::new(dir.path()).unwrap());
+        let out = reg
+            .execute(&call(json!({"action": "open", "path": "/etc"})))
+            .await;
+        assert!(out.is_error);
+        assert!(
+            out.content.contains("outside the sandbox root"),
+            "{}",
+            out.content
+        );
+    }
+
     #[tokio::test]
     async fn missing_action_is_error() {
         let t = make_tool();
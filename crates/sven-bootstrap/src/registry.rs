@@ -18,8 +18,10 @@
 //! separate entries in the tool registry.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use tokio::sync::{mpsc, Mutex};
+use tracing::warn;
 
 use sven_config::{AgentMode, Config};
 use sven_model::ModelProvider;
@@ -29,8 +31,9 @@ use sven_tools::GdbSessionState;
 use sven_tools::{
     events::{TodoItem, ToolEvent},
     AskQuestionTool, ContextStore, EditFileTool, FindFileTool, GrepTool, MemoryTool,
-    OutputBufferStore, QuestionRequest, ReadFileTool, ShellTool, SkillTool, SystemTool, TodoTool,
-    ToolRegistry, WebFetchTool, WebSearchTool, WriteTool,
+    OutputBufferStore, QuestionRequest, ReadFileTool, ReadLintsTool, Sandbox, SearchCodebaseTool,
+    ShellTool, SkillTool, SystemTool, TodoTool, ToolRegistry, WebFetchTool, WebSearchTool,
+    WriteTool,
 };
 
 use sven_core::AgentRuntimeContext;
@@ -131,6 +134,7 @@ pub fn build_tool_registry_with_integrations(
     sub_agent_runtime: AgentRuntimeContext,
     integrations: IntegrationProviders,
 ) -> ToolRegistry {
+    let is_preset = matches!(profile, ToolSetProfile::Minimal | ToolSetProfile::Review);
     let mut reg = match profile {
         ToolSetProfile::Full {
             question_tx,
@@ -183,14 +187,121 @@ pub fn build_tool_registry_with_integrations(
             &sub_agent_runtime,
             buffer_store,
         ),
+        ToolSetProfile::Minimal => build_profile_minimal(),
+        ToolSetProfile::Review => build_profile_review(),
     };
 
-    // Register integration tools if providers are available.
-    register_integration_tools(&mut reg, integrations);
+    // Presets are a fixed tool set: no integration or MCP tools.  Otherwise
+    // register integration tools if providers are available.
+    if is_preset {
+        reg.disable_mcp_tools();
+    } else {
+        register_integration_tools(&mut reg, integrations);
+    }
+    apply_tool_timeouts(&mut reg, cfg);
+    apply_sandbox(&mut reg, cfg, &sub_agent_runtime);
 
     reg
 }
 
+// ── Tool timeouts ─────────────────────────────────────────────────────────────
+
+/// Record `tools.timeouts` on the registry so the agent loop enforces them as
+/// a backstop around every call of the named tools.
+fn apply_tool_timeouts(reg: &mut ToolRegistry, cfg: &Config) {
+    for (name, secs) in cfg.tools.timeouts.iter() {
+        reg.set_timeout(name, Duration::from_secs(secs));
+    }
+}
+
+// ── Path sandbox ──────────────────────────────────────────────────────────────
+
+/// Confine path arguments to `tools.sandbox_root` (default: the project root,
+/// or the working directory when none was detected) unless `tools.sandbox`
+/// is off.  When the root cannot be resolved every path argument is
+/// rejected rather than left unconfined.
+fn apply_sandbox(reg: &mut ToolRegistry, cfg: &Config, runtime: &AgentRuntimeContext) {
+    if !cfg.tools.sandbox {
+        return;
+    }
+    let Some(project_root) = runtime
+        .project_root
+        .clone()
+        .or_else(|| std::env::current_dir().ok())
+    else {
+        warn!("no project root or working directory; rejecting all tool paths");
+        reg.set_sandbox(Sandbox::deny_all(
+            cfg.tools.sandbox_root.clone().unwrap_or_default(),
+            "no project root or working directory",
+        ));
+        return;
+    };
+    let root = match &cfg.tools.sandbox_root {
+        Some(dir) => project_root.join(dir),
+        None => project_root,
+    };
+    match Sandbox::new(&root) {
+        Ok(sandbox) => reg.set_sandbox(sandbox),
+        Err(e) => {
+            warn!(
+                root = %root.display(),
+                "tools.sandbox_root is unusable; rejecting all tool paths: {e}"
+            );
+            reg.set_sandbox(Sandbox::deny_all(root, e.to_string()));
+        }
+    }
+}
+
+fn shell_tool(cfg: &Config) -> ShellTool {
+    ShellTool::from_config(&cfg.tools)
+}
+
+/// Todo tool for a top-level session, saving to `tools.todo.persist_file`
+/// when configured.
+fn session_todo_tool(
+    cfg: &Config,
+    todos: Arc<Mutex<Vec<TodoItem>>>,
+    tool_event_tx: mpsc::Sender<ToolEvent>,
+) -> TodoTool {
+    let tool = TodoTool::new(todos, tool_event_tx);
+    match &cfg.tools.todo.persist_file {
+        Some(path) => tool.with_persist_path(path),
+        None => tool,
+    }
+}
+
+fn web_fetch_tool(cfg: &Config) -> WebFetchTool {
+    WebFetchTool {
+        timeout_secs: cfg
+            .tools
+            .timeouts
+            .get_or("web_fetch", WebFetchTool::DEFAULT_TIMEOUT_SECS),
+    }
+}
+
+fn web_search_tool(cfg: &Config) -> WebSearchTool {
+    WebSearchTool {
+        api_key: cfg.tools.web.search.api_key.clone(),
+        timeout_secs: cfg
+            .tools
+            .timeouts
+            .get_or("web_search", WebSearchTool::DEFAULT_TIMEOUT_SECS),
+        ..WebSearchTool::default()
+    }
+}
+
+/// `tools.gdb` with a `tools.timeouts.gdb` override applied to the command
+/// timeout.  The connect timeout is raised to match when it would be shorter.
+#[cfg(unix)]
+fn gdb_config(cfg: &Config) -> sven_config::GdbConfig {
+    let mut gdb = cfg.tools.gdb.clone();
+    if let Some(secs) = cfg.tools.timeouts.get("gdb") {
+        gdb.command_timeout_secs = secs;
+        gdb.connect_timeout_secs = gdb.connect_timeout_secs.max(secs);
+    }
+    gdb
+}
+
 /// Register integration tools into an existing registry based on available providers.
 fn register_integration_tools(_reg: &mut ToolRegistry, _providers: IntegrationProviders) {
     // Integration tools are registered when the `integrations` feature is enabled
@@ -259,13 +370,12 @@ fn build_profile_full(p: FullProfileParams<'_>) -> ToolRegistry {
     if let Some(tx) = p.question_tx {
         reg.register(AskQuestionTool::new_tui(tx));
     }
-    reg.register(TodoTool::new(p.todos, p.tool_event_tx.clone()));
+    reg.register(session_todo_tool(p.cfg, p.todos, p.tool_event_tx.clone()));
 
-    reg.register(TaskTool::new(
-        Arc::clone(&p.buffer_store),
-        p.tool_event_tx,
-        Some(model_id),
-    ));
+    reg.register(
+        TaskTool::new(Arc::clone(&p.buffer_store), p.tool_event_tx, Some(model_id))
+            .with_tool_lists(p.cfg.tools.allow.clone(), p.cfg.tools.deny.clone()),
+    );
 
     reg
 }
@@ -286,30 +396,52 @@ fn build_profile_research(
     reg.register(ReadFileTool);
     reg.register(FindFileTool);
     reg.register(GrepTool);
-    reg.register(WebFetchTool);
-    reg.register(WebSearchTool {
-        api_key: cfg.tools.web.search.api_key.clone(),
-    });
+    reg.register(web_fetch_tool(cfg));
+    reg.register(web_search_tool(cfg));
     reg.register(MemoryTool::new(
         cfg.tools.memory.memory_file.clone(),
         runtime.knowledge.clone(),
     ));
     reg.register(SkillTool::new(runtime.skills.clone()));
-    reg.register(SystemTool::new(mode_lock, tool_event_tx.clone()));
+    reg.register(
+        SystemTool::new(mode_lock, tool_event_tx.clone())
+            .with_mode_transitions(cfg.agent.mode_transitions.clone())
+            .with_modes(cfg.agent.all_modes()),
+    );
 
     if let Some(tx) = question_tx {
         reg.register(AskQuestionTool::new_tui(tx));
     }
-    reg.register(TodoTool::new(todos, tool_event_tx.clone()));
+    reg.register(session_todo_tool(cfg, todos, tool_event_tx.clone()));
 
     // Task is included for delegation but limited to research mode.
     let buffer_store = Arc::new(Mutex::new(OutputBufferStore::new()));
-    reg.register(TaskTool::new(
-        buffer_store,
-        tool_event_tx,
-        Some(format!("{}/{}", model.name(), model.model_name())),
-    ));
+    reg.register(
+        TaskTool::new(
+            buffer_store,
+            tool_event_tx,
+            Some(format!("{}/{}", model.name(), model.model_name())),
+        )
+        .with_tool_lists(cfg.tools.allow.clone(), cfg.tools.deny.clone()),
+    );
+
+    reg
+}
+
+/// Minimal preset: read, find and grep only.
+fn build_profile_minimal() -> ToolRegistry {
+    let mut reg = ToolRegistry::new();
+    reg.register(ReadFileTool);
+    reg.register(FindFileTool);
+    reg.register(GrepTool);
+    reg
+}
 
+/// Review preset: the minimal tools plus codebase search and lint results.
+fn build_profile_review() -> ToolRegistry {
+    let mut reg = build_profile_minimal();
+    reg.register(SearchCodebaseTool);
+    reg.register(ReadLintsTool);
     reg
 }
 
@@ -372,15 +504,11 @@ fn register_base_tools(
 
     // ── Shell ─────────────────────────────────────────────────────────────────
     // shell covers: run commands, delete files, list dirs, run linters.
-    reg.register(ShellTool {
-        timeout_secs: cfg.tools.timeout_secs,
-    });
+    reg.register(shell_tool(cfg));
 
     // ── Web ───────────────────────────────────────────────────────────────────
-    reg.register(WebFetchTool);
-    reg.register(WebSearchTool {
-        api_key: cfg.tools.web.search.api_key.clone(),
-    });
+    reg.register(web_fetch_tool(cfg));
+    reg.register(web_search_tool(cfg));
 
     // ── Memory (KV + project knowledge) ──────────────────────────────────────
     // Compound tool: set|get|delete|list|search_knowledge|list_knowledge
@@ -393,7 +521,11 @@ fn register_base_tools(
     reg.register(SkillTool::new(runtime.skills.clone()));
 
     // ── System (mode + model switching) ──────────────────────────────────────
-    reg.register(SystemTool::new(mode_lock, tool_event_tx.clone()));
+    reg.register(
+        SystemTool::new(mode_lock, tool_event_tx.clone())
+            .with_mode_transitions(cfg.agent.mode_transitions.clone())
+            .with_modes(cfg.agent.all_modes()),
+    );
 
     // ── Context and GDB (Full profile only) ──────────────────────────────────
     if include_full {
@@ -411,7 +543,7 @@ fn register_base_tools(
         #[cfg(unix)]
         {
             let gdb_state = Arc::new(Mutex::new(GdbSessionState::default()));
-            reg.register(GdbTool::new(gdb_state, cfg.tools.gdb.clone()));
+            reg.register(GdbTool::new(gdb_state, gdb_config(cfg)));
         }
     } else {
         // Suppress unused warnings for the buffer_store in SubAgent path.
@@ -438,15 +570,11 @@ pub fn build_cli_tool_registry(cfg: &Config) -> ToolRegistry {
     reg.register(GrepTool);
 
     // ── Web ───────────────────────────────────────────────────────────────────
-    reg.register(WebFetchTool);
-    reg.register(WebSearchTool {
-        api_key: cfg.tools.web.search.api_key.clone(),
-    });
+    reg.register(web_fetch_tool(cfg));
+    reg.register(web_search_tool(cfg));
 
     // ── System ────────────────────────────────────────────────────────────────
-    reg.register(ShellTool {
-        timeout_secs: cfg.tools.timeout_secs,
-    });
+    reg.register(shell_tool(cfg));
 
     let (event_tx, _event_rx) = mpsc::channel::<ToolEvent>(16);
     let todos = Arc::new(Mutex::new(Vec::<TodoItem>::new()));
@@ -476,3 +604,183 @@ pub fn build_cli_tool_registry(cfg: &Config) -> ToolRegistry {
 
     reg
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with_timeouts(pairs: &[(&str, u64)]) -> Config {
+        let mut cfg = Config::default();
+        for (name, secs) in pairs {
+            cfg.tools.timeouts.0.insert(name.to_string(), *secs);
+        }
+        cfg
+    }
+
+    #[test]
+    fn configured_timeouts_reach_the_tools() {
+        let cfg = config_with_timeouts(&[("web_fetch", 60), ("shell", 5), ("gdb", 45)]);
+        assert_eq!(web_fetch_tool(&cfg).timeout_secs, 60);
+        assert_eq!(shell_tool(&cfg).timeout_secs, 5);
+        assert_eq!(
+            web_search_tool(&cfg).timeout_secs,
+            WebSearchTool::DEFAULT_TIMEOUT_SECS
+        );
+        #[cfg(unix)]
+        {
+            let gdb = gdb_config(&cfg);
+            assert_eq!(gdb.command_timeout_secs, 45);
+            assert!(gdb.connect_timeout_secs >= 45);
+        }
+    }
+
+    #[test]
+    fn unconfigured_tools_keep_their_defaults() {
+        let cfg = Config::default();
+        assert_eq!(shell_tool(&cfg).timeout_secs, cfg.tools.timeout_secs);
+        assert_eq!(
+            web_fetch_tool(&cfg).timeout_secs,
+            WebFetchTool::DEFAULT_TIMEOUT_SECS
+        );
+    }
+
+    #[test]
+    fn sandbox_defaults_to_the_project_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime = AgentRuntimeContext {
+            project_root: Some(dir.path().to_path_buf()),
+            ..AgentRuntimeContext::default()
+        };
+        let mut reg = ToolRegistry::new();
+        apply_sandbox(&mut reg, &Config::default(), &runtime);
+        let root = reg.sandbox().expect("sandbox is on by default").root();
+        assert_eq!(root, dir.path().canonicalize().unwrap());
+
+        let mut cfg = Config::default();
+        cfg.tools.sandbox = false;
+        let mut reg = ToolRegistry::new();
+        apply_sandbox(&mut reg, &cfg, &runtime);
+        assert!(reg.sandbox().is_none());
+    }
+
+    #[test]
+    fn nonexistent_sandbox_root_rejects_every_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime = AgentRuntimeContext {
+            project_root: Some(dir.path().to_path_buf()),
+            ..AgentRuntimeContext::default()
+        };
+        let mut cfg = Config::default();
+        cfg.tools.sandbox_root = Some("does-not-exist".into());
+        let mut reg = ToolRegistry::new();
+        apply_sandbox(&mut reg, &cfg, &runtime);
+
+        let sandbox = reg.sandbox().expect("an unusable root still confines");
+        let inside = dir.path().join("src/main.rs").display().to_string();
+        assert!(!sandbox.contains(&inside));
+        let err = sandbox
+            .check_args(&serde_json::json!({ "path": inside }), &["path"])
+            .unwrap_err();
+        assert!(err.contains("does-not-exist"), "{err}");
+    }
+
+    #[test]
+    fn configured_timeouts_are_recorded_on_the_registry() {
+        let cfg = config_with_timeouts(&[("web_fetch", 60), ("shell", 0)]);
+        let mut reg = ToolRegistry::new();
+        apply_tool_timeouts(&mut reg, &cfg);
+        assert_eq!(reg.timeout_for("web_fetch"), Some(Duration::from_secs(60)));
+        assert_eq!(reg.timeout_for("shell"), None);
+    }
+
+    fn preset_tool_names(profile: ToolSetProfile) -> Vec<String> {
+        let (tx, _rx) = mpsc::channel(1);
+        let reg = build_tool_registry(
+            &Config::default(),
+            Arc::new(sven_model::MockProvider),
+            profile,
+            Arc::new(Mutex::new(AgentMode::Agent)),
+            tx,
+            AgentRuntimeContext::default(),
+        );
+        let mut names = reg.names();
+        names.sort();
+        names
+    }
+
+    fn accepts_mcp_tools(profile: ToolSetProfile) -> bool {
+        let (tx, _rx) = mpsc::channel(1);
+        build_tool_registry(
+            &Config::default(),
+            Arc::new(sven_model::MockProvider),
+            profile,
+            Arc::new(Mutex::new(AgentMode::Agent)),
+            tx,
+            AgentRuntimeContext::default(),
+        )
+        .accepts_mcp_tools()
+    }
+
+    #[test]
+    fn presets_take_no_mcp_tools() {
+        assert!(!accepts_mcp_tools(ToolSetProfile::Minimal));
+        assert!(!accepts_mcp_tools(ToolSetProfile::Review));
+        assert!(accepts_mcp_tools(ToolSetProfile::Research {
+            question_tx: None,
+            todos: Arc::new(Mutex::new(Vec::new())),
+        }));
+    }
+
+    #[test]
+    fn minimal_preset_registers_exactly_the_read_tools() {
+        assert_eq!(
+            preset_tool_names(ToolSetProfile::Minimal),
+            ["find_file", "grep", "read_file"]
+        );
+    }
+
+    #[test]
+    fn review_preset_adds_search_and_lints_without_mutating_tools() {
+        assert_eq!(
+            preset_tool_names(ToolSetProfile::Review),
+            [
+                "find_file",
+                "grep",
+                "read_file",
+                "read_lints",
+                "search_codebase"
+            ]
+        );
+    }
+
+    #[test]
+    fn sub_agent_spawned_with_minimal_preset_has_no_write_tools() {
+        // A `task` sub-agent runs `sven acp serve --tool-profile minimal`,
+        // whose session starts from the full profile.
+        let full = ToolSetProfile::Full {
+            question_tx: None,
+            todos: Arc::new(Mutex::new(Vec::new())),
+            buffer_store: Arc::new(Mutex::new(OutputBufferStore::new())),
+        };
+        let names = preset_tool_names(full.with_preset(Some(sven_config::ToolPreset::Minimal)));
+        for write_tool in ["write", "edit_file", "shell", "task"] {
+            assert!(
+                !names.iter().any(|n| n == write_tool),
+                "{write_tool} in {names:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn preset_tool_names_match_the_preset_registries() {
+        use sven_config::ToolPreset;
+        for (preset, profile) in [
+            (ToolPreset::Minimal, ToolSetProfile::Minimal),
+            (ToolPreset::Review, ToolSetProfile::Review),
+        ] {
+            let mut expected: Vec<&str> = preset.tool_names().to_vec();
+            expected.sort();
+            assert_eq!(preset_tool_names(profile), expected, "{preset}");
+        }
+    }
+}
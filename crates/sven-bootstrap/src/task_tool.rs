@@ -66,7 +66,7 @@ use tokio::time::Instant;
 use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
 use tracing::{debug, warn};
 
-use sven_config::AgentMode;
+use sven_config::{AgentMode, ToolPreset};
 use sven_tools::{
     events::{SubagentUpdate, ToolEvent},
     policy::ApprovalPolicy,
@@ -84,6 +84,13 @@ const DEPTH_ENV: &str = "SVEN_SUBAGENT_DEPTH";
 /// This value is a final safety net for genuinely hung agents.
 const INACTIVITY_TIMEOUT: Duration = Duration::from_secs(600);
 
+/// Sub-agents run at once by `action=batch` when `max_parallel` is not given.
+const DEFAULT_MAX_PARALLEL: usize = 3;
+
+/// Ceiling on `max_parallel`: each sub-agent is a full process with its own
+/// model session, so the model must not be able to start hundreds at once.
+const MAX_PARALLEL_LIMIT: usize = 8;
+
 // ── Cancel guard ──────────────────────────────────────────────────────────────
 
 /// RAII guard that fires the cancel sender when dropped.
@@ -149,6 +156,9 @@ struct SpawnArgs {
     mode: String,
     workdir: PathBuf,
     model_override: Option<String>,
+    tool_profile: Option<ToolPreset>,
+    tools_allow: Option<Vec<String>>,
+    tools_deny: Vec<String>,
     handle_id: String,
     call_id: String,
     buffer_store: Arc<Mutex<OutputBufferStore>>,
@@ -164,6 +174,9 @@ pub struct TaskTool {
     buffer_store: Arc<Mutex<OutputBufferStore>>,
     tool_event_tx: mpsc::Sender<ToolEvent>,
     default_model: Option<String>,
+    /// `tools.allow` / `tools.deny` of the parent, passed on to every child.
+    tools_allow: Option<Vec<String>>,
+    tools_deny: Vec<String>,
 }
 
 impl TaskTool {
@@ -176,8 +189,18 @@ impl TaskTool {
             buffer_store,
             tool_event_tx,
             default_model,
+            tools_allow: None,
+            tools_deny: Vec::new(),
         }
     }
+
+    /// Restrict every sub-agent to `allow` (all tools when `None`) minus
+    /// `deny`, like the parent's `tools.allow` / `tools.deny`.
+    pub fn with_tool_lists(mut self, allow: Option<Vec<String>>, deny: Vec<String>) -> Self {
+        self.tools_allow = allow;
+        self.tools_deny = deny;
+        self
+    }
 }
 
 #[async_trait]
@@ -188,11 +211,15 @@ impl Tool for TaskTool {
 
     fn description(&self) -> &str {
         "Spawn a focused sub-agent or inspect a running sub-agent's output.\n\
-         action: spawn (default) | status | read | grep\n\n\
+         action: spawn (default) | batch | status | read | grep\n\n\
          **Spawn workflow (action=spawn or omitted):**\n\
          1. Call `task` with prompt → subagent runs and returns its final response\n\
          2. Optionally spawn more sub-agents in parallel with different prompts\n\
          3. The tool blocks until the subagent completes and returns the result\n\n\
+         **Batch (action=batch):** pass `tasks`, a list of spawn arguments, to run \
+         independent sub-tasks concurrently (up to `max_parallel`, default 3, at most 8).  Results \
+         come back in one output, labelled in task order; a failed sub-task does not stop \
+         the others.\n\n\
          **When to spawn:**\n\
          - Exploration and research of large unfamiliar areas.\n\
          - Tasks that searching through a lot of context but we are only interested in the final findings. \n\
@@ -200,7 +227,9 @@ impl Tool for TaskTool {
          - Do not use for anything you can easily do with shell.\n\
          - Do not spawn tasks for simple single step commands.\n\
          - Do not spawn tasks for exploring single files or anything that you can readily do directly. \n\
-         Sub-agents have access to all standard tools. Sub-agents cannot spawn further sub-agents."
+         Sub-agents have access to all standard tools unless `tool_profile` restricts them \
+         (minimal: read_file/find_file/grep; review: adds search_codebase/read_lints). \
+         Sub-agents cannot spawn further sub-agents."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -209,8 +238,8 @@ impl Tool for TaskTool {
             "properties": {
                 "action": {
                     "type": "string",
-                    "enum": ["spawn", "status", "read", "grep"],
-                    "description": "spawn (default): launch sub-agent; status/read/grep: inspect existing buffer"
+                    "enum": ["spawn", "batch", "status", "read", "grep"],
+                    "description": "spawn (default): launch sub-agent; batch: launch several; status/read/grep: inspect existing buffer"
                 },
                 "prompt": {
                     "type": "string",
@@ -233,6 +262,31 @@ impl Tool for TaskTool {
                     "type": "string",
                     "description": "[action=spawn] Model override (e.g. 'fast')"
                 },
+                "tool_profile": {
+                    "type": "string",
+                    "enum": ["subagent", "minimal", "review"],
+                    "description": "[action=spawn] Tool set for the sub-agent (default: subagent, the standard tools; minimal and review cannot modify files)"
+                },
+                "tasks": {
+                    "type": "array",
+                    "description": "[action=batch] Sub-tasks to run concurrently, each with the spawn parameters",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "prompt": { "type": "string" },
+                            "description": { "type": "string" },
+                            "mode": { "type": "string", "enum": ["research", "plan", "agent"] },
+                            "workdir": { "type": "string" },
+                            "model": { "type": "string" },
+                            "tool_profile": { "type": "string", "enum": ["subagent", "minimal", "review"] }
+                        },
+                        "required": ["prompt"]
+                    }
+                },
+                "max_parallel": {
+                    "type": "integer",
+                    "description": "[action=batch] Maximum sub-agents running at once (default 3, capped at 8)"
+                },
                 "handle": {
                     "type": "string",
                     "description": "[action=status|read|grep] Buffer handle from a previous spawn"
@@ -270,6 +324,10 @@ impl Tool for TaskTool {
         &[AgentMode::Agent]
     }
 
+    fn path_args(&self) -> &[&str] {
+        &["workdir", "tasks.workdir"]
+    }
+
     async fn execute(&self, call: &ToolCall) -> ToolOutput {
         let action = call
             .args
@@ -386,53 +444,65 @@ impl Tool for TaskTool {
                     })
                     .await;
             }
+            "batch" => return self.batch(call).await,
             _ => {}
         }
 
+        self.spawn(&call.id, &call.args).await
+    }
+}
+
+impl TaskTool {
+    /// Spawn one sub-agent for the task described by `args` and wait for it.
+    async fn spawn(&self, call_id: &str, args: &Value) -> ToolOutput {
         // ── Validate spawn inputs ─────────────────────────────────────────────
-        let prompt = match call.args.get("prompt").and_then(|v| v.as_str()) {
+        let prompt = match args.get("prompt").and_then(|v| v.as_str()) {
             Some(p) => p.to_string(),
-            None => return ToolOutput::err(&call.id, "missing required parameter 'prompt'"),
+            None => return ToolOutput::err(call_id, "missing required parameter 'prompt'"),
         };
 
-        let description = call
-            .args
+        let description = args
             .get("description")
             .and_then(|v| v.as_str())
             .unwrap_or(&prompt[..prompt.len().min(60)])
             .to_string();
 
-        let mode = call
-            .args
+        let mode = args
             .get("mode")
             .and_then(|v| v.as_str())
             .unwrap_or("agent")
             .to_string();
 
-        let workdir = call
-            .args
+        let workdir = args
             .get("workdir")
             .and_then(|v| v.as_str())
             .map(PathBuf::from)
             .or_else(|| std::env::current_dir().ok())
             .unwrap_or_else(|| PathBuf::from("/"));
 
-        let model_override = call
-            .args
+        let model_override = args
             .get("model")
             .and_then(|v| v.as_str())
             .map(str::to_string)
             .or_else(|| self.default_model.clone());
 
+        let tool_profile = match args.get("tool_profile").and_then(|v| v.as_str()) {
+            None => None,
+            Some(name) => match parse_tool_profile(name) {
+                Ok(preset) => preset,
+                Err(e) => return ToolOutput::err(call_id, e),
+            },
+        };
+
         // Subagents (DEPTH_ENV set) cannot spawn further sub-agents.
         if std::env::var(DEPTH_ENV).is_ok() {
-            return ToolOutput::err(&call.id, "sub-agents cannot spawn further sub-agents");
+            return ToolOutput::err(call_id, "sub-agents cannot spawn further sub-agents");
         }
 
         let exe = match std::env::current_exe() {
             Ok(p) => p,
             Err(e) => {
-                return ToolOutput::err(&call.id, format!("could not locate sven executable: {e}"))
+                return ToolOutput::err(call_id, format!("could not locate sven executable: {e}"))
             }
         };
 
@@ -450,7 +520,7 @@ impl Tool for TaskTool {
         let _ = self
             .tool_event_tx
             .send(ToolEvent::SubagentStarted {
-                call_id: call.id.clone(),
+                call_id: call_id.to_string(),
                 handle_id: handle_id.clone(),
                 description: description.clone(),
                 prompt: prompt.clone(),
@@ -477,8 +547,11 @@ impl Tool for TaskTool {
             mode,
             workdir,
             model_override,
+            tool_profile,
+            tools_allow: self.tools_allow.clone(),
+            tools_deny: self.tools_deny.clone(),
             handle_id: handle_id.clone(),
-            call_id: call.id.clone(),
+            call_id: call_id.to_string(),
             buffer_store: Arc::clone(&self.buffer_store),
             tool_event_tx: self.tool_event_tx.clone(),
             cancel_rx,
@@ -506,6 +579,114 @@ impl Tool for TaskTool {
             .await
             .unwrap_or_else(|_| ToolOutput::err(&handle_id, "sub-agent thread died unexpectedly"))
     }
+
+    /// Run every entry of `tasks` as its own sub-agent, at most `max_parallel`
+    /// at a time, and report all results in order.  A failed sub-task does
+    /// not stop the others.
+    async fn batch(&self, call: &ToolCall) -> ToolOutput {
+        let tasks = match call.args.get("tasks").and_then(|v| v.as_array()) {
+            Some(t) if !t.is_empty() => t.clone(),
+            _ => {
+                return ToolOutput::err(
+                    &call.id,
+                    "missing required parameter 'tasks' for action=batch",
+                )
+            }
+        };
+        if std::env::var(DEPTH_ENV).is_ok() {
+            return ToolOutput::err(&call.id, "sub-agents cannot spawn further sub-agents");
+        }
+        let max_parallel = batch_parallelism(&call.args);
+
+        let labels: Vec<String> = tasks.iter().map(task_label).collect();
+        let results = run_batch(tasks, max_parallel, |task| async move {
+            self.spawn(&call.id, &task).await
+        })
+        .await;
+        aggregate_batch(&call.id, &labels, results)
+    }
+}
+
+/// The `max_parallel` argument of a batch, defaulted and clamped to
+/// `1..=MAX_PARALLEL_LIMIT`.
+fn batch_parallelism(args: &Value) -> usize {
+    args.get("max_parallel")
+        .and_then(|v| v.as_u64())
+        .map_or(DEFAULT_MAX_PARALLEL, |n| {
+            n.clamp(1, MAX_PARALLEL_LIMIT as u64) as usize
+        })
+}
+
+/// Run `run` on every task with at most `max_parallel` in flight, returning
+/// the outputs in task order.
+async fn run_batch<F, Fut>(tasks: Vec<Value>, max_parallel: usize, run: F) -> Vec<ToolOutput>
+where
+    F: FnMut(Value) -> Fut,
+    Fut: std::future::Future<Output = ToolOutput>,
+{
+    futures::stream::iter(tasks)
+        .map(run)
+        .buffered(max_parallel.max(1))
+        .collect()
+        .await
+}
+
+/// Label a batch entry by its description, or the start of its prompt.
+fn task_label(task: &Value) -> String {
+    if let Some(d) = task.get("description").and_then(|v| v.as_str()) {
+        return d.to_string();
+    }
+    let prompt = task.get("prompt").and_then(|v| v.as_str()).unwrap_or("");
+    prompt.chars().take(60).collect()
+}
+
+/// Combine the outputs of a batch into one result, one labelled section per
+/// sub-task.  The result is an error only when every sub-task failed.
+fn aggregate_batch(call_id: &str, labels: &[String], results: Vec<ToolOutput>) -> ToolOutput {
+    let total = results.len();
+    let failed = results.iter().filter(|r| r.is_error).count();
+    let mut text = format!(
+        "Batch of {total} sub-tasks: {} succeeded, {failed} failed.",
+        total - failed
+    );
+    for (i, (label, result)) in labels.iter().zip(&results).enumerate() {
+        let status = if result.is_error { "failed" } else { "ok" };
+        text.push_str(&format!(
+            "\n\n=== [{}/{total}] {label} ({status}) ===\n{}",
+            i + 1,
+            result.content
+        ));
+    }
+    if failed == total {
+        ToolOutput::err(call_id, text)
+    } else {
+        ToolOutput::ok(call_id, text)
+    }
+}
+
+/// `sven acp serve` flags that carry the parent's tool allow/deny lists.
+fn tool_list_args(allow: Option<&[String]>, deny: &[String]) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some(allow) = allow {
+        args.extend(["--tools-allow".to_string(), allow.join(",")]);
+    }
+    if !deny.is_empty() {
+        args.extend(["--tools-deny".to_string(), deny.join(",")]);
+    }
+    args
+}
+
+/// Resolve the `tool_profile` argument of a spawn.  `subagent` is the
+/// standard sub-agent tool set and maps to `None`.
+fn parse_tool_profile(name: &str) -> Result<Option<ToolPreset>, String> {
+    match name {
+        "subagent" => Ok(None),
+        "minimal" => Ok(Some(ToolPreset::Minimal)),
+        "review" => Ok(Some(ToolPreset::Review)),
+        other => Err(format!(
+            "unknown tool_profile '{other}' (expected subagent, minimal or review)"
+        )),
+    }
 }
 
 // ── Core ACP session logic (runs in LocalSet) ─────────────────────────────────
@@ -518,6 +699,9 @@ async fn run_acp_session(args: SpawnArgs, depth: u32) -> ToolOutput {
         mode,
         workdir,
         model_override,
+        tool_profile,
+        tools_allow,
+        tools_deny,
         handle_id,
         call_id,
         buffer_store,
@@ -538,6 +722,10 @@ async fn run_acp_session(args: SpawnArgs, depth: u32) -> ToolOutput {
     if let Some(ref m) = model_override {
         cmd.arg("--model").arg(m);
     }
+    if let Some(preset) = tool_profile {
+        cmd.arg("--tool-profile").arg(preset.to_string());
+    }
+    cmd.args(tool_list_args(tools_allow.as_deref(), &tools_deny));
 
     cmd.current_dir(&workdir);
 
@@ -1043,6 +1231,26 @@ mod tests {
         assert_eq!(make_task().name(), "task");
     }
 
+    #[tokio::test]
+    async fn workdir_outside_the_sandbox_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut reg = sven_tools::ToolRegistry::new();
+        reg.register(make_task());
+        reg.set_sandbox(sven_tools::Sandbox::new(dir.path()).unwrap());
+        for args in [
+            json!({"prompt": "p", "workdir": "/etc"}),
+            json!({"action": "batch", "tasks": [{"prompt": "p", "workdir": "/etc"}]}),
+        ] {
+            let out = reg.execute(&call(args)).await;
+            assert!(out.is_error);
+            assert!(
+                out.content.contains("outside the sandbox root"),
+                "{}",
+                out.content
+            );
+        }
+    }
+
     #[tokio::test]
     async fn status_action_missing_handle_is_error() {
         let t = make_task();
@@ -1136,6 +1344,111 @@ mod tests {
         assert!(out.is_error);
     }
 
+    #[test]
+    fn tool_profile_names_resolve_to_presets() {
+        use sven_config::ToolPreset;
+        assert_eq!(super::parse_tool_profile("subagent"), Ok(None));
+        assert_eq!(
+            super::parse_tool_profile("minimal"),
+            Ok(Some(ToolPreset::Minimal))
+        );
+        assert_eq!(
+            super::parse_tool_profile("review"),
+            Ok(Some(ToolPreset::Review))
+        );
+    }
+
+    #[test]
+    fn parent_tool_lists_are_passed_to_the_child() {
+        assert!(super::tool_list_args(None, &[]).is_empty());
+        let allow = ["read_file".to_string(), "grep".to_string()];
+        assert_eq!(
+            super::tool_list_args(Some(&allow), &["grep".to_string()]),
+            ["--tools-allow", "read_file,grep", "--tools-deny", "grep"]
+        );
+    }
+
+    #[tokio::test]
+    async fn spawn_with_unknown_tool_profile_is_error() {
+        let t = make_task();
+        let out = t
+            .execute(&call(
+                json!({"prompt": "look around", "tool_profile": "full"}),
+            ))
+            .await;
+        assert!(out.is_error);
+        assert!(out.content.contains("tool_profile"), "{}", out.content);
+    }
+
+    #[tokio::test]
+    async fn batch_runs_every_sub_task_and_aggregates_in_order() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::Duration;
+
+        let tasks = vec![
+            json!({"prompt": "analyze module a", "description": "module a"}),
+            json!({"prompt": "analyze module b", "description": "module b"}),
+            json!({"prompt": "analyze module c", "description": "module c"}),
+        ];
+        let labels: Vec<String> = tasks.iter().map(super::task_label).collect();
+        let in_flight = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let results = super::run_batch(tasks, 2, |task| {
+            let (in_flight, peak) = (&in_flight, &peak);
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                let prompt = task["prompt"].as_str().unwrap().to_string();
+                // Later tasks finish first; the output must keep task order.
+                let delay = match prompt.as_str() {
+                    "analyze module a" => 30,
+                    "analyze module b" => 20,
+                    _ => 10,
+                };
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                if prompt.ends_with('b') {
+                    super::ToolOutput::err("t1", "sub-agent crashed")
+                } else {
+                    super::ToolOutput::ok("t1", format!("report on {prompt}"))
+                }
+            }
+        })
+        .await;
+        assert_eq!(results.len(), 3, "a failure must not abort the batch");
+        assert_eq!(peak.load(Ordering::SeqCst), 2, "max_parallel is honoured");
+
+        let out = super::aggregate_batch("t1", &labels, results);
+        assert!(!out.is_error);
+        assert!(out
+            .content
+            .starts_with("Batch of 3 sub-tasks: 2 succeeded, 1 failed."));
+        let a = out.content.find("[1/3] module a (ok)").unwrap();
+        let b = out.content.find("[2/3] module b (failed)").unwrap();
+        let c = out.content.find("[3/3] module c (ok)").unwrap();
+        assert!(a < b && b < c, "{}", out.content);
+        assert!(out.content.contains("report on analyze module c"));
+    }
+
+    #[test]
+    fn batch_parallelism_is_clamped() {
+        let par = |args: serde_json::Value| super::batch_parallelism(&args);
+        assert_eq!(par(json!({})), super::DEFAULT_MAX_PARALLEL);
+        assert_eq!(par(json!({"max_parallel": 0})), 1);
+        assert_eq!(par(json!({"max_parallel": 5})), 5);
+        assert_eq!(par(json!({"max_parallel": 500})), super::MAX_PARALLEL_LIMIT);
+    }
+
+    #[tokio::test]
+    async fn batch_without_tasks_is_error() {
+        let t = make_task();
+        let out = t
+            .execute(&call(json!({"action": "batch", "tasks": []})))
+            .await;
+        assert!(out.is_error);
+        assert!(out.content.contains("tasks"));
+    }
+
     #[tokio::test]
     async fn spawn_blocked_when_subagent() {
         let _env = std::env::var(super::DEPTH_ENV).ok();
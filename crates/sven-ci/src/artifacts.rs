@@ -0,0 +1,133 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! Per-run artifact directories under `--artifacts-dir`.
+//!
+//! Every run writes into its own subdirectory, named after the CI run id when
+//! there is one and the start time otherwise, so a rerun never overwrites the
+//! artifacts of an earlier run.  A `latest` entry in the root points at the
+//! newest run: a symlink on Unix, a file holding the directory name elsewhere.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+
+/// Name of the pointer to the newest run directory.
+pub const LATEST: &str = "latest";
+
+/// Directory name for a run: `run-<ci run id>` or `run-<YYYYmmdd-HHMMSS>`.
+pub fn run_dir_name(run_id: Option<&str>, started: DateTime<Local>) -> String {
+    let id = run_id
+        .map(sanitize)
+        .filter(|id| !id.is_empty())
+        .unwrap_or_else(|| started.format("%Y%m%d-%H%M%S").to_string());
+    format!("run-{id}")
+}
+
+/// Create a fresh run directory under `root` and point `latest` at it.
+///
+/// When `name` is taken — a retried CI job keeps its run id, two local runs
+/// may start within the same second — `-2`, `-3`, … is appended.
+pub fn create_run_dir(root: &Path, name: &str) -> io::Result<PathBuf> {
+    std::fs::create_dir_all(root)?;
+    let mut dir_name = name.to_string();
+    let mut attempt = 1;
+    let dir = loop {
+        let dir = root.join(&dir_name);
+        match std::fs::create_dir(&dir) {
+            Ok(()) => break dir,
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                attempt += 1;
+                dir_name = format!("{name}-{attempt}");
+            }
+            Err(e) => return Err(e),
+        }
+    };
+    update_latest(root, &dir_name)?;
+    Ok(dir)
+}
+
+#[cfg(unix)]
+fn update_latest(root: &Path, dir_name: &str) -> io::Result<()> {
+    let link = root.join(LATEST);
+    match std::fs::remove_file(&link) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+    // Relative, so the artifacts root can be moved or uploaded as a whole.
+    std::os::unix::fs::symlink(dir_name, link)
+}
+
+#[cfg(not(unix))]
+fn update_latest(root: &Path, dir_name: &str) -> io::Result<()> {
+    std::fs::write(root.join(LATEST), format!("{dir_name}\n"))
+}
+
+/// Keep CI run ids usable as a single path component.
+fn sanitize(id: &str) -> String {
+    id.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>()
+        .trim_matches('.')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn started() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 3, 14, 9, 26, 53).unwrap()
+    }
+
+    #[test]
+    fn run_dir_is_named_after_ci_run_id_or_start_time() {
+        assert_eq!(
+            run_dir_name(Some("8123456789"), started()),
+            "run-8123456789"
+        );
+        assert_eq!(run_dir_name(Some("../x y"), started()), "run-_x_y");
+        assert_eq!(run_dir_name(None, started()), "run-20260314-092653");
+        assert_eq!(run_dir_name(Some(""), started()), "run-20260314-092653");
+    }
+
+    #[test]
+    fn second_run_gets_its_own_directory() {
+        let root = tempfile::tempdir().unwrap();
+        let first = create_run_dir(root.path(), "run-42").unwrap();
+        std::fs::write(first.join("conversation.md"), "first run").unwrap();
+
+        let second = create_run_dir(root.path(), "run-42").unwrap();
+        assert_eq!(first, root.path().join("run-42"));
+        assert_eq!(second, root.path().join("run-42-2"));
+        assert_eq!(
+            std::fs::read_to_string(first.join("conversation.md")).unwrap(),
+            "first run",
+            "the first run's artifacts are untouched"
+        );
+        assert!(std::fs::read_dir(&second).unwrap().next().is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn latest_points_at_the_newest_run() {
+        let root = tempfile::tempdir().unwrap();
+        create_run_dir(root.path(), "run-a").unwrap();
+        let newest = create_run_dir(root.path(), "run-b").unwrap();
+        let latest = root.path().join(LATEST);
+        assert_eq!(std::fs::read_link(&latest).unwrap(), Path::new("run-b"));
+        assert_eq!(
+            std::fs::canonicalize(&latest).unwrap(),
+            std::fs::canonicalize(newest).unwrap()
+        );
+    }
+}
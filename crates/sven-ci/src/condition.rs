@@ -0,0 +1,372 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! Step conditions — the small expression language behind `when=`.
+//!
+//! ```markdown
+//! ## Fix failures
+//! <!-- sven: when="contains(last_output, 'FAIL') && exit_code == 0" -->
+//! ```
+//!
+//! An expression is evaluated against the outcome of the previous executed
+//! step.  It can only read two variables and call three pure string
+//! functions, so evaluating a workflow-supplied condition is always safe.
+//!
+//! | Item | Meaning |
+//! |------|---------|
+//! | `exit_code` | `0` for a clean step, `3` when the step had tool errors |
+//! | `last_output` | The previous step's final agent response |
+//! | `contains(a, b)`, `starts_with(a, b)`, `ends_with(a, b)` | String tests |
+//! | `==` `!=` `<` `<=` `>` `>=` | Comparisons (ordering on integers only) |
+//! | `&&` `\|\|` `!` `( )` | Boolean logic |
+//! | `'text'`, `"text"`, `42`, `true`, `false` | Literals |
+
+use anyhow::{bail, Result};
+
+/// The previous step's result, as seen by a `when=` condition.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StepOutcome<'a> {
+    pub exit_code: i64,
+    pub last_output: &'a str,
+}
+
+/// A parsed, type-checked step condition.
+#[derive(Debug, Clone)]
+pub struct Condition {
+    expr: Expr,
+}
+
+impl Condition {
+    /// Parse `source`, rejecting syntax errors, unknown names and
+    /// expressions that do not produce a boolean.
+    pub fn parse(source: &str) -> Result<Self> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.or()?;
+        if let Some(tok) = parser.tokens.get(parser.pos) {
+            bail!("unexpected {tok:?} in condition {source:?}");
+        }
+        let cond = Self { expr };
+        // Variables have fixed types, so evaluating once against an empty
+        // outcome type-checks the whole expression up front.
+        cond.eval(&StepOutcome::default())
+            .map_err(|e| anyhow::anyhow!("{e} in condition {source:?}"))?;
+        Ok(cond)
+    }
+
+    /// Evaluate against the previous step's outcome.
+    pub fn eval(&self, outcome: &StepOutcome<'_>) -> Result<bool> {
+        match eval(&self.expr, outcome)? {
+            Value::Bool(b) => Ok(b),
+            other => bail!("condition must be a boolean, got {}", other.type_name()),
+        }
+    }
+}
+
+// ── Tokens ────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Int(i64),
+    Str(String),
+    Op(&'static str),
+}
+
+const OPS: &[&str] = &[
+    "==", "!=", "<=", ">=", "&&", "||", "<", ">", "!", "(", ")", ",",
+];
+
+fn tokenize(src: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = src.char_indices().peekable();
+    while let Some(&(i, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '\'' || c == '"' {
+            chars.next();
+            let mut s = String::new();
+            loop {
+                match chars.next() {
+                    Some((_, ch)) if ch == c => break,
+                    Some((_, ch)) => s.push(ch),
+                    None => bail!("unterminated string literal in condition"),
+                }
+            }
+            tokens.push(Token::Str(s));
+        } else if c.is_ascii_digit() {
+            let mut end = i;
+            while let Some(&(j, d)) = chars.peek() {
+                if !d.is_ascii_digit() {
+                    break;
+                }
+                end = j + d.len_utf8();
+                chars.next();
+            }
+            tokens.push(Token::Int(src[i..end].parse()?));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut end = i;
+            while let Some(&(j, d)) = chars.peek() {
+                if !(d.is_alphanumeric() || d == '_') {
+                    break;
+                }
+                end = j + d.len_utf8();
+                chars.next();
+            }
+            tokens.push(Token::Ident(src[i..end].to_string()));
+        } else if let Some(op) = OPS.iter().find(|op| src[i..].starts_with(**op)) {
+            for _ in 0..op.len() {
+                chars.next();
+            }
+            tokens.push(Token::Op(op));
+        } else {
+            bail!("unexpected character {c:?} in condition");
+        }
+    }
+    Ok(tokens)
+}
+
+// ── Syntax tree ───────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Lit(Value),
+    Var(Var),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Cmp(&'static str, Box<Expr>, Box<Expr>),
+    Call(Func, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Var {
+    ExitCode,
+    LastOutput,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Func {
+    Contains,
+    StartsWith,
+    EndsWith,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Bool(bool),
+    Int(i64),
+    Str(String),
+}
+
+impl Value {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Bool(_) => "boolean",
+            Value::Int(_) => "integer",
+            Value::Str(_) => "string",
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek_op(&self, op: &str) -> bool {
+        matches!(self.tokens.get(self.pos), Some(Token::Op(o)) if *o == op)
+    }
+
+    fn expect_op(&mut self, op: &str) -> Result<()> {
+        if !self.peek_op(op) {
+            bail!("expected {op:?} in condition");
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        let mut lhs = self.and()?;
+        while self.peek_op("||") {
+            self.pos += 1;
+            lhs = Expr::Or(Box::new(lhs), Box::new(self.and()?));
+        }
+        Ok(lhs)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut lhs = self.unary()?;
+        while self.peek_op("&&") {
+            self.pos += 1;
+            lhs = Expr::And(Box::new(lhs), Box::new(self.unary()?));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        if self.peek_op("!") {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr> {
+        let lhs = self.primary()?;
+        for op in ["==", "!=", "<=", ">=", "<", ">"] {
+            if self.peek_op(op) {
+                self.pos += 1;
+                let rhs = self.primary()?;
+                return Ok(Expr::Cmp(op, Box::new(lhs), Box::new(rhs)));
+            }
+        }
+        Ok(lhs)
+    }
+
+    fn primary(&mut self) -> Result<Expr> {
+        let Some(tok) = self.tokens.get(self.pos).cloned() else {
+            bail!("condition ends unexpectedly");
+        };
+        self.pos += 1;
+        match tok {
+            Token::Int(n) => Ok(Expr::Lit(Value::Int(n))),
+            Token::Str(s) => Ok(Expr::Lit(Value::Str(s))),
+            Token::Op("(") => {
+                let inner = self.or()?;
+                self.expect_op(")")?;
+                Ok(inner)
+            }
+            Token::Op(op) => bail!("unexpected {op:?} in condition"),
+            Token::Ident(name) => match name.as_str() {
+                "true" => Ok(Expr::Lit(Value::Bool(true))),
+                "false" => Ok(Expr::Lit(Value::Bool(false))),
+                "exit_code" => Ok(Expr::Var(Var::ExitCode)),
+                "last_output" => Ok(Expr::Var(Var::LastOutput)),
+                "contains" | "starts_with" | "ends_with" => {
+                    let func = match name.as_str() {
+                        "contains" => Func::Contains,
+                        "starts_with" => Func::StartsWith,
+                        _ => Func::EndsWith,
+                    };
+                    self.expect_op("(")?;
+                    let a = self.or()?;
+                    self.expect_op(",")?;
+                    let b = self.or()?;
+                    self.expect_op(")")?;
+                    Ok(Expr::Call(func, Box::new(a), Box::new(b)))
+                }
+                other => bail!("unknown name {other:?} in condition"),
+            },
+        }
+    }
+}
+
+// ── Evaluation ────────────────────────────────────────────────────────────────
+
+fn eval(expr: &Expr, outcome: &StepOutcome<'_>) -> Result<Value> {
+    Ok(match expr {
+        Expr::Lit(v) => v.clone(),
+        Expr::Var(Var::ExitCode) => Value::Int(outcome.exit_code),
+        Expr::Var(Var::LastOutput) => Value::Str(outcome.last_output.to_string()),
+        Expr::Not(e) => Value::Bool(!eval_bool(e, outcome)?),
+        // Both sides are always evaluated so type errors surface regardless
+        // of the outcome; expressions have no side effects.
+        Expr::And(a, b) => {
+            let (a, b) = (eval_bool(a, outcome)?, eval_bool(b, outcome)?);
+            Value::Bool(a && b)
+        }
+        Expr::Or(a, b) => {
+            let (a, b) = (eval_bool(a, outcome)?, eval_bool(b, outcome)?);
+            Value::Bool(a || b)
+        }
+        Expr::Cmp(op, a, b) => {
+            let (a, b) = (eval(a, outcome)?, eval(b, outcome)?);
+            Value::Bool(compare(op, &a, &b)?)
+        }
+        Expr::Call(func, a, b) => {
+            let (Value::Str(a), Value::Str(b)) = (eval(a, outcome)?, eval(b, outcome)?) else {
+                bail!("{func:?} expects two strings");
+            };
+            Value::Bool(match func {
+                Func::Contains => a.contains(&b),
+                Func::StartsWith => a.starts_with(&b),
+                Func::EndsWith => a.ends_with(&b),
+            })
+        }
+    })
+}
+
+fn eval_bool(expr: &Expr, outcome: &StepOutcome<'_>) -> Result<bool> {
+    match eval(expr, outcome)? {
+        Value::Bool(b) => Ok(b),
+        other => bail!("expected a boolean, got {}", other.type_name()),
+    }
+}
+
+fn compare(op: &str, a: &Value, b: &Value) -> Result<bool> {
+    match (op, a, b) {
+        ("==", _, _) if a.type_name() == b.type_name() => Ok(a == b),
+        ("!=", _, _) if a.type_name() == b.type_name() => Ok(a != b),
+        (_, Value::Int(a), Value::Int(b)) => Ok(match op {
+            "<" => a < b,
+            "<=" => a <= b,
+            ">" => a > b,
+            _ => a >= b,
+        }),
+        _ => bail!("cannot compare {} {op} {}", a.type_name(), b.type_name()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval_with(src: &str, exit_code: i64, last_output: &str) -> bool {
+        Condition::parse(src)
+            .unwrap()
+            .eval(&StepOutcome {
+                exit_code,
+                last_output,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn exit_code_comparisons() {
+        assert!(eval_with("exit_code == 0", 0, ""));
+        assert!(!eval_with("exit_code == 0", 3, ""));
+        assert!(eval_with("exit_code != 0", 3, ""));
+        assert!(eval_with("exit_code >= 3 && exit_code < 4", 3, ""));
+    }
+
+    #[test]
+    fn string_functions() {
+        assert!(eval_with("contains(last_output, 'PASS')", 0, "tests: PASS"));
+        assert!(!eval_with("contains(last_output, \"PASS\")", 0, "FAIL"));
+        assert!(eval_with("starts_with(last_output, 'ok')", 0, "ok then"));
+        assert!(eval_with("ends_with(last_output, 'done')", 0, "all done"));
+        assert!(eval_with("last_output == 'x'", 0, "x"));
+    }
+
+    #[test]
+    fn boolean_logic_and_precedence() {
+        assert!(eval_with("!contains(last_output, 'FAIL')", 0, "PASS"));
+        assert!(eval_with("false || true && true", 0, ""));
+        assert!(!eval_with("(false || true) && false", 0, ""));
+        assert!(eval_with("true", 0, ""));
+    }
+
+    #[test]
+    fn rejects_unknown_names_and_type_errors() {
+        assert!(Condition::parse("exitcode == 0").is_err());
+        assert!(Condition::parse("system('rm -rf /')").is_err());
+        assert!(Condition::parse("exit_code == 'zero'").is_err());
+        assert!(Condition::parse("last_output < 'a'").is_err());
+        assert!(Condition::parse("exit_code").is_err(), "must be boolean");
+        assert!(Condition::parse("contains(last_output)").is_err());
+        assert!(Condition::parse("exit_code == 0 )").is_err());
+        assert!(Condition::parse("'open").is_err());
+    }
+}
@@ -14,8 +14,8 @@ use sven_bootstrap::{AgentBuilder, ToolSetProfile};
 use sven_config::{AgentMode, Config};
 use sven_core::AgentEvent;
 use sven_input::{
-    parse_conversation, parse_jsonl_full, serialize_conversation_turn_with_metadata,
-    serialize_jsonl_records, ConversationRecord, TurnMetadata,
+    parse_conversation, parse_jsonl_full, serialize_conversation_records, serialize_jsonl_records,
+    ConversationRecord, TurnMetadata,
 };
 use sven_model::{FunctionCall, Message, MessageContent, Role};
 use sven_tools::events::TodoItem;
@@ -32,6 +32,31 @@ pub struct ConversationOptions {
     pub file_path: PathBuf,
     /// The full file content (already read by the caller).
     pub content: String,
+    /// Compact the loaded history before the new turn when it holds more
+    /// than this many user turns.
+    pub max_history_turns: Option<usize>,
+    /// Compact the loaded history before the new turn when its estimated
+    /// size exceeds this many tokens.
+    pub max_context_tokens: Option<usize>,
+}
+
+/// Whether `history` is over either of the caps in [`ConversationOptions`].
+fn exceeds_history_cap(
+    history: &[Message],
+    max_turns: Option<usize>,
+    max_tokens: Option<usize>,
+) -> bool {
+    let turns = history.iter().filter(|m| m.role == Role::User).count();
+    if max_turns.is_some_and(|max| turns > max) {
+        return true;
+    }
+    max_tokens.is_some_and(|max| {
+        history
+            .iter()
+            .map(sven_core::estimate_message_tokens)
+            .sum::<usize>()
+            > max
+    })
 }
 
 /// Headless runner for conversation files.
@@ -60,8 +85,14 @@ impl ConversationRunner {
         // For JSONL files: use the full-fidelity parser which handles both
         // the new ConversationRecord format and the legacy raw-Message format.
         let (history, pending, existing_jsonl_records) = if is_jsonl {
-            let parsed = parse_jsonl_full(&opts.content)
+            let mut parsed = parse_jsonl_full(&opts.content)
                 .context("failed to parse JSONL conversation file")?;
+            // Rewrite the file with its image references as they are; only
+            // the history sent to the model gets the images inlined.
+            let records = parsed.records.clone();
+            parsed
+                .inline_image_files()
+                .context("failed to load images of JSONL conversation file")?;
             let pending = match parsed.pending_user_input {
                 Some(p) => p,
                 None => {
@@ -71,7 +102,7 @@ impl ConversationRunner {
                     return Ok(());
                 }
             };
-            (parsed.history, pending, Some(parsed.records))
+            (parsed.history, pending, Some(records))
         } else {
             let parsed =
                 parse_conversation(&opts.content).context("failed to parse conversation file")?;
@@ -132,7 +163,7 @@ impl ConversationRunner {
             .with_allow_interactive_oauth(false)
             .with_wait_for_mcp_tools(20_000)
             .build(opts.mode, model.clone(), profile)
-            .await;
+            .await?;
 
         // Load conversation history into the agent session.
         // replace_history_and_submit prepends the system message and then adds
@@ -142,9 +173,17 @@ impl ConversationRunner {
         // The submit_fut holds a mutable borrow on `agent`. Scoping it in a
         // block ensures it is dropped before we need to call agent.session()
         // for subsequent operations below.
+        let compact_first =
+            exceeds_history_cap(&history, opts.max_history_turns, opts.max_context_tokens);
+        if compact_first {
+            write_stderr(&format!(
+                "[sven:compact] resumed history over its cap ({} messages) — compacting before the turn",
+                history.len()
+            ));
+        }
         let (new_records, failed) = {
             let (tx, mut rx) = mpsc::channel::<AgentEvent>(256);
-            let submit_fut = agent.replace_history_and_submit(history, &pending, tx);
+            let submit_fut = submit_turn(&mut agent, history, &pending, compact_first, tx);
 
             // Collect full-fidelity records including thinking blocks.
             // The pending user message is first (not yet in the file for md format;
@@ -204,32 +243,25 @@ impl ConversationRunner {
             // Skip the first new_record (the user message that was already in the file)
             all_records.extend_from_slice(&new_records[1..]);
             let serialized = serialize_jsonl_records(&all_records);
-            std::fs::write(&opts.file_path, serialized.as_bytes()).with_context(|| {
-                format!(
-                    "writing JSONL conversation file: {}",
-                    opts.file_path.display()
-                )
-            })?;
+            sven_input::write_atomic(&opts.file_path, serialized.as_bytes()).with_context(
+                || {
+                    format!(
+                        "writing JSONL conversation file: {}",
+                        opts.file_path.display()
+                    )
+                },
+            )?;
             debug!(
                 records = all_records.len(),
                 "rewrote JSONL conversation file"
             );
         } else {
-            // For markdown: append new messages only (excluding the user, which
-            // is already present), using the legacy serializer.
-            let new_messages: Vec<Message> = new_records[1..]
-                .iter()
-                .filter_map(|r| {
-                    if let ConversationRecord::Message(m) = r {
-                        Some(m.clone())
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-            if !new_messages.is_empty() {
-                let serialized =
-                    serialize_conversation_turn_with_metadata(&new_messages, Some(&turn_metadata));
+            // For markdown: append the new records only (excluding the user,
+            // which is already present); thinking blocks and compaction notes
+            // are kept as marker blocks.
+            let new_records = &new_records[1..];
+            if !new_records.is_empty() {
+                let serialized = serialize_conversation_records(new_records, Some(&turn_metadata));
                 let mut file = OpenOptions::new()
                     .append(true)
                     .open(&opts.file_path)
@@ -249,6 +281,23 @@ impl ConversationRunner {
     }
 }
 
+/// Load `history` and run `pending` as the next turn, compacting the
+/// history first when `compact_first` is set.
+async fn submit_turn(
+    agent: &mut sven_core::Agent,
+    history: Vec<Message>,
+    pending: &str,
+    compact_first: bool,
+    tx: mpsc::Sender<AgentEvent>,
+) -> anyhow::Result<()> {
+    if !compact_first {
+        return agent.replace_history_and_submit(history, pending, tx).await;
+    }
+    agent.seed_history(history).await;
+    agent.compact_history(&tx).await?;
+    agent.submit(pending, tx).await
+}
+
 // ── Full-fidelity event → ConversationRecord collector ────────────────────────
 
 /// Translate an `AgentEvent` into `ConversationRecord`s, capturing every
@@ -364,6 +413,12 @@ fn collect_event_full(event: AgentEvent, records: &mut Vec<ConversationRecord>,
             write_stderr(&format!("[sven:model:changed] switching to {model}"));
         }
 
+        AgentEvent::ModelFallback { from, to } => {
+            write_stderr(&format!(
+                "[sven:model:fallback] {from} failed — using {to} for this completion"
+            ));
+        }
+
         AgentEvent::Question { questions, .. } => {
             write_stderr(&format!("[sven:questions] {}", questions.join(" | ")));
         }
@@ -378,8 +433,9 @@ fn collect_event_full(event: AgentEvent, records: &mut Vec<ConversationRecord>,
             max_tokens,
             max_output_tokens,
             cost_usd: _,
+            estimated,
         } => {
-            let line = format_token_usage_line(
+            let mut line = format_token_usage_line(
                 input,
                 output,
                 cache_read,
@@ -389,6 +445,9 @@ fn collect_event_full(event: AgentEvent, records: &mut Vec<ConversationRecord>,
                 max_tokens,
                 max_output_tokens,
             );
+            if estimated {
+                line.push_str(" estimated=true");
+            }
             write_stderr(&line);
         }
 
@@ -402,7 +461,9 @@ fn collect_event_full(event: AgentEvent, records: &mut Vec<ConversationRecord>,
         | AgentEvent::DelegateSummary { .. }
         | AgentEvent::SubagentStarted { .. }
         | AgentEvent::SubagentEvent { .. }
-        | AgentEvent::PeerList(_) => {}
+        | AgentEvent::PeerList(_)
+        | AgentEvent::Paused
+        | AgentEvent::Resumed => {}
         AgentEvent::Aborted { partial_text } => {
             if !partial_text.is_empty() {
                 write_stderr(&format!("[sven:agent:aborted] partial={:?}", partial_text));
@@ -415,3 +476,97 @@ fn collect_event_full(event: AgentEvent, records: &mut Vec<ConversationRecord>,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sven_config::AgentConfig;
+    use sven_model::{ResponseEvent, ScriptedMockProvider};
+    use sven_tools::{events::ToolEvent, ToolRegistry};
+
+    /// A resumed conversation with `turns` question/answer pairs and a
+    /// trailing pending `## User` section.
+    fn long_conversation(turns: usize) -> String {
+        let mut md = String::new();
+        for i in 1..=turns {
+            md.push_str(&format!(
+                "## User\n\nQuestion {i}: what does module {i} do?\n\n\
+                 ## Sven\n\nModule {i} parses section {i} of the config.\n\n"
+            ));
+        }
+        md.push_str("## User\n\nWhich module handles section 3?\n");
+        md
+    }
+
+    fn agent(model: ScriptedMockProvider) -> sven_core::Agent {
+        let (_tx, tool_event_rx) = mpsc::channel::<ToolEvent>(8);
+        sven_core::Agent::new(
+            Arc::new(model),
+            Arc::new(ToolRegistry::default()),
+            Arc::new(AgentConfig::default()),
+            sven_core::AgentRuntimeContext::default(),
+            Arc::new(Mutex::new(AgentMode::Agent)),
+            tool_event_rx,
+            128_000,
+        )
+    }
+
+    fn reply(text: &str) -> Vec<ResponseEvent> {
+        vec![ResponseEvent::TextDelta(text.into()), ResponseEvent::Done]
+    }
+
+    #[test]
+    fn history_caps_count_user_turns_and_estimated_tokens() {
+        let history = parse_conversation(&long_conversation(3)).unwrap().history;
+        assert!(!exceeds_history_cap(&history, None, None));
+        assert!(!exceeds_history_cap(&history, Some(3), None));
+        assert!(exceeds_history_cap(&history, Some(2), None));
+        assert!(exceeds_history_cap(&history, None, Some(10)));
+        assert!(!exceeds_history_cap(&history, None, Some(10_000)));
+    }
+
+    #[tokio::test]
+    async fn long_resumed_conversation_is_compacted_before_the_new_turn() {
+        let parsed = parse_conversation(&long_conversation(40)).unwrap();
+        let pending = parsed.pending_user_input.unwrap();
+        assert!(exceeds_history_cap(&parsed.history, Some(20), None));
+
+        let model = ScriptedMockProvider::new(vec![
+            reply("Summary: modules 1-40 each parse their own config section."),
+            reply("Module 3."),
+        ]);
+        let mut agent = agent(model);
+        let (tx, mut rx) = mpsc::channel(256);
+        submit_turn(&mut agent, parsed.history, &pending, true, tx)
+            .await
+            .unwrap();
+
+        let mut events = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            events.push(ev);
+        }
+        let compacted = events
+            .iter()
+            .position(|e| matches!(e, AgentEvent::ContextCompacted { .. }))
+            .expect("history was compacted");
+        let answer = events
+            .iter()
+            .position(|e| matches!(e, AgentEvent::TextComplete(t) if t == "Module 3."))
+            .expect("the new turn ran");
+        assert!(compacted < answer, "compaction must precede the new turn");
+
+        let messages = &agent.session().messages;
+        assert!(
+            messages.len() < 20,
+            "early turns were replaced by a summary: {} messages",
+            messages.len()
+        );
+        assert!(messages
+            .iter()
+            .any(|m| m.as_text().is_some_and(|t| t.starts_with("Summary:"))));
+        assert!(messages
+            .iter()
+            .all(|m| m.as_text() != Some("Question 1: what does module 1 do?")));
+        assert_eq!(messages.last().and_then(|m| m.as_text()), Some("Module 3."));
+    }
+}
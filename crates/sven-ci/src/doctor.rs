@@ -0,0 +1,319 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! `sven doctor` — check that the environment is ready to run sven.
+//!
+//! Every check yields a [`Check`] with a pass/warn/fail status.  Failures stop
+//! sven from working at all (a config that does not load, no API key, an
+//! endpoint that rejects the key); warnings are optional pieces that only
+//! some features need.
+
+use std::path::{Path, PathBuf};
+
+use sven_config::{Config, ModelConfig};
+use sven_runtime::CiContext;
+
+/// Outcome of a single check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Pass => "ok",
+            Self::Warn => "warn",
+            Self::Fail => "FAIL",
+        }
+    }
+}
+
+/// One line of the doctor checklist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[{:<4}] {}: {}",
+            self.status.label(),
+            self.name,
+            self.detail
+        )
+    }
+}
+
+/// Run every check.  The provider health check makes one network request
+/// and is skipped when `offline` is set or no credentials resolve.
+pub async fn run(extra: Option<&Path>, offline: bool) -> Vec<Check> {
+    let ci = sven_runtime::detect_ci_context();
+    let mut checks = vec![check_ci(&ci)];
+
+    let (config_check, config) = check_config(extra);
+    checks.push(config_check);
+    if let Some(config) = config {
+        let credentials = check_credentials(&config.model);
+        let credentials_ok = credentials.status == CheckStatus::Pass;
+        checks.push(credentials);
+        if !offline && credentials_ok {
+            checks.push(check_provider_health(&config.model).await);
+        }
+    }
+
+    checks.push(check_program(
+        "git",
+        "project detection and git context",
+        true,
+    ));
+    // The editor and the resume picker are interactive-only.
+    let in_ci = ci.provider.is_some();
+    checks.push(optional_program(
+        "nvim",
+        "the embedded editor in the TUI",
+        in_ci,
+    ));
+    checks.push(optional_program(
+        "fzf",
+        "picking a conversation with --resume",
+        in_ci,
+    ));
+    checks
+}
+
+/// Render `checks` as a checklist followed by a summary line.
+pub fn render(checks: &[Check]) -> String {
+    let mut out = String::new();
+    for check in checks {
+        out.push_str(&check.to_string());
+        out.push('\n');
+    }
+    let count = |status| checks.iter().filter(|c| c.status == status).count();
+    out.push_str(&format!(
+        "\n{} passed, {} warning(s), {} failed\n",
+        count(CheckStatus::Pass),
+        count(CheckStatus::Warn),
+        count(CheckStatus::Fail)
+    ));
+    out
+}
+
+/// Report the CI environment sven detects; never fails.
+pub fn check_ci(ci: &CiContext) -> Check {
+    let detail = match &ci.provider {
+        Some(provider) => format!("running in {provider}"),
+        None => "not running in CI".to_string(),
+    };
+    Check::new("environment", CheckStatus::Pass, detail)
+}
+
+/// Load the configuration and check its named `providers` entries.
+pub fn check_config(extra: Option<&Path>) -> (Check, Option<Config>) {
+    match sven_config::load(extra) {
+        Err(e) => (
+            Check::new("config", CheckStatus::Fail, format!("{e:#}")),
+            None,
+        ),
+        Ok(config) => {
+            let issues = sven_model::check_providers(&config);
+            let check = if issues.is_empty() {
+                Check::new("config", CheckStatus::Pass, "loaded")
+            } else {
+                let issues: Vec<String> = issues.iter().map(|i| i.to_string()).collect();
+                Check::new("config", CheckStatus::Warn, issues.join("; "))
+            };
+            (check, Some(config))
+        }
+    }
+}
+
+/// Check that `cfg`'s provider is known and that an API key resolves for it
+/// when one is needed.
+pub fn check_credentials(cfg: &ModelConfig) -> Check {
+    let name = format!("credentials ({})", cfg.provider);
+    let Some(meta) = sven_model::get_driver(&cfg.provider) else {
+        return Check::new(
+            name,
+            CheckStatus::Fail,
+            format!(
+                "unknown provider {:?} (run `sven list-providers`)",
+                cfg.provider
+            ),
+        );
+    };
+    let env = cfg.api_key_env.as_deref().or(meta.default_api_key_env);
+    if sven_model::resolve_api_key(cfg).is_some() {
+        let source = match (&cfg.api_key, env) {
+            (Some(_), _) => "model.api_key".to_string(),
+            (None, Some(env)) => format!("${env}"),
+            (None, None) => "config".to_string(),
+        };
+        return Check::new(name, CheckStatus::Pass, format!("API key from {source}"));
+    }
+    // An explicit api_key_env is a request for a key even on keyless drivers.
+    if !meta.requires_api_key && cfg.api_key_env.is_none() {
+        return Check::new(name, CheckStatus::Pass, "no API key required");
+    }
+    let detail = match env {
+        Some(env) => format!("no API key: ${env} is not set (or set model.api_key)"),
+        None => "no API key: set model.api_key or model.api_key_env".to_string(),
+    };
+    Check::new(name, CheckStatus::Fail, detail)
+}
+
+/// Build the provider for `cfg` and run its health check.
+pub async fn check_provider_health(cfg: &ModelConfig) -> Check {
+    let name = format!("provider ({}/{})", cfg.provider, cfg.name);
+    let provider = match sven_model::from_config(cfg) {
+        Ok(p) => p,
+        Err(e) => {
+            let msg = e.to_string();
+            let first = msg.lines().next().unwrap_or_default();
+            return Check::new(name, CheckStatus::Fail, first);
+        }
+    };
+    match provider.health_check().await {
+        Ok(true) => Check::new(name, CheckStatus::Pass, "endpoint reachable, key accepted"),
+        Ok(false) => Check::new(
+            name,
+            CheckStatus::Warn,
+            "not verified: this driver has no health check",
+        ),
+        Err(e) => Check::new(name, CheckStatus::Fail, format!("{e:#}")),
+    }
+}
+
+/// Check for the program `name` on `PATH`.  A missing `required` program
+/// fails the check; any other missing program is a warning.
+pub fn check_program(name: &str, used_for: &str, required: bool) -> Check {
+    match find_on_path(name) {
+        Some(path) => Check::new(name, CheckStatus::Pass, path.display().to_string()),
+        None => Check::new(
+            name,
+            if required {
+                CheckStatus::Fail
+            } else {
+                CheckStatus::Warn
+            },
+            format!("not found on PATH (used for {used_for})"),
+        ),
+    }
+}
+
+/// [`check_program`] for an interactive-only program, which is not needed
+/// in CI.
+fn optional_program(name: &str, used_for: &str, in_ci: bool) -> Check {
+    let mut check = check_program(name, used_for, false);
+    if in_ci && check.status == CheckStatus::Warn {
+        check.status = CheckStatus::Pass;
+        check.detail.push_str("; not needed in CI");
+    }
+    check
+}
+
+fn find_on_path(name: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(name))
+        .find(|p| p.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(provider: &str) -> ModelConfig {
+        ModelConfig {
+            provider: provider.into(),
+            name: "test-model".into(),
+            ..ModelConfig::default()
+        }
+    }
+
+    #[test]
+    fn credentials_pass_when_key_env_is_set() {
+        std::env::set_var("SVEN_DOCTOR_TEST_KEY_SET", "sk-test");
+        let cfg = ModelConfig {
+            api_key_env: Some("SVEN_DOCTOR_TEST_KEY_SET".into()),
+            ..model("openai")
+        };
+        let check = check_credentials(&cfg);
+        assert_eq!(check.status, CheckStatus::Pass);
+        assert_eq!(check.detail, "API key from $SVEN_DOCTOR_TEST_KEY_SET");
+
+        let cfg = ModelConfig {
+            api_key: Some("sk-inline".into()),
+            ..model("openai")
+        };
+        let check = check_credentials(&cfg);
+        assert_eq!(check.status, CheckStatus::Pass);
+        assert!(!check.detail.contains("sk-inline"), "key must not be shown");
+    }
+
+    #[test]
+    fn credentials_fail_when_key_is_missing() {
+        std::env::remove_var("SVEN_DOCTOR_TEST_KEY_UNSET");
+        let cfg = ModelConfig {
+            api_key_env: Some("SVEN_DOCTOR_TEST_KEY_UNSET".into()),
+            ..model("anthropic")
+        };
+        let check = check_credentials(&cfg);
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(
+            check
+                .detail
+                .contains("$SVEN_DOCTOR_TEST_KEY_UNSET is not set"),
+            "{}",
+            check.detail
+        );
+    }
+
+    #[test]
+    fn credentials_for_local_and_unknown_providers() {
+        assert_eq!(
+            check_credentials(&model("ollama")).status,
+            CheckStatus::Pass
+        );
+        let check = check_credentials(&model("opena"));
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check.detail.contains("unknown provider"));
+    }
+
+    #[test]
+    fn missing_programs_fail_or_warn() {
+        let name = "sven-doctor-no-such-program";
+        assert_eq!(check_program(name, "x", true).status, CheckStatus::Fail);
+        assert_eq!(check_program(name, "x", false).status, CheckStatus::Warn);
+        assert_eq!(optional_program(name, "x", true).status, CheckStatus::Pass);
+    }
+
+    #[test]
+    fn render_lists_checks_and_summary() {
+        let checks = [
+            Check::new("config", CheckStatus::Pass, "loaded"),
+            Check::new("fzf", CheckStatus::Warn, "not found on PATH"),
+        ];
+        assert_eq!(
+            render(&checks),
+            "[ok  ] config: loaded\n[warn] fzf: not found on PATH\n\n\
+             1 passed, 1 warning(s), 0 failed\n"
+        );
+    }
+}
@@ -4,12 +4,13 @@
 //! Per-step event handler: collects messages and records, tracks token usage.
 
 use sven_core::AgentEvent;
-use sven_input::ConversationRecord;
+use sven_input::{serialize_conversation_records, ConversationRecord};
 use sven_model::{FunctionCall, Message, MessageContent, Role};
 
 use crate::output::{format_token_usage_line, write_stderr, write_stdout};
 
-use super::{OutputFormat, EXIT_BUDGET_EXHAUSTED};
+use super::helpers::Failure;
+use super::{OutputFormat, StepMetrics, EXIT_BUDGET_EXHAUSTED};
 
 /// Push a `ConversationRecord` and, when `output_format` is `Jsonl`, also
 /// stream the serialized line to stdout immediately.  This is the single place
@@ -30,9 +31,34 @@ pub(super) fn emit_record(
     records.push(record);
 }
 
+/// In conversation format, stream a thinking block or compaction note as its
+/// marker block so piping the output on does not lose it.
+fn write_conversation_record(
+    record: &ConversationRecord,
+    output_format: OutputFormat,
+    sven_header_emitted: &mut bool,
+) {
+    if output_format != OutputFormat::Conversation {
+        return;
+    }
+    // Close any open Sven text section first
+    if *sven_header_emitted {
+        write_stdout("\n\n");
+        *sven_header_emitted = false;
+    }
+    write_stdout(&serialize_conversation_records(
+        std::slice::from_ref(record),
+        None,
+    ));
+}
+
 /// Per-step mutable state threaded through the event handler.
 pub(super) struct StepState<'a> {
     pub response_text: &'a mut String,
+    /// Assistant text streamed since the last `TextComplete`.
+    pub partial_text: &'a mut String,
+    /// Turn and token counts for the step.
+    pub metrics: &'a mut StepMetrics,
     pub tools_used: &'a mut Vec<String>,
     pub failed: &'a mut bool,
     pub collected: &'a mut Vec<Message>,
@@ -51,6 +77,10 @@ pub(super) struct StepState<'a> {
     pub run_total_tokens: &'a mut u64,
     /// Optional token budget cap; when exceeded the runner exits with code 4.
     pub max_tokens_budget: Option<u64>,
+    /// How a budget exit is reported (see `--json-errors`).
+    pub failure: Failure,
+    /// 1-based index of the step the events belong to.
+    pub step_idx: usize,
 }
 /// Process a single agent event: write diagnostics to stderr, collect
 /// messages into `collected` and `jsonl_records`, and track response text / tool usage.
@@ -67,6 +97,7 @@ pub(super) fn handle_event(event: AgentEvent, s: &mut StepState<'_>) {
     match event {
         AgentEvent::TextDelta(delta) => {
             response_text.push_str(&delta);
+            s.partial_text.push_str(&delta);
             // Stream to stdout in real-time for conversation format.
             if output_format == OutputFormat::Conversation {
                 if !*sven_header_emitted {
@@ -77,6 +108,7 @@ pub(super) fn handle_event(event: AgentEvent, s: &mut StepState<'_>) {
             }
         }
         AgentEvent::TextComplete(text) => {
+            s.partial_text.clear();
             if !text.is_empty() {
                 collected.push(Message::assistant(&text));
                 emit_record(
@@ -199,16 +231,14 @@ pub(super) fn handle_event(event: AgentEvent, s: &mut StepState<'_>) {
             write_stderr(&format!(
                 "[sven:context:compacted:{strategy}] {tokens_before} → {tokens_after} tokens{turn_note}"
             ));
-            emit_record(
-                jsonl_records,
-                ConversationRecord::ContextCompacted {
-                    tokens_before,
-                    tokens_after,
-                    strategy: Some(strategy.to_string()),
-                    turn: Some(turn),
-                },
-                output_format,
-            );
+            let record = ConversationRecord::ContextCompacted {
+                tokens_before,
+                tokens_after,
+                strategy: Some(strategy.to_string()),
+                turn: Some(turn),
+            };
+            write_conversation_record(&record, output_format, sven_header_emitted);
+            emit_record(jsonl_records, record, output_format);
         }
         AgentEvent::Error(msg) => {
             write_stderr(&format!("[sven:agent:error] {msg}"));
@@ -230,6 +260,11 @@ pub(super) fn handle_event(event: AgentEvent, s: &mut StepState<'_>) {
         AgentEvent::ModelChanged(model) => {
             write_stderr(&format!("[sven:model:changed] switching to {model}"));
         }
+        AgentEvent::ModelFallback { from, to } => {
+            write_stderr(&format!(
+                "[sven:model:fallback] {from} failed — using {to} for this completion"
+            ));
+        }
         AgentEvent::Question { questions, .. } => {
             write_stderr(&format!("[sven:questions] {}", questions.join(" | ")));
         }
@@ -243,17 +278,25 @@ pub(super) fn handle_event(event: AgentEvent, s: &mut StepState<'_>) {
             max_tokens,
             max_output_tokens,
             cost_usd: _,
+            estimated: _,
         } => {
             *s.session_input_total += input;
             *s.session_output_total += output;
+            s.metrics.turns += 1;
+            s.metrics.input_tokens += input as u64;
+            s.metrics.output_tokens += output as u64;
+            s.metrics.cache_read_tokens += cache_read as u64;
+            s.metrics.cache_write_tokens += cache_write as u64;
             *s.run_total_tokens += (input + output) as u64;
             if let Some(budget) = s.max_tokens_budget {
                 if budget > 0 && *s.run_total_tokens >= budget {
-                    write_stderr(&format!(
-                        "[sven:error] Token budget exhausted: {} tokens used (budget: {}). Stopping.",
+                    let message = format!(
+                        "Token budget exhausted: {} tokens used (budget: {})",
                         s.run_total_tokens, budget
-                    ));
-                    std::process::exit(EXIT_BUDGET_EXHAUSTED);
+                    );
+                    write_stderr(&format!("[sven:error] {message}. Stopping."));
+                    s.failure
+                        .exit(EXIT_BUDGET_EXHAUSTED, Some(s.step_idx), &message);
                 }
             }
             let mut line = format_token_usage_line(
@@ -275,11 +318,9 @@ pub(super) fn handle_event(event: AgentEvent, s: &mut StepState<'_>) {
         AgentEvent::ThinkingDelta(_) => {}
         AgentEvent::ThinkingComplete(content) => {
             write_stderr(&format!("[sven:thinking] {content}"));
-            emit_record(
-                jsonl_records,
-                ConversationRecord::Thinking { content },
-                output_format,
-            );
+            let record = ConversationRecord::Thinking { content };
+            write_conversation_record(&record, output_format, sven_header_emitted);
+            emit_record(jsonl_records, record, output_format);
         }
         AgentEvent::ToolProgress { message, .. } => {
             write_stderr(&format!("[sven:progress] {message}"));
@@ -291,7 +332,9 @@ pub(super) fn handle_event(event: AgentEvent, s: &mut StepState<'_>) {
         | AgentEvent::DelegateSummary { .. }
         | AgentEvent::SubagentStarted { .. }
         | AgentEvent::SubagentEvent { .. }
-        | AgentEvent::PeerList(_) => {}
+        | AgentEvent::PeerList(_)
+        | AgentEvent::Paused
+        | AgentEvent::Resumed => {}
         AgentEvent::Aborted { partial_text } => {
             if !partial_text.is_empty() {
                 write_stderr(&format!("[sven:agent:aborted] partial={:?}", partial_text));
@@ -2,15 +2,25 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 //! Utility functions: format detection, artifact writing, JSON serialisation,
-//! agent mode parsing, cache key sanitisation, and label normalisation.
+//! agent mode parsing, per-step model resolution, cache key sanitisation, and
+//! label normalisation.
 
-use sven_config::AgentMode;
-use sven_input::serialize_conversation_turn;
-use sven_model::Message;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use sven_config::{AgentMode, Config, ModelConfig, ToolsConfig};
+use sven_input::{
+    jsonl_metrics_line, serialize_conversation_turn, serialize_jsonl_records, ConversationRecord,
+    Step, StepOptions,
+};
+use sven_model::{Message, Role};
 
 use crate::output::write_stderr;
 
-use super::JsonOutput;
+use super::{
+    JsonOutput, OutputFormat, StepMetrics, EXIT_AGENT_ERROR, EXIT_BUDGET_EXHAUSTED, EXIT_INTERRUPT,
+    EXIT_TIMEOUT, EXIT_TOOL_WARNINGS, EXIT_VALIDATION_ERROR,
+};
 
 // ── Helpers ───────────────────────────────────────────────────────────────────
 
@@ -140,8 +150,135 @@ pub(super) fn write_conversation_artifact(dir: &std::path::Path, messages: &[Mes
     }
 }
 
+/// Write combined JSONL to path.
+///
+/// Layout: [system_record] ++ [existing non-system] ++ [new non-system]
+/// The system record from `system_record` is always placed first so the
+/// file is fully self-contained and reproducible.  Any system messages
+/// already present in `existing` are stripped to avoid duplication.
+/// With `image_dir`, embedded images are written there and referenced by
+/// path (see [`sven_input::externalize_images`]).
+pub(super) fn flush_jsonl(
+    path: &std::path::Path,
+    system_record: Option<&ConversationRecord>,
+    existing: &[ConversationRecord],
+    new_records: &[ConversationRecord],
+    image_dir: Option<&std::path::Path>,
+) {
+    let is_system = |r: &ConversationRecord| matches!(r, ConversationRecord::Message(m) if m.role == Role::System);
+    let mut all: Vec<ConversationRecord> = Vec::new();
+    if let Some(sys) = system_record {
+        all.push(sys.clone());
+    }
+    all.extend(existing.iter().filter(|r| !is_system(r)).cloned());
+    all.extend(new_records.iter().filter(|r| !is_system(r)).cloned());
+    if let Some(dir) = image_dir {
+        if let Err(e) = sven_input::externalize_images(&mut all, dir) {
+            eprintln!(
+                "[sven:warn] Failed to extract images to {}: {e:#}",
+                dir.display()
+            );
+        }
+    }
+    let serialized = serialize_jsonl_records(&all);
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Err(e) = sven_input::write_atomic(path, serialized) {
+        eprintln!(
+            "[sven:warn] Failed to write JSONL log {}: {e}",
+            path.display()
+        );
+    }
+}
+
+// ── Interrupts ────────────────────────────────────────────────────────────────
+
+/// Wait for SIGINT (Ctrl+C) or, on Unix, SIGTERM and return the signal's name.
+pub(super) async fn interrupt_signal() -> &'static str {
+    let ctrl_c = async {
+        if tokio::signal::ctrl_c().await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        if let Ok(mut term) = signal(SignalKind::terminate()) {
+            return tokio::select! {
+                _ = ctrl_c => "SIGINT",
+                _ = term.recv() => "SIGTERM",
+            };
+        }
+    }
+    ctrl_c.await;
+    "SIGINT"
+}
+
+/// Where [`save_partial`] writes an interrupted run.
+pub(super) struct PartialSave<'a> {
+    pub output_jsonl: Option<&'a std::path::Path>,
+    pub image_dir: Option<&'a std::path::Path>,
+    pub system_record: &'a ConversationRecord,
+    pub existing_jsonl: &'a [ConversationRecord],
+    pub artifacts_dir: Option<&'a std::path::Path>,
+    pub step_idx: usize,
+    pub step_label: &'a str,
+    /// Index in `collected` of the interrupted step's first message.
+    pub step_msg_start: usize,
+}
+
+/// Persist a run interrupted mid-step.
+///
+/// `partial_text` — assistant text streamed but not yet completed — is
+/// appended as an assistant message, then the JSONL log and the step and
+/// conversation artifacts are written as they would be after the step.
+pub(super) fn save_partial(
+    partial_text: &str,
+    collected: &mut Vec<Message>,
+    jsonl_records: &mut Vec<ConversationRecord>,
+    to: &PartialSave<'_>,
+) {
+    if !partial_text.is_empty() {
+        let msg = Message::assistant(partial_text);
+        collected.push(msg.clone());
+        jsonl_records.push(ConversationRecord::Message(msg));
+    }
+    if let Some(path) = to.output_jsonl {
+        flush_jsonl(
+            path,
+            Some(to.system_record),
+            to.existing_jsonl,
+            jsonl_records,
+            to.image_dir,
+        );
+    }
+    if let Some(dir) = to.artifacts_dir {
+        let start = to.step_msg_start.min(collected.len());
+        write_step_artifact(dir, to.step_idx, to.step_label, &collected[start..]);
+        write_conversation_artifact(dir, collected);
+    }
+}
+
 // ── Helpers ───────────────────────────────────────────────────────────────────
 
+pub(super) fn step_metrics_json(m: &StepMetrics) -> serde_json::Value {
+    serde_json::json!({
+        "index": m.index,
+        "label": m.label,
+        "duration_ms": m.duration_ms,
+        "turns": m.turns,
+        "tool_calls": m.tool_calls,
+        "tokens": {
+            "input": m.input_tokens,
+            "output": m.output_tokens,
+            "cache_read": m.cache_read_tokens,
+            "cache_write": m.cache_write_tokens,
+            "total": m.input_tokens + m.output_tokens,
+        },
+    })
+}
+
 pub(super) fn json_output_to_string(out: &JsonOutput) -> String {
     let steps: Vec<serde_json::Value> = out
         .steps
@@ -155,26 +292,176 @@ pub(super) fn json_output_to_string(out: &JsonOutput) -> String {
                 "tools_used": s.tools_used,
                 "duration_ms": s.duration_ms,
                 "success": s.success,
+                "skipped": s.skipped,
             })
         })
         .collect();
 
+    let metrics: Vec<serde_json::Value> = out.metrics.iter().map(step_metrics_json).collect();
     let obj = serde_json::json!({
         "title": out.title,
         "steps": steps,
+        "metrics": metrics,
     });
 
     serde_json::to_string_pretty(&obj)
         .unwrap_or_else(|e| format!("{{\"error\": \"serialization failed: {e}\"}}"))
 }
 
-pub(super) fn parse_agent_mode(s: &str) -> Option<AgentMode> {
-    match s.trim() {
-        "research" => Some(AgentMode::Research),
-        "plan" => Some(AgentMode::Plan),
-        "agent" => Some(AgentMode::Agent),
-        _ => None,
+/// Stdout text marking a step whose `when=` condition was false.
+///
+/// `conversation` output gets the step label and an HTML comment, `jsonl` a
+/// `metrics` line with `"skipped": true`, and `compact` an empty response.
+/// `json` returns `None`: the step goes into its `steps` array instead.
+pub(super) fn skipped_step_output(
+    format: OutputFormat,
+    index: usize,
+    label: Option<&str>,
+    when: &str,
+) -> Option<String> {
+    match format {
+        OutputFormat::Conversation => {
+            let comment = format!("<!-- step {index} skipped: when={when:?} -->");
+            Some(match label.filter(|l| !l.is_empty()) {
+                Some(label) => format!("## {label}\n\n{comment}\n\n"),
+                None => format!("{comment}\n\n"),
+            })
+        }
+        OutputFormat::Jsonl => Some(jsonl_metrics_line(&serde_json::json!({
+            "index": index,
+            "label": label,
+            "skipped": true,
+            "when": when,
+        }))),
+        OutputFormat::Compact => Some("\n".to_string()),
+        OutputFormat::Json => None,
+    }
+}
+
+/// Apply a workflow's frontmatter `tools_allow` / `tools_deny` to `tools`.
+///
+/// `tools_allow` is intersected with `tools.allow`, so a workflow cannot
+/// widen what the config permits; `tools_deny` adds to `tools.deny`.  The
+/// agent builder applies both after MCP registration and passes them on to
+/// sub-agents.
+pub(crate) fn restrict_tools(
+    tools: &mut ToolsConfig,
+    allow: Option<&[String]>,
+    deny: Option<&[String]>,
+) {
+    if let Some(allow) = allow {
+        tools.allow = Some(match &tools.allow {
+            Some(configured) => allow
+                .iter()
+                .filter(|name| configured.contains(name))
+                .cloned()
+                .collect(),
+            None => allow.to_vec(),
+        });
     }
+    tools.deny.extend(deny.unwrap_or_default().iter().cloned());
+}
+
+/// The built-in or configured custom mode called `s`.
+pub(super) fn parse_agent_mode(config: &Config, s: &str) -> Option<AgentMode> {
+    config.agent.mode(s.trim())
+}
+
+// ── Step model resolution ─────────────────────────────────────────────────────
+
+/// The model override string a step applies, if any.
+///
+/// Priority: explicit step `provider`/`model` > frontmatter `models[mode]` >
+/// frontmatter `models.agent`.  `None` leaves the current model in place.
+pub(super) fn step_model_override(
+    options: &StepOptions,
+    models: Option<&HashMap<String, String>>,
+) -> Option<String> {
+    match (options.provider.as_deref(), options.model.as_deref()) {
+        (Some(prov), Some(model)) => Some(format!("{prov}/{model}")),
+        (Some(prov), None) => Some(prov.to_string()),
+        (None, Some(model)) => Some(model.to_string()),
+        (None, None) => options
+            .mode
+            .as_deref()
+            .and_then(|m| models?.get(m).cloned())
+            .or_else(|| models?.get("agent").cloned()),
+    }
+}
+
+/// `provider=… model=… base_url=… (source)` for a resolved model.
+pub(super) fn describe_model(cfg: &ModelConfig) -> String {
+    let base_url = match (
+        cfg.base_url.as_deref(),
+        sven_model::get_driver(&cfg.provider).and_then(|d| d.default_base_url),
+    ) {
+        (Some(url), _) => format!("{url} (config)"),
+        (None, Some(url)) => format!("{url} (provider default)"),
+        (None, None) => "(unset)".to_string(),
+    };
+    format!(
+        "provider={} model={} base_url={base_url}",
+        cfg.provider, cfg.name
+    )
+}
+
+/// One line per step describing the mode and model it will run with, the
+/// way the runner carries them from step to step: a step's overrides stick
+/// for the steps after it, and until one (or `--model`, `pinned`) picks a
+/// model, `agent.routing` follows the step's mode.  Resolves configuration
+/// only; no provider is constructed or called.
+pub(super) fn dry_run_step_lines(
+    config: &Config,
+    models: Option<&HashMap<String, String>>,
+    mut mode: AgentMode,
+    mut model: ModelConfig,
+    mut pinned: bool,
+    steps: &[&Step],
+) -> Vec<String> {
+    let total = steps.len();
+    steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| {
+            let label = step.label.as_deref().unwrap_or("(unlabelled)");
+            let mut notes = Vec::new();
+            if let Some(m) = &step.options.mode {
+                match parse_agent_mode(config, m) {
+                    Some(parsed) => mode = parsed,
+                    None => notes.push(format!("unknown mode {m:?} ignored")),
+                }
+            }
+            let routed = (!pinned)
+                .then(|| sven_model::routed_model(config, mode))
+                .flatten();
+            let source = match (step_model_override(&step.options, models), routed) {
+                (Some(spec), _) => {
+                    model = sven_model::resolve_model_from_config(config, &spec);
+                    pinned = true;
+                    format!("from {spec:?}")
+                }
+                (None, Some(routed)) => {
+                    model = routed;
+                    format!("routed for {mode} mode")
+                }
+                (None, None) => "inherited".to_string(),
+            };
+            let timeout = step
+                .options
+                .timeout_secs
+                .map_or_else(|| "(inherit)".to_string(), |t| format!("{t}s"));
+            let notes = if notes.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", notes.join(", "))
+            };
+            format!(
+                "Step {}/{total}: label={label:?} mode={mode} {} [{source}] timeout={timeout}{notes}",
+                i + 1,
+                describe_model(&model),
+            )
+        })
+        .collect()
 }
 
 /// Sanitize a `cache_key` value into a safe filesystem component.
@@ -248,5 +535,490 @@ mod normalize_tests {
     }
 }
 
+// ── Structured output ─────────────────────────────────────────────────────────
+
+/// Check the run's final reply against the model's `response_format` schema.
+///
+/// The reply may be wrapped in one Markdown code fence, which providers that
+/// ignore `response_format` tend to add.  Returns a message naming the first
+/// problem on failure.
+pub(crate) fn validate_response_format(
+    reply: &str,
+    schema: &serde_json::Value,
+) -> Result<(), String> {
+    let validator = jsonschema::validator_for(schema)
+        .map_err(|e| format!("model.response_format is not a valid JSON Schema: {e}"))?;
+    let text = reply.trim();
+    let text = text
+        .strip_prefix("```json")
+        .or_else(|| text.strip_prefix("```"))
+        .and_then(|t| t.strip_suffix("```"))
+        .map_or(text, str::trim);
+    let value: serde_json::Value =
+        serde_json::from_str(text).map_err(|e| format!("Final reply is not valid JSON: {e}"))?;
+    validator
+        .validate(&value)
+        .map_err(|e| format!("Final reply does not match model.response_format: {e}"))
+}
+
+// ── Failure reporting ─────────────────────────────────────────────────────────
+
+/// The `kind` reported by `--json-errors` for an `EXIT_*` code.
+pub(crate) fn exit_kind(code: i32) -> &'static str {
+    match code {
+        EXIT_AGENT_ERROR => "agent_error",
+        EXIT_VALIDATION_ERROR => "validation_error",
+        EXIT_TOOL_WARNINGS => "tool_warnings",
+        EXIT_BUDGET_EXHAUSTED => "budget_exhausted",
+        EXIT_TIMEOUT => "timeout",
+        EXIT_INTERRUPT => "interrupt",
+        _ => "unknown",
+    }
+}
+
+/// Build the `--json-errors` diagnostic for a run that exits with `code`.
+/// `step` is 1-based and `null` when the failure is not tied to a step.
+pub(crate) fn json_error(
+    code: i32,
+    message: &str,
+    step: Option<usize>,
+    elapsed: Duration,
+) -> serde_json::Value {
+    serde_json::json!({
+        "code": code,
+        "kind": exit_kind(code),
+        "message": message,
+        "step": step,
+        "elapsed_secs": (elapsed.as_secs_f64() * 1000.0).round() / 1000.0,
+    })
+}
+
+/// Terminates a CI run with one of the `EXIT_*` codes.
+///
+/// With `--json-errors` a single-line [`json_error`] object is written to
+/// stderr just before exiting, after the usual human-readable diagnostics.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Failure {
+    json: bool,
+    start: Instant,
+}
+
+impl Failure {
+    pub(crate) fn new(json: bool) -> Self {
+        Self {
+            json,
+            start: Instant::now(),
+        }
+    }
+
+    pub(crate) fn exit(&self, code: i32, step: Option<usize>, message: &str) -> ! {
+        if self.json {
+            let report = json_error(code, message, step, self.start.elapsed());
+            write_stderr(&report.to_string());
+        }
+        std::process::exit(code)
+    }
+}
+
 // resolve_model_cfg has been moved to sven_model::resolve_model_cfg.
 // resolve_model_from_config (config-aware variant) lives at sven_model::resolve_model_from_config.
+
+#[cfg(test)]
+mod step_fixture {
+    use super::super::event::{handle_event, StepState};
+    use super::*;
+    use crate::OutputFormat;
+    use sven_core::AgentEvent;
+
+    /// Owned storage behind a [`StepState`], for feeding events to the
+    /// runner's event handler in tests.
+    #[derive(Default)]
+    pub(super) struct StepFixture {
+        pub response_text: String,
+        pub partial_text: String,
+        pub metrics: StepMetrics,
+        pub tools_used: Vec<String>,
+        pub collected: Vec<Message>,
+        pub jsonl_records: Vec<ConversationRecord>,
+        pub failed: bool,
+        pub consecutive_tool_errors: u32,
+        pub sven_header_emitted: bool,
+        pub session_input_total: u32,
+        pub session_output_total: u32,
+        pub any_tool_errors: bool,
+        pub run_total_tokens: u64,
+    }
+
+    impl StepFixture {
+        /// Run `events` through `handle_event` as step `step_idx`.
+        pub fn feed(
+            &mut self,
+            step_idx: usize,
+            output_format: OutputFormat,
+            events: impl IntoIterator<Item = AgentEvent>,
+        ) {
+            for event in events {
+                handle_event(
+                    event,
+                    &mut StepState {
+                        response_text: &mut self.response_text,
+                        partial_text: &mut self.partial_text,
+                        metrics: &mut self.metrics,
+                        tools_used: &mut self.tools_used,
+                        failed: &mut self.failed,
+                        collected: &mut self.collected,
+                        jsonl_records: &mut self.jsonl_records,
+                        consecutive_tool_errors: &mut self.consecutive_tool_errors,
+                        trace_level: 0,
+                        output_format,
+                        sven_header_emitted: &mut self.sven_header_emitted,
+                        session_input_total: &mut self.session_input_total,
+                        session_output_total: &mut self.session_output_total,
+                        any_tool_errors: &mut self.any_tool_errors,
+                        run_total_tokens: &mut self.run_total_tokens,
+                        max_tokens_budget: None,
+                        failure: Failure::new(false),
+                        step_idx,
+                    },
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod interrupt_tests {
+    use super::step_fixture::StepFixture;
+    use super::*;
+    use crate::OutputFormat;
+    use sven_core::AgentEvent;
+    use sven_input::parse_jsonl_full;
+
+    #[test]
+    fn interrupt_mid_turn_persists_the_partial_response() {
+        let dir = tempfile::tempdir().unwrap();
+        let jsonl = dir.path().join("run.jsonl");
+        let artifacts = dir.path().join("artifacts");
+        std::fs::create_dir_all(&artifacts).unwrap();
+
+        let mut step = StepFixture {
+            collected: vec![Message::user("Summarise the log")],
+            jsonl_records: vec![ConversationRecord::Message(Message::user(
+                "Summarise the log",
+            ))],
+            ..StepFixture::default()
+        };
+        step.feed(
+            1,
+            OutputFormat::Compact,
+            [
+                AgentEvent::TextDelta("Looking at it.".into()),
+                AgentEvent::TextComplete("Looking at it.".into()),
+                AgentEvent::TextDelta("The log shows three ".into()),
+                AgentEvent::TextDelta("failures in".into()),
+            ],
+        );
+        assert_eq!(step.partial_text, "The log shows three failures in");
+
+        // The interrupt arrives before the second message completes.
+        let system = ConversationRecord::Message(Message::system("You are sven."));
+        save_partial(
+            &step.partial_text,
+            &mut step.collected,
+            &mut step.jsonl_records,
+            &PartialSave {
+                output_jsonl: Some(&jsonl),
+                image_dir: None,
+                system_record: &system,
+                existing_jsonl: &[],
+                artifacts_dir: Some(&artifacts),
+                step_idx: 1,
+                step_label: "summary",
+                step_msg_start: 0,
+            },
+        );
+
+        let saved = parse_jsonl_full(&std::fs::read_to_string(&jsonl).unwrap()).unwrap();
+        let texts: Vec<(Role, String)> = saved
+            .records
+            .iter()
+            .filter_map(|r| match r {
+                ConversationRecord::Message(m) => {
+                    Some((m.role.clone(), m.as_text().unwrap_or_default().to_string()))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                (Role::System, "You are sven.".to_string()),
+                (Role::User, "Summarise the log".to_string()),
+                (Role::Assistant, "Looking at it.".to_string()),
+                (
+                    Role::Assistant,
+                    "The log shows three failures in".to_string()
+                ),
+            ]
+        );
+
+        for name in ["01-summary.md", "conversation.md"] {
+            let md = std::fs::read_to_string(artifacts.join(name)).unwrap();
+            assert!(
+                md.contains("The log shows three failures in"),
+                "{name}:\n{md}"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod metrics_tests {
+    use super::step_fixture::StepFixture;
+    use super::*;
+    use crate::OutputFormat;
+    use sven_core::AgentEvent;
+    use sven_tools::ToolCall;
+
+    fn usage(input: u32, output: u32, cache_read: u32) -> AgentEvent {
+        AgentEvent::TokenUsage {
+            input,
+            output,
+            cache_read,
+            cache_write: 0,
+            cache_read_total: cache_read,
+            cache_write_total: 0,
+            max_tokens: 200_000,
+            max_output_tokens: 8192,
+            cost_usd: None,
+            estimated: false,
+        }
+    }
+
+    fn tool_call(id: &str, name: &str) -> AgentEvent {
+        AgentEvent::ToolCallStarted(ToolCall {
+            id: id.into(),
+            name: name.into(),
+            args: serde_json::json!({}),
+        })
+    }
+
+    /// Feed one step's events through the runner's event handler and
+    /// finish its metrics the way the step loop does.
+    fn run_step(index: usize, label: &str, events: Vec<AgentEvent>) -> StepMetrics {
+        let mut step = StepFixture {
+            metrics: StepMetrics {
+                index,
+                label: Some(label.into()),
+                ..StepMetrics::default()
+            },
+            ..StepFixture::default()
+        };
+        step.feed(index, OutputFormat::Json, events);
+        step.metrics.duration_ms = 1200 * index as u64;
+        step.metrics.tool_calls = step.tools_used.len() as u32;
+        step.metrics
+    }
+
+    #[test]
+    fn two_step_run_reports_metrics_per_step() {
+        let gather = run_step(
+            1,
+            "Gather",
+            vec![
+                tool_call("c1", "grep"),
+                tool_call("c2", "read_file"),
+                usage(900, 40, 0),
+                AgentEvent::TextComplete("Found it.".into()),
+                usage(300, 25, 900),
+                AgentEvent::TurnComplete,
+            ],
+        );
+        let report = run_step(
+            2,
+            "Report",
+            vec![
+                AgentEvent::TextComplete("All done.".into()),
+                usage(150, 60, 1200),
+                AgentEvent::TurnComplete,
+            ],
+        );
+
+        let out = JsonOutput {
+            title: None,
+            steps: Vec::new(),
+            metrics: vec![gather, report],
+        };
+        let v: serde_json::Value = serde_json::from_str(&json_output_to_string(&out)).unwrap();
+        let metrics = v["metrics"].as_array().expect("metrics array");
+        assert_eq!(metrics.len(), 2);
+
+        let first = &metrics[0];
+        assert_eq!(first["label"], "Gather");
+        assert_eq!(first["turns"], 2);
+        assert_eq!(first["tool_calls"], 2);
+        assert_eq!(first["duration_ms"], 1200);
+        assert_eq!(first["tokens"]["input"], 1200);
+        assert_eq!(first["tokens"]["output"], 65);
+        assert_eq!(first["tokens"]["cache_read"], 900);
+        assert_eq!(first["tokens"]["total"], 1265);
+
+        let second = &metrics[1];
+        assert_eq!(second["index"], 2);
+        assert_eq!(second["turns"], 1);
+        assert_eq!(second["tool_calls"], 0);
+        assert_eq!(second["tokens"]["total"], 210);
+    }
+}
+
+#[cfg(test)]
+mod skipped_step_tests {
+    use super::*;
+
+    #[test]
+    fn skipped_steps_are_marked_in_every_streaming_format() {
+        let when = "exit_code != 0";
+        let conv = skipped_step_output(OutputFormat::Conversation, 2, Some("Fix"), when).unwrap();
+        assert_eq!(
+            conv,
+            "## Fix\n\n<!-- step 2 skipped: when=\"exit_code != 0\" -->\n\n"
+        );
+
+        let line = skipped_step_output(OutputFormat::Jsonl, 2, Some("Fix"), when).unwrap();
+        let v: serde_json::Value = serde_json::from_str(line.trim()).unwrap();
+        assert_eq!(v["type"], "metrics");
+        assert_eq!(v["data"]["index"], 2);
+        assert_eq!(v["data"]["skipped"], true);
+
+        let compact = skipped_step_output(OutputFormat::Compact, 2, None, when).unwrap();
+        assert_eq!(compact, "\n");
+
+        assert!(skipped_step_output(OutputFormat::Json, 2, None, when).is_none());
+    }
+}
+
+#[cfg(test)]
+mod dry_run_tests {
+    use super::*;
+    use sven_input::{parse_frontmatter, parse_workflow};
+
+    #[test]
+    fn dry_run_shows_model_override_from_step_two_onwards() {
+        let config = Config {
+            model: ModelConfig {
+                provider: "anthropic".into(),
+                name: "claude-sonnet-4-5".into(),
+                ..ModelConfig::default()
+            },
+            ..Config::default()
+        };
+        let input = "---\nmodels:\n  research: mock/fast\n---\n\
+                     ## Gather\nLook around.\n\n\
+                     ## Review\n<!-- sven: model=openai/gpt-4o mode=plan -->\nReview it.\n\n\
+                     ## Summarise\nWrite it up.\n";
+        let (frontmatter, body) = parse_frontmatter(input);
+        let frontmatter = frontmatter.unwrap();
+        let workflow = parse_workflow(body);
+        let steps: Vec<&Step> = workflow.steps.iter().collect();
+        let lines = dry_run_step_lines(
+            &config,
+            frontmatter.models.as_ref(),
+            AgentMode::Agent,
+            config.model.clone(),
+            false,
+            &steps,
+        );
+        assert_eq!(lines.len(), 3);
+        assert!(
+            lines[0].contains("mode=agent provider=anthropic model=claude-sonnet-4-5")
+                && lines[0].contains("https://api.anthropic.com (provider default)")
+                && lines[0].contains("[inherited]"),
+            "{}",
+            lines[0]
+        );
+        assert!(
+            lines[1].contains("mode=plan provider=openai model=gpt-4o")
+                && lines[1].contains("[from \"openai/gpt-4o\"]"),
+            "{}",
+            lines[1]
+        );
+        // The step-2 override sticks for the next step.
+        assert!(
+            lines[2].contains("mode=plan provider=openai model=gpt-4o ")
+                && lines[2].contains("[inherited]"),
+            "{}",
+            lines[2]
+        );
+    }
+
+    fn routing_config() -> Config {
+        let mut config = Config::default();
+        config
+            .agent
+            .routing
+            .insert(AgentMode::Research, "openai/gpt-4o-mini".into());
+        config
+            .agent
+            .routing
+            .insert(AgentMode::Agent, "anthropic/claude-sonnet-4-5".into());
+        config
+    }
+
+    const ROUTED_WORKFLOW: &str = "## Gather\n<!-- sven: mode=research -->\nLook around.\n\n\
+                                   ## Fix\n<!-- sven: mode=agent -->\nFix it.\n\n\
+                                   ## Pin\n<!-- sven: model=mock/pinned mode=research -->\nAgain.\n\n\
+                                   ## After\n<!-- sven: mode=agent -->\nDone.\n";
+
+    #[test]
+    fn dry_run_routes_model_by_step_mode() {
+        let config = routing_config();
+        let workflow = parse_workflow(ROUTED_WORKFLOW);
+        let steps: Vec<&Step> = workflow.steps.iter().collect();
+        let lines = dry_run_step_lines(
+            &config,
+            None,
+            AgentMode::Agent,
+            config.model.clone(),
+            false,
+            &steps,
+        );
+        assert!(
+            lines[0].contains("mode=research provider=openai model=gpt-4o-mini")
+                && lines[0].contains("[routed for research mode]"),
+            "{}",
+            lines[0]
+        );
+        assert!(
+            lines[1].contains("mode=agent provider=anthropic model=claude-sonnet-4-5"),
+            "{}",
+            lines[1]
+        );
+        // An explicit step model wins and stays pinned afterwards.
+        assert!(
+            lines[2].contains("provider=mock model=pinned"),
+            "{}",
+            lines[2]
+        );
+        assert!(
+            lines[3].contains("mode=agent provider=mock model=pinned")
+                && lines[3].contains("[inherited]"),
+            "{}",
+            lines[3]
+        );
+    }
+
+    #[test]
+    fn dry_run_with_pinned_model_ignores_routing() {
+        let config = routing_config();
+        let workflow = parse_workflow(ROUTED_WORKFLOW);
+        let steps: Vec<&Step> = workflow.steps.iter().collect();
+        let pinned = sven_model::resolve_model_from_config(&config, "mock/cli");
+        let lines = dry_run_step_lines(&config, None, AgentMode::Agent, pinned, true, &steps);
+        assert!(
+            lines[0].contains("mode=research provider=mock model=cli")
+                && lines[0].contains("[inherited]"),
+            "{}",
+            lines[0]
+        );
+    }
+}
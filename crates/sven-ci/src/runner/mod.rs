@@ -6,12 +6,17 @@ mod event;
 mod helpers;
 
 use event::{emit_record, handle_event, StepState};
+use helpers::{
+    describe_model, dry_run_step_lines, flush_jsonl, interrupt_signal, json_output_to_string,
+    normalize_label, parse_agent_mode, sanitize_cache_key, save_partial, skipped_step_output,
+    step_metrics_json, step_model_override, write_conversation_artifact, write_step_artifact,
+    Failure, PartialSave,
+};
+#[cfg(test)]
+pub(crate) use helpers::{exit_kind, json_error};
 pub(crate) use helpers::{
     is_conversation_format, is_json_summary_format, is_jsonl_format, parse_json_summary,
-};
-use helpers::{
-    json_output_to_string, normalize_label, parse_agent_mode, sanitize_cache_key,
-    write_conversation_artifact, write_step_artifact,
+    restrict_tools, validate_response_format,
 };
 
 use std::collections::HashMap;
@@ -27,13 +32,14 @@ use sven_bootstrap::{AgentBuilder, RuntimeContext, ToolSetProfile};
 use sven_config::{AgentMode, Config};
 use sven_core::AgentEvent;
 use sven_input::{
-    history, parse_conversation, parse_frontmatter, parse_jsonl_full, parse_workflow,
-    serialize_jsonl_records, ConversationRecord, ParsedJsonlConversation, Step, StepQueue,
+    history, jsonl_meta_line, jsonl_metrics_line, parse_conversation, parse_frontmatter,
+    parse_jsonl_full, parse_workflow, ConversationRecord, ParsedJsonlConversation, Step, StepQueue,
 };
 use sven_model::{Message, MessageContent, Role};
 use sven_runtime::resolve_auto_log_path;
 use sven_tools::events::TodoItem;
 
+use crate::condition::{Condition, StepOutcome};
 use crate::output::{write_progress, write_stderr, write_stdout};
 use crate::template::apply_template;
 
@@ -81,6 +87,7 @@ pub enum OutputFormat {
 pub(super) struct JsonOutput {
     pub title: Option<String>,
     pub steps: Vec<JsonStep>,
+    pub metrics: Vec<StepMetrics>,
 }
 
 pub(super) struct JsonStep {
@@ -91,6 +98,25 @@ pub(super) struct JsonStep {
     pub tools_used: Vec<String>,
     pub duration_ms: u64,
     pub success: bool,
+    /// The step's `when=` condition was false, so it did not run.
+    pub skipped: bool,
+}
+
+/// Efficiency metrics for one executed step, reported under `metrics` in
+/// `json` output and as a `metrics` line after the step in `jsonl` output.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct StepMetrics {
+    pub index: usize,
+    pub label: Option<String>,
+    pub duration_ms: u64,
+    /// Model responses in the step, counted from their usage reports.
+    pub turns: u32,
+    pub tool_calls: u32,
+    /// Non-cached input tokens.
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_write_tokens: u64,
 }
 
 // ── Options ───────────────────────────────────────────────────────────────────
@@ -112,7 +138,8 @@ pub struct CiOptions {
     pub project_root: Option<PathBuf>,
     /// Output format for stdout.
     pub output_format: OutputFormat,
-    /// Directory to write per-run artifacts to (optional).
+    /// Root directory for run artifacts (optional).  Each run writes into
+    /// its own subdirectory; `latest` points at the newest.
     pub artifacts_dir: Option<PathBuf>,
     /// Template variables substituted as `{{key}}` in step content.
     pub vars: HashMap<String, String>,
@@ -139,6 +166,9 @@ pub struct CiOptions {
     /// Write the full-fidelity JSONL output to this path after every step.
     /// If `None`, falls back to the auto-log path (.sven/logs/<timestamp>.jsonl).
     pub output_jsonl: Option<PathBuf>,
+    /// Move images embedded in the output JSONL into files in this directory
+    /// and reference them by path instead of inlining them as data URLs.
+    pub output_image_dir: Option<PathBuf>,
     /// Replay all tool calls in the loaded JSONL with fresh results before
     /// submitting to the model.  Only meaningful when `load_jsonl` is set.
     pub rerun_toolcalls: bool,
@@ -156,6 +186,9 @@ pub struct CiOptions {
     pub load_chat: Option<PathBuf>,
     /// Write (or update) the YAML chat document after every step.
     pub output_chat: Option<PathBuf>,
+    /// On any non-zero exit, also print a single-line JSON diagnostic
+    /// `{code, kind, message, step, elapsed_secs}` to stderr.
+    pub json_errors: bool,
 }
 
 // ── Runner ────────────────────────────────────────────────────────────────────
@@ -171,6 +204,19 @@ impl CiRunner {
     }
 
     pub async fn run(&self, opts: CiOptions) -> anyhow::Result<()> {
+        let json_errors = opts.json_errors;
+        let failure = Failure::new(json_errors);
+        match self.run_steps(opts, failure).await {
+            Err(e) if json_errors => {
+                let message = format!("{e:#}");
+                write_stderr(&format!("[sven:error] {message}"));
+                failure.exit(EXIT_AGENT_ERROR, None, &message)
+            }
+            result => result,
+        }
+    }
+
+    async fn run_steps(&self, opts: CiOptions, failure: Failure) -> anyhow::Result<()> {
         // ── Parse frontmatter ────────────────────────────────────────────────
         let (frontmatter, markdown_body) = parse_frontmatter(&opts.input);
         let frontmatter = frontmatter.unwrap_or_default();
@@ -180,6 +226,7 @@ impl CiRunner {
         // workflows can reference {{branch}}, {{commit}}, {{GITHUB_SHA}}, etc.
         // without any explicit --var flag.
         let ci_ctx = crate::context::detect_ci_context();
+        let ci_run_id = ci_ctx.run_id.clone();
         let mut vars: HashMap<String, String> = crate::context::ci_template_vars(&ci_ctx);
 
         // Inject built-in workspace path vars so workflows can reference
@@ -232,7 +279,14 @@ impl CiRunner {
         // trailing pending user turn that was not yet answered.
         let (conversation_history, piped_pending) = if is_jsonl_input {
             match parse_jsonl_full(markdown_body) {
-                Ok(parsed) => (parsed.history, parsed.pending_user_input),
+                Ok(mut parsed) => {
+                    if let Err(e) = parsed.inline_image_files() {
+                        write_stderr(&format!(
+                            "[sven:warn] Failed to load images of piped JSONL: {e:#}"
+                        ));
+                    }
+                    (parsed.history, parsed.pending_user_input)
+                }
                 Err(e) => {
                     write_stderr(&format!(
                         "[sven:warn] Failed to parse piped input as JSONL ({e}), \
@@ -345,7 +399,11 @@ impl CiRunner {
                          Or end the piped output with an unanswered ## User section\n\
                          so the next sven instance picks it up automatically."
                     ));
-                    std::process::exit(EXIT_VALIDATION_ERROR);
+                    failure.exit(
+                        EXIT_VALIDATION_ERROR,
+                        None,
+                        &format!("Piped {format_name} has no pending task"),
+                    );
                 }
             }
         } else if let Some(w) = workflow {
@@ -383,36 +441,19 @@ impl CiRunner {
 
         let total = queue.len();
 
-        // ── Dry-run mode ─────────────────────────────────────────────────────
-        if opts.dry_run {
-            write_progress(&format!(
-                "[sven:dry-run] Workflow validated — {} step(s)",
-                total
-            ));
-            if let Some(t) = &title {
-                write_progress(&format!("[sven:dry-run] Title: {}", t));
-            }
-            let mut i = 0;
-            while let Some(step) = queue.pop() {
-                i += 1;
-                let label = step.label.as_deref().unwrap_or("(unlabelled)");
-                let mode_hint = step.options.mode.as_deref().unwrap_or("(inherit)");
-                let provider_hint = step.options.provider.as_deref().unwrap_or("(inherit)");
-                let model_hint = step.options.model.as_deref().unwrap_or("(inherit)");
-                let timeout_hint = step
-                    .options
-                    .timeout_secs
-                    .map(|t| format!("{t}s"))
-                    .unwrap_or_else(|| "(inherit)".to_string());
-                write_progress(&format!(
-                    "[sven:dry-run] Step {i}/{total}: label={label:?} mode={mode_hint} provider={provider_hint} model={model_hint} timeout={timeout_hint}"
-                ));
+        // ── Reject malformed when= conditions before anything runs ───────────
+        for (i, step) in queue.iter().enumerate() {
+            if let Some(when) = &step.options.when {
+                if let Err(e) = Condition::parse(when) {
+                    let message = format!("invalid when= condition: {e:#}");
+                    write_stderr(&format!("[sven:error] Step {}/{total}: {message}", i + 1));
+                    failure.exit(EXIT_VALIDATION_ERROR, Some(i + 1), &message);
+                }
             }
-            return Ok(());
         }
 
         // ── Build model config ───────────────────────────────────────────────
-        // CLI --model > frontmatter models[current_mode] > config
+        // CLI --model > frontmatter models[current_mode] > agent.routing > config
         let model_override = opts.model_override.clone().or_else(|| {
             frontmatter
                 .models
@@ -423,9 +464,42 @@ impl CiRunner {
         let model_cfg = if let Some(ref name) = model_override {
             sven_model::resolve_model_from_config(&self.config, name)
         } else {
-            self.config.model.clone()
+            sven_model::routed_model(&self.config, opts.mode)
+                .unwrap_or_else(|| self.config.model.clone())
         };
 
+        // ── Dry-run mode ─────────────────────────────────────────────────────
+        if opts.dry_run {
+            write_progress(&format!(
+                "[sven:dry-run] Workflow validated — {} step(s)",
+                total
+            ));
+            if let Some(t) = &title {
+                write_progress(&format!("[sven:dry-run] Title: {}", t));
+            }
+            let source = match &model_override {
+                Some(name) => format!("from {name:?}"),
+                None => "config".to_string(),
+            };
+            write_progress(&format!(
+                "[sven:dry-run] Start: mode={} {} [{source}]",
+                opts.mode,
+                describe_model(&model_cfg)
+            ));
+            let steps: Vec<&Step> = queue.iter().collect();
+            for line in dry_run_step_lines(
+                &self.config,
+                frontmatter.models.as_ref(),
+                opts.mode,
+                model_cfg,
+                model_override.is_some(),
+                &steps,
+            ) {
+                write_progress(&format!("[sven:dry-run] {line}"));
+            }
+            return Ok(());
+        }
+
         let model =
             sven_model::from_config(&model_cfg).context("failed to initialise model provider")?;
         let model: Arc<dyn sven_model::ModelProvider> = Arc::from(model);
@@ -466,12 +540,23 @@ impl CiRunner {
             ));
         }
 
+        let workspace_root = opts
+            .project_root
+            .as_deref()
+            .map(crate::context::find_workspace_root);
+        let build_systems = opts
+            .project_root
+            .as_deref()
+            .map(|r| sven_runtime::detect_build_systems(r, workspace_root.as_deref()))
+            .unwrap_or_default();
         let mut runtime_ctx = RuntimeContext {
             project_root: opts.project_root.clone(),
+            workspace_root,
+            build_systems,
             git_context: opts
                 .project_root
                 .as_ref()
-                .map(|r| sven_runtime::collect_git_context(r)),
+                .map(|r| sven_runtime::collect_git_context_cached(r)),
             ci_context: Some(ci_ctx),
             project_context_file: project_context.map(|(_, content)| content),
             append_system_prompt: combined_append,
@@ -493,11 +578,12 @@ impl CiRunner {
                     ));
                 }
                 Err(e) => {
-                    write_stderr(&format!(
-                        "[sven:error] Failed to read --system-prompt-file {}: {e}",
+                    let message = format!(
+                        "Failed to read --system-prompt-file {}: {e}",
                         sp_file.display()
-                    ));
-                    std::process::exit(EXIT_VALIDATION_ERROR);
+                    );
+                    write_stderr(&format!("[sven:error] {message}"));
+                    failure.exit(EXIT_VALIDATION_ERROR, None, &message);
                 }
             }
         }
@@ -507,45 +593,51 @@ impl CiRunner {
         // be injected as `system_prompt_override` before the agent is built.
         // This ensures resumed conversations are fully reproducible by default.
         // `--regen-system-prompt` or `--system-prompt-file` bypass this.
-        let pre_parsed_jsonl: Option<ParsedJsonlConversation> =
-            if let Some(ref jpath) = opts.load_jsonl {
-                match std::fs::read_to_string(jpath) {
-                    Ok(content) => match parse_jsonl_full(&content) {
-                        Ok(parsed) => {
-                            // Apply stored system message unless caller asked to regenerate
-                            // or already provided an explicit override via --system-prompt-file.
-                            if !opts.regen_system_prompt
-                                && opts.system_prompt_file.is_none()
-                                && runtime_ctx.system_prompt_override.is_none()
-                            {
-                                if let Some(ref sys_text) = parsed.system_message {
-                                    runtime_ctx.system_prompt_override = Some(sys_text.clone());
-                                    write_progress(
-                                        "[sven:info] System prompt loaded from JSONL conversation",
-                                    );
-                                }
-                            }
-                            Some(parsed)
-                        }
-                        Err(e) => {
-                            write_stderr(&format!(
-                                "[sven:error] Failed to parse --load-jsonl {}: {e}",
+        let pre_parsed_jsonl: Option<ParsedJsonlConversation> = if let Some(ref jpath) =
+            opts.load_jsonl
+        {
+            match std::fs::read_to_string(jpath) {
+                Ok(content) => match parse_jsonl_full(&content) {
+                    Ok(mut parsed) => {
+                        if let Err(e) = parsed.inline_image_files() {
+                            let message = format!(
+                                "Failed to load images of --load-jsonl {}: {e:#}",
                                 jpath.display()
-                            ));
-                            std::process::exit(EXIT_VALIDATION_ERROR);
+                            );
+                            write_stderr(&format!("[sven:error] {message}"));
+                            failure.exit(EXIT_VALIDATION_ERROR, None, &message);
                         }
-                    },
+                        // Apply stored system message unless caller asked to regenerate
+                        // or already provided an explicit override via --system-prompt-file.
+                        if !opts.regen_system_prompt
+                            && opts.system_prompt_file.is_none()
+                            && runtime_ctx.system_prompt_override.is_none()
+                        {
+                            if let Some(ref sys_text) = parsed.system_message {
+                                runtime_ctx.system_prompt_override = Some(sys_text.clone());
+                                write_progress(
+                                    "[sven:info] System prompt loaded from JSONL conversation",
+                                );
+                            }
+                        }
+                        Some(parsed)
+                    }
                     Err(e) => {
-                        write_stderr(&format!(
-                            "[sven:error] Failed to read --load-jsonl {}: {e}",
-                            jpath.display()
-                        ));
-                        std::process::exit(EXIT_VALIDATION_ERROR);
+                        let message =
+                            format!("Failed to parse --load-jsonl {}: {e}", jpath.display());
+                        write_stderr(&format!("[sven:error] {message}"));
+                        failure.exit(EXIT_VALIDATION_ERROR, None, &message);
                     }
+                },
+                Err(e) => {
+                    let message = format!("Failed to read --load-jsonl {}: {e}", jpath.display());
+                    write_stderr(&format!("[sven:error] {message}"));
+                    failure.exit(EXIT_VALIDATION_ERROR, None, &message);
                 }
-            } else {
-                None
-            };
+            }
+        } else {
+            None
+        };
 
         // Resolve timeouts (CLI > config)
         // Frontmatter no longer carries timeout fields (removed in redesign).
@@ -581,12 +673,33 @@ impl CiRunner {
             buffer_store,
         };
 
-        let mut agent = AgentBuilder::new(self.config.clone())
+        // Frontmatter tools_allow / tools_deny go into the tools config so the
+        // builder applies them after MCP registration and sub-agents get them.
+        let restricted = frontmatter.tools_allow.is_some() || frontmatter.tools_deny.is_some();
+        let agent_config = if restricted {
+            let mut config = (*self.config).clone();
+            restrict_tools(
+                &mut config.tools,
+                frontmatter.tools_allow.as_deref(),
+                frontmatter.tools_deny.as_deref(),
+            );
+            Arc::new(config)
+        } else {
+            self.config.clone()
+        };
+
+        let mut agent = AgentBuilder::new(agent_config)
             .with_runtime_context(runtime_ctx)
             .with_allow_interactive_oauth(false)
             .with_wait_for_mcp_tools(20_000)
             .build(initial_mode, model, profile)
-            .await;
+            .await?;
+
+        if restricted {
+            let mut names = agent.tools().names();
+            names.sort();
+            write_stderr(&format!("[sven:tools] restricted to: {}", names.join(", ")));
+        }
 
         // ── Capture system message for JSONL persistence ──────────────────────
         // Always record the exact system message used for this run so the JSONL
@@ -615,19 +728,17 @@ impl CiRunner {
                                 Some(msgs)
                             }
                             Err(e) => {
-                                write_stderr(&format!(
-                                    "[sven:error] Failed to parse --load-chat {}: {e}",
-                                    cpath.display()
-                                ));
-                                std::process::exit(EXIT_VALIDATION_ERROR);
+                                let message =
+                                    format!("Failed to parse --load-chat {}: {e}", cpath.display());
+                                write_stderr(&format!("[sven:error] {message}"));
+                                failure.exit(EXIT_VALIDATION_ERROR, None, &message);
                             }
                         },
                         Err(e) => {
-                            write_stderr(&format!(
-                                "[sven:error] Failed to read --load-chat {}: {e}",
-                                cpath.display()
-                            ));
-                            std::process::exit(EXIT_VALIDATION_ERROR);
+                            let message =
+                                format!("Failed to read --load-chat {}: {e}", cpath.display());
+                            write_stderr(&format!("[sven:error] {message}"));
+                            failure.exit(EXIT_VALIDATION_ERROR, None, &message);
                         }
                     }
                 } else {
@@ -704,27 +815,37 @@ impl CiRunner {
         // ── Accumulated full-fidelity JSONL records for this run ─────────────
         let mut run_jsonl_records: Vec<ConversationRecord> = Vec::new();
 
-        // ── Set up Ctrl+C handler ────────────────────────────────────────────
-        let (cancel_tx, mut cancel_rx) = mpsc::channel::<()>(1);
+        // ── Set up SIGINT / SIGTERM handler ──────────────────────────────────
+        let (cancel_tx, mut cancel_rx) = mpsc::channel::<&'static str>(1);
         tokio::spawn(async move {
-            if tokio::signal::ctrl_c().await.is_ok() {
-                let _ = cancel_tx.send(()).await;
-            }
+            let signal = interrupt_signal().await;
+            let _ = cancel_tx.send(signal).await;
         });
 
-        // ── Output: emit title for conversation format ───────────────────────
+        // ── Output: emit title / JSONL meta line ─────────────────────────────
         if opts.output_format == OutputFormat::Conversation {
             if let Some(t) = &title {
                 write_stdout(&format!("# {}\n\n", t));
             }
+        } else if opts.output_format == OutputFormat::Jsonl {
+            write_stdout(&jsonl_meta_line());
         }
 
         // ── Artifacts setup ──────────────────────────────────────────────────
-        if let Some(dir) = &opts.artifacts_dir {
-            if let Err(e) = std::fs::create_dir_all(dir) {
-                write_stderr(&format!("[sven:warn] Could not create artifacts dir: {e}"));
+        // A fresh subdirectory per run so reruns never clobber earlier output.
+        let artifacts_dir: Option<PathBuf> = opts.artifacts_dir.as_deref().and_then(|root| {
+            let name = crate::artifacts::run_dir_name(ci_run_id.as_deref(), chrono::Local::now());
+            match crate::artifacts::create_run_dir(root, &name) {
+                Ok(dir) => {
+                    write_progress(&format!("[sven:artifacts] dir={}", dir.display()));
+                    Some(dir)
+                }
+                Err(e) => {
+                    write_stderr(&format!("[sven:warn] Could not create artifacts dir: {e}"));
+                    None
+                }
             }
-        }
+        });
 
         // ── Cache directory for cache_key step skipping ──────────────────────
         let cache_dir: PathBuf = opts
@@ -738,6 +859,7 @@ impl CiRunner {
         let mut step_idx = 0usize;
         let mut collected: Vec<Message> = Vec::new();
         let mut json_steps: Vec<JsonStep> = Vec::new();
+        let mut run_metrics: Vec<StepMetrics> = Vec::new();
         // Running session-level token counters for [sven:tokens] output.
         let mut session_input_total: u32 = 0;
         let mut session_output_total: u32 = 0;
@@ -745,38 +867,14 @@ impl CiRunner {
         // Cross-step tracking for exit code decisions.
         let mut any_tool_errors: bool = false;
         let mut run_total_tokens: u64 = 0;
+        // Outcome of the last executed step, seen by `when=` conditions.
+        let mut prev_exit_code: i64 = EXIT_SUCCESS as i64;
+        let mut prev_output = String::new();
         let max_tokens_budget = opts.max_tokens_budget;
-
-        // Write combined JSONL to path.
-        //
-        // Layout: [system_record] ++ [existing non-system] ++ [new non-system]
-        // The system record from `system_record` is always placed first so the
-        // file is fully self-contained and reproducible.  Any system messages
-        // already present in `existing` are stripped to avoid duplication.
-        fn flush_jsonl(
-            path: &PathBuf,
-            system_record: Option<&ConversationRecord>,
-            existing: &[ConversationRecord],
-            new_records: &[ConversationRecord],
-        ) {
-            let is_system = |r: &ConversationRecord| matches!(r, ConversationRecord::Message(m) if m.role == Role::System);
-            let mut all: Vec<ConversationRecord> = Vec::new();
-            if let Some(sys) = system_record {
-                all.push(sys.clone());
-            }
-            all.extend(existing.iter().filter(|r| !is_system(r)).cloned());
-            all.extend(new_records.iter().filter(|r| !is_system(r)).cloned());
-            let serialized = serialize_jsonl_records(&all);
-            if let Some(parent) = path.parent() {
-                let _ = std::fs::create_dir_all(parent);
-            }
-            if let Err(e) = std::fs::write(path, serialized) {
-                eprintln!(
-                    "[sven:warn] Failed to write JSONL log {}: {e}",
-                    path.display()
-                );
-            }
-        }
+        // `agent.routing` picks the model by mode until one is chosen
+        // explicitly, by --model, the workflow or a step.
+        let mut model_pinned = model_override.is_some();
+        let mut active_model = format!("{}/{}", model_cfg.provider, model_cfg.name);
 
         while let Some(step) = queue.pop() {
             step_idx += 1;
@@ -785,19 +883,57 @@ impl CiRunner {
             // Check total run timeout (between steps)
             if let Some(t) = run_timeout_secs {
                 if run_start.elapsed() > Duration::from_secs(t) {
-                    write_stderr(&format!(
-                        "[sven:error] Total run timeout exceeded ({}s). Completed {}/{} steps.",
+                    let message = format!(
+                        "Total run timeout exceeded ({}s). Completed {}/{} steps.",
                         t,
                         step_idx - 1,
                         total
+                    );
+                    write_stderr(&format!("[sven:error] {message}"));
+                    failure.exit(EXIT_TIMEOUT, Some(step_idx), &message);
+                }
+            }
+
+            // ── when= condition: skip the step when it does not hold ─────────
+            if let Some(when) = &step.options.when {
+                let outcome = StepOutcome {
+                    exit_code: prev_exit_code,
+                    last_output: &prev_output,
+                };
+                // Conditions were validated before the loop.
+                let holds = Condition::parse(when)
+                    .and_then(|c| c.eval(&outcome))
+                    .unwrap_or(true);
+                if !holds {
+                    write_progress(&format!(
+                        "[sven:step:skip] {step_idx}/{total} label=\"{label}\" when={when:?}"
                     ));
-                    std::process::exit(EXIT_TIMEOUT);
+                    if let Some(out) = skipped_step_output(
+                        opts.output_format,
+                        step_idx,
+                        step.label.as_deref(),
+                        when,
+                    ) {
+                        write_stdout(&out);
+                    } else {
+                        json_steps.push(JsonStep {
+                            index: step_idx,
+                            label: step.label.clone(),
+                            user_input: step.content.clone(),
+                            agent_response: String::new(),
+                            tools_used: Vec::new(),
+                            duration_ms: 0,
+                            success: true,
+                            skipped: true,
+                        });
+                    }
+                    continue;
                 }
             }
 
             // Apply per-step mode override
             if let Some(mode_str) = &step.options.mode {
-                if let Some(mode) = parse_agent_mode(mode_str) {
+                if let Some(mode) = parse_agent_mode(&self.config, mode_str) {
                     agent.set_mode(mode).await;
                 } else {
                     write_stderr(&format!(
@@ -808,28 +944,43 @@ impl CiRunner {
             }
 
             // Apply per-step provider and/or model override.
-            // Priority: explicit step model/provider > frontmatter models[mode] > current model.
-            let fm_mode_model: Option<String> = step
-                .options
-                .mode
-                .as_deref()
-                .and_then(|m| frontmatter.models.as_ref()?.get(m).cloned())
-                .or_else(|| frontmatter.models.as_ref()?.get("agent").cloned());
-
-            let effective_model_str: Option<String> = match (
-                step.options.provider.as_deref(),
-                step.options.model.as_deref(),
-            ) {
-                (Some(prov), Some(model)) => Some(format!("{prov}/{model}")),
-                (Some(prov), None) => Some(prov.to_string()),
-                (None, Some(model)) => Some(model.to_string()),
-                (None, None) => fm_mode_model,
+            // Priority: explicit step model/provider > frontmatter models[mode]
+            // > agent.routing (unless a model was chosen explicitly) > current model.
+            let effective_model_str =
+                step_model_override(&step.options, frontmatter.models.as_ref());
+            let routed = match &effective_model_str {
+                None if !model_pinned => {
+                    let mode = *agent.current_mode_lock().lock().await;
+                    sven_model::routed_model(&self.config, mode)
+                        .filter(|cfg| format!("{}/{}", cfg.provider, cfg.name) != active_model)
+                }
+                _ => None,
             };
+            if let Some(routed) = routed {
+                match sven_model::from_config(&routed) {
+                    Ok(m) => {
+                        agent.set_model(Arc::from(m));
+                        active_model = format!("{}/{}", routed.provider, routed.name);
+                        write_stderr(&format!(
+                            "[sven:model:routed] step {step_idx} uses {active_model}"
+                        ));
+                    }
+                    Err(e) => {
+                        write_stderr(&format!(
+                            "[sven:warn] Failed to build routed model {}/{} for step {step_idx}: {e}, using current model",
+                            routed.provider, routed.name
+                        ));
+                    }
+                }
+            }
             if let Some(model_str) = &effective_model_str {
                 let step_model_cfg = sven_model::resolve_model_from_config(&self.config, model_str);
                 match sven_model::from_config(&step_model_cfg) {
                     Ok(m) => {
                         agent.set_model(Arc::from(m));
+                        active_model =
+                            format!("{}/{}", step_model_cfg.provider, step_model_cfg.name);
+                        model_pinned = true;
                     }
                     Err(e) => {
                         write_stderr(&format!(
@@ -882,9 +1033,19 @@ impl CiRunner {
                 }
             }
 
+            // Track tool errors per step for `exit_code`; merged back below.
+            let tool_errors_before = std::mem::take(&mut any_tool_errors);
+
             // Per-step output accumulators — declared here so both the cache-hit
             // path and the agent path share the same downstream output logic.
             let mut response_text = String::new();
+            // Assistant text streamed but not yet completed; saved on interrupt.
+            let mut partial_text = String::new();
+            let mut step_metrics = StepMetrics {
+                index: step_idx,
+                label: step.label.clone(),
+                ..StepMetrics::default()
+            };
             let mut tools_used: Vec<String> = Vec::new();
             let mut failed = false;
             let mut step_duration_ms = 0u64;
@@ -943,34 +1104,51 @@ impl CiRunner {
 
                         timed_out = &mut step_timeout_fut => {
                             if timed_out {
-                                write_stderr(&format!(
-                                    "[sven:error] Step {step_idx} ({label:?}) timed out after {}s",
+                                let message = format!(
+                                    "Step {step_idx} ({label:?}) timed out after {}s",
                                     step_timeout_secs.unwrap_or(0)
-                                ));
+                                );
+                                write_stderr(&format!("[sven:error] {message}"));
                                 if !collected.is_empty() {
-                                    let _ = history::save(&collected);
+                                    let _ = history::save(&self.config.history, &collected);
                                 }
                                 if let Some(ref path) = effective_output_jsonl {
-                                    flush_jsonl(path, Some(&run_system_record), &existing_jsonl_records, &run_jsonl_records);
+                                    flush_jsonl(path, Some(&run_system_record), &existing_jsonl_records, &run_jsonl_records, opts.output_image_dir.as_deref());
                                 }
-                                std::process::exit(EXIT_TIMEOUT);
+                                failure.exit(EXIT_TIMEOUT, Some(step_idx), &message);
                             }
                         }
 
-                        _ = cancel_rx.recv() => {
-                            write_stderr("[sven:interrupted] Ctrl+C received — saving partial conversation");
+                        Some(signal) = cancel_rx.recv() => {
+                            write_stderr(&format!(
+                                "[sven:interrupted] {signal} received — saving partial conversation"
+                            ));
+                            save_partial(
+                                &partial_text,
+                                &mut collected,
+                                &mut run_jsonl_records,
+                                &PartialSave {
+                                    output_jsonl: effective_output_jsonl.as_deref(),
+                                    image_dir: opts.output_image_dir.as_deref(),
+                                    system_record: &run_system_record,
+                                    existing_jsonl: &existing_jsonl_records,
+                                    artifacts_dir: artifacts_dir.as_deref(),
+                                    step_idx,
+                                    step_label: label,
+                                    step_msg_start,
+                                },
+                            );
                             if !collected.is_empty() {
-                                let _ = history::save(&collected);
-                            }
-                            if let Some(ref path) = effective_output_jsonl {
-                                flush_jsonl(path, Some(&run_system_record), &existing_jsonl_records, &run_jsonl_records);
+                                let _ = history::save(&self.config.history, &collected);
                             }
-                            std::process::exit(EXIT_INTERRUPT);
+                            failure.exit(EXIT_INTERRUPT, Some(step_idx), &format!("Interrupted by {signal}"));
                         }
 
                         Some(event) = rx.recv() => {
                             handle_event(event, &mut StepState {
                                 response_text: &mut response_text,
+                                partial_text: &mut partial_text,
+                                metrics: &mut step_metrics,
                                 tools_used: &mut tools_used,
                                 failed: &mut failed,
                                 collected: &mut collected,
@@ -984,37 +1162,43 @@ impl CiRunner {
                                 any_tool_errors: &mut any_tool_errors,
                                 run_total_tokens: &mut run_total_tokens,
                                 max_tokens_budget,
+                                failure,
+                                step_idx,
                             });
 
                             // Abort if too many consecutive tool errors
                             const MAX_CONSECUTIVE_TOOL_ERRORS: u32 = 20;
                             if consecutive_tool_errors >= MAX_CONSECUTIVE_TOOL_ERRORS {
+                                let message = format!(
+                                    "Step {step_idx} ({label:?}) aborted: \
+                                     {MAX_CONSECUTIVE_TOOL_ERRORS} consecutive tool errors."
+                                );
                                 write_stderr(&format!(
-                                    "[sven:fatal] Step {step_idx} ({label:?}) aborted: \
-                                     {MAX_CONSECUTIVE_TOOL_ERRORS} consecutive tool errors. \
+                                    "[sven:fatal] {message} \
                                      This often indicates the model is using wrong parameter names \
                                      or is confused. Consider using a more capable model."
                                 ));
                                 if !collected.is_empty() {
-                                    let _ = history::save(&collected);
+                                    let _ = history::save(&self.config.history, &collected);
                                 }
                                 if let Some(ref path) = effective_output_jsonl {
-                                    flush_jsonl(path, Some(&run_system_record), &existing_jsonl_records, &run_jsonl_records);
+                                    flush_jsonl(path, Some(&run_system_record), &existing_jsonl_records, &run_jsonl_records, opts.output_image_dir.as_deref());
                                 }
-                                std::process::exit(EXIT_AGENT_ERROR);
+                                failure.exit(EXIT_AGENT_ERROR, Some(step_idx), &message);
                             }
                         }
 
                         result = &mut submit_fut => {
                             if let Err(e) = result {
-                                write_stderr(&format!(
-                                    "[sven:fatal] Step {step_idx} ({label:?}) failed: {e:#}"
-                                ));
-                                std::process::exit(EXIT_AGENT_ERROR);
+                                let message = format!("Step {step_idx} ({label:?}) failed: {e:#}");
+                                write_stderr(&format!("[sven:fatal] {message}"));
+                                failure.exit(EXIT_AGENT_ERROR, Some(step_idx), &message);
                             }
                             while let Ok(ev) = rx.try_recv() {
                                 handle_event(ev, &mut StepState {
                                     response_text: &mut response_text,
+                                    partial_text: &mut partial_text,
+                                    metrics: &mut step_metrics,
                                     tools_used: &mut tools_used,
                                     failed: &mut failed,
                                     collected: &mut collected,
@@ -1028,6 +1212,8 @@ impl CiRunner {
                                     any_tool_errors: &mut any_tool_errors,
                                     run_total_tokens: &mut run_total_tokens,
                                     max_tokens_budget,
+                                    failure,
+                                    step_idx,
                                 });
                             }
                             break;
@@ -1060,6 +1246,14 @@ impl CiRunner {
                 }
             }
 
+            // ── Step metrics ─────────────────────────────────────────────────
+            step_metrics.duration_ms = step_duration_ms;
+            step_metrics.tool_calls = tools_used.len() as u32;
+            if opts.output_format == OutputFormat::Jsonl {
+                write_stdout(&jsonl_metrics_line(&step_metrics_json(&step_metrics)));
+            }
+            run_metrics.push(step_metrics);
+
             // ── Inject step output into template vars for subsequent steps ───
             // Makes {{step.<label>.output}} and {{step.<N>.output}} available
             // in all following steps without any file I/O.
@@ -1074,6 +1268,7 @@ impl CiRunner {
                     Some(&run_system_record),
                     &existing_jsonl_records,
                     &run_jsonl_records,
+                    opts.output_image_dir.as_deref(),
                 );
             }
 
@@ -1104,13 +1299,17 @@ impl CiRunner {
                         tools_used: tools_used.clone(),
                         duration_ms: step_duration_ms,
                         success: !failed,
+                        skipped: false,
                     });
                 }
             }
 
             // ── Write per-step artifact ──────────────────────────────────────
-            if let Some(dir) = &opts.artifacts_dir {
+            // The conversation so far is rewritten too, so a run that dies in
+            // a later step still leaves a usable conversation.md.
+            if let Some(dir) = &artifacts_dir {
                 write_step_artifact(dir, step_idx, label, &collected[step_msg_start..]);
+                write_conversation_artifact(dir, &collected);
             }
 
             // ── Progress report ──────────────────────────────────────────────
@@ -1139,11 +1338,10 @@ impl CiRunner {
             }
 
             if failed {
-                write_stderr(&format!(
-                    "[sven:error] Step {step_idx} ({label:?}) reported an error. Aborting."
-                ));
+                let message = format!("Step {step_idx} ({label:?}) reported an error");
+                write_stderr(&format!("[sven:error] {message}. Aborting."));
                 if !collected.is_empty() {
-                    let _ = history::save(&collected);
+                    let _ = history::save(&self.config.history, &collected);
                 }
                 if let Some(ref path) = effective_output_jsonl {
                     flush_jsonl(
@@ -1151,11 +1349,20 @@ impl CiRunner {
                         Some(&run_system_record),
                         &existing_jsonl_records,
                         &run_jsonl_records,
+                        opts.output_image_dir.as_deref(),
                     );
                 }
-                std::process::exit(EXIT_AGENT_ERROR);
+                failure.exit(EXIT_AGENT_ERROR, Some(step_idx), &message);
             }
 
+            prev_exit_code = if any_tool_errors {
+                EXIT_TOOL_WARNINGS
+            } else {
+                EXIT_SUCCESS
+            } as i64;
+            prev_output = response_text;
+            any_tool_errors |= tool_errors_before;
+
             if step_idx < total {
                 write_stderr(&format!("\n--- step {}/{} complete ---\n", step_idx, total));
             }
@@ -1169,6 +1376,7 @@ impl CiRunner {
                 Some(&run_system_record),
                 &existing_jsonl_records,
                 &run_jsonl_records,
+                opts.output_image_dir.as_deref(),
             );
             write_progress(&format!("[sven:jsonl] Log written to {}", path.display()));
         }
@@ -1213,6 +1421,7 @@ impl CiRunner {
             let out = JsonOutput {
                 title,
                 steps: json_steps,
+                metrics: run_metrics,
             };
             let json = json_output_to_string(&out);
             write_stdout(&format!("{json}\n"));
@@ -1248,24 +1457,34 @@ impl CiRunner {
         }
 
         // ── Save artifacts metadata ──────────────────────────────────────────
-        if let Some(dir) = &opts.artifacts_dir {
+        if let Some(dir) = &artifacts_dir {
             write_conversation_artifact(dir, &collected);
         }
 
         // ── Persist conversation to history ──────────────────────────────────
         if !collected.is_empty() {
-            if let Err(e) = history::save(&collected) {
+            if let Err(e) = history::save(&self.config.history, &collected) {
                 debug!("failed to save conversation to history: {e}");
             }
         }
 
+        // ── Structured output ────────────────────────────────────────────────
+        // Outputs above are written first so a non-conforming reply can still
+        // be inspected.
+        if let Some(schema) = &model_cfg.response_format {
+            if let Err(message) = validate_response_format(&prev_output, schema) {
+                write_stderr(&format!("[sven:error] {message}"));
+                failure.exit(EXIT_VALIDATION_ERROR, None, &message);
+            }
+        }
+
         // ── Exit with tool-warning code if any non-fatal tool errors occurred ─
         // Exit code 3 signals "run completed but with tool warnings" — the
         // caller can use this to distinguish a clean run from a partially
         // successful one without treating it as a hard failure.
         if any_tool_errors {
             write_stderr("[sven:warn] Run completed with tool errors (exit 3).");
-            std::process::exit(EXIT_TOOL_WARNINGS);
+            failure.exit(EXIT_TOOL_WARNINGS, None, "Run completed with tool errors");
         }
 
         Ok(())
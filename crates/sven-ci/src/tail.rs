@@ -0,0 +1,176 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! `sven tail` — follow a JSONL conversation file while a run writes it.
+//!
+//! The runner rewrites `--output-jsonl` files after every step, always with
+//! the same prefix, so following the file by byte offset sees each record
+//! exactly once.  New records are printed in conversation markdown, the same
+//! format `--output-format conversation` streams.
+
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use sven_input::{parse_jsonl_full, serialize_conversation_records, ConversationRecord};
+
+use crate::output::{write_stderr, write_stdout};
+
+/// How often a followed file is checked for new data.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Incremental reader for a JSONL conversation file that is still growing.
+#[derive(Debug, Default)]
+pub struct JsonlTail {
+    /// Bytes of complete lines consumed so far.
+    offset: u64,
+    /// The file was shorter than `offset` on the previous poll.
+    shrunk: bool,
+}
+
+impl JsonlTail {
+    /// Return the records appended to `path` since the previous call.
+    ///
+    /// A final line without a trailing newline is still being written and is
+    /// left for a later call.  A missing file yields no records.  When the
+    /// file stays shorter than what was already read on two consecutive polls
+    /// it was replaced, and reading starts over from the beginning; a single
+    /// short poll is the runner's rewrite in progress and is ignored.
+    pub fn poll(&mut self, path: &Path) -> Result<Vec<ConversationRecord>> {
+        let mut file = match std::fs::File::open(path) {
+            Ok(f) => f,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("opening {}", path.display())),
+        };
+        let len = file.metadata()?.len();
+        if len < self.offset {
+            if !self.shrunk {
+                self.shrunk = true;
+                return Ok(Vec::new());
+            }
+            self.offset = 0;
+        }
+        self.shrunk = false;
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)?;
+        let Some(end) = buf.iter().rposition(|&b| b == b'\n') else {
+            return Ok(Vec::new());
+        };
+        let chunk = std::str::from_utf8(&buf[..=end])
+            .with_context(|| format!("{} is not valid UTF-8", path.display()))?;
+        let parsed =
+            parse_jsonl_full(chunk).with_context(|| format!("parsing {}", path.display()))?;
+        self.offset += end as u64 + 1;
+        Ok(parsed.records)
+    }
+}
+
+/// Print the records of `path` as conversation markdown, then keep printing
+/// new ones as they are appended until interrupted when `follow` is set.
+pub async fn tail_jsonl(path: &Path, follow: bool) -> Result<()> {
+    if !follow && !path.exists() {
+        anyhow::bail!("{} does not exist", path.display());
+    }
+    let mut tail = JsonlTail::default();
+    loop {
+        match tail.poll(path) {
+            Ok(records) if !records.is_empty() => {
+                write_stdout(&serialize_conversation_records(&records, None));
+            }
+            Ok(_) => {}
+            Err(e) if follow => write_stderr(&format!("[sven:warn] {e:#}")),
+            Err(e) => return Err(e),
+        }
+        if !follow {
+            return Ok(());
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    use sven_input::{jsonl_meta_line, serialize_jsonl_records};
+    use sven_model::Message;
+
+    fn line(record: &ConversationRecord) -> String {
+        format!("{}\n", serde_json::to_string(record).unwrap())
+    }
+
+    fn append(path: &Path, text: &str) {
+        let mut f = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        f.write_all(text.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn reads_appended_records_incrementally() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.jsonl");
+        let mut tail = JsonlTail::default();
+        assert!(tail.poll(&path).unwrap().is_empty(), "missing file");
+
+        let user = ConversationRecord::Message(Message::user("ping"));
+        append(&path, &format!("{}{}", jsonl_meta_line(), line(&user)));
+        assert_eq!(tail.poll(&path).unwrap().len(), 1);
+        assert!(tail.poll(&path).unwrap().is_empty(), "nothing new");
+
+        // A half-written line is held back until its newline arrives.
+        let reply = line(&ConversationRecord::Message(Message::assistant("pong")));
+        let (head, rest) = reply.split_at(10);
+        append(&path, head);
+        assert!(tail.poll(&path).unwrap().is_empty());
+        append(&path, rest);
+        let records = tail.poll(&path).unwrap();
+        assert_eq!(records.len(), 1);
+        assert!(
+            matches!(&records[0], ConversationRecord::Message(m) if m.as_text() == Some("pong"))
+        );
+    }
+
+    #[test]
+    fn full_rewrite_with_same_prefix_yields_only_new_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.jsonl");
+        let mut records = vec![ConversationRecord::Message(Message::user("one"))];
+        std::fs::write(&path, serialize_jsonl_records(&records)).unwrap();
+        let mut tail = JsonlTail::default();
+        assert_eq!(tail.poll(&path).unwrap().len(), 1);
+
+        records.push(ConversationRecord::Thinking {
+            content: "hmm".into(),
+        });
+        records.push(ConversationRecord::Message(Message::assistant("two")));
+        std::fs::write(&path, serialize_jsonl_records(&records)).unwrap();
+        let new = tail.poll(&path).unwrap();
+        assert_eq!(new.len(), 2);
+        assert!(matches!(new[0], ConversationRecord::Thinking { .. }));
+    }
+
+    #[test]
+    fn replaced_file_is_read_from_the_start() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.jsonl");
+        let long = vec![
+            ConversationRecord::Message(Message::user("first run")),
+            ConversationRecord::Message(Message::assistant("a long answer")),
+        ];
+        std::fs::write(&path, serialize_jsonl_records(&long)).unwrap();
+        let mut tail = JsonlTail::default();
+        assert_eq!(tail.poll(&path).unwrap().len(), 2);
+
+        let short = vec![ConversationRecord::Message(Message::user("new"))];
+        std::fs::write(&path, serialize_jsonl_records(&short)).unwrap();
+        assert!(tail.poll(&path).unwrap().is_empty(), "first short poll");
+        assert_eq!(tail.poll(&path).unwrap().len(), 1);
+    }
+}
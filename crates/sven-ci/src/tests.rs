@@ -716,6 +716,79 @@ mod tests {
         assert!(parsed.pending_user_input.is_none());
     }
 
+    // ── Frontmatter tool allow/deny lists ─────────────────────────────────────
+
+    /// Build the agent a workflow with frontmatter `md` would run with.
+    async fn restricted_tool_names(md: &str) -> Vec<String> {
+        let fm = sven_input::parse_frontmatter(md).0.unwrap();
+        let mut config = sven_config::Config::default();
+        crate::runner::restrict_tools(
+            &mut config.tools,
+            fm.tools_allow.as_deref(),
+            fm.tools_deny.as_deref(),
+        );
+        let agent = sven_bootstrap::AgentBuilder::new(std::sync::Arc::new(config))
+            .build(
+                sven_config::AgentMode::Agent,
+                std::sync::Arc::new(sven_model::MockProvider),
+                sven_bootstrap::ToolSetProfile::Full {
+                    question_tx: None,
+                    todos: Default::default(),
+                    buffer_store: std::sync::Arc::new(tokio::sync::Mutex::new(
+                        sven_tools::OutputBufferStore::new(),
+                    )),
+                },
+            )
+            .await
+            .unwrap();
+        let mut names = agent.tools().names();
+        names.sort();
+        names
+    }
+
+    #[tokio::test]
+    async fn frontmatter_tools_deny_removes_write_tools() {
+        let md = "---\ntools_deny: [write_file, edit_file]\n---\n## Review\nLook only.";
+        let names = restricted_tool_names(md).await;
+        assert!(names.iter().any(|n| n == "read_file"), "{names:?}");
+        assert!(!names.iter().any(|n| n == "write_file" || n == "edit_file"));
+    }
+
+    #[tokio::test]
+    async fn frontmatter_tools_allow_then_deny() {
+        let md = "---\ntools_allow:\n  - read_file\n  - grep\n  - reed_file\ntools_deny: [grep]\n---\n## s\ngo.";
+        assert_eq!(restricted_tool_names(md).await, ["read_file"]);
+    }
+
+    #[test]
+    fn frontmatter_tool_lists_extend_the_config() {
+        let mut tools = sven_config::ToolsConfig {
+            deny: vec!["shell".into()],
+            ..Default::default()
+        };
+        crate::runner::restrict_tools(&mut tools, None, None);
+        assert!(tools.allow.is_none());
+        assert_eq!(tools.deny, ["shell"]);
+
+        let allow = ["grep".to_string()];
+        crate::runner::restrict_tools(&mut tools, Some(&allow), Some(&["task".to_string()]));
+        assert_eq!(tools.allow.as_deref(), Some(&allow[..]));
+        assert_eq!(tools.deny, ["shell", "task"]);
+    }
+
+    #[test]
+    fn frontmatter_tools_allow_cannot_widen_the_config() {
+        let mut tools = sven_config::ToolsConfig {
+            allow: Some(vec!["read_file".into(), "grep".into()]),
+            deny: vec!["grep".into()],
+            ..Default::default()
+        };
+        let allow = ["grep".to_string(), "shell".to_string()];
+        crate::runner::restrict_tools(&mut tools, Some(&allow), None);
+        assert_eq!(tools.allow.as_deref(), Some(&["grep".to_string()][..]));
+        assert_eq!(tools.deny, ["grep"]);
+    }
+
     // ── Edge cases ────────────────────────────────────────────────────────────
 
     #[test]
@@ -749,6 +822,90 @@ mod tests {
         assert!(response.contains("The answer is 4."));
     }
 
+    // ── --json-errors ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn every_exit_code_has_a_json_error_kind() {
+        use crate::runner::*;
+        let kinds = [
+            (EXIT_AGENT_ERROR, "agent_error"),
+            (EXIT_VALIDATION_ERROR, "validation_error"),
+            (EXIT_TOOL_WARNINGS, "tool_warnings"),
+            (EXIT_BUDGET_EXHAUSTED, "budget_exhausted"),
+            (EXIT_TIMEOUT, "timeout"),
+            (EXIT_INTERRUPT, "interrupt"),
+        ];
+        for (code, kind) in kinds {
+            assert_eq!(exit_kind(code), kind, "exit code {code}");
+        }
+    }
+
+    #[test]
+    fn json_error_has_the_documented_shape() {
+        use crate::runner::{json_error, EXIT_TIMEOUT, EXIT_VALIDATION_ERROR};
+        use std::time::Duration;
+
+        let v = json_error(
+            EXIT_TIMEOUT,
+            "Step 2 timed out",
+            Some(2),
+            Duration::from_millis(1500),
+        );
+        let obj = v.as_object().unwrap();
+        let mut keys: Vec<_> = obj.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, ["code", "elapsed_secs", "kind", "message", "step"]);
+        assert_eq!(v["code"], 124);
+        assert_eq!(v["kind"], "timeout");
+        assert_eq!(v["message"], "Step 2 timed out");
+        assert_eq!(v["step"], 2);
+        assert_eq!(v["elapsed_secs"], 1.5);
+
+        let v = json_error(EXIT_VALIDATION_ERROR, "bad", None, Duration::ZERO);
+        assert!(v["step"].is_null());
+        assert!(!v.to_string().contains('\n'), "must be a single line");
+    }
+
+    // ── response_format validation ────────────────────────────────────────────
+
+    fn verdict_schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "verdict": { "enum": ["pass", "fail"] },
+                "reasons": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["verdict"],
+            "additionalProperties": false
+        })
+    }
+
+    #[test]
+    fn conforming_reply_passes_validation() {
+        use crate::runner::validate_response_format;
+        let schema = verdict_schema();
+        assert_eq!(
+            validate_response_format(r#"{"verdict":"pass","reasons":[]}"#, &schema),
+            Ok(())
+        );
+        assert_eq!(
+            validate_response_format("```json\n{\"verdict\": \"fail\"}\n```\n", &schema),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn non_conforming_reply_fails_validation() {
+        use crate::runner::validate_response_format;
+        let schema = verdict_schema();
+        let err = validate_response_format(r#"{"verdict":"maybe"}"#, &schema).unwrap_err();
+        assert!(err.contains("does not match"), "{err}");
+        let err = validate_response_format(r#"{"reasons":["x"]}"#, &schema).unwrap_err();
+        assert!(err.contains("verdict"), "{err}");
+        let err = validate_response_format("The verdict is pass.", &schema).unwrap_err();
+        assert!(err.contains("not valid JSON"), "{err}");
+    }
+
     #[test]
     fn jsonl_detection_stops_at_10_lines() {
         // Build a 20-line JSONL with the 11th being invalid — must still pass
@@ -8,9 +8,13 @@
 //! JSONL conversation, updating the tool-result messages in-place before
 //! seeding the agent.  The model's text responses are preserved so that the
 //! re-run reflects the original reasoning with updated tool outputs.
+//!
+//! `sven replay` uses [`verify_tool_calls`] instead, which leaves the records
+//! alone and reports how each fresh result compares to the recorded one.
 
 use std::sync::Arc;
 
+use similar::TextDiff;
 use sven_input::ConversationRecord;
 use sven_model::{Message, MessageContent, Role};
 use sven_tools::{ToolCall, ToolRegistry};
@@ -42,9 +46,38 @@ pub async fn replay_tool_calls(
 ) -> usize {
     let mut replayed = 0;
 
-    // Collect (index, tool_call_id, name, args) for all assistant ToolCall records
-    // so we can mutate the slice afterwards without conflicting borrows.
-    let call_sites: Vec<(usize, String, String, String)> = records
+    // Collect the call sites first so we can mutate the slice afterwards
+    // without conflicting borrows.
+    for (call_idx, tc) in tool_call_sites(records) {
+        let tool_call_id = tc.id.clone();
+        // Execute the tool call with fresh inputs.
+        let output = tools.execute(&tc).await;
+
+        // Find the matching ToolResult record after the call and update it in place.
+        let after_call = &mut records[call_idx + 1..];
+        let mut found = false;
+        for slot in after_call.iter_mut() {
+            if is_tool_result_for(slot, &tool_call_id) {
+                *slot = ConversationRecord::Message(Message::tool_result(
+                    &tool_call_id,
+                    &output.content,
+                ));
+                found = true;
+                break;
+            }
+        }
+        if found {
+            replayed += 1;
+        }
+    }
+
+    replayed
+}
+
+/// (index, call) for every assistant `ToolCall` record, with the stored JSON
+/// arguments parsed (an empty object when they do not parse).
+fn tool_call_sites(records: &[ConversationRecord]) -> Vec<(usize, ToolCall)> {
+    records
         .iter()
         .enumerate()
         .filter_map(|(i, record)| {
@@ -57,50 +90,100 @@ pub async fn replay_tool_calls(
                     },
             }) = record
             {
+                let args = serde_json::from_str::<serde_json::Value>(&function.arguments)
+                    .unwrap_or(serde_json::Value::Object(Default::default()));
                 Some((
                     i,
-                    tool_call_id.clone(),
-                    function.name.clone(),
-                    function.arguments.clone(),
+                    ToolCall {
+                        id: tool_call_id.clone(),
+                        name: function.name.clone(),
+                        args,
+                    },
                 ))
             } else {
                 None
             }
         })
-        .collect();
+        .collect()
+}
 
-    for (call_idx, tool_call_id, name, args_json) in call_sites {
-        // Parse the stored JSON arguments.
-        let args = serde_json::from_str::<serde_json::Value>(&args_json)
-            .unwrap_or(serde_json::Value::Object(Default::default()));
+/// One recorded tool call re-run by [`verify_tool_calls`].
+#[derive(Debug, Clone)]
+pub struct ReplayedCall {
+    pub tool_call_id: String,
+    pub name: String,
+    /// The result stored in the conversation; `None` when the call has no
+    /// result record.
+    pub recorded: Option<String>,
+    /// The result of running the call now; `None` when it was skipped
+    /// because the tool is not read-only and mutations were not allowed.
+    pub current: Option<String>,
+}
 
-        // Execute the tool call with fresh inputs.
-        let tc = ToolCall {
-            id: tool_call_id.clone(),
-            name,
-            args,
-        };
-        let output = tools.execute(&tc).await;
+impl ReplayedCall {
+    pub fn skipped(&self) -> bool {
+        self.current.is_none()
+    }
 
-        // Find the matching ToolResult record after the call and update it in place.
-        let after_call = &mut records[call_idx + 1..];
-        let mut found = false;
-        for slot in after_call.iter_mut() {
-            if is_tool_result_for(slot, &tool_call_id) {
-                *slot = ConversationRecord::Message(Message::tool_result(
-                    &tool_call_id,
-                    &output.content,
-                ));
-                found = true;
-                break;
-            }
-        }
-        if found {
-            replayed += 1;
+    /// Whether the call ran and produced exactly the recorded result.
+    pub fn reproduces(&self) -> bool {
+        self.current.is_some() && self.current == self.recorded
+    }
+
+    /// Unified diff from the recorded to the current result; empty when the
+    /// call was skipped or reproduces.
+    pub fn diff(&self) -> String {
+        let Some(current) = &self.current else {
+            return String::new();
+        };
+        let recorded = self.recorded.as_deref().unwrap_or("");
+        if current == recorded {
+            return String::new();
         }
+        TextDiff::from_lines(recorded, current.as_str())
+            .unified_diff()
+            .context_radius(2)
+            .header("recorded", "current")
+            .to_string()
     }
+}
 
-    replayed
+/// Re-run the tool calls in `records` against the current working tree and
+/// compare each fresh result with the recorded one.  Only read-only tools
+/// (see [`sven_tools::Tool::read_only`]) run unless `allow_mutations` is
+/// set; `records` is left unchanged.
+pub async fn verify_tool_calls(
+    records: &[ConversationRecord],
+    tools: &ToolRegistry,
+    allow_mutations: bool,
+) -> Vec<ReplayedCall> {
+    let mut out = Vec::new();
+    for (call_idx, tc) in tool_call_sites(records) {
+        let recorded = records[call_idx + 1..].iter().find_map(|r| match r {
+            ConversationRecord::Message(Message {
+                role: Role::Tool,
+                content:
+                    MessageContent::ToolResult {
+                        tool_call_id,
+                        content,
+                    },
+            }) if *tool_call_id == tc.id => Some(content.as_text().unwrap_or("").to_string()),
+            _ => None,
+        });
+        let runnable = allow_mutations || tools.get(&tc.name).is_some_and(|t| t.read_only());
+        let current = if runnable {
+            Some(tools.execute(&tc).await.content)
+        } else {
+            None
+        };
+        out.push(ReplayedCall {
+            tool_call_id: tc.id,
+            name: tc.name,
+            recorded,
+            current,
+        });
+    }
+    out
 }
 
 #[cfg(test)]
@@ -126,6 +209,9 @@ mod tests {
         fn default_policy(&self) -> ApprovalPolicy {
             ApprovalPolicy::Auto
         }
+        fn read_only(&self) -> bool {
+            true
+        }
         async fn execute(&self, call: &ToolCall) -> ToolOutput {
             let msg = call
                 .args
@@ -136,6 +222,80 @@ mod tests {
         }
     }
 
+    /// A tool that would change the working tree.
+    struct TouchTool;
+
+    #[async_trait::async_trait]
+    impl Tool for TouchTool {
+        fn name(&self) -> &str {
+            "touch"
+        }
+        fn description(&self) -> &str {
+            "creates a file"
+        }
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({})
+        }
+        fn default_policy(&self) -> ApprovalPolicy {
+            ApprovalPolicy::Auto
+        }
+        async fn execute(&self, call: &ToolCall) -> ToolOutput {
+            ToolOutput::ok(&call.id, "touched")
+        }
+    }
+
+    /// A saved conversation with a deterministic `echo` call and a mutating
+    /// `touch` call, as written by the JSONL history format.
+    const FIXTURE: &str = concat!(
+        r#"{"type":"meta","schema_version":1}"#,
+        "\n",
+        r#"{"type":"message","data":{"role":"user","content":"say hello"}}"#,
+        "\n",
+        r#"{"type":"message","data":{"role":"assistant","content":{"tool_call_id":"c1","function":{"name":"echo","arguments":"{\"message\":\"hello\"}"}}}}"#,
+        "\n",
+        r#"{"type":"message","data":{"role":"tool","content":{"tool_call_id":"c1","content":"echo: hello"}}}"#,
+        "\n",
+        r#"{"type":"message","data":{"role":"assistant","content":{"tool_call_id":"c2","function":{"name":"touch","arguments":"{}"}}}}"#,
+        "\n",
+        r#"{"type":"message","data":{"role":"tool","content":{"tool_call_id":"c2","content":"touched"}}}"#,
+        "\n",
+    );
+
+    fn fixture_registry() -> ToolRegistry {
+        let mut reg = ToolRegistry::new();
+        reg.register(EchoTool);
+        reg.register(TouchTool);
+        reg
+    }
+
+    #[tokio::test]
+    async fn verify_replays_fixture_read_only_by_default() {
+        let records = sven_input::parse_jsonl_full(FIXTURE).unwrap().records;
+        let calls = verify_tool_calls(&records, &fixture_registry(), false).await;
+        assert_eq!(calls.len(), 2);
+
+        assert_eq!(calls[0].name, "echo");
+        assert!(calls[0].reproduces(), "{calls:?}");
+        assert!(calls[0].diff().is_empty());
+
+        assert_eq!(calls[1].name, "touch");
+        assert!(calls[1].skipped());
+
+        let applied = verify_tool_calls(&records, &fixture_registry(), true).await;
+        assert!(applied.iter().all(ReplayedCall::reproduces));
+    }
+
+    #[tokio::test]
+    async fn verify_reports_a_diff_when_the_result_changed() {
+        let fixture = FIXTURE.replace(r#""content":"echo: hello""#, r#""content":"echo: hi""#);
+        let records = sven_input::parse_jsonl_full(&fixture).unwrap().records;
+        let calls = verify_tool_calls(&records, &fixture_registry(), false).await;
+        assert!(!calls[0].reproduces());
+        let diff = calls[0].diff();
+        assert!(diff.contains("-echo: hi"), "{diff}");
+        assert!(diff.contains("+echo: hello"), "{diff}");
+    }
+
     #[tokio::test]
     async fn replays_tool_calls_and_updates_results() {
         let mut reg = ToolRegistry::new();
@@ -0,0 +1,226 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! Static checks behind `sven validate --strict`.
+//!
+//! The runner only discovers a bad `provider=`/`model=` reference when it
+//! reaches the step, and then merely warns and keeps the current model.  These
+//! checks resolve every reference up front, the same way the runner does, so a
+//! typo fails validation before a long CI run starts.
+
+use sven_config::Config;
+use sven_input::{ParsedWorkflow, Step, WorkflowMetadata};
+use sven_model::DriverCategory;
+
+/// A model reference in a workflow that does not resolve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelRefIssue {
+    /// Where the reference appears, e.g. `step 2 "Review"` or `frontmatter models.plan`.
+    pub location: String,
+    /// The reference as written in the workflow.
+    pub reference: String,
+    /// Why it does not resolve.
+    pub reason: String,
+}
+
+impl std::fmt::Display for ModelRefIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {:?}: {}",
+            self.location, self.reference, self.reason
+        )
+    }
+}
+
+/// Check every provider/model reference in `workflow` and its frontmatter.
+///
+/// Returns one issue per unresolvable reference; an empty vector means the
+/// workflow passes strict validation.
+pub fn check_model_refs(
+    config: &Config,
+    frontmatter: Option<&WorkflowMetadata>,
+    workflow: &ParsedWorkflow,
+) -> Vec<ModelRefIssue> {
+    let mut issues = Vec::new();
+
+    if let Some(models) = frontmatter.and_then(|fm| fm.models.as_ref()) {
+        let mut pairs: Vec<_> = models.iter().collect();
+        pairs.sort_by(|a, b| a.0.cmp(b.0));
+        for (mode, reference) in pairs {
+            if let Err(reason) = check_model_ref(config, reference) {
+                issues.push(ModelRefIssue {
+                    location: format!("frontmatter models.{mode}"),
+                    reference: reference.clone(),
+                    reason,
+                });
+            }
+        }
+    }
+
+    for (i, step) in workflow.steps.iter().enumerate() {
+        if let Err((reference, reason)) = check_step(config, step) {
+            let location = match step.label.as_deref() {
+                Some(label) => format!("step {} {label:?}", i + 1),
+                None => format!("step {}", i + 1),
+            };
+            issues.push(ModelRefIssue {
+                location,
+                reference,
+                reason,
+            });
+        }
+    }
+
+    issues
+}
+
+/// Check one step's `provider=`/`model=` options, combined the same way the
+/// runner combines them.  On failure returns the offending reference and the
+/// reason.
+fn check_step(config: &Config, step: &Step) -> Result<(), (String, String)> {
+    let (provider, model) = (
+        step.options.provider.as_deref(),
+        step.options.model.as_deref(),
+    );
+    // A bare provider typo would otherwise be taken for a model name by the
+    // resolver, so check it on its own first.
+    if let Some(prov) = provider {
+        if !is_known_provider(config, prov) {
+            return Err((prov.to_string(), format!("unknown provider {prov:?}")));
+        }
+    }
+    let reference = match (provider, model) {
+        (Some(prov), Some(model)) => format!("{prov}/{model}"),
+        (Some(prov), None) => prov.to_string(),
+        (None, Some(model)) => model.to_string(),
+        (None, None) => return Ok(()),
+    };
+    check_model_ref(config, &reference).map_err(|reason| (reference, reason))
+}
+
+/// Resolve `reference` through [`sven_model::resolve_model_from_config`] and
+/// check that the result names a registered driver and a model it offers.
+fn check_model_ref(config: &Config, reference: &str) -> Result<(), String> {
+    let (prov, model) = match reference.split_once('/') {
+        Some((prov, model)) => (prov, Some(model)),
+        None => (reference, None),
+    };
+    if model.is_some() && !is_known_provider(config, prov) {
+        return Err(format!("unknown provider {prov:?}"));
+    }
+    let resolved = sven_model::resolve_model_from_config(config, reference);
+    if sven_model::get_driver(&resolved.provider).is_none() {
+        return Err(format!(
+            "resolves to unknown provider {:?}",
+            resolved.provider
+        ));
+    }
+    if resolved.name.trim().is_empty() {
+        return Err("does not name a model".to_string());
+    }
+    // A bare provider keeps the current model; only a name the workflow
+    // wrote itself is checked.
+    let names_model = model.is_some() || !is_known_provider(config, reference);
+    if names_model && !is_known_model(config, prov, &resolved.provider, &resolved.name) {
+        return Err(format!(
+            "unknown model {:?} for provider {:?}",
+            resolved.name, resolved.provider
+        ));
+    }
+    Ok(())
+}
+
+/// Whether `name` is a model of driver `provider`, reached through the
+/// reference prefix `key`.
+///
+/// A named entry in `config.providers` decides by its `models` list (any
+/// name when it has none); otherwise the catalog must list the model.  The
+/// models of local servers, gateways and test drivers are not listed
+/// exhaustively, so any name is accepted for them.
+fn is_known_model(config: &Config, key: &str, provider: &str, name: &str) -> bool {
+    if let Some(entry) = config.providers.get(key) {
+        return entry.models.is_empty() || entry.models.contains_key(name);
+    }
+    let unlisted = sven_model::get_driver(provider).is_some_and(|d| {
+        matches!(
+            d.category,
+            DriverCategory::Local | DriverCategory::Gateway | DriverCategory::Testing
+        )
+    });
+    unlisted
+        || name == config.model.name
+        || sven_model::catalog::lookup(provider, name).is_some()
+        || config
+            .providers
+            .values()
+            .any(|p| p.name == provider && p.models.contains_key(name))
+}
+
+/// A provider is known when it is a registered driver or a named entry in
+/// `config.providers`.
+fn is_known_provider(config: &Config, provider: &str) -> bool {
+    sven_model::get_driver(provider).is_some() || config.providers.contains_key(provider)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sven_input::{parse_frontmatter, parse_workflow};
+
+    fn check(content: &str) -> Vec<ModelRefIssue> {
+        let (fm, body) = parse_frontmatter(content);
+        let workflow = parse_workflow(body);
+        check_model_refs(&Config::default(), fm.as_ref(), &workflow)
+    }
+
+    #[test]
+    fn bogus_provider_fails_strict_but_parses() {
+        let content = "## Build\n<!-- sven: provider=bogusprov model=gpt-4o -->\nBuild it.";
+        // Lenient validation only parses; the workflow is well-formed.
+        let (_, body) = parse_frontmatter(content);
+        assert_eq!(parse_workflow(body).steps.len(), 1);
+
+        let issues = check(content);
+        assert_eq!(issues.len(), 1, "{issues:?}");
+        assert_eq!(issues[0].location, "step 1 \"Build\"");
+        assert_eq!(issues[0].reference, "bogusprov");
+        assert!(issues[0].reason.contains("unknown provider"));
+    }
+
+    #[test]
+    fn compound_model_with_bogus_provider_fails() {
+        let issues = check("## Step\n<!-- sven: model=bogusprov/some-model -->\nGo.");
+        assert_eq!(issues.len(), 1, "{issues:?}");
+        assert_eq!(issues[0].reference, "bogusprov/some-model");
+    }
+
+    #[test]
+    fn known_providers_and_models_pass() {
+        let content = "---\nmodels:\n  plan: anthropic/claude-opus-4-6\n---\n\
+                       ## A\n<!-- sven: provider=openai model=gpt-4o -->\nOne.\n\
+                       ## B\n<!-- sven: model=mock/mock-model -->\nTwo.\n\
+                       ## C\nThree.";
+        assert!(check(content).is_empty());
+    }
+
+    #[test]
+    fn model_name_typo_fails() {
+        let issues = check("## A\n<!-- sven: model=openai/gpt-4oo -->\nOne.");
+        assert_eq!(issues.len(), 1, "{issues:?}");
+        assert_eq!(issues[0].reference, "openai/gpt-4oo");
+        assert!(issues[0].reason.contains("unknown model"), "{issues:?}");
+
+        let issues = check("## A\n<!-- sven: provider=openai model=gpt-4oo -->\nOne.");
+        assert_eq!(issues.len(), 1, "{issues:?}");
+        // Local servers serve whatever the user pulled.
+        assert!(check("## A\n<!-- sven: model=ollama/my-finetune -->\nOne.").is_empty());
+    }
+
+    #[test]
+    fn frontmatter_model_with_bogus_provider_fails() {
+        let issues = check("---\nmodels:\n  agent: nope/model-x\n---\n## A\nOne.");
+        assert_eq!(issues.len(), 1, "{issues:?}");
+        assert_eq!(issues[0].location, "frontmatter models.agent");
+    }
+}
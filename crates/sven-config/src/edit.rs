@@ -0,0 +1,451 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! Dotted-key access to config values for `sven config get/set`.
+//!
+//! [`set_key`] edits the config file in place where it can: an existing
+//! scalar is replaced on its own line and a new key is inserted under the
+//! nearest existing parent, so comments and layout survive.  Anything the
+//! line editor cannot express (block scalars, sequence values, flow
+//! mappings) falls back to re-serialising the whole file, which drops
+//! comments.
+
+use std::path::Path;
+
+use anyhow::{bail, Context};
+use serde_yaml::{Mapping, Value};
+
+use crate::loader::{merge_yaml, unknown_fields};
+use crate::Config;
+
+/// Look up `key` (e.g. `model.temperature`) in the effective `config`.
+///
+/// Returns `None` when the key is part of the schema but not set, and an
+/// error when it is not a config key at all.
+pub fn get_key(config: &Config, key: &str) -> anyhow::Result<Option<Value>> {
+    let segments = split_key(key)?;
+    check_known(&segments)?;
+    let root = serde_yaml::to_value(config).context("serialising config")?;
+    Ok(lookup(&root, &segments).filter(|v| !v.is_null()).cloned())
+}
+
+/// Set `key` to `raw` in the config file at `path`, creating the file when
+/// it does not exist, and return the value that was stored.
+///
+/// `raw` is parsed as a YAML scalar, so `0.2`, `true` and `null` become a
+/// number, a boolean and an unset value; when the field expects a string the
+/// raw text is stored as one instead.  The file is only written when the
+/// result still deserialises as a config.
+pub fn set_key(path: &Path, key: &str, raw: &str) -> anyhow::Result<Value> {
+    let segments = split_key(key)?;
+    check_known(&segments)?;
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+    };
+
+    let typed = parse_value(raw);
+    let mut candidates = vec![typed.clone()];
+    if !typed.is_string() {
+        candidates.push(Value::String(raw.to_string()));
+    }
+    let mut last_err = None;
+    for value in candidates {
+        let updated = edit_text(&text, &segments, &value)?;
+        match check_config(&updated) {
+            Ok(()) => {
+                if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+                    std::fs::create_dir_all(dir)
+                        .with_context(|| format!("creating {}", dir.display()))?;
+                }
+                std::fs::write(path, updated)
+                    .with_context(|| format!("writing {}", path.display()))?;
+                return Ok(value);
+            }
+            Err(e) => last_err = Some(e),
+        }
+    }
+    let err = last_err.expect("at least one candidate value");
+    Err(err.context(format!("invalid value {raw:?} for `{key}`")))
+}
+
+/// Parse a command-line value as a YAML scalar; text that is not valid YAML
+/// is taken as a plain string.
+fn parse_value(raw: &str) -> Value {
+    serde_yaml::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+}
+
+fn split_key(key: &str) -> anyhow::Result<Vec<&str>> {
+    let segments: Vec<&str> = key.split('.').collect();
+    if segments.iter().any(|s| s.is_empty()) {
+        bail!("invalid config key `{key}`: expected a dotted path like `model.temperature`");
+    }
+    Ok(segments)
+}
+
+/// Reject keys the schema does not know, using the same field lists as the
+/// unknown-field warnings at load time.
+fn check_known(segments: &[&str]) -> anyhow::Result<()> {
+    let probe = nest(segments, Value::Null);
+    if let Some(field) = unknown_fields(&probe, "").into_iter().next() {
+        bail!("unknown config key `{field}`");
+    }
+    Ok(())
+}
+
+/// Build `{a: {b: value}}` from `[a, b]`.
+fn nest(segments: &[&str], value: Value) -> Value {
+    segments.iter().rev().fold(value, |inner, seg| {
+        let mut map = Mapping::new();
+        map.insert(Value::String((*seg).to_string()), inner);
+        Value::Mapping(map)
+    })
+}
+
+fn lookup<'v>(root: &'v Value, segments: &[&str]) -> Option<&'v Value> {
+    segments.iter().try_fold(root, |node, seg| match node {
+        Value::Mapping(map) => map.get(*seg),
+        Value::Sequence(items) => items.get(seg.parse::<usize>().ok()?),
+        _ => None,
+    })
+}
+
+/// The edited file must still load: merge it over the defaults (a layer may
+/// omit required fields such as `model.name`) and deserialise.
+fn check_config(text: &str) -> anyhow::Result<()> {
+    let layer: Value = serde_yaml::from_str(text).context("parsing edited config")?;
+    if layer.is_null() {
+        return Ok(());
+    }
+    let mut merged = serde_yaml::to_value(Config::default())?;
+    merge_yaml(&mut merged, layer);
+    serde_yaml::from_value::<Config>(merged)?;
+    Ok(())
+}
+
+/// Return `text` with `segments` set to `value`, editing lines in place when
+/// possible and re-serialising the document otherwise.
+fn edit_text(text: &str, segments: &[&str], value: &Value) -> anyhow::Result<String> {
+    if let Some(edited) = edit_lines(text, segments, value) {
+        let reparsed: Option<Value> = serde_yaml::from_str(&edited).ok();
+        if reparsed.as_ref().and_then(|v| lookup(v, segments)) == Some(value) {
+            return Ok(edited);
+        }
+    }
+    let mut doc: Value = if text.trim().is_empty() {
+        Value::Mapping(Mapping::new())
+    } else {
+        serde_yaml::from_str(text).context("parsing config file")?
+    };
+    set_in_value(&mut doc, segments, value.clone());
+    Ok(serde_yaml::to_string(&doc)?)
+}
+
+fn set_in_value(node: &mut Value, segments: &[&str], value: Value) {
+    let Some((first, rest)) = segments.split_first() else {
+        *node = value;
+        return;
+    };
+    if !node.is_mapping() {
+        *node = Value::Mapping(Mapping::new());
+    }
+    let Value::Mapping(map) = node else {
+        unreachable!()
+    };
+    let child = map
+        .entry(Value::String((*first).to_string()))
+        .or_insert(Value::Null);
+    set_in_value(child, rest, value);
+}
+
+/// A `key: value` line of a block mapping.
+struct Entry {
+    line: usize,
+    indent: usize,
+    path: Vec<String>,
+}
+
+/// Line-level edit of a block-style YAML document.  Returns `None` when the
+/// change cannot be made without re-serialising.
+fn edit_lines(text: &str, segments: &[&str], value: &Value) -> Option<String> {
+    let rendered = serde_yaml::to_string(value).ok()?;
+    let rendered = rendered.trim_end();
+    if rendered.contains('\n') {
+        return None;
+    }
+
+    let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+    let entries = scan_entries(&lines);
+    let target: Vec<String> = segments.iter().map(|s| s.to_string()).collect();
+
+    if let Some(entry) = entries.iter().find(|e| e.path == target) {
+        let line = &lines[entry.line];
+        let (head, rest) = line.split_at(line.find(':')? + 1);
+        let rest = rest.trim_start();
+        if rest.is_empty() || rest.starts_with(['|', '>', '&', '*', '!', '{', '[']) {
+            return None;
+        }
+        let comment = trailing_comment(rest).unwrap_or("");
+        lines[entry.line] = format!("{head} {rendered}{comment}");
+        return Some(join_lines(&lines));
+    }
+
+    // Insert below the deepest ancestor that already exists.
+    let (depth, parent) = (1..segments.len())
+        .rev()
+        .find_map(|d| {
+            entries
+                .iter()
+                .find(|e| e.path == target[..d])
+                .map(|e| (d, Some(e)))
+        })
+        .unwrap_or((0, None));
+    let (at, mut indent, step) = match parent {
+        Some(parent) => {
+            let line = &lines[parent.line];
+            let rest = line[line.find(':')? + 1..].trim_start();
+            if !rest.is_empty() && !rest.starts_with('#') {
+                // A scalar or flow value cannot take children in place.
+                return None;
+            }
+            // Follow the indentation of existing children, if any.
+            let child_indent = entries
+                .iter()
+                .find(|e| {
+                    e.line > parent.line
+                        && e.path.len() == depth + 1
+                        && e.path[..depth] == target[..depth]
+                })
+                .map_or(parent.indent + 2, |e| e.indent);
+            (
+                block_end(&lines, parent),
+                child_indent,
+                (child_indent - parent.indent).max(1),
+            )
+        }
+        None => (lines.len(), 0, 2),
+    };
+    let mut new_lines = Vec::new();
+    for (i, seg) in segments[depth..].iter().enumerate() {
+        let last = depth + i + 1 == segments.len();
+        let pad = " ".repeat(indent);
+        new_lines.push(if last {
+            format!("{pad}{seg}: {rendered}")
+        } else {
+            format!("{pad}{seg}:")
+        });
+        indent += step;
+    }
+    lines.splice(at..at, new_lines);
+    Some(join_lines(&lines))
+}
+
+/// Find every `key:` line and its dotted path.  Sequence items and lines
+/// inside block scalars are skipped.
+fn scan_entries(lines: &[String]) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    let mut stack: Vec<(usize, String)> = Vec::new();
+    let mut block_scalar: Option<usize> = None;
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if let Some(owner) = block_scalar {
+            if indent > owner {
+                continue;
+            }
+            block_scalar = None;
+        }
+        if trimmed.starts_with('-') {
+            continue;
+        }
+        let Some(key) = entry_key(trimmed) else {
+            continue;
+        };
+        while stack.last().is_some_and(|(ind, _)| *ind >= indent) {
+            stack.pop();
+        }
+        stack.push((indent, key.to_string()));
+        entries.push(Entry {
+            line: i,
+            indent,
+            path: stack.iter().map(|(_, k)| k.clone()).collect(),
+        });
+        let rest = trimmed[trimmed.find(':').unwrap_or(0) + 1..].trim_start();
+        if rest.starts_with(['|', '>']) {
+            block_scalar = Some(indent);
+        }
+    }
+    entries
+}
+
+/// The key of a `key: value` / `key:` line, unquoted.
+fn entry_key(trimmed: &str) -> Option<&str> {
+    let colon = trimmed
+        .char_indices()
+        .find(|&(i, c)| c == ':' && trimmed[i + 1..].chars().next().is_none_or(|n| n == ' '))?
+        .0;
+    let key = trimmed[..colon].trim_end();
+    let key = key
+        .strip_prefix('"')
+        .and_then(|k| k.strip_suffix('"'))
+        .or_else(|| key.strip_prefix('\'').and_then(|k| k.strip_suffix('\'')))
+        .unwrap_or(key);
+    (!key.is_empty() && !key.contains(' ')).then_some(key)
+}
+
+/// The ` # comment` at the end of a scalar value, if any.
+fn trailing_comment(rest: &str) -> Option<&str> {
+    let search_from = match rest.chars().next() {
+        Some(q @ ('"' | '\'')) => rest[1..].find(q).map(|i| i + 2)?,
+        _ => 0,
+    };
+    rest[search_from..]
+        .find(" #")
+        .map(|i| &rest[search_from + i..])
+}
+
+/// Index of the line after the last line belonging to `entry`'s block.
+fn block_end(lines: &[String], entry: &Entry) -> usize {
+    let mut end = entry.line + 1;
+    for (i, line) in lines.iter().enumerate().skip(entry.line + 1) {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if line.len() - trimmed.len() <= entry.indent {
+            break;
+        }
+        end = i + 1;
+    }
+    end
+}
+
+fn join_lines(lines: &[String]) -> String {
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+# Global sven config
+model:
+  provider: openai   # main provider
+  name: gpt-4o
+  temperature: 0.7
+
+agent:
+  max_tool_rounds: 20
+";
+
+    fn file_with(text: &str) -> (tempfile::TempDir, std::path::PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        std::fs::write(&path, text).unwrap();
+        (dir, path)
+    }
+
+    fn file_get(path: &Path, key: &str) -> Option<Value> {
+        let text = std::fs::read_to_string(path).unwrap();
+        let mut merged = serde_yaml::to_value(Config::default()).unwrap();
+        merge_yaml(&mut merged, serde_yaml::from_str(&text).unwrap());
+        let config: Config = serde_yaml::from_value(merged).unwrap();
+        get_key(&config, key).unwrap()
+    }
+
+    #[test]
+    fn set_then_get_round_trips_nested_keys() {
+        let (_dir, path) = file_with(SAMPLE);
+
+        assert_eq!(
+            set_key(&path, "model.temperature", "0.2").unwrap(),
+            Value::from(0.2)
+        );
+        assert_eq!(
+            file_get(&path, "model.temperature"),
+            Some(Value::from(0.2f32))
+        );
+
+        set_key(&path, "model.base_url", "http://localhost:4000").unwrap();
+        assert_eq!(
+            file_get(&path, "model.base_url"),
+            Some(Value::from("http://localhost:4000"))
+        );
+
+        set_key(&path, "tools.web.fetch_max_chars", "5000").unwrap();
+        assert_eq!(
+            file_get(&path, "tools.web.fetch_max_chars"),
+            Some(Value::from(5000))
+        );
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with("# Global sven config\n"), "{text}");
+        assert!(
+            text.contains("  provider: openai   # main provider\n"),
+            "{text}"
+        );
+        assert!(text.contains("  temperature: 0.2\n"), "{text}");
+        assert!(
+            text.contains(
+                "  name: gpt-4o\n  temperature: 0.2\n  base_url: http://localhost:4000\n"
+            ),
+            "new key is inserted inside its section:\n{text}"
+        );
+    }
+
+    #[test]
+    fn values_are_parsed_by_type() {
+        let (_dir, path) = file_with(SAMPLE);
+        assert_eq!(
+            set_key(&path, "tui.ascii_borders", "true").unwrap(),
+            Value::Bool(true)
+        );
+        // `name` is a string field, so a numeric-looking value stays text.
+        assert_eq!(set_key(&path, "model.name", "4").unwrap(), Value::from("4"));
+        assert_eq!(file_get(&path, "model.name"), Some(Value::from("4")));
+
+        let err = set_key(&path, "agent.max_tool_rounds", "many").unwrap_err();
+        assert!(
+            format!("{err:#}").contains("agent.max_tool_rounds"),
+            "{err:#}"
+        );
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("max_tool_rounds: 20"));
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let (_dir, path) = file_with(SAMPLE);
+        let err = set_key(&path, "model.temprature", "0.2").unwrap_err();
+        assert_eq!(err.to_string(), "unknown config key `model.temprature`");
+        assert!(get_key(&Config::default(), "modle").is_err());
+        assert!(set_key(&path, "model..name", "x").is_err());
+    }
+
+    #[test]
+    fn set_creates_a_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sven/config.yaml");
+        set_key(&path, "model.temperature", "1").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "model:\n  temperature: 1\n"
+        );
+    }
+
+    #[test]
+    fn unset_known_key_reads_as_none() {
+        assert_eq!(get_key(&Config::default(), "model.base_url").unwrap(), None);
+        assert!(get_key(&Config::default(), "model.provider")
+            .unwrap()
+            .is_some());
+    }
+}
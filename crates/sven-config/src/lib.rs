@@ -1,8 +1,10 @@
 // Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
 //
 // SPDX-License-Identifier: Apache-2.0
+mod edit;
 mod loader;
 mod schema;
 
-pub use loader::load;
+pub use edit::{get_key, set_key};
+pub use loader::{edit_target, load};
 pub use schema::*;
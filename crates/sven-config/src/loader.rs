@@ -9,9 +9,30 @@ use tracing::{debug, warn};
 
 use crate::Config;
 
+/// File names of a project-local config, relative to a project directory,
+/// from lowest to highest priority.
+const PROJECT_CONFIG_FILES: &[&str] = &[
+    ".sven/config.yaml",
+    ".sven/config.yml",
+    ".sven.yaml",
+    ".sven.yml",
+    "sven.yaml",
+    "sven.yml",
+];
+
 /// Ordered list of config file locations searched from lowest to highest priority.
-/// Later files override earlier ones.
+/// Later files override earlier ones: the global layers first, then the
+/// project layers.
 fn config_search_paths() -> Vec<PathBuf> {
+    let mut paths = global_config_paths();
+    if let Ok(cwd) = std::env::current_dir() {
+        paths.extend(project_config_paths(&cwd));
+    }
+    paths
+}
+
+/// System-wide and per-user config locations, lowest priority first.
+fn global_config_paths() -> Vec<PathBuf> {
     let mut paths = Vec::new();
 
     // 1. System-wide default.  /etc/ is a Linux convention; macOS and Windows
@@ -35,41 +56,87 @@ fn config_search_paths() -> Vec<PathBuf> {
         paths.push(cfg.join("sven/config.yml"));
     }
 
-    // 3. Workspace-local
-    paths.push(PathBuf::from(".sven/config.yaml"));
-    paths.push(PathBuf::from(".sven/config.yml"));
-    paths.push(PathBuf::from(".sven.yaml"));
-    paths.push(PathBuf::from(".sven.yml"));
-    paths.push(PathBuf::from("sven.yaml"));
-    paths.push(PathBuf::from("sven.yml"));
-
     paths
 }
 
+/// Project-local config locations for a session started in `cwd`, lowest
+/// priority first.
+///
+/// The project root is the nearest ancestor of `cwd` that contains `.git`
+/// (the same rule as `sven_runtime::find_project_root`), so running sven from
+/// a subdirectory still picks up the project's `.sven/config.yaml`.  When
+/// `cwd` is below the project root, config files in `cwd` itself are layered
+/// on top.
+fn project_config_paths(cwd: &Path) -> Vec<PathBuf> {
+    let root = cwd
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .unwrap_or(cwd);
+    let mut dirs = vec![root];
+    if root != cwd {
+        dirs.push(cwd);
+    }
+    dirs.into_iter()
+        .flat_map(|dir| PROJECT_CONFIG_FILES.iter().map(move |f| dir.join(f)))
+        .collect()
+}
+
+/// The config file `sven config set` edits when no `--config` path is given.
+///
+/// This is the highest-priority config file that already exists, skipping
+/// the system-wide ones under `/etc`; with `global` only the per-user files
+/// are considered.  When none exists, the per-user
+/// `<config dir>/sven/config.yaml` is returned so that it gets created.
+pub fn edit_target(global: bool) -> PathBuf {
+    let candidates = if global {
+        global_config_paths()
+    } else {
+        config_search_paths()
+    };
+    candidates
+        .into_iter()
+        .rev()
+        .find(|p| p.is_file() && !p.starts_with("/etc"))
+        .unwrap_or_else(|| {
+            dirs::config_dir()
+                .unwrap_or_else(|| PathBuf::from("~/.config"))
+                .join("sven/config.yaml")
+        })
+}
+
+/// Read, env-expand and parse one config file.
+fn read_layer(path: &Path) -> anyhow::Result<serde_yaml::Value> {
+    let raw =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let text = expand_env_vars(&raw, &path.display().to_string());
+    serde_yaml::from_str(&text).with_context(|| format!("parsing {}", path.display()))
+}
+
 /// Load configuration by merging all discovered YAML files.
 /// The `extra` argument may provide an explicit path (e.g. `--config` CLI flag).
+///
+/// Layers are deep-merged in order — global config, then the project's
+/// config, then `extra` — so a later layer overrides individual fields and
+/// map entries (e.g. one named provider) without replacing whole sections.
 pub fn load(extra: Option<&Path>) -> anyhow::Result<Config> {
+    load_layers(&config_search_paths(), extra)
+}
+
+/// [`load`] with an explicit list of candidate layer files.  Paths that do
+/// not exist are skipped.
+fn load_layers(paths: &[PathBuf], extra: Option<&Path>) -> anyhow::Result<Config> {
     let mut merged = serde_yaml::Value::Mapping(serde_yaml::Mapping::new());
 
-    for path in config_search_paths() {
+    for path in paths {
         if path.is_file() {
             debug!(path = %path.display(), "loading config layer");
-            let raw = std::fs::read_to_string(&path)
-                .with_context(|| format!("reading {}", path.display()))?;
-            let text = expand_env_vars(&raw, &path.display().to_string());
-            let layer: serde_yaml::Value = serde_yaml::from_str(&text)
-                .with_context(|| format!("parsing {}", path.display()))?;
-            merge_yaml(&mut merged, layer);
+            merge_yaml(&mut merged, read_layer(path)?);
         }
     }
 
     if let Some(p) = extra {
         debug!(path = %p.display(), "loading explicit config");
-        let raw = std::fs::read_to_string(p).with_context(|| format!("reading {}", p.display()))?;
-        let text = expand_env_vars(&raw, &p.display().to_string());
-        let layer: serde_yaml::Value =
-            serde_yaml::from_str(&text).with_context(|| format!("parsing {}", p.display()))?;
-        merge_yaml(&mut merged, layer);
+        merge_yaml(&mut merged, read_layer(p)?);
     }
 
     // Track whether the merged config contains an explicit model section before
@@ -115,6 +182,7 @@ pub fn load(extra: Option<&Path>) -> anyhow::Result<Config> {
     // provider-first config structure work.
     resolve_named_model_provider(&mut config);
 
+    check_custom_modes(&mut config.agent);
     validate_token_limits(&config.model, "model");
     for (alias, entry) in &config.providers {
         for (model_name, params) in &entry.models {
@@ -131,6 +199,65 @@ pub fn load(extra: Option<&Path>) -> anyhow::Result<Config> {
     Ok(config)
 }
 
+/// Check the `agent.modes` entries and every reference to a mode against
+/// them.  Entries that cannot be used are dropped and an undefined
+/// `default_mode` falls back to the read-only research mode, each with a
+/// warning.
+fn check_custom_modes(agent: &mut crate::AgentConfig) {
+    use crate::AgentMode;
+
+    agent
+        .modes
+        .retain(|name, mode| usable_custom_mode(name, mode));
+
+    if !agent.defines(agent.default_mode) {
+        warn!(
+            "agent.default_mode: '{}' is not defined in agent.modes; using research",
+            agent.default_mode
+        );
+        agent.default_mode = AgentMode::Research;
+    }
+    for mode in agent.routing.keys() {
+        if !agent.defines(*mode) {
+            warn!("agent.routing.{mode}: '{mode}' is not defined in agent.modes; ignored");
+        }
+    }
+    let transitions = agent.mode_transitions.0.iter();
+    for mode in transitions.flat_map(|(from, to)| std::iter::once(from).chain(to)) {
+        if !agent.defines(*mode) {
+            warn!("agent.mode_transitions: '{mode}' is not defined in agent.modes");
+        }
+    }
+}
+
+/// Whether the `agent.modes` entry `name` can be used, warning when not (or
+/// when its `base` is unusable).
+fn usable_custom_mode(name: &str, mode: &crate::CustomModeConfig) -> bool {
+    match crate::AgentMode::parse(name) {
+        Some(crate::AgentMode::Custom(_)) => {
+            if mode.base.is_custom() {
+                warn!(
+                    "agent.modes.{name}.base: '{}' is not a built-in mode; \
+                     research tools are used",
+                    mode.base
+                );
+            }
+            true
+        }
+        Some(_) => {
+            warn!("agent.modes.{name}: cannot redefine the built-in {name} mode; ignored");
+            false
+        }
+        None => {
+            warn!(
+                "agent.modes: mode name '{name}' must be 1 to {} bytes; ignored",
+                crate::ModeName::MAX_LEN
+            );
+            false
+        }
+    }
+}
+
 /// Warn when `max_tokens` (total context) is less than the sum of the
 /// optional `max_input_tokens` and `max_output_tokens` limits.
 ///
@@ -252,7 +379,15 @@ fn expand_env_vars(text: &str, source_desc: &str) -> String {
 // ── Unknown-field validation ──────────────────────────────────────────────────
 
 /// Known top-level keys in [`Config`].
-const CONFIG_KEYS: &[&str] = &["model", "agent", "tools", "tui", "providers", "mcp_servers"];
+const CONFIG_KEYS: &[&str] = &[
+    "model",
+    "agent",
+    "tools",
+    "tui",
+    "history",
+    "providers",
+    "mcp_servers",
+];
 
 /// Known keys in [`crate::ModelConfig`].
 const MODEL_CONFIG_KEYS: &[&str] = &[
@@ -269,6 +404,10 @@ const MODEL_CONFIG_KEYS: &[&str] = &[
     "azure_deployment",
     "azure_api_version",
     "aws_region",
+    "reasoning_effort",
+    "thinking_budget_tokens",
+    "response_format",
+    "stop",
     "cache_system_prompt",
     "extended_cache_time",
     "cache_tools",
@@ -326,6 +465,20 @@ const AGENT_CONFIG_KEYS: &[&str] = &[
     "system_prompt",
     "max_step_timeout_secs",
     "max_run_timeout_secs",
+    "git_recent_commits",
+    "mode_transitions",
+    "fallback_models",
+    "routing",
+    "modes",
+];
+
+/// Known keys in [`crate::CustomModeConfig`].
+const CUSTOM_MODE_KEYS: &[&str] = &[
+    "description",
+    "instructions",
+    "base",
+    "tool_profile",
+    "model",
 ];
 
 /// Known keys in [`crate::ToolsConfig`].
@@ -333,26 +486,58 @@ const TOOLS_CONFIG_KEYS: &[&str] = &[
     "auto_approve_patterns",
     "deny_patterns",
     "timeout_secs",
+    "profile",
+    "allow",
+    "deny",
+    "timeouts",
+    "secrets",
+    "inherit_env",
+    "env_allowlist",
+    "sandbox",
+    "sandbox_root",
     "use_docker",
     "docker_image",
     "web",
     "memory",
+    "todo",
     "lints",
     "gdb",
 ];
 
 /// Known keys in [`crate::TuiConfig`].
-const TUI_CONFIG_KEYS: &[&str] = &["theme", "code_line_numbers", "wrap_width", "ascii_borders"];
+const TUI_CONFIG_KEYS: &[&str] = &[
+    "theme",
+    "code_line_numbers",
+    "wrap_width",
+    "ascii_borders",
+    "autosave_debounce_ms",
+    "notify_on_complete",
+    "notify_style",
+    "keymap",
+    "vi_mode",
+];
+
+/// Known keys in the `tui.keymap:` section.
+const KEYMAP_CONFIG_KEYS: &[&str] = &["global", "input", "chat"];
 
 /// Known keys in [`crate::WebConfig`].
 const WEB_CONFIG_KEYS: &[&str] = &["search", "fetch_max_chars"];
 
+/// Known keys in [`crate::HistoryConfig`].
+const HISTORY_CONFIG_KEYS: &[&str] = &["dir", "format"];
+
 /// Known keys in [`crate::WebSearchConfig`].
 const WEB_SEARCH_CONFIG_KEYS: &[&str] = &["api_key"];
 
 /// Known keys in [`crate::MemoryConfig`].
 const MEMORY_CONFIG_KEYS: &[&str] = &["memory_file"];
 
+/// Known keys in [`crate::SecretsConfig`].
+const SECRETS_CONFIG_KEYS: &[&str] = &["mask", "patterns", "env_suffixes"];
+
+/// Known keys in [`crate::TodoConfig`].
+const TODO_CONFIG_KEYS: &[&str] = &["persist_file"];
+
 /// Known keys in [`crate::LintsConfig`].
 const LINTS_CONFIG_KEYS: &[&str] = &["rust_command", "typescript_command", "python_command"];
 
@@ -379,12 +564,26 @@ const MCP_OAUTH_CONFIG_KEYS: &[&str] = &[
     "callback_port",
 ];
 
-/// Recursively walk `value` and emit a `warn!` for any mapping key that is
-/// not listed in the expected set for that schema level.
-///
-/// `path` is the dot-separated JSON path used in the warning message
-/// (e.g. `"model"`, `"providers.my_ollama"`).
+/// Emit a `warn!` for every mapping key in `value` that is not part of the
+/// config schema.
 fn validate_unknown_fields(value: &serde_yaml::Value, path: &str) {
+    for field in unknown_fields(value, path) {
+        warn!("Unrecognised config field `{field}` — check spelling or update sven");
+    }
+}
+
+/// Dotted paths of the mapping keys in `value` that are not listed in the
+/// expected set for their schema level.
+///
+/// `path` is the dot-separated path of `value` itself (e.g. `"model"`,
+/// `"providers.my_ollama"`; empty for the document root).
+pub(crate) fn unknown_fields(value: &serde_yaml::Value, path: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    collect_unknown_fields(value, path, &mut out);
+    out
+}
+
+fn collect_unknown_fields(value: &serde_yaml::Value, path: &str, out: &mut Vec<String>) {
     let serde_yaml::Value::Mapping(map) = value else {
         return;
     };
@@ -395,6 +594,8 @@ fn validate_unknown_fields(value: &serde_yaml::Value, path: &str) {
         (MODEL_CONFIG_KEYS, "model")
     } else if path == "agent" {
         (AGENT_CONFIG_KEYS, "agent")
+    } else if path.starts_with("agent.modes.") {
+        (CUSTOM_MODE_KEYS, "custom mode")
     } else if path == "tools" {
         (TOOLS_CONFIG_KEYS, "tools")
     } else if path == "tools.web" {
@@ -403,12 +604,20 @@ fn validate_unknown_fields(value: &serde_yaml::Value, path: &str) {
         (WEB_SEARCH_CONFIG_KEYS, "tools.web.search")
     } else if path == "tools.memory" {
         (MEMORY_CONFIG_KEYS, "tools.memory")
+    } else if path == "tools.secrets" {
+        (SECRETS_CONFIG_KEYS, "tools.secrets")
+    } else if path == "tools.todo" {
+        (TODO_CONFIG_KEYS, "tools.todo")
     } else if path == "tools.lints" {
         (LINTS_CONFIG_KEYS, "tools.lints")
     } else if path == "tools.gdb" {
         (GDB_CONFIG_KEYS, "tools.gdb")
     } else if path == "tui" {
         (TUI_CONFIG_KEYS, "tui")
+    } else if path == "tui.keymap" {
+        (KEYMAP_CONFIG_KEYS, "tui.keymap")
+    } else if path == "history" {
+        (HISTORY_CONFIG_KEYS, "history")
     } else if path == "providers" {
         // The providers map has arbitrary provider names as keys — all are valid.
         // We descend into each named entry to validate its fields.
@@ -418,7 +627,7 @@ fn validate_unknown_fields(value: &serde_yaml::Value, path: &str) {
                 _ => continue,
             };
             let child_path = format!("providers.{key_str}");
-            validate_unknown_fields(val, &child_path);
+            collect_unknown_fields(val, &child_path, out);
         }
         return;
     } else if path == "mcp_servers" {
@@ -429,7 +638,7 @@ fn validate_unknown_fields(value: &serde_yaml::Value, path: &str) {
                 _ => continue,
             };
             let child_path = format!("mcp_servers.{key_str}");
-            validate_unknown_fields(val, &child_path);
+            collect_unknown_fields(val, &child_path, out);
         }
         return;
     } else if let Some(rest) = path.strip_prefix("providers.") {
@@ -460,10 +669,11 @@ fn validate_unknown_fields(value: &serde_yaml::Value, path: &str) {
             _ => continue,
         };
         if !known.contains(&key_str) {
-            warn!(
-                "Unrecognised config field `{}.{}` — check spelling or update sven",
-                path, key_str
-            );
+            out.push(if path.is_empty() {
+                key_str.to_string()
+            } else {
+                format!("{path}.{key_str}")
+            });
         } else {
             // Recurse into known nested sections.
             let child_path = if path.is_empty() {
@@ -476,12 +686,28 @@ fn validate_unknown_fields(value: &serde_yaml::Value, path: &str) {
                 | ("config", "agent")
                 | ("config", "tools")
                 | ("config", "tui")
+                | ("config", "history")
                 | ("config", "providers")
-                | ("config", "mcp_servers") => validate_unknown_fields(val, &child_path),
-                ("tools", "web") | ("tools", "memory") | ("tools", "lints") | ("tools", "gdb") => {
-                    validate_unknown_fields(val, &child_path)
+                | ("config", "mcp_servers") => collect_unknown_fields(val, &child_path, out),
+                ("tools", "web")
+                | ("tools", "memory")
+                | ("tools", "secrets")
+                | ("tools", "lints")
+                | ("tools", "gdb") => collect_unknown_fields(val, &child_path, out),
+                ("tools.web", "search") => collect_unknown_fields(val, &child_path, out),
+                ("tui", "keymap") => collect_unknown_fields(val, &child_path, out),
+                ("agent", "modes") => {
+                    // Each key is a mode name; validate its definition.
+                    if let serde_yaml::Value::Mapping(modes_map) = val {
+                        for (mode_key, mode_val) in modes_map {
+                            let Some(mode_name) = mode_key.as_str() else {
+                                continue;
+                            };
+                            let mode_path = format!("{child_path}.{mode_name}");
+                            collect_unknown_fields(mode_val, &mode_path, out);
+                        }
+                    }
                 }
-                ("tools.web", "search") => validate_unknown_fields(val, &child_path),
                 ("provider entry", "models") => {
                     // Each key is a model name; validate its params.
                     if let serde_yaml::Value::Mapping(models_map) = val {
@@ -491,12 +717,12 @@ fn validate_unknown_fields(value: &serde_yaml::Value, path: &str) {
                                 _ => continue,
                             };
                             let model_path = format!("{child_path}.{model_name}");
-                            validate_unknown_fields(model_val, &model_path);
+                            collect_unknown_fields(model_val, &model_path, out);
                         }
                     }
                 }
                 ("mcp server", "transport") | ("mcp server", "oauth") => {
-                    validate_unknown_fields(val, &child_path)
+                    collect_unknown_fields(val, &child_path, out)
                 }
                 _ => {}
             }
@@ -505,7 +731,7 @@ fn validate_unknown_fields(value: &serde_yaml::Value, path: &str) {
 }
 
 /// Deep-merge `src` into `dst`; src wins on scalar conflicts.
-fn merge_yaml(dst: &mut serde_yaml::Value, src: serde_yaml::Value) {
+pub(crate) fn merge_yaml(dst: &mut serde_yaml::Value, src: serde_yaml::Value) {
     match (dst, src) {
         (serde_yaml::Value::Mapping(d), serde_yaml::Value::Mapping(s)) => {
             for (k, v) in s {
@@ -555,6 +781,62 @@ mod tests {
         assert_eq!(dst["model"]["name"].as_str(), Some("gpt-4o-mini"));
     }
 
+    #[test]
+    fn project_config_paths_start_at_git_root() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        let sub = dir.path().join("src/deep");
+        std::fs::create_dir_all(&sub).unwrap();
+
+        let paths = project_config_paths(&sub);
+        assert_eq!(paths[0], dir.path().join(".sven/config.yaml"));
+        // The working directory's own files come last and win.
+        assert_eq!(paths.last(), Some(&sub.join("sven.yml")));
+        assert_eq!(paths.len(), 2 * PROJECT_CONFIG_FILES.len());
+
+        assert_eq!(
+            project_config_paths(dir.path()).len(),
+            PROJECT_CONFIG_FILES.len()
+        );
+    }
+
+    #[test]
+    fn project_layer_overrides_model_and_keeps_global_providers() {
+        let global_dir = tempfile::tempdir().unwrap();
+        let project_dir = tempfile::tempdir().unwrap();
+        let global = global_dir.path().join("config.yaml");
+        std::fs::write(
+            &global,
+            "model:\n  provider: openai\n  name: gpt-4o\n\
+             providers:\n  \
+               local:\n    name: ollama\n    base_url: http://localhost:11434/v1\n  \
+               proxy:\n    name: litellm\n    base_url: http://proxy:4000\n",
+        )
+        .unwrap();
+        std::fs::create_dir(project_dir.path().join(".sven")).unwrap();
+        std::fs::write(
+            project_dir.path().join(".sven/config.yaml"),
+            "model:\n  provider: anthropic\n  name: claude-sonnet-4-6\n\
+             providers:\n  \
+               proxy:\n    base_url: http://project-proxy:4000\n",
+        )
+        .unwrap();
+
+        let mut paths = vec![global];
+        paths.extend(project_config_paths(project_dir.path()));
+        let cfg = load_layers(&paths, None).unwrap();
+
+        assert_eq!(cfg.model.provider, "anthropic");
+        assert_eq!(cfg.model.name, "claude-sonnet-4-6");
+        let local = &cfg.providers["local"];
+        assert_eq!(local.name, "ollama");
+        assert_eq!(local.base_url.as_deref(), Some("http://localhost:11434/v1"));
+        // A project entry merges into the global entry of the same name.
+        let proxy = &cfg.providers["proxy"];
+        assert_eq!(proxy.name, "litellm");
+        assert_eq!(proxy.base_url.as_deref(), Some("http://project-proxy:4000"));
+    }
+
     #[test]
     fn load_returns_error_when_explicit_path_missing() {
         let result = load(Some(Path::new("/tmp/sven_nonexistent_config_xyz.yaml")));
@@ -571,6 +853,42 @@ mod tests {
         assert!(!cfg.model.name.is_empty());
     }
 
+    #[test]
+    fn load_resolves_custom_modes_against_the_config() {
+        use crate::{AgentMode, ToolPreset};
+        use std::io::Write;
+        let mut f = tempfile::NamedTempFile::new().unwrap();
+        writeln!(
+            f,
+            "agent:\n  default_mode: loader-reviewer\n  routing:\n    loader-reviewer: openai/gpt-4o\n  \
+             modes:\n    loader-reviewer:\n      instructions: Review only.\n      \
+             tool_profile: review\n      base: research"
+        )
+        .unwrap();
+        let cfg = load(Some(f.path())).unwrap();
+        let reviewer = cfg.agent.mode("loader-reviewer").unwrap();
+        assert_eq!(cfg.agent.default_mode, reviewer);
+        assert_eq!(cfg.agent.routing[&reviewer], "openai/gpt-4o");
+        let def = cfg.agent.custom_mode(reviewer).unwrap();
+        assert_eq!(def.instructions, "Review only.");
+        assert_eq!(def.tool_profile, Some(ToolPreset::Review));
+        assert_eq!(cfg.agent.tool_mode(reviewer), AgentMode::Research);
+        assert!(cfg.agent.all_modes().ends_with(&[reviewer]));
+    }
+
+    #[test]
+    fn load_fails_closed_for_undefined_custom_modes() {
+        use crate::AgentMode;
+        use std::io::Write;
+        let mut f = tempfile::NamedTempFile::new().unwrap();
+        writeln!(f, "agent:\n  default_mode: removed-mode").unwrap();
+        let cfg = load(Some(f.path())).unwrap();
+        assert_eq!(cfg.agent.default_mode, AgentMode::Research);
+        assert_eq!(cfg.agent.mode("removed-mode"), None);
+        let removed = AgentMode::parse("removed-mode").unwrap();
+        assert_eq!(cfg.agent.tool_mode(removed), AgentMode::Research);
+    }
+
     #[test]
     fn load_explicit_file_overrides_defaults() {
         use std::io::Write;
@@ -581,6 +899,18 @@ mod tests {
         assert_eq!(cfg.model.name, "test-model");
     }
 
+    #[test]
+    fn load_reads_per_tool_timeouts() {
+        use std::io::Write;
+        let mut f = tempfile::NamedTempFile::new().unwrap();
+        writeln!(f, "tools:\n  timeouts:\n    web_fetch: 60\n    shell: 0").unwrap();
+        let cfg = load(Some(f.path())).unwrap();
+        assert_eq!(cfg.tools.timeouts.get("web_fetch"), Some(60));
+        assert_eq!(cfg.tools.timeouts.get("shell"), None, "0 means unset");
+        assert_eq!(cfg.tools.timeouts.get_or("gdb", 10), 10);
+        assert_eq!(cfg.tools.timeout_secs, 30, "other tools keys keep defaults");
+    }
+
     #[test]
     fn load_resolves_named_provider_to_model_config() {
         use std::io::Write;
@@ -695,6 +1025,12 @@ model:
         validate_unknown_fields(&yaml, "");
     }
 
+    #[test]
+    fn unknown_fields_checks_tools_secrets() {
+        let yaml = val("tools:\n  secrets:\n    mask: true\n    patterns: []\n    masks: true\n");
+        assert_eq!(unknown_fields(&yaml, ""), ["tools.secrets.masks"]);
+    }
+
     // ── Adversarial config inputs ─────────────────────────────────────────────
 
     #[test]
@@ -1,7 +1,7 @@
 // Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
 //
 // SPDX-License-Identifier: Apache-2.0
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use serde::{Deserialize, Serialize};
 
@@ -166,6 +166,8 @@ pub struct Config {
     pub tools: ToolsConfig,
     #[serde(default)]
     pub tui: TuiConfig,
+    #[serde(default)]
+    pub history: HistoryConfig,
     /// Named provider configurations.
     ///
     /// Each entry defines a provider endpoint (a server or service) with its
@@ -471,6 +473,11 @@ pub struct ModelConfig {
     pub max_input_tokens: Option<u32>,
     /// Sampling temperature (0.0–2.0)
     pub temperature: Option<f32>,
+    /// Stop sequences: generation ends before any of them.  Sent as `stop`
+    /// (OpenAI-compatible), `stop_sequences` (Anthropic, Cohere) or
+    /// `stopSequences` (Gemini, Bedrock).  OpenAI and Azure accept at most 4,
+    /// Gemini and Cohere at most 5.
+    pub stop: Option<Vec<String>>,
 
     // ── Azure OpenAI ─────────────────────────────────────────────────────────
     /// Azure resource name (the subdomain of `.openai.azure.com`).
@@ -485,6 +492,30 @@ pub struct ModelConfig {
     /// AWS region override (also honoured via AWS_DEFAULT_REGION env var).
     pub aws_region: Option<String>,
 
+    // ── Reasoning ─────────────────────────────────────────────────────────────
+    /// Reasoning effort for reasoning models, e.g. `"low"`, `"medium"` or
+    /// `"high"`.  Sent as `reasoning_effort` to OpenAI and Azure and as
+    /// `reasoning.effort` to OpenRouter; ignored by other providers.
+    pub reasoning_effort: Option<String>,
+    /// Token budget for Anthropic extended thinking (at least 1024 and below
+    /// the output-token limit).  `None` leaves thinking off; ignored by other
+    /// providers.
+    pub thinking_budget_tokens: Option<u32>,
+
+    // ── Structured output ─────────────────────────────────────────────────────
+    /// JSON Schema that replies must follow.  OpenAI-compatible providers
+    /// receive it as a `json_schema` `response_format`, Anthropic as
+    /// `output_format`; other providers ignore it.  `sven` in CI mode fails
+    /// with a validation error when the final reply does not conform.
+    pub response_format: Option<serde_json::Value>,
+
+    // ── Streaming ─────────────────────────────────────────────────────────────
+    /// Seconds a streaming response may go without receiving any bytes
+    /// before it is aborted (OpenAI-compatible drivers).  SSE keep-alive
+    /// comments count as activity.  `None` uses the 60 s default; `0`
+    /// disables the timeout.
+    pub stream_idle_timeout_secs: Option<u64>,
+
     // ── Prompt caching ────────────────────────────────────────────────────────
     /// Attach an explicit cache-control marker to the system message.
     ///
@@ -599,6 +630,11 @@ impl Default for ModelConfig {
             azure_deployment: None,
             azure_api_version: None,
             aws_region: None,
+            reasoning_effort: None,
+            thinking_budget_tokens: None,
+            response_format: None,
+            stop: None,
+            stream_idle_timeout_secs: None,
             // Comprehensive caching is on by default for every provider that
             // supports it (currently Anthropic).  The flags are no-ops for
             // providers such as OpenAI that cache automatically.  Only the
@@ -711,6 +747,168 @@ pub struct AgentConfig {
     /// Total run wall-clock timeout in seconds (0 = no limit).
     #[serde(default)]
     pub max_run_timeout_secs: u64,
+
+    /// Subjects of this many recent commits are listed in the git context of
+    /// the system prompt (0 = none, at most 20).  Useful for review work.
+    #[serde(default)]
+    pub git_recent_commits: usize,
+
+    /// Modes the agent may switch to with `system switch_mode`, keyed by the
+    /// current mode.  A mode without an entry may switch anywhere, so the
+    /// default (empty) map is fully permissive.
+    ///
+    /// ```yaml
+    /// agent:
+    ///   mode_transitions:
+    ///     research: [plan]   # research may not escalate to agent
+    ///     plan: [research]
+    /// ```
+    #[serde(default, skip_serializing_if = "ModeTransitions::is_empty")]
+    pub mode_transitions: ModeTransitions,
+
+    /// Models tried in order when a completion fails with a retryable error
+    /// (rate limit, overload, server or connection error).  Each entry is
+    /// resolved like `--model`: `provider/name`, a bare model name or a
+    /// `providers` alias.  The fallback only serves the failing completion;
+    /// the next one goes to the configured model again.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fallback_models: Vec<String>,
+
+    /// Model to use in each mode unless one was chosen explicitly (`--model`,
+    /// `/model`, a workflow or step override).  Values are resolved like
+    /// `--model`.  Modes without an entry keep the current model.
+    ///
+    /// ```yaml
+    /// agent:
+    ///   routing:
+    ///     research: openai/gpt-4o-mini
+    ///     agent: anthropic/claude-sonnet-4-5
+    /// ```
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub routing: HashMap<AgentMode, String>,
+
+    /// User-defined modes, keyed by name (see [`CustomModeConfig`]).  They
+    /// are selected like the built-in ones: `default_mode`, `/mode`, the TUI
+    /// mode cycle, `switch_mode` or a workflow step's `mode=`.
+    ///
+    /// ```yaml
+    /// agent:
+    ///   modes:
+    ///     reviewer:
+    ///       description: Reviews the working tree without editing it
+    ///       instructions: |
+    ///         You are a code reviewer.  Point out bugs, missing tests and
+    ///         style problems in the changes; do not fix them yourself.
+    ///       tool_profile: review
+    ///       model: anthropic/claude-opus-4-5
+    /// ```
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub modes: BTreeMap<String, CustomModeConfig>,
+}
+
+impl AgentConfig {
+    /// The definition of `mode` when it is a custom mode.
+    pub fn custom_mode(&self, mode: AgentMode) -> Option<&CustomModeConfig> {
+        match mode {
+            AgentMode::Custom(name) => self.modes.get(name.as_str()),
+            _ => None,
+        }
+    }
+
+    /// The built-in mode whose tools are offered in `mode`.  A custom mode
+    /// that is not defined here (or whose `base` is not a built-in mode) gets
+    /// the read-only research tools rather than the full agent set.
+    pub fn tool_mode(&self, mode: AgentMode) -> AgentMode {
+        match mode {
+            AgentMode::Custom(_) => self
+                .custom_mode(mode)
+                .map(|m| m.base)
+                .filter(|base| !base.is_custom())
+                .unwrap_or(AgentMode::Research),
+            builtin => builtin,
+        }
+    }
+
+    /// The built-in mode called `name`, or the custom mode defined here
+    /// under that name.
+    pub fn mode(&self, name: &str) -> Option<AgentMode> {
+        AgentMode::parse(name).filter(|&mode| self.defines(mode))
+    }
+
+    /// Whether `mode` is built in or defined under `modes`.
+    pub fn defines(&self, mode: AgentMode) -> bool {
+        !mode.is_custom() || self.custom_mode(mode).is_some()
+    }
+
+    /// Every selectable mode: the built-in ones, then the custom ones by name.
+    pub fn all_modes(&self) -> Vec<AgentMode> {
+        AgentMode::BUILTIN
+            .into_iter()
+            .chain(
+                self.modes
+                    .keys()
+                    .filter_map(|name| AgentMode::parse(name))
+                    .filter(|mode| mode.is_custom()),
+            )
+            .collect()
+    }
+}
+
+/// A user-defined mode under `agent.modes`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomModeConfig {
+    /// One-line summary shown in `/mode` completion.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Replaces the built-in mode instructions in the system prompt.
+    #[serde(default)]
+    pub instructions: String,
+    /// Built-in mode whose tools are offered (default `agent`).
+    #[serde(default = "default_custom_mode_base")]
+    pub base: AgentMode,
+    /// Narrow the tools of `base` further to a preset.
+    #[serde(default)]
+    pub tool_profile: Option<ToolPreset>,
+    /// Model to switch to when the mode is entered, resolved like `--model`.
+    /// Ignored when a model was chosen explicitly, as with `routing`.
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+fn default_custom_mode_base() -> AgentMode {
+    AgentMode::Agent
+}
+
+/// Allowed mode switches, keyed by the current mode (see
+/// [`AgentConfig::mode_transitions`]).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ModeTransitions(pub HashMap<AgentMode, Vec<AgentMode>>);
+
+impl ModeTransitions {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Whether switching from `from` to `to` is allowed.  Staying in the same
+    /// mode always is.
+    pub fn allows(&self, from: AgentMode, to: AgentMode) -> bool {
+        from == to
+            || self
+                .0
+                .get(&from)
+                .is_none_or(|allowed| allowed.contains(&to))
+    }
+
+    /// The modes in `modes` reachable from `from`, in the order given
+    /// (usually [`AgentConfig::all_modes`]).
+    pub fn targets(&self, from: AgentMode, modes: &[AgentMode]) -> Vec<AgentMode> {
+        modes
+            .iter()
+            .copied()
+            .filter(|&to| to != from && self.allows(from, to))
+            .collect()
+    }
 }
 
 fn default_compaction_keep_recent() -> usize {
@@ -736,12 +934,16 @@ impl Default for AgentConfig {
             system_prompt: None,
             max_step_timeout_secs: 0,
             max_run_timeout_secs: 0,
+            git_recent_commits: 0,
+            mode_transitions: ModeTransitions::default(),
+            fallback_models: Vec::new(),
+            routing: HashMap::new(),
+            modes: BTreeMap::new(),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
-#[serde(rename_all = "lowercase")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum AgentMode {
     /// Pure research – read-only tools, no writes
     Research,
@@ -749,19 +951,161 @@ pub enum AgentMode {
     Plan,
     /// Full agent with read/write tools
     Agent,
+    /// A mode defined under `agent.modes`, by name (see
+    /// [`AgentConfig::mode`])
+    Custom(ModeName),
 }
 
-impl std::fmt::Display for AgentMode {
+/// The name of a custom mode, stored inline so that [`AgentMode`] stays
+/// `Copy`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ModeName {
+    len: u8,
+    bytes: [u8; ModeName::MAX_LEN],
+}
+
+impl ModeName {
+    /// Longest custom mode name, in bytes.
+    pub const MAX_LEN: usize = 48;
+
+    /// `name` as a mode name, or `None` when it is empty or longer than
+    /// [`Self::MAX_LEN`].
+    pub fn new(name: &str) -> Option<Self> {
+        if name.is_empty() || name.len() > Self::MAX_LEN {
+            return None;
+        }
+        let mut bytes = [0; Self::MAX_LEN];
+        bytes[..name.len()].copy_from_slice(name.as_bytes());
+        Some(Self {
+            len: name.len() as u8,
+            bytes,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Built from a `&str` in `new`, so always valid UTF-8.
+        std::str::from_utf8(&self.bytes[..self.len as usize]).unwrap_or_default()
+    }
+}
+
+impl std::fmt::Debug for ModeName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl AgentMode {
+    /// The built-in modes, in cycle order.
+    pub const BUILTIN: [AgentMode; 3] = [AgentMode::Research, AgentMode::Plan, AgentMode::Agent];
+
+    /// The built-in mode called `name`, or otherwise a custom mode of that
+    /// name.  Whether the custom mode is actually defined is up to the
+    /// config: resolve user input with [`AgentConfig::mode`] instead.
+    pub fn parse(name: &str) -> Option<AgentMode> {
+        match name {
+            "research" => Some(AgentMode::Research),
+            "plan" => Some(AgentMode::Plan),
+            "agent" => Some(AgentMode::Agent),
+            _ => ModeName::new(name).map(AgentMode::Custom),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            AgentMode::Research => "research",
+            AgentMode::Plan => "plan",
+            AgentMode::Agent => "agent",
+            AgentMode::Custom(name) => name.as_str(),
+        }
+    }
+
+    pub fn is_custom(self) -> bool {
+        matches!(self, AgentMode::Custom(_))
+    }
+}
+
+impl Serialize for AgentMode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for AgentMode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        AgentMode::parse(&name).ok_or_else(|| {
+            serde::de::Error::custom(format!(
+                "invalid mode name '{name}' (expected 1 to {} bytes)",
+                ModeName::MAX_LEN
+            ))
+        })
+    }
+}
+
+/// `--mode` takes the built-in modes only: custom ones are not known until
+/// the config has been loaded.
+impl clap::ValueEnum for AgentMode {
+    fn value_variants<'a>() -> &'a [Self] {
+        &Self::BUILTIN
+    }
+
+    fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
+        let (name, help) = match self {
+            AgentMode::Research => ("research", "Pure research – read-only tools, no writes"),
+            AgentMode::Plan => ("plan", "Generate a structured plan, no code changes"),
+            AgentMode::Agent => ("agent", "Full agent with read/write tools"),
+            AgentMode::Custom(_) => return None,
+        };
+        Some(clap::builder::PossibleValue::new(name).help(help))
+    }
+}
+
+/// Restricted tool-set presets, selected with `tools.profile` or
+/// `--tool-profile`.  Without one the tool set is chosen from the session
+/// (interactive, headless or sub-agent).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolPreset {
+    /// `read_file`, `find_file` and `grep` only
+    Minimal,
+    /// The minimal tools plus `search_codebase` and `read_lints`; nothing
+    /// that edits files or runs commands
+    Review,
+}
+
+impl ToolPreset {
+    /// Names of the tools in the preset.
+    pub fn tool_names(self) -> &'static [&'static str] {
+        match self {
+            ToolPreset::Minimal => &["read_file", "find_file", "grep"],
+            ToolPreset::Review => &[
+                "read_file",
+                "find_file",
+                "grep",
+                "search_codebase",
+                "read_lints",
+            ],
+        }
+    }
+}
+
+impl std::fmt::Display for ToolPreset {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            AgentMode::Research => write!(f, "research"),
-            AgentMode::Plan => write!(f, "plan"),
-            AgentMode::Agent => write!(f, "agent"),
+            ToolPreset::Minimal => write!(f, "minimal"),
+            ToolPreset::Review => write!(f, "review"),
         }
     }
 }
 
+impl std::fmt::Display for AgentMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ToolsConfig {
     /// Automatically approve shell commands matching these glob patterns
     pub auto_approve_patterns: Vec<String>,
@@ -769,6 +1113,41 @@ pub struct ToolsConfig {
     pub deny_patterns: Vec<String>,
     /// Timeout in seconds for a single tool call
     pub timeout_secs: u64,
+    /// Restrict the agent to a preset tool set (see [`ToolPreset`])
+    #[serde(default)]
+    pub profile: Option<ToolPreset>,
+    /// Offer only the tools named here (all tools when unset).  Also passed
+    /// to sub-agents started by `task`.
+    #[serde(default)]
+    pub allow: Option<Vec<String>>,
+    /// Remove the tools named here, after [`Self::allow`].  Also passed to
+    /// sub-agents started by `task`.
+    #[serde(default)]
+    pub deny: Vec<String>,
+    /// Per-tool timeouts in seconds, keyed by tool name (see [`ToolTimeouts`]).
+    #[serde(default)]
+    pub timeouts: ToolTimeouts,
+    /// Masking of secrets in shell and terminal command output
+    #[serde(default)]
+    pub secrets: SecretsConfig,
+    /// Run shell commands with the full environment of sven.  When false
+    /// only `PATH`, `HOME`, `LANG` and [`Self::env_allowlist`] are passed on.
+    /// Unset inherits in interactive sessions and scrubs in headless runs.
+    #[serde(default)]
+    pub inherit_env: Option<bool>,
+    /// Extra environment variables passed to shell commands when the
+    /// environment is scrubbed (`inherit_env: false`).
+    #[serde(default)]
+    pub env_allowlist: Vec<String>,
+    /// Reject tool calls whose path arguments (file paths, search roots,
+    /// working directories) resolve outside [`Self::sandbox_root`].
+    /// Set to false to allow any path (default true).
+    #[serde(default = "default_true")]
+    pub sandbox: bool,
+    /// Directory path arguments must stay inside.  Relative paths are taken
+    /// from the project root; unset means the project root itself.
+    #[serde(default)]
+    pub sandbox_root: Option<String>,
     /// Use Docker sandbox for shell execution
     pub use_docker: bool,
     /// Docker image to use when use_docker is true
@@ -779,6 +1158,9 @@ pub struct ToolsConfig {
     /// Persistent memory configuration
     #[serde(default)]
     pub memory: MemoryConfig,
+    /// Todo list persistence
+    #[serde(default)]
+    pub todo: TodoConfig,
     /// Linter configuration
     #[serde(default)]
     pub lints: LintsConfig,
@@ -811,10 +1193,20 @@ impl Default for ToolsConfig {
             ],
             deny_patterns: vec!["rm -rf /*".into(), "dd if=*".into()],
             timeout_secs: 30,
+            profile: None,
+            allow: None,
+            deny: Vec::new(),
+            timeouts: ToolTimeouts::default(),
+            secrets: SecretsConfig::default(),
+            inherit_env: None,
+            env_allowlist: Vec::new(),
+            sandbox: true,
+            sandbox_root: None,
             use_docker: false,
             docker_image: None,
             web: WebConfig::default(),
             memory: MemoryConfig::default(),
+            todo: TodoConfig::default(),
             lints: LintsConfig::default(),
             gdb: GdbConfig::default(),
             context: ContextConfig::default(),
@@ -881,12 +1273,103 @@ impl Default for WebConfig {
     }
 }
 
+/// Per-tool timeout overrides in seconds, keyed by tool name:
+///
+/// ```yaml
+/// tools:
+///   timeouts:
+///     web_fetch: 60
+///     shell: 10
+/// ```
+///
+/// A configured value replaces the tool's built-in timeout (for `shell`,
+/// `tools.timeout_secs`; for `gdb`, `tools.gdb.command_timeout_secs`) and is
+/// also enforced by the agent loop around every call of that tool.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ToolTimeouts(pub HashMap<String, u64>);
+
+impl ToolTimeouts {
+    /// The configured timeout for `tool`, if any.  `0` counts as unset.
+    pub fn get(&self, tool: &str) -> Option<u64> {
+        self.0.get(tool).copied().filter(|&secs| secs > 0)
+    }
+
+    /// The configured timeout for `tool`, or `default` when none is set.
+    pub fn get_or(&self, tool: &str, default: u64) -> u64 {
+        self.get(tool).unwrap_or(default)
+    }
+
+    /// Iterate over `(tool, seconds)` pairs with a non-zero timeout.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.0
+            .iter()
+            .filter(|(_, &secs)| secs > 0)
+            .map(|(name, &secs)| (name.as_str(), secs))
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MemoryConfig {
     /// Path to the memory JSON file (default: ~/.config/sven/memory.json)
     pub memory_file: Option<String>,
 }
 
+/// Which parts of command output are replaced with `****` before the model
+/// sees them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretsConfig {
+    /// Mask secrets in `shell` and `run_terminal_command` output (default true).
+    #[serde(default = "default_true")]
+    pub mask: bool,
+    /// Regular expressions matching secrets.  When a pattern has a capture
+    /// group only the first group is masked, so a prefix such as `Bearer `
+    /// stays readable.  Replaces the built-in list when set.
+    #[serde(default = "SecretsConfig::default_patterns")]
+    pub patterns: Vec<String>,
+    /// Environment variables whose names end with one of these suffixes have
+    /// their values masked wherever they appear in the output.
+    #[serde(default = "SecretsConfig::default_env_suffixes")]
+    pub env_suffixes: Vec<String>,
+}
+
+impl SecretsConfig {
+    fn default_patterns() -> Vec<String> {
+        vec![
+            // AWS access key ids
+            r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b".into(),
+            // OpenAI / Anthropic style API keys
+            r"\bsk-[A-Za-z0-9_-]{16,}".into(),
+            // GitHub personal access and app tokens
+            r"\bgh[pousr]_[A-Za-z0-9]{36,}".into(),
+            // HTTP bearer tokens
+            r"(?i)\bbearer\s+([A-Za-z0-9._~+/=-]{8,})".into(),
+        ]
+    }
+
+    fn default_env_suffixes() -> Vec<String> {
+        vec!["_KEY".into(), "_TOKEN".into(), "_SECRET".into()]
+    }
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        Self {
+            mask: true,
+            patterns: Self::default_patterns(),
+            env_suffixes: Self::default_env_suffixes(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TodoConfig {
+    /// JSON file the todo list is saved to after every change and restored
+    /// from at startup, e.g. `.sven/todos.json` (relative to the working
+    /// directory).  Unset (default) keeps todos in memory only.
+    pub persist_file: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GdbConfig {
     /// Path to gdb-multiarch (or gdb) executable
@@ -955,6 +1438,86 @@ pub struct TuiConfig {
     /// Can also be forced with the SVEN_ASCII_BORDERS=1 environment variable.
     #[serde(default)]
     pub ascii_borders: bool,
+    /// Coalesce conversation saves requested within this many milliseconds
+    /// into one write (0 = write on every change).  Pending changes are
+    /// always flushed when a turn completes and on quit.
+    #[serde(default = "default_autosave_debounce_ms")]
+    pub autosave_debounce_ms: u64,
+    /// Notify when a turn completes while the terminal window is not focused.
+    #[serde(default)]
+    pub notify_on_complete: bool,
+    /// How the completion notification is delivered.
+    #[serde(default)]
+    pub notify_style: NotifyStyle,
+    /// Key bindings that take precedence over the built-in ones.
+    #[serde(default)]
+    pub keymap: KeymapConfig,
+    /// Vi-style modal editing in the input box and when editing a message:
+    /// Esc enters normal mode (motions, `d`/`c`/`y` operators, `p`, `u`),
+    /// `i`/`a`/`o` return to insert mode.
+    #[serde(default)]
+    pub vi_mode: bool,
+}
+
+/// Custom TUI key bindings (see [`TuiConfig::keymap`]).
+///
+/// Each table maps a key such as `ctrl+e`, `alt+enter`, `G` or `f5` to an
+/// action name such as `scroll_down` or `submit`; the action `none` removes
+/// the built-in binding of the key.  `input` applies while the input pane has
+/// focus, `chat` while the chat pane has, and `global` in both, after the
+/// pane's own table.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeymapConfig {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub global: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub input: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub chat: BTreeMap<String, String>,
+}
+
+/// Where and how saved conversations (`sven chats`, `--resume`) are stored.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HistoryConfig {
+    /// Directory holding saved conversations.  A relative path is taken from
+    /// the project root, so `.sven/history` keeps a per-project history.
+    /// Defaults to `$XDG_DATA_HOME/sven/history`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir: Option<std::path::PathBuf>,
+    /// File format of newly saved conversations.  Conversations in either
+    /// format are listed and resumed whatever this is set to.
+    #[serde(default)]
+    pub format: HistoryFormat,
+}
+
+/// File format of saved conversations (see [`HistoryConfig::format`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryFormat {
+    /// `## User` / `## Sven` sections in a `.md` file.
+    #[default]
+    Markdown,
+    /// One JSON record per line in a `.jsonl` file.
+    Jsonl,
+}
+
+/// Delivery of the turn-completion notification (see
+/// [`TuiConfig::notify_on_complete`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyStyle {
+    /// Terminal bell (BEL).
+    Bell,
+    /// OSC 9 desktop notification, shown by iTerm2, kitty, WezTerm, foot
+    /// and others.
+    Osc9,
+    /// Both the bell and the OSC 9 notification.
+    #[default]
+    Both,
+}
+
+fn default_autosave_debounce_ms() -> u64 {
+    500
 }
 
 impl Default for TuiConfig {
@@ -964,6 +1527,11 @@ impl Default for TuiConfig {
             code_line_numbers: false,
             wrap_width: 0,
             ascii_borders: false,
+            autosave_debounce_ms: default_autosave_debounce_ms(),
+            notify_on_complete: false,
+            notify_style: NotifyStyle::default(),
+            keymap: KeymapConfig::default(),
+            vi_mode: false,
         }
     }
 }
@@ -1431,6 +1999,25 @@ mod tests {
         assert_eq!(back.mode, AgentMode::Plan);
     }
 
+    #[test]
+    fn mode_transitions_parse_and_default_to_permissive() {
+        let c: Config =
+            serde_yaml::from_str("agent:\n  mode_transitions:\n    research: [plan]\n").unwrap();
+        let t = &c.agent.mode_transitions;
+        assert!(t.allows(AgentMode::Research, AgentMode::Plan));
+        assert!(!t.allows(AgentMode::Research, AgentMode::Agent));
+        assert!(t.allows(AgentMode::Research, AgentMode::Research));
+        // Modes without an entry are unrestricted.
+        assert!(t.allows(AgentMode::Plan, AgentMode::Agent));
+        assert_eq!(
+            t.targets(AgentMode::Research, &AgentMode::BUILTIN),
+            [AgentMode::Plan]
+        );
+        assert!(AgentConfig::default()
+            .mode_transitions
+            .allows(AgentMode::Research, AgentMode::Agent));
+    }
+
     // ── providers map ─────────────────────────────────────────────────────────
 
     #[test]
@@ -28,9 +28,11 @@ use sven_tools::{events::ToolEvent, Tool, ToolCall, ToolRegistry};
 use crate::{
     compact::{compact_session_with_strategy, emergency_compact, smart_truncate},
     events::{AgentEvent, CompactionStrategyUsed},
+    pause::PauseGate,
     prompts::system_prompt,
     runtime_context::AgentRuntimeContext,
     session::Session,
+    tokens::tokenizer_for,
     tool_slots::ToolSlotManager,
 };
 
@@ -76,6 +78,8 @@ pub struct Agent {
     /// start of the next loop iteration so the full conversation is replayed
     /// against the new model before the next user message.
     pending_model: Option<Arc<dyn sven_model::ModelProvider>>,
+    /// Checked at the top of every loop round; see [`Agent::pause_gate`].
+    pause: PauseGate,
 }
 
 impl Agent {
@@ -127,6 +131,7 @@ impl Agent {
             .unwrap_or(0) as usize;
         let mut session = Session::new(max_context_tokens);
         session.max_output_tokens = max_output_tokens;
+        session.set_tokenizer(tokenizer_for(model.name(), model.model_name()));
         // Pre-populate with prior messages if provided (used by session executor
         // to restore conversation context from the local conversation store).
         if !runtime.prior_messages.is_empty() {
@@ -142,6 +147,7 @@ impl Agent {
             tool_event_rx,
             model_resolver,
             pending_model: None,
+            pause: PauseGate::new(),
         }
     }
 
@@ -167,6 +173,17 @@ impl Agent {
         &self.current_mode
     }
 
+    /// Handle for suspending the agent loop from outside.
+    ///
+    /// Obtain it before handing the agent to a task that holds it for the
+    /// duration of a run.  A pause takes effect at the next safe point (after
+    /// the in-flight tool batch completes) and emits [`AgentEvent::Paused`];
+    /// resuming emits [`AgentEvent::Resumed`] and continues with the same
+    /// history.
+    pub fn pause_gate(&self) -> PauseGate {
+        self.pause.clone()
+    }
+
     /// Used by the CI runner to switch models mid-workflow (per-step model
     /// overrides).  The session history is preserved.
     pub fn set_model(&mut self, model: Arc<dyn sven_model::ModelProvider>) {
@@ -184,6 +201,8 @@ impl Agent {
         {
             self.session.max_output_tokens = mot as usize;
         }
+        self.session
+            .set_tokenizer(tokenizer_for(model.name(), model.model_name()));
         self.model = model;
     }
 
@@ -237,6 +256,24 @@ impl Agent {
         messages: Vec<Message>,
         new_user_content: &str,
         tx: mpsc::Sender<AgentEvent>,
+        cancel: tokio::sync::oneshot::Receiver<()>,
+    ) -> anyhow::Result<()> {
+        self.replace_history_and_submit_message_with_cancel(
+            messages,
+            Message::user(new_user_content),
+            tx,
+            cancel,
+        )
+        .await
+    }
+
+    /// Like [`replace_history_and_submit_with_cancel`] with a prepared user
+    /// message, e.g. one carrying images (see [`Message::user_with_parts`]).
+    pub async fn replace_history_and_submit_message_with_cancel(
+        &mut self,
+        messages: Vec<Message>,
+        new_user_message: Message,
+        tx: mpsc::Sender<AgentEvent>,
         mut cancel: tokio::sync::oneshot::Receiver<()>,
     ) -> anyhow::Result<()> {
         if cancel.try_recv().is_ok() {
@@ -249,13 +286,13 @@ impl Agent {
         }
 
         let mode = *self.current_mode.lock().await;
-        let mut msgs = messages;
+        let mut msgs = sven_model::sanitize::for_resume(messages);
         if msgs.is_empty() || msgs[0].role != Role::System {
             let sys = self.system_message(mode);
             msgs.insert(0, sys);
         }
         self.session.replace_messages(msgs);
-        self.session.push(Message::user(new_user_content));
+        self.session.push(new_user_message);
 
         self.run_agentic_loop_cancellable(tx, &mut cancel).await
     }
@@ -317,12 +354,16 @@ impl Agent {
     ///
     /// System messages in `messages` are stripped — the correct system message
     /// is injected automatically by `submit()` / `replace_history_and_submit`.
+    /// The rest is normalized with [`sven_model::sanitize::for_resume`], since
+    /// the history may have been recorded with a different provider.
     pub async fn seed_history(&mut self, messages: Vec<Message>) {
         let mode = *self.current_mode.lock().await;
-        let mut msgs: Vec<Message> = messages
-            .into_iter()
-            .filter(|m| m.role != Role::System)
-            .collect();
+        let mut msgs = sven_model::sanitize::for_resume(
+            messages
+                .into_iter()
+                .filter(|m| m.role != Role::System)
+                .collect(),
+        );
         if !msgs.is_empty() {
             let sys = self.system_message(mode);
             msgs.insert(0, sys);
@@ -332,7 +373,9 @@ impl Agent {
 
     /// Replace session history with the given messages, then run with the new user message.
     /// Used for edit-and-resubmit: TUI sends truncated history + new user content.
-    /// Prepends system message if the list does not start with one.
+    /// Prepends system message if the list does not start with one.  The
+    /// history is normalized with [`sven_model::sanitize::for_resume`], since
+    /// it may have been recorded with a different provider.
     pub async fn replace_history_and_submit(
         &mut self,
         messages: Vec<Message>,
@@ -340,7 +383,7 @@ impl Agent {
         tx: mpsc::Sender<AgentEvent>,
     ) -> anyhow::Result<()> {
         let mode = *self.current_mode.lock().await;
-        let mut msgs = messages;
+        let mut msgs = sven_model::sanitize::for_resume(messages);
         if msgs.is_empty() || msgs[0].role != Role::System {
             let sys = self.system_message(mode);
             msgs.insert(0, sys);
@@ -389,6 +432,25 @@ impl Agent {
                 }
             }
 
+            // Safe point: the previous tool batch (if any) has finished and its
+            // results are in the session, so a pause here loses nothing.
+            if self.pause.is_paused() {
+                let _ = tx.send(AgentEvent::Paused).await;
+                let resumed = tokio::select! {
+                    biased;
+                    _ = &mut *cancel => false,
+                    _ = self.pause.wait_resumed() => true,
+                };
+                if !resumed {
+                    if !partial_text.is_empty() {
+                        self.session.push(Message::assistant(&partial_text));
+                    }
+                    let _ = tx.send(AgentEvent::Aborted { partial_text }).await;
+                    return Ok(());
+                }
+                let _ = tx.send(AgentEvent::Resumed).await;
+            }
+
             rounds += 1;
             if rounds > self.config.max_tool_rounds {
                 // Instead of hard-stopping with an error, give the model one
@@ -572,6 +634,13 @@ impl Agent {
         const MAX_EMPTY_TURN_RETRIES: u32 = 2;
 
         loop {
+            // Safe point: see run_agentic_loop_cancellable.
+            if self.pause.is_paused() {
+                let _ = tx.send(AgentEvent::Paused).await;
+                self.pause.wait_resumed().await;
+                let _ = tx.send(AgentEvent::Resumed).await;
+            }
+
             rounds += 1;
             if rounds > self.config.max_tool_rounds {
                 // Give the model one final tool-free turn to summarise its
@@ -838,6 +907,52 @@ impl Agent {
         }
     }
 
+    /// Send `req` to each of `config.fallback_models` in turn after the
+    /// current model failed with `primary_err`, returning the first stream
+    /// that opens.  Emits [`AgentEvent::ModelFallback`] for the model that
+    /// answers.  A fallback that fails with a non-retryable error ends the
+    /// search, since the next model would most likely reject the request too.
+    async fn complete_with_fallback(
+        &self,
+        req: CompletionRequest,
+        primary_err: anyhow::Error,
+        tx: &mpsc::Sender<AgentEvent>,
+    ) -> anyhow::Result<sven_model::ResponseStream> {
+        let Some(resolver) = &self.model_resolver else {
+            return Err(primary_err)
+                .context("model completion failed (fallback models need a model resolver)");
+        };
+        let from = format!("{}/{}", self.model.name(), self.model.model_name());
+        let mut last_err = primary_err;
+        for name in &self.config.fallback_models {
+            let fallback = match resolver(name) {
+                Ok(m) => m,
+                Err(e) => {
+                    warn!(model = %name, error = %e, "skipping unresolvable fallback model");
+                    continue;
+                }
+            };
+            let to = format!("{}/{}", fallback.name(), fallback.model_name());
+            warn!(%from, %to, error = %format!("{last_err:#}"), "model failed; trying fallback");
+            let mut req = req.clone();
+            req.messages = sven_model::sanitize::strip_images_if_unsupported(
+                req.messages,
+                &fallback.input_modalities(),
+            );
+            match fallback.complete(req).await {
+                Ok(stream) => {
+                    let _ = tx.send(AgentEvent::ModelFallback { from, to }).await;
+                    return Ok(stream);
+                }
+                Err(e) if is_retryable_model_error(&e) => last_err = e,
+                Err(e) => {
+                    return Err(e).with_context(|| format!("fallback model {to} failed"));
+                }
+            }
+        }
+        Err(last_err).context("model completion failed (fallback models exhausted)")
+    }
+
     /// Call the model once, streaming text deltas and dispatching tool calls
     /// as soon as their JSON arguments are complete.
     ///
@@ -854,7 +969,7 @@ impl Agent {
         with_tools: bool,
     ) -> anyhow::Result<(String, ToolSlotManager, bool)> {
         let raw_schemas = if with_tools {
-            self.tools.schemas_for_mode(mode)
+            self.schemas_for_mode(mode)
         } else {
             vec![]
         };
@@ -884,7 +999,7 @@ impl Agent {
             core_tool_count,
         };
 
-        let mut stream = match self.model.complete(req).await {
+        let mut stream = match self.model.complete(req.clone()).await {
             Ok(s) => s,
             Err(e) => {
                 // When the provider reports a hard context-size overflow
@@ -936,6 +1051,8 @@ impl Agent {
                         .complete(req2)
                         .await
                         .context("model completion failed (after context recovery)")?
+                } else if is_retryable_model_error(&e) && !self.config.fallback_models.is_empty() {
+                    self.complete_with_fallback(req, e, &tx).await?
                 } else {
                     return Err(e).context("model completion failed");
                 }
@@ -949,6 +1066,7 @@ impl Agent {
         // Accumulate thinking deltas so we can emit a single ThinkingComplete
         // event to consumers (CI runner, TUI) once the thinking block ends.
         let mut thinking_buf = String::new();
+        let mut usage_reported = false;
 
         loop {
             // Enforce a per-chunk idle timeout.  If the model API stalls —
@@ -997,13 +1115,20 @@ impl Agent {
                         let _ = tx.send(AgentEvent::ToolCallStarted(tc)).await;
                     }
                 }
+                // Reported usage wins: an estimate only stands in when the
+                // provider sent nothing for this request.
+                ResponseEvent::Usage {
+                    estimated: true, ..
+                } if usage_reported => {}
                 ResponseEvent::Usage {
                     input_tokens,
                     output_tokens,
                     cache_read_tokens,
                     cache_write_tokens,
                     cost_usd,
+                    estimated,
                 } => {
+                    usage_reported |= !estimated;
                     self.session
                         .add_cache_usage(cache_read_tokens, cache_write_tokens);
                     // Update the running calibration factor using the provider's
@@ -1011,8 +1136,9 @@ impl Agent {
                     // approximation for the current workload and model.
                     // input_tokens is fresh-only; add cache_read and cache_write
                     // to get the true total context size sent to the model.
+                    // An estimate carries no information to calibrate against.
                     let actual_input = input_tokens + cache_read_tokens + cache_write_tokens;
-                    if actual_input > 0 {
+                    if actual_input > 0 && !estimated {
                         let estimated = self.session.token_count + self.session.schema_overhead;
                         self.session.update_calibration(actual_input, estimated);
                     }
@@ -1027,6 +1153,7 @@ impl Agent {
                             max_tokens: self.session.max_tokens,
                             max_output_tokens: self.session.max_output_tokens,
                             cost_usd,
+                            estimated,
                         })
                         .await;
                 }
@@ -1119,13 +1246,20 @@ impl Agent {
     /// Estimate the token overhead for items sent with every request but NOT
     /// stored in `session.messages`: tool schemas and the dynamic context block.
     fn estimate_schema_overhead(&self, mode: AgentMode) -> usize {
+        let tokenizer = self.session.tokenizer();
         let schema_tokens: usize = self
-            .tools
             .schemas_for_mode(mode)
             .iter()
-            .map(|s| (s.name.len() + s.description.len() + s.parameters.to_string().len()) / 4)
+            .map(|s| {
+                tokenizer.count(&s.name)
+                    + tokenizer.count(&s.description)
+                    + tokenizer.count(&s.parameters.to_string())
+            })
             .sum();
-        let dynamic_tokens = self.dynamic_context().map(|s| s.len() / 4).unwrap_or(0);
+        let dynamic_tokens = self
+            .dynamic_context()
+            .map(|s| tokenizer.count(&s))
+            .unwrap_or(0);
         schema_tokens + dynamic_tokens
     }
 
@@ -1158,7 +1292,30 @@ impl Agent {
         if !self.session.is_near_limit(threshold) {
             return Ok(());
         }
+        self.compact(tx, mode, turn).await
+    }
+
+    /// Compact the session now, however full the context window is, and emit
+    /// `ContextCompacted`.  Headless resumes use this to keep long loaded
+    /// conversations well below the window; the agent loop compacts on its
+    /// own when the window fills.  Does nothing when there is no history.
+    pub async fn compact_history(&mut self, tx: &mpsc::Sender<AgentEvent>) -> anyhow::Result<()> {
+        if self.session.messages.iter().all(|m| m.role == Role::System) {
+            return Ok(());
+        }
+        let mode = *self.current_mode.lock().await;
+        self.compact(tx, mode, 0).await
+    }
 
+    /// The compaction paths of [`Self::ensure_fits_budget`], without the
+    /// threshold check.
+    async fn compact(
+        &mut self,
+        tx: &mpsc::Sender<AgentEvent>,
+        mode: AgentMode,
+        turn: u32,
+    ) -> anyhow::Result<()> {
+        let input_budget = self.session.input_budget();
         let tokens_before = self.session.token_count;
         let sys = self.system_message(mode);
         let keep_n = self.config.compaction_keep_recent;
@@ -1219,14 +1376,16 @@ impl Agent {
         // budget, there is not enough space left for the model to emit a summary.
         let recent_raw_tokens: usize = non_system[summarize_count..]
             .iter()
-            .map(|m| m.approx_tokens())
+            .map(|m| self.session.tokenizer().count_message(m))
             .sum();
         let compaction_input_raw = self.session.token_count.saturating_sub(recent_raw_tokens);
         let calibrated_compaction_input =
             (compaction_input_raw as f32 * self.session.calibration_factor) as usize;
         let emergency_fraction = 0.95_f32;
         let compaction_would_overflow = summarize_count == 0
-            || (calibrated_compaction_input as f32 / input_budget as f32) >= emergency_fraction;
+            || (input_budget > 0
+                && (calibrated_compaction_input as f32 / input_budget as f32)
+                    >= emergency_fraction);
 
         let strategy_used = if compaction_would_overflow {
             // Emergency path: even the compaction call would overflow, or there
@@ -1315,8 +1474,22 @@ impl Agent {
         self.system_message(mode)
     }
 
+    /// Tools offered in `mode`: those of its built-in mode, narrowed to the
+    /// tool profile of a custom mode.
+    pub(crate) fn schemas_for_mode(&self, mode: AgentMode) -> Vec<sven_tools::ToolSchema> {
+        let mut schemas = self.tools.schemas_for_mode(self.config.tool_mode(mode));
+        if let Some(preset) = self.config.custom_mode(mode).and_then(|m| m.tool_profile) {
+            schemas.retain(|s| preset.tool_names().contains(&s.name.as_str()));
+        }
+        schemas
+    }
+
     fn system_message(&self, mode: AgentMode) -> Message {
-        let ctx = self.prompt_context();
+        let mut ctx = self.prompt_context();
+        ctx.mode_instructions = self
+            .config
+            .custom_mode(mode)
+            .map(|m| m.instructions.as_str());
         // Use the STABLE portion only — volatile context (git/CI) is injected
         // per-request via `system_dynamic_suffix` so it does not break prompt
         // caching across sessions.
@@ -1331,17 +1504,7 @@ impl Agent {
 
     /// Build a `PromptContext` from the current runtime environment.
     fn prompt_context(&self) -> crate::prompts::PromptContext<'_> {
-        crate::prompts::PromptContext {
-            project_root: self.runtime.project_root.as_deref(),
-            git_context: self.runtime.git_context_note.as_deref(),
-            project_context_file: self.runtime.project_context_file.as_deref(),
-            ci_context: self.runtime.ci_context_note.as_deref(),
-            append: self.runtime.append_system_prompt.as_deref(),
-            skills: self.runtime.skills.get(),
-            agents: self.runtime.agents.get(),
-            knowledge: self.runtime.knowledge.get(),
-            knowledge_drift_note: self.runtime.knowledge_drift_note.as_deref(),
-        }
+        self.runtime.prompt_context()
     }
 
     /// Volatile context (git + CI) formatted for injection as an uncached
@@ -1404,6 +1567,34 @@ fn extract_n_ctx_from_error(err: &anyhow::Error) -> Option<usize> {
     body["n_ctx"].as_u64().map(|n| n as usize)
 }
 
+/// Whether a failed completion is worth sending to a fallback model: rate
+/// limits, overload and server errors (HTTP 408, 429, 5xx), transport
+/// failures and stalled streams.  Client errors such as a malformed request
+/// or a rejected API key are not, as every model would fail the same way.
+///
+/// Providers report HTTP failures as `"<driver> error <status>: <body>"`.
+fn is_retryable_model_error(err: &anyhow::Error) -> bool {
+    let msg = format!("{err:#}");
+    let status = msg
+        .split_whitespace()
+        .skip_while(|w| *w != "error")
+        .nth(1)
+        .and_then(|w| w.trim_end_matches(':').parse::<u16>().ok());
+    if let Some(status) = status {
+        return matches!(status, 408 | 429 | 500..=599);
+    }
+    let msg = msg.to_lowercase();
+    [
+        "rate limit",
+        "overloaded",
+        "request failed",
+        "retryable",
+        "timed out",
+    ]
+    .iter()
+    .any(|p| msg.contains(p))
+}
+
 /// Strip `<think>` / `</think>` wrapper tags from accumulated thinking content.
 ///
 /// Some model servers (llama.cpp without `reasoning_format: deepseek`,
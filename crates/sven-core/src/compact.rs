@@ -5,6 +5,8 @@ use sven_config::CompactionStrategy;
 use sven_model::{Message, Role};
 use sven_tools::OutputCategory;
 
+use crate::tokens::CHARS_PER_TOKEN;
+
 // ─── Compaction prompts ───────────────────────────────────────────────────────
 
 const SUMMARIZE_PROMPT: &str =
@@ -154,7 +156,7 @@ pub fn smart_truncate(content: &str, category: OutputCategory, cap_tokens: usize
     if cap_tokens == 0 {
         return content.to_string();
     }
-    let cap_chars = cap_tokens * 4;
+    let cap_chars = cap_tokens * CHARS_PER_TOKEN;
     if content.len() <= cap_chars {
         return content.to_string();
     }
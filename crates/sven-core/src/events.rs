@@ -97,6 +97,9 @@ pub enum AgentEvent {
         max_output_tokens: usize,
         /// Cost in USD when reported by the API (e.g. OpenRouter).
         cost_usd: Option<f64>,
+        /// `true` when the provider reported no usage and these counts are a
+        /// local estimate.
+        estimated: bool,
     },
     /// The agent has finished processing the current user turn
     TurnComplete,
@@ -106,6 +109,12 @@ pub enum AgentEvent {
     /// The agent has committed `partial_text` (when non-empty) to its session
     /// history so a follow-up Resubmit will see it.
     Aborted { partial_text: String },
+    /// The agent reached a safe point with its [`crate::PauseGate`] paused and
+    /// is now suspended.  History is retained; nothing more happens until the
+    /// gate is resumed (or the run is cancelled).
+    Paused,
+    /// A paused agent was resumed and continues its loop.
+    Resumed,
     /// A recoverable error occurred
     Error(String),
     /// A long-running tool is reporting incremental progress.
@@ -125,6 +134,10 @@ pub enum AgentEvent {
     /// (e.g. `"anthropic/claude-opus-4-6"`).
     /// The TUI/CI runner should apply this for subsequent submissions.
     ModelChanged(String),
+    /// A completion failed on `from` with a retryable error and was served
+    /// by the fallback model `to` instead (see `agent.fallback_models`).
+    /// Both are `"provider/name"`.  Only that completion is affected.
+    ModelFallback { from: String, to: String },
     /// The agent is asking the user a question (id links to QuestionAnswer)
     Question { id: String, questions: Vec<String> },
     /// Answer to a previous Question event
@@ -211,11 +224,14 @@ pub trait AgentEventVisitor {
     }
     fn on_turn_complete(&mut self) {}
     fn on_aborted(&mut self, _partial_text: &str) {}
+    fn on_paused(&mut self) {}
+    fn on_resumed(&mut self) {}
     fn on_error(&mut self, _message: &str) {}
     fn on_tool_progress(&mut self, _call_id: &str, _message: &str) {}
     fn on_todo_update(&mut self, _todos: &[sven_tools::events::TodoItem]) {}
     fn on_mode_changed(&mut self, _mode: &sven_config::AgentMode) {}
     fn on_model_changed(&mut self, _model: &str) {}
+    fn on_model_fallback(&mut self, _from: &str, _to: &str) {}
     fn on_question(&mut self, _id: &str, _questions: &[String]) {}
     fn on_question_answer(&mut self, _id: &str, _answer: &str) {}
     fn on_title_generated(&mut self, _title: &str) {}
@@ -276,6 +292,7 @@ pub trait AgentEventVisitor {
                 max_tokens,
                 max_output_tokens,
                 cost_usd: _,
+                estimated: _,
             } => self.on_token_usage(
                 *input,
                 *output,
@@ -288,6 +305,8 @@ pub trait AgentEventVisitor {
             ),
             AgentEvent::TurnComplete => self.on_turn_complete(),
             AgentEvent::Aborted { partial_text } => self.on_aborted(partial_text),
+            AgentEvent::Paused => self.on_paused(),
+            AgentEvent::Resumed => self.on_resumed(),
             AgentEvent::Error(m) => self.on_error(m),
             AgentEvent::ToolProgress { call_id, message } => {
                 self.on_tool_progress(call_id, message)
@@ -295,6 +314,7 @@ pub trait AgentEventVisitor {
             AgentEvent::TodoUpdate(todos) => self.on_todo_update(todos),
             AgentEvent::ModeChanged(mode) => self.on_mode_changed(mode),
             AgentEvent::ModelChanged(model) => self.on_model_changed(model),
+            AgentEvent::ModelFallback { from, to } => self.on_model_fallback(from, to),
             AgentEvent::Question { id, questions } => self.on_question(id, questions),
             AgentEvent::QuestionAnswer { id, answer } => self.on_question_answer(id, answer),
             AgentEvent::TitleGenerated(t) => self.on_title_generated(t),
@@ -4,11 +4,13 @@
 mod agent;
 mod compact;
 mod events;
+mod pause;
 pub mod prompts;
 mod runtime_context;
 mod session;
 #[cfg(test)]
 mod tests;
+mod tokens;
 mod tool_slots;
 
 pub use agent::{Agent, AgentNewParams, ModelResolver};
@@ -16,6 +18,13 @@ pub use compact::{
     compact_session, compact_session_with_strategy, emergency_compact, smart_truncate,
 };
 pub use events::{AgentEvent, AgentEventVisitor, CompactionStrategyUsed, PeerInfo};
+pub use pause::PauseGate;
 pub use prompts::{system_prompt, CollabEvent};
 pub use runtime_context::AgentRuntimeContext;
 pub use session::{Session, TurnRecord};
+#[cfg(feature = "tiktoken")]
+pub use tokens::TiktokenTokenizer;
+pub use tokens::{
+    estimate_message_tokens, estimate_tokens, tokenizer_for, HeuristicTokenizer, Tokenizer,
+    CHARS_PER_TOKEN,
+};
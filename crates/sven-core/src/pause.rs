@@ -0,0 +1,101 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! Cooperative pause/resume for a running agent loop.
+//!
+//! A [`PauseGate`] is shared between the [`crate::Agent`] and whoever wants to
+//! suspend it (e.g. the node control service handing control to a human).
+//! Pausing never interrupts work in flight: the agent checks the gate at the
+//! top of every loop round, i.e. after the current tool batch has completed
+//! and its results are committed to the session.  While paused the agent
+//! keeps its full history, so resuming continues exactly where it stopped.
+
+use std::sync::Arc;
+
+use tokio::sync::watch;
+
+/// Shared pause flag checked by the agent loop at every safe point.
+///
+/// Cheap to clone; all clones control the same agent.
+#[derive(Debug, Clone)]
+pub struct PauseGate {
+    tx: Arc<watch::Sender<bool>>,
+}
+
+impl Default for PauseGate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PauseGate {
+    /// Create a gate in the running (not paused) state.
+    pub fn new() -> Self {
+        let (tx, _) = watch::channel(false);
+        Self { tx: Arc::new(tx) }
+    }
+
+    /// Request a pause at the next safe point.
+    ///
+    /// Returns `false` when the gate was already paused.
+    pub fn pause(&self) -> bool {
+        self.tx
+            .send_if_modified(|paused| !std::mem::replace(paused, true))
+    }
+
+    /// Release a pause.  A loop waiting in [`PauseGate::wait_resumed`]
+    /// continues immediately.
+    ///
+    /// Returns `false` when the gate was not paused.
+    pub fn resume(&self) -> bool {
+        self.tx
+            .send_if_modified(|paused| std::mem::replace(paused, false))
+    }
+
+    /// Whether a pause is currently requested.
+    pub fn is_paused(&self) -> bool {
+        *self.tx.borrow()
+    }
+
+    /// Wait until the gate is no longer paused.  Returns immediately when it
+    /// is not paused.
+    pub(crate) async fn wait_resumed(&self) {
+        let mut rx = self.tx.subscribe();
+        // The sender lives as long as `self`, so this cannot fail.
+        let _ = rx.wait_for(|paused| !*paused).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pause_and_resume_report_state_changes() {
+        let gate = PauseGate::new();
+        assert!(!gate.is_paused());
+        assert!(gate.pause());
+        assert!(!gate.pause(), "second pause is a no-op");
+        assert!(gate.is_paused());
+        assert!(gate.resume());
+        assert!(!gate.resume(), "second resume is a no-op");
+        assert!(!gate.is_paused());
+    }
+
+    #[tokio::test]
+    async fn wait_resumed_unblocks_on_resume() {
+        let gate = PauseGate::new();
+        gate.pause();
+        let waiter = tokio::spawn({
+            let gate = gate.clone();
+            async move { gate.wait_resumed().await }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished(), "must block while paused");
+        gate.resume();
+        tokio::time::timeout(std::time::Duration::from_millis(500), waiter)
+            .await
+            .expect("waiter must finish after resume")
+            .unwrap();
+    }
+}
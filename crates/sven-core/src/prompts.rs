@@ -5,13 +5,18 @@ use std::path::Path;
 use std::sync::Arc;
 
 use sven_config::AgentMode;
-use sven_runtime::{find_workspace_root, AgentInfo, KnowledgeInfo, SkillInfo};
+use sven_runtime::{AgentInfo, KnowledgeInfo, SkillInfo};
 
 /// All optional contextual blocks that can be injected into the system prompt.
 #[derive(Debug)]
 pub struct PromptContext<'a> {
     /// Absolute path to the project root (from `.git` detection).
     pub project_root: Option<&'a Path>,
+    /// Workspace root detected above the project root.  A `## Workspace`
+    /// section is added only when it differs from `project_root`.
+    pub workspace_root: Option<&'a Path>,
+    /// Detected build systems; listed in a `## Project Type` section.
+    pub build_systems: &'a [String],
     /// Pre-formatted git context (branch, commit, dirty status).
     ///
     /// **Caching note**: this field is *volatile* — it changes on every commit
@@ -44,12 +49,17 @@ pub struct PromptContext<'a> {
     /// Injected verbatim into the stable system-prompt block.  `None` when all
     /// knowledge documents are current or no `updated:` fields are set.
     pub knowledge_drift_note: Option<&'a str>,
+    /// Instructions of the active custom mode (`agent.modes`), used in place
+    /// of the built-in mode instructions.
+    pub mode_instructions: Option<&'a str>,
 }
 
 impl<'a> Default for PromptContext<'a> {
     fn default() -> Self {
         Self {
             project_root: None,
+            workspace_root: None,
+            build_systems: &[],
             git_context: None,
             project_context_file: None,
             ci_context: None,
@@ -58,6 +68,7 @@ impl<'a> Default for PromptContext<'a> {
             agents: Arc::from(Vec::<AgentInfo>::new()),
             knowledge: Arc::from(Vec::<KnowledgeInfo>::new()),
             knowledge_drift_note: None,
+            mode_instructions: None,
         }
     }
 }
@@ -72,6 +83,8 @@ impl<'a> PromptContext<'a> {
     pub fn stable_only(&self) -> Self {
         Self {
             project_root: self.project_root,
+            workspace_root: self.workspace_root,
+            build_systems: self.build_systems,
             git_context: None,
             project_context_file: self.project_context_file,
             ci_context: None,
@@ -80,6 +93,7 @@ impl<'a> PromptContext<'a> {
             agents: self.agents.clone(),
             knowledge: self.knowledge.clone(),
             knowledge_drift_note: self.knowledge_drift_note,
+            mode_instructions: self.mode_instructions,
         }
     }
 
@@ -118,7 +132,7 @@ mod guidelines {
         "- Prefer `edit_file` over `write` for modifying existing files (preserves surrounding context).\n\
          - Use `shell` for all command execution (delete files, run linters, list directories, etc.).\n\
          - Discovery workflow: `find_file` to locate files → `grep` to narrow → `read_file` with specific ranges for context.\n\
-         - Use `grep` output_mode='content' + context_lines for code-level inspection; use whole_project=true for codebase-wide sweeps.\n\
+         - Use `grep` output_mode='content' + context (or before/after) for code-level inspection; use whole_project=true for codebase-wide sweeps; multiline=true for patterns spanning lines.\n\
          - Use `shell` one-liners (sed, awk) for replacements at scale.\n\
          - Batch `read_file` calls in parallel — read all potentially relevant files in one turn."
     }
@@ -658,28 +672,26 @@ pub fn system_prompt(mode: AgentMode, custom: Option<&str>, ctx: PromptContext<'
              - Always complete all todos before completing your turn.\n\
              - Always complete the task requested by the user before completion your turn."
         }
+        AgentMode::Custom(_) => ctx.mode_instructions.unwrap_or_default(),
+    };
+
+    // A workspace root distinct from the project root means the repository
+    // sits inside a multi-repo layout (west, .cursor, …).
+    let workspace_root = ctx
+        .workspace_root
+        .filter(|ws| ctx.project_root.is_some_and(|root| root != *ws));
+    let relative_to = if workspace_root.is_some() {
+        "workspace"
+    } else {
+        "project"
     };
 
     let project_section = if let Some(root) = ctx.project_root {
-        let workspace_root = find_workspace_root(root);
-        let workspace_line = if workspace_root != root {
-            format!(
-                "\nWorkspace root: `{}` (contains shared tooling above the git repository)\n\
-                 - When the user provides relative paths, resolve them relative to the \
-                   workspace root.",
-                workspace_root.display()
-            )
-        } else {
-            String::from(
-                "\nWorkspace root: same as project root.\n\
-                 - When the user provides relative paths, resolve them relative to the \
-                   project root.",
-            )
-        };
         format!(
             "\n\n## Project Context\n\
-             Project root: `{project_root}`\
-             {workspace_line}\n\
+             Project root: `{project_root}`\n\
+             - When the user provides relative paths, resolve them relative to the \
+               {relative_to} root.\n\
              - Use absolute paths for all file read/write operations.\n\
              - Pass the project root as the `workdir` argument to `run_terminal_command` \
                so shell commands execute in the correct directory.",
@@ -689,6 +701,38 @@ pub fn system_prompt(mode: AgentMode, custom: Option<&str>, ctx: PromptContext<'
         String::new()
     };
 
+    let workspace_section = if let Some(ws) = workspace_root {
+        format!(
+            "\n\n## Workspace\n\
+             Workspace root: `{}`\n\
+             The project is one repository in a larger workspace; shared tooling and \
+             sibling repositories live under the workspace root.  Look there when a \
+             file or module is not found inside the project.",
+            ws.display()
+        )
+    } else {
+        String::new()
+    };
+
+    let project_type_section = if ctx.build_systems.is_empty() {
+        String::new()
+    } else {
+        let lines: Vec<String> = ctx
+            .build_systems
+            .iter()
+            .map(|id| match sven_runtime::build_system_hint(id) {
+                Some(hint) => format!("- `{id}`: {hint}"),
+                None => format!("- `{id}`"),
+            })
+            .collect();
+        format!(
+            "\n\n## Project Type\n\
+             Build systems detected from marker files in the project root.  Prefer their \
+             commands for building, testing and running the project:\n{}",
+            lines.join("\n")
+        )
+    };
+
     let git_section = if let Some(git) = ctx.git_context {
         format!("\n\n{git}")
     } else {
@@ -756,7 +800,8 @@ pub fn system_prompt(mode: AgentMode, custom: Option<&str>, ctx: PromptContext<'
 
     format!(
         "{agent_identity}\n\n\
-         {mode_instructions}{project_section}{git_section}\
+         {mode_instructions}{project_section}{workspace_section}{project_type_section}\
+         {git_section}\
          {context_file_section}{skills_section}{agents_section}\
          {knowledge_section}{knowledge_drift_section}{ci_section}\n\n\
          {guidelines_section}\
@@ -796,6 +841,19 @@ mod tests {
         assert!(prompt.contains("Extra rule."));
     }
 
+    #[test]
+    fn custom_mode_uses_its_own_instructions() {
+        let reviewer = AgentMode::parse("prompt-reviewer").unwrap();
+        let ctx = PromptContext {
+            mode_instructions: Some("You are a code reviewer.  Do not edit files."),
+            ..Default::default()
+        };
+        let pr = system_prompt(reviewer, None, ctx);
+        assert!(pr.contains("Operating Mode: `prompt-reviewer`"));
+        assert!(pr.contains("You are a code reviewer.  Do not edit files."));
+        assert!(!pr.contains("You are a capable coding agent."));
+    }
+
     #[test]
     fn research_mode_mentions_read_only() {
         let pr = system_prompt(AgentMode::Research, None, empty());
@@ -906,6 +964,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn detected_build_systems_get_a_project_type_section() {
+        let build_systems = ["west".to_string(), "cmake".to_string()];
+        let ctx = PromptContext {
+            build_systems: &build_systems,
+            ..Default::default()
+        };
+        let pr = system_prompt(AgentMode::Agent, None, ctx.stable_only());
+        assert!(pr.contains("## Project Type"), "{pr}");
+        assert!(pr.contains("- `west`: Zephyr/west workspace"));
+        assert!(pr.contains("- `cmake`: CMake"));
+
+        let pr = system_prompt(AgentMode::Agent, None, empty());
+        assert!(!pr.contains("## Project Type"));
+    }
+
+    #[test]
+    fn distinct_workspace_root_gets_its_own_section() {
+        let root = p("/work/zephyr-app");
+        let ws = p("/work");
+        let ctx = PromptContext {
+            project_root: Some(&root),
+            workspace_root: Some(&ws),
+            ..Default::default()
+        };
+        let pr = system_prompt(AgentMode::Agent, None, ctx);
+        let section = pr
+            .split("## Workspace\n")
+            .nth(1)
+            .expect("prompt should have a Workspace section");
+        assert!(section.starts_with("Workspace root: `/work`"), "{section}");
+        assert!(pr.contains("relative to the workspace root"));
+    }
+
+    #[test]
+    fn workspace_equal_to_project_root_adds_no_section() {
+        let root = p("/home/user/my-project");
+        let ctx = PromptContext {
+            project_root: Some(&root),
+            workspace_root: Some(&root),
+            ..Default::default()
+        };
+        let pr = system_prompt(AgentMode::Agent, None, ctx);
+        assert!(!pr.contains("## Workspace"));
+        assert!(pr.contains("relative to the project root"));
+    }
+
     #[test]
     fn no_project_root_no_section() {
         let pr = system_prompt(AgentMode::Agent, None, empty());
@@ -1099,6 +1204,7 @@ mod tests {
             skill_dir: PathBuf::from(format!("/tmp/{command}")),
             content: format!("## {command} content"),
             sven_meta: None,
+            requires: vec![],
         }
     }
 
@@ -10,14 +10,24 @@
 
 use std::path::PathBuf;
 
+use sven_config::AgentMode;
 use sven_model::Message;
 use sven_runtime::{SharedAgents, SharedKnowledge, SharedSkills};
 
+use crate::prompts::{system_prompt, PromptContext};
+
 /// Environment-detected context injected into an agent at construction time.
 #[derive(Debug, Default, Clone)]
 pub struct AgentRuntimeContext {
     /// Absolute path to the project root (found via `.git` walk-up).
     pub project_root: Option<PathBuf>,
+    /// Workspace root above the project (see
+    /// [`sven_runtime::find_workspace_root`]); equal to `project_root` when
+    /// the project is not part of a larger workspace.
+    pub workspace_root: Option<PathBuf>,
+    /// Build systems detected from marker files in the project (see
+    /// [`sven_runtime::detect_build_systems`]), e.g. `["west", "cmake"]`.
+    pub build_systems: Vec<String>,
     /// Pre-formatted git context block (branch, commit, dirty status).
     pub git_context_note: Option<String>,
     /// Pre-formatted CI environment context block.
@@ -58,3 +68,79 @@ pub struct AgentRuntimeContext {
     /// peer to retransmit history.
     pub prior_messages: Vec<Message>,
 }
+
+impl AgentRuntimeContext {
+    /// Borrow the prompt-relevant fields as a [`PromptContext`].
+    pub fn prompt_context(&self) -> PromptContext<'_> {
+        PromptContext {
+            project_root: self.project_root.as_deref(),
+            workspace_root: self.workspace_root.as_deref(),
+            build_systems: &self.build_systems,
+            git_context: self.git_context_note.as_deref(),
+            project_context_file: self.project_context_file.as_deref(),
+            ci_context: self.ci_context_note.as_deref(),
+            append: self.append_system_prompt.as_deref(),
+            skills: self.skills.get(),
+            agents: self.agents.get(),
+            knowledge: self.knowledge.get(),
+            knowledge_drift_note: self.knowledge_drift_note.as_deref(),
+            mode_instructions: None,
+        }
+    }
+
+    /// The complete system prompt an agent with this context sends in
+    /// `mode`: the cacheable prompt followed by the volatile git/CI block.
+    ///
+    /// `config_prompt` is `agent.system_prompt` from the config.  As in the
+    /// agent, a custom prompt (that or `system_prompt_override`) replaces the
+    /// built-in one and suppresses the volatile block.
+    pub fn render_system_prompt(&self, mode: AgentMode, config_prompt: Option<&str>) -> String {
+        let custom = self.system_prompt_override.as_deref().or(config_prompt);
+        let ctx = self.prompt_context();
+        let stable = system_prompt(mode, custom, ctx.stable_only());
+        match ctx.dynamic_block().filter(|_| custom.is_none()) {
+            Some(dynamic) => format!("{stable}\n\n{dynamic}"),
+            None => stable,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rendered_prompt_includes_project_and_git_sections() {
+        let runtime = AgentRuntimeContext {
+            project_root: Some(PathBuf::from("/work/app")),
+            project_context_file: Some("Always run `make check`.".into()),
+            git_context_note: Some("## Git Context\nBranch: main".into()),
+            ci_context_note: Some("## CI Environment\nProvider: GitHub Actions".into()),
+            ..AgentRuntimeContext::default()
+        };
+        let prompt = runtime.render_system_prompt(AgentMode::Agent, None);
+        assert!(prompt.contains("Project root: `/work/app`"), "{prompt}");
+        assert!(prompt.contains("## Project Instructions\n\nAlways run `make check`."));
+        // The volatile block comes last, after the cacheable prompt.
+        assert!(prompt.ends_with(
+            "## Git Context\nBranch: main\n\n## CI Environment\nProvider: GitHub Actions"
+        ));
+
+        let bare = AgentRuntimeContext::default().render_system_prompt(AgentMode::Agent, None);
+        assert!(!bare.contains("## Project Instructions"));
+        assert!(!bare.contains("## Git Context"));
+    }
+
+    #[test]
+    fn custom_prompt_replaces_built_in_and_volatile_block() {
+        let runtime = AgentRuntimeContext {
+            git_context_note: Some("## Git Context\nBranch: main".into()),
+            append_system_prompt: Some("Extra.".into()),
+            ..AgentRuntimeContext::default()
+        };
+        assert_eq!(
+            runtime.render_system_prompt(AgentMode::Plan, Some("Be terse.")),
+            "Be terse.\n\nExtra."
+        );
+    }
+}
@@ -1,11 +1,15 @@
 // Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
 //
 // SPDX-License-Identifier: Apache-2.0
+use std::sync::Arc;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sven_model::Message;
 use uuid::Uuid;
 
+use crate::tokens::{HeuristicTokenizer, Tokenizer};
+
 /// One saved turn in the conversation log.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TurnRecord {
@@ -50,6 +54,8 @@ pub struct Session {
     pub cache_read_total: u32,
     /// Running total of cache-write tokens across all turns in this session.
     pub cache_write_total: u32,
+    /// Counts `token_count`; see [`Session::set_tokenizer`].
+    tokenizer: Arc<dyn Tokenizer>,
 }
 
 impl Session {
@@ -64,9 +70,22 @@ impl Session {
             schema_overhead: 0,
             cache_read_total: 0,
             cache_write_total: 0,
+            tokenizer: Arc::new(HeuristicTokenizer),
         }
     }
 
+    /// The tokenizer this session counts messages with.
+    pub fn tokenizer(&self) -> &dyn Tokenizer {
+        self.tokenizer.as_ref()
+    }
+
+    /// Count with `tokenizer` from now on and recount the current messages.
+    /// The agent sets one matching its model (see [`crate::tokenizer_for`]).
+    pub fn set_tokenizer(&mut self, tokenizer: Arc<dyn Tokenizer>) {
+        self.tokenizer = tokenizer;
+        self.recalculate_tokens();
+    }
+
     /// Accumulate cache token usage from one model turn.
     pub fn add_cache_usage(&mut self, read: u32, write: u32) {
         self.cache_read_total += read;
@@ -74,7 +93,7 @@ impl Session {
     }
 
     pub fn push(&mut self, msg: Message) {
-        self.token_count += msg.approx_tokens();
+        self.token_count += self.tokenizer.count_message(&msg);
         self.messages.push(msg);
     }
 
@@ -145,7 +164,11 @@ impl Session {
 
     /// Recalculate token count from scratch (call after compaction).
     pub fn recalculate_tokens(&mut self) {
-        self.token_count = self.messages.iter().map(|m| m.approx_tokens()).sum();
+        self.token_count = self
+            .messages
+            .iter()
+            .map(|m| self.tokenizer.count_message(m))
+            .sum();
     }
 
     /// Replace the message list and recalculate token count (for resubmit / edit).
@@ -197,6 +220,30 @@ mod tests {
         assert_eq!(s.schema_overhead, 0);
     }
 
+    // ── Tokenizer ────────────────────────────────────────────────────────────
+
+    #[test]
+    fn set_tokenizer_recounts_only_this_session() {
+        struct Words;
+        impl Tokenizer for Words {
+            fn name(&self) -> &str {
+                "words"
+            }
+            fn count(&self, text: &str) -> usize {
+                text.split_whitespace().count()
+            }
+        }
+        let text = "one two three four five six seven eight";
+        let mut a = Session::new(1000);
+        let mut b = Session::new(1000);
+        a.push(Message::user(text));
+        b.push(Message::user(text));
+        a.set_tokenizer(Arc::new(Words));
+        assert_eq!(a.tokenizer().name(), "words");
+        assert_eq!(a.token_count, 8);
+        assert_eq!(b.token_count, text.len() / 4);
+    }
+
     // ── Cache usage accumulation ──────────────────────────────────────────────
 
     #[test]
@@ -69,6 +69,52 @@ mod agent_tests {
         events
     }
 
+    /// Tool that blocks until the test releases it, so a pause can be
+    /// requested while a tool is in flight.
+    struct GateTool(Arc<tokio::sync::Notify>);
+
+    #[async_trait::async_trait]
+    impl sven_tools::Tool for GateTool {
+        fn name(&self) -> &str {
+            "gate"
+        }
+        fn description(&self) -> &str {
+            "blocks until released"
+        }
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object", "properties": {}})
+        }
+        fn default_policy(&self) -> sven_tools::ApprovalPolicy {
+            sven_tools::ApprovalPolicy::Auto
+        }
+        async fn execute(&self, call: &sven_tools::ToolCall) -> sven_tools::ToolOutput {
+            self.0.notified().await;
+            sven_tools::ToolOutput::ok(&call.id, "released")
+        }
+    }
+
+    /// Tool whose `execute` always panics.
+    struct PanickingTool;
+
+    #[async_trait::async_trait]
+    impl sven_tools::Tool for PanickingTool {
+        fn name(&self) -> &str {
+            "boom"
+        }
+        fn description(&self) -> &str {
+            "always panics"
+        }
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object", "properties": {}})
+        }
+        fn default_policy(&self) -> sven_tools::ApprovalPolicy {
+            sven_tools::ApprovalPolicy::Auto
+        }
+        async fn execute(&self, _call: &sven_tools::ToolCall) -> sven_tools::ToolOutput {
+            panic!("tool bug");
+        }
+    }
+
     // ── Basic text turn ───────────────────────────────────────────────────────
 
     fn agent_with_prior(model: ScriptedMockProvider, prior: Vec<sven_model::Message>) -> Agent {
@@ -188,6 +234,80 @@ mod agent_tests {
         assert_eq!(complete_text, Some("full response text"));
     }
 
+    #[tokio::test]
+    async fn single_shot_response_emits_one_text_complete() {
+        // A non-streaming completion arrives as one delta per kind of content.
+        let model = ScriptedMockProvider::new(vec![vec![
+            ResponseEvent::ThinkingDelta("Plan the answer.".into()),
+            ResponseEvent::TextDelta("The whole answer at once.".into()),
+            ResponseEvent::Usage {
+                input_tokens: 12,
+                output_tokens: 6,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
+                cost_usd: None,
+                estimated: false,
+            },
+            ResponseEvent::Done,
+        ]]);
+        let mut agent = default_agent(model);
+        let (tx, rx) = mpsc::channel(64);
+
+        agent.submit("hi", tx).await.unwrap();
+        let events = collect_events(rx).await;
+
+        let completes: Vec<&str> = events
+            .iter()
+            .filter_map(|e| match e {
+                AgentEvent::TextComplete(t) => Some(t.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(completes, vec!["The whole answer at once."]);
+        assert!(events.iter().any(|e| matches!(e, AgentEvent::TurnComplete)));
+    }
+
+    #[tokio::test]
+    async fn interleaved_thinking_and_text_are_routed_separately() {
+        let model = ScriptedMockProvider::new(vec![vec![
+            ResponseEvent::ThinkingDelta("Read the ".into()),
+            ResponseEvent::ThinkingDelta("question.".into()),
+            ResponseEvent::TextDelta("Part one.".into()),
+            ResponseEvent::ThinkingDelta("Now the rest.".into()),
+            ResponseEvent::TextDelta(" Part two.".into()),
+            ResponseEvent::Done,
+        ]]);
+        let mut agent = default_agent(model);
+        let (tx, rx) = mpsc::channel(64);
+
+        agent.submit("hi", tx).await.unwrap();
+        let events = collect_events(rx).await;
+
+        let routed: Vec<(&str, &str)> = events
+            .iter()
+            .filter_map(|e| match e {
+                AgentEvent::ThinkingDelta(t) => Some(("thinking_delta", t.as_str())),
+                AgentEvent::ThinkingComplete(t) => Some(("thinking", t.as_str())),
+                AgentEvent::TextDelta(t) => Some(("text_delta", t.as_str())),
+                AgentEvent::TextComplete(t) => Some(("text", t.as_str())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            routed,
+            vec![
+                ("thinking_delta", "Read the "),
+                ("thinking_delta", "question."),
+                ("thinking", "Read the question."),
+                ("text_delta", "Part one."),
+                ("thinking_delta", "Now the rest."),
+                ("thinking", "Now the rest."),
+                ("text_delta", " Part two."),
+                ("text", "Part one. Part two."),
+            ]
+        );
+    }
+
     // ── Session history ───────────────────────────────────────────────────────
 
     #[tokio::test]
@@ -206,6 +326,107 @@ mod agent_tests {
         );
     }
 
+    #[tokio::test]
+    async fn seeded_history_is_normalized_for_the_current_provider() {
+        use sven_model::{Message, Role};
+        let mut agent = default_agent(ScriptedMockProvider::always_text("ok"));
+        // Recorded with Anthropic: tool results in user turns, adjacent user
+        // turns, and a trailing call that never got a result.
+        let call = |id: &str| Message {
+            role: Role::Assistant,
+            content: MessageContent::ToolCall {
+                tool_call_id: id.into(),
+                function: sven_model::FunctionCall {
+                    name: "shell".into(),
+                    arguments: "{}".into(),
+                },
+            },
+        };
+        agent
+            .seed_history(vec![
+                Message::system("old system prompt"),
+                Message::user("first"),
+                Message::user("second"),
+                call("toolu_1"),
+                Message {
+                    role: Role::User,
+                    ..Message::tool_result("toolu_1", "ok")
+                },
+                call("toolu_2"),
+            ])
+            .await;
+
+        let roles: Vec<&Role> = agent.session().messages.iter().map(|m| &m.role).collect();
+        assert_eq!(
+            roles,
+            [
+                &Role::System,
+                &Role::User,
+                &Role::Assistant,
+                &Role::Tool,
+                &Role::Assistant,
+                &Role::Tool,
+            ]
+        );
+        assert_eq!(
+            agent.session().messages[1].as_text(),
+            Some("first\n\nsecond")
+        );
+    }
+
+    #[tokio::test]
+    async fn replaced_history_is_normalized_for_the_current_provider() {
+        use sven_model::{Message, Role};
+        let mut agent = default_agent(ScriptedMockProvider::always_text("ok"));
+        let call = Message {
+            role: Role::Assistant,
+            content: MessageContent::ToolCall {
+                tool_call_id: "toolu_1".into(),
+                function: sven_model::FunctionCall {
+                    name: "shell".into(),
+                    arguments: "{}".into(),
+                },
+            },
+        };
+        let (tx, rx) = mpsc::channel(64);
+        agent
+            .replace_history_and_submit(
+                vec![
+                    Message::user("first"),
+                    call,
+                    Message {
+                        role: Role::User,
+                        ..Message::tool_result("toolu_1", "ok")
+                    },
+                    Message::assistant("done"),
+                    Message::assistant("really"),
+                ],
+                "next",
+                tx,
+            )
+            .await
+            .unwrap();
+        let _ = collect_events(rx).await;
+
+        let roles: Vec<&Role> = agent.session().messages.iter().map(|m| &m.role).collect();
+        assert_eq!(
+            roles,
+            [
+                &Role::System,
+                &Role::User,
+                &Role::Assistant,
+                &Role::Tool,
+                &Role::Assistant,
+                &Role::User,
+                &Role::Assistant,
+            ]
+        );
+        assert_eq!(
+            agent.session().messages[4].as_text(),
+            Some("done\n\nreally")
+        );
+    }
+
     #[tokio::test]
     async fn user_message_appended_to_session() {
         let model = ScriptedMockProvider::always_text("reply");
@@ -336,6 +557,32 @@ mod agent_tests {
         assert!(has_tool_result, "tool result should be appended to session");
     }
 
+    #[tokio::test]
+    async fn panicking_tool_yields_error_result_and_agent_continues() {
+        let model = ScriptedMockProvider::tool_then_text("tc-1", "boom", "{}", "recovered");
+        let mut reg = ToolRegistry::new();
+        reg.register(PanickingTool);
+        let mut agent = agent_with(model, reg, AgentConfig::default(), AgentMode::Agent);
+        let (tx, rx) = mpsc::channel(64);
+
+        agent.submit("go", tx).await.unwrap();
+        let events = collect_events(rx).await;
+
+        let finished = events.iter().find_map(|e| match e {
+            AgentEvent::ToolCallFinished {
+                output, is_error, ..
+            } => Some((output.as_str(), *is_error)),
+            _ => None,
+        });
+        assert_eq!(finished, Some(("tool 'boom' panicked: tool bug", true)));
+        assert!(
+            events
+                .iter()
+                .any(|e| matches!(e, AgentEvent::TextComplete(t) if t == "recovered")),
+            "the agent must carry on after the panic"
+        );
+    }
+
     // ── File tool integration ─────────────────────────────────────────────────
 
     #[tokio::test]
@@ -457,6 +704,7 @@ mod agent_tests {
                 cache_read_tokens: 0,
                 cache_write_tokens: 0,
                 cost_usd: None,
+                estimated: false,
             },
             ResponseEvent::Done,
         ]]);
@@ -476,6 +724,65 @@ mod agent_tests {
         assert_eq!(usage, Some((42, 17)));
     }
 
+    #[tokio::test]
+    async fn estimated_usage_is_flagged_and_yields_to_reported_usage() {
+        let usage = |input_tokens, estimated| ResponseEvent::Usage {
+            input_tokens,
+            output_tokens: 1,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+            cost_usd: None,
+            estimated,
+        };
+        let model = ScriptedMockProvider::new(vec![
+            vec![
+                ResponseEvent::TextDelta("one".into()),
+                usage(500, true),
+                ResponseEvent::Done,
+            ],
+            vec![
+                ResponseEvent::TextDelta("two".into()),
+                usage(42, false),
+                usage(500, true),
+                ResponseEvent::Done,
+            ],
+        ]);
+        let mut agent = default_agent(model);
+
+        let (tx, rx) = mpsc::channel(64);
+        agent.submit("first", tx).await.unwrap();
+        let first: Vec<(u32, bool)> = collect_events(rx)
+            .await
+            .iter()
+            .filter_map(|e| match e {
+                AgentEvent::TokenUsage {
+                    input, estimated, ..
+                } => Some((*input, *estimated)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(first, vec![(500, true)]);
+        assert_eq!(
+            agent.session().calibration_factor,
+            1.0,
+            "an estimate must not calibrate the estimator"
+        );
+
+        let (tx, rx) = mpsc::channel(64);
+        agent.submit("second", tx).await.unwrap();
+        let second: Vec<(u32, bool)> = collect_events(rx)
+            .await
+            .iter()
+            .filter_map(|e| match e {
+                AgentEvent::TokenUsage {
+                    input, estimated, ..
+                } => Some((*input, *estimated)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(second, vec![(42, false)]);
+    }
+
     #[tokio::test]
     async fn cache_tokens_propagated_to_agent_event() {
         let model = ScriptedMockProvider::new(vec![vec![
@@ -486,6 +793,7 @@ mod agent_tests {
                 cache_read_tokens: 800,
                 cache_write_tokens: 200,
                 cost_usd: None,
+                estimated: false,
             },
             ResponseEvent::Done,
         ]]);
@@ -521,6 +829,7 @@ mod agent_tests {
                     cache_read_tokens: 400,
                     cache_write_tokens: 50,
                     cost_usd: None,
+                    estimated: false,
                 },
                 ResponseEvent::TextDelta("turn1".into()),
                 ResponseEvent::Done,
@@ -532,6 +841,7 @@ mod agent_tests {
                     cache_read_tokens: 550,
                     cache_write_tokens: 0,
                     cost_usd: None,
+                    estimated: false,
                 },
                 ResponseEvent::TextDelta("turn2".into()),
                 ResponseEvent::Done,
@@ -868,7 +1178,10 @@ mod agent_tests {
 
         let model = ScriptedMockProvider::new(scripts);
         let mut reg = ToolRegistry::new();
-        reg.register(ShellTool { timeout_secs: 5 });
+        reg.register(ShellTool {
+            timeout_secs: 5,
+            ..Default::default()
+        });
         let mut agent = agent_with(model, reg, AgentConfig::default(), AgentMode::Agent);
 
         let (tx, rx) = mpsc::channel(64);
@@ -1366,6 +1679,7 @@ mod agent_tests {
                 cache_read_tokens: 0,
                 cache_write_tokens: 0,
                 cost_usd: None,
+                estimated: false,
             },
             ResponseEvent::TextDelta("hi".into()),
             ResponseEvent::Done,
@@ -2019,4 +2333,243 @@ mod agent_tests {
             .any(|e| matches!(e, AgentEvent::ToolCallFinished { call_id, .. } if call_id == "tc-chunked"));
         assert!(finished, "ToolCallFinished must fire for chunked slot");
     }
+
+    // ── Pause / resume ────────────────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn pause_waits_for_in_flight_tool_and_resume_loses_no_turns() {
+        let release = Arc::new(tokio::sync::Notify::new());
+        let model = ScriptedMockProvider::tool_then_text("tc-1", "gate", "{}", "all done");
+        let mut reg = ToolRegistry::new();
+        reg.register(GateTool(release.clone()));
+        let mut agent = agent_with(model, reg, AgentConfig::default(), AgentMode::Agent);
+        let gate = agent.pause_gate();
+        let (tx, mut rx) = mpsc::channel(128);
+        // Keep the sender alive: dropping it counts as a cancellation.
+        let (_cancel_tx, cancel_rx) = tokio::sync::oneshot::channel();
+
+        let run = tokio::spawn(async move {
+            agent.submit_with_cancel("go", tx, cancel_rx).await.unwrap();
+            agent
+        });
+
+        // Request the pause while the tool is still running.
+        while !matches!(rx.recv().await, Some(AgentEvent::ToolCallStarted(_))) {}
+        gate.pause();
+        release.notify_one();
+
+        let mut before_pause = Vec::new();
+        loop {
+            match rx.recv().await.expect("agent stopped before pausing") {
+                AgentEvent::Paused => break,
+                ev => before_pause.push(ev),
+            }
+        }
+        assert!(
+            before_pause.iter().any(
+                |e| matches!(e, AgentEvent::ToolCallFinished { call_id, .. } if call_id == "tc-1")
+            ),
+            "the in-flight tool must complete before the pause takes effect"
+        );
+        assert!(
+            !before_pause
+                .iter()
+                .any(|e| matches!(e, AgentEvent::TextDelta(_))),
+            "no model turn may start once the pause is requested"
+        );
+        assert!(
+            tokio::time::timeout(std::time::Duration::from_millis(50), rx.recv())
+                .await
+                .is_err(),
+            "a paused agent must stay silent"
+        );
+
+        gate.resume();
+        let events = collect_events(rx).await;
+        assert!(matches!(events.first(), Some(AgentEvent::Resumed)));
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, AgentEvent::TextComplete(t) if t == "all done")));
+
+        let agent = run.await.unwrap();
+        let msgs = &agent.session().messages;
+        assert!(msgs.iter().any(|m| m.as_text() == Some("go")));
+        assert!(msgs.iter().any(|m| matches!(
+            &m.content,
+            MessageContent::ToolCall { tool_call_id, .. } if tool_call_id == "tc-1"
+        )));
+        assert!(msgs.iter().any(|m| matches!(
+            &m.content,
+            MessageContent::ToolResult { tool_call_id, .. } if tool_call_id == "tc-1"
+        )));
+        assert_eq!(msgs.last().and_then(|m| m.as_text()), Some("all done"));
+    }
+
+    #[tokio::test]
+    async fn cancel_while_paused_aborts() {
+        let model = ScriptedMockProvider::always_text("never sent");
+        let mut agent = default_agent(model);
+        let gate = agent.pause_gate();
+        gate.pause();
+        let (tx, rx) = mpsc::channel(16);
+        let (cancel_tx, cancel_rx) = tokio::sync::oneshot::channel();
+
+        let run = tokio::spawn(async move { agent.submit_with_cancel("hi", tx, cancel_rx).await });
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        cancel_tx.send(()).unwrap();
+        run.await.unwrap().unwrap();
+
+        let mut events = Vec::new();
+        let mut rx = rx;
+        while let Some(ev) = rx.recv().await {
+            events.push(ev);
+        }
+        assert!(matches!(events.first(), Some(AgentEvent::Paused)));
+        assert!(matches!(events.last(), Some(AgentEvent::Aborted { .. })));
+    }
+
+    // ── Fallback models ───────────────────────────────────────────────────────
+
+    /// A provider whose every completion fails with `error`.
+    struct FailingProvider {
+        error: &'static str,
+    }
+
+    #[async_trait::async_trait]
+    impl sven_model::ModelProvider for FailingProvider {
+        fn name(&self) -> &str {
+            "mock"
+        }
+        fn model_name(&self) -> &str {
+            "primary"
+        }
+        async fn complete(
+            &self,
+            _req: sven_model::CompletionRequest,
+        ) -> anyhow::Result<sven_model::ResponseStream> {
+            anyhow::bail!("{}", self.error)
+        }
+    }
+
+    fn agent_with_fallback(error: &'static str, fallback: ScriptedMockProvider) -> Agent {
+        let fallback: Arc<dyn sven_model::ModelProvider> = Arc::new(fallback);
+        let resolver: crate::ModelResolver = Arc::new(move |name: &str| {
+            assert_eq!(name, "mock/fallback");
+            Ok(Arc::clone(&fallback))
+        });
+        let (_tx, tool_event_rx) = mpsc::channel::<ToolEvent>(64);
+        Agent::new_with_params(crate::AgentNewParams {
+            model: Arc::new(FailingProvider { error }),
+            tools: Arc::new(ToolRegistry::default()),
+            config: Arc::new(AgentConfig {
+                fallback_models: vec!["mock/fallback".into()],
+                ..AgentConfig::default()
+            }),
+            runtime: AgentRuntimeContext::default(),
+            mode_lock: Arc::new(Mutex::new(AgentMode::Agent)),
+            tool_event_rx,
+            max_context_tokens: 128_000,
+            model_resolver: Some(resolver),
+        })
+    }
+
+    #[tokio::test]
+    async fn rate_limited_model_falls_back_and_emits_event() {
+        let mut agent = agent_with_fallback(
+            "mock error 429 Too Many Requests: rate limited",
+            ScriptedMockProvider::always_text("answer from fallback"),
+        );
+        let (tx, rx) = mpsc::channel(64);
+        agent.submit("hi", tx).await.unwrap();
+        let events = collect_events(rx).await;
+
+        let fallback = events
+            .iter()
+            .position(|e| {
+                matches!(e, AgentEvent::ModelFallback { from, to }
+                    if from == "mock/primary" && to == "scripted-mock/scripted-mock-model")
+            })
+            .expect("ModelFallback must be emitted");
+        let answer = events
+            .iter()
+            .position(|e| matches!(e, AgentEvent::TextComplete(t) if t == "answer from fallback"))
+            .expect("the fallback model must answer");
+        assert!(fallback < answer);
+        assert_eq!(
+            agent.session().messages.last().and_then(|m| m.as_text()),
+            Some("answer from fallback")
+        );
+    }
+
+    #[tokio::test]
+    async fn client_errors_do_not_fall_back() {
+        let mut agent = agent_with_fallback(
+            "mock error 401 Unauthorized: bad key",
+            ScriptedMockProvider::always_text("never sent"),
+        );
+        let (tx, rx) = mpsc::channel(64);
+        let result = agent.submit("hi", tx).await;
+        let events = collect_events(rx).await;
+        assert!(
+            result.is_err() || events.iter().any(|e| matches!(e, AgentEvent::Error(_))),
+            "a 401 must surface as an error"
+        );
+        assert!(!events
+            .iter()
+            .any(|e| matches!(e, AgentEvent::ModelFallback { .. })));
+    }
+
+    // ── Custom modes ──────────────────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn custom_mode_instructions_and_tool_profile_apply_when_active() {
+        use sven_config::{CustomModeConfig, ToolPreset};
+        use sven_tools::{GrepTool, ReadFileTool};
+
+        let reviewer = AgentMode::parse("core-reviewer").unwrap();
+        let config = AgentConfig {
+            modes: [(
+                "core-reviewer".to_string(),
+                CustomModeConfig {
+                    description: None,
+                    instructions: "Review the diff and list defects; never edit files.".into(),
+                    base: AgentMode::Agent,
+                    tool_profile: Some(ToolPreset::Minimal),
+                    model: None,
+                },
+            )]
+            .into(),
+            ..AgentConfig::default()
+        };
+        let mut tools = ToolRegistry::default();
+        tools.register(ReadFileTool);
+        tools.register(GrepTool);
+        tools.register(WriteTool);
+        tools.register(ShellTool::default());
+        let mut agent = agent_with(
+            ScriptedMockProvider::always_text("looks fine"),
+            tools,
+            config,
+            reviewer,
+        );
+
+        let (tx, rx) = mpsc::channel(64);
+        agent.submit("review this", tx).await.unwrap();
+        collect_events(rx).await;
+
+        let system = agent.session().messages[0].as_text().unwrap_or_default();
+        assert!(
+            system.contains("Review the diff and list defects; never edit files."),
+            "{system}"
+        );
+        assert!(system.contains("Operating Mode: `core-reviewer`"));
+        assert!(!system.contains("You are a capable coding agent."));
+
+        let names: Vec<String> = agent
+            .schemas_for_mode(reviewer)
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(names, ["grep", "read_file"]);
+    }
 }
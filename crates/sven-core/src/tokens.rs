@@ -0,0 +1,276 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! Token estimates shared by the compactor, the session budget, the TUI and
+//! CI budget tracking.
+//!
+//! Each [`crate::Session`] counts with its own [`Tokenizer`], picked by the
+//! agent from its model with [`tokenizer_for`].  The default,
+//! [`HeuristicTokenizer`], is an approximation: text is counted at
+//! [`CHARS_PER_TOKEN`] bytes per token, which is close for English prose and
+//! code with BPE tokenizers but can be off by a factor of two or more for
+//! other scripts.  With the `tiktoken` feature, OpenAI models are counted
+//! with their real tokenizer instead (see [`tokenizer_for`]).  Either way the
+//! agent corrects the remaining gap at runtime by calibrating against the
+//! usage the provider reports.
+
+use std::sync::Arc;
+
+use sven_model::{ContentPart, Message, MessageContent, ToolContentPart, ToolResultContent};
+
+/// Bytes of text per estimated token.
+pub const CHARS_PER_TOKEN: usize = 4;
+
+/// Token estimate for an image sent with `detail = "low"`.
+const LOW_DETAIL_IMAGE_TOKENS: usize = 85;
+/// Token estimate for any other image (OpenAI's typical auto/high estimate).
+const IMAGE_TOKENS: usize = 765;
+
+/// Counts tokens in text.  Runs on every message pushed to a session, so
+/// implementations must be cheap.
+pub trait Tokenizer: Send + Sync {
+    /// Short name for diagnostics, e.g. `"heuristic"` or `"tiktoken/o200k_base"`.
+    fn name(&self) -> &str;
+
+    /// Token count of `text`.
+    fn count(&self, text: &str) -> usize;
+
+    /// Token count of `message`: every text part through [`Self::count`],
+    /// fixed estimates for images, and at least one token per message.
+    fn count_message(&self, message: &Message) -> usize {
+        let tokens: usize = match &message.content {
+            MessageContent::Text(t) => self.count(t),
+            MessageContent::ContentParts(parts) => parts
+                .iter()
+                .map(|p| match p {
+                    ContentPart::Text { text } => self.count(text),
+                    ContentPart::Image { detail, .. } if detail.as_deref() == Some("low") => {
+                        LOW_DETAIL_IMAGE_TOKENS
+                    }
+                    ContentPart::Image { .. } => IMAGE_TOKENS,
+                })
+                .sum(),
+            MessageContent::ToolCall { function, .. } => {
+                self.count(&function.name) + self.count(&function.arguments)
+            }
+            MessageContent::ToolResult { content, .. } => match content {
+                ToolResultContent::Text(t) => self.count(t),
+                ToolResultContent::Parts(parts) => parts
+                    .iter()
+                    .map(|p| match p {
+                        ToolContentPart::Text { text } => self.count(text),
+                        ToolContentPart::Image { .. } => IMAGE_TOKENS,
+                    })
+                    .sum(),
+            },
+        };
+        tokens.max(1)
+    }
+}
+
+/// The default tokenizer: [`CHARS_PER_TOKEN`] bytes per token.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HeuristicTokenizer;
+
+impl Tokenizer for HeuristicTokenizer {
+    fn name(&self) -> &str {
+        "heuristic"
+    }
+
+    fn count(&self, text: &str) -> usize {
+        text.len() / CHARS_PER_TOKEN
+    }
+
+    fn count_message(&self, message: &Message) -> usize {
+        message.approx_tokens()
+    }
+}
+
+/// OpenAI's BPE tokenizers, via `tiktoken-rs`.
+#[cfg(feature = "tiktoken")]
+pub struct TiktokenTokenizer {
+    name: &'static str,
+    bpe: &'static tiktoken_rs::CoreBPE,
+}
+
+#[cfg(feature = "tiktoken")]
+impl TiktokenTokenizer {
+    /// The encoding tiktoken uses for `model`, or `None` when it does not
+    /// know the model.
+    pub fn for_model(model: &str) -> Option<Self> {
+        use tiktoken_rs::tokenizer::Tokenizer as Encoding;
+        let (name, bpe) = match tiktoken_rs::tokenizer::get_tokenizer(model)? {
+            Encoding::O200kBase => ("tiktoken/o200k_base", tiktoken_rs::o200k_base_singleton()),
+            Encoding::Cl100kBase => ("tiktoken/cl100k_base", tiktoken_rs::cl100k_base_singleton()),
+            Encoding::P50kBase => ("tiktoken/p50k_base", tiktoken_rs::p50k_base_singleton()),
+            Encoding::P50kEdit => ("tiktoken/p50k_edit", tiktoken_rs::p50k_edit_singleton()),
+            Encoding::R50kBase | Encoding::Gpt2 => {
+                ("tiktoken/r50k_base", tiktoken_rs::r50k_base_singleton())
+            }
+        };
+        Some(Self { name, bpe })
+    }
+
+    /// `o200k_base`, the encoding of current OpenAI models.
+    pub fn o200k() -> Self {
+        Self {
+            name: "tiktoken/o200k_base",
+            bpe: tiktoken_rs::o200k_base_singleton(),
+        }
+    }
+}
+
+#[cfg(feature = "tiktoken")]
+impl Tokenizer for TiktokenTokenizer {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn count(&self, text: &str) -> usize {
+        self.bpe.encode_ordinary(text).len()
+    }
+}
+
+/// The tokenizer that best matches `model` served by `provider`.
+///
+/// OpenAI models — on the `openai` and `azure` drivers, or behind a gateway
+/// under a name tiktoken knows (`openai/gpt-4o` on OpenRouter) — get their
+/// real tokenizer when the `tiktoken` feature is enabled.  Everything else,
+/// including Anthropic whose tokenizer is not public, uses the heuristic.
+pub fn tokenizer_for(provider: &str, model: &str) -> Arc<dyn Tokenizer> {
+    #[cfg(feature = "tiktoken")]
+    {
+        let bare = model.rsplit('/').next().unwrap_or(model);
+        if let Some(t) = TiktokenTokenizer::for_model(bare) {
+            return Arc::new(t);
+        }
+        if matches!(provider, "openai" | "azure") {
+            return Arc::new(TiktokenTokenizer::o200k());
+        }
+    }
+    let _ = (provider, model);
+    Arc::new(HeuristicTokenizer)
+}
+
+impl std::fmt::Debug for dyn Tokenizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Estimated token count of `text` with [`HeuristicTokenizer`], for callers
+/// that are not tied to a session's model.
+pub fn estimate_tokens(text: &str) -> usize {
+    HeuristicTokenizer.count(text)
+}
+
+/// Estimated token count of `message` with [`HeuristicTokenizer`].
+///
+/// Every message counts as at least one token; images use fixed per-image
+/// estimates (see [`Message::approx_tokens`]).
+pub fn estimate_message_tokens(message: &Message) -> usize {
+    HeuristicTokenizer.count_message(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // These pin the heuristic: changing it shifts every context budget, so
+    // it should be a deliberate change to these numbers.
+
+    #[test]
+    fn text_estimates_are_pinned() {
+        let h = HeuristicTokenizer;
+        assert_eq!(h.count(""), 0);
+        assert_eq!(h.count("abc"), 0);
+        assert_eq!(h.count("abcd"), 1);
+        assert_eq!(h.count("Hello, world!"), 3);
+        assert_eq!(h.count("fn main() { println!(\"hello\"); }"), 8);
+        // Counted in bytes, so multi-byte characters weigh more.
+        assert_eq!(h.count("åäö"), 1);
+        assert_eq!(h.count(&"x".repeat(4000)), 1000);
+    }
+
+    #[test]
+    fn message_estimates_are_pinned() {
+        let h = HeuristicTokenizer;
+        assert_eq!(h.count_message(&Message::user("")), 1);
+        assert_eq!(h.count_message(&Message::user("Hello, world!")), 3);
+        assert_eq!(h.count_message(&Message::assistant("x".repeat(4000))), 1000);
+        assert_eq!(
+            h.count_message(&Message::tool_result("id", "line 1\nline 2\nline 3\n")),
+            5
+        );
+    }
+
+    #[test]
+    fn default_count_message_adds_image_estimates() {
+        struct Words;
+        impl Tokenizer for Words {
+            fn name(&self) -> &str {
+                "words"
+            }
+            fn count(&self, text: &str) -> usize {
+                text.split_whitespace().count()
+            }
+        }
+        let m = Message::user_with_parts(vec![
+            ContentPart::Text {
+                text: "two words".into(),
+            },
+            ContentPart::Image {
+                image_url: "data:image/png;base64,AAAA".into(),
+                detail: Some("low".into()),
+            },
+        ]);
+        assert_eq!(Words.count_message(&m), 2 + LOW_DETAIL_IMAGE_TOKENS);
+        assert_eq!(Words.count_message(&Message::user("")), 1);
+    }
+
+    #[cfg(not(feature = "tiktoken"))]
+    #[test]
+    fn without_tiktoken_every_provider_uses_the_heuristic() {
+        assert_eq!(tokenizer_for("openai", "gpt-4o").name(), "heuristic");
+        assert_eq!(
+            tokenizer_for("anthropic", "claude-sonnet-4-5").name(),
+            "heuristic"
+        );
+    }
+
+    #[cfg(feature = "tiktoken")]
+    #[test]
+    fn tiktoken_is_selected_for_openai_models() {
+        assert_eq!(
+            tokenizer_for("openai", "gpt-4o").name(),
+            "tiktoken/o200k_base"
+        );
+        assert_eq!(
+            tokenizer_for("openrouter", "openai/gpt-4").name(),
+            "tiktoken/cl100k_base"
+        );
+        assert_eq!(
+            tokenizer_for("openai", "some-future-model").name(),
+            "tiktoken/o200k_base"
+        );
+        assert_eq!(
+            tokenizer_for("anthropic", "claude-sonnet-4-5").name(),
+            "heuristic"
+        );
+    }
+
+    #[cfg(feature = "tiktoken")]
+    #[test]
+    fn tiktoken_counts_differ_from_the_heuristic() {
+        let tiktoken = TiktokenTokenizer::o200k();
+        assert_eq!(tiktoken.count("Hello, world!"), 4);
+        assert_eq!(HeuristicTokenizer.count("Hello, world!"), 3);
+        // On ordinary code the heuristic stays within a factor of two.
+        let sample = "fn main() { println!(\"hello\"); }";
+        let (exact, approx) = (tiktoken.count(sample), HeuristicTokenizer.count(sample));
+        assert!(
+            exact / 2 <= approx && approx <= exact * 2,
+            "{exact} vs {approx}"
+        );
+    }
+}
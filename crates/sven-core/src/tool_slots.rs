@@ -341,8 +341,21 @@ impl ToolSlotManager {
 
     // ── Private helpers ───────────────────────────────────────────────────────
 
+    /// Run `tc` on its own task, cut off after the tool's configured timeout
+    /// (see [`ToolRegistry::timeout_for`]) when one is set.
     fn spawn_task(registry: Arc<ToolRegistry>, tc: ToolCall) -> JoinHandle<ToolOutput> {
-        tokio::spawn(async move { registry.execute(&tc).await })
+        tokio::spawn(async move {
+            let Some(limit) = registry.timeout_for(&tc.name) else {
+                return registry.execute(&tc).await;
+            };
+            match tokio::time::timeout(limit, registry.execute(&tc)).await {
+                Ok(output) => output,
+                Err(_) => {
+                    warn!(tool = %tc.name, secs = limit.as_secs(), "tool call timed out");
+                    ToolOutput::timed_out(&tc.id, &tc.name, limit)
+                }
+            }
+        })
     }
 
     /// Consume `self` into a flat list of `(index, ToolCall, JoinHandle)`.
@@ -507,6 +520,28 @@ mod tests {
         Arc::new(reg)
     }
 
+    /// Never finishes on its own.
+    struct HangTool;
+
+    #[async_trait]
+    impl Tool for HangTool {
+        fn name(&self) -> &str {
+            "hang"
+        }
+        fn description(&self) -> &str {
+            "never returns"
+        }
+        fn parameters_schema(&self) -> Value {
+            json!({ "type": "object" })
+        }
+        fn default_policy(&self) -> ApprovalPolicy {
+            ApprovalPolicy::Auto
+        }
+        async fn execute(&self, _call: &ToolCall) -> ToolOutput {
+            std::future::pending().await
+        }
+    }
+
     fn make_tx() -> (mpsc::Sender<AgentEvent>, mpsc::Receiver<AgentEvent>) {
         mpsc::channel(64)
     }
@@ -673,6 +708,31 @@ mod tests {
         assert_eq!(finished_count, 1);
     }
 
+    // ── Tool timeouts ─────────────────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn hanging_tool_is_cut_off_at_its_timeout() {
+        let mut reg = ToolRegistry::new();
+        reg.register(EchoTool);
+        reg.register(HangTool);
+        reg.set_timeout("hang", std::time::Duration::from_secs(1));
+        let mut mgr = ToolSlotManager::new(Arc::new(reg));
+        mgr.feed(0, "id0", "hang", "{}");
+        mgr.feed(1, "id1", "echo", "{}");
+
+        let (tx, _rx) = make_tx();
+        let results = mgr.join_all(&tx).await;
+        assert_eq!(results.len(), 2);
+        let hung = &results[0].1;
+        assert!(hung.is_error);
+        assert!(
+            hung.content.contains("timed out after 1s"),
+            "{}",
+            hung.content
+        );
+        assert!(!results[1].1.is_error, "other calls are unaffected");
+    }
+
     // ── abort_all() / Drop ────────────────────────────────────────────────────
 
     #[tokio::test]
@@ -18,6 +18,8 @@ use sven_mcp_client::McpEvent;
 use sven_model::{CompletionRequest, Message, ResponseEvent};
 use sven_runtime::{SharedAgents, SharedSkills};
 use sven_tools::Tool;
+
+use crate::segment::user_message;
 use sven_tools::{OutputBufferStore, QuestionRequest, SharedToolDisplays, SharedTools, TodoItem};
 use tokio::sync::{broadcast, mpsc, oneshot, Mutex};
 use tracing::{debug, warn};
@@ -41,6 +43,8 @@ pub enum AgentRequest {
     Resubmit {
         messages: Vec<Message>,
         new_user_content: String,
+        /// Data URLs of images attached to the new user message.
+        images: Vec<String>,
         /// Pre-resolved model config; agent calls `from_config` to instantiate.
         model_override: Option<ModelConfig>,
         mode_override: Option<AgentMode>,
@@ -167,12 +171,21 @@ pub async fn agent_task(
     };
 
     let shared_tools_loop = shared_tools.clone();
-    let (mut agent, mcp_manager, mcp_event_rx) = AgentBuilder::new(config.clone())
+    let built = AgentBuilder::new(config.clone())
         .with_runtime_context(runtime_ctx)
         .with_shared_tools(shared_tools)
         .with_shared_tool_displays(shared_tool_displays)
         .build_with_mcp(mode, model.clone(), profile)
         .await;
+    let (mut agent, mcp_manager, mcp_event_rx) = match built {
+        Ok(built) => built,
+        Err(e) => {
+            let _ = tx
+                .send(AgentEvent::Error(format!("agent init: {e:#}")))
+                .await;
+            return;
+        }
+    };
 
     if let Some(tx_mcp) = mcp_manager_tx {
         let _ = tx_mcp.send((Arc::clone(&mcp_manager), mcp_event_rx));
@@ -257,6 +270,7 @@ pub async fn agent_task(
             AgentRequest::Resubmit {
                 messages,
                 new_user_content,
+                images,
                 model_override,
                 mode_override,
             } => {
@@ -285,9 +299,9 @@ pub async fn agent_task(
                 let (cancel_tx, cancel_rx) = tokio::sync::oneshot::channel();
                 *cancel_handle.lock().await = Some(cancel_tx);
                 let result = agent
-                    .replace_history_and_submit_with_cancel(
+                    .replace_history_and_submit_message_with_cancel(
                         messages,
-                        &new_user_content,
+                        user_message(&new_user_content, &images),
                         tx.clone(),
                         cancel_rx,
                     )
@@ -2,6 +2,8 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 //! `/clear` command — erase all chat segments and reset the conversation view.
+//!
+//! `/clear` asks for confirmation first; `/clear!` clears immediately.
 
 use crate::commands::{
     CommandContext, CommandResult, CompletionItem, ImmediateAction, SlashCommand,
@@ -29,7 +31,36 @@ impl SlashCommand for ClearCommand {
 
     fn execute(&self, _args: Vec<String>) -> CommandResult {
         CommandResult {
-            immediate_action: Some(ImmediateAction::ClearChat),
+            immediate_action: Some(ImmediateAction::ClearChat { confirm: true }),
+            ..Default::default()
+        }
+    }
+}
+
+/// `/clear!` — clear without the confirmation prompt.
+pub struct ForceClearCommand;
+
+impl SlashCommand for ForceClearCommand {
+    fn name(&self) -> &str {
+        "clear!"
+    }
+
+    fn description(&self) -> &str {
+        "Clear the chat history without asking for confirmation"
+    }
+
+    fn complete(
+        &self,
+        _arg_index: usize,
+        _partial: &str,
+        _ctx: &CommandContext,
+    ) -> Vec<CompletionItem> {
+        vec![]
+    }
+
+    fn execute(&self, _args: Vec<String>) -> CommandResult {
+        CommandResult {
+            immediate_action: Some(ImmediateAction::ClearChat { confirm: false }),
             ..Default::default()
         }
     }
@@ -44,7 +75,16 @@ mod tests {
         let result = ClearCommand.execute(vec![]);
         assert!(matches!(
             result.immediate_action,
-            Some(ImmediateAction::ClearChat)
+            Some(ImmediateAction::ClearChat { confirm: true })
+        ));
+    }
+
+    #[test]
+    fn force_clear_skips_confirmation() {
+        let result = ForceClearCommand.execute(vec![]);
+        assert!(matches!(
+            result.immediate_action,
+            Some(ImmediateAction::ClearChat { confirm: false })
         ));
     }
 
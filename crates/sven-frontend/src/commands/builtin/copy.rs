@@ -0,0 +1,97 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! `/copy` command — copy conversation text to the clipboard.
+
+use crate::commands::{
+    CommandContext, CommandResult, CompletionItem, CopyTarget, ImmediateAction, SlashCommand,
+};
+
+pub struct CopyCommand;
+
+static TARGETS: &[(&str, &str, CopyTarget)] = &[
+    (
+        "last",
+        "The last agent reply (default)",
+        CopyTarget::LastReply,
+    ),
+    ("all", "The whole conversation", CopyTarget::All),
+];
+
+impl SlashCommand for CopyCommand {
+    fn name(&self) -> &str {
+        "copy"
+    }
+
+    fn description(&self) -> &str {
+        "Copy to the clipboard, also over SSH (last / all)"
+    }
+
+    fn complete(
+        &self,
+        arg_index: usize,
+        partial: &str,
+        _ctx: &CommandContext,
+    ) -> Vec<CompletionItem> {
+        if arg_index != 0 {
+            return vec![];
+        }
+        let items = TARGETS
+            .iter()
+            .map(|(name, desc, _)| CompletionItem::with_desc(*name, *name, *desc))
+            .collect();
+        crate::commands::completion::filter_and_rank(items, partial)
+    }
+
+    fn execute(&self, args: Vec<String>) -> CommandResult {
+        let arg = args.into_iter().next().unwrap_or_else(|| "last".into());
+        let action = match TARGETS.iter().find(|(name, _, _)| *name == arg) {
+            Some((_, _, target)) => ImmediateAction::Copy { target: *target },
+            None => ImmediateAction::ShowUsage {
+                usage: "usage: /copy [last|all]".into(),
+            },
+        };
+        CommandResult {
+            immediate_action: Some(action),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{try_dispatch, CommandRegistry};
+
+    #[test]
+    fn copy_last_targets_the_last_reply() {
+        let (name, result) = try_dispatch("/copy last", &CommandRegistry::with_builtins()).unwrap();
+        assert_eq!(name, "copy");
+        assert!(matches!(
+            result.immediate_action,
+            Some(ImmediateAction::Copy {
+                target: CopyTarget::LastReply
+            })
+        ));
+    }
+
+    #[test]
+    fn target_defaults_to_last_and_unknown_shows_usage() {
+        assert!(matches!(
+            CopyCommand.execute(vec![]).immediate_action,
+            Some(ImmediateAction::Copy {
+                target: CopyTarget::LastReply
+            })
+        ));
+        assert!(matches!(
+            CopyCommand.execute(vec!["all".into()]).immediate_action,
+            Some(ImmediateAction::Copy {
+                target: CopyTarget::All
+            })
+        ));
+        assert!(matches!(
+            CopyCommand.execute(vec!["bogus".into()]).immediate_action,
+            Some(ImmediateAction::ShowUsage { usage }) if usage == "usage: /copy [last|all]"
+        ));
+    }
+}
@@ -5,6 +5,7 @@
 
 pub mod abort;
 pub mod clear;
+pub mod copy;
 pub mod inspect;
 pub mod mode;
 pub mod model;
@@ -12,4 +13,6 @@ pub mod new;
 pub mod provider;
 pub mod quit;
 pub mod refresh;
+pub mod retry;
 pub mod team;
+pub mod title;
@@ -28,22 +28,27 @@ impl SlashCommand for ModeCommand {
     }
 
     fn description(&self) -> &str {
-        "Switch agent mode for the next message (research / plan / agent)"
+        "Switch agent mode for the next message (research / plan / agent / custom)"
     }
 
     fn complete(
         &self,
         arg_index: usize,
         partial: &str,
-        _ctx: &CommandContext,
+        ctx: &CommandContext,
     ) -> Vec<CompletionItem> {
         if arg_index != 0 {
             return vec![];
         }
 
+        let custom = ctx.config.agent.modes.iter().map(|(name, mode)| {
+            let desc = mode.description.as_deref().unwrap_or("Custom mode");
+            CompletionItem::with_desc(name, name, desc)
+        });
         let items: Vec<CompletionItem> = MODES
             .iter()
             .map(|(name, desc)| CompletionItem::with_desc(*name, *name, *desc))
+            .chain(custom)
             .collect();
 
         crate::commands::completion::filter_and_rank(items, partial)
@@ -51,15 +56,16 @@ impl SlashCommand for ModeCommand {
 
     fn execute(&self, args: Vec<String>) -> CommandResult {
         let mode_str = args.into_iter().next().unwrap_or_default();
-        let mode = match mode_str.as_str() {
-            "research" => Some(AgentMode::Research),
-            "plan" => Some(AgentMode::Plan),
-            "agent" => Some(AgentMode::Agent),
-            _ => None,
-        };
-        CommandResult {
-            mode_override: mode,
-            ..Default::default()
+        match AgentMode::parse(&mode_str) {
+            Some(mode) if !mode.is_custom() => CommandResult {
+                mode_override: Some(mode),
+                ..Default::default()
+            },
+            Some(_) => CommandResult {
+                custom_mode: Some(mode_str),
+                ..Default::default()
+            },
+            None => CommandResult::default(),
         }
     }
 }
@@ -123,6 +129,41 @@ mod tests {
         assert!(names.contains(&"agent"));
     }
 
+    #[test]
+    fn custom_modes_are_completed_and_selectable() {
+        use crate::commands::CommandContext;
+        use std::sync::Arc;
+        use sven_config::{Config, CustomModeConfig};
+        let mut config = Config::default();
+        config.agent.modes.insert(
+            "frontend-reviewer".into(),
+            CustomModeConfig {
+                description: Some("Reviews without editing".into()),
+                instructions: "Review only.".into(),
+                base: AgentMode::Research,
+                tool_profile: None,
+                model: None,
+            },
+        );
+        let reviewer = config.agent.mode("frontend-reviewer").unwrap();
+        let ctx = CommandContext {
+            config: Arc::new(config),
+            current_model_provider: "openai".into(),
+            current_model_name: "gpt-4o".into(),
+        };
+        let items = ModeCommand.complete(0, "front", &ctx);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].value, "frontend-reviewer");
+
+        let registry = crate::commands::CommandRegistry::with_builtins();
+        let dispatch = |input| crate::commands::dispatch_command(input, &registry, &ctx);
+        let (_, result) = dispatch("/mode frontend-reviewer").unwrap();
+        assert_eq!(result.mode_override, Some(reviewer));
+
+        let (_, result) = dispatch("/mode undefined-mode").unwrap();
+        assert_eq!(result.mode_override, None);
+    }
+
     #[test]
     fn complete_filters_by_prefix() {
         use crate::commands::CommandContext;
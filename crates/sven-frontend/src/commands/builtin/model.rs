@@ -2,8 +2,12 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 //! `/model` command — override the model for the next queued message.
+//!
+//! Trailing `key=value` tokens adjust the selected model for experiments
+//! without editing the config: `/model openai/gpt-4o temp=0.2 max=8000`.
 
 use sven_model::catalog;
+use tracing::warn;
 
 use crate::commands::{CommandContext, CommandResult, CompletionItem, SlashCommand};
 
@@ -15,7 +19,7 @@ impl SlashCommand for ModelCommand {
     }
 
     fn description(&self) -> &str {
-        "Switch model permanently (e.g. /model anthropic/claude-opus-4-6)"
+        "Switch model permanently (e.g. /model anthropic/claude-opus-4-6 temp=0.2 max=8000)"
     }
 
     fn complete(
@@ -104,14 +108,36 @@ impl SlashCommand for ModelCommand {
     }
 
     fn execute(&self, args: Vec<String>) -> CommandResult {
-        let model = args.into_iter().next().unwrap_or_default();
-        if model.is_empty() {
-            return CommandResult::default();
+        let mut result = CommandResult::default();
+        let mut args = args.into_iter().filter(|a| !a.is_empty()).peekable();
+        if let Some(model) = args.next_if(|a| !a.contains('=')) {
+            result.model_override = Some(model);
         }
-        CommandResult {
-            model_override: Some(model),
-            ..Default::default()
+        for arg in args {
+            apply_param(&mut result, &arg);
         }
+        result
+    }
+}
+
+/// Apply one `key=value` token to `result`: `temp`/`temperature`, or
+/// `max`/`max_tokens`, the per-request output cap.  Unknown keys and
+/// unparsable values are skipped with a warning.
+fn apply_param(result: &mut CommandResult, arg: &str) {
+    let Some((key, value)) = arg.split_once('=') else {
+        warn!(arg, "/model: ignoring argument that is not key=value");
+        return;
+    };
+    match key {
+        "temp" | "temperature" => match value.parse::<f32>() {
+            Ok(t) => result.temperature_override = Some(t),
+            Err(_) => warn!(value, "/model: temperature must be a number"),
+        },
+        "max" | "max_tokens" => match value.parse::<u32>() {
+            Ok(n) => result.max_tokens_override = Some(n),
+            Err(_) => warn!(value, "/model: max_tokens must be a whole number"),
+        },
+        _ => warn!(key, "/model: ignoring unknown parameter"),
     }
 }
 
@@ -136,6 +162,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn execute_parses_inline_params() {
+        let result = ModelCommand.execute(vec![
+            "openai/gpt-4o".into(),
+            "temp=0.2".into(),
+            "max_tokens=8000".into(),
+        ]);
+        assert_eq!(result.model_override.as_deref(), Some("openai/gpt-4o"));
+        assert_eq!(result.temperature_override, Some(0.2));
+        assert_eq!(result.max_tokens_override, Some(8000));
+    }
+
+    #[test]
+    fn execute_skips_unknown_keys_and_bad_values() {
+        let result = ModelCommand.execute(vec![
+            "gpt-4o".into(),
+            "top_p=0.9".into(),
+            "max=lots".into(),
+            "temp=0.5".into(),
+        ]);
+        assert_eq!(result.model_override.as_deref(), Some("gpt-4o"));
+        assert_eq!(result.temperature_override, Some(0.5));
+        assert!(result.max_tokens_override.is_none());
+    }
+
     #[test]
     fn execute_empty_arg_list_returns_no_override() {
         let result = ModelCommand.execute(vec![]);
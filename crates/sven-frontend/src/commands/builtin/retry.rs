@@ -0,0 +1,65 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! `/retry` command — resend the most recent user message.
+//!
+//! The frontend drops everything from that message on (the response being
+//! retried included) and submits the message again.  An optional model
+//! argument is staged for the retried turn, like `/model`.
+
+use crate::commands::{
+    CommandContext, CommandResult, CompletionItem, ImmediateAction, SlashCommand,
+};
+
+use super::model::ModelCommand;
+
+pub struct RetryCommand;
+
+impl SlashCommand for RetryCommand {
+    fn name(&self) -> &str {
+        "retry"
+    }
+
+    fn description(&self) -> &str {
+        "Resend the last message, optionally to another model (e.g. /retry openai/gpt-4o)"
+    }
+
+    fn complete(
+        &self,
+        arg_index: usize,
+        partial: &str,
+        ctx: &CommandContext,
+    ) -> Vec<CompletionItem> {
+        ModelCommand.complete(arg_index, partial, ctx)
+    }
+
+    fn execute(&self, args: Vec<String>) -> CommandResult {
+        CommandResult {
+            model_override: args.into_iter().next(),
+            immediate_action: Some(ImmediateAction::Retry),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn execute_returns_retry_action() {
+        let result = RetryCommand.execute(vec![]);
+        assert!(matches!(
+            result.immediate_action,
+            Some(ImmediateAction::Retry)
+        ));
+        assert!(result.model_override.is_none());
+        assert!(result.message_to_send.is_none());
+    }
+
+    #[test]
+    fn model_argument_is_staged_for_the_retry() {
+        let result = RetryCommand.execute(vec!["openai/gpt-4o".into()]);
+        assert_eq!(result.model_override.as_deref(), Some("openai/gpt-4o"));
+    }
+}
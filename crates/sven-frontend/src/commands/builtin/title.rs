@@ -0,0 +1,69 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! `/title` command — override the conversation title.
+
+use crate::commands::{
+    CommandContext, CommandResult, CompletionItem, ImmediateAction, SlashCommand,
+};
+
+pub struct TitleCommand;
+
+impl SlashCommand for TitleCommand {
+    fn name(&self) -> &str {
+        "title"
+    }
+
+    fn description(&self) -> &str {
+        "Set the conversation title (no argument: derive it from the first message)"
+    }
+
+    fn complete(
+        &self,
+        _arg_index: usize,
+        _partial: &str,
+        _ctx: &CommandContext,
+    ) -> Vec<CompletionItem> {
+        vec![]
+    }
+
+    fn execute(&self, args: Vec<String>) -> CommandResult {
+        let title = args.join(" ").trim().to_string();
+        CommandResult {
+            immediate_action: Some(ImmediateAction::SetTitle {
+                title: (!title.is_empty()).then_some(title),
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{try_dispatch, CommandRegistry};
+
+    #[test]
+    fn words_are_joined_into_the_title() {
+        let (name, result) = try_dispatch(
+            "/title Release notes  for v2",
+            &CommandRegistry::with_builtins(),
+        )
+        .unwrap();
+        assert_eq!(name, "title");
+        assert!(matches!(
+            result.immediate_action,
+            Some(ImmediateAction::SetTitle { title: Some(ref t) }) if t == "Release notes for v2"
+        ));
+        assert!(result.message_to_send.is_none());
+    }
+
+    #[test]
+    fn no_argument_resets_to_derived_title() {
+        let result = TitleCommand.execute(vec![]);
+        assert!(matches!(
+            result.immediate_action,
+            Some(ImmediateAction::SetTitle { title: None })
+        ));
+    }
+}
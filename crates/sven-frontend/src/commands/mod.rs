@@ -21,7 +21,7 @@ pub use parser::{parse, ParsedCommand};
 pub use registry::CommandRegistry;
 
 use std::sync::Arc;
-use sven_config::{AgentMode, Config};
+use sven_config::{AgentMode, Config, ModelConfig};
 
 // ── Inspector kind ────────────────────────────────────────────────────────────
 
@@ -53,6 +53,15 @@ impl InspectorKind {
     }
 }
 
+/// What `/copy` puts on the clipboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyTarget {
+    /// The most recent agent text reply.
+    LastReply,
+    /// The whole conversation as markdown.
+    All,
+}
+
 // ── Context ───────────────────────────────────────────────────────────────────
 
 /// Context passed to commands when generating completions.
@@ -68,25 +77,81 @@ pub struct CommandContext {
 #[derive(Debug, Default)]
 pub struct CommandResult {
     pub model_override: Option<String>,
+    /// Sampling temperature for the selected model (`/model … temp=0.2`).
+    pub temperature_override: Option<f32>,
+    /// Per-request output cap (`max_output_tokens`) for the selected model
+    /// (`/model … max=8000`).
+    pub max_tokens_override: Option<u32>,
     pub mode_override: Option<AgentMode>,
+    /// A `/mode` argument that names no built-in mode.  [`dispatch_command`]
+    /// resolves it against `agent.modes` into `mode_override`.
+    pub custom_mode: Option<String>,
     pub message_to_send: Option<String>,
     pub immediate_action: Option<ImmediateAction>,
 }
 
+impl CommandResult {
+    /// The model this result switches to: `model_override` resolved against
+    /// `config` (or `current` when only parameters were given) with the
+    /// temperature and output-cap overrides applied.  `None` when the
+    /// command does not touch the model.
+    pub fn resolve_model(&self, config: &Config, current: &ModelConfig) -> Option<ModelConfig> {
+        let has_params = self.temperature_override.is_some() || self.max_tokens_override.is_some();
+        let mut cfg = match &self.model_override {
+            Some(model) => sven_model::resolve_model_from_config(config, model),
+            None if has_params => current.clone(),
+            None => return None,
+        };
+        if let Some(t) = self.temperature_override {
+            cfg.temperature = Some(t);
+        }
+        if let Some(max) = self.max_tokens_override {
+            cfg.max_output_tokens = Some(max);
+        }
+        Some(cfg)
+    }
+}
+
 /// Side-effects that must be handled by the app immediately (before queuing).
 #[derive(Debug)]
 pub enum ImmediateAction {
     Quit,
     Abort,
     RefreshSkills,
-    ClearChat,
+    /// Erase the conversation; `confirm` asks the user first.
+    ClearChat {
+        confirm: bool,
+    },
     NewConversation,
-    ApprovePlan { task_id: String },
-    RejectPlan { task_id: String, feedback: String },
+    /// Resend the most recent user message, dropping what followed it.
+    Retry,
+    /// Override the conversation title; `None` derives it from the first
+    /// user message again.
+    SetTitle {
+        title: Option<String>,
+    },
+    ApprovePlan {
+        task_id: String,
+    },
+    RejectPlan {
+        task_id: String,
+        feedback: String,
+    },
     OpenTeamPicker,
     ToggleTaskList,
-    OpenInspector { kind: InspectorKind },
-    McpAuth { server: String },
+    OpenInspector {
+        kind: InspectorKind,
+    },
+    McpAuth {
+        server: String,
+    },
+    Copy {
+        target: CopyTarget,
+    },
+    /// The arguments were not understood; show `usage` to the user.
+    ShowUsage {
+        usage: String,
+    },
 }
 
 // ── Trait ─────────────────────────────────────────────────────────────────────
@@ -112,9 +177,13 @@ pub trait SlashCommand: Send + Sync {
 pub fn dispatch_command(
     input: &str,
     registry: &CommandRegistry,
-    _ctx: &CommandContext,
+    ctx: &CommandContext,
 ) -> Option<(String, CommandResult)> {
-    try_dispatch(input, registry)
+    let (name, mut result) = try_dispatch(input, registry)?;
+    if let Some(custom) = result.custom_mode.take() {
+        result.mode_override = ctx.config.agent.mode(&custom);
+    }
+    Some((name, result))
 }
 
 /// Dispatch without a context argument.
@@ -171,6 +240,48 @@ mod dispatch_tests {
         );
     }
 
+    #[test]
+    fn model_inline_params_carry_into_resolved_config() {
+        let (_, result) = try_dispatch(
+            "/model anthropic/claude-opus-4-6 temp=0.2 max=8000",
+            &registry(),
+        )
+        .unwrap();
+        let cfg = result
+            .resolve_model(&Config::default(), &ModelConfig::default())
+            .unwrap();
+        assert_eq!(cfg.provider, "anthropic");
+        assert_eq!(cfg.name, "claude-opus-4-6");
+        assert_eq!(cfg.temperature, Some(0.2));
+        assert_eq!(cfg.max_output_tokens, Some(8000));
+        assert_eq!(cfg.max_tokens, ModelConfig::default().max_tokens);
+    }
+
+    #[test]
+    fn model_params_alone_adjust_the_current_model() {
+        let (_, result) = try_dispatch("/model temperature=1.0 bogus=1", &registry()).unwrap();
+        assert!(result.model_override.is_none());
+        let current = ModelConfig {
+            provider: "openai".into(),
+            name: "gpt-4o".into(),
+            max_tokens: Some(128_000),
+            ..ModelConfig::default()
+        };
+        let cfg = result.resolve_model(&Config::default(), &current).unwrap();
+        assert_eq!(cfg.name, "gpt-4o");
+        assert_eq!(cfg.temperature, Some(1.0));
+        assert_eq!(cfg.max_tokens, Some(128_000));
+        assert_eq!(cfg.max_output_tokens, current.max_output_tokens);
+    }
+
+    #[test]
+    fn model_without_args_resolves_nothing() {
+        let (_, result) = try_dispatch("/model", &registry()).unwrap();
+        assert!(result
+            .resolve_model(&Config::default(), &ModelConfig::default())
+            .is_none());
+    }
+
     #[test]
     fn mode_research_no_trailing_space() {
         let (name, result) = try_dispatch("/mode research", &registry()).unwrap();
@@ -212,7 +323,7 @@ mod dispatch_tests {
         assert_eq!(name, "clear");
         assert!(matches!(
             result.immediate_action,
-            Some(ImmediateAction::ClearChat)
+            Some(ImmediateAction::ClearChat { confirm: true })
         ));
     }
 
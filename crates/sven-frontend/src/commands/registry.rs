@@ -29,12 +29,16 @@ impl CommandRegistry {
         let mut reg = Self::empty();
         reg.register(Arc::new(builtin::abort::AbortCommand));
         reg.register(Arc::new(builtin::clear::ClearCommand));
+        reg.register(Arc::new(builtin::clear::ForceClearCommand));
+        reg.register(Arc::new(builtin::copy::CopyCommand));
         reg.register(Arc::new(builtin::model::ModelCommand));
         reg.register(Arc::new(builtin::new::NewCommand));
         reg.register(Arc::new(builtin::provider::ProviderCommand));
         reg.register(Arc::new(builtin::mode::ModeCommand));
         reg.register(Arc::new(builtin::quit::QuitCommand));
         reg.register(Arc::new(builtin::refresh::RefreshCommand));
+        reg.register(Arc::new(builtin::retry::RetryCommand));
+        reg.register(Arc::new(builtin::title::TitleCommand));
         reg.register(Arc::new(builtin::team::ApproveCommand));
         reg.register(Arc::new(builtin::team::RejectCommand));
         reg.register(Arc::new(builtin::team::AgentsCommand));
@@ -283,6 +283,7 @@ mod tests {
             skill_dir: PathBuf::from("/tmp"),
             content: "body".to_string(),
             sven_meta: None,
+            requires: vec![],
         };
         let cmds = make_command_slash_commands(&[info]);
         assert_eq!(cmds[0].name, "review-code");
@@ -0,0 +1,442 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! Standalone HTML export of a conversation.
+//!
+//! [`render_html`] turns a slice of [`ChatSegment`]s into a single page with
+//! embedded CSS and no external resources: markdown is rendered with
+//! `pulldown-cmark`, fenced code blocks are highlighted with `syntect` using
+//! inline styles, tool calls, results and thinking blocks are collapsible
+//! `<details>` sections, and images stored as data URLs are shown inline.
+//! Raw HTML inside messages is escaped, never passed through, and links to
+//! anything but http(s), mailto or relative targets are rendered as text.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::OnceLock;
+
+use pulldown_cmark::{CodeBlockKind, CowStr, Event, Options, Parser, Tag, TagEnd};
+use sven_model::{ContentPart, MessageContent, Role, ToolContentPart, ToolResultContent};
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::parsing::SyntaxSet;
+
+use crate::segment::ChatSegment;
+
+const STYLE: &str = r#"
+:root { color-scheme: light; }
+body { margin: 0; background: #f6f7f9; color: #1f2328;
+       font: 15px/1.55 -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; }
+main { max-width: 860px; margin: 0 auto; padding: 24px 16px 64px; }
+h1.title { font-size: 1.6em; margin: 0 0 24px; }
+.msg { background: #fff; border: 1px solid #d0d7de; border-radius: 8px;
+       margin: 12px 0; padding: 4px 16px; }
+.msg.user { border-left: 4px solid #0969da; }
+.msg.assistant { border-left: 4px solid #1a7f37; }
+.role { font-size: .8em; font-weight: 600; text-transform: uppercase;
+        color: #57606a; margin: 8px 0 0; }
+details { background: #fff; border: 1px solid #d0d7de; border-radius: 8px;
+          margin: 8px 0; padding: 6px 12px; }
+details > summary { cursor: pointer; color: #57606a; }
+details.thinking { font-style: italic; }
+details.error, .error { border-color: #cf222e; color: #cf222e; }
+.note { color: #57606a; font-size: .9em; margin: 8px 0; text-align: center; }
+pre { overflow-x: auto; padding: 10px 12px; border-radius: 6px; font-size: 13px;
+      background: #f6f8fa; }
+code { font-family: ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; }
+img { max-width: 100%; border-radius: 6px; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #d0d7de; padding: 4px 8px; }
+ul.todos { list-style: none; padding-left: 8px; }
+"#;
+
+/// Theme used for highlighted code blocks.
+const CODE_THEME: &str = "InspiredGitHub";
+
+/// Render `segments` as a complete, self-contained HTML document.
+pub fn render_html(title: &str, segments: &[ChatSegment]) -> String {
+    let tool_names: HashMap<&str, &str> = segments
+        .iter()
+        .filter_map(|s| match s {
+            ChatSegment::Message(m) => match &m.content {
+                MessageContent::ToolCall {
+                    tool_call_id,
+                    function,
+                } => Some((tool_call_id.as_str(), function.name.as_str())),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+
+    let mut body = String::new();
+    for seg in segments {
+        render_segment(&mut body, seg, &tool_names);
+    }
+
+    let title = escape(title);
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <meta name=\"generator\" content=\"sven\">\n<title>{title}</title>\n\
+         <style>{STYLE}</style>\n</head>\n<body>\n<main>\n\
+         <h1 class=\"title\">{title}</h1>\n{body}</main>\n</body>\n</html>\n"
+    )
+}
+
+fn render_segment(out: &mut String, seg: &ChatSegment, tool_names: &HashMap<&str, &str>) {
+    match seg {
+        ChatSegment::Message(m) => match (&m.role, &m.content) {
+            (Role::System, _) => {}
+            (role, MessageContent::Text(text)) => {
+                open_message(out, role);
+                out.push_str(&markdown_to_html(text));
+                out.push_str("</section>\n");
+            }
+            (role, MessageContent::ContentParts(parts)) => {
+                open_message(out, role);
+                for part in parts {
+                    match part {
+                        ContentPart::Text { text } => out.push_str(&markdown_to_html(text)),
+                        ContentPart::Image { image_url, .. } => push_image(out, image_url),
+                    }
+                }
+                out.push_str("</section>\n");
+            }
+            (_, MessageContent::ToolCall { function, .. }) => {
+                let args = serde_json::from_str::<serde_json::Value>(&function.arguments)
+                    .and_then(|v| serde_json::to_string_pretty(&v))
+                    .unwrap_or_else(|_| function.arguments.clone());
+                let _ = writeln!(
+                    out,
+                    "<details class=\"tool-call\"><summary>Tool call: <code>{}</code></summary>\
+                     <pre><code>{}</code></pre></details>",
+                    escape(&function.name),
+                    escape(&args)
+                );
+            }
+            (
+                _,
+                MessageContent::ToolResult {
+                    tool_call_id,
+                    content,
+                },
+            ) => {
+                let name = tool_names
+                    .get(tool_call_id.as_str())
+                    .copied()
+                    .unwrap_or("tool");
+                let _ = write!(
+                    out,
+                    "<details class=\"tool-result\"><summary>Result: <code>{}</code></summary>",
+                    escape(name)
+                );
+                match content {
+                    ToolResultContent::Text(text) => push_pre(out, text),
+                    ToolResultContent::Parts(parts) => {
+                        for part in parts {
+                            match part {
+                                ToolContentPart::Text { text } => push_pre(out, text),
+                                ToolContentPart::Image { image_url } => push_image(out, image_url),
+                            }
+                        }
+                    }
+                }
+                out.push_str("</details>\n");
+            }
+        },
+        ChatSegment::Thinking { content } => {
+            let _ = writeln!(
+                out,
+                "<details class=\"thinking\"><summary>Thinking</summary>{}</details>",
+                markdown_to_html(content)
+            );
+        }
+        ChatSegment::ContextCompacted {
+            tokens_before,
+            tokens_after,
+            strategy,
+            ..
+        } => {
+            let _ = writeln!(
+                out,
+                "<p class=\"note\">Context compacted ({strategy}): \
+                 {tokens_before} → {tokens_after} tokens</p>"
+            );
+        }
+        ChatSegment::Error(msg) => {
+            let _ = writeln!(out, "<p class=\"note error\">Error: {}</p>", escape(msg));
+        }
+        ChatSegment::TodoUpdate(items) => {
+            out.push_str(
+                "<details class=\"todos\"><summary>Todo list</summary><ul class=\"todos\">",
+            );
+            for item in items {
+                let _ = write!(
+                    out,
+                    "<li>{} {}</li>",
+                    item.status.icon(),
+                    escape(&item.content)
+                );
+            }
+            out.push_str("</ul></details>\n");
+        }
+        ChatSegment::CollabEvent(ev) => {
+            let _ = writeln!(
+                out,
+                "<p class=\"note\">{}</p>",
+                escape(&sven_core::prompts::format_collab_event(ev))
+            );
+        }
+        ChatSegment::DelegateSummary {
+            to_name,
+            task_title,
+            status,
+            result_preview,
+            inner,
+            ..
+        } => {
+            let _ = writeln!(
+                out,
+                "<details class=\"delegate\"><summary>Delegated to {}: {} ({})</summary><p>{}</p>",
+                escape(to_name),
+                escape(task_title),
+                escape(status),
+                escape(result_preview)
+            );
+            for seg in inner {
+                render_segment(out, seg, tool_names);
+            }
+            out.push_str("</details>\n");
+        }
+    }
+}
+
+fn open_message(out: &mut String, role: &Role) {
+    let (class, label) = match role {
+        Role::User => ("user", "User"),
+        Role::Assistant => ("assistant", "Sven"),
+        Role::Tool => ("tool", "Tool"),
+        Role::System => ("system", "System"),
+    };
+    let _ = writeln!(
+        out,
+        "<section class=\"msg {class}\"><p class=\"role\">{label}</p>"
+    );
+}
+
+fn push_pre(out: &mut String, text: &str) {
+    let _ = write!(out, "<pre><code>{}</code></pre>", escape(text));
+}
+
+/// Inline images only when they are embedded; anything else would make the
+/// page depend on an external resource, so it is linked instead.
+fn push_image(out: &mut String, url: &str) {
+    if url.starts_with("data:image/") {
+        let _ = write!(out, "<p><img src=\"{}\" alt=\"image\"></p>", escape(url));
+    } else if is_safe_link(url) {
+        let url = escape(url);
+        let _ = write!(out, "<p><a href=\"{url}\">{url}</a></p>");
+    } else {
+        let _ = write!(out, "<p>{}</p>", escape(url));
+    }
+}
+
+/// Whether `url` may become a link target: http(s), mailto or a relative
+/// reference.  Other schemes (`javascript:`, `data:`, `file:`, …) could run
+/// code or reach outside the page when clicked.
+fn is_safe_link(url: &str) -> bool {
+    let url = url.trim_start();
+    let scheme_end = url.find(|c: char| !(c.is_ascii_alphanumeric() || "+-.".contains(c)));
+    match scheme_end {
+        Some(i) if i > 0 && url[i..].starts_with(':') => {
+            let scheme = url[..i].to_ascii_lowercase();
+            matches!(scheme.as_str(), "http" | "https" | "mailto")
+        }
+        _ => true,
+    }
+}
+
+/// Render markdown to HTML, highlighting fenced code blocks and escaping any
+/// raw HTML in the source.
+fn markdown_to_html(text: &str) -> String {
+    let opts = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut events = Vec::new();
+    let mut code: Option<(String, String)> = None;
+    // Set while inside a link or image whose target is dropped; its text is
+    // kept as plain text.
+    let mut dropped_target = false;
+    for event in Parser::new_ext(text, opts) {
+        match (&mut code, event) {
+            (None, Event::Start(Tag::Link { dest_url, .. } | Tag::Image { dest_url, .. }))
+                if !is_safe_link(&dest_url) =>
+            {
+                dropped_target = true;
+            }
+            (None, Event::End(TagEnd::Link | TagEnd::Image)) if dropped_target => {
+                dropped_target = false;
+            }
+            (None, Event::Start(Tag::CodeBlock(kind))) => {
+                let lang = match kind {
+                    CodeBlockKind::Fenced(info) => {
+                        info.split_whitespace().next().unwrap_or("").to_string()
+                    }
+                    CodeBlockKind::Indented => String::new(),
+                };
+                code = Some((lang, String::new()));
+            }
+            (Some((_, buf)), Event::Text(t)) => buf.push_str(&t),
+            (Some(_), Event::End(TagEnd::CodeBlock)) => {
+                let (lang, buf) = code.take().unwrap_or_default();
+                events.push(Event::Html(CowStr::from(highlight(&lang, &buf))));
+            }
+            (Some(_), _) => {}
+            (None, Event::Html(raw) | Event::InlineHtml(raw)) => events.push(Event::Text(raw)),
+            (None, event) => events.push(event),
+        }
+    }
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, events.into_iter());
+    html
+}
+
+fn highlight(lang: &str, code: &str) -> String {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    static THEME: OnceLock<Option<Theme>> = OnceLock::new();
+    let syntaxes = SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines);
+    let theme = THEME.get_or_init(|| ThemeSet::load_defaults().themes.remove(CODE_THEME));
+    let syntax = (!lang.is_empty())
+        .then(|| syntaxes.find_syntax_by_token(lang))
+        .flatten();
+    match (syntax, theme) {
+        (Some(syntax), Some(theme)) => {
+            syntect::html::highlighted_html_for_string(code, syntaxes, syntax, theme)
+                .unwrap_or_else(|_| plain_code(code))
+        }
+        _ => plain_code(code),
+    }
+}
+
+fn plain_code(code: &str) -> String {
+    format!("<pre><code>{}</code></pre>\n", escape(code))
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sven_model::{FunctionCall, Message};
+
+    fn sample() -> Vec<ChatSegment> {
+        vec![
+            ChatSegment::Message(Message::user("Why does **parse** fail on <script>?")),
+            ChatSegment::Thinking {
+                content: "Check the tokenizer.".into(),
+            },
+            ChatSegment::Message(Message {
+                role: Role::Assistant,
+                content: MessageContent::ToolCall {
+                    tool_call_id: "c1".into(),
+                    function: FunctionCall {
+                        name: "read_file".into(),
+                        arguments: r#"{"path":"src/lib.rs"}"#.into(),
+                    },
+                },
+            }),
+            ChatSegment::Message(Message {
+                role: Role::Tool,
+                content: MessageContent::ToolResult {
+                    tool_call_id: "c1".into(),
+                    content: ToolResultContent::Parts(vec![
+                        ToolContentPart::Text {
+                            text: "fn parse() {}".into(),
+                        },
+                        ToolContentPart::Image {
+                            image_url: "data:image/png;base64,iVBORw0KGgo=".into(),
+                        },
+                    ]),
+                },
+            }),
+            ChatSegment::Message(Message::assistant(
+                "The lexer stops early:\n\n```rust\nfn parse() -> u8 { 0 }\n```\n",
+            )),
+        ]
+    }
+
+    #[test]
+    fn renders_a_complete_document_with_all_turns() {
+        let html = render_html("Parser <bug>", &sample());
+        assert!(html.starts_with("<!DOCTYPE html>\n<html lang=\"en\">"));
+        assert!(html.trim_end().ends_with("</html>"));
+        assert!(html.contains("<title>Parser &lt;bug&gt;</title>"));
+        assert!(html.contains("<style>"), "CSS is embedded");
+        assert!(!html.contains("<link"), "no external resources");
+
+        // User text is rendered as markdown, raw HTML is escaped.
+        assert!(html.contains("<section class=\"msg user\">"));
+        assert!(html.contains("<strong>parse</strong>"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(!html.contains("<script>"));
+
+        // Assistant text with a highlighted code block.
+        assert!(html.contains("<section class=\"msg assistant\">"));
+        assert!(html.contains("The lexer stops early:"));
+        assert!(html.contains("<pre style="), "code block is highlighted");
+
+        // Tool call and result are collapsible and paired by id.
+        assert!(html.contains("Tool call: <code>read_file</code>"));
+        assert!(html.contains("Result: <code>read_file</code>"));
+        assert!(html.contains("&quot;path&quot;: &quot;src/lib.rs&quot;"));
+        assert!(html.contains("<img src=\"data:image/png;base64,iVBORw0KGgo=\""));
+        assert!(html.contains("<details class=\"thinking\">"));
+
+        for tag in ["section", "details", "main", "pre"] {
+            let opened = html.matches(&format!("<{tag}")).count();
+            let closed = html.matches(&format!("</{tag}>")).count();
+            assert_eq!(opened, closed, "unbalanced <{tag}>");
+        }
+    }
+
+    #[test]
+    fn remote_images_are_linked_not_embedded() {
+        let mut out = String::new();
+        push_image(&mut out, "https://example.com/a.png");
+        assert_eq!(
+            out,
+            "<p><a href=\"https://example.com/a.png\">https://example.com/a.png</a></p>"
+        );
+    }
+
+    #[test]
+    fn only_web_mail_and_relative_links_keep_their_target() {
+        let html = markdown_to_html(
+            "[a](https://example.com) [b](mailto:x@example.com) [c](docs/a.md) \
+             [d](javascript:alert(1)) [e](JavaScript:alert(1)) [f](data:text/html,x) \
+             ![g](vbscript:x)",
+        );
+        assert!(html.contains("<a href=\"https://example.com\">a</a>"));
+        assert!(html.contains("<a href=\"mailto:x@example.com\">b</a>"));
+        assert!(html.contains("<a href=\"docs/a.md\">c</a>"));
+        assert!(!html.to_lowercase().contains("script:"), "{html}");
+        assert!(!html.contains("data:text"), "{html}");
+        assert!(html.contains(" d ") && html.contains(" e ") && html.contains(" f "));
+        assert!(!html.contains("<img"), "{html}");
+
+        let mut out = String::new();
+        push_image(&mut out, "javascript:alert(1)");
+        assert_eq!(out, "<p>javascript:alert(1)</p>");
+    }
+}
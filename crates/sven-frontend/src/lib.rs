@@ -27,6 +27,7 @@
 
 pub mod agent;
 pub mod commands;
+pub mod export;
 pub mod markdown;
 pub mod node_agent;
 pub mod queue;
@@ -40,6 +41,7 @@ pub use agent::{agent_task, AgentRequest};
 pub use node_agent::{fetch_node_tools, node_agent_task};
 pub use segment::{
     messages_for_resubmit, segment_at_line, segment_editable_text, segment_is_removable,
-    segment_is_rerunnable, segment_short_preview, segment_tool_call_id, ChatSegment,
+    segment_is_rerunnable, segment_short_preview, segment_tool_call_id, segments_from_records,
+    user_message, ChatSegment,
 };
 pub use types::{FrontendOptions, ModelDirective, NodeBackend, QueuedMessage, SessionMeta};
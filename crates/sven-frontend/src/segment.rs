@@ -5,7 +5,8 @@
 //! slices without needing access to any frontend-specific state.
 
 use sven_core::{prompts::CollabEvent, CompactionStrategyUsed};
-use sven_model::{Message, MessageContent, Role};
+use sven_input::ConversationRecord;
+use sven_model::{ContentPart, Message, MessageContent, Role};
 use sven_tools::TodoItem;
 
 /// One entry in the chat display (a concrete message or a display-only note).
@@ -54,6 +55,22 @@ pub fn segment_at_line(segment_line_ranges: &[(usize, usize)], line: usize) -> O
         .position(|&(start, end)| line >= start && line < end)
 }
 
+/// User message of `text` with the `images` (data URLs) attached, or a plain
+/// text message when there are none.
+pub fn user_message(text: &str, images: &[String]) -> Message {
+    if images.is_empty() {
+        return Message::user(text);
+    }
+    let mut parts = Vec::with_capacity(images.len() + 1);
+    if !text.is_empty() {
+        parts.push(ContentPart::Text {
+            text: text.to_string(),
+        });
+    }
+    parts.extend(images.iter().map(ContentPart::image));
+    Message::user_with_parts(parts)
+}
+
 /// If the segment at index `i` is an editable user or assistant text message,
 /// return a clone of its text. Returns `None` for tool calls, results, etc.
 pub fn segment_editable_text(segments: &[ChatSegment], i: usize) -> Option<String> {
@@ -188,6 +205,37 @@ fn sanitize_tool_groups(messages: Vec<Message>) -> Vec<Message> {
     out
 }
 
+/// Convert stored conversation records into display segments.  System
+/// messages are dropped; they are regenerated at runtime and never shown.
+pub fn segments_from_records(records: Vec<ConversationRecord>) -> Vec<ChatSegment> {
+    records
+        .into_iter()
+        .filter_map(|r| match r {
+            ConversationRecord::Message(m) if m.role == Role::System => None,
+            ConversationRecord::Message(m) => Some(ChatSegment::Message(m)),
+            ConversationRecord::Thinking { content } => Some(ChatSegment::Thinking { content }),
+            ConversationRecord::ContextCompacted {
+                tokens_before,
+                tokens_after,
+                strategy,
+                turn,
+            } => {
+                let strategy = match strategy.as_deref() {
+                    Some("emergency") => CompactionStrategyUsed::Emergency,
+                    Some("narrative") => CompactionStrategyUsed::Narrative,
+                    _ => CompactionStrategyUsed::Structured,
+                };
+                Some(ChatSegment::ContextCompacted {
+                    tokens_before,
+                    tokens_after,
+                    strategy,
+                    turn: turn.unwrap_or(0),
+                })
+            }
+        })
+        .collect()
+}
+
 /// Collect the `Message` objects from a segment slice, skipping non-message
 /// entries (ContextCompacted, Error, Thinking, TodoUpdate, CollabEvent,
 /// DelegateSummary). Used when building the payload for a Resubmit request.
@@ -33,6 +33,8 @@ impl ModelDirective {
 #[derive(Debug, Clone)]
 pub struct QueuedMessage {
     pub content: String,
+    /// Data URLs of images pasted into the message.
+    pub images: Vec<String>,
     pub model_transition: Option<ModelDirective>,
     pub mode_transition: Option<AgentMode>,
 }
@@ -41,6 +43,7 @@ impl QueuedMessage {
     pub fn plain(content: String) -> Self {
         Self {
             content,
+            images: Vec::new(),
             model_transition: None,
             mode_transition: None,
         }
@@ -71,6 +74,8 @@ pub struct FrontendOptions {
     pub model_override: Option<String>,
     pub jsonl_path: Option<PathBuf>,
     pub jsonl_load_path: Option<PathBuf>,
+    /// Append new records to `jsonl_path` instead of rewriting it each turn.
+    pub jsonl_append: bool,
     pub initial_queue: Vec<QueuedMessage>,
     /// When `Some`, connect the frontend to a running node.
     pub node_backend: Option<NodeBackend>,
@@ -89,6 +94,7 @@ impl Default for FrontendOptions {
             model_override: None,
             jsonl_path: None,
             jsonl_load_path: None,
+            jsonl_append: false,
             initial_queue: vec![],
             node_backend: None,
             chat_path: None,
@@ -517,6 +517,7 @@ impl SvenApp {
                             .send(AgentRequest::Resubmit {
                                 messages,
                                 new_user_content: content,
+                                images: Vec::new(),
                                 model_override: None,
                                 mode_override: Some(mode_val),
                             })
@@ -552,7 +553,7 @@ impl SvenApp {
                             let ch3 = Arc::clone(&cancel_handle_sm);
                             let _pt3 = Arc::clone(&pt_send);
                             match action {
-                                ImmediateAction::ClearChat => {
+                                ImmediateAction::ClearChat { .. } => {
                                     let _ = slint::invoke_from_event_loop(move || {
                                         *sb3.lock().unwrap() = String::new();
                                         *tb3.lock().unwrap() = String::new();
@@ -623,6 +624,7 @@ impl SvenApp {
                             if agent_busy {
                                 queue.lock().unwrap().push(QueuedMessage {
                                     content: msg,
+                                    images: Vec::new(),
                                     model_transition: None,
                                     mode_transition: result.mode_override,
                                 });
@@ -695,6 +697,7 @@ impl SvenApp {
                 if agent_busy {
                     queue.lock().unwrap().push(QueuedMessage {
                         content: content.clone(),
+                        images: Vec::new(),
                         model_transition: None,
                         mode_transition: None,
                     });
@@ -1570,13 +1573,11 @@ impl SvenApp {
                 let title = win.get_picker_title().to_string();
 
                 if title == "Switch mode" {
-                    let mode = match id.as_str() {
-                        "plan" => AgentMode::Plan,
-                        "research" => AgentMode::Research,
-                        _ => AgentMode::Agent,
-                    };
-                    *cur_mode_ps.lock().unwrap() = mode;
-                    win.set_mode(SharedString::from(id));
+                    // The picker offers the built-in modes only.
+                    if let Some(mode) = AgentMode::parse(&id) {
+                        *cur_mode_ps.lock().unwrap() = mode;
+                        win.set_mode(SharedString::from(id));
+                    }
                 } else {
                     let parts: Vec<&str> = id.splitn(2, '/').collect();
                     if parts.len() == 2 {
@@ -2044,6 +2045,7 @@ impl SvenApp {
                         max_tokens,
                         max_output_tokens,
                         cost_usd,
+                        estimated: _,
                     } => {
                         let ctx_pct = if max_tokens > 0 {
                             let budget = max_tokens.saturating_sub(max_output_tokens);
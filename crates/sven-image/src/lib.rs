@@ -81,16 +81,25 @@ impl EncodedImage {
 /// PNG images are re-encoded as PNG; everything else is re-encoded as JPEG.
 pub fn load_image(path: &Path) -> Result<EncodedImage, ImageError> {
     let raw = std::fs::read(path).map_err(|e| ImageError::Io(path.display().to_string(), e))?;
+    load_cached(&raw, path)
+}
+
+/// [`load_image`] for image bytes already in memory, e.g. read from the
+/// clipboard.  The format is detected from the bytes alone.
+pub fn load_image_bytes(raw: &[u8]) -> Result<EncodedImage, ImageError> {
+    load_cached(raw, Path::new("(clipboard)"))
+}
 
+fn load_cached(raw: &[u8], hint_path: &Path) -> Result<EncodedImage, ImageError> {
     // Content-addressed cache lookup.
-    let key: CacheKey = sha2::Sha256::digest(&raw).into();
+    let key: CacheKey = sha2::Sha256::digest(raw).into();
     if let Ok(mut cache) = image_cache().lock() {
         if let Some(cached) = cache.get(&key) {
             return Ok(cached.clone());
         }
     }
 
-    let result = encode_image_bytes(&raw, path)?;
+    let result = encode_image_bytes(raw, hint_path)?;
 
     if let Ok(mut cache) = image_cache().lock() {
         cache.put(key, result.clone());
@@ -184,6 +193,12 @@ fn resize_if_needed(img: image::DynamicImage) -> image::DynamicImage {
     img.resize(new_w, new_h, image::imageops::FilterType::Lanczos3)
 }
 
+/// MIME type of the image format `raw` is encoded in, detected from its
+/// leading bytes, or `None` when `raw` is not a recognized image.
+pub fn sniff_image_mime(raw: &[u8]) -> Option<&'static str> {
+    image::guess_format(raw).ok().map(|fmt| fmt.to_mime_type())
+}
+
 /// Return whether the given file extension belongs to a supported image format.
 pub fn is_image_extension(ext: &str) -> bool {
     matches!(
@@ -198,6 +213,19 @@ pub fn is_image_extension(ext: &str) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn load_image_bytes_detects_format_from_content() {
+        let png = B64
+            .decode("iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNkYPhfDwAChwGA60e6kgAAAABJRU5ErkJggg==")
+            .unwrap();
+        let img = load_image_bytes(&png).unwrap();
+        assert_eq!(img.mime_type, "image/png");
+        assert!(matches!(
+            load_image_bytes(b"not an image"),
+            Err(ImageError::UnsupportedFormat(_))
+        ));
+    }
+
     #[test]
     fn parse_data_url_valid() {
         let url = "data:image/png;base64,aGVsbG8=";
@@ -269,14 +269,17 @@ pub fn parse_chat_document(yaml: &str) -> Result<ChatDocument> {
 /// a `ChatDocument`.
 ///
 /// System messages are skipped — the agent regenerates the system prompt at
-/// runtime from the current config.
+/// runtime from the current config — and the rest pass through
+/// [`sven_model::sanitize::for_persistence`].
 pub fn records_to_turns(records: &[ConversationRecord]) -> Vec<TurnRecord> {
     records.iter().filter_map(record_to_turn).collect()
 }
 
 fn record_to_turn(record: &ConversationRecord) -> Option<TurnRecord> {
     match record {
-        ConversationRecord::Message(m) => message_to_turn(m),
+        ConversationRecord::Message(m) => sven_model::sanitize::message_for_persistence(m.clone())
+            .as_ref()
+            .and_then(message_to_turn),
         ConversationRecord::Thinking { content } => Some(TurnRecord::Thinking {
             content: content.clone(),
         }),
@@ -463,7 +466,7 @@ pub fn save_chat(doc: &mut ChatDocument) -> Result<()> {
     let dir = ensure_chat_dir()?;
     let path = dir.join(format!("{}.yaml", doc.id));
     let content = serialize_chat_document(doc)?;
-    std::fs::write(&path, content)
+    crate::write_atomic(&path, content)
         .with_context(|| format!("writing chat document to {}", path.display()))
 }
 
@@ -471,7 +474,7 @@ pub fn save_chat(doc: &mut ChatDocument) -> Result<()> {
 pub fn save_chat_to(path: &Path, doc: &mut ChatDocument) -> Result<()> {
     doc.touch();
     let content = serialize_chat_document(doc)?;
-    std::fs::write(path, content)
+    crate::write_atomic(path, content)
         .with_context(|| format!("writing chat document to {}", path.display()))
 }
 
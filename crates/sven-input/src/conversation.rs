@@ -90,6 +90,56 @@ pub struct ConversationFile {
     pub pending_user_input: Option<String>,
 }
 
+/// A conversation markdown file parsed into full-fidelity records.
+///
+/// Unlike [`ConversationFile`] this keeps thinking blocks and
+/// context-compaction notes, so serializing it with
+/// [`ParsedConversation::to_markdown`] loses nothing the markdown format can
+/// express.
+#[derive(Debug, Clone, Default)]
+pub struct ParsedConversation {
+    /// Optional H1 title of the conversation.
+    pub title: Option<String>,
+    /// Every segment of the conversation in file order.
+    pub records: Vec<ConversationRecord>,
+    /// Trailing `## User` section without a response, if any.
+    pub pending_user_input: Option<String>,
+}
+
+impl ParsedConversation {
+    /// The message records only, i.e. the history to seed an agent with.
+    pub fn messages(&self) -> Vec<Message> {
+        self.records
+            .iter()
+            .filter_map(|r| match r {
+                ConversationRecord::Message(m) => Some(m.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Serialize back into conversation markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut result = String::new();
+        if let Some(t) = &self.title {
+            result.push_str(&format!("# {t}\n\n"));
+        }
+        result.push_str(&serialize_conversation_records(&self.records, None));
+        if let Some(pending) = &self.pending_user_input {
+            result.push_str(&format!("## User\n{}\n\n", pending.trim()));
+        }
+        result
+    }
+}
+
+/// Serialize `conversation` to markdown and parse it again.
+///
+/// Used by tests to check that a conversation survives the markdown format;
+/// compare the segments of the result against the input.
+pub fn round_trip(conversation: &ParsedConversation) -> Result<ParsedConversation, ParseError> {
+    parse_conversation_records(&conversation.to_markdown())
+}
+
 /// A raw H2 section parsed from the markdown file.
 #[derive(Debug)]
 struct Section {
@@ -105,9 +155,18 @@ enum SectionKind {
     Sven,
     Tool,
     ToolResult,
+    /// A ```` ```sven-thinking ```` block; never produced from a heading.
+    Thinking,
+    /// A ```` ```sven-compacted ```` block; never produced from a heading.
+    Compacted,
     Unknown(String),
 }
 
+/// Info string of the fenced block holding a [`ConversationRecord::Thinking`].
+const THINKING_MARKER: &str = "sven-thinking";
+/// Info string of the fenced block holding a [`ConversationRecord::ContextCompacted`].
+const COMPACTED_MARKER: &str = "sven-compacted";
+
 impl SectionKind {
     fn from_str(s: &str) -> Self {
         match s.trim() {
@@ -137,8 +196,15 @@ pub enum ParseError {
     InvalidToolJson(String),
     #[error("## Tool section missing JSON code block")]
     MissingToolJson,
+    #[error("```sven-compacted block contains invalid JSON: {0}")]
+    InvalidCompactionJson(String),
     #[error("JSONL line {line} contains invalid JSON: {error}")]
     InvalidJsonlLine { line: usize, error: String },
+    #[error(
+        "JSONL schema version {found} is newer than the supported version {supported}; \
+         upgrade sven to load this file"
+    )]
+    UnsupportedSchemaVersion { found: u32, supported: u32 },
 }
 
 /// Parse a conversation markdown file into history messages and optional pending input.
@@ -167,8 +233,32 @@ pub enum ParseError {
 /// If the file ends with a `## User` section, it is returned as `pending_user_input`
 /// and not included in `history`.
 pub fn parse_conversation(markdown: &str) -> Result<ConversationFile, ParseError> {
+    let parsed = parse_conversation_records(markdown)?;
+    Ok(ConversationFile {
+        history: parsed.messages(),
+        title: parsed.title,
+        pending_user_input: parsed.pending_user_input,
+    })
+}
+
+/// Parse a conversation markdown file, keeping thinking blocks and
+/// context-compaction notes.
+///
+/// Besides the H2 sections understood by [`parse_conversation`], two fenced
+/// blocks at the start of a line are recognised as segments of their own:
+///
+/// ````markdown
+/// ```sven-thinking
+/// The user wants the module list; start with the workspace manifest.
+/// ```
+///
+/// ```sven-compacted
+/// {"tokens_before":91000,"tokens_after":12000,"strategy":"structured","turn":3}
+/// ```
+/// ````
+pub fn parse_conversation_records(markdown: &str) -> Result<ParsedConversation, ParseError> {
     let (title, sections) = split_sections(markdown);
-    convert_sections_to_conversation(title, sections)
+    convert_sections_to_records(title, sections)
 }
 
 // ── Internal helpers ──────────────────────────────────────────────────────────
@@ -179,22 +269,52 @@ pub fn parse_conversation(markdown: &str) -> Result<ConversationFile, ParseError
 /// `Tool Result`) are treated as structural boundaries.  Any other `## …`
 /// line — for example a heading the agent wrote inside its own response —
 /// is kept as literal content within the current section.
+///
+/// Thinking and compaction marker blocks become sections of their own; text
+/// between such a block and the next heading is dropped like a preamble.
 fn split_sections(markdown: &str) -> (Option<String>, Vec<Section>) {
     let mut title: Option<String> = None;
     let mut sections: Vec<Section> = Vec::new();
     let mut current_heading: Option<SectionKind> = None;
     let mut current_content = String::new();
     let mut preamble = String::new();
+    let mut lines = markdown.lines();
 
-    for line in markdown.lines() {
+    while let Some(line) = lines.next() {
         // H1 — title, only before any section starts
         if let Some(h1) = line.strip_prefix("# ").filter(|_| !line.starts_with("## ")) {
-            if current_heading.is_none() && preamble.is_empty() && title.is_none() {
+            if current_heading.is_none()
+                && sections.is_empty()
+                && preamble.is_empty()
+                && title.is_none()
+            {
                 title = Some(h1.trim().to_string());
                 continue;
             }
         }
 
+        if let Some((fence, kind)) = parse_marker_fence(line) {
+            if let Some(heading) = current_heading.take() {
+                sections.push(Section {
+                    heading,
+                    content: current_content.trim_matches('\n').to_string(),
+                });
+            }
+            current_content = String::new();
+            let mut body: Vec<&str> = Vec::new();
+            for inner in lines.by_ref() {
+                if inner.trim_end() == fence {
+                    break;
+                }
+                body.push(inner);
+            }
+            sections.push(Section {
+                heading: kind,
+                content: body.join("\n"),
+            });
+            continue;
+        }
+
         // H2 — only start a new section if the heading is a recognized kind
         if let Some(h2) = line.strip_prefix("## ") {
             let kind = SectionKind::from_str(h2.trim());
@@ -230,12 +350,27 @@ fn split_sections(markdown: &str) -> (Option<String>, Vec<Section>) {
     (title, sections)
 }
 
-/// Convert a list of sections into a `ConversationFile`.
-fn convert_sections_to_conversation(
+/// Recognise the opening fence of a thinking or compaction marker block and
+/// return the fence (to match the closing line) and the section kind.
+fn parse_marker_fence(line: &str) -> Option<(&str, SectionKind)> {
+    let ticks = line.len() - line.trim_start_matches('`').len();
+    if ticks < 3 {
+        return None;
+    }
+    let kind = match line[ticks..].trim_end() {
+        THINKING_MARKER => SectionKind::Thinking,
+        COMPACTED_MARKER => SectionKind::Compacted,
+        _ => return None,
+    };
+    Some((&line[..ticks], kind))
+}
+
+/// Convert a list of sections into a `ParsedConversation`.
+fn convert_sections_to_records(
     title: Option<String>,
     sections: Vec<Section>,
-) -> Result<ConversationFile, ParseError> {
-    let mut history: Vec<Message> = Vec::new();
+) -> Result<ParsedConversation, ParseError> {
+    let mut history: Vec<ConversationRecord> = Vec::new();
     let mut pending_tool_call_id: Option<String> = None;
     let mut iter = sections.into_iter().peekable();
 
@@ -251,26 +386,30 @@ fn convert_sections_to_conversation(
                 // we'll handle it after the loop.
                 if iter.peek().is_none() {
                     // Last section — treat as pending input
-                    return Ok(ConversationFile {
+                    return Ok(ParsedConversation {
                         title,
-                        history,
+                        records: history,
                         pending_user_input: Some(content.to_string().trim().to_string()),
                     });
                 }
-                history.push(Message::user(content.to_string().trim()));
+                history.push(ConversationRecord::Message(Message::user(
+                    content.to_string().trim(),
+                )));
             }
 
             SectionKind::Sven => {
                 // Strip HTML metadata comments (<!-- provider: openai, model: gpt-4o -->)
                 // that appear at the start of Sven sections
                 let content = strip_leading_html_comment(&section.content);
-                history.push(Message::assistant(content.trim()));
+                history.push(ConversationRecord::Message(Message::assistant(
+                    content.trim(),
+                )));
             }
 
             SectionKind::Tool => {
                 let envelope = parse_tool_envelope(&section.content)?;
                 pending_tool_call_id = Some(envelope.tool_call_id.clone());
-                history.push(Message {
+                history.push(ConversationRecord::Message(Message {
                     role: Role::Assistant,
                     content: MessageContent::ToolCall {
                         tool_call_id: envelope.tool_call_id,
@@ -279,7 +418,7 @@ fn convert_sections_to_conversation(
                             arguments: envelope.args.to_string(),
                         },
                     },
-                });
+                }));
             }
 
             SectionKind::ToolResult => {
@@ -288,14 +427,34 @@ fn convert_sections_to_conversation(
                     None => return Err(ParseError::OrphanedToolResult),
                 };
                 let content = extract_code_block_content(&section.content);
-                history.push(Message::tool_result(call_id, content.to_string().trim()));
+                history.push(ConversationRecord::Message(Message::tool_result(
+                    call_id,
+                    content.to_string().trim(),
+                )));
+            }
+
+            SectionKind::Thinking => {
+                history.push(ConversationRecord::Thinking {
+                    content: section.content,
+                });
+            }
+
+            SectionKind::Compacted => {
+                let data: serde_json::Value = serde_json::from_str(&section.content)
+                    .map_err(|e| ParseError::InvalidCompactionJson(e.to_string()))?;
+                let record = serde_json::from_value(serde_json::json!({
+                    "type": "context_compacted",
+                    "data": data,
+                }))
+                .map_err(|e| ParseError::InvalidCompactionJson(e.to_string()))?;
+                history.push(record);
             }
         }
     }
 
-    Ok(ConversationFile {
+    Ok(ParsedConversation {
         title,
-        history,
+        records: history,
         pending_user_input: None,
     })
 }
@@ -455,6 +614,16 @@ pub fn parse_jsonl_conversation(content: &str) -> Result<ConversationFile, Parse
 
 // ── Full-fidelity JSONL record format ─────────────────────────────────────────
 
+/// Current version of the full-fidelity JSONL format.
+///
+/// [`serialize_jsonl_records`] writes it as a leading
+/// `{"type":"meta","schema_version":N}` line.  Files without that line are
+/// version 0 and are upgraded on load by [`parse_jsonl_full`].
+pub const JSONL_SCHEMA_VERSION: u32 = 1;
+
+const META_TYPE: &str = "meta";
+const METRICS_TYPE: &str = "metrics";
+
 /// A single record in a full-fidelity JSONL conversation file.
 ///
 /// Unlike the old raw-`Message` JSONL format this type captures every element
@@ -465,6 +634,10 @@ pub fn parse_jsonl_conversation(content: &str) -> Result<ConversationFile, Parse
 /// - Message:          `{"type":"message","data":{<Message fields>}}`
 /// - Thinking:         `{"type":"thinking","data":{"content":"..."}}`
 /// - ContextCompacted: `{"type":"context_compacted","data":{"tokens_before":N,"tokens_after":M,...}}`
+///
+/// The file-level `{"type":"meta",...}` line is not a record; see
+/// [`JSONL_SCHEMA_VERSION`].  Neither are `{"type":"metrics",...}` lines
+/// (see [`jsonl_metrics_line`]), which readers skip.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "data", rename_all = "snake_case")]
 pub enum ConversationRecord {
@@ -505,46 +678,77 @@ pub struct ParsedJsonlConversation {
     /// use this as the `system_prompt_override` so that resumed conversations
     /// use the exact same prompt they were started with.
     pub system_message: Option<String>,
+    /// Schema version the file was written with.  `0` means a versionless
+    /// legacy file that was upgraded on load.
+    pub schema_version: u32,
 }
 
 /// Parse a full-fidelity JSONL conversation file.
 ///
-/// Handles both the new `ConversationRecord` tagged format and the legacy raw
-/// `Message` format so that files written by older versions of sven still load.
+/// Reads the leading meta line (if any) and upgrades older files to
+/// [`JSONL_SCHEMA_VERSION`] before decoding records, so files written by older
+/// versions of sven still load.  Files from a newer version are rejected with
+/// [`ParseError::UnsupportedSchemaVersion`].  A meta line anywhere but first,
+/// e.g. from concatenated streams, is logged and ignored.
 pub fn parse_jsonl_full(content: &str) -> Result<ParsedJsonlConversation, ParseError> {
-    let mut records: Vec<ConversationRecord> = Vec::new();
+    let mut schema_version = 0;
+    let mut lines: Vec<(usize, serde_json::Value)> = Vec::new();
+    let mut leading = true;
 
     for (line_no, line) in content.lines().enumerate() {
         let line = line.trim();
         if line.is_empty() {
             continue;
         }
+        let invalid = |error: String| ParseError::InvalidJsonlLine {
+            line: line_no + 1,
+            error,
+        };
 
         let v: serde_json::Value =
-            serde_json::from_str(line).map_err(|e| ParseError::InvalidJsonlLine {
-                line: line_no + 1,
-                error: e.to_string(),
-            })?;
-
-        if v.get("type").is_some() {
-            // New tagged format.
-            let record: ConversationRecord =
-                serde_json::from_value(v).map_err(|e| ParseError::InvalidJsonlLine {
-                    line: line_no + 1,
-                    error: e.to_string(),
-                })?;
-            records.push(record);
-        } else {
-            // Legacy format — raw Message JSON.
-            let msg: Message =
-                serde_json::from_value(v).map_err(|e| ParseError::InvalidJsonlLine {
-                    line: line_no + 1,
-                    error: e.to_string(),
-                })?;
-            records.push(ConversationRecord::Message(msg));
+            serde_json::from_str(line).map_err(|e| invalid(e.to_string()))?;
+
+        let first = std::mem::replace(&mut leading, false);
+        if v.get("type").and_then(|t| t.as_str()) == Some(META_TYPE) {
+            if !first {
+                tracing::warn!(
+                    line = line_no + 1,
+                    "ignoring JSONL meta line that is not the first line"
+                );
+                continue;
+            }
+            let version = v
+                .get("schema_version")
+                .and_then(|n| n.as_u64())
+                .and_then(|n| u32::try_from(n).ok())
+                .ok_or_else(|| invalid("meta record has no valid schema_version".into()))?;
+            if version > JSONL_SCHEMA_VERSION {
+                return Err(ParseError::UnsupportedSchemaVersion {
+                    found: version,
+                    supported: JSONL_SCHEMA_VERSION,
+                });
+            }
+            schema_version = version;
+            continue;
+        }
+        if v.get("type").and_then(|t| t.as_str()) == Some(METRICS_TYPE) {
+            continue;
         }
+        lines.push((line_no + 1, v));
     }
 
+    migrate_jsonl(&mut lines, schema_version);
+
+    let records = lines
+        .into_iter()
+        .map(|(line, v)| {
+            serde_json::from_value(v).map_err(|e| ParseError::InvalidJsonlLine {
+                line,
+                error: e.to_string(),
+            })
+        })
+        .collect::<Result<Vec<ConversationRecord>, _>>()?;
+
     // Extract the first system message text (if any) for the caller to reuse.
     let system_message: Option<String> = records.iter().find_map(|r| {
         if let ConversationRecord::Message(m) = r {
@@ -582,17 +786,54 @@ pub fn parse_jsonl_full(content: &str) -> Result<ParsedJsonlConversation, ParseE
         history,
         pending_user_input,
         system_message,
+        schema_version,
     })
 }
 
-/// Serialize a slice of `ConversationRecord`s to JSONL.
+/// Upgrade raw JSONL lines written with schema version `from` to
+/// [`JSONL_SCHEMA_VERSION`], one version step at a time.
+fn migrate_jsonl(lines: &mut [(usize, serde_json::Value)], from: u32) {
+    for version in from..JSONL_SCHEMA_VERSION {
+        if version == 0 {
+            // Versionless files may mix tagged records with raw `Message`
+            // lines written before records were introduced.
+            for (_, v) in lines.iter_mut() {
+                if v.get("type").is_none() {
+                    *v = serde_json::json!({ "type": "message", "data": v.take() });
+                }
+            }
+        }
+    }
+}
+
+/// The `{"type":"meta","schema_version":N}` line that opens a JSONL file,
+/// including the trailing newline.
+///
+/// Emit it once before streaming records one by one; [`serialize_jsonl_records`]
+/// already includes it.
+pub fn jsonl_meta_line() -> String {
+    let meta = serde_json::json!({ "type": META_TYPE, "schema_version": JSONL_SCHEMA_VERSION });
+    format!("{meta}\n")
+}
+
+/// A `{"type":"metrics","data":...}` line, including the trailing newline.
 ///
-/// Each record becomes one line.  Suitable for both full-file writes and
-/// append-only updates.
+/// Carries run statistics alongside the records in a JSONL stream;
+/// [`parse_jsonl_full`] skips it, so the stream still loads as a conversation.
+pub fn jsonl_metrics_line(data: &serde_json::Value) -> String {
+    let line = serde_json::json!({ "type": METRICS_TYPE, "data": data });
+    format!("{line}\n")
+}
+
+/// Serialize a slice of `ConversationRecord`s to a complete JSONL file.
+///
+/// The output starts with the [`jsonl_meta_line`] followed by one line per
+/// record.  Messages pass through [`sven_model::sanitize::for_persistence`]
+/// first, so a message that held only injected content is left out.
 pub fn serialize_jsonl_records(records: &[ConversationRecord]) -> String {
-    let mut result = String::new();
-    for record in records {
-        match serde_json::to_string(record) {
+    let mut result = jsonl_meta_line();
+    for record in records.iter().filter_map(record_for_persistence) {
+        match serde_json::to_string(&record) {
             Ok(line) => {
                 result.push_str(&line);
                 result.push('\n');
@@ -607,15 +848,16 @@ pub fn serialize_jsonl_records(records: &[ConversationRecord]) -> String {
 
 /// Serialize a slice of messages as JSONL lines for appending to a conversation file.
 ///
-/// System messages are skipped (they are re-injected by the agent at runtime).
+/// System messages are skipped (they are re-injected by the agent at runtime)
+/// and the rest pass through [`sven_model::sanitize::for_persistence`].
 /// Each message is serialized as a single JSON line followed by a newline.
 pub fn serialize_jsonl_conversation_turn(messages: &[Message]) -> String {
     let mut result = String::new();
-    for msg in messages {
+    for msg in sven_model::sanitize::for_persistence(messages.to_vec()) {
         if msg.role == Role::System {
             continue;
         }
-        match serde_json::to_string(msg) {
+        match serde_json::to_string(&msg) {
             Ok(line) => {
                 result.push_str(&line);
                 result.push('\n');
@@ -628,6 +870,16 @@ pub fn serialize_jsonl_conversation_turn(messages: &[Message]) -> String {
     result
 }
 
+/// `record` as it should be saved: messages pass through
+/// [`sven_model::sanitize::message_for_persistence`], other records are kept.
+pub(crate) fn record_for_persistence(record: &ConversationRecord) -> Option<ConversationRecord> {
+    match record {
+        ConversationRecord::Message(m) => sven_model::sanitize::message_for_persistence(m.clone())
+            .map(ConversationRecord::Message),
+        other => Some(other.clone()),
+    }
+}
+
 // ── Serializer ────────────────────────────────────────────────────────────────
 
 /// Serialize a slice of messages into conversation markdown sections.
@@ -669,6 +921,44 @@ pub fn serialize_conversation(title: Option<&str>, messages: &[Message]) -> Stri
     result
 }
 
+/// Serialize full-fidelity records into conversation markdown.
+///
+/// Messages are written like [`serialize_conversation_turn_with_metadata`];
+/// thinking blocks and compaction notes become ```` ```sven-thinking ```` and
+/// ```` ```sven-compacted ```` fenced blocks that [`parse_conversation_records`]
+/// reads back.
+pub fn serialize_conversation_records(
+    records: &[ConversationRecord],
+    metadata: Option<&TurnMetadata>,
+) -> String {
+    let mut result = String::new();
+    for record in records {
+        match record {
+            ConversationRecord::Message(msg) => {
+                result.push_str(&message_to_section_with_metadata(msg, metadata));
+            }
+            ConversationRecord::Thinking { content } => {
+                result.push_str(&marker_block(THINKING_MARKER, content));
+            }
+            ConversationRecord::ContextCompacted { .. } => {
+                let data = serde_json::to_value(record)
+                    .map(|mut v| v["data"].take())
+                    .unwrap_or_default();
+                result.push_str(&marker_block(COMPACTED_MARKER, &data.to_string()));
+            }
+        }
+    }
+    result
+}
+
+/// A fenced block tagged with `marker`, fenced with more backticks than any
+/// run inside `body` so the body cannot close it early.
+fn marker_block(marker: &str, body: &str) -> String {
+    let longest_run = body.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!("{fence}{marker}\n{body}\n{fence}\n\n")
+}
+
 #[cfg_attr(not(test), allow(dead_code))]
 fn message_to_section(msg: &Message) -> String {
     message_to_section_with_metadata(msg, None)
@@ -1325,6 +1615,266 @@ mod tests {
         }
     }
 
+    // ── Markdown round-trip fidelity ──────────────────────────────────────────
+
+    fn kind(record: &ConversationRecord) -> &'static str {
+        match record {
+            ConversationRecord::Message(m) => match (&m.role, &m.content) {
+                (Role::User, _) => "user",
+                (Role::Assistant, MessageContent::ToolCall { .. }) => "tool_call",
+                (Role::Assistant, _) => "assistant",
+                (Role::Tool, _) => "tool_result",
+                (Role::System, _) => "system",
+            },
+            ConversationRecord::Thinking { .. } => "thinking",
+            ConversationRecord::ContextCompacted { .. } => "compacted",
+        }
+    }
+
+    /// Building blocks for generated conversations; each unit is a valid
+    /// sequence on its own.
+    fn units() -> Vec<Vec<ConversationRecord>> {
+        use sven_model::FunctionCall;
+        vec![
+            vec![ConversationRecord::Message(user_msg("List the modules."))],
+            vec![ConversationRecord::Message(sven_msg(
+                "Here they are:\n\n## Not a section\n- core",
+            ))],
+            vec![
+                ConversationRecord::Message(Message {
+                    role: Role::Assistant,
+                    content: MessageContent::ToolCall {
+                        tool_call_id: "call_1".into(),
+                        function: FunctionCall {
+                            name: "list_dir".into(),
+                            arguments: r#"{"path":"."}"#.into(),
+                        },
+                    },
+                }),
+                ConversationRecord::Message(Message::tool_result("call_1", "src/\ncrates/")),
+            ],
+            vec![ConversationRecord::Thinking {
+                content: "Check the manifest.\n\n```toml\n[workspace]\n```\nThen ``` more.".into(),
+            }],
+            vec![ConversationRecord::ContextCompacted {
+                tokens_before: 91_000,
+                tokens_after: 12_000,
+                strategy: Some("structured".into()),
+                turn: Some(3),
+            }],
+            vec![ConversationRecord::ContextCompacted {
+                tokens_before: 10,
+                tokens_after: 5,
+                strategy: None,
+                turn: None,
+            }],
+        ]
+    }
+
+    #[test]
+    fn markdown_round_trip_preserves_segment_kinds_and_content() {
+        let units = units();
+        let n = units.len();
+        let mut cases = 0;
+        // Every sequence of up to three units, with and without a title and
+        // a pending user turn.
+        for len in 1..=3u32 {
+            for mut index in 0..n.pow(len) {
+                let mut records = Vec::new();
+                for _ in 0..len {
+                    records.extend(units[index % n].iter().cloned());
+                    index /= n;
+                }
+                // A trailing user turn would be read back as pending input.
+                records.push(ConversationRecord::Message(sven_msg("Done.")));
+                let conv = ParsedConversation {
+                    title: (cases % 2 == 0).then(|| "Modules".to_string()),
+                    records,
+                    pending_user_input: (cases % 3 == 0).then(|| "Next task".to_string()),
+                };
+
+                let back = round_trip(&conv).unwrap();
+                let kinds = |c: &ParsedConversation| c.records.iter().map(kind).collect::<Vec<_>>();
+                assert_eq!(kinds(&back), kinds(&conv), "{}", conv.to_markdown());
+                assert_eq!(
+                    serde_json::to_value(&back.records).unwrap(),
+                    serde_json::to_value(&conv.records).unwrap(),
+                    "{}",
+                    conv.to_markdown()
+                );
+                assert_eq!(back.title, conv.title);
+                assert_eq!(back.pending_user_input, conv.pending_user_input);
+                cases += 1;
+            }
+        }
+        assert_eq!(cases, n + n * n + n * n * n);
+    }
+
+    #[test]
+    fn parse_conversation_skips_marker_blocks_in_history() {
+        let md = "## User\nQ\n\n```sven-thinking\nhmm\n```\n\n## Sven\nA\n\n\
+                  ```sven-compacted\n{\"tokens_before\":9,\"tokens_after\":3}\n```\n\n\
+                  ## User\nNext\n";
+        let conv = parse_conversation(md).unwrap();
+        assert_eq!(conv.history.len(), 2);
+        assert_eq!(conv.history[0].as_text(), Some("Q"));
+        assert_eq!(conv.history[1].as_text(), Some("A"));
+        assert_eq!(conv.pending_user_input.as_deref(), Some("Next"));
+
+        let parsed = parse_conversation_records(md).unwrap();
+        let kinds: Vec<_> = parsed.records.iter().map(kind).collect();
+        assert_eq!(kinds, ["user", "thinking", "assistant", "compacted"]);
+    }
+
+    #[test]
+    fn invalid_compaction_block_is_an_error() {
+        let md = "## User\nQ\n\n```sven-compacted\nnot json\n```\n";
+        assert!(matches!(
+            parse_conversation_records(md),
+            Err(ParseError::InvalidCompactionJson(_))
+        ));
+    }
+
+    // ── JSONL schema versioning ───────────────────────────────────────────────
+
+    #[test]
+    fn jsonl_full_round_trip_current_version() {
+        let records = vec![
+            ConversationRecord::Message(Message::system("You are sven.")),
+            ConversationRecord::Message(user_msg("Task")),
+            ConversationRecord::Thinking {
+                content: "hmm".into(),
+            },
+            ConversationRecord::Message(sven_msg("Done.")),
+            ConversationRecord::ContextCompacted {
+                tokens_before: 100,
+                tokens_after: 10,
+                strategy: Some("structured".into()),
+                turn: None,
+            },
+        ];
+        let jsonl = serialize_jsonl_records(&records);
+        let first: serde_json::Value = serde_json::from_str(jsonl.lines().next().unwrap()).unwrap();
+        assert_eq!(first["type"], "meta");
+        assert_eq!(first["schema_version"], JSONL_SCHEMA_VERSION);
+
+        let parsed = parse_jsonl_full(&jsonl).unwrap();
+        assert_eq!(parsed.schema_version, JSONL_SCHEMA_VERSION);
+        assert_eq!(
+            serde_json::to_value(&parsed.records).unwrap(),
+            serde_json::to_value(&records).unwrap()
+        );
+        assert_eq!(parsed.system_message.as_deref(), Some("You are sven."));
+        assert_eq!(parsed.history.len(), 2);
+        assert_eq!(serialize_jsonl_records(&parsed.records), jsonl);
+    }
+
+    #[test]
+    fn jsonl_serialize_strips_injected_reminders() {
+        let reminder = "<system-reminder>Today is Monday.</system-reminder>";
+        let records = vec![
+            ConversationRecord::Message(user_msg(&format!("{reminder}\nTask"))),
+            ConversationRecord::Message(user_msg(reminder)),
+            ConversationRecord::Message(sven_msg("Done.")),
+        ];
+        let parsed = parse_jsonl_full(&serialize_jsonl_records(&records)).unwrap();
+        let texts: Vec<_> = parsed
+            .history
+            .iter()
+            .map(|m| m.as_text().unwrap())
+            .collect();
+        assert_eq!(texts, ["Task", "Done."]);
+    }
+
+    #[test]
+    fn jsonl_full_loads_versionless_legacy_file() {
+        // Written before the meta line existed: raw `Message` lines mixed
+        // with tagged records.
+        let legacy = format!(
+            "{}\n{}\n{}\n",
+            serde_json::to_string(&user_msg("Old task")).unwrap(),
+            r#"{"type":"thinking","data":{"content":"hmm"}}"#,
+            serde_json::to_string(&sven_msg("Old answer")).unwrap(),
+        );
+        let parsed = parse_jsonl_full(&legacy).unwrap();
+        assert_eq!(parsed.schema_version, 0);
+        assert_eq!(parsed.records.len(), 3);
+        assert!(matches!(
+            parsed.records[1],
+            ConversationRecord::Thinking { .. }
+        ));
+        assert_eq!(parsed.history[0].as_text(), Some("Old task"));
+        assert_eq!(parsed.history[1].as_text(), Some("Old answer"));
+
+        // Saving upgrades the file to the current version.
+        let upgraded = parse_jsonl_full(&serialize_jsonl_records(&parsed.records)).unwrap();
+        assert_eq!(upgraded.schema_version, JSONL_SCHEMA_VERSION);
+        assert_eq!(upgraded.records.len(), 3);
+    }
+
+    #[test]
+    fn jsonl_full_rejects_newer_schema_version() {
+        let future = format!(
+            "{{\"type\":\"meta\",\"schema_version\":{}}}\n",
+            JSONL_SCHEMA_VERSION + 1
+        );
+        let Err(err) = parse_jsonl_full(&future) else {
+            panic!("newer schema version must be rejected");
+        };
+        assert!(
+            matches!(err, ParseError::UnsupportedSchemaVersion { found, .. } if found == JSONL_SCHEMA_VERSION + 1),
+            "{err}"
+        );
+        assert!(err.to_string().contains("upgrade sven"));
+    }
+
+    #[test]
+    fn jsonl_full_skips_metrics_lines() {
+        let user = serde_json::to_string(&ConversationRecord::Message(user_msg("x"))).unwrap();
+        let metrics = jsonl_metrics_line(&serde_json::json!({ "turns": 2 }));
+        let jsonl = format!("{}{user}\n{metrics}", jsonl_meta_line());
+        let parsed = parse_jsonl_full(&jsonl).unwrap();
+        assert_eq!(parsed.records.len(), 1);
+        assert_eq!(parsed.pending_user_input.as_deref(), Some("x"));
+    }
+
+    #[test]
+    fn jsonl_full_ignores_a_meta_line_that_is_not_first() {
+        // Concatenating two JSONL streams leaves the second meta line mid-file.
+        let user = serde_json::to_string(&ConversationRecord::Message(user_msg("x"))).unwrap();
+        let answer = serde_json::to_string(&ConversationRecord::Message(sven_msg("y"))).unwrap();
+        let future = format!(
+            "{{\"type\":\"meta\",\"schema_version\":{}}}",
+            JSONL_SCHEMA_VERSION + 1
+        );
+        let concatenated = format!(
+            "{meta}{user}\n{meta}{future}\n{answer}\n",
+            meta = jsonl_meta_line()
+        );
+        let parsed = parse_jsonl_full(&concatenated).unwrap();
+        assert_eq!(parsed.schema_version, JSONL_SCHEMA_VERSION);
+        assert_eq!(parsed.records.len(), 2);
+
+        // Without a leading meta line the file stays versionless.
+        let parsed = parse_jsonl_full(&format!("{user}\n{}", jsonl_meta_line())).unwrap();
+        assert_eq!(parsed.schema_version, 0);
+        assert_eq!(parsed.records.len(), 1);
+    }
+
+    #[test]
+    fn jsonl_full_rejects_untagged_lines_in_versioned_files() {
+        // Raw `Message` lines are only accepted in versionless files.
+        let untagged = format!(
+            "{}{}\n",
+            jsonl_meta_line(),
+            serde_json::to_string(&user_msg("x")).unwrap()
+        );
+        assert!(matches!(
+            parse_jsonl_full(&untagged),
+            Err(ParseError::InvalidJsonlLine { line: 2, .. })
+        ));
+    }
+
     // ── Whitespace edge cases ─────────────────────────────────────────────────
 
     #[test]
@@ -17,6 +17,7 @@ use std::collections::HashMap;
 /// vars:
 ///   branch: main
 ///   pr_number: "42"
+/// tools_deny: [write_file, edit_file, delete_file]
 /// ---
 ///
 /// ## Step one
@@ -47,6 +48,12 @@ pub struct WorkflowMetadata {
     /// Override with CLI `--var KEY=VALUE`; environment variables provide a
     /// final fallback (see `apply_template`).
     pub vars: Option<HashMap<String, String>>,
+    /// Tool names the agent may use in this workflow; every other tool is
+    /// removed from the registry.  `None` allows all tools.
+    pub tools_allow: Option<Vec<String>>,
+    /// Tool names removed from the registry for this workflow.  Applied after
+    /// `tools_allow`.
+    pub tools_deny: Option<Vec<String>>,
 }
 
 /// Parse optional YAML-style frontmatter from a markdown workflow string.
@@ -87,9 +94,12 @@ pub fn parse_frontmatter(content: &str) -> (Option<WorkflowMetadata>, &str) {
 /// - Top-level string fields: `key: value` (with optional quotes)
 /// - A `vars:` section with indented `  key: value` entries
 /// - A `models:` section with indented `  mode: model_id` entries
+/// - `tools_allow:` / `tools_deny:` lists, either inline (`[a, b]`) or as
+///   indented `  - name` entries
 fn parse_simple_yaml(src: &str) -> Option<WorkflowMetadata> {
     let mut meta = WorkflowMetadata::default();
-    // Which top-level section we are currently inside ("vars" | "models" | "")
+    // Which top-level section we are currently inside
+    // ("vars" | "models" | "tools_allow" | "tools_deny" | "")
     let mut current_section = "";
     let mut vars: HashMap<String, String> = HashMap::new();
     let mut models: HashMap<String, String> = HashMap::new();
@@ -101,7 +111,16 @@ fn parse_simple_yaml(src: &str) -> Option<WorkflowMetadata> {
 
         // Indented line: belongs to the current section
         if !current_section.is_empty() && (line.starts_with(' ') || line.starts_with('\t')) {
-            if let Some((k, v)) = split_kv(line.trim()) {
+            if let Some(item) = line.trim().strip_prefix('-') {
+                let list = match current_section {
+                    "tools_allow" => meta.tools_allow.as_mut(),
+                    "tools_deny" => meta.tools_deny.as_mut(),
+                    _ => None,
+                };
+                if let Some(list) = list {
+                    list.extend(parse_list(item));
+                }
+            } else if let Some((k, v)) = split_kv(line.trim()) {
                 match current_section {
                     "vars" => {
                         vars.insert(k, v);
@@ -134,6 +153,18 @@ fn parse_simple_yaml(src: &str) -> Option<WorkflowMetadata> {
                         current_section = "models";
                     }
                 }
+                "tools_allow" => {
+                    meta.tools_allow = Some(parse_list(&val));
+                    if val.is_empty() {
+                        current_section = "tools_allow";
+                    }
+                }
+                "tools_deny" => {
+                    meta.tools_deny = Some(parse_list(&val));
+                    if val.is_empty() {
+                        current_section = "tools_deny";
+                    }
+                }
                 // Silently ignore unknown / removed keys for forward compat
                 _ => {}
             }
@@ -164,6 +195,20 @@ fn split_kv(s: &str) -> Option<(String, String)> {
     Some((key, val))
 }
 
+/// Parse an inline list value: `[a, "b"]`, a bare `a, b`, or a single name.
+fn parse_list(s: &str) -> Vec<String> {
+    let s = s.trim();
+    let inner = s
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .unwrap_or(s);
+    inner
+        .split(',')
+        .map(|item| unquote(item.trim()).trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
 /// Strip a single layer of matching `"..."` or `'...'` quotes if present.
 fn unquote(s: &str) -> &str {
     if (s.starts_with('"') && s.ends_with('"')) || (s.starts_with('\'') && s.ends_with('\'')) {
@@ -251,6 +296,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn frontmatter_with_inline_tool_lists() {
+        let md = "---\ntools_allow: [read_file, \"grep\"]\ntools_deny: write_file\n---\n## s\ngo.";
+        let m = parse_frontmatter(md).0.unwrap();
+        assert_eq!(m.tools_allow.unwrap(), ["read_file", "grep"]);
+        assert_eq!(m.tools_deny.unwrap(), ["write_file"]);
+    }
+
+    #[test]
+    fn frontmatter_with_block_tool_list() {
+        let md = "---\ntools_deny:\n  - write_file\n  - 'edit_file'\ntitle: Review\n---\n## s\ngo.";
+        let m = parse_frontmatter(md).0.unwrap();
+        assert_eq!(m.tools_deny.unwrap(), ["write_file", "edit_file"]);
+        assert!(m.tools_allow.is_none());
+        assert_eq!(m.title.as_deref(), Some("Review"));
+    }
+
+    #[test]
+    fn empty_tools_allow_allows_nothing() {
+        let m = parse_frontmatter("---\ntools_allow: []\n---\n## s\ngo.")
+            .0
+            .unwrap();
+        assert_eq!(m.tools_allow, Some(Vec::new()));
+    }
+
     #[test]
     fn missing_closing_delimiter_returns_none() {
         let md = "---\ntitle: oops\n## Step\nno closing delimiter";
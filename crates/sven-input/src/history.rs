@@ -3,27 +3,49 @@
 // SPDX-License-Identifier: Apache-2.0
 /// Persistent conversation history management.
 ///
-/// Conversations are stored as markdown files in `~/.local/share/sven/history/`
-/// (XDG data directory).  File names follow the pattern:
+/// Conversations are stored in `~/.local/share/sven/history/` (XDG data
+/// directory) unless `history.dir` says otherwise; every function that
+/// touches the history directory takes the `history:` config section.  File
+/// names follow the pattern:
 ///
 ///   `<YYYY-MM-DDTHH-MM-SSZ>_<slug>.md`
 ///
-/// where the slug is derived from the first user message.
+/// where the slug is derived from the first user message.  With
+/// `history.format: jsonl` new conversations are saved as `.jsonl` instead;
+/// files of both formats are listed, searched and resumed.
+///
+/// The titles and turn counts shown by [`list`] are cached in
+/// [`INDEX_FILE`] next to the conversations, keyed by file name and
+/// invalidated by size and modification time, so listing a large history
+/// does not parse every file.
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
 
 use anyhow::{Context, Result};
-use chrono::Utc;
-use sven_model::Message;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sven_config::{HistoryConfig, HistoryFormat};
+use sven_model::{Message, Role};
 
-use crate::{parse_conversation, serialize_conversation, ConversationFile};
+use crate::{
+    parse_conversation, parse_jsonl_full, serialize_conversation, serialize_jsonl_records,
+    write_atomic, ConversationFile, ConversationRecord,
+};
 
 // ─── Directory ───────────────────────────────────────────────────────────────
 
 /// Returns the directory where sven stores conversation history.
 ///
-/// Defaults to `$XDG_DATA_HOME/sven/history` (i.e. `~/.local/share/sven/history`).
-pub fn history_dir() -> PathBuf {
+/// This is `cfg.dir` when configured, otherwise `$XDG_DATA_HOME/sven/history`
+/// (i.e. `~/.local/share/sven/history`).  A relative `cfg.dir` is taken from
+/// the current directory; callers resolve it against the project root first.
+pub fn history_dir(cfg: &HistoryConfig) -> PathBuf {
+    cfg.dir.clone().unwrap_or_else(default_history_dir)
+}
+
+fn default_history_dir() -> PathBuf {
     dirs::data_dir()
         .unwrap_or_else(|| {
             dirs::home_dir()
@@ -36,8 +58,8 @@ pub fn history_dir() -> PathBuf {
 }
 
 /// Creates the history directory if it does not exist and returns its path.
-pub fn ensure_history_dir() -> Result<PathBuf> {
-    let dir = history_dir();
+pub fn ensure_history_dir(cfg: &HistoryConfig) -> Result<PathBuf> {
+    let dir = history_dir(cfg);
     fs::create_dir_all(&dir)
         .with_context(|| format!("creating history directory {}", dir.display()))?;
     Ok(dir)
@@ -45,17 +67,21 @@ pub fn ensure_history_dir() -> Result<PathBuf> {
 
 // ─── Save ────────────────────────────────────────────────────────────────────
 
-/// Writes `messages` as a new conversation file in the history directory.
+/// Writes `messages` as a new conversation file in the history directory, in
+/// the format given by `cfg.format`.
 ///
 /// Returns the path of the written file.  Does nothing and returns an error if
-/// `messages` is empty.
-pub fn save(messages: &[Message]) -> Result<PathBuf> {
+/// `messages` is empty.  Provider-injected content is stripped first (see
+/// [`sven_model::sanitize::for_persistence`]).
+pub fn save(cfg: &HistoryConfig, messages: &[Message]) -> Result<PathBuf> {
     if messages.is_empty() {
         anyhow::bail!("cannot save empty conversation");
     }
+    let messages = sven_model::sanitize::for_persistence(messages.to_vec());
+    save_in(&ensure_history_dir(cfg)?, cfg.format, &messages)
+}
 
-    let dir = ensure_history_dir()?;
-
+fn save_in(dir: &Path, format: HistoryFormat, messages: &[Message]) -> Result<PathBuf> {
     let first_user = messages
         .iter()
         .find(|m| matches!(m.role, sven_model::Role::User))
@@ -64,25 +90,36 @@ pub fn save(messages: &[Message]) -> Result<PathBuf> {
 
     // Derive a human-readable title from the first user message.
     let title = make_title(first_user);
-    let filename = make_filename(first_user);
-    let path = dir.join(&filename);
-
-    // Embed the title as an H1 so the file is self-describing.
-    let content = serialize_conversation(Some(&title), messages);
-    fs::write(&path, &content)
+    let mut path = dir.join(make_filename(first_user));
+    let content = match format {
+        // Embed the title as an H1 so the file is self-describing.
+        HistoryFormat::Markdown => serialize_conversation(Some(&title), messages),
+        HistoryFormat::Jsonl => {
+            path.set_extension("jsonl");
+            serialize_jsonl(messages)
+        }
+    };
+    write_atomic(&path, &content)
         .with_context(|| format!("writing conversation to {}", path.display()))?;
 
     Ok(path)
 }
 
-/// Overwrites an existing conversation file with the given messages.
+/// Overwrites an existing conversation file with the given messages, in the
+/// format given by its extension.
 ///
-/// Preserves the H1 title already present in the file (if any); otherwise
-/// derives one from the first user message, consistent with `save()`.
+/// Preserves the H1 title already present in a markdown file (if any);
+/// otherwise derives one from the first user message, consistent with
+/// `save()`.
 pub fn save_to(path: &Path, messages: &[Message]) -> Result<()> {
     if messages.is_empty() {
         return Ok(());
     }
+    let messages = &sven_model::sanitize::for_persistence(messages.to_vec());
+    if is_jsonl(path) {
+        return write_atomic(path, serialize_jsonl(messages))
+            .with_context(|| format!("writing conversation to {}", path.display()));
+    }
 
     // Preserve an existing title so repeated saves don't lose it.
     let existing_title: Option<String> = fs::read_to_string(path).ok().and_then(|s| {
@@ -100,7 +137,8 @@ pub fn save_to(path: &Path, messages: &[Message]) -> Result<()> {
     });
 
     let content = serialize_conversation(title.as_deref(), messages);
-    fs::write(path, &content).with_context(|| format!("writing conversation to {}", path.display()))
+    write_atomic(path, &content)
+        .with_context(|| format!("writing conversation to {}", path.display()))
 }
 
 // ─── List ────────────────────────────────────────────────────────────────────
@@ -121,34 +159,69 @@ pub struct HistoryEntry {
 }
 
 /// Lists all conversations in the history directory, most recent first.
-pub fn list(limit: Option<usize>) -> Result<Vec<HistoryEntry>> {
-    let dir = history_dir();
+pub fn list(cfg: &HistoryConfig, limit: Option<usize>) -> Result<Vec<HistoryEntry>> {
+    list_in(&history_dir(cfg), limit)
+}
+
+/// The most recent saved conversation, used by `--continue`.
+pub fn latest(cfg: &HistoryConfig) -> Result<Option<HistoryEntry>> {
+    latest_in(&history_dir(cfg))
+}
+
+fn latest_in(dir: &Path) -> Result<Option<HistoryEntry>> {
+    Ok(list_in(dir, Some(1))?.into_iter().next())
+}
+
+fn list_in(dir: &Path, limit: Option<usize>) -> Result<Vec<HistoryEntry>> {
     if !dir.exists() {
         return Ok(Vec::new());
     }
 
+    let mut index = HistoryIndex::load(dir);
+    let mut fresh = BTreeMap::new();
+    let mut changed = false;
     let mut entries: Vec<HistoryEntry> = Vec::new();
-    for entry in fs::read_dir(&dir).context("reading history directory")? {
+    for entry in fs::read_dir(dir).context("reading history directory")? {
         let entry = entry?;
         let path = entry.path();
-        if path.extension().and_then(|e| e.to_str()) != Some("md") {
+        if !is_conversation_file(&path) {
             continue;
         }
+        let name = entry.file_name().to_string_lossy().to_string();
         let stem = path
             .file_stem()
             .map(|s| s.to_string_lossy().to_string())
             .unwrap_or_default();
 
-        let (timestamp, title) = parse_stem_and_title(&stem, &path);
-        let turns = count_turns(&path);
+        let stamp = entry.metadata().map(|m| FileStamp::of(&m)).ok();
+        let record = match index.files.remove(&name) {
+            Some(record) if stamp.is_some_and(|s| s == record.stamp) => record,
+            _ => {
+                changed = true;
+                let (timestamp, title) = parse_stem_and_title(&stem, &path);
+                IndexRecord {
+                    stamp: stamp.unwrap_or_default(),
+                    timestamp,
+                    title,
+                    turns: count_turns(&path),
+                }
+            }
+        };
 
         entries.push(HistoryEntry {
             id: stem,
             path,
-            timestamp,
-            title,
-            turns,
+            timestamp: record.timestamp.clone(),
+            title: record.title.clone(),
+            turns: record.turns,
         });
+        fresh.insert(name, record);
+    }
+
+    // Whatever is left in the old index belongs to deleted files.
+    if changed || !index.files.is_empty() {
+        index.files = fresh;
+        index.save(dir);
     }
 
     entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
@@ -158,15 +231,244 @@ pub fn list(limit: Option<usize>) -> Result<Vec<HistoryEntry>> {
     Ok(entries)
 }
 
+// ─── Index ───────────────────────────────────────────────────────────────────
+
+/// File in the history directory caching the listing of each conversation.
+pub const INDEX_FILE: &str = ".index.json";
+
+/// Cached listing of the history directory, keyed by file name.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HistoryIndex {
+    files: BTreeMap<String, IndexRecord>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct IndexRecord {
+    #[serde(flatten)]
+    stamp: FileStamp,
+    timestamp: String,
+    title: String,
+    turns: usize,
+}
+
+/// Size and modification time a cached record was computed from; any
+/// change to the file invalidates the record.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct FileStamp {
+    size: u64,
+    modified_ns: u64,
+}
+
+impl FileStamp {
+    fn of(meta: &fs::Metadata) -> Self {
+        let modified_ns = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_nanos() as u64);
+        Self {
+            size: meta.len(),
+            modified_ns,
+        }
+    }
+}
+
+impl HistoryIndex {
+    /// The index of `dir`; empty when missing or unreadable, in which case
+    /// the next listing rebuilds it.
+    fn load(dir: &Path) -> Self {
+        fs::read_to_string(dir.join(INDEX_FILE))
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    /// Write the index next to the conversations.  Failures are only logged:
+    /// the index is a cache and listing works without it.
+    fn save(&self, dir: &Path) {
+        let path = dir.join(INDEX_FILE);
+        let result = serde_json::to_vec(self)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| Ok(write_atomic(&path, json)?));
+        if let Err(e) = result {
+            tracing::debug!("could not write history index {}: {e}", path.display());
+        }
+    }
+}
+
+// ─── Vacuum ──────────────────────────────────────────────────────────────────
+
+/// Deletes the oldest saved conversations beyond the limits: all but the
+/// newest `max_entries`, and every conversation older than `max_age_days`.
+/// `None` disables a limit, as does an age beyond the representable range.
+/// Returns the removed conversations, newest first.
+pub fn vacuum(
+    cfg: &HistoryConfig,
+    max_entries: Option<usize>,
+    max_age_days: Option<u64>,
+) -> Result<Vec<HistoryEntry>> {
+    vacuum_in(&history_dir(cfg), max_entries, max_age_days, Utc::now())
+}
+
+fn vacuum_in(
+    dir: &Path,
+    max_entries: Option<usize>,
+    max_age_days: Option<u64>,
+    now: DateTime<Utc>,
+) -> Result<Vec<HistoryEntry>> {
+    // An age too large to subtract from `now` keeps everything.
+    let cutoff = max_age_days
+        .and_then(|days| i64::try_from(days).ok())
+        .and_then(chrono::TimeDelta::try_days)
+        .and_then(|age| now.checked_sub_signed(age));
+    // Newest first; conversations whose name carries no timestamp count as
+    // the oldest but are never removed for their age.
+    let mut entries: Vec<_> = list_in(dir, None)?
+        .into_iter()
+        .map(|e| (parse_timestamp(&e.timestamp), e))
+        .collect();
+    entries.sort_by_key(|(saved, _)| std::cmp::Reverse(*saved));
+
+    let mut removed = Vec::new();
+    for (i, (saved, entry)) in entries.into_iter().enumerate() {
+        let over_count = max_entries.is_some_and(|n| i >= n);
+        let too_old = match (cutoff, saved) {
+            (Some(cutoff), Some(saved)) => saved < cutoff,
+            _ => false,
+        };
+        if over_count || too_old {
+            fs::remove_file(&entry.path)
+                .with_context(|| format!("removing conversation {}", entry.path.display()))?;
+            removed.push(entry);
+        }
+    }
+    if !removed.is_empty() {
+        // Drops the records of the removed files.
+        list_in(dir, None)?;
+    }
+    Ok(removed)
+}
+
+/// The time encoded in a history file name (`2026-02-20T09-00-00Z`).
+fn parse_timestamp(ts: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(ts, "%Y-%m-%dT%H-%M-%SZ")
+        .ok()
+        .map(|t| t.and_utc())
+}
+
+// ─── Search ──────────────────────────────────────────────────────────────────
+
+/// Characters of context kept on each side of a match in a search snippet.
+const SNIPPET_CONTEXT_CHARS: usize = 40;
+
+/// A saved conversation whose content matches a search query.
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub entry: HistoryEntry,
+    /// 1-based turn (`## User` section) of the first match; 0 when it is in
+    /// the title or preamble.
+    pub turn: usize,
+    /// The matching line, trimmed to the text around the match.
+    pub snippet: String,
+}
+
+/// Finds saved conversations containing `query` (case-insensitive), most
+/// recent first, reporting the first match in each.
+pub fn search(cfg: &HistoryConfig, query: &str, limit: Option<usize>) -> Result<Vec<SearchHit>> {
+    search_in(&history_dir(cfg), query, limit)
+}
+
+fn search_in(dir: &Path, query: &str, limit: Option<usize>) -> Result<Vec<SearchHit>> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        anyhow::bail!("search query is empty");
+    }
+    let mut hits = Vec::new();
+    for entry in list_in(dir, None)? {
+        if limit.is_some_and(|n| hits.len() >= n) {
+            break;
+        }
+        let Some(content) = read_markdown(&entry.path) else {
+            continue;
+        };
+        if let Some((turn, snippet)) = first_match(&content, &query) {
+            hits.push(SearchHit {
+                entry,
+                turn,
+                snippet,
+            });
+        }
+    }
+    Ok(hits)
+}
+
+/// Turn number and snippet of the first line containing `query` (already
+/// lowercased).
+fn first_match(content: &str, query: &str) -> Option<(usize, String)> {
+    let mut turn = 0;
+    for line in content.lines() {
+        if line.trim() == "## User" {
+            turn += 1;
+            continue;
+        }
+        if let Some((start, len)) = find_lowercase(line, query) {
+            return Some((turn, snippet(line, start, len)));
+        }
+    }
+    None
+}
+
+/// Char offset and char length in `line` of the first case-insensitive
+/// match of `query` (already lowercased).
+///
+/// Lowercasing can change the number of chars ('İ' becomes two), so the
+/// match found in the lowercased line is mapped back to the chars of `line`
+/// it came from.
+fn find_lowercase(line: &str, query: &str) -> Option<(usize, usize)> {
+    let mut lower = String::with_capacity(line.len());
+    // Original char index of every byte of `lower`.
+    let mut origin = Vec::with_capacity(line.len());
+    for (i, c) in line.chars().enumerate() {
+        for l in c.to_lowercase() {
+            lower.push(l);
+            origin.resize(lower.len(), i);
+        }
+    }
+    if query.is_empty() {
+        return Some((0, 0));
+    }
+    let pos = lower.find(query)?;
+    let start = origin[pos];
+    let end = origin[pos + query.len() - 1] + 1;
+    Some((start, end - start))
+}
+
+/// `line` cut to [`SNIPPET_CONTEXT_CHARS`] around the match at char `start`.
+fn snippet(line: &str, start: usize, len: usize) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let from = start.saturating_sub(SNIPPET_CONTEXT_CHARS);
+    let to = (start + len + SNIPPET_CONTEXT_CHARS).min(chars.len());
+    let text: String = chars[from..to].iter().collect();
+    let mut out = String::new();
+    if from > 0 {
+        out.push('…');
+    }
+    out.push_str(text.trim());
+    if to < chars.len() {
+        out.push('…');
+    }
+    out
+}
+
 // ─── Resolve / Load ──────────────────────────────────────────────────────────
 
 /// Resolves a conversation ID to its file path.
 ///
 /// Accepts:
-/// - Exact file stem (filename without `.md`)
+/// - Exact file stem (filename without `.md` / `.jsonl`)
 /// - Unique timestamp prefix (e.g. `2026-02-20`)
-/// - Absolute or relative filesystem path to a `.md` file
-pub fn resolve(id: &str) -> Result<PathBuf> {
+/// - Absolute or relative filesystem path to a `.md` or `.jsonl` file
+pub fn resolve(cfg: &HistoryConfig, id: &str) -> Result<PathBuf> {
     let p = PathBuf::from(id);
     if p.is_absolute() || id.contains('/') {
         if p.exists() {
@@ -175,11 +477,13 @@ pub fn resolve(id: &str) -> Result<PathBuf> {
         anyhow::bail!("file not found: {}", p.display());
     }
 
-    let dir = history_dir();
+    let dir = history_dir(cfg);
 
-    let with_ext = dir.join(format!("{id}.md"));
-    if with_ext.exists() {
-        return Ok(with_ext);
+    for ext in ["md", "jsonl"] {
+        let with_ext = dir.join(format!("{id}.{ext}"));
+        if with_ext.exists() {
+            return Ok(with_ext);
+        }
     }
 
     if dir.exists() {
@@ -187,7 +491,7 @@ pub fn resolve(id: &str) -> Result<PathBuf> {
         for entry in fs::read_dir(&dir)? {
             let entry = entry?;
             let name = entry.file_name().to_string_lossy().to_string();
-            if name.starts_with(id) && name.ends_with(".md") {
+            if name.starts_with(id) && is_conversation_file(&entry.path()) {
                 matches.push(entry.path());
             }
         }
@@ -218,17 +522,61 @@ pub fn resolve(id: &str) -> Result<PathBuf> {
 
 /// Loads and parses a conversation by ID.  Returns both the parsed conversation
 /// and the resolved file path (needed for subsequent saves).
-pub fn load(id: &str) -> Result<(ConversationFile, PathBuf)> {
-    let path = resolve(id)?;
+pub fn load(cfg: &HistoryConfig, id: &str) -> Result<(ConversationFile, PathBuf)> {
+    let path = resolve(cfg, id)?;
     let content = fs::read_to_string(&path)
         .with_context(|| format!("reading conversation file {}", path.display()))?;
-    let parsed = parse_conversation(&content)
-        .with_context(|| format!("parsing conversation file {}", path.display()))?;
+    let parsed = if is_jsonl(&path) {
+        parse_jsonl_full(&content).map(|p| ConversationFile {
+            title: None,
+            history: p.history,
+            pending_user_input: p.pending_user_input,
+        })
+    } else {
+        parse_conversation(&content)
+    }
+    .with_context(|| format!("parsing conversation file {}", path.display()))?;
     Ok((parsed, path))
 }
 
 // ─── Internal helpers ────────────────────────────────────────────────────────
 
+fn is_jsonl(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "jsonl")
+}
+
+fn is_conversation_file(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "md") || is_jsonl(path)
+}
+
+fn serialize_jsonl(messages: &[Message]) -> String {
+    let records: Vec<ConversationRecord> = messages
+        .iter()
+        .cloned()
+        .map(ConversationRecord::Message)
+        .collect();
+    serialize_jsonl_records(&records)
+}
+
+/// The conversation at `path` as markdown.  JSONL files are converted so
+/// titles, turn counts and search work the same for both formats.
+fn read_markdown(path: &Path) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    if !is_jsonl(path) {
+        return Some(content);
+    }
+    let messages: Vec<Message> = parse_jsonl_full(&content)
+        .ok()?
+        .records
+        .into_iter()
+        .filter_map(|r| match r {
+            ConversationRecord::Message(m) if m.role != Role::System => Some(m),
+            _ => None,
+        })
+        .collect();
+    Some(serialize_conversation(None, &messages))
+}
+
 /// Builds a filename for a new conversation file.
 pub fn make_filename(first_user_message: &str) -> String {
     let ts = Utc::now().format("%Y-%m-%dT%H-%M-%SZ").to_string();
@@ -271,18 +619,36 @@ pub fn sanitize_llm_title(raw: &str) -> String {
     }
 }
 
-/// Derives a human-readable title (capitalised, up to ~80 chars) from a
-/// free-form text string — used as the H1 title in saved conversation files.
+/// Longest title [`make_title`] derives, in characters (excluding the `…`).
+pub const MAX_TITLE_CHARS: usize = 60;
+
+/// Derives a human-readable title (capitalised, up to [`MAX_TITLE_CHARS`])
+/// from the first user message — used as the H1 title in saved conversation
+/// files when no title was set.
 pub fn make_title(text: &str) -> String {
-    // Take up to the first sentence (stop at '.', '!', '?') or 80 chars.
-    let trimmed = text.trim();
-    let sentence_end = trimmed
+    // Only the first non-empty line, up to the first sentence end.
+    let line = text
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or("");
+    let sentence_end = line
         .char_indices()
         .find(|(_, c)| matches!(*c, '.' | '!' | '?'))
         .map(|(i, _)| i + 1)
-        .unwrap_or(trimmed.len());
-    let raw: String = trimmed.chars().take(sentence_end.min(80)).collect();
-    let raw = raw.trim_end_matches(['.', '!', '?']).trim();
+        .unwrap_or(line.len());
+    let sentence = line[..sentence_end]
+        .trim_end_matches(['.', '!', '?'])
+        .trim();
+    let raw = match sentence.char_indices().nth(MAX_TITLE_CHARS) {
+        // Cut at the last word boundary that fits.
+        Some((cut, _)) => {
+            let head = &sentence[..cut];
+            let head = head.rfind(' ').map_or(head, |i| &head[..i]).trim_end();
+            format!("{head}…")
+        }
+        None => sentence.to_string(),
+    };
     if raw.is_empty() {
         return "Conversation".to_string();
     }
@@ -323,7 +689,7 @@ fn parse_stem_and_title(stem: &str, path: &Path) -> (String, String) {
 }
 
 fn read_title_from_file(path: &Path) -> Option<String> {
-    let content = fs::read_to_string(path).ok()?;
+    let content = read_markdown(path)?;
 
     // Prefer an explicit H1 title line.
     for line in content.lines() {
@@ -335,33 +701,294 @@ fn read_title_from_file(path: &Path) -> Option<String> {
         }
     }
 
-    // Fall back to the first non-empty line of the first ## User section.
-    let mut in_user = false;
-    for line in content.lines() {
-        if line.trim() == "## User" {
-            in_user = true;
-            continue;
-        }
-        if in_user {
-            if line.starts_with("## ") {
-                break;
-            }
-            if !line.trim().is_empty() {
-                let s: String = line.chars().take(60).collect();
-                return Some(if line.len() > 60 {
-                    format!("{s}…")
-                } else {
-                    s
-                });
-            }
-        }
-    }
-    None
+    // Fall back to a title derived from the first ## User section.
+    let mut lines = content.lines().skip_while(|l| l.trim() != "## User");
+    lines.next()?;
+    let body: Vec<&str> = lines.take_while(|l| !l.starts_with("## ")).collect();
+    let body = body.join("\n");
+    (!body.trim().is_empty()).then(|| make_title(&body))
 }
 
 fn count_turns(path: &Path) -> usize {
-    let Ok(content) = fs::read_to_string(path) else {
+    let Some(content) = read_markdown(path) else {
         return 0;
     };
     content.lines().filter(|l| l.trim() == "## User").count()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn title_comes_from_first_line_of_multiline_message() {
+        let msg = "\n  fix the flaky retry test in the scheduler\nIt fails on CI about once a day.\n\n```\npanic\n```";
+        assert_eq!(make_title(msg), "Fix the flaky retry test in the scheduler");
+        assert_eq!(make_title("why? it broke"), "Why");
+        assert_eq!(make_title("  \n\n"), "Conversation");
+    }
+
+    #[test]
+    fn long_title_is_cut_at_a_word_boundary() {
+        let msg = "please refactor the configuration loader so that unknown keys are reported with their full dotted path";
+        let title = make_title(msg);
+        assert_eq!(
+            title,
+            "Please refactor the configuration loader so that unknown…"
+        );
+        assert!(title.chars().count() <= MAX_TITLE_CHARS + 1);
+    }
+
+    #[test]
+    fn list_derives_missing_title_from_first_user_message() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("2026-02-20T09-00-00Z_x.md");
+        fs::write(
+            &path,
+            "## User\n\nadd a --continue flag\nlike other agents\n",
+        )
+        .unwrap();
+        let (_, title) = parse_stem_and_title("2026-02-20T09-00-00Z_x", &path);
+        assert_eq!(title, "Add a --continue flag");
+    }
+
+    #[test]
+    fn search_returns_only_matching_conversations_with_snippets() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |stem: &str, body: &str| {
+            fs::write(dir.path().join(format!("{stem}.md")), body).unwrap();
+        };
+        write(
+            "2026-01-01T10-00-00Z_retry",
+            "# Retry logic\n\n## User\n\nhow do retries work?\n\n## Sven\n\nSee backoff.rs.\n\n\
+             ## User\n\nand the Jitter?\n\n## Sven\n\nThe jitter is added after the exponential \
+             backoff delay is computed, so two clients that failed together do not retry together.\n",
+        );
+        write(
+            "2026-01-02T10-00-00Z_parser",
+            "## User\n\nfix the parser\n\n## Sven\n\nDone.\n",
+        );
+        write(
+            "2026-01-03T10-00-00Z_jitter",
+            "# Jitter in the scheduler\n\n## User\n\nwhy?\n",
+        );
+
+        let hits = search_in(dir.path(), "JITTER", None).unwrap();
+        let found: Vec<_> = hits
+            .iter()
+            .map(|h| (h.entry.id.as_str(), h.turn, h.snippet.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    "2026-01-03T10-00-00Z_jitter",
+                    0,
+                    "# Jitter in the scheduler"
+                ),
+                ("2026-01-01T10-00-00Z_retry", 2, "and the Jitter?"),
+            ]
+        );
+
+        let hits = search_in(dir.path(), "two clients", None).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].turn, 2);
+        assert_eq!(
+            hits[0].snippet,
+            "…ponential backoff delay is computed, so two clients that failed together do not retry toget…"
+        );
+
+        assert_eq!(search_in(dir.path(), "e", Some(2)).unwrap().len(), 2);
+        assert!(search_in(dir.path(), "nowhere", None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn search_snippet_handles_case_expanding_chars() {
+        // 'İ' lowercases to two chars, so offsets in the lowercased line run
+        // ahead of the original one.
+        let line = format!("{} Needle here", "İ".repeat(60));
+        let (turn, snippet) = first_match(&line, "needle").unwrap();
+        assert_eq!(turn, 0);
+        assert_eq!(
+            snippet,
+            format!("…{} Needle here", "İ".repeat(SNIPPET_CONTEXT_CHARS - 1))
+        );
+        assert_eq!(find_lowercase("aİb", "i\u{307}b"), Some((1, 2)));
+    }
+
+    fn fixture_history(dir: &Path) {
+        for stem in [
+            "2026-01-01T10-00-00Z_old",
+            "2026-03-01T10-00-00Z_recent",
+            "2026-02-01T10-00-00Z_middle",
+            "2026-03-10T10-00-00Z_newest",
+            "undated",
+        ] {
+            fs::write(dir.join(format!("{stem}.md")), "## User\n\nhi\n").unwrap();
+        }
+    }
+
+    fn indexed_files(dir: &Path) -> Vec<String> {
+        HistoryIndex::load(dir).files.into_keys().collect()
+    }
+
+    fn md_files(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .filter(|n| n.ends_with(".md"))
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn now() -> DateTime<Utc> {
+        parse_timestamp("2026-03-15T00-00-00Z").unwrap()
+    }
+
+    #[test]
+    fn list_builds_and_refreshes_the_index() {
+        let dir = tempfile::tempdir().unwrap();
+        fixture_history(dir.path());
+        assert_eq!(list_in(dir.path(), None).unwrap().len(), 5);
+        assert_eq!(indexed_files(dir.path()), md_files(dir.path()));
+
+        // A rewritten file is re-read; a deleted one leaves the index.
+        let path = dir.path().join("2026-02-01T10-00-00Z_middle.md");
+        fs::write(&path, "# Renamed\n\n## User\n\na\n\n## User\n\nb\n").unwrap();
+        fs::remove_file(dir.path().join("undated.md")).unwrap();
+        let entries = list_in(dir.path(), None).unwrap();
+        let middle = entries.iter().find(|e| e.path == path).unwrap();
+        assert_eq!((middle.title.as_str(), middle.turns), ("Renamed", 2));
+        assert_eq!(indexed_files(dir.path()), md_files(dir.path()));
+    }
+
+    #[test]
+    fn vacuum_keeps_the_newest_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        fixture_history(dir.path());
+        let removed = vacuum_in(dir.path(), Some(2), None, now()).unwrap();
+        let ids: Vec<_> = removed.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "2026-02-01T10-00-00Z_middle",
+                "2026-01-01T10-00-00Z_old",
+                "undated"
+            ]
+        );
+        assert_eq!(
+            md_files(dir.path()),
+            [
+                "2026-03-01T10-00-00Z_recent.md",
+                "2026-03-10T10-00-00Z_newest.md"
+            ]
+        );
+        assert_eq!(indexed_files(dir.path()), md_files(dir.path()));
+    }
+
+    #[test]
+    fn vacuum_removes_entries_older_than_max_age() {
+        let dir = tempfile::tempdir().unwrap();
+        fixture_history(dir.path());
+        list_in(dir.path(), None).unwrap();
+        let removed = vacuum_in(dir.path(), None, Some(30), now()).unwrap();
+        assert_eq!(removed.len(), 2);
+        assert_eq!(
+            md_files(dir.path()),
+            [
+                "2026-03-01T10-00-00Z_recent.md",
+                "2026-03-10T10-00-00Z_newest.md",
+                "undated.md"
+            ]
+        );
+        assert_eq!(indexed_files(dir.path()), md_files(dir.path()));
+
+        assert!(vacuum_in(dir.path(), None, None, now()).unwrap().is_empty());
+        assert_eq!(md_files(dir.path()).len(), 3);
+    }
+
+    #[test]
+    fn vacuum_with_a_huge_max_age_keeps_everything() {
+        let dir = tempfile::tempdir().unwrap();
+        fixture_history(dir.path());
+        for days in [u64::MAX, i64::MAX as u64, 1 << 40] {
+            assert!(vacuum_in(dir.path(), None, Some(days), now())
+                .unwrap()
+                .is_empty());
+        }
+        assert_eq!(md_files(dir.path()).len(), 5);
+    }
+
+    #[test]
+    fn configured_dir_and_format_are_used_for_save_and_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = dir.path().join(".sven").join("history");
+        let cfg = HistoryConfig {
+            dir: Some(history.clone()),
+            format: HistoryFormat::Jsonl,
+        };
+
+        let messages = [
+            Message::user("Explain the build script"),
+            Message::assistant("It generates bindings."),
+        ];
+        let path = save(&cfg, &messages).unwrap();
+        assert_eq!(path.parent(), Some(history.as_path()));
+        assert_eq!(path.extension().unwrap(), "jsonl");
+
+        let entries = list(&cfg, None).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path, path);
+        assert_eq!(entries[0].title, "Explain the build script");
+        assert_eq!(entries[0].turns, 1);
+
+        let (loaded, loaded_path) = load(&cfg, &entries[0].id).unwrap();
+        assert_eq!(loaded_path, path);
+        assert_eq!(loaded.history.len(), 2);
+        assert_eq!(search(&cfg, "bindings", None).unwrap().len(), 1);
+
+        assert_ne!(history_dir(&HistoryConfig::default()), history);
+    }
+
+    #[test]
+    fn markdown_and_jsonl_files_are_listed_together() {
+        let dir = tempfile::tempdir().unwrap();
+        let messages = [Message::user("first"), Message::assistant("ok")];
+        let md = save_in(dir.path(), HistoryFormat::Markdown, &messages).unwrap();
+        let jsonl = save_in(dir.path(), HistoryFormat::Jsonl, &messages).unwrap();
+        assert_eq!(md.extension().unwrap(), "md");
+        assert_eq!(jsonl.extension().unwrap(), "jsonl");
+
+        let mut paths: Vec<_> = list_in(dir.path(), None)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.path)
+            .collect();
+        paths.sort();
+        let mut expected = vec![md, jsonl];
+        expected.sort();
+        assert_eq!(paths, expected);
+    }
+
+    #[test]
+    fn latest_picks_newest_timestamp() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(latest_in(dir.path()).unwrap().is_none());
+
+        // Written out of order so neither creation order nor directory order
+        // decides the result.
+        for stem in [
+            "2026-02-20T09-00-00Z_middle",
+            "2026-03-01T08-30-00Z_newest",
+            "2025-12-31T23-59-59Z_oldest",
+        ] {
+            fs::write(dir.path().join(format!("{stem}.md")), "## User\n\nhi\n").unwrap();
+        }
+        fs::write(dir.path().join("2027-01-01T00-00-00Z_notes.txt"), "").unwrap();
+
+        let entry = latest_in(dir.path()).unwrap().unwrap();
+        assert_eq!(entry.id, "2026-03-01T08-30-00Z_newest");
+        assert_eq!(entry.turns, 1);
+    }
+}
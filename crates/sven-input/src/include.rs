@@ -0,0 +1,287 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! `@include` directives for workflow files.
+//!
+//! A line consisting of `@include path/to/other.md` between steps is replaced
+//! by the steps (`##` sections) of the referenced workflow before the document
+//! is handed to [`crate::parse_workflow`]:
+//!
+//! ```markdown
+//! ## Prepare
+//! Check out the branch.
+//!
+//! @include lib/review.md
+//!
+//! ## Report
+//! Summarise the findings.
+//! ```
+//!
+//! Paths are relative to the file containing the directive.  The included
+//! file's frontmatter, title and preamble are dropped — only its steps are
+//! inlined — so the parent's `vars:` apply to included steps just like to its
+//! own.  Includes nest up to [`MAX_INCLUDE_DEPTH`] levels; cycles are rejected.
+
+use std::path::{Path, PathBuf};
+
+use crate::parse_frontmatter;
+
+/// Maximum nesting depth of `@include` directives.
+pub const MAX_INCLUDE_DEPTH: usize = 8;
+
+const DIRECTIVE: &str = "@include";
+
+/// Error expanding `@include` directives.
+#[derive(Debug, thiserror::Error)]
+pub enum IncludeError {
+    #[error("{from}:{line}: cannot read included workflow {path}: {source}")]
+    Missing {
+        path: PathBuf,
+        from: PathBuf,
+        line: usize,
+        source: std::io::Error,
+    },
+    #[error("include cycle: {chain}")]
+    Cycle { chain: String },
+    #[error("{from}:{line}: includes nested deeper than {max} levels")]
+    TooDeep {
+        from: PathBuf,
+        line: usize,
+        max: usize,
+    },
+    #[error("{from}:{line}: @include without a path")]
+    EmptyPath { from: PathBuf, line: usize },
+    #[error("included workflow {path} has no ## steps")]
+    NoSteps { path: PathBuf },
+    #[error("{from}:{line}: @include must be followed by a ## step or the end of the file")]
+    NotAtStepBoundary { from: PathBuf, line: usize },
+}
+
+/// Expand every `@include` directive in `content`, which was read from `path`.
+///
+/// Frontmatter of `content` is kept as-is; directives inside fenced code
+/// blocks are left alone.  Documents without directives are returned
+/// unchanged.
+pub fn expand_includes(content: &str, path: &Path) -> Result<String, IncludeError> {
+    if !content.contains(DIRECTIVE) {
+        return Ok(content.to_string());
+    }
+    let mut stack = vec![canonical(path)];
+    expand(content, path, &mut stack)
+}
+
+fn expand(content: &str, path: &Path, stack: &mut Vec<PathBuf>) -> Result<String, IncludeError> {
+    let (_, body) = parse_frontmatter(content);
+    let header_len = content.len() - body.len();
+    let header_lines = content[..header_len].lines().count();
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+
+    let mut out = String::with_capacity(content.len());
+    out.push_str(&content[..header_len]);
+
+    let mut in_fence = false;
+    // Line of the last directive while only blank lines have followed it.
+    let mut open_include: Option<usize> = None;
+
+    for (idx, line) in body.lines().enumerate() {
+        let line_no = header_lines + idx + 1;
+        let trimmed = line.trim();
+
+        if is_fence(trimmed) {
+            in_fence = !in_fence;
+        }
+        let target = (!in_fence).then(|| parse_directive(trimmed)).flatten();
+
+        if let Some(from_line) = open_include {
+            if target.is_none() && !trimmed.is_empty() {
+                if !trimmed.starts_with("## ") {
+                    return Err(IncludeError::NotAtStepBoundary {
+                        from: path.to_path_buf(),
+                        line: from_line,
+                    });
+                }
+                open_include = None;
+            }
+        }
+
+        let Some(target) = target else {
+            out.push_str(line);
+            out.push('\n');
+            continue;
+        };
+        if target.is_empty() {
+            return Err(IncludeError::EmptyPath {
+                from: path.to_path_buf(),
+                line: line_no,
+            });
+        }
+        if stack.len() > MAX_INCLUDE_DEPTH {
+            return Err(IncludeError::TooDeep {
+                from: path.to_path_buf(),
+                line: line_no,
+                max: MAX_INCLUDE_DEPTH,
+            });
+        }
+
+        let child = dir.join(target);
+        let key = canonical(&child);
+        if let Some(pos) = stack.iter().position(|p| *p == key) {
+            let chain = stack[pos..]
+                .iter()
+                .chain(std::iter::once(&key))
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            return Err(IncludeError::Cycle { chain });
+        }
+        let child_content =
+            std::fs::read_to_string(&child).map_err(|source| IncludeError::Missing {
+                path: child.clone(),
+                from: path.to_path_buf(),
+                line: line_no,
+                source,
+            })?;
+
+        stack.push(key);
+        let expanded = expand(&child_content, &child, stack)?;
+        stack.pop();
+
+        let steps = steps_section(&expanded).ok_or(IncludeError::NoSteps { path: child })?;
+        if !out.is_empty() && !out.ends_with("\n\n") {
+            out.push('\n');
+        }
+        out.push_str(steps.trim_end());
+        out.push_str("\n\n");
+        open_include = Some(line_no);
+    }
+
+    Ok(out)
+}
+
+/// The part of an expanded workflow from its first `##` heading onwards.
+fn steps_section(content: &str) -> Option<&str> {
+    let (_, body) = parse_frontmatter(content);
+    let mut in_fence = false;
+    let mut offset = 0;
+    for line in body.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if is_fence(trimmed) {
+            in_fence = !in_fence;
+        } else if !in_fence && trimmed.starts_with("## ") {
+            return Some(&body[offset..]);
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Return the include path when `line` is an `@include` directive.
+fn parse_directive(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix(DIRECTIVE)?;
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(
+        rest.trim()
+            .trim_matches(|c| c == '"' || c == '\'' || c == '`'),
+    )
+}
+
+fn is_fence(line: &str) -> bool {
+    line.starts_with("```") || line.starts_with("~~~")
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_workflow;
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn two_file_include_inlines_steps() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "shared.md",
+            "---\ntitle: Shared\n---\n# Shared lib\nPreamble is dropped.\n\n\
+             ## Review\nReview {{branch}}.\n\n## Lint\nRun the linter.\n",
+        );
+        let main = write(
+            dir.path(),
+            "main.md",
+            "---\nvars:\n  branch: main\n---\n## Prepare\nCheck out.\n\n\
+             @include shared.md\n\n## Report\nSummarise.\n",
+        );
+
+        let content = std::fs::read_to_string(&main).unwrap();
+        let expanded = expand_includes(&content, &main).unwrap();
+        let (fm, body) = parse_frontmatter(&expanded);
+        let vars = fm.unwrap().vars.unwrap();
+        assert_eq!(vars.get("branch").map(String::as_str), Some("main"));
+
+        let mut wf = parse_workflow(body);
+        let mut labels = Vec::new();
+        let mut contents = Vec::new();
+        while let Some(step) = wf.steps.pop() {
+            labels.push(step.label.unwrap());
+            contents.push(step.content);
+        }
+        assert_eq!(labels, ["Prepare", "Review", "Lint", "Report"]);
+        assert!(contents[1].contains("{{branch}}"), "{contents:?}");
+        assert!(!contents.iter().any(|c| c.contains("Preamble is dropped")));
+    }
+
+    #[test]
+    fn missing_include_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let main = write(dir.path(), "main.md", "## A\nOne.\n\n@include nope.md\n");
+        let content = std::fs::read_to_string(&main).unwrap();
+        let err = expand_includes(&content, &main).unwrap_err();
+        assert!(
+            matches!(err, IncludeError::Missing { line: 4, .. }),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn self_include_cycle_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let main = write(dir.path(), "loop.md", "## A\nOne.\n\n@include loop.md\n");
+        let content = std::fs::read_to_string(&main).unwrap();
+        let err = expand_includes(&content, &main).unwrap_err();
+        assert!(matches!(err, IncludeError::Cycle { .. }), "{err}");
+    }
+
+    #[test]
+    fn include_must_sit_at_a_step_boundary() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "lib.md", "## B\nTwo.\n");
+        let main = write(
+            dir.path(),
+            "main.md",
+            "## A\nOne.\n@include lib.md\nstray text\n",
+        );
+        let content = std::fs::read_to_string(&main).unwrap();
+        let err = expand_includes(&content, &main).unwrap_err();
+        assert!(
+            matches!(err, IncludeError::NotAtStepBoundary { line: 3, .. }),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn directive_inside_code_fence_is_ignored() {
+        let content = "## A\n```\n@include nope.md\n```\n";
+        let expanded = expand_includes(content, Path::new("main.md")).unwrap();
+        assert_eq!(expanded, content);
+    }
+}
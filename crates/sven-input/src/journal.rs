@@ -0,0 +1,180 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! Append-only writer for JSONL conversation files.
+//!
+//! Rewriting the whole file after every turn costs O(conversation) per turn
+//! and leaves a truncated file behind when interrupted.  [`JsonlJournal`]
+//! remembers what it has already written and appends only the new records.
+//! It falls back to a full rewrite (to a temporary file, then renamed over
+//! the original) when the file is new to it, when earlier records changed
+//! (e.g. after an edit or rewind), or when the file on disk no longer has
+//! the length it left behind — which also discards a torn final line from an
+//! interrupted append.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::conversation::{jsonl_meta_line, record_for_persistence, ConversationRecord};
+
+/// How [`JsonlJournal::write`] brought the file up to date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalWrite {
+    /// Nothing changed since the last write.
+    Unchanged,
+    /// This many records were appended.
+    Appended(usize),
+    /// The whole file was rewritten.
+    Rewritten,
+}
+
+/// Incremental writer for one JSONL conversation file.
+#[derive(Debug)]
+pub struct JsonlJournal {
+    path: PathBuf,
+    /// Hash of each record line written so far, in file order.
+    written: Vec<u64>,
+    /// File length after the last write; `None` until the first write.
+    len: Option<u64>,
+}
+
+impl JsonlJournal {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            written: Vec::new(),
+            len: None,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Make the file hold exactly `records`, appending when the records
+    /// written so far are an unchanged prefix of them.
+    pub fn write(&mut self, records: &[ConversationRecord]) -> std::io::Result<JournalWrite> {
+        let lines: Vec<String> = records
+            .iter()
+            .filter_map(record_for_persistence)
+            .filter_map(|record| match serde_json::to_string(&record) {
+                Ok(line) => Some(line),
+                Err(e) => {
+                    tracing::warn!("failed to serialize ConversationRecord to JSONL: {e}");
+                    None
+                }
+            })
+            .collect();
+        let hashes: Vec<u64> = lines.iter().map(|l| line_hash(l)).collect();
+
+        let appendable = self.len.is_some()
+            && hashes.starts_with(&self.written)
+            && std::fs::metadata(&self.path).ok().map(|m| m.len()) == self.len;
+        if !appendable {
+            self.rewrite(&lines)?;
+            self.written = hashes;
+            return Ok(JournalWrite::Rewritten);
+        }
+
+        let new = &lines[self.written.len()..];
+        if new.is_empty() {
+            return Ok(JournalWrite::Unchanged);
+        }
+        let mut delta = String::new();
+        for line in new {
+            delta.push_str(line);
+            delta.push('\n');
+        }
+        let mut file = std::fs::OpenOptions::new().append(true).open(&self.path)?;
+        if let Err(e) = file.write_all(delta.as_bytes()) {
+            // The file may now end in a partial line; force a rewrite next time.
+            self.len = None;
+            return Err(e);
+        }
+        self.len = self.len.map(|len| len + delta.len() as u64);
+        self.written = hashes;
+        Ok(JournalWrite::Appended(new.len()))
+    }
+
+    fn rewrite(&mut self, lines: &[String]) -> std::io::Result<()> {
+        let mut content = jsonl_meta_line();
+        for line in lines {
+            content.push_str(line);
+            content.push('\n');
+        }
+        self.len = None;
+        crate::write_atomic(&self.path, &content)?;
+        self.len = Some(content.len() as u64);
+        Ok(())
+    }
+}
+
+fn line_hash(line: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    line.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_jsonl_full, serialize_jsonl_records};
+    use sven_model::Message;
+
+    fn msg(record: Message) -> ConversationRecord {
+        ConversationRecord::Message(record)
+    }
+
+    #[test]
+    fn second_turn_is_appended() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chat.jsonl");
+        let mut journal = JsonlJournal::new(&path);
+
+        let mut records = vec![msg(Message::user("one")), msg(Message::assistant("uno"))];
+        assert_eq!(journal.write(&records).unwrap(), JournalWrite::Rewritten);
+        let after_first = std::fs::read_to_string(&path).unwrap();
+
+        records.push(msg(Message::user("two")));
+        records.push(msg(Message::assistant("dos")));
+        assert_eq!(journal.write(&records).unwrap(), JournalWrite::Appended(2));
+        assert_eq!(journal.write(&records).unwrap(), JournalWrite::Unchanged);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with(&after_first), "first turn rewritten");
+        assert_eq!(content, serialize_jsonl_records(&records));
+        assert_eq!(parse_jsonl_full(&content).unwrap().records.len(), 4);
+    }
+
+    #[test]
+    fn changed_history_or_foreign_write_forces_rewrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chat.jsonl");
+        let mut journal = JsonlJournal::new(&path);
+        let mut records = vec![msg(Message::user("one")), msg(Message::assistant("uno"))];
+        journal.write(&records).unwrap();
+
+        // An edited earlier message cannot be appended.
+        records[1] = msg(Message::assistant("eins"));
+        assert_eq!(journal.write(&records).unwrap(), JournalWrite::Rewritten);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            serialize_jsonl_records(&records)
+        );
+
+        // A torn line left by an interrupted append changes the length.
+        let mut f = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        f.write_all(b"{\"type\":\"mess").unwrap();
+        records.push(msg(Message::user("two")));
+        assert_eq!(journal.write(&records).unwrap(), JournalWrite::Rewritten);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            serialize_jsonl_records(&records)
+        );
+    }
+}
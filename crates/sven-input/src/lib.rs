@@ -1,13 +1,18 @@
 // Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
 //
 // SPDX-License-Identifier: Apache-2.0
+mod atomic;
 pub mod chat_document;
 pub mod conversation;
 pub mod frontmatter;
 pub mod history;
+mod images;
+pub mod include;
+pub mod journal;
 mod markdown;
 mod queue;
 
+pub use atomic::{write_atomic, write_atomic_with};
 pub use chat_document::{
     chat_dir, chat_path, ensure_chat_dir, json_str_to_yaml, list_chats, load_chat, load_chat_from,
     load_chat_from_with_metadata, load_chat_with_metadata, parse_chat_document, records_to_turns,
@@ -16,12 +21,17 @@ pub use chat_document::{
     ChatUsage, FileMetadata, FileModifiedError, SessionId, TurnRecord,
 };
 pub use conversation::{
-    parse_conversation, parse_jsonl_conversation, parse_jsonl_full, serialize_conversation,
-    serialize_conversation_turn, serialize_conversation_turn_with_metadata,
-    serialize_jsonl_conversation_turn, serialize_jsonl_records, ConversationFile,
-    ConversationRecord, ParsedJsonlConversation, TurnMetadata,
+    jsonl_meta_line, jsonl_metrics_line, parse_conversation, parse_conversation_records,
+    parse_jsonl_conversation, parse_jsonl_full, round_trip, serialize_conversation,
+    serialize_conversation_records, serialize_conversation_turn,
+    serialize_conversation_turn_with_metadata, serialize_jsonl_conversation_turn,
+    serialize_jsonl_records, ConversationFile, ConversationRecord, ParsedConversation,
+    ParsedJsonlConversation, TurnMetadata, JSONL_SCHEMA_VERSION,
 };
 pub use frontmatter::{parse_frontmatter, WorkflowMetadata};
 pub use history::{make_title, sanitize_llm_title};
+pub use images::{externalize_images, inline_image_files};
+pub use include::{expand_includes, IncludeError};
+pub use journal::{JournalWrite, JsonlJournal};
 pub use markdown::{parse_workflow, ParsedWorkflow};
 pub use queue::{Step, StepOptions, StepQueue};
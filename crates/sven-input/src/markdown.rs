@@ -248,13 +248,12 @@ fn parse_sven_comment_into(comment: &str, opts: &mut StepOptions) {
                     // like "step:"), and at least one must be a known sven key.
                     // This keeps "<!-- model=gpt-4o -->" working while correctly
                     // ignoring old formats like "<!-- step: mode=research -->".
-                    let all_kv = potential_content
-                        .split_whitespace()
-                        .all(|t| t.contains('='));
-                    let has_known_key = potential_content.split_whitespace().any(|t| {
+                    let tokens = split_option_tokens(potential_content);
+                    let all_kv = tokens.iter().all(|t| t.contains('='));
+                    let has_known_key = tokens.iter().any(|t| {
                         matches!(
                             t.split_once('=').map(|(k, _)| k),
-                            Some("mode" | "model" | "provider" | "timeout" | "cache_key")
+                            Some("mode" | "model" | "provider" | "timeout" | "cache_key" | "when")
                         )
                     });
                     if potential_content.contains('=') && all_kv && has_known_key {
@@ -272,21 +271,62 @@ fn parse_sven_comment_into(comment: &str, opts: &mut StepOptions) {
 
     let inner = comment[start..end].trim();
 
-    for token in inner.split_whitespace() {
+    for token in split_option_tokens(inner) {
         if let Some((key, val)) = token.split_once('=') {
-            let val = val.trim_matches('"').trim_matches('\'');
+            let val = unquote(val);
             match key {
                 "mode" => opts.mode = Some(val.to_string()),
                 "provider" => opts.provider = Some(val.to_string()),
                 "model" => opts.model = Some(val.to_string()),
                 "timeout" => opts.timeout_secs = val.parse().ok(),
                 "cache_key" => opts.cache_key = Some(val.to_string()),
+                "when" => opts.when = Some(val.to_string()),
                 _ => {}
             }
         }
     }
 }
 
+/// Split `key=value` option text on whitespace, keeping quoted values
+/// (`when="exit_code == 0"`) together.
+fn split_option_tokens(s: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut quote: Option<char> = None;
+    for (i, c) in s.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                start.get_or_insert(i);
+            }
+            None if c.is_whitespace() => {
+                if let Some(st) = start.take() {
+                    tokens.push(&s[st..i]);
+                }
+            }
+            None => {
+                start.get_or_insert(i);
+            }
+        }
+    }
+    if let Some(st) = start {
+        tokens.push(&s[st..]);
+    }
+    tokens
+}
+
+/// Strip one pair of matching surrounding quotes.
+fn unquote(val: &str) -> &str {
+    for q in ['"', '\''] {
+        if let Some(inner) = val.strip_prefix(q).and_then(|v| v.strip_suffix(q)) {
+            return inner;
+        }
+    }
+    val
+}
+
 fn flush_step(out: &mut Vec<Step>, label: Option<String>, body: &mut String, options: StepOptions) {
     let content = body.trim().to_string();
     body.clear();
@@ -506,6 +546,18 @@ mod tests {
         assert_eq!(s.options.cache_key.as_deref(), Some("abc"));
     }
 
+    #[test]
+    fn sven_comment_quoted_when_keeps_spaces() {
+        let md = "## Step\n<!-- sven: mode=agent when=\"contains(last_output, 'PASS') && exit_code == 0\" -->\nShip.";
+        let mut w = parse_workflow(md);
+        let s = w.steps.pop().unwrap();
+        assert_eq!(s.options.mode.as_deref(), Some("agent"));
+        assert_eq!(
+            s.options.when.as_deref(),
+            Some("contains(last_output, 'PASS') && exit_code == 0")
+        );
+    }
+
     #[test]
     fn sven_comment_sets_model() {
         let md = "## Step\n<!-- sven: model=gpt-4o -->\nDo the work.";
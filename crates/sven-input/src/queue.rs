@@ -19,6 +19,9 @@ pub struct StepOptions {
     pub timeout_secs: Option<u64>,
     /// Optional cache key — if set, a matching cached result is reused
     pub cache_key: Option<String>,
+    /// Run the step only when this condition holds for the previous step's
+    /// result, e.g. `exit_code == 0` or `contains(last_output, 'PASS')`.
+    pub when: Option<String>,
 }
 
 /// A single step / message to be sent to the agent.
@@ -61,6 +64,11 @@ impl StepQueue {
     pub fn len(&self) -> usize {
         self.0.len()
     }
+
+    /// Iterate over the queued steps in delivery order without consuming them.
+    pub fn iter(&self) -> impl Iterator<Item = &Step> {
+        self.0.iter()
+    }
 }
 
 impl From<Vec<Step>> for StepQueue {
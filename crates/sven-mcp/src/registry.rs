@@ -12,6 +12,7 @@
 //! The tools registered here are stateless from the MCP client's perspective
 //! and work without any running sven node or TUI.
 
+use sven_config::ToolsConfig;
 use sven_tools::{
     DeleteFileTool, EditFileTool, FindFileTool, GrepTool, ReadFileTool, ReadImageTool,
     ReadLintsTool, RunTerminalCommandTool, SearchCodebaseTool, ShellTool, ToolRegistry,
@@ -41,6 +42,9 @@ pub const DEFAULT_TOOL_NAMES: &[&str] = &[
 
 /// Build a [`ToolRegistry`] populated with the default MCP-safe tool set.
 ///
+/// `shell` and `run_terminal_command` are configured from `tools` (timeouts,
+/// secret masking, `inherit_env` and `env_allowlist`) as in an agent session.
+///
 /// `web_search_api_key` is forwarded to [`WebSearchTool`].  When `None` the
 /// web_search tool is still registered but will return an error if invoked
 /// without a Brave API key configured via the `BRAVE_API_KEY` environment
@@ -52,6 +56,7 @@ pub const DEFAULT_TOOL_NAMES: &[&str] = &[
 /// against clients accidentally requesting internal tools that were never
 /// registered.
 pub fn build_mcp_registry(
+    tools: &ToolsConfig,
     web_search_api_key: Option<String>,
     allowed_names: Option<&str>,
 ) -> ToolRegistry {
@@ -91,20 +96,21 @@ pub fn build_mcp_registry(
         reg.register(ReadLintsTool);
     }
     if allow("run_terminal_command") {
-        reg.register(RunTerminalCommandTool::default());
+        reg.register(RunTerminalCommandTool::from_config(tools));
     }
     if allow("search_codebase") {
         reg.register(SearchCodebaseTool);
     }
     if allow("shell") {
-        reg.register(ShellTool::default());
+        reg.register(ShellTool::from_config(tools));
     }
     if allow("web_fetch") {
-        reg.register(WebFetchTool);
+        reg.register(WebFetchTool::default());
     }
     if allow("web_search") {
         reg.register(WebSearchTool {
             api_key: web_search_api_key,
+            ..WebSearchTool::default()
         });
     }
     if allow("write_file") {
@@ -122,7 +128,7 @@ mod tests {
 
     #[test]
     fn default_registry_contains_all_default_tools() {
-        let reg = build_mcp_registry(None, None);
+        let reg = build_mcp_registry(&ToolsConfig::default(), None, None);
         let names = reg.names();
         for expected in DEFAULT_TOOL_NAMES {
             assert!(
@@ -134,14 +140,14 @@ mod tests {
 
     #[test]
     fn all_keyword_includes_all_default_tools() {
-        let reg = build_mcp_registry(None, Some("all"));
+        let reg = build_mcp_registry(&ToolsConfig::default(), None, Some("all"));
         let names = reg.names();
         assert_eq!(names.len(), DEFAULT_TOOL_NAMES.len());
     }
 
     #[test]
     fn allowed_names_filter_restricts_tools() {
-        let reg = build_mcp_registry(None, Some("read_file,write_file"));
+        let reg = build_mcp_registry(&ToolsConfig::default(), None, Some("read_file,write_file"));
         let mut names = reg.names();
         names.sort();
         assert_eq!(names, vec!["read_file", "write_file"]);
@@ -149,14 +155,18 @@ mod tests {
 
     #[test]
     fn single_tool_allowed() {
-        let reg = build_mcp_registry(None, Some("grep"));
+        let reg = build_mcp_registry(&ToolsConfig::default(), None, Some("grep"));
         assert_eq!(reg.names().len(), 1);
         assert!(reg.get("grep").is_some());
     }
 
     #[test]
     fn unknown_tool_name_in_filter_is_ignored() {
-        let reg = build_mcp_registry(None, Some("read_file,nonexistent_tool"));
+        let reg = build_mcp_registry(
+            &ToolsConfig::default(),
+            None,
+            Some("read_file,nonexistent_tool"),
+        );
         let names = reg.names();
         assert_eq!(names.len(), 1);
         assert!(reg.get("read_file").is_some());
@@ -164,7 +174,11 @@ mod tests {
 
     #[test]
     fn whitespace_around_tool_names_is_trimmed() {
-        let reg = build_mcp_registry(None, Some(" read_file , write_file "));
+        let reg = build_mcp_registry(
+            &ToolsConfig::default(),
+            None,
+            Some(" read_file , write_file "),
+        );
         let mut names = reg.names();
         names.sort();
         assert_eq!(names, vec!["read_file", "write_file"]);
@@ -172,10 +186,43 @@ mod tests {
 
     #[test]
     fn web_search_registered_with_api_key() {
-        let reg = build_mcp_registry(Some("test_key".to_string()), Some("web_search"));
+        let reg = build_mcp_registry(
+            &ToolsConfig::default(),
+            Some("test_key".to_string()),
+            Some("web_search"),
+        );
         assert!(reg.get("web_search").is_some());
     }
 
+    #[tokio::test]
+    async fn command_tools_follow_the_tools_config() {
+        let tools = ToolsConfig {
+            inherit_env: Some(false),
+            env_allowlist: vec!["SVEN_MCP_TEST_ALLOWED".into()],
+            ..ToolsConfig::default()
+        };
+        let reg = build_mcp_registry(&tools, None, Some("shell,run_terminal_command"));
+        // Anything outside the base variables, the allowlist and what the
+        // shell sets itself is reported.
+        let command = "env | cut -d= -f1 \
+             | grep -vxE 'PATH|HOME|LANG|PWD|OLDPWD|SHLVL|_|SVEN_MCP_TEST_ALLOWED' \
+             | sed 's/^/leaked:/'; echo done";
+        for (name, arg) in [
+            ("shell", "shell_command"),
+            ("run_terminal_command", "command"),
+        ] {
+            let call = sven_tools::ToolCall {
+                id: "1".into(),
+                name: name.into(),
+                args: serde_json::json!({ arg: command, "description": "print the variable" }),
+            };
+            let out = reg.execute(&call).await;
+            assert!(!out.is_error, "{name}: {}", out.content);
+            assert!(out.content.contains("done"), "{name}: {}", out.content);
+            assert!(!out.content.contains("leaked:"), "{name}: {}", out.content);
+        }
+    }
+
     #[test]
     fn default_tool_names_constant_is_sorted() {
         let mut sorted = DEFAULT_TOOL_NAMES.to_vec();
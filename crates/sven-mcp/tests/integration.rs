@@ -442,7 +442,11 @@ async fn tools_call_multiple_tools_independently() {
 /// actually executing the tools.
 #[tokio::test]
 async fn default_registry_tools_are_listed_by_server() {
-    let reg = Arc::new(sven_mcp::build_mcp_registry(None, None));
+    let reg = Arc::new(sven_mcp::build_mcp_registry(
+        &sven_config::ToolsConfig::default(),
+        None,
+        None,
+    ));
     let (mut writer, mut reader) = start_test_server(reg).await;
     initialize(&mut writer, &mut reader).await;
 
@@ -478,7 +482,11 @@ async fn default_registry_tools_are_listed_by_server() {
 /// Filtered registry only exposes the requested tools.
 #[tokio::test]
 async fn filtered_registry_limits_exposed_tools() {
-    let reg = Arc::new(sven_mcp::build_mcp_registry(None, Some("read_file,grep")));
+    let reg = Arc::new(sven_mcp::build_mcp_registry(
+        &sven_config::ToolsConfig::default(),
+        None,
+        Some("read_file,grep"),
+    ));
     let (mut writer, mut reader) = start_test_server(reg).await;
     initialize(&mut writer, &mut reader).await;
 
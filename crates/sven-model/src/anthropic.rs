@@ -8,9 +8,9 @@ use serde_json::{json, Value};
 use tracing::{debug, warn};
 
 use crate::{
-    catalog::{static_catalog, ModelCatalogEntry},
+    catalog::{static_catalog, InputModality, ModelCatalogEntry},
     provider::ResponseStream,
-    CompletionRequest, ResponseEvent,
+    CompletionRequest, ResponseEvent, Role,
 };
 
 pub struct AnthropicProvider {
@@ -40,6 +40,14 @@ pub struct AnthropicProvider {
     /// conversation history with `cache_control`.  File reads and command
     /// outputs that persist across many turns are ideal candidates.
     cache_tool_results: bool,
+    /// Extended-thinking budget; `None` disables thinking.
+    thinking_budget_tokens: Option<u32>,
+    /// JSON Schema that replies must follow (structured outputs beta).
+    response_format: Option<Value>,
+    /// Stop sequences, sent as `stop_sequences`.
+    stop: Vec<String>,
+    /// `driver_options.extra_body`, merged into the request body.
+    extra_body: Value,
     client: reqwest::Client,
 }
 
@@ -115,37 +123,57 @@ impl AnthropicProvider {
             cache_conversation,
             cache_images,
             cache_tool_results,
+            thinking_budget_tokens: None,
+            response_format: None,
+            stop: Vec::new(),
+            extra_body: Value::Null,
             client: crate::build_http_client(),
         }
     }
-}
 
-#[async_trait]
-impl crate::ModelProvider for AnthropicProvider {
-    fn name(&self) -> &str {
-        "anthropic"
+    /// Enable extended thinking with the given token budget.  `None` leaves
+    /// thinking off.
+    pub fn with_thinking_budget(mut self, budget_tokens: Option<u32>) -> Self {
+        self.thinking_budget_tokens = budget_tokens;
+        self
     }
-    fn model_name(&self) -> &str {
-        &self.model
+
+    /// Ask for replies that follow the JSON Schema `schema`, sent as
+    /// `output_format`.  `None` leaves the reply format free.
+    pub fn with_response_format(mut self, schema: Option<Value>) -> Self {
+        self.response_format = schema;
+        self
     }
 
-    /// Anthropic does not expose a public list-models endpoint with full
-    /// metadata, so we return the static catalog entries for this provider.
-    async fn list_models(&self) -> anyhow::Result<Vec<ModelCatalogEntry>> {
-        let mut entries: Vec<ModelCatalogEntry> = static_catalog()
-            .into_iter()
-            .filter(|e| e.provider == "anthropic")
-            .collect();
-        entries.sort_by(|a, b| a.id.cmp(&b.id));
-        Ok(entries)
+    /// Stop generating at any of `stop`.
+    pub fn with_stop(mut self, stop: Vec<String>) -> Self {
+        self.stop = stop;
+        self
     }
 
-    async fn complete(&self, req: CompletionRequest) -> anyhow::Result<ResponseStream> {
-        let key = self
-            .api_key
-            .as_deref()
-            .context("ANTHROPIC_API_KEY not set")?;
+    /// Extra request-body fields; see [`crate::merge_extra_body`].
+    pub fn with_extra_body(mut self, extra_body: Value) -> Self {
+        self.extra_body = extra_body;
+        self
+    }
 
+    /// The `budget_tokens` to send for a request capped at `max_tokens`.
+    ///
+    /// Anthropic requires at least 1 024 tokens and less than `max_tokens`.
+    /// Thinking is also left off when the request continues a tool loop: the
+    /// API then expects the previous assistant turn to start with its signed
+    /// thinking block, which is not kept in the conversation history.
+    fn thinking_budget(&self, max_tokens: u32, req: &CompletionRequest) -> Option<u32> {
+        let budget = self
+            .thinking_budget_tokens?
+            .max(1024)
+            .min(max_tokens.saturating_sub(1));
+        let continues_tool_loop = req.messages.last().is_some_and(|m| m.role == Role::Tool);
+        (budget >= 1024 && !continues_tool_loop).then_some(budget)
+    }
+
+    /// Build the `/v1/messages` request body.
+    fn build_request_body(&self, req: &CompletionRequest) -> Value {
         let (system_text, mut messages) = build_anthropic_messages(&req.messages);
 
         // Build the TTL-appropriate cache_control object.
@@ -269,6 +297,11 @@ impl crate::ModelProvider for AnthropicProvider {
             "temperature": self.temperature,
             "stream": req.stream,
         });
+        if let Some(budget) = self.thinking_budget(max_tokens, req) {
+            body["thinking"] = json!({ "type": "enabled", "budget_tokens": budget });
+            // Extended thinking does not accept a custom temperature.
+            body.as_object_mut().unwrap().remove("temperature");
+        }
 
         // Automatic conversation caching — add a top-level cache_control block.
         // Anthropic automatically moves the breakpoint to the last cacheable
@@ -325,6 +358,75 @@ impl crate::ModelProvider for AnthropicProvider {
         if !tools.is_empty() {
             body["tools"] = json!(tools);
         }
+        if let Some(schema) = &self.response_format {
+            body["output_format"] = json!({ "type": "json_schema", "schema": schema });
+        }
+        if !self.stop.is_empty() {
+            body["stop_sequences"] = json!(self.stop);
+        }
+        crate::merge_extra_body(&mut body, &self.extra_body);
+        body
+    }
+}
+
+#[async_trait]
+impl crate::ModelProvider for AnthropicProvider {
+    fn name(&self) -> &str {
+        "anthropic"
+    }
+    fn model_name(&self) -> &str {
+        &self.model
+    }
+
+    /// `GET /v1/models`, enriched with static catalog metadata.  Falls back
+    /// to the static catalog when no key is set or the request fails.
+    async fn list_models(&self) -> anyhow::Result<Vec<ModelCatalogEntry>> {
+        let mut catalog_entries: Vec<ModelCatalogEntry> = static_catalog()
+            .into_iter()
+            .filter(|e| e.provider == "anthropic")
+            .collect();
+        catalog_entries.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let Some(key) = self.api_key.as_deref() else {
+            return Ok(catalog_entries);
+        };
+        let req = self
+            .client
+            .get(format!("{}/v1/models?limit=1000", self.base_url))
+            .header("x-api-key", key)
+            .header("anthropic-version", "2023-06-01");
+        let Some(body) = crate::fetch_model_list(req).await else {
+            return Ok(catalog_entries);
+        };
+        let live = parse_models_response(&body, &catalog_entries);
+        if live.is_empty() {
+            return Ok(catalog_entries);
+        }
+        Ok(live)
+    }
+
+    /// `GET /v1/models`, which needs a valid key but costs no tokens.
+    async fn health_check(&self) -> anyhow::Result<bool> {
+        let key = self
+            .api_key
+            .as_deref()
+            .context("ANTHROPIC_API_KEY not set")?;
+        let req = self
+            .client
+            .get(format!("{}/v1/models", self.base_url))
+            .header("x-api-key", key)
+            .header("anthropic-version", "2023-06-01");
+        crate::check_endpoint(req).await?;
+        Ok(true)
+    }
+
+    async fn complete(&self, req: CompletionRequest) -> anyhow::Result<ResponseStream> {
+        let key = self
+            .api_key
+            .as_deref()
+            .context("ANTHROPIC_API_KEY not set")?;
+
+        let body = self.build_request_body(&req);
 
         let any_caching = self.cache_system_prompt
             || self.cache_tools
@@ -354,13 +456,20 @@ impl crate::ModelProvider for AnthropicProvider {
         //   Claude 3 / 3.5 Sonnet models.  Safe to send for all claude-3+ models;
         //   newer models silently ignore it.
         // • `extended-cache-ttl-2025-04-11` — required when using 1-hour TTL.
+        // • `structured-outputs-2025-11-13` — required for `output_format`.
         //
         // Multiple beta features are enabled via a comma-separated value.
+        let mut betas: Vec<&str> = Vec::new();
         if any_caching {
-            let mut betas: Vec<&str> = vec!["prompt-caching-2024-07-31"];
+            betas.push("prompt-caching-2024-07-31");
             if self.extended_cache_time {
                 betas.push("extended-cache-ttl-2025-04-11");
             }
+        }
+        if self.response_format.is_some() {
+            betas.push("structured-outputs-2025-11-13");
+        }
+        if !betas.is_empty() {
             request_builder = request_builder.header("anthropic-beta", betas.join(","));
         }
 
@@ -482,6 +591,7 @@ pub(crate) fn parse_anthropic_event(v: &Value) -> anyhow::Result<ResponseEvent>
                     cache_read_tokens: 0,
                     cache_write_tokens: 0,
                     cost_usd: None,
+                    estimated: false,
                 });
             }
             Ok(ResponseEvent::TextDelta(String::new()))
@@ -497,6 +607,7 @@ pub(crate) fn parse_anthropic_event(v: &Value) -> anyhow::Result<ResponseEvent>
                     cache_write_tokens: usage["cache_creation_input_tokens"].as_u64().unwrap_or(0)
                         as u32,
                     cost_usd: None,
+                    estimated: false,
                 });
             }
             Ok(ResponseEvent::TextDelta(String::new()))
@@ -648,11 +759,111 @@ pub(crate) fn build_anthropic_messages(messages: &[crate::Message]) -> (String,
     (system_text, out)
 }
 
+/// Map an Anthropic `GET /v1/models` response to catalog entries.
+///
+/// The API returns ids and display names; token limits are read from
+/// `max_input_tokens` / `max_tokens` when present and otherwise taken from
+/// the matching static-catalog entry (zero for models the catalog does not
+/// know).
+pub(crate) fn parse_models_response(
+    body: &Value,
+    catalog_entries: &[ModelCatalogEntry],
+) -> Vec<ModelCatalogEntry> {
+    let Some(data) = body["data"].as_array() else {
+        return Vec::new();
+    };
+    let mut entries: Vec<ModelCatalogEntry> = data
+        .iter()
+        .filter_map(|item| {
+            let id = item["id"].as_str()?;
+            let cat = catalog_entries.iter().find(|e| e.id == id);
+            let limit = |key: &str, fallback: Option<u32>| {
+                item[key]
+                    .as_u64()
+                    .map(|n| n as u32)
+                    .or(fallback)
+                    .unwrap_or(0)
+            };
+            Some(ModelCatalogEntry {
+                id: id.to_string(),
+                name: item["display_name"]
+                    .as_str()
+                    .filter(|s| !s.is_empty())
+                    .unwrap_or(id)
+                    .to_string(),
+                provider: "anthropic".to_string(),
+                context_window: limit("max_input_tokens", cat.map(|c| c.context_window)),
+                max_output_tokens: limit("max_tokens", cat.map(|c| c.max_output_tokens)),
+                description: cat.map(|c| c.description.clone()).unwrap_or_default(),
+                input_modalities: cat
+                    .map(|c| c.input_modalities.clone())
+                    .unwrap_or_else(|| vec![InputModality::Text]),
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| a.id.cmp(&b.id));
+    entries
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::ModelProvider;
 
+    #[test]
+    fn models_response_maps_to_catalog_entries() {
+        let catalog = vec![ModelCatalogEntry {
+            id: "claude-known".into(),
+            name: "Known".into(),
+            provider: "anthropic".into(),
+            context_window: 200_000,
+            max_output_tokens: 64_000,
+            description: "from catalog".into(),
+            input_modalities: vec![InputModality::Text, InputModality::Image],
+        }];
+        let body = json!({
+            "data": [
+                {
+                    "type": "model",
+                    "id": "claude-new",
+                    "display_name": "Claude New",
+                    "created_at": "2026-01-01T00:00:00Z",
+                    "max_input_tokens": 1_000_000,
+                    "max_tokens": 128_000
+                },
+                {
+                    "type": "model",
+                    "id": "claude-known",
+                    "display_name": "Claude Known",
+                    "created_at": "2025-01-01T00:00:00Z"
+                }
+            ],
+            "has_more": false,
+            "first_id": "claude-new",
+            "last_id": "claude-known"
+        });
+        let entries = parse_models_response(&body, &catalog);
+        assert_eq!(entries.len(), 2);
+
+        let known = &entries[0];
+        assert_eq!(known.id, "claude-known");
+        assert_eq!(known.name, "Claude Known");
+        assert_eq!(
+            known.context_window, 200_000,
+            "catalog fills missing limits"
+        );
+        assert_eq!(known.max_output_tokens, 64_000);
+        assert!(known.supports_images());
+
+        let new = &entries[1];
+        assert_eq!(new.id, "claude-new");
+        assert_eq!(new.provider, "anthropic");
+        assert_eq!(new.context_window, 1_000_000);
+        assert_eq!(new.max_output_tokens, 128_000);
+
+        assert!(parse_models_response(&json!({ "error": {} }), &catalog).is_empty());
+    }
+
     #[test]
     fn provider_name_and_model() {
         let p = AnthropicProvider::new("claude-3-5-sonnet-20241022".into(), None, None, None, None);
@@ -952,6 +1163,108 @@ mod tests {
         assert_eq!(img["source"]["type"], "base64");
     }
 
+    // ── Extended thinking ─────────────────────────────────────────────────────
+
+    fn thinking_provider(budget: Option<u32>, max_tokens: u32) -> AnthropicProvider {
+        AnthropicProvider::new(
+            "claude-sonnet-4-5".into(),
+            None,
+            None,
+            Some(max_tokens),
+            None,
+        )
+        .with_thinking_budget(budget)
+    }
+
+    #[test]
+    fn thinking_budget_is_sent_without_temperature() {
+        let req = CompletionRequest {
+            messages: vec![crate::Message::user("hi")],
+            ..CompletionRequest::default()
+        };
+        let body = thinking_provider(Some(2048), 8192).build_request_body(&req);
+        assert_eq!(
+            body["thinking"],
+            json!({ "type": "enabled", "budget_tokens": 2048 })
+        );
+        assert!(body.get("temperature").is_none(), "{body}");
+
+        let body = thinking_provider(None, 8192).build_request_body(&req);
+        assert!(body.get("thinking").is_none());
+        assert!(body.get("temperature").is_some());
+    }
+
+    #[test]
+    fn thinking_budget_is_clamped_to_the_api_limits() {
+        let req = CompletionRequest {
+            messages: vec![crate::Message::user("hi")],
+            ..CompletionRequest::default()
+        };
+        let body = thinking_provider(Some(100), 8192).build_request_body(&req);
+        assert_eq!(body["thinking"]["budget_tokens"], json!(1024));
+        let body = thinking_provider(Some(50_000), 8192).build_request_body(&req);
+        assert_eq!(body["thinking"]["budget_tokens"], json!(8191));
+        let body = thinking_provider(Some(2048), 1000).build_request_body(&req);
+        assert!(body.get("thinking").is_none(), "no room for a budget");
+    }
+
+    #[test]
+    fn thinking_is_off_when_continuing_a_tool_loop() {
+        let req = CompletionRequest {
+            messages: vec![
+                crate::Message::user("list files"),
+                crate::Message::tool_result("toolu_1", "a.rs"),
+            ],
+            ..CompletionRequest::default()
+        };
+        let body = thinking_provider(Some(2048), 8192).build_request_body(&req);
+        assert!(body.get("thinking").is_none(), "{body}");
+    }
+
+    #[test]
+    fn response_format_is_sent_as_output_format() {
+        let schema = json!({ "type": "object", "required": ["ok"] });
+        let req = CompletionRequest {
+            messages: vec![crate::Message::user("hi")],
+            ..CompletionRequest::default()
+        };
+        let body = thinking_provider(None, 4096)
+            .with_response_format(Some(schema.clone()))
+            .build_request_body(&req);
+        assert_eq!(
+            body["output_format"],
+            json!({ "type": "json_schema", "schema": schema })
+        );
+        let body = thinking_provider(None, 4096).build_request_body(&req);
+        assert!(body.get("output_format").is_none());
+    }
+
+    #[test]
+    fn extra_body_is_merged_into_native_requests() {
+        let req = CompletionRequest {
+            messages: vec![crate::Message::user("hi")],
+            ..CompletionRequest::default()
+        };
+        let body = thinking_provider(None, 4096)
+            .with_extra_body(json!({ "service_tier": "auto", "max_tokens": 1 }))
+            .build_request_body(&req);
+        assert_eq!(body["service_tier"], json!("auto"));
+        assert_eq!(body["max_tokens"], json!(4096), "sven fields win");
+    }
+
+    #[test]
+    fn stop_sequences_are_sent_as_stop_sequences() {
+        let req = CompletionRequest {
+            messages: vec![crate::Message::user("hi")],
+            ..CompletionRequest::default()
+        };
+        let body = thinking_provider(None, 4096)
+            .with_stop(vec!["</answer>".into()])
+            .build_request_body(&req);
+        assert_eq!(body["stop_sequences"], json!(["</answer>"]));
+        assert!(body.get("stop").is_none());
+    }
+
     // ── SSE Unicode chunk-boundary preservation ───────────────────────────────
     // Simulate the scan closure: accumulate raw bytes, drain complete SSE lines,
     // decode as UTF-8 only after a full newline-terminated line is assembled.
@@ -39,6 +39,10 @@ pub struct BedrockProvider {
     region: String,
     max_tokens: u32,
     temperature: f32,
+    /// Stop sequences, sent as `inferenceConfig.stopSequences`.
+    stop: Vec<String>,
+    /// `driver_options.extra_body`, merged into the request body.
+    extra_body: Value,
     client: reqwest::Client,
 }
 
@@ -58,9 +62,23 @@ impl BedrockProvider {
             region,
             max_tokens: max_tokens.unwrap_or(4096),
             temperature: temperature.unwrap_or(0.2),
+            stop: Vec::new(),
+            extra_body: Value::Null,
             client: crate::build_http_client(),
         }
     }
+
+    /// Stop generating at any of `stop`.
+    pub fn with_stop(mut self, stop: Vec<String>) -> Self {
+        self.stop = stop;
+        self
+    }
+
+    /// Extra request-body fields; see [`crate::merge_extra_body`].
+    pub fn with_extra_body(mut self, extra_body: Value) -> Self {
+        self.extra_body = extra_body;
+        self
+    }
 }
 
 #[async_trait]
@@ -213,12 +231,16 @@ impl crate::ModelProvider for BedrockProvider {
                 "temperature": self.temperature,
             }
         });
+        if !self.stop.is_empty() {
+            body["inferenceConfig"]["stopSequences"] = json!(self.stop);
+        }
         if !system_parts.is_empty() {
             body["system"] = json!(system_parts);
         }
         if let Some(tc) = tool_config {
             body["toolConfig"] = tc;
         }
+        crate::merge_extra_body(&mut body, &self.extra_body);
 
         let body_bytes = serde_json::to_vec(&body)?;
         let url = format!(
@@ -362,6 +384,7 @@ impl crate::ModelProvider for BedrockProvider {
                 cache_read_tokens: 0,
                 cache_write_tokens: 0,
                 cost_usd: None,
+                estimated: false,
             }));
         }
 
@@ -3,9 +3,11 @@
 // SPDX-License-Identifier: Apache-2.0
 //! Cohere driver — native Chat API v2.
 //!
-//! Uses the `POST /v2/chat` endpoint with streaming.
-//! Cohere's wire format differs from OpenAI: different message structure,
-//! different tool format, and a custom SSE event schema.
+//! Uses the `POST /v2/chat` endpoint, streaming or not.
+//! Cohere's wire format differs from OpenAI: an assistant turn carries all of
+//! its `tool_calls` together with the `tool_plan` (the model's reasoning
+//! before calling tools), responses list `content` blocks, and streaming uses
+//! a custom SSE event schema.
 
 use anyhow::{bail, Context};
 use async_trait::async_trait;
@@ -16,7 +18,7 @@ use tracing::debug;
 use crate::{
     catalog::{static_catalog, ModelCatalogEntry},
     provider::ResponseStream,
-    CompletionRequest, MessageContent, ResponseEvent, Role,
+    CompletionRequest, Message, MessageContent, ResponseEvent, Role,
 };
 
 pub struct CohereProvider {
@@ -25,6 +27,10 @@ pub struct CohereProvider {
     base_url: String,
     max_tokens: u32,
     temperature: f32,
+    /// Stop sequences, sent as `stop_sequences`.
+    stop: Vec<String>,
+    /// `driver_options.extra_body`, merged into the request body.
+    extra_body: Value,
     client: reqwest::Client,
 }
 
@@ -42,118 +48,31 @@ impl CohereProvider {
             base_url: base_url.unwrap_or_else(|| "https://api.cohere.com".into()),
             max_tokens: max_tokens.unwrap_or(4096),
             temperature: temperature.unwrap_or(0.2),
+            stop: Vec::new(),
+            extra_body: Value::Null,
             client: crate::build_http_client(),
         }
     }
-}
 
-#[async_trait]
-impl crate::ModelProvider for CohereProvider {
-    fn name(&self) -> &str {
-        "cohere"
-    }
-    fn model_name(&self) -> &str {
-        &self.model
+    /// Stop generating at any of `stop`.
+    pub fn with_stop(mut self, stop: Vec<String>) -> Self {
+        self.stop = stop;
+        self
     }
 
-    async fn list_models(&self) -> anyhow::Result<Vec<ModelCatalogEntry>> {
-        let mut entries: Vec<ModelCatalogEntry> = static_catalog()
-            .into_iter()
-            .filter(|e| e.provider == "cohere")
-            .collect();
-        entries.sort_by(|a, b| a.id.cmp(&b.id));
-        Ok(entries)
+    /// Extra request-body fields; see [`crate::merge_extra_body`].
+    pub fn with_extra_body(mut self, extra_body: Value) -> Self {
+        self.extra_body = extra_body;
+        self
     }
 
-    async fn complete(&self, req: CompletionRequest) -> anyhow::Result<ResponseStream> {
-        let key = self.api_key.as_deref().context("COHERE_API_KEY not set")?;
-
-        // Cohere v2 uses the same roles as OpenAI but different tool shapes.
-        let mut system_text = String::new();
-        let mut messages: Vec<Value> = Vec::new();
-
-        for m in &req.messages {
-            match m.role {
-                Role::System => {
-                    if let Some(t) = m.as_text() {
-                        system_text = if let Some(suffix) = &req.system_dynamic_suffix {
-                            if !suffix.trim().is_empty() {
-                                format!("{t}\n\n{suffix}")
-                            } else {
-                                t.to_string()
-                            }
-                        } else {
-                            t.to_string()
-                        };
-                    }
-                }
-                _ => {
-                    let role = match m.role {
-                        Role::User => "user",
-                        Role::Assistant => "assistant",
-                        Role::Tool => "tool",
-                        Role::System => unreachable!(),
-                    };
-                    match &m.content {
-                        MessageContent::Text(t) => {
-                            messages.push(json!({ "role": role, "content": t }));
-                        }
-                        // Cohere command-r does not support image inputs natively.
-                        // Concatenate text parts; images are represented as a note.
-                        MessageContent::ContentParts(parts) => {
-                            let text = parts
-                                .iter()
-                                .map(|p| match p {
-                                    crate::ContentPart::Text { text } => text.clone(),
-                                    crate::ContentPart::Image { .. } => "[image]".to_string(),
-                                })
-                                .collect::<Vec<_>>()
-                                .join("\n");
-                            messages.push(json!({ "role": role, "content": text }));
-                        }
-                        MessageContent::ToolCall {
-                            tool_call_id,
-                            function,
-                        } => {
-                            messages.push(json!({
-                                "role": "assistant",
-                                "tool_calls": [{
-                                    "id": tool_call_id,
-                                    "type": "function",
-                                    "function": {
-                                        "name": function.name,
-                                        "arguments": function.arguments,
-                                    }
-                                }]
-                            }));
-                        }
-                        MessageContent::ToolResult {
-                            tool_call_id,
-                            content,
-                        } => {
-                            let text_content = match content {
-                                crate::ToolResultContent::Text(t) => t.clone(),
-                                crate::ToolResultContent::Parts(parts) => parts
-                                    .iter()
-                                    .map(|p| match p {
-                                        crate::ToolContentPart::Text { text } => text.clone(),
-                                        crate::ToolContentPart::Image { .. } => {
-                                            "[image]".to_string()
-                                        }
-                                    })
-                                    .collect::<Vec<_>>()
-                                    .join("\n"),
-                            };
-                            messages.push(json!({
-                                "role": "tool",
-                                "tool_call_id": tool_call_id,
-                                "content": text_content,
-                            }));
-                        }
-                    }
-                }
-            }
+    /// Build the `/v2/chat` request body.
+    fn build_request_body(&self, req: &CompletionRequest) -> Value {
+        let mut messages = Vec::new();
+        if let Some(system) = system_text(req) {
+            messages.push(json!({ "role": "system", "content": system }));
         }
+        messages.extend(build_cohere_messages(&req.messages));
 
         let tools: Vec<Value> = req
             .tools
@@ -178,15 +97,38 @@ impl crate::ModelProvider for CohereProvider {
             "max_tokens": max_tokens,
             "temperature": self.temperature,
         });
-        if !system_text.is_empty() {
-            // Cohere v2: system message as first message with role "system"
-            if let Some(msgs) = body["messages"].as_array_mut() {
-                msgs.insert(0, json!({ "role": "system", "content": system_text }));
-            }
-        }
         if !tools.is_empty() {
             body["tools"] = json!(tools);
         }
+        if !self.stop.is_empty() {
+            body["stop_sequences"] = json!(self.stop);
+        }
+        crate::merge_extra_body(&mut body, &self.extra_body);
+        body
+    }
+}
+
+#[async_trait]
+impl crate::ModelProvider for CohereProvider {
+    fn name(&self) -> &str {
+        "cohere"
+    }
+    fn model_name(&self) -> &str {
+        &self.model
+    }
+
+    async fn list_models(&self) -> anyhow::Result<Vec<ModelCatalogEntry>> {
+        let mut entries: Vec<ModelCatalogEntry> = static_catalog()
+            .into_iter()
+            .filter(|e| e.provider == "cohere")
+            .collect();
+        entries.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(entries)
+    }
+
+    async fn complete(&self, req: CompletionRequest) -> anyhow::Result<ResponseStream> {
+        let key = self.api_key.as_deref().context("COHERE_API_KEY not set")?;
+        let body = self.build_request_body(&req);
 
         debug!(model = %self.model, "sending Cohere request");
 
@@ -206,6 +148,15 @@ impl crate::ModelProvider for CohereProvider {
             bail!("Cohere error {status}: {text}");
         }
 
+        if !req.stream {
+            let v: Value = resp
+                .json()
+                .await
+                .context("Cohere returned an invalid response")?;
+            let events = parse_cohere_response(&v);
+            return Ok(Box::pin(futures::stream::iter(events.into_iter().map(Ok))));
+        }
+
         let byte_stream = resp.bytes_stream();
         // Use a raw-byte buffer so that multi-byte UTF-8 sequences split
         // across chunk boundaries are never corrupted.  '\n' (0x0A) is
@@ -241,6 +192,104 @@ impl crate::ModelProvider for CohereProvider {
     }
 }
 
+/// The system prompt with the request's dynamic suffix appended.
+fn system_text(req: &CompletionRequest) -> Option<String> {
+    let text = req
+        .messages
+        .iter()
+        .rev()
+        .find(|m| m.role == Role::System)?
+        .as_text()?;
+    Some(match &req.system_dynamic_suffix {
+        Some(suffix) if !suffix.trim().is_empty() => format!("{text}\n\n{suffix}"),
+        _ => text.to_string(),
+    })
+}
+
+/// Convert the conversation (system messages excluded) to v2 `messages`.
+///
+/// Consecutive tool calls are merged into one assistant message, and the
+/// assistant text immediately before them becomes that message's
+/// `tool_plan`, which is how Cohere itself returns a tool-calling turn.
+fn build_cohere_messages(messages: &[Message]) -> Vec<Value> {
+    let mut out: Vec<Value> = Vec::new();
+    for m in messages {
+        let role = match m.role {
+            Role::System => continue,
+            Role::User => "user",
+            Role::Assistant => "assistant",
+            Role::Tool => "tool",
+        };
+        match &m.content {
+            MessageContent::Text(t) => {
+                out.push(json!({ "role": role, "content": t }));
+            }
+            // Cohere command models do not support image inputs natively.
+            // Concatenate text parts; images are represented as a note.
+            MessageContent::ContentParts(parts) => {
+                let text = parts
+                    .iter()
+                    .map(|p| match p {
+                        crate::ContentPart::Text { text } => text.clone(),
+                        crate::ContentPart::Image { .. } => "[image]".to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                out.push(json!({ "role": role, "content": text }));
+            }
+            MessageContent::ToolCall {
+                tool_call_id,
+                function,
+            } => {
+                let call = json!({
+                    "id": tool_call_id,
+                    "type": "function",
+                    "function": {
+                        "name": function.name,
+                        "arguments": function.arguments,
+                    }
+                });
+                match out.last_mut() {
+                    Some(prev) if prev["role"] == "assistant" && prev["tool_calls"].is_array() => {
+                        prev["tool_calls"].as_array_mut().unwrap().push(call);
+                    }
+                    Some(prev) if prev["role"] == "assistant" && prev["content"].is_string() => {
+                        let plan = prev["content"].take();
+                        *prev = json!({
+                            "role": "assistant",
+                            "tool_plan": plan,
+                            "tool_calls": [call],
+                        });
+                    }
+                    _ => out.push(json!({ "role": "assistant", "tool_calls": [call] })),
+                }
+            }
+            MessageContent::ToolResult {
+                tool_call_id,
+                content,
+            } => {
+                let text_content = match content {
+                    crate::ToolResultContent::Text(t) => t.clone(),
+                    crate::ToolResultContent::Parts(parts) => parts
+                        .iter()
+                        .map(|p| match p {
+                            crate::ToolContentPart::Text { text } => text.clone(),
+                            crate::ToolContentPart::Image { .. } => "[image]".to_string(),
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                };
+                out.push(json!({
+                    "role": "tool",
+                    "tool_call_id": tool_call_id,
+                    "content": text_content,
+                }));
+            }
+        }
+    }
+    out
+}
+
 fn parse_cohere_event(v: &Value) -> anyhow::Result<ResponseEvent> {
     let event_type = v["type"].as_str().unwrap_or("");
     match event_type {
@@ -251,6 +300,13 @@ fn parse_cohere_event(v: &Value) -> anyhow::Result<ResponseEvent> {
                 .to_string();
             Ok(ResponseEvent::TextDelta(text))
         }
+        "tool-plan-delta" => {
+            let plan = v["delta"]["message"]["tool_plan"]
+                .as_str()
+                .unwrap_or("")
+                .to_string();
+            Ok(ResponseEvent::ThinkingDelta(plan))
+        }
         "tool-call-start" | "tool-call-delta" => {
             let tc = &v["delta"]["message"]["tool_calls"];
             let id = tc["id"].as_str().unwrap_or("").to_string();
@@ -260,7 +316,7 @@ fn parse_cohere_event(v: &Value) -> anyhow::Result<ResponseEvent> {
                 .unwrap_or("")
                 .to_string();
             Ok(ResponseEvent::ToolCall {
-                index: 0,
+                index: v["index"].as_u64().unwrap_or(0) as u32,
                 id,
                 name,
                 arguments: args,
@@ -271,29 +327,75 @@ fn parse_cohere_event(v: &Value) -> anyhow::Result<ResponseEvent> {
             if v["delta"]["finish_reason"].as_str() == Some("MAX_TOKENS") {
                 return Ok(ResponseEvent::MaxTokens);
             }
-            if let Some(usage) = v.get("delta").and_then(|d| d.get("usage")) {
-                let input_tokens = usage["billed_units"]["input_tokens"]
-                    .as_u64()
-                    .or_else(|| usage["tokens"]["input_tokens"].as_u64())
-                    .unwrap_or(0) as u32;
-                let output_tokens = usage["billed_units"]["output_tokens"]
-                    .as_u64()
-                    .or_else(|| usage["tokens"]["output_tokens"].as_u64())
-                    .unwrap_or(0) as u32;
-                return Ok(ResponseEvent::Usage {
-                    input_tokens,
-                    output_tokens,
-                    cache_read_tokens: 0,
-                    cache_write_tokens: 0,
-                    cost_usd: None,
-                });
+            match v.get("delta").and_then(|d| d.get("usage")) {
+                Some(usage) => Ok(usage_event(usage)),
+                None => Ok(ResponseEvent::Done),
             }
-            Ok(ResponseEvent::Done)
         }
         _ => Ok(ResponseEvent::TextDelta(String::new())),
     }
 }
 
+/// Events for a non-streaming `/v2/chat` response, in stream order.
+fn parse_cohere_response(v: &Value) -> Vec<ResponseEvent> {
+    let message = &v["message"];
+    let mut events = Vec::new();
+    if let Some(plan) = message["tool_plan"].as_str().filter(|p| !p.is_empty()) {
+        events.push(ResponseEvent::ThinkingDelta(plan.to_string()));
+    }
+    for block in message["content"].as_array().into_iter().flatten() {
+        if block["type"] == "text" {
+            if let Some(text) = block["text"].as_str() {
+                events.push(ResponseEvent::TextDelta(text.to_string()));
+            }
+        }
+    }
+    for (index, tc) in message["tool_calls"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .enumerate()
+    {
+        events.push(ResponseEvent::ToolCall {
+            index: index as u32,
+            id: tc["id"].as_str().unwrap_or("").to_string(),
+            name: tc["function"]["name"].as_str().unwrap_or("").to_string(),
+            arguments: tc["function"]["arguments"]
+                .as_str()
+                .unwrap_or("")
+                .to_string(),
+        });
+    }
+    if v["finish_reason"].as_str() == Some("MAX_TOKENS") {
+        events.push(ResponseEvent::MaxTokens);
+    }
+    if let Some(usage) = v.get("usage") {
+        events.push(usage_event(usage));
+    }
+    events.push(ResponseEvent::Done);
+    events
+}
+
+/// `Usage` from a v2 `usage` object, preferring billed units.
+fn usage_event(usage: &Value) -> ResponseEvent {
+    let input_tokens = usage["billed_units"]["input_tokens"]
+        .as_u64()
+        .or_else(|| usage["tokens"]["input_tokens"].as_u64())
+        .unwrap_or(0) as u32;
+    let output_tokens = usage["billed_units"]["output_tokens"]
+        .as_u64()
+        .or_else(|| usage["tokens"]["output_tokens"].as_u64())
+        .unwrap_or(0) as u32;
+    ResponseEvent::Usage {
+        input_tokens,
+        output_tokens,
+        cache_read_tokens: 0,
+        cache_write_tokens: 0,
+        cost_usd: None,
+        estimated: false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -409,6 +511,220 @@ mod tests {
         assert!(matches!(ev, ResponseEvent::TextDelta(t) if t.is_empty()));
     }
 
+    #[test]
+    fn tool_plan_delta_is_thinking() {
+        let v = json!({
+            "type": "tool-plan-delta",
+            "delta": { "message": { "tool_plan": "I will list the files." } }
+        });
+        let ev = parse_cohere_event(&v).unwrap();
+        assert!(matches!(ev, ResponseEvent::ThinkingDelta(t) if t == "I will list the files."));
+    }
+
+    #[test]
+    fn parallel_tool_calls_keep_their_index() {
+        let v = json!({
+            "type": "tool-call-start",
+            "index": 1,
+            "delta": {
+                "message": {
+                    "tool_calls": {
+                        "id": "call_b",
+                        "type": "function",
+                        "function": { "name": "read_file", "arguments": "" }
+                    }
+                }
+            }
+        });
+        let ev = parse_cohere_event(&v).unwrap();
+        assert!(
+            matches!(&ev, ResponseEvent::ToolCall { index: 1, id, .. } if id == "call_b"),
+            "unexpected: {ev:?}"
+        );
+    }
+
+    // ── v2 request body ───────────────────────────────────────────────────────
+
+    fn tool_call(id: &str, name: &str, arguments: &str) -> Message {
+        Message {
+            role: Role::Assistant,
+            content: MessageContent::ToolCall {
+                tool_call_id: id.into(),
+                function: crate::FunctionCall {
+                    name: name.into(),
+                    arguments: arguments.into(),
+                },
+            },
+        }
+    }
+
+    #[test]
+    fn request_body_has_v2_message_and_tool_shape() {
+        let p = CohereProvider::new(
+            "command-a-03-2025".into(),
+            None,
+            Some("https://proxy.example/".into()),
+            Some(2048),
+            Some(0.7),
+        );
+        let req = CompletionRequest {
+            messages: vec![
+                Message::system("You are helpful."),
+                Message::user("What is in src?"),
+                Message::assistant("I will list both directories."),
+                tool_call("call_a", "shell", r#"{"cmd":"ls src"}"#),
+                tool_call("call_b", "shell", r#"{"cmd":"ls tests"}"#),
+                Message::tool_result("call_a", "main.rs"),
+                Message::tool_result("call_b", "it.rs"),
+                Message::assistant("src holds main.rs."),
+            ],
+            tools: vec![crate::ToolSchema {
+                name: "shell".into(),
+                description: "Run a command".into(),
+                parameters: json!({
+                    "type": "object",
+                    "properties": { "cmd": { "type": "string" } },
+                    "required": ["cmd"]
+                }),
+                ..crate::ToolSchema::default()
+            }],
+            stream: true,
+            system_dynamic_suffix: Some("Branch: main".into()),
+            ..CompletionRequest::default()
+        };
+
+        let body = p.build_request_body(&req);
+        assert_eq!(body["model"], "command-a-03-2025");
+        assert_eq!(body["stream"], true);
+        assert_eq!(body["max_tokens"], 2048);
+        assert!((body["temperature"].as_f64().unwrap() - 0.7).abs() < 1e-6);
+        assert_eq!(
+            body["tools"],
+            json!([{
+                "type": "function",
+                "function": {
+                    "name": "shell",
+                    "description": "Run a command",
+                    "parameters": {
+                        "type": "object",
+                        "properties": { "cmd": { "type": "string" } },
+                        "required": ["cmd"]
+                    }
+                }
+            }])
+        );
+        assert_eq!(
+            body["messages"],
+            json!([
+                { "role": "system", "content": "You are helpful.\n\nBranch: main" },
+                { "role": "user", "content": "What is in src?" },
+                {
+                    "role": "assistant",
+                    "tool_plan": "I will list both directories.",
+                    "tool_calls": [
+                        {
+                            "id": "call_a",
+                            "type": "function",
+                            "function": { "name": "shell", "arguments": "{\"cmd\":\"ls src\"}" }
+                        },
+                        {
+                            "id": "call_b",
+                            "type": "function",
+                            "function": { "name": "shell", "arguments": "{\"cmd\":\"ls tests\"}" }
+                        }
+                    ]
+                },
+                { "role": "tool", "tool_call_id": "call_a", "content": "main.rs" },
+                { "role": "tool", "tool_call_id": "call_b", "content": "it.rs" },
+                { "role": "assistant", "content": "src holds main.rs." }
+            ])
+        );
+    }
+
+    #[test]
+    fn max_output_override_and_no_tools() {
+        let p = CohereProvider::new("command-r".into(), None, None, None, None);
+        let req = CompletionRequest {
+            messages: vec![Message::user("hi"), tool_call("call_1", "shell", "{}")],
+            max_output_tokens_override: Some(64),
+            ..CompletionRequest::default()
+        };
+        let body = p.build_request_body(&req);
+        assert_eq!(body["max_tokens"], 64);
+        assert!(body.get("tools").is_none());
+        // A tool call without preceding assistant text opens its own message.
+        assert_eq!(body["messages"][1]["role"], "assistant");
+        assert!(body["messages"][1].get("tool_plan").is_none());
+        assert_eq!(body["messages"][1]["tool_calls"][0]["id"], "call_1");
+    }
+
+    // ── v2 non-streaming response ─────────────────────────────────────────────
+
+    #[test]
+    fn v2_tool_call_response_is_decoded() {
+        let v = json!({
+            "id": "resp_1",
+            "finish_reason": "TOOL_CALL",
+            "message": {
+                "role": "assistant",
+                "tool_plan": "I need the weather in two cities.",
+                "tool_calls": [
+                    {
+                        "id": "get_weather_1",
+                        "type": "function",
+                        "function": { "name": "get_weather", "arguments": "{\"city\":\"Oslo\"}" }
+                    },
+                    {
+                        "id": "get_weather_2",
+                        "type": "function",
+                        "function": { "name": "get_weather", "arguments": "{\"city\":\"Lund\"}" }
+                    }
+                ]
+            },
+            "usage": {
+                "billed_units": { "input_tokens": 37, "output_tokens": 21 },
+                "tokens": { "input_tokens": 900, "output_tokens": 60 }
+            }
+        });
+        let events = parse_cohere_response(&v);
+        assert!(
+            matches!(&events[0], ResponseEvent::ThinkingDelta(t) if t == "I need the weather in two cities.")
+        );
+        assert!(
+            matches!(&events[1], ResponseEvent::ToolCall { index: 0, id, name, arguments }
+            if id == "get_weather_1" && name == "get_weather" && arguments == r#"{"city":"Oslo"}"#)
+        );
+        assert!(
+            matches!(&events[2], ResponseEvent::ToolCall { index: 1, id, arguments, .. }
+            if id == "get_weather_2" && arguments == r#"{"city":"Lund"}"#)
+        );
+        assert!(matches!(
+            events[3],
+            ResponseEvent::Usage {
+                input_tokens: 37,
+                output_tokens: 21,
+                ..
+            }
+        ));
+        assert!(matches!(events[4], ResponseEvent::Done));
+        assert_eq!(events.len(), 5);
+    }
+
+    #[test]
+    fn v2_text_response_is_decoded() {
+        let v = json!({
+            "finish_reason": "MAX_TOKENS",
+            "message": {
+                "role": "assistant",
+                "content": [{ "type": "text", "text": "Partial answ" }]
+            }
+        });
+        let events = parse_cohere_response(&v);
+        assert!(matches!(&events[0], ResponseEvent::TextDelta(t) if t == "Partial answ"));
+        assert!(matches!(events[1], ResponseEvent::MaxTokens));
+        assert!(matches!(events[2], ResponseEvent::Done));
+    }
+
     // ── SSE Unicode chunk-boundary preservation ───────────────────────────────
 
     fn drain_sse_bytes(buf: &mut Vec<u8>) -> Vec<ResponseEvent> {
@@ -486,4 +802,16 @@ mod tests {
         assert_cohere_unicode_survives_split(content, lead + 2);
         assert_cohere_unicode_survives_split(content, lead + 3);
     }
+
+    #[test]
+    fn stop_sequences_are_sent_as_stop_sequences() {
+        let p = CohereProvider::new("command-r".into(), None, None, None, None)
+            .with_stop(vec!["END".into()]);
+        let req = CompletionRequest {
+            messages: vec![crate::Message::user("hi")],
+            ..CompletionRequest::default()
+        };
+        let body = p.build_request_body(&req);
+        assert_eq!(body["stop_sequences"], json!(["END"]));
+    }
 }
@@ -6,6 +6,12 @@
 //! Uses the `generateContent` / `streamGenerateContent` endpoints.
 //! Supports text, tool calls, and thinking deltas via `thought` parts.
 //!
+//! The system prompt goes to `systemInstruction`, tools to
+//! `functionDeclarations`, and tool calls and results to `functionCall` /
+//! `functionResponse` parts.  Gemini pairs those by function name and order,
+//! so the calls of one turn share a single `model` content and their results
+//! a single `user` content.
+//!
 //! # Auth
 //! API key via `x-goog-api-key` header (or `?key=...` query param).
 //!
@@ -21,7 +27,7 @@ use serde_json::{json, Value};
 use tracing::debug;
 
 use crate::{
-    catalog::{static_catalog, ModelCatalogEntry},
+    catalog::{static_catalog, InputModality, ModelCatalogEntry},
     provider::ResponseStream,
     CompletionRequest, MessageContent, ResponseEvent, Role,
 };
@@ -32,6 +38,10 @@ pub struct GoogleProvider {
     base_url: String,
     max_tokens: u32,
     temperature: f32,
+    /// Stop sequences, sent as `generationConfig.stopSequences`.
+    stop: Vec<String>,
+    /// `driver_options.extra_body`, merged into the request body.
+    extra_body: Value,
     client: reqwest::Client,
 }
 
@@ -50,32 +60,26 @@ impl GoogleProvider {
                 .unwrap_or_else(|| "https://generativelanguage.googleapis.com".into()),
             max_tokens: max_tokens.unwrap_or(8192),
             temperature: temperature.unwrap_or(0.2),
+            stop: Vec::new(),
+            extra_body: Value::Null,
             client: crate::build_http_client(),
         }
     }
-}
 
-#[async_trait]
-impl crate::ModelProvider for GoogleProvider {
-    fn name(&self) -> &str {
-        "google"
-    }
-    fn model_name(&self) -> &str {
-        &self.model
+    /// Stop generating at any of `stop`.
+    pub fn with_stop(mut self, stop: Vec<String>) -> Self {
+        self.stop = stop;
+        self
     }
 
-    async fn list_models(&self) -> anyhow::Result<Vec<ModelCatalogEntry>> {
-        let mut entries: Vec<ModelCatalogEntry> = static_catalog()
-            .into_iter()
-            .filter(|e| e.provider == "google")
-            .collect();
-        entries.sort_by(|a, b| a.id.cmp(&b.id));
-        Ok(entries)
+    /// Extra request-body fields; see [`crate::merge_extra_body`].
+    pub fn with_extra_body(mut self, extra_body: Value) -> Self {
+        self.extra_body = extra_body;
+        self
     }
 
-    async fn complete(&self, req: CompletionRequest) -> anyhow::Result<ResponseStream> {
-        let key = self.api_key.as_deref().context("GEMINI_API_KEY not set")?;
-
+    /// Build the `streamGenerateContent` request body.
+    fn build_request_body(&self, req: &CompletionRequest) -> Value {
         // Separate system instruction from conversation.
         // Also build a mapping from tool_call_id → function_name so that
         // functionResponse parts can use the correct function name (Gemini
@@ -83,6 +87,7 @@ impl crate::ModelProvider for GoogleProvider {
         let mut system_parts: Vec<Value> = Vec::new();
         let mut contents: Vec<Value> = Vec::new();
         let mut tc_name_map: HashMap<String, String> = HashMap::new();
+        let mut previous_was_result = false;
 
         for m in &req.messages {
             if let MessageContent::ToolCall {
@@ -109,13 +114,30 @@ impl crate::ModelProvider for GoogleProvider {
                         system_parts.push(json!({ "text": t }));
                     }
                 }
-                Role::User | Role::Tool => {
-                    let parts = message_to_gemini_parts(m, &tc_name_map);
-                    contents.push(json!({ "role": "user", "parts": parts }));
-                }
-                Role::Assistant => {
+                Role::User | Role::Tool | Role::Assistant => {
+                    let role = if m.role == Role::Assistant {
+                        "model"
+                    } else {
+                        "user"
+                    };
                     let parts = message_to_gemini_parts(m, &tc_name_map);
-                    contents.push(json!({ "role": "model", "parts": parts }));
+                    // A tool call joins the model turn it belongs to (text
+                    // and earlier calls); a tool result joins the results
+                    // of the other calls in that turn.
+                    let joins_previous = match &m.content {
+                        MessageContent::ToolCall { .. } => true,
+                        MessageContent::ToolResult { .. } => previous_was_result,
+                        _ => false,
+                    };
+                    previous_was_result = matches!(m.content, MessageContent::ToolResult { .. });
+                    match contents.last_mut() {
+                        Some(last) if joins_previous && last["role"] == role => {
+                            if let Some(existing) = last["parts"].as_array_mut() {
+                                existing.extend(parts);
+                            }
+                        }
+                        _ => contents.push(json!({ "role": role, "parts": parts })),
+                    }
                 }
             }
         }
@@ -131,7 +153,7 @@ impl crate::ModelProvider for GoogleProvider {
                     json!({
                         "name": t.name,
                         "description": t.description,
-                        "parameters": t.parameters,
+                        "parameters": gemini_schema(&t.parameters),
                     })
                 })
                 .collect();
@@ -146,13 +168,63 @@ impl crate::ModelProvider for GoogleProvider {
                 "temperature": self.temperature,
             }
         });
+        if !self.stop.is_empty() {
+            body["generationConfig"]["stopSequences"] = json!(self.stop);
+        }
         if !system_parts.is_empty() {
             body["systemInstruction"] = json!({ "parts": system_parts });
         }
         if let Some(tools) = tools_section {
             body["tools"] = tools;
         }
+        crate::merge_extra_body(&mut body, &self.extra_body);
+        body
+    }
+}
 
+#[async_trait]
+impl crate::ModelProvider for GoogleProvider {
+    fn name(&self) -> &str {
+        "google"
+    }
+    fn model_name(&self) -> &str {
+        &self.model
+    }
+
+    /// `GET /v1beta/models`, keeping the models that support
+    /// `generateContent`.  Falls back to the static catalog when no key is
+    /// set or the request fails.
+    async fn list_models(&self) -> anyhow::Result<Vec<ModelCatalogEntry>> {
+        let mut catalog_entries: Vec<ModelCatalogEntry> = static_catalog()
+            .into_iter()
+            .filter(|e| e.provider == "google")
+            .collect();
+        catalog_entries.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let Some(key) = self.api_key.as_deref() else {
+            return Ok(catalog_entries);
+        };
+        let req = self
+            .client
+            .get(format!(
+                "{}/v1beta/models?pageSize=1000",
+                self.base_url.trim_end_matches('/')
+            ))
+            .header("x-goog-api-key", key);
+        let Some(body) = crate::fetch_model_list(req).await else {
+            return Ok(catalog_entries);
+        };
+        let live = parse_models_response(&body, &catalog_entries);
+        if live.is_empty() {
+            return Ok(catalog_entries);
+        }
+        Ok(live)
+    }
+
+    async fn complete(&self, req: CompletionRequest) -> anyhow::Result<ResponseStream> {
+        let key = self.api_key.as_deref().context("GEMINI_API_KEY not set")?;
+
+        let body = self.build_request_body(&req);
         let url = format!(
             "{}/v1beta/models/{}:streamGenerateContent?alt=sse&key={}",
             self.base_url.trim_end_matches('/'),
@@ -181,7 +253,7 @@ impl crate::ModelProvider for GoogleProvider {
         // across chunk boundaries are never corrupted.  '\n' (0x0A) is
         // never a continuation byte, so splitting on it is safe.
         let event_stream = byte_stream
-            .scan(Vec::<u8>::new(), |buf, chunk| {
+            .scan((Vec::<u8>::new(), 0u32), |(buf, next_call), chunk| {
                 match chunk {
                     Ok(b) => buf.extend_from_slice(&b),
                     Err(e) => {
@@ -199,7 +271,7 @@ impl crate::ModelProvider for GoogleProvider {
                         if data == "[DONE]" {
                             events.push(Ok(ResponseEvent::Done));
                         } else if let Ok(v) = serde_json::from_str::<Value>(data) {
-                            events.push(parse_gemini_chunk(&v));
+                            events.extend(parse_gemini_chunk(&v, next_call).into_iter().map(Ok));
                         }
                     }
                 }
@@ -211,6 +283,109 @@ impl crate::ModelProvider for GoogleProvider {
     }
 }
 
+/// JSON Schema keywords `functionDeclarations` rejects.
+const UNSUPPORTED_SCHEMA_KEYS: &[&str] = &["$schema", "additionalProperties"];
+
+/// Convert a tool's JSON Schema to the OpenAPI subset Gemini accepts: drop
+/// the keywords it rejects and turn `"type": ["string", "null"]` into
+/// `"type": "string", "nullable": true`.
+fn gemini_schema(schema: &Value) -> Value {
+    match schema {
+        Value::Object(map) => {
+            let mut out = serde_json::Map::new();
+            for (key, value) in map {
+                if UNSUPPORTED_SCHEMA_KEYS.contains(&key.as_str()) {
+                    continue;
+                }
+                if let (Some(types), "type") = (value.as_array(), key.as_str()) {
+                    if let Some(first) = types.iter().find(|t| *t != "null") {
+                        out.insert("type".into(), first.clone());
+                    }
+                    if types.iter().any(|t| t == "null") {
+                        out.insert("nullable".into(), Value::Bool(true));
+                    }
+                    continue;
+                }
+                out.insert(key.clone(), gemini_schema(value));
+            }
+            Value::Object(out)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(gemini_schema).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Image MIME types Gemini accepts as `inlineData`.
+const GEMINI_IMAGE_MIMES: &[&str] = &[
+    "image/png",
+    "image/jpeg",
+    "image/webp",
+    "image/heic",
+    "image/heif",
+];
+
+/// Normalise an image MIME type for Gemini: strip parameters, fold aliases
+/// such as `image/jpg`, and — when the data URL carries no usable type —
+/// recognise PNG, JPEG and WebP from the start of the base64 payload.
+fn gemini_image_mime(mime: &str, b64: &str) -> String {
+    let mime = mime
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    match mime.as_str() {
+        "image/jpg" | "image/pjpeg" => "image/jpeg".into(),
+        "" | "application/octet-stream" => {
+            let sniffed = if b64.starts_with("iVBORw0KGgo") {
+                "image/png"
+            } else if b64.starts_with("/9j/") {
+                "image/jpeg"
+            } else if b64.starts_with("UklGR") {
+                "image/webp"
+            } else {
+                ""
+            };
+            sniffed.into()
+        }
+        _ => mime,
+    }
+}
+
+/// The Gemini part for an image attachment: `inlineData` for a data URL,
+/// `fileData` for a remote URL, or a text note when Gemini cannot read the
+/// format (GIF, BMP, …).
+fn gemini_image_part(image_url: &str) -> Value {
+    match crate::types::parse_data_url_parts(image_url) {
+        Ok((mime, data)) => {
+            let mime = gemini_image_mime(&mime, &data);
+            if GEMINI_IMAGE_MIMES.contains(&mime.as_str()) {
+                json!({ "inlineData": { "mimeType": mime, "data": data } })
+            } else {
+                let shown = if mime.is_empty() { "unknown" } else { &mime };
+                json!({ "text": format!("[image omitted: {shown} is not supported by Gemini]") })
+            }
+        }
+        Err(_) => {
+            // Remote URL: Gemini wants its type alongside the URI.
+            let ext = image_url
+                .split(['?', '#'])
+                .next()
+                .and_then(|path| path.rsplit_once('.'))
+                .map(|(_, ext)| ext.to_ascii_lowercase())
+                .unwrap_or_default();
+            let mime = match ext.as_str() {
+                "png" => "image/png",
+                "webp" => "image/webp",
+                "heic" => "image/heic",
+                "heif" => "image/heif",
+                _ => "image/jpeg",
+            };
+            json!({ "fileData": { "mimeType": mime, "fileUri": image_url } })
+        }
+    }
+}
+
 /// Convert a sven message into Gemini API `parts` array.
 ///
 /// `tc_name_map` maps `tool_call_id → function_name` so that `functionResponse`
@@ -230,19 +405,7 @@ fn message_to_gemini_parts(
                 .iter()
                 .map(|p| match p {
                     crate::ContentPart::Text { text } => json!({ "text": text }),
-                    crate::ContentPart::Image { image_url, .. } => {
-                        if let Ok((mime, data)) = crate::types::parse_data_url_parts(image_url) {
-                            json!({
-                                "inline_data": {
-                                    "mime_type": mime,
-                                    "data": data,
-                                }
-                            })
-                        } else {
-                            // Remote URL
-                            json!({ "file_data": { "file_uri": image_url } })
-                        }
-                    }
+                    crate::ContentPart::Image { image_url, .. } => gemini_image_part(image_url),
                 })
                 .collect()
         }
@@ -280,7 +443,7 @@ fn message_to_gemini_parts(
                 }
                 crate::ToolResultContent::Parts(parts) => {
                     // Gemini functionResponse carries text in "output".
-                    // Images are emitted as separate inline_data parts alongside
+                    // Images are emitted as separate inlineData parts alongside
                     // the functionResponse part.
                     let output_text: String = parts
                         .iter()
@@ -305,12 +468,7 @@ fn message_to_gemini_parts(
                     })];
                     for p in parts {
                         if let crate::ToolContentPart::Image { image_url } = p {
-                            if let Ok((mime, data)) = crate::types::parse_data_url_parts(image_url)
-                            {
-                                result_parts.push(json!({
-                                    "inline_data": { "mime_type": mime, "data": data }
-                                }));
-                            }
+                            result_parts.push(gemini_image_part(image_url));
                         }
                     }
                     result_parts
@@ -320,7 +478,48 @@ fn message_to_gemini_parts(
     }
 }
 
-fn parse_gemini_chunk(v: &Value) -> anyhow::Result<ResponseEvent> {
+/// Events for one streamed `GenerateContentResponse`, in the order the
+/// agent consumes them: parts, then usage, then the finish signal.
+///
+/// Gemini sends every `functionCall` complete in a single part, so each gets
+/// its own slot; `next_call` numbers them across the whole stream.  Calls
+/// carry no id unless Gemini assigns one, so a unique id is derived from the
+/// name and slot.
+fn parse_gemini_chunk(v: &Value, next_call: &mut u32) -> Vec<ResponseEvent> {
+    let mut events = Vec::new();
+    let candidate = &v["candidates"][0];
+
+    for part in candidate["content"]["parts"]
+        .as_array()
+        .into_iter()
+        .flatten()
+    {
+        // Thinking / reasoning delta
+        if part.get("thought").and_then(|t| t.as_bool()) == Some(true) {
+            if let Some(text) = part["text"].as_str() {
+                events.push(ResponseEvent::ThinkingDelta(text.to_string()));
+            }
+        } else if let Some(fc) = part.get("functionCall") {
+            let index = *next_call;
+            *next_call += 1;
+            let name = fc["name"].as_str().unwrap_or("").to_string();
+            let id = fc["id"]
+                .as_str()
+                .filter(|id| !id.is_empty())
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("{name}-{index}"));
+            let args = serde_json::to_string(&fc["args"]).unwrap_or_default();
+            events.push(ResponseEvent::ToolCall {
+                index,
+                id,
+                name,
+                arguments: args,
+            });
+        } else if let Some(text) = part["text"].as_str() {
+            events.push(ResponseEvent::TextDelta(text.to_string()));
+        }
+    }
+
     // Usage metadata
     if let Some(meta) = v.get("usageMetadata") {
         // Google Gemini reports cached tokens in cachedContentTokenCount.
@@ -328,62 +527,78 @@ fn parse_gemini_chunk(v: &Value) -> anyhow::Result<ResponseEvent> {
         // get fresh-only so that total_ctx = input + cache_read is not inflated.
         let cache_read_tokens = meta["cachedContentTokenCount"].as_u64().unwrap_or(0) as u32;
         let prompt_total = meta["promptTokenCount"].as_u64().unwrap_or(0) as u32;
-        return Ok(ResponseEvent::Usage {
+        events.push(ResponseEvent::Usage {
             input_tokens: prompt_total.saturating_sub(cache_read_tokens),
             output_tokens: meta["candidatesTokenCount"].as_u64().unwrap_or(0) as u32,
             cache_read_tokens,
             cache_write_tokens: 0,
             cost_usd: None,
+            estimated: false,
         });
     }
 
-    let candidate = &v["candidates"][0];
-    let content = &candidate["content"];
-    let parts = match content["parts"].as_array() {
-        Some(p) => p,
-        None => {
-            // End of stream signal — check whether the model ran out of tokens.
-            match candidate["finishReason"].as_str() {
-                Some("MAX_TOKENS") => return Ok(ResponseEvent::MaxTokens),
-                Some(_) => return Ok(ResponseEvent::Done),
-                None => {}
-            }
-            return Ok(ResponseEvent::TextDelta(String::new()));
-        }
-    };
-
-    for part in parts {
-        // Thinking / reasoning delta
-        if part.get("thought").and_then(|t| t.as_bool()) == Some(true) {
-            if let Some(text) = part["text"].as_str() {
-                return Ok(ResponseEvent::ThinkingDelta(text.to_string()));
-            }
-        }
-        // Function call
-        if let Some(fc) = part.get("functionCall") {
-            let name = fc["name"].as_str().unwrap_or("").to_string();
-            let args = serde_json::to_string(&fc["args"]).unwrap_or_default();
-            return Ok(ResponseEvent::ToolCall {
-                index: 0,
-                id: name.clone(),
-                name,
-                arguments: args,
-            });
-        }
-        // Text
-        if let Some(text) = part["text"].as_str() {
-            return Ok(ResponseEvent::TextDelta(text.to_string()));
-        }
-    }
-
-    // finishReason present without parts → stream finished
+    // finishReason marks the last chunk of the stream.
     match candidate["finishReason"].as_str() {
-        Some("MAX_TOKENS") => return Ok(ResponseEvent::MaxTokens),
-        Some(_) => return Ok(ResponseEvent::Done),
+        Some("MAX_TOKENS") => events.push(ResponseEvent::MaxTokens),
+        Some(_) => events.push(ResponseEvent::Done),
         None => {}
     }
+    events
+}
 
-    Ok(ResponseEvent::TextDelta(String::new()))
+/// Map a Gemini `GET /v1beta/models` response to catalog entries.
+///
+/// Models that cannot `generateContent` (embedding and AQA models) are
+/// skipped.  `inputTokenLimit` / `outputTokenLimit` give the token limits;
+/// input modalities and the description come from the matching
+/// static-catalog entry when there is one.
+pub(crate) fn parse_models_response(
+    body: &Value,
+    catalog_entries: &[ModelCatalogEntry],
+) -> Vec<ModelCatalogEntry> {
+    let Some(models) = body["models"].as_array() else {
+        return Vec::new();
+    };
+    let mut entries: Vec<ModelCatalogEntry> = models
+        .iter()
+        .filter(|item| {
+            item["supportedGenerationMethods"]
+                .as_array()
+                .is_some_and(|m| m.iter().any(|v| v == "generateContent"))
+        })
+        .filter_map(|item| {
+            let name = item["name"].as_str()?;
+            let id = name.strip_prefix("models/").unwrap_or(name);
+            let cat = catalog_entries.iter().find(|e| e.id == id);
+            let limit = |key: &str, fallback: Option<u32>| {
+                item[key]
+                    .as_u64()
+                    .map(|n| n as u32)
+                    .or(fallback)
+                    .unwrap_or(0)
+            };
+            Some(ModelCatalogEntry {
+                id: id.to_string(),
+                name: item["displayName"]
+                    .as_str()
+                    .filter(|s| !s.is_empty())
+                    .unwrap_or(id)
+                    .to_string(),
+                provider: "google".to_string(),
+                context_window: limit("inputTokenLimit", cat.map(|c| c.context_window)),
+                max_output_tokens: limit("outputTokenLimit", cat.map(|c| c.max_output_tokens)),
+                description: cat
+                    .map(|c| c.description.clone())
+                    .or_else(|| item["description"].as_str().map(str::to_string))
+                    .unwrap_or_default(),
+                input_modalities: cat
+                    .map(|c| c.input_modalities.clone())
+                    .unwrap_or_else(|| vec![InputModality::Text]),
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| a.id.cmp(&b.id));
+    entries
 }
 
 #[cfg(test)]
@@ -391,6 +606,68 @@ mod tests {
     use super::*;
     use crate::ModelProvider;
 
+    #[test]
+    fn models_response_maps_to_catalog_entries() {
+        let catalog = vec![ModelCatalogEntry {
+            id: "gemini-known".into(),
+            name: "Known".into(),
+            provider: "google".into(),
+            context_window: 1,
+            max_output_tokens: 1,
+            description: "from catalog".into(),
+            input_modalities: vec![InputModality::Text, InputModality::Image],
+        }];
+        let body = json!({
+            "models": [
+                {
+                    "name": "models/gemini-known",
+                    "displayName": "Gemini Known",
+                    "description": "from api",
+                    "inputTokenLimit": 1_048_576,
+                    "outputTokenLimit": 65_536,
+                    "supportedGenerationMethods": ["generateContent", "countTokens"]
+                },
+                {
+                    "name": "models/gemini-new",
+                    "displayName": "Gemini New",
+                    "inputTokenLimit": 32_768,
+                    "outputTokenLimit": 8_192,
+                    "supportedGenerationMethods": ["generateContent"]
+                },
+                {
+                    "name": "models/text-embedding-004",
+                    "inputTokenLimit": 2_048,
+                    "outputTokenLimit": 1,
+                    "supportedGenerationMethods": ["embedContent"]
+                }
+            ],
+            "nextPageToken": ""
+        });
+        let entries = parse_models_response(&body, &catalog);
+        let ids: Vec<&str> = entries.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, ["gemini-known", "gemini-new"]);
+
+        let known = &entries[0];
+        assert_eq!(known.name, "Gemini Known");
+        assert_eq!(known.context_window, 1_048_576, "live limits win");
+        assert_eq!(known.max_output_tokens, 65_536);
+        assert_eq!(known.description, "from catalog");
+        assert!(known.supports_images());
+
+        let new = &entries[1];
+        assert_eq!(new.provider, "google");
+        assert_eq!(new.context_window, 32_768);
+        assert_eq!(new.max_output_tokens, 8_192);
+        assert!(!new.supports_images());
+    }
+
+    /// The single event a one-part chunk decodes to.
+    fn parse_one(v: &Value) -> ResponseEvent {
+        let mut events = parse_gemini_chunk(v, &mut 0);
+        assert_eq!(events.len(), 1, "{events:?}");
+        events.remove(0)
+    }
+
     #[test]
     fn provider_name() {
         let p = GoogleProvider::new("gemini-2.0-flash-exp".into(), None, None, None, None);
@@ -406,7 +683,7 @@ mod tests {
                 "candidatesTokenCount": 50,
             }
         });
-        let ev = parse_gemini_chunk(&v).unwrap();
+        let ev = parse_one(&v);
         assert!(matches!(
             ev,
             ResponseEvent::Usage {
@@ -426,7 +703,7 @@ mod tests {
                 }
             }]
         });
-        let ev = parse_gemini_chunk(&v).unwrap();
+        let ev = parse_one(&v);
         assert!(matches!(ev, ResponseEvent::TextDelta(t) if t == "hello"));
     }
 
@@ -439,7 +716,7 @@ mod tests {
                 }
             }]
         });
-        let ev = parse_gemini_chunk(&v).unwrap();
+        let ev = parse_one(&v);
         assert!(matches!(ev, ResponseEvent::ThinkingDelta(t) if t == "thinking..."));
     }
 
@@ -457,10 +734,176 @@ mod tests {
                 }
             }]
         });
-        let ev = parse_gemini_chunk(&v).unwrap();
+        let ev = parse_one(&v);
         assert!(matches!(ev, ResponseEvent::ToolCall { name, .. } if name == "shell"));
     }
 
+    #[test]
+    fn parallel_function_calls_get_distinct_slots_across_chunks() {
+        let mut next_call = 0;
+        let first = json!({
+            "candidates": [{
+                "content": {
+                    "role": "model",
+                    "parts": [
+                        { "text": "Reading both files." },
+                        { "functionCall": { "name": "read_file", "args": { "path": "a.rs" } } },
+                        { "functionCall": { "name": "read_file", "args": { "path": "b.rs" } } }
+                    ]
+                }
+            }]
+        });
+        let events = parse_gemini_chunk(&first, &mut next_call);
+        assert!(matches!(&events[0], ResponseEvent::TextDelta(t) if t == "Reading both files."));
+        assert!(
+            matches!(&events[1], ResponseEvent::ToolCall { index: 0, id, name, arguments }
+            if id == "read_file-0" && name == "read_file" && arguments == r#"{"path":"a.rs"}"#)
+        );
+        assert!(
+            matches!(&events[2], ResponseEvent::ToolCall { index: 1, id, arguments, .. }
+            if id == "read_file-1" && arguments == r#"{"path":"b.rs"}"#)
+        );
+        assert_eq!(events.len(), 3);
+
+        let last = json!({
+            "candidates": [{
+                "content": {
+                    "role": "model",
+                    "parts": [{
+                        "functionCall": { "id": "gc_7", "name": "shell", "args": { "cmd": "ls" } }
+                    }]
+                },
+                "finishReason": "STOP"
+            }],
+            "usageMetadata": { "promptTokenCount": 120, "candidatesTokenCount": 30 }
+        });
+        let events = parse_gemini_chunk(&last, &mut next_call);
+        assert!(
+            matches!(&events[0], ResponseEvent::ToolCall { index: 2, id, name, .. }
+            if id == "gc_7" && name == "shell")
+        );
+        assert!(matches!(
+            events[1],
+            ResponseEvent::Usage {
+                input_tokens: 120,
+                output_tokens: 30,
+                ..
+            }
+        ));
+        assert!(matches!(events[2], ResponseEvent::Done));
+        assert_eq!(events.len(), 3);
+    }
+
+    #[test]
+    fn final_chunk_keeps_text_and_reports_max_tokens_after_usage() {
+        let v = json!({
+            "candidates": [{
+                "content": { "parts": [{ "text": "cut sho" }] },
+                "finishReason": "MAX_TOKENS"
+            }],
+            "usageMetadata": { "promptTokenCount": 10, "candidatesTokenCount": 5 }
+        });
+        let events = parse_gemini_chunk(&v, &mut 0);
+        assert!(matches!(&events[0], ResponseEvent::TextDelta(t) if t == "cut sho"));
+        assert!(matches!(events[1], ResponseEvent::Usage { .. }));
+        assert!(matches!(events[2], ResponseEvent::MaxTokens));
+    }
+
+    // ── Request mapping ───────────────────────────────────────────────────────
+
+    #[test]
+    fn request_maps_system_tools_and_function_turns() {
+        use crate::{FunctionCall, Message, ToolSchema};
+        let call = |id: &str, path: &str| Message {
+            role: Role::Assistant,
+            content: MessageContent::ToolCall {
+                tool_call_id: id.into(),
+                function: FunctionCall {
+                    name: "read_file".into(),
+                    arguments: format!(r#"{{"path":"{path}"}}"#),
+                },
+            },
+        };
+        let p = GoogleProvider::new("gemini-2.5-pro".into(), None, None, Some(1024), Some(0.5));
+        let req = CompletionRequest {
+            messages: vec![
+                Message::system("Be brief."),
+                Message::user("Compare a.rs and b.rs"),
+                Message::assistant("Reading both."),
+                call("read_file-0", "a.rs"),
+                call("read_file-1", "b.rs"),
+                Message::tool_result("read_file-0", "fn a() {}"),
+                Message::tool_result("read_file-1", "fn b() {}"),
+                Message::assistant("They differ in name only."),
+                Message::user("Thanks"),
+            ],
+            tools: vec![ToolSchema {
+                name: "read_file".into(),
+                description: "Read a file".into(),
+                parameters: json!({
+                    "$schema": "http://json-schema.org/draft-07/schema#",
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "limit": { "type": ["integer", "null"] }
+                    },
+                    "required": ["path"],
+                    "additionalProperties": false
+                }),
+                ..ToolSchema::default()
+            }],
+            system_dynamic_suffix: Some("cwd: /repo".into()),
+            ..CompletionRequest::default()
+        };
+
+        let body = p.build_request_body(&req);
+        assert_eq!(
+            body["systemInstruction"],
+            json!({ "parts": [{ "text": "Be brief.\n\ncwd: /repo" }] })
+        );
+        assert_eq!(
+            body["tools"],
+            json!([{
+                "functionDeclarations": [{
+                    "name": "read_file",
+                    "description": "Read a file",
+                    "parameters": {
+                        "type": "object",
+                        "properties": {
+                            "path": { "type": "string" },
+                            "limit": { "type": "integer", "nullable": true }
+                        },
+                        "required": ["path"]
+                    }
+                }]
+            }])
+        );
+        assert_eq!(body["generationConfig"]["maxOutputTokens"], 1024);
+        assert_eq!(
+            body["contents"],
+            json!([
+                { "role": "user", "parts": [{ "text": "Compare a.rs and b.rs" }] },
+                {
+                    "role": "model",
+                    "parts": [
+                        { "text": "Reading both." },
+                        { "functionCall": { "name": "read_file", "args": { "path": "a.rs" } } },
+                        { "functionCall": { "name": "read_file", "args": { "path": "b.rs" } } }
+                    ]
+                },
+                {
+                    "role": "user",
+                    "parts": [
+                        { "functionResponse": { "name": "read_file", "response": { "output": "fn a() {}" } } },
+                        { "functionResponse": { "name": "read_file", "response": { "output": "fn b() {}" } } }
+                    ]
+                },
+                { "role": "model", "parts": [{ "text": "They differ in name only." }] },
+                { "role": "user", "parts": [{ "text": "Thanks" }] }
+            ])
+        );
+    }
+
     // ── message_to_gemini_parts ───────────────────────────────────────────────
 
     #[test]
@@ -479,7 +922,7 @@ mod tests {
         };
         let tr_msg = Message::tool_result("call_opaque_id_123", "contents");
 
-        // Build the lookup map as `complete()` does.
+        // Build the lookup map as `build_request_body()` does.
         let mut tc_name_map = HashMap::new();
         if let MessageContent::ToolCall {
             tool_call_id,
@@ -516,7 +959,7 @@ mod tests {
             }],
         );
         let parts = message_to_gemini_parts(&msg, &HashMap::new());
-        // Should have functionResponse + 1 inline_data part
+        // Should have functionResponse + 1 inlineData part
         assert!(!parts.is_empty());
         let resp_output = &parts[0]["functionResponse"]["response"]["output"];
         assert_eq!(
@@ -536,8 +979,88 @@ mod tests {
         ]);
         let parts = message_to_gemini_parts(&msg, &HashMap::new());
         assert_eq!(parts[0]["text"], "look");
-        assert_eq!(parts[1]["inline_data"]["mime_type"], "image/png");
-        assert_eq!(parts[1]["inline_data"]["data"], "abc=");
+        assert_eq!(parts[1]["inlineData"]["mimeType"], "image/png");
+        assert_eq!(parts[1]["inlineData"]["data"], "abc=");
+    }
+
+    /// Base64 of a PNG signature and IHDR chunk start.
+    const PNG_B64: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAAB";
+
+    #[test]
+    fn attached_png_becomes_inline_data_part() {
+        use crate::{ContentPart, Message};
+        let msg = Message::user_with_parts(vec![
+            ContentPart::Text {
+                text: "What is in this screenshot?".into(),
+            },
+            ContentPart::image(format!("data:image/png;base64,{PNG_B64}")),
+        ]);
+        let parts = message_to_gemini_parts(&msg, &HashMap::new());
+        assert_eq!(
+            Value::Array(parts),
+            json!([
+                { "text": "What is in this screenshot?" },
+                { "inlineData": { "mimeType": "image/png", "data": PNG_B64 } }
+            ])
+        );
+    }
+
+    #[test]
+    fn read_image_result_sends_the_image_beside_the_function_response() {
+        use crate::{Message, ToolContentPart};
+        let msg = Message::tool_result_with_parts(
+            "call_1",
+            vec![
+                ToolContentPart::Text {
+                    text: "shot.png (1x1)".into(),
+                },
+                ToolContentPart::Image {
+                    image_url: format!("data:image/png;base64,{PNG_B64}"),
+                },
+            ],
+        );
+        let names = HashMap::from([("call_1".to_string(), "read_image".to_string())]);
+        let parts = message_to_gemini_parts(&msg, &names);
+        assert_eq!(
+            Value::Array(parts),
+            json!([
+                {
+                    "functionResponse": {
+                        "name": "read_image",
+                        "response": { "output": "shot.png (1x1)" }
+                    }
+                },
+                { "inlineData": { "mimeType": "image/png", "data": PNG_B64 } }
+            ])
+        );
+    }
+
+    #[test]
+    fn image_mime_types_are_mapped_for_gemini() {
+        assert_eq!(gemini_image_mime("image/JPG", "/9j/4AAQ"), "image/jpeg");
+        assert_eq!(gemini_image_mime("image/png; name=a.png", ""), "image/png");
+        assert_eq!(gemini_image_mime("", PNG_B64), "image/png");
+        assert_eq!(
+            gemini_image_mime("application/octet-stream", "/9j/4AAQ"),
+            "image/jpeg"
+        );
+        assert_eq!(gemini_image_mime("", "UklGRiQAAABXRUJQ"), "image/webp");
+
+        let gif = gemini_image_part("data:image/gif;base64,R0lGODlhAQABAAAAACw=");
+        assert_eq!(
+            gif["text"],
+            "[image omitted: image/gif is not supported by Gemini]"
+        );
+        let remote = gemini_image_part("https://example.com/chart.PNG?size=2");
+        assert_eq!(
+            remote,
+            json!({
+                "fileData": {
+                    "mimeType": "image/png",
+                    "fileUri": "https://example.com/chart.PNG?size=2"
+                }
+            })
+        );
     }
 
     // ── SSE Unicode chunk-boundary preservation ───────────────────────────────
@@ -551,9 +1074,7 @@ mod tests {
                 .to_string();
             if let Some(data) = line.strip_prefix("data: ") {
                 if let Ok(v) = serde_json::from_str::<serde_json::Value>(data) {
-                    if let Ok(ev) = parse_gemini_chunk(&v) {
-                        events.push(ev);
-                    }
+                    events.extend(parse_gemini_chunk(&v, &mut 0));
                 }
             }
         }
@@ -617,4 +1138,16 @@ mod tests {
         assert_google_unicode_survives_split(content, lead + 2);
         assert_google_unicode_survives_split(content, lead + 3);
     }
+
+    #[test]
+    fn stop_sequences_go_in_generation_config() {
+        let p = GoogleProvider::new("gemini-2.5-pro".into(), None, None, None, None)
+            .with_stop(vec!["END".into()]);
+        let req = CompletionRequest {
+            messages: vec![crate::Message::user("hi")],
+            ..CompletionRequest::default()
+        };
+        let body = p.build_request_body(&req);
+        assert_eq!(body["generationConfig"]["stopSequences"], json!(["END"]));
+    }
 }
@@ -19,15 +19,20 @@ pub use anthropic::AnthropicProvider;
 pub use catalog::{InputModality, ModelCatalogEntry};
 pub use mock::{MockProvider, ScriptedMockProvider};
 pub use openai::OpenAiProvider;
-pub use provider::ModelProvider;
-pub use registry::{get_driver, list_drivers, DriverMeta};
+pub use openai_compat::StreamIdleTimeout;
+pub use provider::{ModelProvider, ResponseStream};
+pub use registry::{
+    check_providers, filter_drivers, get_driver, list_drivers, DriverCategory, DriverFilter,
+    DriverMeta, ProviderIssue,
+};
 pub use types::*;
 pub use yaml_mock::YamlMockProvider;
 
-use anyhow::bail;
+use anyhow::{bail, Context};
 use async_trait::async_trait;
 use futures::Stream;
 use openai_compat::{AuthStyle, OpenAICompatProvider};
+use std::path::Path;
 use std::pin::Pin;
 use std::time::Duration;
 use sven_config::ModelConfig;
@@ -46,8 +51,10 @@ use sven_config::ModelConfig;
 ///   endpoint is unreachable or DNS resolution stalls.
 ///
 /// No total request timeout is set because SSE streaming responses legitimately
-/// run for minutes (or hours for long agentic tasks).  The per-chunk idle
-/// timeout is enforced separately in the agent's streaming loop.
+/// run for minutes (or hours for long agentic tasks).  Instead, the
+/// OpenAI-compatible drivers abort a stream that receives no bytes for
+/// `stream_idle_timeout_secs`, and the agent's streaming loop enforces a
+/// coarser per-chunk timeout for every provider.
 pub(crate) fn build_http_client() -> reqwest::Client {
     reqwest::Client::builder()
         .tcp_keepalive(Duration::from_secs(30))
@@ -56,8 +63,188 @@ pub(crate) fn build_http_client() -> reqwest::Client {
         .expect("failed to build HTTP client")
 }
 
+/// Send a lightweight authenticated request for
+/// [`ModelProvider::health_check`] and fail on a transport error or a
+/// non-success status.
+pub(crate) async fn check_endpoint(req: reqwest::RequestBuilder) -> anyhow::Result<()> {
+    let resp = req
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await
+        .context("endpoint unreachable")?;
+    let status = resp.status();
+    if !status.is_success() {
+        let body = resp.text().await.unwrap_or_default();
+        let body: String = body.chars().take(200).collect();
+        bail!("endpoint returned {status}: {}", body.trim());
+    }
+    Ok(())
+}
+
+/// Send a model-list request for [`ModelProvider::list_models`] and return
+/// the JSON body, or `None` on any failure so the caller can fall back to the
+/// static catalog.
+pub(crate) async fn fetch_model_list(req: reqwest::RequestBuilder) -> Option<serde_json::Value> {
+    let resp = req.timeout(Duration::from_secs(10)).send().await.ok()?;
+    if !resp.status().is_success() {
+        tracing::debug!(status = %resp.status(), "model list request failed");
+        return None;
+    }
+    resp.json().await.ok()
+}
+
+/// Merge `driver_options.extra_body` into a finished request `body`.
+///
+/// Fields sven already set win: a colliding key is skipped with a warning,
+/// so experimental parameters can be added but never change what sven
+/// manages (model, messages, tools, limits, …).
+pub(crate) fn merge_extra_body(body: &mut serde_json::Value, extra_body: &serde_json::Value) {
+    let (Some(body), Some(extra)) = (body.as_object_mut(), extra_body.as_object()) else {
+        return;
+    };
+    for (key, value) in extra {
+        if body.contains_key(key) {
+            tracing::warn!(
+                key = %key,
+                "driver_options.extra_body sets a field sven manages; ignoring it"
+            );
+        } else {
+            body.insert(key.clone(), value.clone());
+        }
+    }
+}
+
 // ── Private helpers ───────────────────────────────────────────────────────────
 
+/// The most stop sequences `provider` accepts in one request, when it has a
+/// documented limit.
+fn stop_sequence_limit(provider: &str) -> Option<usize> {
+    match provider {
+        "openai" | "azure" => Some(4),
+        "google" | "cohere" => Some(5),
+        _ => None,
+    }
+}
+
+/// Reject `model.stop` lists the provider would refuse, so the error names
+/// the config key instead of surfacing as an HTTP 400 mid-run.
+fn check_stop_sequences(cfg: &ModelConfig) -> anyhow::Result<()> {
+    let Some(stop) = &cfg.stop else {
+        return Ok(());
+    };
+    if stop.iter().any(String::is_empty) {
+        bail!("model.stop must not contain empty strings");
+    }
+    if let Some(max) = stop_sequence_limit(&cfg.provider) {
+        if stop.len() > max {
+            bail!(
+                "model.stop has {} stop sequences but provider '{}' accepts at most {max}",
+                stop.len(),
+                cfg.provider,
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Validate `driver_options.logit_bias`, which OpenAI-compatible drivers
+/// forward verbatim, and note when the driver has no use for it.
+fn check_logit_bias(cfg: &ModelConfig) -> anyhow::Result<()> {
+    let Some(bias) = cfg.driver_options.get("logit_bias") else {
+        return Ok(());
+    };
+    let Some(map) = bias.as_object() else {
+        bail!("driver_options.logit_bias must be a JSON object mapping tokens to numbers");
+    };
+    if let Some((token, value)) = map.iter().find(|(_, v)| !v.is_number()) {
+        bail!("driver_options.logit_bias[{token:?}] must be a number, got {value}");
+    }
+    // The native drivers build their own request bodies and never read
+    // driver_options.
+    if matches!(
+        cfg.provider.as_str(),
+        "anthropic" | "google" | "aws" | "cohere"
+    ) {
+        tracing::debug!(
+            provider = %cfg.provider,
+            "driver_options.logit_bias is not supported by this driver; ignoring it"
+        );
+    }
+    Ok(())
+}
+
+/// Validate `driver_options.openrouter`, the routing preferences the
+/// OpenRouter driver sends as the request's `provider` field.
+fn check_openrouter_options(cfg: &ModelConfig) -> anyhow::Result<()> {
+    let Some(prefs) = cfg.driver_options.get("openrouter") else {
+        return Ok(());
+    };
+    let Some(map) = prefs.as_object() else {
+        bail!("driver_options.openrouter must be a JSON object");
+    };
+    for (key, value) in map {
+        let valid = match key.as_str() {
+            "order" => value
+                .as_array()
+                .is_some_and(|a| a.iter().all(serde_json::Value::is_string)),
+            "allow_fallbacks" | "require_parameters" => value.is_boolean(),
+            _ => bail!(
+                "driver_options.openrouter: unknown key {key:?} (expected one of: {})",
+                openai_compat::OPENROUTER_PROVIDER_KEYS.join(", ")
+            ),
+        };
+        if !valid {
+            let expected = if key == "order" {
+                "a list of provider names"
+            } else {
+                "true or false"
+            };
+            bail!("driver_options.openrouter.{key} must be {expected}, got {value}");
+        }
+    }
+    if cfg.provider != "openrouter" {
+        tracing::debug!(
+            provider = %cfg.provider,
+            "driver_options.openrouter only applies to the openrouter driver; ignoring it"
+        );
+    }
+    Ok(())
+}
+
+/// Parse `driver_options.extra_headers`, a map of header names to string
+/// values that OpenAI-compatible drivers send with every request.
+fn driver_extra_headers(cfg: &ModelConfig) -> anyhow::Result<Vec<(String, String)>> {
+    let Some(headers) = cfg.driver_options.get("extra_headers") else {
+        return Ok(Vec::new());
+    };
+    let Some(map) = headers.as_object() else {
+        bail!("driver_options.extra_headers must be a JSON object of header names to strings");
+    };
+    let mut out = Vec::with_capacity(map.len());
+    for (name, value) in map {
+        if reqwest::header::HeaderName::from_bytes(name.as_bytes()).is_err() {
+            bail!("driver_options.extra_headers: {name:?} is not a valid HTTP header name");
+        }
+        let Some(value) = value.as_str() else {
+            bail!("driver_options.extra_headers[{name:?}] must be a string, got {value}");
+        };
+        if reqwest::header::HeaderValue::from_str(value).is_err() {
+            bail!("driver_options.extra_headers[{name:?}] is not a valid HTTP header value");
+        }
+        out.push((name.clone(), value.to_string()));
+    }
+    if matches!(
+        cfg.provider.as_str(),
+        "anthropic" | "google" | "aws" | "cohere"
+    ) {
+        tracing::debug!(
+            provider = %cfg.provider,
+            "driver_options.extra_headers is not supported by this driver; ignoring it"
+        );
+    }
+    Ok(out)
+}
+
 /// Perform early-exit API key validation before attempting any network call.
 ///
 /// When the user has configured neither an explicit key nor a key-env override,
@@ -161,6 +348,10 @@ impl ModelProvider for ConfigBoundedProvider {
         self.inner.list_models().await
     }
 
+    async fn health_check(&self) -> anyhow::Result<bool> {
+        self.inner.health_check().await
+    }
+
     fn catalog_max_output_tokens(&self) -> Option<u32> {
         self.max_output_tokens
             .or_else(|| self.inner.catalog_max_output_tokens())
@@ -188,6 +379,27 @@ impl ModelProvider for ConfigBoundedProvider {
     }
 }
 
+// ── load_config ───────────────────────────────────────────────────────────────
+
+/// Load configuration with [`sven_config::load`], then check the named
+/// `providers` entries against the driver registry.
+///
+/// A typo in an entry's `provider` field would otherwise only surface when a
+/// model from it is first used.  Each problem is logged as a warning, or —
+/// when `strict` is set — all of them are returned as an error.
+pub fn load_config(extra: Option<&Path>, strict: bool) -> anyhow::Result<sven_config::Config> {
+    let config = sven_config::load(extra)?;
+    let issues = check_providers(&config);
+    if strict && !issues.is_empty() {
+        let lines: Vec<String> = issues.iter().map(|i| i.to_string()).collect();
+        bail!("invalid provider configuration:\n  {}", lines.join("\n  "));
+    }
+    for issue in &issues {
+        tracing::warn!("{issue}");
+    }
+    Ok(config)
+}
+
 // ── from_config ───────────────────────────────────────────────────────────────
 
 /// Construct a boxed [`ModelProvider`] from configuration.
@@ -207,6 +419,16 @@ impl ModelProvider for ConfigBoundedProvider {
 /// window (original behaviour, fully backward-compatible).
 pub fn from_config(cfg: &ModelConfig) -> anyhow::Result<Box<dyn ModelProvider>> {
     check_api_key_requirement(cfg)?;
+    check_stop_sequences(cfg)?;
+    check_logit_bias(cfg)?;
+    check_openrouter_options(cfg)?;
+    let stop = cfg.stop.clone().unwrap_or_default();
+    let extra_body = match cfg.driver_options.get("extra_body") {
+        None => serde_json::Value::Null,
+        Some(v) if v.is_object() => v.clone(),
+        Some(_) => bail!("driver_options.extra_body must be a JSON object"),
+    };
+    let extra_headers = driver_extra_headers(cfg)?;
 
     // key() returns a fresh Option<String> on each call so that each match arm
     // can take ownership without cross-arm borrow issues.
@@ -227,6 +449,7 @@ pub fn from_config(cfg: &ModelConfig) -> anyhow::Result<Box<dyn ModelProvider>>
     // - When only max_tokens is set, it doubles as both output cap and context.
     // Either way, exposing max_tokens here is correct.
     let config_ctx = cfg.max_tokens;
+    let idle_timeout = stream_idle_timeout(cfg);
 
     // Helper that reads `base_url` from config or falls back to a static default.
     let base_url =
@@ -234,47 +457,72 @@ pub fn from_config(cfg: &ModelConfig) -> anyhow::Result<Box<dyn ModelProvider>>
 
     let inner: Box<dyn ModelProvider> = match cfg.provider.as_str() {
         // ── Native drivers ────────────────────────────────────────────────────
-        "openai" => Box::new(OpenAiProvider::new(
-            cfg.name.clone(),
-            key(),
-            cfg.base_url.clone(),
-            resolved_max_tokens,
-            cfg.temperature,
-            cfg.driver_options.clone(),
-        )),
-        "anthropic" => Box::new(AnthropicProvider::with_cache(
-            cfg.name.clone(),
-            key(),
-            cfg.base_url.clone(),
-            resolved_max_tokens,
-            cfg.temperature,
-            cfg.cache_system_prompt,
-            cfg.extended_cache_time,
-            cfg.cache_tools,
-            cfg.cache_conversation,
-            cfg.cache_images,
-            cfg.cache_tool_results,
-        )),
-        "google" => Box::new(google::GoogleProvider::new(
-            cfg.name.clone(),
-            key(),
-            cfg.base_url.clone(),
-            resolved_max_tokens,
-            cfg.temperature,
-        )),
-        "aws" => Box::new(aws::BedrockProvider::new(
-            cfg.name.clone(),
-            cfg.aws_region.clone(),
-            resolved_max_tokens,
-            cfg.temperature,
-        )),
-        "cohere" => Box::new(cohere::CohereProvider::new(
-            cfg.name.clone(),
-            key(),
-            cfg.base_url.clone(),
-            resolved_max_tokens,
-            cfg.temperature,
-        )),
+        "openai" => Box::new(
+            OpenAiProvider::new(
+                cfg.name.clone(),
+                key(),
+                cfg.base_url.clone(),
+                resolved_max_tokens,
+                cfg.temperature,
+                cfg.driver_options.clone(),
+            )
+            .with_idle_timeout(idle_timeout)
+            .with_extra_headers(extra_headers.clone())
+            .with_response_format(cfg.response_format.clone())
+            .with_stop(stop.clone())
+            .with_reasoning_effort(cfg.reasoning_effort.clone()),
+        ),
+        "anthropic" => Box::new(
+            AnthropicProvider::with_cache(
+                cfg.name.clone(),
+                key(),
+                cfg.base_url.clone(),
+                resolved_max_tokens,
+                cfg.temperature,
+                cfg.cache_system_prompt,
+                cfg.extended_cache_time,
+                cfg.cache_tools,
+                cfg.cache_conversation,
+                cfg.cache_images,
+                cfg.cache_tool_results,
+            )
+            .with_thinking_budget(cfg.thinking_budget_tokens)
+            .with_response_format(cfg.response_format.clone())
+            .with_stop(stop.clone())
+            .with_extra_body(extra_body.clone()),
+        ),
+        "google" => Box::new(
+            google::GoogleProvider::new(
+                cfg.name.clone(),
+                key(),
+                cfg.base_url.clone(),
+                resolved_max_tokens,
+                cfg.temperature,
+            )
+            .with_stop(stop.clone())
+            .with_extra_body(extra_body.clone()),
+        ),
+        "aws" => Box::new(
+            aws::BedrockProvider::new(
+                cfg.name.clone(),
+                cfg.aws_region.clone(),
+                resolved_max_tokens,
+                cfg.temperature,
+            )
+            .with_stop(stop.clone())
+            .with_extra_body(extra_body.clone()),
+        ),
+        "cohere" => Box::new(
+            cohere::CohereProvider::new(
+                cfg.name.clone(),
+                key(),
+                cfg.base_url.clone(),
+                resolved_max_tokens,
+                cfg.temperature,
+            )
+            .with_stop(stop.clone())
+            .with_extra_body(extra_body.clone()),
+        ),
 
         // ── Azure OpenAI (OpenAI-compat with special URL + api-key header) ────
         "azure" => {
@@ -293,17 +541,24 @@ pub fn from_config(cfg: &ModelConfig) -> anyhow::Result<Box<dyn ModelProvider>>
                     "https://{resource}.openai.azure.com/openai/deployments/{deployment}/chat/completions?api-version={api_ver}"
                 )
             };
-            Box::new(OpenAICompatProvider::with_full_chat_url(
-                "azure",
-                cfg.name.clone(),
-                key(),
-                chat_url,
-                resolved_max_tokens,
-                cfg.temperature,
-                vec![],
-                openai_compat::AuthStyle::ApiKeyHeader,
-                cfg.driver_options.clone(),
-            ))
+            Box::new(
+                OpenAICompatProvider::with_full_chat_url(
+                    "azure",
+                    cfg.name.clone(),
+                    key(),
+                    chat_url,
+                    resolved_max_tokens,
+                    cfg.temperature,
+                    vec![],
+                    openai_compat::AuthStyle::ApiKeyHeader,
+                    cfg.driver_options.clone(),
+                )
+                .with_idle_timeout(idle_timeout)
+                .with_extra_headers(extra_headers.clone())
+                .with_response_format(cfg.response_format.clone())
+                .with_stop(stop.clone())
+                .with_reasoning_effort(cfg.reasoning_effort.clone()),
+            )
         }
 
         // ── OpenAI-compatible gateways (special-cased for custom behaviour) ──
@@ -313,51 +568,70 @@ pub fn from_config(cfg: &ModelConfig) -> anyhow::Result<Box<dyn ModelProvider>>
             catalog::load_disk_cache("openrouter");
             // Spawn a background task to refresh the cache when stale.
             maybe_spawn_openrouter_cache_refresh(key(), or_base.clone());
-            Box::new(OpenAICompatProvider::new(
-                "openrouter",
+            Box::new(
+                OpenAICompatProvider::new(
+                    "openrouter",
+                    cfg.name.clone(),
+                    key(),
+                    &or_base,
+                    resolved_max_tokens,
+                    cfg.temperature,
+                    vec![
+                        (
+                            "HTTP-Referer".into(),
+                            "https://github.com/svenai/sven".into(),
+                        ),
+                        ("X-Title".into(), "sven".into()),
+                    ],
+                    AuthStyle::Bearer,
+                    transform_openrouter_options(cfg),
+                )
+                .with_idle_timeout(idle_timeout)
+                .with_extra_headers(extra_headers.clone())
+                .with_response_format(cfg.response_format.clone())
+                .with_stop(stop.clone())
+                .with_reasoning_effort(cfg.reasoning_effort.clone()),
+            )
+        }
+        "portkey" => Box::new(
+            OpenAICompatProvider::new(
+                "portkey",
                 cfg.name.clone(),
                 key(),
-                &or_base,
+                &base_url("https://api.portkey.ai/v1"),
                 resolved_max_tokens,
                 cfg.temperature,
-                vec![
-                    (
-                        "HTTP-Referer".into(),
-                        "https://github.com/svenai/sven".into(),
-                    ),
-                    ("X-Title".into(), "sven".into()),
-                ],
+                portkey_extra_headers(cfg),
                 AuthStyle::Bearer,
-                transform_openrouter_options(cfg),
-            ))
-        }
-        "portkey" => Box::new(OpenAICompatProvider::new(
-            "portkey",
-            cfg.name.clone(),
-            key(),
-            &base_url("https://api.portkey.ai/v1"),
-            resolved_max_tokens,
-            cfg.temperature,
-            portkey_extra_headers(cfg),
-            AuthStyle::Bearer,
-            cfg.driver_options.clone(),
-        )),
+                cfg.driver_options.clone(),
+            )
+            .with_idle_timeout(idle_timeout)
+            .with_extra_headers(extra_headers.clone())
+            .with_response_format(cfg.response_format.clone())
+            .with_stop(stop.clone()),
+        ),
         "litellm" => {
             let b = cfg
                 .base_url
                 .as_deref()
                 .ok_or_else(|| anyhow::anyhow!("litellm provider requires base_url in config"))?;
-            Box::new(OpenAICompatProvider::new(
-                "litellm",
-                cfg.name.clone(),
-                key(),
-                b,
-                resolved_max_tokens,
-                cfg.temperature,
-                vec![],
-                AuthStyle::Bearer,
-                cfg.driver_options.clone(),
-            ))
+            Box::new(
+                OpenAICompatProvider::new(
+                    "litellm",
+                    cfg.name.clone(),
+                    key(),
+                    b,
+                    resolved_max_tokens,
+                    cfg.temperature,
+                    vec![],
+                    AuthStyle::Bearer,
+                    cfg.driver_options.clone(),
+                )
+                .with_idle_timeout(idle_timeout)
+                .with_extra_headers(extra_headers.clone())
+                .with_response_format(cfg.response_format.clone())
+                .with_stop(stop.clone()),
+            )
         }
         "cloudflare" => {
             let b = cfg.base_url.as_deref().ok_or_else(|| {
@@ -365,17 +639,23 @@ pub fn from_config(cfg: &ModelConfig) -> anyhow::Result<Box<dyn ModelProvider>>
                     "cloudflare provider requires base_url in config (account-specific URL)"
                 )
             })?;
-            Box::new(OpenAICompatProvider::new(
-                "cloudflare",
-                cfg.name.clone(),
-                key(),
-                b,
-                resolved_max_tokens,
-                cfg.temperature,
-                vec![],
-                AuthStyle::Bearer,
-                cfg.driver_options.clone(),
-            ))
+            Box::new(
+                OpenAICompatProvider::new(
+                    "cloudflare",
+                    cfg.name.clone(),
+                    key(),
+                    b,
+                    resolved_max_tokens,
+                    cfg.temperature,
+                    vec![],
+                    AuthStyle::Bearer,
+                    cfg.driver_options.clone(),
+                )
+                .with_idle_timeout(idle_timeout)
+                .with_extra_headers(extra_headers.clone())
+                .with_response_format(cfg.response_format.clone())
+                .with_stop(stop.clone()),
+            )
         }
         // vLLM accepts an optional bearer token; auth style depends on whether
         // a key is actually configured.
@@ -386,17 +666,23 @@ pub fn from_config(cfg: &ModelConfig) -> anyhow::Result<Box<dyn ModelProvider>>
             } else {
                 AuthStyle::None
             };
-            Box::new(OpenAICompatProvider::new(
-                "vllm",
-                cfg.name.clone(),
-                k,
-                &base_url("http://localhost:8000/v1"),
-                resolved_max_tokens,
-                cfg.temperature,
-                vec![],
-                auth,
-                cfg.driver_options.clone(),
-            ))
+            Box::new(
+                OpenAICompatProvider::new(
+                    "vllm",
+                    cfg.name.clone(),
+                    k,
+                    &base_url("http://localhost:8000/v1"),
+                    resolved_max_tokens,
+                    cfg.temperature,
+                    vec![],
+                    auth,
+                    cfg.driver_options.clone(),
+                )
+                .with_idle_timeout(idle_timeout)
+                .with_extra_headers(extra_headers.clone())
+                .with_response_format(cfg.response_format.clone())
+                .with_stop(stop.clone()),
+            )
         }
 
         // ── Testing / Mock ────────────────────────────────────────────────────
@@ -436,17 +722,23 @@ pub fn from_config(cfg: &ModelConfig) -> anyhow::Result<Box<dyn ModelProvider>>
             } else {
                 AuthStyle::None
             };
-            Box::new(OpenAICompatProvider::new(
-                meta.id,
-                cfg.name.clone(),
-                key(),
-                &base_url(default_url),
-                resolved_max_tokens,
-                cfg.temperature,
-                vec![],
-                auth,
-                cfg.driver_options.clone(),
-            ))
+            Box::new(
+                OpenAICompatProvider::new(
+                    meta.id,
+                    cfg.name.clone(),
+                    key(),
+                    &base_url(default_url),
+                    resolved_max_tokens,
+                    cfg.temperature,
+                    vec![],
+                    auth,
+                    cfg.driver_options.clone(),
+                )
+                .with_idle_timeout(idle_timeout)
+                .with_extra_headers(extra_headers.clone())
+                .with_response_format(cfg.response_format.clone())
+                .with_stop(stop.clone()),
+            )
         }
     };
 
@@ -462,7 +754,19 @@ pub fn from_config(cfg: &ModelConfig) -> anyhow::Result<Box<dyn ModelProvider>>
     }))
 }
 
-fn resolve_api_key(cfg: &ModelConfig) -> Option<String> {
+/// The stream idle timeout `cfg` asks for; `stream_idle_timeout_secs: 0`
+/// disables it.
+fn stream_idle_timeout(cfg: &ModelConfig) -> Option<Duration> {
+    match cfg.stream_idle_timeout_secs {
+        None => Some(openai_compat::DEFAULT_STREAM_IDLE_TIMEOUT),
+        Some(0) => None,
+        Some(secs) => Some(Duration::from_secs(secs)),
+    }
+}
+
+/// The API key `cfg` would authenticate with: the explicit `api_key`, then
+/// the `api_key_env` variable, then the driver's default key variable.
+pub fn resolve_api_key(cfg: &ModelConfig) -> Option<String> {
     if let Some(k) = &cfg.api_key {
         return Some(k.clone());
     }
@@ -736,6 +1040,21 @@ pub fn resolve_model_from_config(config: &sven_config::Config, override_str: &st
     ModelResolver::new(config, override_str).resolve()
 }
 
+/// The model `agent.routing` assigns to `mode`, resolved like `--model`.
+/// `None` when the mode has no route.  Callers apply it only while the user
+/// has not picked a model explicitly.
+pub fn routed_model(
+    config: &sven_config::Config,
+    mode: sven_config::AgentMode,
+) -> Option<ModelConfig> {
+    config
+        .agent
+        .routing
+        .get(&mode)
+        .or_else(|| config.agent.custom_mode(mode)?.model.as_ref())
+        .map(|spec| resolve_model_from_config(config, spec))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -825,6 +1144,130 @@ mod tests {
         }
     }
 
+    #[test]
+    fn stop_sequences_over_the_provider_limit_are_rejected() {
+        let stop = |n: usize| Some((0..n).map(|i| format!("<<END{i}>>")).collect());
+        let cfg = ModelConfig {
+            api_key: Some("k".into()),
+            stop: stop(5),
+            ..minimal_config("openai", "gpt-4o")
+        };
+        let msg = from_config(&cfg).err().unwrap().to_string();
+        assert!(
+            msg.contains("model.stop") && msg.contains("at most 4"),
+            "unexpected error: {msg}"
+        );
+
+        let cfg = ModelConfig {
+            stop: stop(4),
+            ..cfg
+        };
+        assert!(from_config(&cfg).is_ok());
+
+        // Anthropic documents no count limit.
+        let cfg = ModelConfig {
+            api_key: Some("k".into()),
+            stop: stop(8),
+            ..minimal_config("anthropic", "claude-sonnet-4-5")
+        };
+        assert!(from_config(&cfg).is_ok());
+
+        let cfg = ModelConfig {
+            stop: Some(vec![String::new()]),
+            ..cfg
+        };
+        assert!(from_config(&cfg).is_err());
+    }
+
+    #[test]
+    fn logit_bias_must_map_tokens_to_numbers() {
+        let with_bias = |provider: &str, bias: serde_json::Value| ModelConfig {
+            api_key: Some("k".into()),
+            driver_options: serde_json::json!({ "logit_bias": bias }),
+            ..minimal_config(provider, "m")
+        };
+        assert!(from_config(&with_bias("openai", serde_json::json!({ "50256": -100 }))).is_ok());
+        // Ignored, not rejected, by drivers without logit bias.
+        assert!(from_config(&with_bias("anthropic", serde_json::json!({ "1": 5 }))).is_ok());
+
+        let msg = from_config(&with_bias("openai", serde_json::json!({ "50256": "ban" })))
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(msg.contains("logit_bias[\"50256\"]"), "{msg}");
+        assert!(from_config(&with_bias("openai", serde_json::json!([50256]))).is_err());
+    }
+
+    #[test]
+    fn extra_headers_must_be_valid_string_headers() {
+        let with_headers = |headers: serde_json::Value| ModelConfig {
+            api_key: Some("k".into()),
+            driver_options: serde_json::json!({ "extra_headers": headers }),
+            ..minimal_config("openai", "m")
+        };
+        assert!(from_config(&with_headers(serde_json::json!({ "X-Tenant": "a" }))).is_ok());
+
+        let msg = from_config(&with_headers(serde_json::json!({ "bad header": "a" })))
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(msg.contains("not a valid HTTP header name"), "{msg}");
+        assert!(from_config(&with_headers(serde_json::json!({ "X-Tenant": 1 }))).is_err());
+        assert!(from_config(&with_headers(serde_json::json!({ "X-Tenant": "a\nb" }))).is_err());
+        assert!(from_config(&with_headers(serde_json::json!(["X-Tenant"]))).is_err());
+    }
+
+    #[test]
+    fn openrouter_options_are_validated() {
+        let with_prefs = |prefs: serde_json::Value| ModelConfig {
+            api_key: Some("k".into()),
+            driver_options: serde_json::json!({ "openrouter": prefs }),
+            ..minimal_config("openrouter", "anthropic/claude-sonnet-4")
+        };
+        let ok = serde_json::json!({
+            "order": ["anthropic", "amazon-bedrock"],
+            "allow_fallbacks": false,
+            "require_parameters": true,
+        });
+        assert!(from_config(&with_prefs(ok)).is_ok());
+
+        let msg = from_config(&with_prefs(serde_json::json!({ "order": "anthropic" })))
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(msg.contains("openrouter.order"), "{msg}");
+        let msg = from_config(&with_prefs(serde_json::json!({ "allow_fallback": false })))
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(msg.contains("unknown key \"allow_fallback\""), "{msg}");
+        assert!(from_config(&with_prefs(serde_json::json!({ "allow_fallbacks": "no" }))).is_err());
+    }
+
+    #[test]
+    fn extra_body_keys_fill_gaps_but_never_override() {
+        let mut body = serde_json::json!({ "model": "m", "max_tokens": 10 });
+        merge_extra_body(
+            &mut body,
+            &serde_json::json!({ "max_tokens": 99999, "service_tier": "flex" }),
+        );
+        assert_eq!(
+            body,
+            serde_json::json!({ "model": "m", "max_tokens": 10, "service_tier": "flex" })
+        );
+    }
+
+    #[test]
+    fn extra_body_must_be_an_object() {
+        let cfg = ModelConfig {
+            api_key: Some("k".into()),
+            driver_options: serde_json::json!({ "extra_body": ["x"] }),
+            ..minimal_config("anthropic", "claude-sonnet-4-5")
+        };
+        let msg = from_config(&cfg).err().unwrap().to_string();
+        assert!(msg.contains("extra_body"), "{msg}");
+    }
+
     #[test]
     fn from_config_unknown_provider_returns_error() {
         let cfg = minimal_config("totally_unknown_provider_xyz", "some-model");
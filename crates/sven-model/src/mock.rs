@@ -40,6 +40,7 @@ impl crate::ModelProvider for MockProvider {
                 cache_read_tokens: 0,
                 cache_write_tokens: 0,
                 cost_usd: None,
+                estimated: false,
             }),
             Ok(ResponseEvent::Done),
         ];
@@ -94,6 +95,7 @@ impl ScriptedMockProvider {
                 cache_read_tokens: 0,
                 cache_write_tokens: 0,
                 cost_usd: None,
+                estimated: false,
             },
             ResponseEvent::Done,
         ]])
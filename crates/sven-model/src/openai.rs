@@ -43,6 +43,36 @@ impl OpenAiProvider {
             ),
         }
     }
+
+    /// See [`OpenAICompatProvider::with_idle_timeout`].
+    pub fn with_idle_timeout(mut self, idle: Option<std::time::Duration>) -> Self {
+        self.inner = self.inner.with_idle_timeout(idle);
+        self
+    }
+
+    /// See [`OpenAICompatProvider::with_extra_headers`].
+    pub fn with_extra_headers(mut self, headers: Vec<(String, String)>) -> Self {
+        self.inner = self.inner.with_extra_headers(headers);
+        self
+    }
+
+    /// See [`OpenAICompatProvider::with_response_format`].
+    pub fn with_response_format(mut self, schema: Option<serde_json::Value>) -> Self {
+        self.inner = self.inner.with_response_format(schema);
+        self
+    }
+
+    /// See [`OpenAICompatProvider::with_stop`].
+    pub fn with_stop(mut self, stop: Vec<String>) -> Self {
+        self.inner = self.inner.with_stop(stop);
+        self
+    }
+
+    /// See [`OpenAICompatProvider::with_reasoning_effort`].
+    pub fn with_reasoning_effort(mut self, effort: Option<String>) -> Self {
+        self.inner = self.inner.with_reasoning_effort(effort);
+        self
+    }
 }
 
 #[async_trait]
@@ -58,6 +88,10 @@ impl crate::ModelProvider for OpenAiProvider {
         self.inner.list_models().await
     }
 
+    async fn health_check(&self) -> anyhow::Result<bool> {
+        self.inner.health_check().await
+    }
+
     async fn complete(&self, req: CompletionRequest) -> anyhow::Result<ResponseStream> {
         self.inner.complete(req).await
     }
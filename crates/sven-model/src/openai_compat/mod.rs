@@ -22,12 +22,15 @@ mod stream;
 
 pub(crate) use request::build_openai_messages;
 pub(crate) use stream::drain_complete_sse_lines_bytes;
+pub use stream::StreamIdleTimeout;
 
 #[cfg(test)]
 pub(crate) use stream::drain_complete_sse_lines;
 #[cfg(test)]
 use stream::parse_sse_chunk_test as parse_sse_chunk;
 
+use std::time::Duration;
+
 use anyhow::{bail, Context};
 use async_trait::async_trait;
 use futures::StreamExt;
@@ -86,8 +89,22 @@ pub struct OpenAICompatProvider {
     /// `None` when constructed via `with_full_chat_url` (no derivable root).
     /// Used by `probe_context_window()` to query `GET {server_root}/props`.
     server_root: Option<String>,
+    /// Abort a streaming response that receives no bytes for this long.
+    /// `None` waits indefinitely.
+    idle_timeout: Option<Duration>,
+    /// Reasoning effort (`"low"`, `"medium"`, `"high"`, …) for drivers that
+    /// accept one; ignored by the others.
+    reasoning_effort: Option<String>,
+    /// JSON Schema that replies must follow, sent as a `json_schema`
+    /// `response_format`.
+    response_format: Option<Value>,
+    /// Stop sequences, sent as `stop`.
+    stop: Vec<String>,
 }
 
+/// Default for [`OpenAICompatProvider::with_idle_timeout`].
+pub const DEFAULT_STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
 impl OpenAICompatProvider {
     /// Construct a provider from its full endpoint URLs and auth configuration.
     ///
@@ -128,6 +145,10 @@ impl OpenAICompatProvider {
             auth_style,
             extra_body,
             server_root: Some(derive_server_root(base)),
+            idle_timeout: Some(DEFAULT_STREAM_IDLE_TIMEOUT),
+            reasoning_effort: None,
+            response_format: None,
+            stop: Vec::new(),
         }
     }
 
@@ -165,145 +186,64 @@ impl OpenAICompatProvider {
             auth_style,
             extra_body,
             server_root: None,
+            idle_timeout: Some(DEFAULT_STREAM_IDLE_TIMEOUT),
+            reasoning_effort: None,
+            response_format: None,
+            stop: Vec::new(),
         }
     }
-}
 
-/// Derive the server root from a `/v1`-prefixed API base URL.
-///
-/// Strips common API path suffixes so that `probe_context_window()` can reach
-/// the server's properties endpoint (e.g. llama.cpp's `GET /props`).
-///
-/// Examples:
-/// - `http://localhost:8080/v1`   → `http://localhost:8080`
-/// - `https://api.openai.com/v1` → `https://api.openai.com`
-/// - `http://host:8080/api/v1`   → `http://host:8080`
-/// - `http://host:8080`          → `http://host:8080` (unchanged)
-fn derive_server_root(base_url: &str) -> String {
-    let b = base_url.trim_end_matches('/');
-    if let Some(root) = b.strip_suffix("/api/v1") {
-        return root.to_string();
+    /// Send `headers` on every request in addition to the driver's own
+    /// (`driver_options.extra_headers`).
+    pub fn with_extra_headers(mut self, headers: Vec<(String, String)>) -> Self {
+        self.extra_headers.extend(headers);
+        self
     }
-    if let Some(root) = b.strip_suffix("/v1") {
-        return root.to_string();
-    }
-    b.to_string()
-}
 
-#[async_trait]
-impl crate::ModelProvider for OpenAICompatProvider {
-    fn name(&self) -> &str {
-        self.driver_name
+    /// Abort streaming responses that receive no bytes (not even an SSE
+    /// keep-alive comment) for `idle`; `None` disables the timeout.
+    pub fn with_idle_timeout(mut self, idle: Option<Duration>) -> Self {
+        self.idle_timeout = idle;
+        self
     }
 
-    fn model_name(&self) -> &str {
-        &self.model
+    /// Request the given reasoning effort.  OpenAI and Azure receive it as
+    /// `reasoning_effort`, OpenRouter as `reasoning.effort`; other drivers
+    /// ignore it.
+    pub fn with_reasoning_effort(mut self, effort: Option<String>) -> Self {
+        self.reasoning_effort = effort;
+        self
     }
 
-    /// Query the server's `/props` endpoint for the actual loaded context window.
-    ///
-    /// llama.cpp and compatible servers expose `GET /props` which includes
-    /// `n_ctx` — the actual KV-cache size the model was loaded with.  This
-    /// value may be smaller than the catalog entry (e.g. the server was
-    /// started with `--ctx-size 54272`).
-    ///
-    /// Returns `Some(n_ctx)` on success, `None` when the endpoint is absent
-    /// (hosted providers like OpenAI, Anthropic) or unreachable.
-    async fn probe_context_window(&self) -> Option<u32> {
-        let root = self.server_root.as_deref()?;
-        let props_url = format!("{root}/props");
-        let mut req = self
-            .client
-            .get(&props_url)
-            // Short timeout: the probe is a startup hint, not a critical path.
-            // A slow or unreachable server must not block agent startup.
-            .timeout(std::time::Duration::from_secs(5));
-        if let Some(key) = &self.api_key {
-            req = match self.auth_style {
-                AuthStyle::Bearer => req.bearer_auth(key),
-                AuthStyle::ApiKeyHeader => req.header("api-key", key),
-                AuthStyle::None => req,
-            };
-        }
-        let resp = req.send().await.ok()?;
-        if !resp.status().is_success() {
-            return None;
-        }
-        let body: serde_json::Value = resp.json().await.ok()?;
-        // llama.cpp /props returns {"n_ctx": <u32>, ...}
-        body["n_ctx"].as_u64().map(|v| v as u32)
+    /// Ask for replies that follow the JSON Schema `schema` (structured
+    /// outputs).  `None` leaves the reply format free.
+    pub fn with_response_format(mut self, schema: Option<Value>) -> Self {
+        self.response_format = schema;
+        self
     }
 
-    /// List models via `GET /models`, enriched with metadata.
-    ///
-    /// For OpenRouter, uses the rich metadata fields the API returns
-    /// (`context_length`, `top_provider.max_completion_tokens`,
-    /// `architecture.input_modalities`).  For other OpenAI-compatible
-    /// providers, enriches bare model IDs with static catalog metadata.
-    ///
-    /// Falls back to the static catalog when no API key is present, the
-    /// endpoint is unavailable, or the response is empty.
-    async fn list_models(&self) -> anyhow::Result<Vec<ModelCatalogEntry>> {
-        let catalog_entries: Vec<ModelCatalogEntry> = static_catalog()
-            .into_iter()
-            .filter(|e| e.provider == self.driver_name)
-            .collect();
-
-        let url = match &self.models_url {
-            Some(u) => u.clone(),
-            None => return Ok(catalog_entries),
-        };
-
-        let key = match &self.api_key {
-            Some(k) => k.clone(),
-            None => {
-                // Local provider with no key — just return catalog.
-                return Ok(catalog_entries);
-            }
-        };
+    /// Stop generating at any of `stop`.
+    pub fn with_stop(mut self, stop: Vec<String>) -> Self {
+        self.stop = stop;
+        self
+    }
 
-        let mut req = self.client.get(&url);
-        req = match self.auth_style {
-            AuthStyle::Bearer => req.bearer_auth(&key),
-            AuthStyle::ApiKeyHeader => req.header("api-key", &key),
-            AuthStyle::None => req,
-        };
+    /// Add the configured extra headers to an outgoing request.
+    fn apply_extra_headers(&self, mut req: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
         for (name, val) in &self.extra_headers {
             req = req.header(name.as_str(), val.as_str());
         }
+        req
+    }
 
-        let resp = match req.send().await {
-            Ok(r) => r,
-            Err(_) => {
-                // Network error (e.g. local server not running) — return catalog.
-                return Ok(catalog_entries);
-            }
-        };
-
-        if !resp.status().is_success() {
-            return Ok(catalog_entries);
-        }
-
-        let body: Value = match resp.json().await {
-            Ok(v) => v,
-            Err(_) => return Ok(catalog_entries),
-        };
-
-        let live = parse_models_response(&body, self.driver_name, &catalog_entries);
-        if live.is_empty() {
-            return Ok(catalog_entries);
-        }
-
-        // Persist live data to the catalog cache so subsequent lookups (context
-        // window, modalities) benefit from the fresh metadata.
-        if self.driver_name == "openrouter" {
-            catalog::cache_update(self.driver_name, live.clone());
-        }
-
-        Ok(live)
+    /// Whether this is an Anthropic model reached through OpenRouter.
+    fn routes_to_anthropic(&self) -> bool {
+        self.driver_name == "openrouter" && self.model.starts_with("anthropic/")
     }
 
-    async fn complete(&self, req: CompletionRequest) -> anyhow::Result<ResponseStream> {
+    /// Build the JSON body for a `/chat/completions` request.  A `stream`
+    /// key in `driver_options` overrides `req.stream`.
+    fn build_request_body(&self, req: &CompletionRequest) -> Value {
         // When routing to an Anthropic or Google Gemini model via OpenRouter,
         // OpenRouter passes through content-block `cache_control` markers to
         // the underlying provider.  Using content blocks lets us separate the
@@ -318,8 +258,7 @@ impl crate::ModelProvider for OpenAICompatProvider {
         // context (system + tools + conversation history).  Marking the system
         // prompt and tools as well is harmless — Gemini ignores all but the
         // last — and keeps the two code paths identical.
-        let use_anthropic_cache =
-            self.driver_name == "openrouter" && self.model.starts_with("anthropic/");
+        let use_anthropic_cache = self.routes_to_anthropic();
         let use_gemini_cache =
             self.driver_name == "openrouter" && self.model.starts_with("google/");
         let use_block_cache = use_anthropic_cache || use_gemini_cache;
@@ -404,15 +343,18 @@ impl crate::ModelProvider for OpenAICompatProvider {
         };
         let max_tokens = req.max_output_tokens_override.unwrap_or(self.max_tokens);
 
+        let native_effort = matches!(self.driver_name, "openai" | "azure");
+
         // GPT-5 models only support temperature=1 (the default)
-        // Reasoning models (o1, o3) don't support temperature parameter at all
+        // Reasoning models (o1, o3) don't support temperature parameter at all,
+        // and neither does any request that sets a reasoning effort.
         let use_temperature = if self.driver_name == "openai" {
             !(self.model.starts_with("o1-")
                 || self.model.starts_with("o3-")
                 || self.model.starts_with("gpt-5"))
         } else {
             true
-        };
+        } && !(native_effort && self.reasoning_effort.is_some());
 
         let mut body = json!({
             "model": self.model,
@@ -427,6 +369,22 @@ impl crate::ModelProvider for OpenAICompatProvider {
         if !tools.is_empty() {
             body["tools"] = json!(tools);
         }
+        if let Some(effort) = &self.reasoning_effort {
+            if native_effort {
+                body["reasoning_effort"] = json!(effort);
+            } else if self.driver_name == "openrouter" {
+                body["reasoning"] = json!({ "effort": effort });
+            }
+        }
+        if let Some(schema) = &self.response_format {
+            body["response_format"] = json!({
+                "type": "json_schema",
+                "json_schema": { "name": "response", "schema": schema },
+            });
+        }
+        if !self.stop.is_empty() {
+            body["stop"] = json!(self.stop);
+        }
 
         // OpenRouter supports a `prompt_cache_key` body field that pins all
         // requests sharing the same key to the same cached KV prefix.  Using
@@ -439,6 +397,11 @@ impl crate::ModelProvider for OpenAICompatProvider {
             if let Some(key) = &req.cache_key {
                 body["prompt_cache_key"] = json!(key);
             }
+            // `driver_options.openrouter` → OpenRouter's `provider` routing
+            // preferences (upstream order and fallback behaviour).
+            if let Some(prefs) = self.extra_body.get("openrouter") {
+                body["provider"] = openrouter_provider_preferences(prefs);
+            }
         }
 
         // For Anthropic and Google Gemini models via OpenRouter, rewrite the
@@ -515,17 +478,194 @@ impl crate::ModelProvider for OpenAICompatProvider {
         //                                      text alongside tool calls
         //   • `reasoning_format: "deepseek"` – extract <think> → reasoning_content
         //   • any other provider-specific key that sven doesn't model natively
+        //
+        // The nested `extra_body` object is different: its keys only fill in
+        // fields sven did not set (see `crate::merge_extra_body`),
+        // `extra_headers` is sent as HTTP headers rather than in the body, and
+        // `openrouter` was translated into `provider` above.
         if let Some(map) = self.extra_body.as_object() {
             for (k, v) in map {
-                body[k] = v.clone();
+                if !matches!(k.as_str(), "extra_body" | "extra_headers" | "openrouter") {
+                    body[k] = v.clone();
+                }
+            }
+        }
+        if let Some(extra) = self.extra_body.get("extra_body") {
+            crate::merge_extra_body(&mut body, extra);
+        }
+
+        // `stream_options` is only accepted alongside `stream: true`.
+        if body["stream"] == json!(false) {
+            if let Some(obj) = body.as_object_mut() {
+                obj.remove("stream_options");
+            }
+        }
+        body
+    }
+}
+
+/// Derive the server root from a `/v1`-prefixed API base URL.
+///
+/// Strips common API path suffixes so that `probe_context_window()` can reach
+/// the server's properties endpoint (e.g. llama.cpp's `GET /props`).
+///
+/// Examples:
+/// - `http://localhost:8080/v1`   → `http://localhost:8080`
+/// - `https://api.openai.com/v1` → `https://api.openai.com`
+/// - `http://host:8080/api/v1`   → `http://host:8080`
+/// - `http://host:8080`          → `http://host:8080` (unchanged)
+fn derive_server_root(base_url: &str) -> String {
+    let b = base_url.trim_end_matches('/');
+    if let Some(root) = b.strip_suffix("/api/v1") {
+        return root.to_string();
+    }
+    if let Some(root) = b.strip_suffix("/v1") {
+        return root.to_string();
+    }
+    b.to_string()
+}
+
+#[async_trait]
+impl crate::ModelProvider for OpenAICompatProvider {
+    fn name(&self) -> &str {
+        self.driver_name
+    }
+
+    fn model_name(&self) -> &str {
+        &self.model
+    }
+
+    /// Query the server's `/props` endpoint for the actual loaded context window.
+    ///
+    /// llama.cpp and compatible servers expose `GET /props` which includes
+    /// `n_ctx` — the actual KV-cache size the model was loaded with.  This
+    /// value may be smaller than the catalog entry (e.g. the server was
+    /// started with `--ctx-size 54272`).
+    ///
+    /// Returns `Some(n_ctx)` on success, `None` when the endpoint is absent
+    /// (hosted providers like OpenAI, Anthropic) or unreachable.
+    async fn probe_context_window(&self) -> Option<u32> {
+        let root = self.server_root.as_deref()?;
+        let props_url = format!("{root}/props");
+        let mut req = self
+            .client
+            .get(&props_url)
+            // Short timeout: the probe is a startup hint, not a critical path.
+            // A slow or unreachable server must not block agent startup.
+            .timeout(std::time::Duration::from_secs(5));
+        if let Some(key) = &self.api_key {
+            req = match self.auth_style {
+                AuthStyle::Bearer => req.bearer_auth(key),
+                AuthStyle::ApiKeyHeader => req.header("api-key", key),
+                AuthStyle::None => req,
+            };
+        }
+        let resp = req.send().await.ok()?;
+        if !resp.status().is_success() {
+            return None;
+        }
+        let body: serde_json::Value = resp.json().await.ok()?;
+        // llama.cpp /props returns {"n_ctx": <u32>, ...}
+        body["n_ctx"].as_u64().map(|v| v as u32)
+    }
+
+    /// `GET /models` with the configured credentials.  Drivers built from a
+    /// full chat URL (Azure) have no models URL and are not checked.
+    async fn health_check(&self) -> anyhow::Result<bool> {
+        let Some(url) = &self.models_url else {
+            return Ok(false);
+        };
+        let mut req = self.client.get(url);
+        if let Some(key) = &self.api_key {
+            req = match self.auth_style {
+                AuthStyle::Bearer => req.bearer_auth(key),
+                AuthStyle::ApiKeyHeader => req.header("api-key", key),
+                AuthStyle::None => req,
+            };
+        }
+        req = self.apply_extra_headers(req);
+        crate::check_endpoint(req).await?;
+        Ok(true)
+    }
+
+    /// List models via `GET /models`, enriched with metadata.
+    ///
+    /// For OpenRouter, uses the rich metadata fields the API returns
+    /// (`context_length`, `top_provider.max_completion_tokens`,
+    /// `architecture.input_modalities`).  For other OpenAI-compatible
+    /// providers, enriches bare model IDs with static catalog metadata.
+    ///
+    /// Falls back to the static catalog when no API key is present, the
+    /// endpoint is unavailable, or the response is empty.
+    async fn list_models(&self) -> anyhow::Result<Vec<ModelCatalogEntry>> {
+        let catalog_entries: Vec<ModelCatalogEntry> = static_catalog()
+            .into_iter()
+            .filter(|e| e.provider == self.driver_name)
+            .collect();
+
+        let url = match &self.models_url {
+            Some(u) => u.clone(),
+            None => return Ok(catalog_entries),
+        };
+
+        let key = match &self.api_key {
+            Some(k) => k.clone(),
+            None => {
+                // Local provider with no key — just return catalog.
+                return Ok(catalog_entries);
             }
+        };
+
+        let mut req = self.client.get(&url);
+        req = match self.auth_style {
+            AuthStyle::Bearer => req.bearer_auth(&key),
+            AuthStyle::ApiKeyHeader => req.header("api-key", &key),
+            AuthStyle::None => req,
+        };
+        req = self.apply_extra_headers(req);
+
+        let resp = match req.send().await {
+            Ok(r) => r,
+            Err(_) => {
+                // Network error (e.g. local server not running) — return catalog.
+                return Ok(catalog_entries);
+            }
+        };
+
+        if !resp.status().is_success() {
+            return Ok(catalog_entries);
+        }
+
+        let body: Value = match resp.json().await {
+            Ok(v) => v,
+            Err(_) => return Ok(catalog_entries),
+        };
+
+        let live = parse_models_response(&body, self.driver_name, &catalog_entries);
+        if live.is_empty() {
+            return Ok(catalog_entries);
+        }
+
+        // Persist live data to the catalog cache so subsequent lookups (context
+        // window, modalities) benefit from the fresh metadata.
+        if self.driver_name == "openrouter" {
+            catalog::cache_update(self.driver_name, live.clone());
         }
 
+        Ok(live)
+    }
+
+    async fn complete(&self, req: CompletionRequest) -> anyhow::Result<ResponseStream> {
+        let estimated_input = stream::estimate_request_tokens(&req);
+        let body = self.build_request_body(&req);
+        let streaming = body["stream"].as_bool().unwrap_or(true);
+
         debug!(
             driver = %self.driver_name,
             model = %self.model,
-            tool_count = tools.len(),
-            message_count = messages.len(),
+            tool_count = body["tools"].as_array().map_or(0, Vec::len),
+            message_count = body["messages"].as_array().map_or(0, Vec::len),
+            streaming,
             "sending completion request"
         );
 
@@ -550,13 +690,11 @@ impl crate::ModelProvider for OpenAICompatProvider {
             }
             AuthStyle::None => http_req,
         };
-        for (name, val) in &self.extra_headers {
-            http_req = http_req.header(name.as_str(), val.as_str());
-        }
+        http_req = self.apply_extra_headers(http_req);
         // For Anthropic models via OpenRouter, opt in to the prompt-caching
         // beta so that the cache_control markers we added above are honoured.
         // Gemini does not require this header; the markers are always active.
-        if use_anthropic_cache {
+        if self.routes_to_anthropic() {
             http_req = http_req.header("anthropic-beta", "prompt-caching-2024-07-31");
         }
 
@@ -571,7 +709,21 @@ impl crate::ModelProvider for OpenAICompatProvider {
             bail!("{} error {status}: {text}", self.driver_name);
         }
 
-        let byte_stream = resp.bytes_stream();
+        if !streaming {
+            // A single JSON completion (`--no-stream` or
+            // `driver_options.stream: false`), replayed as one burst of events.
+            let v: Value = resp
+                .json()
+                .await
+                .with_context(|| format!("{} returned an invalid response", self.driver_name))?;
+            let events = stream::parse_completion_response(&v)?;
+            return Ok(Box::pin(stream::with_usage_fallback(
+                futures::stream::iter(events.into_iter().map(Ok)),
+                estimated_input,
+            )));
+        }
+
+        let byte_stream = stream::with_idle_timeout(resp.bytes_stream(), self.idle_timeout);
         // SSE events can be split across multiple TCP packets.  Maintain a
         // raw-byte line buffer across chunks; emit events only for complete
         // lines.  Using Vec<u8> (rather than String) avoids silently
@@ -586,16 +738,38 @@ impl crate::ModelProvider for OpenAICompatProvider {
                         buf.extend_from_slice(&b);
                         drain_complete_sse_lines_bytes(buf)
                     }
-                    Err(e) => vec![Err(anyhow::anyhow!(e))],
+                    Err(e) => vec![Err(e)],
                 };
                 std::future::ready(Some(events))
             })
             .flat_map(futures::stream::iter);
 
-        Ok(Box::pin(event_stream))
+        Ok(Box::pin(stream::with_usage_fallback(
+            event_stream,
+            estimated_input,
+        )))
     }
 }
 
+// ── OpenRouter routing preferences ────────────────────────────────────────────
+
+/// The keys of `driver_options.openrouter` forwarded in OpenRouter's
+/// `provider` request field.
+pub(crate) const OPENROUTER_PROVIDER_KEYS: &[&str] =
+    &["order", "allow_fallbacks", "require_parameters"];
+
+/// Build OpenRouter's `provider` routing object from
+/// `driver_options.openrouter` (validated by `from_config`).
+fn openrouter_provider_preferences(prefs: &Value) -> Value {
+    let mut provider = serde_json::Map::new();
+    for key in OPENROUTER_PROVIDER_KEYS {
+        if let Some(v) = prefs.get(*key) {
+            provider.insert((*key).to_string(), v.clone());
+        }
+    }
+    Value::Object(provider)
+}
+
 // ── OpenRouter-specific model response parsing ────────────────────────────────
 
 /// Parse a `GET /models` response body into a list of [`ModelCatalogEntry`].
@@ -698,7 +872,7 @@ fn parse_openrouter_input_modalities(arr: Option<&Vec<Value>>) -> Vec<InputModal
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ModelProvider;
+    use crate::{Message, ModelProvider, ToolSchema};
 
     fn make_provider() -> OpenAICompatProvider {
         OpenAICompatProvider::new(
@@ -856,6 +1030,265 @@ mod tests {
         );
     }
 
+    #[test]
+    fn stream_false_requests_a_single_completion() {
+        let p = OpenAICompatProvider::new(
+            "test",
+            "m".into(),
+            None,
+            "http://localhost/v1",
+            None,
+            None,
+            vec![],
+            AuthStyle::None,
+            json!({ "stream": false }),
+        );
+        let req = CompletionRequest {
+            messages: vec![Message::user("hi")],
+            stream: true,
+            ..CompletionRequest::default()
+        };
+        let body = p.build_request_body(&req);
+        assert_eq!(body["stream"], json!(false));
+        assert!(
+            body.get("stream_options").is_none(),
+            "stream_options is only valid on streaming requests: {body}"
+        );
+
+        let body = make_provider().build_request_body(&req);
+        assert_eq!(body["stream"], json!(true));
+        assert_eq!(body["stream_options"]["include_usage"], json!(true));
+    }
+
+    #[test]
+    fn reasoning_effort_is_mapped_per_driver() {
+        let req = CompletionRequest {
+            messages: vec![Message::user("hi")],
+            ..CompletionRequest::default()
+        };
+        let provider = |driver| {
+            OpenAICompatProvider::new(
+                driver,
+                "gpt-4.1".into(),
+                None,
+                "http://localhost/v1",
+                None,
+                None,
+                vec![],
+                AuthStyle::None,
+                Value::Null,
+            )
+            .with_reasoning_effort(Some("high".into()))
+        };
+
+        let body = provider("openai").build_request_body(&req);
+        assert_eq!(body["reasoning_effort"], json!("high"));
+        assert!(
+            body.get("temperature").is_none(),
+            "reasoning requests must not set a temperature: {body}"
+        );
+
+        let body = provider("openrouter").build_request_body(&req);
+        assert_eq!(body["reasoning"], json!({ "effort": "high" }));
+        assert!(body.get("reasoning_effort").is_none());
+
+        let body = provider("groq").build_request_body(&req);
+        assert!(body.get("reasoning_effort").is_none());
+        assert!(body.get("reasoning").is_none());
+        assert!(body.get("temperature").is_some());
+
+        let body = make_provider().build_request_body(&req);
+        assert!(body.get("reasoning_effort").is_none());
+    }
+
+    #[test]
+    fn response_format_carries_the_schema() {
+        let schema = json!({
+            "type": "object",
+            "properties": { "verdict": { "type": "string" } },
+            "required": ["verdict"],
+        });
+        let req = CompletionRequest {
+            messages: vec![Message::user("hi")],
+            ..CompletionRequest::default()
+        };
+        let body = make_provider()
+            .with_response_format(Some(schema.clone()))
+            .build_request_body(&req);
+        assert_eq!(body["response_format"]["type"], json!("json_schema"));
+        assert_eq!(body["response_format"]["json_schema"]["schema"], schema);
+
+        let body = make_provider().build_request_body(&req);
+        assert!(body.get("response_format").is_none());
+    }
+
+    #[test]
+    fn nested_extra_body_adds_fields_without_overriding() {
+        let p = OpenAICompatProvider::new(
+            "openai",
+            "gpt-4o".into(),
+            None,
+            "http://localhost/v1",
+            None,
+            None,
+            vec![],
+            AuthStyle::Bearer,
+            json!({ "extra_body": { "service_tier": "flex", "model": "other" } }),
+        );
+        let req = CompletionRequest {
+            messages: vec![Message::user("hi")],
+            ..CompletionRequest::default()
+        };
+        let body = p.build_request_body(&req);
+        assert_eq!(body["service_tier"], json!("flex"));
+        assert_eq!(body["model"], json!("gpt-4o"), "sven fields win");
+        assert!(body.get("extra_body").is_none());
+    }
+
+    #[test]
+    fn extra_headers_are_sent_and_kept_out_of_the_body() {
+        let p = OpenAICompatProvider::new(
+            "openrouter",
+            "m".into(),
+            None,
+            "https://openrouter.ai/api/v1",
+            None,
+            None,
+            vec![("HTTP-Referer".into(), "https://example.com".into())],
+            AuthStyle::Bearer,
+            json!({ "extra_headers": { "X-Gateway-Tenant": "team-a" } }),
+        )
+        .with_extra_headers(vec![("X-Gateway-Tenant".into(), "team-a".into())]);
+        let req = p
+            .apply_extra_headers(p.client.post(&p.chat_url))
+            .build()
+            .unwrap();
+        assert_eq!(req.headers()["x-gateway-tenant"], "team-a");
+        assert_eq!(req.headers()["http-referer"], "https://example.com");
+
+        let body = p.build_request_body(&CompletionRequest {
+            messages: vec![Message::user("hi")],
+            ..CompletionRequest::default()
+        });
+        assert!(body.get("extra_headers").is_none());
+    }
+
+    #[test]
+    fn openrouter_routing_preferences_become_provider_field() {
+        let prefs = json!({
+            "order": ["anthropic", "amazon-bedrock"],
+            "allow_fallbacks": false,
+            "require_parameters": true,
+        });
+        let build = |driver: &'static str| {
+            OpenAICompatProvider::new(
+                driver,
+                "anthropic/claude-sonnet-4".into(),
+                None,
+                "https://openrouter.ai/api/v1",
+                None,
+                None,
+                vec![],
+                AuthStyle::Bearer,
+                json!({ "openrouter": prefs.clone() }),
+            )
+            .build_request_body(&CompletionRequest {
+                messages: vec![Message::user("hi")],
+                ..CompletionRequest::default()
+            })
+        };
+
+        let body = build("openrouter");
+        assert_eq!(body["provider"], prefs);
+        assert!(body.get("openrouter").is_none());
+
+        let body = build("groq");
+        assert!(body.get("provider").is_none());
+        assert!(body.get("openrouter").is_none());
+    }
+
+    #[test]
+    fn logit_bias_is_forwarded_verbatim() {
+        let bias = json!({ "50256": -100, "2159": 1.5 });
+        let p = OpenAICompatProvider::new(
+            "openai",
+            "gpt-4o".into(),
+            None,
+            "http://localhost/v1",
+            None,
+            None,
+            vec![],
+            AuthStyle::Bearer,
+            json!({ "logit_bias": bias.clone() }),
+        );
+        let req = CompletionRequest {
+            messages: vec![Message::user("hi")],
+            ..CompletionRequest::default()
+        };
+        assert_eq!(p.build_request_body(&req)["logit_bias"], bias);
+    }
+
+    #[test]
+    fn stop_sequences_are_sent_as_stop() {
+        let req = CompletionRequest {
+            messages: vec![Message::user("hi")],
+            ..CompletionRequest::default()
+        };
+        let body = make_provider()
+            .with_stop(vec!["<<END>>".into(), "\n\n##".into()])
+            .build_request_body(&req);
+        assert_eq!(body["stop"], json!(["<<END>>", "\n\n##"]));
+        assert!(make_provider()
+            .build_request_body(&req)
+            .get("stop")
+            .is_none());
+    }
+
+    #[test]
+    fn non_streaming_response_is_parsed_into_events() {
+        let v = json!({
+            "choices": [{
+                "message": {
+                    "role": "assistant",
+                    "reasoning_content": "Look it up.",
+                    "content": "Reading the file.",
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": { "name": "read_file", "arguments": "{\"path\":\"a.rs\"}" }
+                    }]
+                },
+                "finish_reason": "tool_calls"
+            }],
+            "usage": { "prompt_tokens": 30, "completion_tokens": 8 }
+        });
+        let events = stream::parse_completion_response(&v).unwrap();
+        assert!(matches!(&events[0], ResponseEvent::ThinkingDelta(t) if t == "Look it up."));
+        assert!(matches!(&events[1], ResponseEvent::TextDelta(t) if t == "Reading the file."));
+        assert!(matches!(
+            &events[2],
+            ResponseEvent::ToolCall { index: 0, id, name, arguments }
+                if id == "call_1" && name == "read_file" && arguments == r#"{"path":"a.rs"}"#
+        ));
+        assert!(matches!(
+            &events[3],
+            ResponseEvent::Usage {
+                input_tokens: 30,
+                output_tokens: 8,
+                estimated: false,
+                ..
+            }
+        ));
+        assert!(matches!(events[4], ResponseEvent::Done));
+        assert_eq!(events.len(), 5);
+
+        let err = stream::parse_completion_response(&json!({
+            "error": { "message": "model overloaded" }
+        }))
+        .unwrap_err();
+        assert!(err.to_string().contains("model overloaded"), "{err}");
+    }
+
     // ── parse_sse_chunk ───────────────────────────────────────────────────────
 
     #[test]
@@ -1016,6 +1449,7 @@ mod tests {
                     cache_read_tokens: 0,
                     cache_write_tokens: 4800,
                     cost_usd: None,
+                    estimated: false,
                 }
             ),
             "unexpected event: {ev:?}"
@@ -1289,6 +1723,133 @@ mod tests {
         assert!(matches!(events[0], Ok(ResponseEvent::Done)));
     }
 
+    // ── Missing usage fallback ───────────────────────────────────────────────
+
+    async fn collect_with_fallback(events: Vec<ResponseEvent>) -> Vec<ResponseEvent> {
+        let events = futures::stream::iter(events.into_iter().map(Ok));
+        stream::with_usage_fallback(events, 120)
+            .map(|e| e.unwrap())
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn stream_without_usage_gets_flagged_estimate_before_done() {
+        let events = collect_with_fallback(vec![
+            ResponseEvent::TextDelta("Hello, ".into()),
+            ResponseEvent::TextDelta("world!".into()),
+            ResponseEvent::ToolCall {
+                index: 0,
+                id: "call_1".into(),
+                name: "read_file".into(),
+                arguments: r#"{"path":"a.rs"}"#.into(),
+            },
+            ResponseEvent::Done,
+        ])
+        .await;
+        assert_eq!(events.len(), 5);
+        match &events[3] {
+            ResponseEvent::Usage {
+                input_tokens,
+                output_tokens,
+                estimated,
+                ..
+            } => {
+                assert_eq!(*input_tokens, 120);
+                // 13 bytes of text + 9 + 15 bytes of tool call = 37 → 9 tokens.
+                assert_eq!(*output_tokens, 9);
+                assert!(*estimated);
+            }
+            other => panic!("expected estimated usage, got {other:?}"),
+        }
+        assert!(matches!(events[4], ResponseEvent::Done));
+    }
+
+    #[tokio::test]
+    async fn reported_usage_is_passed_through_unchanged() {
+        let events = collect_with_fallback(vec![
+            ResponseEvent::TextDelta("hi".into()),
+            ResponseEvent::Usage {
+                input_tokens: 10,
+                output_tokens: 1,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
+                cost_usd: None,
+                estimated: false,
+            },
+            ResponseEvent::Done,
+        ])
+        .await;
+        assert_eq!(events.len(), 3);
+        assert!(matches!(
+            events[1],
+            ResponseEvent::Usage {
+                input_tokens: 10,
+                estimated: false,
+                ..
+            }
+        ));
+    }
+
+    // ── Stream idle timeout ──────────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn silent_stream_fails_with_idle_timeout() {
+        let idle = Duration::from_millis(50);
+        let chunks = futures::stream::iter([Ok::<_, anyhow::Error>("data: x\n")])
+            .chain(futures::stream::pending());
+        let mut chunks = Box::pin(stream::with_idle_timeout(chunks, Some(idle)));
+
+        assert_eq!(chunks.next().await.unwrap().unwrap(), "data: x\n");
+        let err = chunks.next().await.unwrap().unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<StreamIdleTimeout>(),
+            Some(&StreamIdleTimeout { idle })
+        );
+        assert!(err.to_string().contains("retryable"), "{err}");
+        assert!(
+            chunks.next().await.is_none(),
+            "the stream ends after timing out"
+        );
+    }
+
+    #[tokio::test]
+    async fn finished_stream_ends_cleanly_under_idle_timeout() {
+        let chunks = futures::stream::iter([Ok::<_, anyhow::Error>(1), Ok(2)]);
+        let items: Vec<i32> = stream::with_idle_timeout(chunks, Some(Duration::from_secs(1)))
+            .map(|c| c.unwrap())
+            .collect()
+            .await;
+        assert_eq!(items, vec![1, 2]);
+    }
+
+    #[test]
+    fn idle_timeout_is_configurable_and_can_be_disabled() {
+        let p = make_provider();
+        assert_eq!(p.idle_timeout, Some(DEFAULT_STREAM_IDLE_TIMEOUT));
+        let p = p.with_idle_timeout(None);
+        assert_eq!(p.idle_timeout, None);
+    }
+
+    #[test]
+    fn request_estimate_counts_messages_and_tool_schemas() {
+        let req = CompletionRequest {
+            messages: vec![
+                Message::system("x".repeat(400)),
+                Message::user("y".repeat(40)),
+            ],
+            tools: vec![ToolSchema {
+                name: "grep".into(),
+                description: "d".repeat(36),
+                parameters: json!({}),
+                ..ToolSchema::default()
+            }],
+            ..CompletionRequest::default()
+        };
+        // 100 + 10 for the messages, (4 + 36 + 2) / 4 = 10 for the schema.
+        assert_eq!(stream::estimate_request_tokens(&req), 120);
+    }
+
     #[test]
     fn windows_crlf_line_endings_are_handled() {
         let line = r#"{"choices":[{"delta":{"content":"hi"}}]}"#;
@@ -1526,6 +2087,63 @@ mod tests {
         );
     }
 
+    #[test]
+    fn interleaved_reasoning_and_text_stay_distinct() {
+        let chunks = [
+            json!({ "choices": [{ "delta": { "reasoning": "First, ", "content": "" } }] }),
+            json!({ "choices": [{ "delta": { "reasoning": "check.", "content": "It " } }] }),
+            json!({ "choices": [{ "delta": { "content": "works." } }] }),
+            json!({ "choices": [{ "delta": { "reasoning_details": [
+                { "type": "reasoning.summary", "summary": "Verify again." },
+                { "type": "reasoning.encrypted", "data": "gAAAA" }
+            ] } }] }),
+            json!({ "choices": [{ "delta": { "content": " Done." } }] }),
+        ];
+        let mut buf: String = chunks.iter().map(|c| format!("data: {c}\n\n")).collect();
+        let events: Vec<ResponseEvent> = drain_complete_sse_lines(&mut buf)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+
+        let routed: Vec<(&str, &str)> = events
+            .iter()
+            .map(|e| match e {
+                ResponseEvent::ThinkingDelta(t) => ("thinking", t.as_str()),
+                ResponseEvent::TextDelta(t) => ("text", t.as_str()),
+                other => panic!("unexpected event: {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            routed,
+            vec![
+                ("thinking", "First, "),
+                ("thinking", "check."),
+                ("text", "It "),
+                ("text", "works."),
+                ("thinking", "Verify again."),
+                ("text", " Done."),
+            ]
+        );
+    }
+
+    #[test]
+    fn non_streaming_reasoning_summary_is_thinking() {
+        let v = json!({
+            "choices": [{
+                "message": {
+                    "content": "Answer.",
+                    "reasoning_details": [
+                        { "type": "reasoning.summary", "summary": "Short summary." }
+                    ]
+                },
+                "finish_reason": "stop"
+            }]
+        });
+        let events = stream::parse_completion_response(&v).unwrap();
+        assert!(matches!(&events[0], ResponseEvent::ThinkingDelta(t) if t == "Short summary."));
+        assert!(matches!(&events[1], ResponseEvent::TextDelta(t) if t == "Answer."));
+    }
+
     // ── llama.cpp timings ─────────────────────────────────────────────────────
     // llama.cpp emits performance metrics in a top-level `timings` object in
     // the final SSE chunk.  We parse this into a Usage event.
@@ -3,23 +3,30 @@
 // SPDX-License-Identifier: Apache-2.0
 //! SSE stream parsing for OpenAI-compatible chat completion responses.
 
+use std::time::Duration;
+
+use futures::{Stream, StreamExt};
 use serde_json::Value;
 
-use crate::ResponseEvent;
+use crate::{approx_tokens_for_len, CompletionRequest, Message, ResponseEvent};
 
-/// Parse a single complete SSE `data:` line into a [`ResponseEvent`].
+/// Parse a single complete SSE `data:` line into [`ResponseEvent`]s.
 ///
-/// Returns `None` for empty lines, comment lines, or unparseable data.
-pub(super) fn parse_sse_data_line(line: &str) -> Option<anyhow::Result<ResponseEvent>> {
-    let data = line.strip_prefix("data: ")?.trim();
+/// Returns nothing for empty lines, comment lines, or unparseable data.
+pub(super) fn parse_sse_data_line(line: &str) -> Vec<ResponseEvent> {
+    let Some(data) = line.strip_prefix("data: ").map(str::trim) else {
+        return Vec::new();
+    };
     if data.is_empty() {
-        return None;
+        return Vec::new();
     }
     if data == "[DONE]" {
-        return Some(Ok(ResponseEvent::Done));
+        return vec![ResponseEvent::Done];
+    }
+    match serde_json::from_str::<Value>(data) {
+        Ok(v) => parse_sse_chunk(&v),
+        Err(_) => Vec::new(),
     }
-    let v: Value = serde_json::from_str(data).ok()?;
-    Some(parse_sse_chunk(&v))
 }
 
 /// Drain all complete `\n`-terminated SSE lines from a raw-byte buffer.
@@ -39,9 +46,7 @@ pub(crate) fn drain_complete_sse_lines_bytes(
         let line = String::from_utf8_lossy(&line_bytes)
             .trim_end_matches(['\r', '\n'])
             .to_string();
-        if let Some(ev) = parse_sse_data_line(&line) {
-            events.push(ev);
-        }
+        events.extend(parse_sse_data_line(&line).into_iter().map(Ok));
     }
     events
 }
@@ -61,55 +66,140 @@ pub(crate) fn drain_complete_sse_lines(buf: &mut String) -> Vec<anyhow::Result<R
     events
 }
 
+/// The first event of a chunk, for tests written against one event per chunk.
 #[cfg(test)]
 pub(super) fn parse_sse_chunk_test(v: &Value) -> anyhow::Result<ResponseEvent> {
-    parse_sse_chunk(v)
+    Ok(parse_sse_chunk(v)
+        .into_iter()
+        .next()
+        .unwrap_or(ResponseEvent::TextDelta(String::new())))
+}
+
+/// Reasoning text carried by a `delta` or `message` object.
+///
+/// Field names vary by server:
+///   • `reasoning_content` — llama.cpp, Qwen3, DeepSeek-R1, xAI Grok-3-mini
+///   • `reasoning`         — OpenRouter (and some other aggregators)
+///   • `reasoning_details` — OpenRouter's structured form; OpenAI reasoning
+///     summaries arrive as `reasoning.summary` entries.  Only consulted when
+///     neither string field is present, since OpenRouter usually sends both.
+///
+/// Encrypted reasoning entries carry no readable text and are skipped.
+fn reasoning_text(obj: &Value) -> Option<String> {
+    let text = obj["reasoning_content"]
+        .as_str()
+        .or_else(|| obj["reasoning"].as_str())
+        .map(str::to_string)
+        .or_else(|| {
+            let details = obj["reasoning_details"].as_array()?;
+            Some(
+                details
+                    .iter()
+                    .filter_map(|d| match d["type"].as_str() {
+                        Some("reasoning.summary") => d["summary"].as_str(),
+                        Some("reasoning.text") => d["text"].as_str(),
+                        _ => None,
+                    })
+                    .collect::<String>(),
+            )
+        })?;
+    (!text.is_empty()).then_some(text)
+}
+
+/// Turn a non-streaming `/chat/completions` response into the events the
+/// streaming parser would have produced for it, ending with `Done`.
+pub(super) fn parse_completion_response(v: &Value) -> anyhow::Result<Vec<ResponseEvent>> {
+    if let Some(err) = v.get("error").filter(|e| !e.is_null()) {
+        let msg = err["message"].as_str().unwrap_or("unknown error");
+        anyhow::bail!("completion failed: {msg}");
+    }
+    let choice = &v["choices"][0];
+    let message = &choice["message"];
+    let mut events = Vec::new();
+    if let Some(reasoning) = reasoning_text(message) {
+        events.push(ResponseEvent::ThinkingDelta(reasoning));
+    }
+    if let Some(text) = message["content"].as_str().filter(|t| !t.is_empty()) {
+        events.push(ResponseEvent::TextDelta(text.to_string()));
+    }
+    if let Some(calls) = message["tool_calls"].as_array() {
+        for (index, call) in calls.iter().enumerate() {
+            events.push(ResponseEvent::ToolCall {
+                index: index as u32,
+                id: call["id"].as_str().unwrap_or_default().to_string(),
+                name: call["function"]["name"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                arguments: call["function"]["arguments"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+            });
+        }
+    }
+    if choice["finish_reason"].as_str() == Some("length") {
+        events.push(ResponseEvent::MaxTokens);
+    }
+    if let Some(usage) = v.get("usage").filter(|u| !u.is_null()) {
+        events.push(parse_usage(usage));
+    }
+    events.push(ResponseEvent::Done);
+    Ok(events)
 }
 
-fn parse_sse_chunk(v: &Value) -> anyhow::Result<ResponseEvent> {
+/// Parse an OpenAI-style `usage` object.
+fn parse_usage(usage: &Value) -> ResponseEvent {
+    // OpenAI/OpenRouter reports cached tokens in
+    // prompt_tokens_details.cached_tokens.  DeepSeek V3 uses the root-level
+    // prompt_cache_hit_tokens field instead.  We try the nested format
+    // first and fall back to DeepSeek's flat format.
+    let prompt_tokens_details = usage.get("prompt_tokens_details");
+    let cache_read_tokens = prompt_tokens_details
+        .and_then(|d| d.get("cached_tokens"))
+        .and_then(|t| t.as_u64())
+        .or_else(|| {
+            usage
+                .get("prompt_cache_hit_tokens")
+                .and_then(|t| t.as_u64())
+        })
+        .unwrap_or(0) as u32;
+    // OpenRouter reports cache write tokens in
+    // prompt_tokens_details.cache_write_tokens (non-zero when a new cache
+    // entry was written, e.g. first Anthropic or Gemini turn).
+    let cache_write_tokens = prompt_tokens_details
+        .and_then(|d| d.get("cache_write_tokens"))
+        .and_then(|t| t.as_u64())
+        .unwrap_or(0) as u32;
+    // OpenAI/DeepSeek/OpenRouter report `prompt_tokens` as the grand total
+    // (fresh + cache_read + cache_write).  The ResponseEvent::Usage contract
+    // requires `input_tokens` to be fresh-only so that callers can compute
+    // total_ctx = input + cache_read + cache_write without double-counting.
+    let prompt_tokens = usage["prompt_tokens"].as_u64().unwrap_or(0) as u32;
+    let fresh_input = prompt_tokens
+        .saturating_sub(cache_read_tokens)
+        .saturating_sub(cache_write_tokens);
+    // OpenRouter returns usage.cost; some providers use total_cost.
+    let cost_usd = usage
+        .get("cost")
+        .and_then(|v| v.as_f64())
+        .or_else(|| usage.get("total_cost").and_then(|v| v.as_f64()));
+    ResponseEvent::Usage {
+        input_tokens: fresh_input,
+        output_tokens: usage["completion_tokens"].as_u64().unwrap_or(0) as u32,
+        cache_read_tokens,
+        cache_write_tokens,
+        cost_usd,
+        estimated: false,
+    }
+}
+
+/// Events for one streaming chunk.  A chunk may carry reasoning and answer
+/// text together; the reasoning is emitted first so it stays distinct.
+fn parse_sse_chunk(v: &Value) -> Vec<ResponseEvent> {
     // Usage-only chunk (emitted when stream_options.include_usage = true)
     if let Some(usage) = v.get("usage").filter(|u| !u.is_null()) {
-        // OpenAI/OpenRouter reports cached tokens in
-        // prompt_tokens_details.cached_tokens.  DeepSeek V3 uses the root-level
-        // prompt_cache_hit_tokens field instead.  We try the nested format
-        // first and fall back to DeepSeek's flat format.
-        let prompt_tokens_details = usage.get("prompt_tokens_details");
-        let cache_read_tokens = prompt_tokens_details
-            .and_then(|d| d.get("cached_tokens"))
-            .and_then(|t| t.as_u64())
-            .or_else(|| {
-                usage
-                    .get("prompt_cache_hit_tokens")
-                    .and_then(|t| t.as_u64())
-            })
-            .unwrap_or(0) as u32;
-        // OpenRouter reports cache write tokens in
-        // prompt_tokens_details.cache_write_tokens (non-zero when a new cache
-        // entry was written, e.g. first Anthropic or Gemini turn).
-        let cache_write_tokens = prompt_tokens_details
-            .and_then(|d| d.get("cache_write_tokens"))
-            .and_then(|t| t.as_u64())
-            .unwrap_or(0) as u32;
-        // OpenAI/DeepSeek/OpenRouter report `prompt_tokens` as the grand total
-        // (fresh + cache_read + cache_write).  The ResponseEvent::Usage contract
-        // requires `input_tokens` to be fresh-only so that callers can compute
-        // total_ctx = input + cache_read + cache_write without double-counting.
-        let prompt_tokens = usage["prompt_tokens"].as_u64().unwrap_or(0) as u32;
-        let fresh_input = prompt_tokens
-            .saturating_sub(cache_read_tokens)
-            .saturating_sub(cache_write_tokens);
-        // OpenRouter returns usage.cost; some providers use total_cost.
-        let cost_usd = usage
-            .get("cost")
-            .and_then(|v| v.as_f64())
-            .or_else(|| usage.get("total_cost").and_then(|v| v.as_f64()));
-        return Ok(ResponseEvent::Usage {
-            input_tokens: fresh_input,
-            output_tokens: usage["completion_tokens"].as_u64().unwrap_or(0) as u32,
-            cache_read_tokens,
-            cache_write_tokens,
-            cost_usd,
-        });
+        return vec![parse_usage(usage)];
     }
 
     // llama.cpp performance metrics (top-level `timings` object)
@@ -124,13 +214,14 @@ fn parse_sse_chunk(v: &Value) -> anyhow::Result<ResponseEvent> {
 
         // llama.cpp reports cache hits and fresh tokens separately.
         // `prompt_n` is the fresh-only count; `cache_n` goes into cache_read_tokens.
-        return Ok(ResponseEvent::Usage {
+        return vec![ResponseEvent::Usage {
             input_tokens: prompt_n,
             output_tokens: predicted_n,
             cache_read_tokens: cache_n,
             cache_write_tokens: 0,
             cost_usd: None,
-        });
+            estimated: false,
+        }];
     }
 
     let choice = &v["choices"][0];
@@ -139,7 +230,7 @@ fn parse_sse_chunk(v: &Value) -> anyhow::Result<ResponseEvent> {
     // Emit MaxTokens so the agent knows any pending tool-call arguments
     // are truncated.  The [DONE] sentinel that follows will emit Done.
     if choice["finish_reason"].as_str() == Some("length") {
-        return Ok(ResponseEvent::MaxTokens);
+        return vec![ResponseEvent::MaxTokens];
     }
 
     let delta = &choice["delta"];
@@ -157,34 +248,133 @@ fn parse_sse_chunk(v: &Value) -> anyhow::Result<ResponseEvent> {
                 .as_str()
                 .unwrap_or("")
                 .to_string();
-            return Ok(ResponseEvent::ToolCall {
+            return vec![ResponseEvent::ToolCall {
                 index,
                 id,
                 name,
                 arguments: args,
-            });
+            }];
         }
     }
 
-    // Thinking delta — two common field names for chain-of-thought reasoning:
-    //   • `reasoning_content` — llama.cpp, Qwen3, DeepSeek-R1, xAI Grok-3-mini
-    //   • `reasoning`         — OpenRouter (and some other aggregators)
-    // Both carry the same semantics: readable CoT text that arrived before the
-    // final answer.  Prefer `reasoning_content`; fall back to `reasoning`.
-    let thinking_text = delta
-        .get("reasoning_content")
-        .and_then(|c| c.as_str())
-        .or_else(|| delta.get("reasoning").and_then(|c| c.as_str()));
-    if let Some(thinking) = thinking_text {
-        if !thinking.is_empty() {
-            return Ok(ResponseEvent::ThinkingDelta(thinking.to_string()));
-        }
+    // Thinking delta — readable chain-of-thought that arrived before (or, on
+    // some servers, in the same chunk as) the answer text.  See
+    // `reasoning_text` for the field names.
+    let mut events = Vec::new();
+    if let Some(thinking) = reasoning_text(delta) {
+        events.push(ResponseEvent::ThinkingDelta(thinking));
     }
 
     // Text delta
     if let Some(text) = delta.get("content").and_then(|c| c.as_str()) {
-        return Ok(ResponseEvent::TextDelta(text.to_string()));
+        if !text.is_empty() || events.is_empty() {
+            events.push(ResponseEvent::TextDelta(text.to_string()));
+        }
     }
 
-    Ok(ResponseEvent::TextDelta(String::new()))
+    if events.is_empty() {
+        events.push(ResponseEvent::TextDelta(String::new()));
+    }
+    events
+}
+
+/// Rough input-token count of `req`, for [`with_usage_fallback`].
+pub(super) fn estimate_request_tokens(req: &CompletionRequest) -> u32 {
+    let messages: usize = req.messages.iter().map(Message::approx_tokens).sum();
+    let schema_chars: usize = req
+        .tools
+        .iter()
+        .map(|t| t.name.len() + t.description.len() + t.parameters.to_string().len())
+        .sum();
+    let suffix_chars = req.system_dynamic_suffix.as_deref().map_or(0, str::len);
+    (messages + approx_tokens_for_len(schema_chars + suffix_chars)) as u32
+}
+
+/// Pass `events` through, inserting an estimated [`ResponseEvent::Usage`]
+/// before `Done` when the stream carried no usage of its own.
+///
+/// Some gateways ignore `stream_options.include_usage` and never send a
+/// usage chunk, which would leave context and budget tracking at zero.  The
+/// input side comes from `input_tokens` (see [`estimate_request_tokens`]);
+/// the output side is estimated from the streamed text and tool calls with
+/// the same heuristic ([`approx_tokens_for_len`]).
+pub(super) fn with_usage_fallback<S>(
+    events: S,
+    input_tokens: u32,
+) -> impl Stream<Item = anyhow::Result<ResponseEvent>>
+where
+    S: Stream<Item = anyhow::Result<ResponseEvent>>,
+{
+    events
+        .scan((false, 0usize), move |(usage_seen, output_bytes), event| {
+            let mut out = Vec::with_capacity(2);
+            match &event {
+                Ok(ResponseEvent::Usage { .. }) => *usage_seen = true,
+                Ok(ResponseEvent::TextDelta(t)) | Ok(ResponseEvent::ThinkingDelta(t)) => {
+                    *output_bytes += t.len();
+                }
+                Ok(ResponseEvent::ToolCall {
+                    name, arguments, ..
+                }) => *output_bytes += name.len() + arguments.len(),
+                Ok(ResponseEvent::Done) if !*usage_seen => {
+                    *usage_seen = true;
+                    out.push(Ok(ResponseEvent::Usage {
+                        input_tokens,
+                        output_tokens: approx_tokens_for_len(*output_bytes) as u32,
+                        cache_read_tokens: 0,
+                        cache_write_tokens: 0,
+                        cost_usd: None,
+                        estimated: true,
+                    }));
+                }
+                _ => {}
+            }
+            out.push(event);
+            std::future::ready(Some(out))
+        })
+        .flat_map(futures::stream::iter)
+}
+
+/// A streaming response received no bytes within the idle window.
+///
+/// Unlike a clean finish (`Done`), the stream was cut off mid-response, so
+/// the turn's output is incomplete.  The request itself was fine and can be
+/// retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "model stream received no data for {}s; the connection may have stalled (retryable)",
+    .idle.as_secs()
+)]
+pub struct StreamIdleTimeout {
+    pub idle: Duration,
+}
+
+/// Pass `chunks` through, failing with [`StreamIdleTimeout`] (and ending the
+/// stream) when no chunk arrives within `idle`.  `None` disables the timeout.
+pub(super) fn with_idle_timeout<S, T, E>(
+    chunks: S,
+    idle: Option<Duration>,
+) -> impl Stream<Item = anyhow::Result<T>>
+where
+    S: Stream<Item = Result<T, E>>,
+    E: Into<anyhow::Error>,
+{
+    futures::stream::unfold(
+        (Box::pin(chunks), false),
+        move |(mut chunks, timed_out)| async move {
+            if timed_out {
+                return None;
+            }
+            let next = match idle {
+                Some(idle) => match tokio::time::timeout(idle, chunks.next()).await {
+                    Ok(next) => next,
+                    Err(_) => {
+                        return Some((Err(StreamIdleTimeout { idle }.into()), (chunks, true)))
+                    }
+                },
+                None => chunks.next().await,
+            };
+            next.map(|chunk| (chunk.map_err(Into::into), (chunks, false)))
+        },
+    )
 }
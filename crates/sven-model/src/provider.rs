@@ -37,6 +37,15 @@ pub trait ModelProvider: Send + Sync {
         Ok(entries)
     }
 
+    /// Check that the endpoint is reachable and accepts the configured
+    /// credentials, without spending tokens.
+    ///
+    /// Returns `Ok(true)` when the check passed and `Ok(false)` when the
+    /// driver has no cheap request to check with (the default).
+    async fn health_check(&self) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+
     /// Maximum output tokens for this provider/model combination.
     ///
     /// Reads from the static catalog; returns `None` if the model is unknown.
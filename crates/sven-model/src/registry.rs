@@ -7,11 +7,76 @@
 //! and what their defaults are.  It does **not** contain construction logic –
 //! that lives in [`crate::from_config`].
 
+/// Group a driver belongs to.  Variants are declared in the order
+/// [`list_drivers`] presents them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DriverCategory {
+    Cloud,
+    Gateway,
+    FastInference,
+    OpenModels,
+    Specialized,
+    Regional,
+    Local,
+    Testing,
+}
+
+impl DriverCategory {
+    /// Every category, in presentation order.
+    pub const ALL: [DriverCategory; 8] = [
+        Self::Cloud,
+        Self::Gateway,
+        Self::FastInference,
+        Self::OpenModels,
+        Self::Specialized,
+        Self::Regional,
+        Self::Local,
+        Self::Testing,
+    ];
+
+    /// Short name accepted by `sven list-providers --category`.
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Cloud => "cloud",
+            Self::Gateway => "gateway",
+            Self::FastInference => "fast",
+            Self::OpenModels => "open",
+            Self::Specialized => "specialized",
+            Self::Regional => "regional",
+            Self::Local => "local",
+            Self::Testing => "testing",
+        }
+    }
+
+    /// The category whose [`Self::id`] is `id`, ignoring case.
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|c| c.id().eq_ignore_ascii_case(id.trim()))
+    }
+
+    /// Heading shown by `sven list-providers`, followed by [`Self::id`].
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Cloud => "Major cloud providers",
+            Self::Gateway => "Gateways",
+            Self::FastInference => "Fast inference platforms",
+            Self::OpenModels => "Open model platforms",
+            Self::Specialized => "Specialized",
+            Self::Regional => "Regional providers",
+            Self::Local => "Local / OSS",
+            Self::Testing => "Testing",
+        }
+    }
+}
+
 /// Metadata describing a registered model driver.
 #[derive(Debug, Clone)]
 pub struct DriverMeta {
     /// Unique provider id used in `model.provider` config field (e.g. `"openai"`).
     pub id: &'static str,
+    /// Group the driver is listed under.
+    pub category: DriverCategory,
     /// Human-readable display name (e.g. `"OpenAI"`).
     pub name: &'static str,
     /// One-line description shown by `sven list-providers`.
@@ -26,19 +91,13 @@ pub struct DriverMeta {
     pub requires_api_key: bool,
 }
 
-/// Complete registry of supported drivers.
+/// Complete registry of supported drivers, grouped by [`DriverCategory`] in
+/// its declaration order and sorted by id within each group.
 pub static DRIVERS: &[DriverMeta] = &[
     // ── Major cloud providers ─────────────────────────────────────────────────
-    DriverMeta {
-        id: "openai",
-        name: "OpenAI",
-        description: "OpenAI GPT and o-series models",
-        default_api_key_env: Some("OPENAI_API_KEY"),
-        default_base_url: Some("https://api.openai.com/v1"),
-        requires_api_key: true,
-    },
     DriverMeta {
         id: "anthropic",
+        category: DriverCategory::Cloud,
         name: "Anthropic",
         description: "Anthropic Claude models",
         default_api_key_env: Some("ANTHROPIC_API_KEY"),
@@ -46,56 +105,81 @@ pub static DRIVERS: &[DriverMeta] = &[
         requires_api_key: true,
     },
     DriverMeta {
-        id: "google",
-        name: "Google Gemini",
-        description: "Google Gemini models via Generative Language API",
-        default_api_key_env: Some("GEMINI_API_KEY"),
-        default_base_url: Some("https://generativelanguage.googleapis.com"),
-        requires_api_key: true,
+        id: "aws",
+        category: DriverCategory::Cloud,
+        name: "AWS Bedrock",
+        description: "AWS Bedrock Converse API (uses AWS_ACCESS_KEY_ID / AWS_SECRET_ACCESS_KEY)",
+        default_api_key_env: None,
+        default_base_url: None,
+        requires_api_key: false,
     },
     DriverMeta {
         id: "azure",
+        category: DriverCategory::Cloud,
         name: "Azure OpenAI",
         description: "Azure-hosted OpenAI deployments (set base_url or azure_* config fields)",
         default_api_key_env: Some("AZURE_OPENAI_API_KEY"),
         default_base_url: None,
         requires_api_key: true,
     },
-    DriverMeta {
-        id: "aws",
-        name: "AWS Bedrock",
-        description: "AWS Bedrock Converse API (uses AWS_ACCESS_KEY_ID / AWS_SECRET_ACCESS_KEY)",
-        default_api_key_env: None,
-        default_base_url: None,
-        requires_api_key: false,
-    },
     DriverMeta {
         id: "cohere",
+        category: DriverCategory::Cloud,
         name: "Cohere",
         description: "Cohere Command models",
         default_api_key_env: Some("COHERE_API_KEY"),
         default_base_url: Some("https://api.cohere.com"),
         requires_api_key: true,
     },
+    DriverMeta {
+        id: "google",
+        category: DriverCategory::Cloud,
+        name: "Google Gemini",
+        description: "Google Gemini models via Generative Language API",
+        default_api_key_env: Some("GEMINI_API_KEY"),
+        default_base_url: Some("https://generativelanguage.googleapis.com"),
+        requires_api_key: true,
+    },
+    DriverMeta {
+        id: "openai",
+        category: DriverCategory::Cloud,
+        name: "OpenAI",
+        description: "OpenAI GPT and o-series models",
+        default_api_key_env: Some("OPENAI_API_KEY"),
+        default_base_url: Some("https://api.openai.com/v1"),
+        requires_api_key: true,
+    },
     // ── Gateways ──────────────────────────────────────────────────────────────
     DriverMeta {
-        id: "openrouter",
-        name: "OpenRouter",
-        description: "OpenRouter gateway (200+ models from many providers)",
-        default_api_key_env: Some("OPENROUTER_API_KEY"),
-        default_base_url: Some("https://openrouter.ai/api/v1"),
+        id: "cloudflare",
+        category: DriverCategory::Gateway,
+        name: "Cloudflare AI Gateway",
+        description: "Cloudflare AI Gateway (set base_url to your account-specific URL)",
+        default_api_key_env: Some("CLOUDFLARE_API_TOKEN"),
+        default_base_url: None,
         requires_api_key: true,
     },
     DriverMeta {
         id: "litellm",
+        category: DriverCategory::Gateway,
         name: "LiteLLM",
         description: "LiteLLM proxy gateway (set base_url to your server)",
         default_api_key_env: Some("LITELLM_API_KEY"),
         default_base_url: None,
         requires_api_key: false,
     },
+    DriverMeta {
+        id: "openrouter",
+        category: DriverCategory::Gateway,
+        name: "OpenRouter",
+        description: "OpenRouter gateway (200+ models from many providers)",
+        default_api_key_env: Some("OPENROUTER_API_KEY"),
+        default_base_url: Some("https://openrouter.ai/api/v1"),
+        requires_api_key: true,
+    },
     DriverMeta {
         id: "portkey",
+        category: DriverCategory::Gateway,
         name: "Portkey",
         description: "Portkey AI gateway and observability platform",
         default_api_key_env: Some("PORTKEY_API_KEY"),
@@ -104,48 +188,45 @@ pub static DRIVERS: &[DriverMeta] = &[
     },
     DriverMeta {
         id: "vercel",
+        category: DriverCategory::Gateway,
         name: "Vercel AI Gateway",
         description: "Vercel AI SDK gateway",
         default_api_key_env: Some("VERCEL_API_KEY"),
         default_base_url: Some("https://sdk.vercel.ai/openai"),
         requires_api_key: true,
     },
+    // ── Fast inference platforms ───────────────────────────────────────────────
     DriverMeta {
-        id: "cloudflare",
-        name: "Cloudflare AI Gateway",
-        description: "Cloudflare AI Gateway (set base_url to your account-specific URL)",
-        default_api_key_env: Some("CLOUDFLARE_API_TOKEN"),
-        default_base_url: None,
+        id: "cerebras",
+        category: DriverCategory::FastInference,
+        name: "Cerebras",
+        description: "Cerebras fast inference platform",
+        default_api_key_env: Some("CEREBRAS_API_KEY"),
+        default_base_url: Some("https://api.cerebras.ai/v1"),
         requires_api_key: true,
     },
-    // ── Fast inference platforms ───────────────────────────────────────────────
     DriverMeta {
         id: "groq",
+        category: DriverCategory::FastInference,
         name: "Groq",
         description: "Groq LPU fast inference",
         default_api_key_env: Some("GROQ_API_KEY"),
         default_base_url: Some("https://api.groq.com/openai/v1"),
         requires_api_key: true,
     },
-    DriverMeta {
-        id: "cerebras",
-        name: "Cerebras",
-        description: "Cerebras fast inference platform",
-        default_api_key_env: Some("CEREBRAS_API_KEY"),
-        default_base_url: Some("https://api.cerebras.ai/v1"),
-        requires_api_key: true,
-    },
     // ── Open model platforms ───────────────────────────────────────────────────
     DriverMeta {
-        id: "together",
-        name: "Together AI",
-        description: "Together AI open model hosting platform",
-        default_api_key_env: Some("TOGETHER_API_KEY"),
-        default_base_url: Some("https://api.together.xyz/v1"),
+        id: "deepinfra",
+        category: DriverCategory::OpenModels,
+        name: "DeepInfra",
+        description: "DeepInfra open model hosting",
+        default_api_key_env: Some("DEEPINFRA_API_KEY"),
+        default_base_url: Some("https://api.deepinfra.com/v1/openai"),
         requires_api_key: true,
     },
     DriverMeta {
         id: "fireworks",
+        category: DriverCategory::OpenModels,
         name: "Fireworks AI",
         description: "Fireworks AI fast open model inference",
         default_api_key_env: Some("FIREWORKS_API_KEY"),
@@ -153,23 +234,35 @@ pub static DRIVERS: &[DriverMeta] = &[
         requires_api_key: true,
     },
     DriverMeta {
-        id: "deepinfra",
-        name: "DeepInfra",
-        description: "DeepInfra open model hosting",
-        default_api_key_env: Some("DEEPINFRA_API_KEY"),
-        default_base_url: Some("https://api.deepinfra.com/v1/openai"),
+        id: "huggingface",
+        category: DriverCategory::OpenModels,
+        name: "Hugging Face",
+        description: "Hugging Face Inference Router",
+        default_api_key_env: Some("HF_API_KEY"),
+        default_base_url: Some("https://router.huggingface.co/v1"),
         requires_api_key: true,
     },
     DriverMeta {
         id: "nebius",
+        category: DriverCategory::OpenModels,
         name: "Nebius AI",
         description: "Nebius AI model platform",
         default_api_key_env: Some("NEBIUS_API_KEY"),
         default_base_url: Some("https://api.studio.nebius.ai/v1"),
         requires_api_key: true,
     },
+    DriverMeta {
+        id: "nvidia",
+        category: DriverCategory::OpenModels,
+        name: "NVIDIA NIM",
+        description: "NVIDIA NIM inference platform",
+        default_api_key_env: Some("NVIDIA_API_KEY"),
+        default_base_url: Some("https://integrate.api.nvidia.com/v1"),
+        requires_api_key: true,
+    },
     DriverMeta {
         id: "sambanova",
+        category: DriverCategory::OpenModels,
         name: "SambaNova",
         description: "SambaNova fast inference",
         default_api_key_env: Some("SAMBANOVA_API_KEY"),
@@ -177,40 +270,36 @@ pub static DRIVERS: &[DriverMeta] = &[
         requires_api_key: true,
     },
     DriverMeta {
-        id: "huggingface",
-        name: "Hugging Face",
-        description: "Hugging Face Inference Router",
-        default_api_key_env: Some("HF_API_KEY"),
-        default_base_url: Some("https://router.huggingface.co/v1"),
+        id: "together",
+        category: DriverCategory::OpenModels,
+        name: "Together AI",
+        description: "Together AI open model hosting platform",
+        default_api_key_env: Some("TOGETHER_API_KEY"),
+        default_base_url: Some("https://api.together.xyz/v1"),
         requires_api_key: true,
     },
+    // ── Specialized ───────────────────────────────────────────────────────────
     DriverMeta {
-        id: "nvidia",
-        name: "NVIDIA NIM",
-        description: "NVIDIA NIM inference platform",
-        default_api_key_env: Some("NVIDIA_API_KEY"),
-        default_base_url: Some("https://integrate.api.nvidia.com/v1"),
+        id: "mistral",
+        category: DriverCategory::Specialized,
+        name: "Mistral AI",
+        description: "Mistral AI models including Codestral",
+        default_api_key_env: Some("MISTRAL_API_KEY"),
+        default_base_url: Some("https://api.mistral.ai/v1"),
         requires_api_key: true,
     },
-    // ── Specialized ───────────────────────────────────────────────────────────
     DriverMeta {
         id: "perplexity",
+        category: DriverCategory::Specialized,
         name: "Perplexity",
         description: "Perplexity AI online search and reasoning models",
         default_api_key_env: Some("PERPLEXITY_API_KEY"),
         default_base_url: Some("https://api.perplexity.ai"),
         requires_api_key: true,
     },
-    DriverMeta {
-        id: "mistral",
-        name: "Mistral AI",
-        description: "Mistral AI models including Codestral",
-        default_api_key_env: Some("MISTRAL_API_KEY"),
-        default_base_url: Some("https://api.mistral.ai/v1"),
-        requires_api_key: true,
-    },
     DriverMeta {
         id: "xai",
+        category: DriverCategory::Specialized,
         name: "xAI",
         description: "xAI Grok models",
         default_api_key_env: Some("XAI_API_KEY"),
@@ -218,32 +307,27 @@ pub static DRIVERS: &[DriverMeta] = &[
         requires_api_key: true,
     },
     // ── Regional providers ────────────────────────────────────────────────────
-    DriverMeta {
-        id: "deepseek",
-        name: "DeepSeek",
-        description: "DeepSeek reasoning and coder models",
-        default_api_key_env: Some("DEEPSEEK_API_KEY"),
-        default_base_url: Some("https://api.deepseek.com/v1"),
-        requires_api_key: true,
-    },
-    DriverMeta {
-        id: "moonshot",
-        name: "Moonshot AI",
-        description: "Moonshot AI Kimi models",
-        default_api_key_env: Some("MOONSHOT_API_KEY"),
-        default_base_url: Some("https://api.moonshot.cn/v1"),
-        requires_api_key: true,
-    },
     DriverMeta {
         id: "dashscope",
+        category: DriverCategory::Regional,
         name: "Qwen/DashScope",
         description: "Alibaba Qwen models via DashScope compatible API",
         default_api_key_env: Some("DASHSCOPE_API_KEY"),
         default_base_url: Some("https://dashscope.aliyuncs.com/compatible-mode/v1"),
         requires_api_key: true,
     },
+    DriverMeta {
+        id: "deepseek",
+        category: DriverCategory::Regional,
+        name: "DeepSeek",
+        description: "DeepSeek reasoning and coder models",
+        default_api_key_env: Some("DEEPSEEK_API_KEY"),
+        default_base_url: Some("https://api.deepseek.com/v1"),
+        requires_api_key: true,
+    },
     DriverMeta {
         id: "glm",
+        category: DriverCategory::Regional,
         name: "GLM/Z.AI",
         description: "Zhipu AI GLM models",
         default_api_key_env: Some("GLM_API_KEY"),
@@ -252,14 +336,25 @@ pub static DRIVERS: &[DriverMeta] = &[
     },
     DriverMeta {
         id: "minimax",
+        category: DriverCategory::Regional,
         name: "MiniMax",
         description: "MiniMax AI models",
         default_api_key_env: Some("MINIMAX_API_KEY"),
         default_base_url: Some("https://api.minimax.chat/v1"),
         requires_api_key: true,
     },
+    DriverMeta {
+        id: "moonshot",
+        category: DriverCategory::Regional,
+        name: "Moonshot AI",
+        description: "Moonshot AI Kimi models",
+        default_api_key_env: Some("MOONSHOT_API_KEY"),
+        default_base_url: Some("https://api.moonshot.cn/v1"),
+        requires_api_key: true,
+    },
     DriverMeta {
         id: "qianfan",
+        category: DriverCategory::Regional,
         name: "Baidu Qianfan",
         description: "Baidu Qianfan LLM platform",
         default_api_key_env: Some("QIANFAN_API_KEY"),
@@ -267,8 +362,18 @@ pub static DRIVERS: &[DriverMeta] = &[
         requires_api_key: true,
     },
     // ── Local / OSS ───────────────────────────────────────────────────────────
+    DriverMeta {
+        id: "lmstudio",
+        category: DriverCategory::Local,
+        name: "LM Studio",
+        description: "LM Studio local model server (http://localhost:1234)",
+        default_api_key_env: None,
+        default_base_url: Some("http://localhost:1234/v1"),
+        requires_api_key: false,
+    },
     DriverMeta {
         id: "ollama",
+        category: DriverCategory::Local,
         name: "Ollama",
         description: "Ollama local model runner (http://localhost:11434)",
         default_api_key_env: None,
@@ -277,23 +382,17 @@ pub static DRIVERS: &[DriverMeta] = &[
     },
     DriverMeta {
         id: "vllm",
+        category: DriverCategory::Local,
         name: "vLLM",
         description: "vLLM local inference server (http://localhost:8000)",
         default_api_key_env: None,
         default_base_url: Some("http://localhost:8000/v1"),
         requires_api_key: false,
     },
-    DriverMeta {
-        id: "lmstudio",
-        name: "LM Studio",
-        description: "LM Studio local model server (http://localhost:1234)",
-        default_api_key_env: None,
-        default_base_url: Some("http://localhost:1234/v1"),
-        requires_api_key: false,
-    },
     // ── Testing ───────────────────────────────────────────────────────────────
     DriverMeta {
         id: "mock",
+        category: DriverCategory::Testing,
         name: "Mock",
         description: "Mock driver for tests (no network, echoes input)",
         default_api_key_env: None,
@@ -302,7 +401,10 @@ pub static DRIVERS: &[DriverMeta] = &[
     },
 ];
 
-/// Returns all registered drivers in declaration order.
+/// Returns all registered drivers: grouped by category in
+/// [`DriverCategory::ALL`] order, sorted by id within each category.  The
+/// order is stable, so `sven list-providers` output can be compared across
+/// runs.
 pub fn list_drivers() -> &'static [DriverMeta] {
     DRIVERS
 }
@@ -312,11 +414,154 @@ pub fn get_driver(id: &str) -> Option<&'static DriverMeta> {
     DRIVERS.iter().find(|d| d.id == id)
 }
 
-/// Returns an iterator over all known driver ids.
+/// Returns an iterator over all known driver ids, in [`list_drivers`] order.
 pub fn known_driver_ids() -> impl Iterator<Item = &'static str> {
     DRIVERS.iter().map(|d| d.id)
 }
 
+/// Narrows [`list_drivers`] for `sven list-providers --filter/--category`.
+#[derive(Debug, Clone, Default)]
+pub struct DriverFilter {
+    /// Case-insensitive substring of the id, name, description or category.
+    pub term: Option<String>,
+    /// Only drivers in this category.
+    pub category: Option<DriverCategory>,
+}
+
+impl DriverFilter {
+    /// Whether `driver` passes every set criterion.
+    pub fn matches(&self, driver: &DriverMeta) -> bool {
+        if self.category.is_some_and(|c| c != driver.category) {
+            return false;
+        }
+        let Some(term) = self.term.as_deref() else {
+            return true;
+        };
+        let term = term.to_lowercase();
+        [
+            driver.id,
+            driver.name,
+            driver.description,
+            driver.category.id(),
+            driver.category.label(),
+        ]
+        .iter()
+        .any(|field| field.to_lowercase().contains(&term))
+    }
+}
+
+/// The drivers `filter` matches, in [`list_drivers`] order.
+pub fn filter_drivers(filter: &DriverFilter) -> Vec<&'static DriverMeta> {
+    DRIVERS.iter().filter(|d| filter.matches(d)).collect()
+}
+
+/// Render `drivers` for `sven list-providers`, grouped under a heading per
+/// category that names the category's `--category` id.  `verbose` adds each
+/// driver's API key variable, default URL and auth requirement below it;
+/// otherwise each driver is one table row.
+pub fn render_driver_list(drivers: &[&DriverMeta], verbose: bool) -> String {
+    use std::fmt::Write as _;
+
+    let id_w = drivers
+        .iter()
+        .map(|d| d.id.len())
+        .max()
+        .unwrap_or(0)
+        .max(10);
+    let name_w = drivers
+        .iter()
+        .map(|d| d.name.len())
+        .max()
+        .unwrap_or(0)
+        .max(8);
+    let mut out = String::new();
+    if !verbose {
+        let _ = writeln!(out, "  {:<id_w$}  {:<name_w$}  DESCRIPTION", "ID", "NAME");
+    }
+    for category in DriverCategory::ALL {
+        let mut group = drivers.iter().filter(|d| d.category == category).peekable();
+        if group.peek().is_none() {
+            continue;
+        }
+        let _ = writeln!(out, "\n{} ({})", category.label(), category.id());
+        for d in group {
+            if !verbose {
+                let _ = writeln!(
+                    out,
+                    "  {:<id_w$}  {:<name_w$}  {}",
+                    d.id, d.name, d.description
+                );
+                continue;
+            }
+            let _ = writeln!(out, "  {} — {}", d.id, d.name);
+            let _ = writeln!(out, "    {}", d.description);
+            if let Some(env) = d.default_api_key_env {
+                let _ = writeln!(out, "    API key env : {env}");
+            }
+            if let Some(url) = d.default_base_url {
+                let _ = writeln!(out, "    Default URL : {url}");
+            }
+            if !d.requires_api_key {
+                let _ = writeln!(out, "    Auth        : none required");
+            }
+        }
+    }
+    out
+}
+
+/// Drivers that have no usable default endpoint: [`crate::from_config`]
+/// fails unless `base_url` is set.  Azure and AWS are not listed because they
+/// derive the endpoint from `azure_resource` and the AWS region instead.
+fn requires_base_url(id: &str) -> bool {
+    matches!(id, "litellm" | "cloudflare")
+}
+
+/// A named `providers` entry that cannot be turned into a model driver.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderIssue {
+    /// Key of the entry under `providers`.
+    pub alias: String,
+    /// What is wrong with it.
+    pub reason: String,
+}
+
+impl std::fmt::Display for ProviderIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "providers.{}: {}", self.alias, self.reason)
+    }
+}
+
+/// Check every named entry in `config.providers` against the registry.
+///
+/// Reports entries whose driver id is unknown and entries that lack a field
+/// their driver cannot work without.  Issues are sorted by alias; an empty
+/// vector means every entry is usable.
+pub fn check_providers(config: &sven_config::Config) -> Vec<ProviderIssue> {
+    let mut entries: Vec<_> = config.providers.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut issues = Vec::new();
+    for (alias, entry) in entries {
+        let reason = if get_driver(&entry.name).is_none() {
+            format!(
+                "unknown provider {:?} (run `sven list-providers` for the full list)",
+                entry.name
+            )
+        } else if requires_base_url(&entry.name)
+            && !matches!(entry.base_url.as_deref(), Some(url) if !url.trim().is_empty())
+        {
+            format!("provider {:?} requires base_url", entry.name)
+        } else {
+            continue;
+        };
+        issues.push(ProviderIssue {
+            alias: alias.clone(),
+            reason,
+        });
+    }
+    issues
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -334,6 +579,121 @@ mod tests {
         }
     }
 
+    #[test]
+    fn drivers_are_grouped_by_category_then_sorted_by_id() {
+        let categories: Vec<DriverCategory> = DRIVERS.iter().map(|d| d.category).collect();
+        let mut groups = categories.clone();
+        groups.dedup();
+        assert_eq!(groups, DriverCategory::ALL, "categories out of order");
+
+        for pair in list_drivers().windows(2) {
+            let (a, b) = (&pair[0], &pair[1]);
+            assert!(
+                (a.category, a.id) < (b.category, b.id),
+                "{:?}/{} must come before {:?}/{}",
+                a.category,
+                a.id,
+                b.category,
+                b.id
+            );
+        }
+        let ids: Vec<&str> = known_driver_ids().collect();
+        let listed: Vec<&str> = list_drivers().iter().map(|d| d.id).collect();
+        assert_eq!(ids, listed);
+    }
+
+    #[test]
+    fn every_category_has_a_driver() {
+        for category in DriverCategory::ALL {
+            assert!(
+                DRIVERS.iter().any(|d| d.category == category),
+                "no driver in {category:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn driver_list_is_grouped_under_category_headers() {
+        for verbose in [false, true] {
+            let text = render_driver_list(&filter_drivers(&DriverFilter::default()), verbose);
+            let headers: Vec<usize> = DriverCategory::ALL
+                .iter()
+                .map(|c| {
+                    text.find(&format!("\n{} ({})\n", c.label(), c.id()))
+                        .unwrap_or_else(|| panic!("missing header {:?}", c.label()))
+                })
+                .collect();
+            assert!(headers.windows(2).all(|w| w[0] < w[1]), "{text}");
+
+            // Each driver appears after its own header and before the next.
+            for d in DRIVERS {
+                let at = text.find(&format!("  {} ", d.id)).expect(d.id);
+                let idx = DriverCategory::ALL
+                    .iter()
+                    .position(|c| *c == d.category)
+                    .unwrap();
+                assert!(at > headers[idx], "{} above its header", d.id);
+                if let Some(next) = headers.get(idx + 1) {
+                    assert!(at < *next, "{} below the next header", d.id);
+                }
+            }
+        }
+        let all = filter_drivers(&DriverFilter::default());
+        assert_eq!(all.len(), DRIVERS.len());
+        assert!(render_driver_list(&all, true).contains("    API key env : OPENAI_API_KEY"));
+    }
+
+    #[test]
+    fn filter_by_category_keeps_only_that_category() {
+        let category = DriverCategory::from_id("Local").expect("local is a category");
+        let filter = DriverFilter {
+            category: Some(category),
+            ..DriverFilter::default()
+        };
+        let ids: Vec<&str> = filter_drivers(&filter).iter().map(|d| d.id).collect();
+        assert_eq!(ids, ["lmstudio", "ollama", "vllm"]);
+
+        let text = render_driver_list(&filter_drivers(&filter), false);
+        assert!(text.contains("\nLocal / OSS (local)\n"), "{text}");
+        assert!(!text.contains("Gateways"), "{text}");
+        assert!(!text.contains("openai"), "{text}");
+        assert_eq!(DriverCategory::from_id("nope"), None);
+
+        // The id shown in each heading and in JSON is the one accepted back.
+        for c in DriverCategory::ALL {
+            assert_eq!(DriverCategory::from_id(c.id()), Some(c));
+        }
+    }
+
+    #[test]
+    fn filter_by_term_matches_any_field_case_insensitively() {
+        let by = |term: &str| -> Vec<&str> {
+            let filter = DriverFilter {
+                term: Some(term.into()),
+                ..DriverFilter::default()
+            };
+            filter_drivers(&filter).iter().map(|d| d.id).collect()
+        };
+        // Id and name.
+        assert_eq!(by("GROQ"), ["groq"]);
+        // Description.
+        assert!(by("bedrock").contains(&"aws"));
+        // Category label: every gateway, nothing else.
+        let gateways: Vec<&str> = DRIVERS
+            .iter()
+            .filter(|d| d.category == DriverCategory::Gateway)
+            .map(|d| d.id)
+            .collect();
+        assert_eq!(by("gateways"), gateways);
+        assert!(by("no-such-provider-anywhere").is_empty());
+
+        let both = DriverFilter {
+            term: Some("local".into()),
+            category: Some(DriverCategory::Testing),
+        };
+        assert!(filter_drivers(&both).is_empty());
+    }
+
     #[test]
     fn get_driver_returns_correct_entry() {
         let d = get_driver("openai").expect("openai must be registered");
@@ -363,4 +723,61 @@ mod tests {
             );
         }
     }
+
+    fn config_with(alias: &str, entry: sven_config::ProviderEntry) -> sven_config::Config {
+        let mut config = sven_config::Config::default();
+        config.providers.insert(alias.into(), entry);
+        config
+    }
+
+    #[test]
+    fn check_providers_reports_unknown_driver_id() {
+        let config = config_with(
+            "work",
+            sven_config::ProviderEntry {
+                name: "opena".into(),
+                ..sven_config::ProviderEntry::default()
+            },
+        );
+        let issues = check_providers(&config);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].alias, "work");
+        let msg = issues[0].to_string();
+        assert!(
+            msg.starts_with("providers.work: unknown provider \"opena\""),
+            "{msg}"
+        );
+    }
+
+    #[test]
+    fn check_providers_requires_base_url_for_litellm() {
+        let mut config = config_with(
+            "proxy",
+            sven_config::ProviderEntry {
+                name: "litellm".into(),
+                ..sven_config::ProviderEntry::default()
+            },
+        );
+        let issues = check_providers(&config);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            issues[0].to_string(),
+            "providers.proxy: provider \"litellm\" requires base_url"
+        );
+
+        config.providers.get_mut("proxy").unwrap().base_url = Some("http://localhost:4000".into());
+        assert!(check_providers(&config).is_empty());
+    }
+
+    #[test]
+    fn check_providers_accepts_drivers_with_default_endpoints() {
+        let config = config_with(
+            "local",
+            sven_config::ProviderEntry {
+                name: "ollama".into(),
+                ..sven_config::ProviderEntry::default()
+            },
+        );
+        assert!(check_providers(&config).is_empty());
+    }
 }
@@ -1,19 +1,33 @@
 // Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
 //
 // SPDX-License-Identifier: Apache-2.0
-//! Message sanitization: strip image content when the model does not support it.
+//! Message sanitization: strip image content when the model does not support
+//! it, and reshape conversations for saving and resuming.
 //!
 //! Call [`strip_images_if_unsupported`] before building a [`CompletionRequest`]
 //! to ensure that image parts are replaced with a text placeholder whenever the
 //! target model only supports text input.
+//!
+//! Call [`for_persistence`] before writing messages to disk so a conversation
+//! reloaded into a different provider carries none of the previous one's
+//! injected artifacts, and [`for_resume`] on a loaded conversation so its
+//! tool calls and turns are shaped the way every driver expects.
 
 use crate::{
     catalog::InputModality,
-    types::{ContentPart, Message, MessageContent, ToolContentPart, ToolResultContent},
+    types::{ContentPart, Message, MessageContent, Role, ToolContentPart, ToolResultContent},
 };
 
 const IMAGE_OMITTED: &str = "[image omitted: model does not support image input]";
 
+/// Result recorded for a resumed tool call that never got one.
+const MISSING_TOOL_RESULT: &str = "[no result: the tool call did not complete]";
+
+/// Wrapper tags of the reminder blocks some providers and gateways inject
+/// into user turns and tool results (and models occasionally echo back).
+const SYSTEM_REMINDER_OPEN: &str = "<system-reminder>";
+const SYSTEM_REMINDER_CLOSE: &str = "</system-reminder>";
+
 /// Replace all image content in `messages` with a text placeholder when
 /// `modalities` does not include [`InputModality::Image`].
 ///
@@ -91,6 +105,277 @@ fn strip_tool_result_content(content: ToolResultContent) -> ToolResultContent {
     }
 }
 
+// ─── Persistence ──────────────────────────────────────────────────────────────
+
+/// Remove provider-injected content from `messages` before they are saved.
+///
+/// `<system-reminder>` blocks are cut out of user, assistant and tool-result
+/// text.  A user or assistant message left empty by this is dropped; tool
+/// calls and tool results are always kept so their ids stay paired.  System
+/// messages and everything else the user or model wrote are unchanged.
+pub fn for_persistence(messages: Vec<Message>) -> Vec<Message> {
+    messages
+        .into_iter()
+        .filter_map(message_for_persistence)
+        .collect()
+}
+
+/// Single-message form of [`for_persistence`], for savers that write one
+/// record at a time.  `None` means the message held only injected content
+/// and should not be written.
+pub fn message_for_persistence(mut m: Message) -> Option<Message> {
+    if m.role == Role::System {
+        return Some(m);
+    }
+    m.content = match m.content {
+        MessageContent::Text(text) => {
+            let stripped = strip_system_reminders(&text);
+            if stripped.is_empty() && !text.is_empty() {
+                return None;
+            }
+            MessageContent::Text(stripped)
+        }
+        MessageContent::ContentParts(parts) => {
+            let had_parts = !parts.is_empty();
+            let kept: Vec<ContentPart> = parts
+                .into_iter()
+                .filter_map(|p| match p {
+                    ContentPart::Text { text } => {
+                        let stripped = strip_system_reminders(&text);
+                        // Drop parts that held nothing but a reminder.
+                        if stripped.is_empty() && !text.is_empty() {
+                            None
+                        } else {
+                            Some(ContentPart::Text { text: stripped })
+                        }
+                    }
+                    image => Some(image),
+                })
+                .collect();
+            if kept.is_empty() && had_parts {
+                return None;
+            }
+            MessageContent::ContentParts(kept)
+        }
+        MessageContent::ToolResult {
+            tool_call_id,
+            content,
+        } => MessageContent::ToolResult {
+            tool_call_id,
+            content: match content {
+                ToolResultContent::Text(text) => {
+                    ToolResultContent::Text(strip_system_reminders(&text))
+                }
+                ToolResultContent::Parts(parts) => ToolResultContent::Parts(
+                    parts
+                        .into_iter()
+                        .map(|p| match p {
+                            ToolContentPart::Text { text } => ToolContentPart::Text {
+                                text: strip_system_reminders(&text),
+                            },
+                            image => image,
+                        })
+                        .collect(),
+                ),
+            },
+        },
+        tool_call @ MessageContent::ToolCall { .. } => tool_call,
+    };
+    Some(m)
+}
+
+/// Cut every complete `<system-reminder>…</system-reminder>` block out of
+/// `text`.  Text without a block is returned unchanged; otherwise the result
+/// is trimmed of the whitespace the blocks leave behind.
+fn strip_system_reminders(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    let mut found = false;
+    while let Some(start) = rest.find(SYSTEM_REMINDER_OPEN) {
+        let Some(len) = rest[start..].find(SYSTEM_REMINDER_CLOSE) else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        rest = &rest[start + len + SYSTEM_REMINDER_CLOSE.len()..];
+        found = true;
+    }
+    if !found {
+        return text.to_string();
+    }
+    out.push_str(rest);
+    out.trim().to_string()
+}
+
+// ─── Resume ───────────────────────────────────────────────────────────────────
+
+/// Reshape a loaded conversation so that any driver accepts it, whichever
+/// provider it was recorded with.
+///
+/// - Tool calls are sent by the assistant and tool results by the tool role;
+///   anything else in the tool role becomes a user message.
+/// - Each batch of tool calls is directly followed by one result per call:
+///   results are put in call order, a call without a result gets a
+///   placeholder, and a result without a call becomes a user message.
+/// - Consecutive user (or assistant) messages are merged and empty ones are
+///   dropped.
+///
+/// System messages are left where they are.
+pub fn for_resume(messages: Vec<Message>) -> Vec<Message> {
+    let mut out: Vec<Message> = Vec::with_capacity(messages.len());
+    let mut iter = messages.into_iter().peekable();
+    while let Some(m) = iter.next() {
+        if is_tool_message(&m) {
+            let mut batch = vec![m];
+            while let Some(next) = iter.next_if(|next| continues_batch(&batch, next)) {
+                batch.push(next);
+            }
+            for m in resolve_tool_batch(batch) {
+                push_merged(&mut out, m);
+            }
+        } else if m.role == Role::Tool {
+            push_merged(
+                &mut out,
+                Message {
+                    role: Role::User,
+                    ..m
+                },
+            );
+        } else {
+            push_merged(&mut out, m);
+        }
+    }
+    out
+}
+
+fn is_tool_message(m: &Message) -> bool {
+    matches!(
+        m.content,
+        MessageContent::ToolCall { .. } | MessageContent::ToolResult { .. }
+    )
+}
+
+/// Whether `next` belongs to the tool batch collected so far.  A batch is a
+/// run of calls followed by their results, so a call after a result starts
+/// the next one.
+fn continues_batch(batch: &[Message], next: &Message) -> bool {
+    match next.content {
+        MessageContent::ToolResult { .. } => true,
+        MessageContent::ToolCall { .. } => !batch
+            .iter()
+            .any(|m| matches!(m.content, MessageContent::ToolResult { .. })),
+        _ => false,
+    }
+}
+
+/// Order one run of tool messages as calls, then their results in call
+/// order, then results that answer none of the calls (as user messages).
+fn resolve_tool_batch(batch: Vec<Message>) -> Vec<Message> {
+    let mut calls = Vec::new();
+    let mut results: Vec<Option<(String, ToolResultContent)>> = Vec::new();
+    for m in batch {
+        match m.content {
+            MessageContent::ToolResult {
+                tool_call_id,
+                content,
+            } => results.push(Some((tool_call_id, content))),
+            content => calls.push(Message {
+                role: Role::Assistant,
+                content,
+            }),
+        }
+    }
+
+    let answers: Vec<Message> = calls
+        .iter()
+        .filter_map(|call| match &call.content {
+            MessageContent::ToolCall { tool_call_id, .. } => Some(tool_call_id.clone()),
+            _ => None,
+        })
+        .map(|tool_call_id| {
+            let content = results
+                .iter_mut()
+                .find(|r| r.as_ref().is_some_and(|(id, _)| *id == tool_call_id))
+                .and_then(Option::take)
+                .map_or_else(
+                    || ToolResultContent::Text(MISSING_TOOL_RESULT.into()),
+                    |(_, content)| content,
+                );
+            Message {
+                role: Role::Tool,
+                content: MessageContent::ToolResult {
+                    tool_call_id,
+                    content,
+                },
+            }
+        })
+        .collect();
+    let orphans = results
+        .into_iter()
+        .flatten()
+        .map(|(tool_call_id, content)| orphan_result_to_user(&tool_call_id, content));
+    calls.into_iter().chain(answers).chain(orphans).collect()
+}
+
+/// A tool result whose call is not in the conversation, as a user message.
+fn orphan_result_to_user(tool_call_id: &str, content: ToolResultContent) -> Message {
+    let header = format!("Tool result ({tool_call_id}):");
+    match content {
+        ToolResultContent::Text(text) => Message::user(format!("{header}\n{text}")),
+        ToolResultContent::Parts(parts) => Message::user_with_parts(
+            std::iter::once(ContentPart::text(header))
+                .chain(parts.into_iter().map(|p| match p {
+                    ToolContentPart::Text { text } => ContentPart::Text { text },
+                    ToolContentPart::Image { image_url } => ContentPart::image(image_url),
+                }))
+                .collect(),
+        ),
+    }
+}
+
+/// Append `m` to `out`, dropping it when it is an empty user or assistant
+/// message and merging it into the previous message when both are user (or
+/// both assistant) text.
+fn push_merged(out: &mut Vec<Message>, m: Message) {
+    if !matches!(m.role, Role::User | Role::Assistant) || !is_text_or_parts(&m.content) {
+        out.push(m);
+        return;
+    }
+    match &m.content {
+        MessageContent::Text(t) if t.trim().is_empty() => return,
+        MessageContent::ContentParts(parts) if parts.is_empty() => return,
+        _ => {}
+    }
+    match out.last_mut() {
+        Some(prev) if prev.role == m.role && is_text_or_parts(&prev.content) => {
+            let earlier = std::mem::replace(&mut prev.content, MessageContent::Text(String::new()));
+            prev.content = match (earlier, m.content) {
+                (MessageContent::Text(a), MessageContent::Text(b)) => {
+                    MessageContent::Text(format!("{a}\n\n{b}"))
+                }
+                (a, b) => MessageContent::ContentParts(
+                    into_parts(a).into_iter().chain(into_parts(b)).collect(),
+                ),
+            };
+        }
+        _ => out.push(m),
+    }
+}
+
+fn is_text_or_parts(content: &MessageContent) -> bool {
+    matches!(
+        content,
+        MessageContent::Text(_) | MessageContent::ContentParts(_)
+    )
+}
+
+fn into_parts(content: MessageContent) -> Vec<ContentPart> {
+    match content {
+        MessageContent::Text(text) => vec![ContentPart::Text { text }],
+        MessageContent::ContentParts(parts) => parts,
+        _ => Vec::new(),
+    }
+}
+
 // ─── Tests ────────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -190,6 +475,218 @@ mod tests {
         }
     }
 
+    fn tool_call(id: &str) -> Message {
+        Message {
+            role: Role::Assistant,
+            content: MessageContent::ToolCall {
+                tool_call_id: id.into(),
+                function: crate::FunctionCall {
+                    name: "read_file".into(),
+                    arguments: "{}".into(),
+                },
+            },
+        }
+    }
+
+    /// A tool result in the user role, the way Anthropic carries them.
+    fn user_tool_result(id: &str, text: &str) -> Message {
+        Message {
+            role: Role::User,
+            ..Message::tool_result(id, text)
+        }
+    }
+
+    /// The first breach of the rules OpenAI enforces: every assistant
+    /// `tool_calls` message is followed by one `tool` message per call,
+    /// `tool` messages appear nowhere else, and user and assistant text turns
+    /// alternate.
+    fn openai_violation(wire: &[serde_json::Value]) -> Option<String> {
+        let mut prev = "";
+        let mut i = 0;
+        while i < wire.len() {
+            let role = wire[i]["role"].as_str().unwrap_or_default();
+            if role == "tool" {
+                return Some(format!("tool message without a call at {i}"));
+            }
+            if let Some(calls) = wire[i]["tool_calls"].as_array() {
+                for call in calls {
+                    i += 1;
+                    if wire.get(i).map(|m| &m["tool_call_id"]) != Some(&call["id"]) {
+                        return Some(format!("call {} is not answered at {i}", call["id"]));
+                    }
+                }
+                prev = "tool";
+            } else if role == prev {
+                return Some(format!("consecutive {role} messages at {i}"));
+            } else {
+                prev = role;
+            }
+            i += 1;
+        }
+        None
+    }
+
+    #[test]
+    fn for_resume_reshapes_anthropic_history_for_openai() {
+        let history = vec![
+            Message::system("You are sven."),
+            Message::user("Find the bug"),
+            Message::user("in src/main.rs"),
+            Message::assistant("Looking."),
+            tool_call("toolu_a"),
+            tool_call("toolu_b"),
+            user_tool_result("toolu_b", "tests"),
+            user_tool_result("toolu_a", "fn main() {}"),
+            Message::user("also check the tests"),
+            Message::assistant(""),
+            Message::assistant("Found it."),
+            tool_call("toolu_c"),
+            user_tool_result("toolu_gone", "stale output"),
+            Message::user("continue"),
+        ];
+        let wire = crate::openai_compat::build_openai_messages(&history);
+        assert!(openai_violation(&wire).is_some());
+
+        let resumed = for_resume(history);
+        let wire = crate::openai_compat::build_openai_messages(&resumed);
+        assert_eq!(openai_violation(&wire), None, "{wire:#?}");
+
+        assert_eq!(resumed[0].role, Role::System);
+        assert_eq!(resumed[1].as_text(), Some("Find the bug\n\nin src/main.rs"));
+        let ids: Vec<(&Role, &str)> = resumed
+            .iter()
+            .filter_map(|m| match &m.content {
+                MessageContent::ToolCall { tool_call_id, .. }
+                | MessageContent::ToolResult { tool_call_id, .. } => {
+                    Some((&m.role, tool_call_id.as_str()))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            ids,
+            [
+                (&Role::Assistant, "toolu_a"),
+                (&Role::Assistant, "toolu_b"),
+                (&Role::Tool, "toolu_a"),
+                (&Role::Tool, "toolu_b"),
+                (&Role::Assistant, "toolu_c"),
+                (&Role::Tool, "toolu_c"),
+            ]
+        );
+        let missing = resumed.iter().find_map(|m| match &m.content {
+            MessageContent::ToolResult {
+                tool_call_id,
+                content,
+            } if tool_call_id == "toolu_c" => content.as_text(),
+            _ => None,
+        });
+        assert_eq!(missing, Some(MISSING_TOOL_RESULT));
+        let last = resumed.last().unwrap();
+        assert_eq!(last.role, Role::User);
+        assert_eq!(
+            last.as_text(),
+            Some("Tool result (toolu_gone):\nstale output\n\ncontinue")
+        );
+    }
+
+    #[test]
+    fn for_resume_keeps_well_formed_history_unchanged() {
+        let history = vec![
+            Message::system("You are sven."),
+            Message::user("hi"),
+            tool_call("call_1"),
+            Message::tool_result("call_1", "ok"),
+            tool_call("call_2"),
+            tool_call("call_3"),
+            Message::tool_result("call_2", "ok"),
+            Message::tool_result("call_3", "ok"),
+            Message::assistant("done"),
+        ];
+        let before = serde_json::to_value(&history).unwrap();
+        assert_eq!(serde_json::to_value(for_resume(history)).unwrap(), before);
+    }
+
+    #[test]
+    fn for_persistence_strips_injected_reminders_and_keeps_content() {
+        let reminder = "<system-reminder>\nThe date is 2026-01-01.\n</system-reminder>";
+        let msgs = vec![
+            Message::system(format!("You are sven. {reminder}")),
+            Message::user(format!("{reminder}\nfix the bug")),
+            Message::user(reminder),
+            Message::assistant(format!("Done.\n{reminder}")),
+            Message::tool_result("call-1", format!("ok {reminder}")),
+            Message::tool_result_with_parts(
+                "call-2",
+                vec![
+                    ToolContentPart::Text {
+                        text: reminder.into(),
+                    },
+                    ToolContentPart::Image {
+                        image_url: "data:image/png;base64,XYZ".into(),
+                    },
+                ],
+            ),
+            Message::user_with_parts(vec![
+                ContentPart::text(reminder),
+                ContentPart::image("data:image/png;base64,ABC"),
+            ]),
+        ];
+        let result = for_persistence(msgs);
+
+        assert_eq!(result.len(), 6, "the reminder-only user turn is dropped");
+        assert!(
+            result[0].as_text().unwrap().contains(reminder),
+            "system kept"
+        );
+        assert_eq!(result[1].as_text(), Some("fix the bug"));
+        assert_eq!(result[2].as_text(), Some("Done."));
+        match &result[3].content {
+            MessageContent::ToolResult {
+                tool_call_id,
+                content,
+            } => {
+                assert_eq!(tool_call_id, "call-1");
+                assert_eq!(content.as_text(), Some("ok"));
+            }
+            other => panic!("expected ToolResult, got {other:?}"),
+        }
+        match &result[4].content {
+            MessageContent::ToolResult {
+                content: ToolResultContent::Parts(parts),
+                ..
+            } => {
+                assert!(matches!(&parts[0], ToolContentPart::Text { text } if text.is_empty()));
+                assert!(matches!(&parts[1], ToolContentPart::Image { .. }));
+            }
+            other => panic!("expected ToolResult, got {other:?}"),
+        }
+        match &result[5].content {
+            MessageContent::ContentParts(parts) => {
+                assert_eq!(parts.len(), 1);
+                assert!(matches!(&parts[0], ContentPart::Image { .. }));
+            }
+            other => panic!("expected ContentParts, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn for_persistence_leaves_ordinary_messages_alone() {
+        let msgs = vec![
+            Message::user("  keep my <b>markup</b> and spacing  "),
+            Message::assistant("an unterminated <system-reminder> stays"),
+        ];
+        let result = for_persistence(msgs);
+        assert_eq!(
+            result[0].as_text(),
+            Some("  keep my <b>markup</b> and spacing  ")
+        );
+        assert_eq!(
+            result[1].as_text(),
+            Some("an unterminated <system-reminder> stays")
+        );
+    }
+
     #[test]
     fn plain_text_messages_pass_through_unchanged() {
         let msgs = vec![Message::user("hello"), Message::assistant("world")];
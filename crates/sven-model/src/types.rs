@@ -302,10 +302,16 @@ impl Message {
                     .sum(),
             },
         };
-        (chars / 4).max(1)
+        approx_tokens_for_len(chars).max(1)
     }
 }
 
+/// Approximate token count of `bytes` bytes of text: the 4-chars-per-token
+/// heuristic behind [`Message::approx_tokens`].
+pub fn approx_tokens_for_len(bytes: usize) -> usize {
+    bytes / 4
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Role {
@@ -431,6 +437,9 @@ pub enum ResponseEvent {
         cache_write_tokens: u32,
         /// Cost in USD when reported by the API (e.g. OpenRouter `usage.cost`).
         cost_usd: Option<f64>,
+        /// `true` when the provider reported no usage and the driver filled
+        /// in a local estimate instead.
+        estimated: bool,
     },
     /// The stream finished normally
     Done,
@@ -235,6 +235,7 @@ fn text_events(text: &str, thinking: Option<&str>) -> Vec<anyhow::Result<Respons
         cache_read_tokens: 0,
         cache_write_tokens: 0,
         cost_usd: None,
+        estimated: false,
     }));
     events.push(Ok(ResponseEvent::Done));
     events
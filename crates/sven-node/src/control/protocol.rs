@@ -29,6 +29,17 @@
 //!    │   (accepted — state is Completed) │
 //! ```
 //!
+//! A running session can be suspended with `Pause` and continued with
+//! `Resume`.  The agent stops at the next safe point (after the in-flight
+//! tool completes), emits `Paused`, and keeps its history until resumed:
+//!
+//! ```text
+//!    │── Pause {session_id} ─────────────►│  SessionState::Paused broadcast
+//!    │◄─ Paused {session_id} ─────────────│  (agent reached a safe point)
+//!    │── Resume {session_id} ────────────►│  SessionState::Running broadcast
+//!    │◄─ Resumed {session_id} ────────────│
+//! ```
+//!
 //! # CBOR codec example
 //!
 //! ```rust
@@ -69,6 +80,13 @@ pub enum ControlCommand {
     /// Cancel a running session gracefully.
     CancelSession { session_id: Uuid },
 
+    /// Suspend a running session at the next safe point (after the current
+    /// tool completes).  The agent keeps its history while paused.
+    Pause { session_id: Uuid },
+
+    /// Continue a session previously suspended with [`ControlCommand::Pause`].
+    Resume { session_id: Uuid },
+
     /// Approve a tool call that is waiting for operator confirmation.
     ApproveTool { session_id: Uuid, call_id: String },
 
@@ -176,6 +194,12 @@ pub enum ControlEvent {
         args: serde_json::Value,
     },
 
+    /// The agent reached a safe point and is now suspended.
+    Paused { session_id: Uuid },
+
+    /// A paused agent continued its run.
+    Resumed { session_id: Uuid },
+
     /// The session's lifecycle state changed.
     SessionState {
         session_id: Uuid,
@@ -248,6 +272,9 @@ pub enum SessionState {
     Running,
     /// Waiting for the operator to approve or deny a tool call.
     AwaitingApproval,
+    /// The run is suspended (or will be at the next safe point) until the
+    /// operator sends [`ControlCommand::Resume`].
+    Paused,
     /// The session completed and will accept no more input.
     Completed,
     /// The session was cancelled.
@@ -385,6 +412,19 @@ mod tests {
         assert!(matches!(back, ControlCommand::ListSessions));
     }
 
+    #[test]
+    fn pause_resume_commands_json_round_trip() {
+        let id = Uuid::new_v4();
+        let json = serde_json::to_string(&ControlCommand::Pause { session_id: id }).unwrap();
+        assert!(json.contains("\"type\":\"pause\""), "{json}");
+        let back: ControlCommand = serde_json::from_str(&json).unwrap();
+        assert!(matches!(back, ControlCommand::Pause { session_id } if session_id == id));
+
+        let bytes = encode_command(&ControlCommand::Resume { session_id: id }).unwrap();
+        let back = decode_command(&bytes).unwrap();
+        assert!(matches!(back, ControlCommand::Resume { session_id } if session_id == id));
+    }
+
     #[test]
     fn session_state_serializes_as_snake_case() {
         let s = serde_json::to_string(&SessionState::AwaitingApproval).unwrap();
@@ -72,7 +72,7 @@ use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 use sven_config::AgentMode;
-use sven_core::{Agent, AgentEvent};
+use sven_core::{Agent, AgentEvent, PauseGate};
 use sven_p2p::P2pHandle;
 
 use super::protocol::{
@@ -156,6 +156,9 @@ impl Session {
 /// The core control service. Owns the agent and processes operator commands.
 pub struct ControlService {
     agent: Arc<Mutex<Agent>>,
+    /// Pause handle taken from the agent before it is locked behind `agent`;
+    /// a running turn holds that lock, so pause/resume must bypass it.
+    pause_gate: PauseGate,
     cmd_rx: mpsc::Receiver<(ControlCommand, Option<oneshot::Sender<ControlEvent>>)>,
     /// Internal completion notifications from spawned agent tasks.
     /// When an agent run finishes, the task sends the session UUID here so
@@ -191,19 +194,28 @@ impl ControlService {
     /// runs for integration-level tests.
     #[cfg(test)]
     pub fn new_for_test() -> (Self, AgentHandle) {
-        use sven_core::AgentRuntimeContext;
         use sven_tools::{ReadFileTool, ToolRegistry};
 
         let mut registry = ToolRegistry::new();
         registry.register(ReadFileTool);
+        Self::new_for_test_with(Arc::new(sven_model::MockProvider), registry)
+    }
+
+    /// Like [`ControlService::new_for_test`] but with a caller-supplied model
+    /// and tool registry, for tests that need scripted turns or custom tools.
+    #[cfg(test)]
+    pub fn new_for_test_with(
+        model: Arc<dyn sven_model::ModelProvider>,
+        registry: sven_tools::ToolRegistry,
+    ) -> (Self, AgentHandle) {
+        use sven_core::AgentRuntimeContext;
 
-        let model = sven_model::MockProvider;
         let config = std::sync::Arc::new(sven_config::AgentConfig::default());
         let mode = std::sync::Arc::new(tokio::sync::Mutex::new(sven_config::AgentMode::Agent));
         let (_, tool_rx) = tokio::sync::mpsc::channel(1);
 
         let agent = Agent::new(
-            std::sync::Arc::new(model),
+            model,
             std::sync::Arc::new(registry),
             config,
             AgentRuntimeContext::default(),
@@ -239,8 +251,10 @@ impl ControlService {
             event_tx: event_tx.clone(),
         };
 
+        let pause_gate = agent.pause_gate();
         let svc = Self {
             agent: Arc::new(Mutex::new(agent)),
+            pause_gate,
             cmd_rx,
             completion_rx,
             completion_tx,
@@ -290,6 +304,9 @@ impl ControlService {
                         session.state = SessionState::Completed;
                         session.cancel_tx = None;
                     }
+                    // A pause requested after the last safe point never took
+                    // effect; clear it so the next run does not start paused.
+                    self.pause_gate.resume();
                 }
             }
         }
@@ -311,6 +328,12 @@ impl ControlService {
             ControlCommand::CancelSession { session_id } => {
                 self.handle_cancel(session_id).await;
             }
+            ControlCommand::Pause { session_id } => {
+                self.handle_pause(session_id).await;
+            }
+            ControlCommand::Resume { session_id } => {
+                self.handle_resume(session_id).await;
+            }
             ControlCommand::ApproveTool {
                 session_id,
                 call_id,
@@ -539,6 +562,13 @@ impl ControlService {
             });
             return;
         }
+        if session.state == SessionState::Paused {
+            self.broadcast(ControlEvent::NodeError {
+                code: 409,
+                message: format!("session {session_id} is paused; resume it first"),
+            });
+            return;
+        }
 
         session.state = SessionState::Running;
         info!(%session_id, "session running");
@@ -625,6 +655,9 @@ impl ControlService {
             if let Some(tx) = session.cancel_tx.take() {
                 let _ = tx.send(());
             }
+            // A paused agent is waiting on the gate *and* the cancel channel;
+            // the cancel wins, but clear the gate for the next run.
+            self.pause_gate.resume();
             session.state = SessionState::Cancelled;
             self.broadcast(ControlEvent::SessionState {
                 session_id,
@@ -635,6 +668,56 @@ impl ControlService {
         }
     }
 
+    async fn handle_pause(&mut self, session_id: Uuid) {
+        let Some(session) = self.sessions.get_mut(&session_id) else {
+            self.broadcast(ControlEvent::NodeError {
+                code: 404,
+                message: format!("session {session_id} not found"),
+            });
+            return;
+        };
+        if session.state != SessionState::Running {
+            self.broadcast(ControlEvent::NodeError {
+                code: 409,
+                message: format!("session {session_id} is not running"),
+            });
+            return;
+        }
+        // The agent observes the gate at its next safe point and emits
+        // AgentEvent::Paused, which the bridge forwards as ControlEvent::Paused.
+        self.pause_gate.pause();
+        session.state = SessionState::Paused;
+        info!(%session_id, "session pause requested");
+        self.broadcast(ControlEvent::SessionState {
+            session_id,
+            state: SessionState::Paused,
+        });
+    }
+
+    async fn handle_resume(&mut self, session_id: Uuid) {
+        let Some(session) = self.sessions.get_mut(&session_id) else {
+            self.broadcast(ControlEvent::NodeError {
+                code: 404,
+                message: format!("session {session_id} not found"),
+            });
+            return;
+        };
+        if session.state != SessionState::Paused {
+            self.broadcast(ControlEvent::NodeError {
+                code: 409,
+                message: format!("session {session_id} is not paused"),
+            });
+            return;
+        }
+        self.pause_gate.resume();
+        session.state = SessionState::Running;
+        info!(%session_id, "session resumed");
+        self.broadcast(ControlEvent::SessionState {
+            session_id,
+            state: SessionState::Running,
+        });
+    }
+
     async fn handle_approve_tool(
         &mut self,
         session_id: Uuid,
@@ -713,6 +796,8 @@ fn agent_event_to_control(ev: AgentEvent, session_id: Uuid) -> Option<ControlEve
             session_id: Some(session_id),
             message: msg,
         }),
+        AgentEvent::Paused => Some(ControlEvent::Paused { session_id }),
+        AgentEvent::Resumed => Some(ControlEvent::Resumed { session_id }),
         // TurnComplete, TokenUsage, ContextCompacted etc. are not forwarded
         // to operators — they're internal agent bookkeeping.
         _ => None,
@@ -926,4 +1011,177 @@ mod tests {
             other => panic!("expected SessionList, got {other:?}"),
         }
     }
+
+    /// Tool that blocks until the test releases it, so a pause can be
+    /// requested while the agent is mid-turn.
+    struct GateTool(Arc<tokio::sync::Notify>);
+
+    #[async_trait::async_trait]
+    impl sven_tools::Tool for GateTool {
+        fn name(&self) -> &str {
+            "gate"
+        }
+        fn description(&self) -> &str {
+            "blocks until released"
+        }
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object", "properties": {}})
+        }
+        fn default_policy(&self) -> sven_tools::ApprovalPolicy {
+            sven_tools::ApprovalPolicy::Auto
+        }
+        async fn execute(&self, call: &sven_tools::ToolCall) -> sven_tools::ToolOutput {
+            self.0.notified().await;
+            sven_tools::ToolOutput::ok(&call.id, "released")
+        }
+    }
+
+    /// Receive events until one matches `pred`, failing after a timeout.
+    async fn recv_until(
+        events: &mut broadcast::Receiver<ControlEvent>,
+        pred: impl Fn(&ControlEvent) -> bool,
+    ) -> ControlEvent {
+        loop {
+            let ev = tokio::time::timeout(std::time::Duration::from_secs(2), events.recv())
+                .await
+                .expect("timed out waiting for event")
+                .unwrap();
+            if pred(&ev) {
+                return ev;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn pause_and_resume_round_trip_keeps_turns() {
+        let release = Arc::new(tokio::sync::Notify::new());
+        let mut registry = sven_tools::ToolRegistry::new();
+        registry.register(GateTool(release.clone()));
+        let model = sven_model::ScriptedMockProvider::tool_then_text("tc-1", "gate", "{}", "done");
+        let (svc, handle) = ControlService::new_for_test_with(Arc::new(model), registry);
+        let agent = svc.agent.clone();
+        tokio::spawn(svc.run());
+
+        let mut events = handle.subscribe();
+        let session_id = Uuid::new_v4();
+        handle
+            .send(ControlCommand::NewSession {
+                id: session_id,
+                mode: sven_config::AgentMode::Agent,
+                working_dir: None,
+            })
+            .await
+            .unwrap();
+        handle
+            .send(ControlCommand::SendInput {
+                session_id,
+                text: "go".to_string(),
+            })
+            .await
+            .unwrap();
+        recv_until(&mut events, |ev| {
+            matches!(ev, ControlEvent::ToolCall { .. })
+        })
+        .await;
+
+        // Running → Paused while the tool is still in flight.
+        handle
+            .send(ControlCommand::Pause { session_id })
+            .await
+            .unwrap();
+        recv_until(&mut events, |ev| {
+            matches!(
+                ev,
+                ControlEvent::SessionState {
+                    state: SessionState::Paused,
+                    ..
+                }
+            )
+        })
+        .await;
+        release.notify_one();
+        recv_until(&mut events, |ev| matches!(ev, ControlEvent::Paused { .. })).await;
+
+        handle.send(ControlCommand::ListSessions).await.unwrap();
+        match recv_until(&mut events, |ev| {
+            matches!(ev, ControlEvent::SessionList { .. })
+        })
+        .await
+        {
+            ControlEvent::SessionList { sessions } => {
+                assert_eq!(sessions[0].state, SessionState::Paused);
+            }
+            _ => unreachable!(),
+        }
+
+        // Paused → Running; the turn finishes normally.
+        handle
+            .send(ControlCommand::Resume { session_id })
+            .await
+            .unwrap();
+        recv_until(&mut events, |ev| {
+            matches!(
+                ev,
+                ControlEvent::SessionState {
+                    state: SessionState::Running,
+                    ..
+                }
+            )
+        })
+        .await;
+        recv_until(&mut events, |ev| matches!(ev, ControlEvent::Resumed { .. })).await;
+        match recv_until(&mut events, |ev| {
+            matches!(ev, ControlEvent::OutputComplete { .. })
+        })
+        .await
+        {
+            ControlEvent::OutputComplete { text, .. } => assert_eq!(text, "done"),
+            _ => unreachable!(),
+        }
+        recv_until(&mut events, |ev| {
+            matches!(
+                ev,
+                ControlEvent::SessionState {
+                    state: SessionState::Completed,
+                    ..
+                }
+            )
+        })
+        .await;
+
+        let agent = agent.lock().await;
+        let msgs = &agent.session().messages;
+        assert!(msgs.iter().any(|m| matches!(
+            &m.content,
+            sven_model::MessageContent::ToolResult { tool_call_id, .. } if tool_call_id == "tc-1"
+        )));
+        assert_eq!(msgs.last().and_then(|m| m.as_text()), Some("done"));
+    }
+
+    #[tokio::test]
+    async fn pause_idle_session_returns_conflict() {
+        let (svc, handle) = ControlService::new_for_test();
+        tokio::spawn(svc.run());
+
+        let mut events = handle.subscribe();
+        let session_id = Uuid::new_v4();
+        handle
+            .send(ControlCommand::NewSession {
+                id: session_id,
+                mode: sven_config::AgentMode::Agent,
+                working_dir: None,
+            })
+            .await
+            .unwrap();
+        handle
+            .send(ControlCommand::Pause { session_id })
+            .await
+            .unwrap();
+
+        let ev = recv_until(&mut events, |ev| {
+            matches!(ev, ControlEvent::NodeError { .. })
+        })
+        .await;
+        assert!(matches!(ev, ControlEvent::NodeError { code: 409, .. }));
+    }
 }
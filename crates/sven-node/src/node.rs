@@ -1494,6 +1494,10 @@ pub async fn pair_peer(
             .unwrap_or("-".into())
     );
     println!();
+    match pairing.to_qr_string() {
+        Ok(qr) => println!("Scan to share this pairing URI:\n{qr}"),
+        Err(e) => tracing::debug!("pairing QR code unavailable: {e}"),
+    }
 
     let label = label.unwrap_or_else(|| format!("device-{}", &pairing.peer_id.to_base58()[..8]));
 
@@ -22,6 +22,7 @@
 //! in transit.
 
 use libp2p::{Multiaddr, PeerId};
+use qrcode::{render::unicode::Dense1x2, QrCode};
 use sha2::{Digest, Sha256};
 
 /// A parsed `sven://` pairing URI.
@@ -99,6 +100,29 @@ impl PairingUri {
             .collect::<Vec<_>>()
             .join(":")
     }
+
+    /// Render the pairing URI as a QR code drawn with Unicode half-block
+    /// characters, ready to print to a terminal so a phone can scan it.
+    ///
+    /// Colours are inverted (light modules on dark) because most terminals
+    /// have a dark background; the quiet zone is included.
+    pub fn to_qr_string(&self) -> anyhow::Result<String> {
+        render_qr(&self.to_uri())
+    }
+}
+
+/// Encode `data` as a terminal-printable QR block.
+fn render_qr(data: &str) -> anyhow::Result<String> {
+    if data.is_empty() {
+        anyhow::bail!("cannot render an empty pairing URI as a QR code");
+    }
+    let code = QrCode::new(data.as_bytes())
+        .map_err(|e| anyhow::anyhow!("cannot encode pairing URI as QR code: {e}"))?;
+    Ok(code
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .build())
 }
 
 impl std::fmt::Display for PairingUri {
@@ -196,4 +220,23 @@ mod tests {
             "different peers must produce different fingerprints"
         );
     }
+
+    #[test]
+    fn qr_string_renders_valid_uri() {
+        let uri = PairingUri {
+            peer_id: sample_peer(),
+            addr: Some("/ip4/1.2.3.4/tcp/4001".parse().unwrap()),
+        };
+        let qr = uri.to_qr_string().unwrap();
+        assert!(!qr.trim().is_empty());
+        assert!(
+            qr.lines().count() > 10,
+            "QR block must span many rows:\n{qr}"
+        );
+    }
+
+    #[test]
+    fn qr_string_rejects_empty_uri() {
+        assert!(render_qr("").is_err());
+    }
 }
@@ -209,6 +209,7 @@ mod tests {
             skill_dir: PathBuf::from(path).parent().unwrap().to_path_buf(),
             content: String::new(),
             sven_meta: None,
+            requires: vec![],
         }
     }
 
@@ -3,8 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 //! Git context collection: branch, commit, remote URL, dirty-file count.
 
-use std::path::Path;
-use std::time::Duration;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime};
 
 // ─── Git context ──────────────────────────────────────────────────────────────
 
@@ -14,8 +16,25 @@ const GIT_OUTPUT_LIMIT: usize = 4 * 1024;
 /// Per-command timeout for git sub-processes.
 const GIT_TIMEOUT: Duration = Duration::from_secs(5);
 
+/// Maximum bytes read from `git log --name-only` for [`collect_file_recency`].
+const GIT_LOG_OUTPUT_LIMIT: usize = 1024 * 1024;
+
+/// Number of recent commits scanned by [`collect_file_recency`].
+const RECENCY_COMMITS: &str = "300";
+
+/// How long [`collect_git_context_cached`] reuses a snapshot while the
+/// repository looks unchanged.  Bounds how stale the dirty count can get
+/// from edits the fingerprint does not see (files in subdirectories).
+const GIT_CONTEXT_TTL: Duration = Duration::from_secs(10);
+
+/// Most commit subjects [`collect_recent_commits`] returns.
+pub const MAX_RECENT_COMMITS: usize = 20;
+
+/// Longest commit subject kept, in characters; longer ones are cut with `…`.
+const MAX_COMMIT_SUBJECT_CHARS: usize = 120;
+
 /// Live state of the git repository at the project root.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct GitContext {
     pub branch: Option<String>,
     /// Short (7-char) commit hash.
@@ -23,6 +42,9 @@ pub struct GitContext {
     pub remote_url: Option<String>,
     /// Number of changed / untracked files reported by `git status --porcelain`.
     pub dirty_count: usize,
+    /// Subjects of the most recent commits, newest first.  Only collected on
+    /// request (see [`collect_recent_commits`]); empty otherwise.
+    pub recent_commits: Vec<String>,
 }
 
 /// Collect git metadata from the repository at `project_root` by running git
@@ -43,7 +65,134 @@ pub fn collect_git_context(project_root: &Path) -> GitContext {
         commit,
         remote_url,
         dirty_count,
+        recent_commits: Vec::new(),
+    }
+}
+
+/// Subjects of the last `n` commits (at most [`MAX_RECENT_COMMITS`]) of the
+/// repository at `project_root`, newest first.  Empty when `n` is zero or
+/// git fails.
+pub fn collect_recent_commits(project_root: &Path, n: usize) -> Vec<String> {
+    let n = n.min(MAX_RECENT_COMMITS);
+    if n == 0 {
+        return Vec::new();
+    }
+    run_git_timed(&["log", "-n", &n.to_string(), "--format=%s"], project_root)
+        .map(|log| parse_commit_subjects(&log, n))
+        .unwrap_or_default()
+}
+
+/// Parse `git log --format=%s` output into at most `n` subjects.  The output
+/// may end mid-line when it hit [`GIT_OUTPUT_LIMIT`]; a cut-off last line is
+/// still kept.
+fn parse_commit_subjects(log: &str, n: usize) -> Vec<String> {
+    log.lines()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .take(n)
+        .map(|s| match s.char_indices().nth(MAX_COMMIT_SUBJECT_CHARS) {
+            Some((cut, _)) => format!("{}…", &s[..cut]),
+            None => s.to_string(),
+        })
+        .collect()
+}
+
+/// [`collect_git_context`] through a per-repository cache.
+///
+/// A snapshot is reused for a few seconds as long as HEAD, the branch it
+/// points at, the index and the working-tree root are unchanged, so code
+/// that rebuilds the prompt in a loop does not spawn four git processes
+/// every time.  A commit, checkout, `git add` or a file created or removed
+/// at the top level invalidates it immediately.
+pub fn collect_git_context_cached(project_root: &Path) -> GitContext {
+    static CACHE: OnceLock<GitContextCache> = OnceLock::new();
+    cached_git_context(
+        CACHE.get_or_init(GitContextCache::default),
+        project_root,
+        GIT_CONTEXT_TTL,
+        collect_git_context,
+    )
+}
+
+type GitContextCache = Mutex<HashMap<PathBuf, CachedGitContext>>;
+
+struct CachedGitContext {
+    fingerprint: GitFingerprint,
+    taken: Instant,
+    context: GitContext,
+}
+
+/// The cache lookup behind [`collect_git_context_cached`], with the cache,
+/// TTL and collector injected for tests.
+fn cached_git_context(
+    cache: &GitContextCache,
+    project_root: &Path,
+    ttl: Duration,
+    collect: impl FnOnce(&Path) -> GitContext,
+) -> GitContext {
+    let fingerprint = GitFingerprint::of(project_root);
+    // Held across `collect` so concurrent callers wait for one snapshot
+    // instead of each spawning their own.
+    let mut cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(hit) = cache.get(project_root) {
+        if hit.fingerprint == fingerprint && hit.taken.elapsed() < ttl {
+            return hit.context.clone();
+        }
     }
+    let context = collect(project_root);
+    cache.insert(
+        project_root.to_path_buf(),
+        CachedGitContext {
+            fingerprint,
+            taken: Instant::now(),
+            context: context.clone(),
+        },
+    );
+    context
+}
+
+/// Cheap, subprocess-free signs that the repository changed: what HEAD
+/// points at and the modification times of the branch ref, the index and
+/// the working-tree root.
+#[derive(Debug, PartialEq, Eq)]
+struct GitFingerprint {
+    head: Option<String>,
+    head_ref_mtime: Option<SystemTime>,
+    index_mtime: Option<SystemTime>,
+    tree_mtime: Option<SystemTime>,
+}
+
+impl GitFingerprint {
+    fn of(project_root: &Path) -> Self {
+        let mtime = |p: &Path| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+        let git_dir = git_dir(project_root);
+        let head = git_dir
+            .as_ref()
+            .and_then(|d| std::fs::read_to_string(d.join("HEAD")).ok())
+            .map(|s| s.trim().to_string());
+        let head_ref = head.as_deref().and_then(|h| h.strip_prefix("ref: "));
+        Self {
+            head_ref_mtime: git_dir
+                .as_ref()
+                .zip(head_ref)
+                .and_then(|(d, r)| mtime(&d.join(r))),
+            index_mtime: git_dir.as_ref().and_then(|d| mtime(&d.join("index"))),
+            tree_mtime: mtime(project_root),
+            head,
+        }
+    }
+}
+
+/// The git directory of the repository at `project_root`: `.git` itself,
+/// or the `gitdir:` a worktree's or submodule's `.git` file points at.
+fn git_dir(project_root: &Path) -> Option<PathBuf> {
+    let dot_git = project_root.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+    let link = std::fs::read_to_string(&dot_git).ok()?;
+    let target = link.trim().strip_prefix("gitdir:")?.trim();
+    Some(project_root.join(target))
 }
 
 /// Run a git command in `dir` with a hard timeout.
@@ -55,6 +204,10 @@ pub(crate) fn run_git_timed_pub(args: &[&str], dir: &Path) -> Option<String> {
 }
 
 fn run_git_timed(args: &[&str], dir: &Path) -> Option<String> {
+    run_git_limited(args, dir, GIT_OUTPUT_LIMIT)
+}
+
+fn run_git_limited(args: &[&str], dir: &Path, limit: usize) -> Option<String> {
     use std::sync::mpsc;
     use std::thread;
 
@@ -74,7 +227,7 @@ fn run_git_timed(args: &[&str], dir: &Path) -> Option<String> {
     if !output.status.success() {
         return None;
     }
-    let raw = String::from_utf8_lossy(&output.stdout[..output.stdout.len().min(GIT_OUTPUT_LIMIT)]);
+    let raw = String::from_utf8_lossy(&output.stdout[..output.stdout.len().min(limit)]);
     let s = raw.trim().to_string();
     if s.is_empty() {
         None
@@ -109,10 +262,84 @@ impl GitContext {
         } else if self.commit.is_some() {
             lines.push("Working tree: clean".to_string());
         }
+        if !self.recent_commits.is_empty() {
+            lines.push("Recent commits:".to_string());
+            lines.extend(self.recent_commits.iter().map(|s| format!("- {s}")));
+        }
         Some(lines.join("\n"))
     }
 }
 
+// ─── File recency ─────────────────────────────────────────────────────────────
+
+/// When files in a repository last changed, according to git.
+///
+/// Committed files get the time of the newest commit (among the last few
+/// hundred) that touched them; files with uncommitted changes get their
+/// modification time, since they are being edited right now.
+#[derive(Debug, Default)]
+pub struct FileRecency {
+    committed: HashMap<PathBuf, SystemTime>,
+    dirty: HashSet<PathBuf>,
+}
+
+/// Collect [`FileRecency`] for the repository containing `dir`.
+///
+/// Returns `None` if git is not available or `dir` is not inside a repository.
+pub fn collect_file_recency(dir: &Path) -> Option<FileRecency> {
+    let top = PathBuf::from(run_git_timed(&["rev-parse", "--show-toplevel"], dir)?);
+    let log = run_git_limited(
+        &[
+            "log",
+            "-n",
+            RECENCY_COMMITS,
+            "--format=%x00%ct",
+            "--name-only",
+        ],
+        &top,
+        GIT_LOG_OUTPUT_LIMIT,
+    )
+    .unwrap_or_default();
+    let changed = run_git_limited(&["diff", "--name-only", "HEAD"], &top, GIT_LOG_OUTPUT_LIMIT)
+        .unwrap_or_default();
+    let untracked = run_git_limited(
+        &["ls-files", "--others", "--exclude-standard"],
+        &top,
+        GIT_LOG_OUTPUT_LIMIT,
+    )
+    .unwrap_or_default();
+
+    let mut recency = FileRecency::default();
+    let mut commit_time = None;
+    for line in log.lines() {
+        if let Some(ts) = line.strip_prefix('\0') {
+            commit_time = ts
+                .trim()
+                .parse::<u64>()
+                .ok()
+                .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+        } else if let (Some(time), false) = (commit_time, line.is_empty()) {
+            // Newest commit first: keep the first time seen for each file.
+            recency.committed.entry(top.join(line)).or_insert(time);
+        }
+    }
+    for line in changed.lines().chain(untracked.lines()) {
+        recency.dirty.insert(top.join(line));
+    }
+    Some(recency)
+}
+
+impl FileRecency {
+    /// When `path` (absolute) last changed, or `None` when git has no
+    /// recent record of it.
+    pub fn modified(&self, path: &Path) -> Option<SystemTime> {
+        if self.dirty.contains(path) {
+            return std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        }
+        self.committed.get(path).copied()
+    }
+}
+
 // ─── Unit tests ───────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -133,6 +360,7 @@ mod tests {
             commit: Some("d3adb33".to_string()),
             remote_url: Some("git@github.com:acme/sven.git".to_string()),
             dirty_count: 3,
+            recent_commits: Vec::new(),
         };
         let section = ctx.to_prompt_section().unwrap();
         assert!(section.contains("Git Context"));
@@ -148,8 +376,145 @@ mod tests {
             commit: Some("abc1234".to_string()),
             remote_url: None,
             dirty_count: 0,
+            recent_commits: Vec::new(),
         };
         let section = ctx.to_prompt_section().unwrap();
         assert!(section.contains("clean"));
+        assert!(!section.contains("Recent commits"));
+    }
+
+    #[test]
+    fn recent_commit_subjects_are_parsed_and_rendered() {
+        let log = "Fix UART overrun on nRF52\n\
+                   \n\
+                   Add west manifest for v3.6\n  Bump MSRV  \nfourth\nfifth";
+        let subjects = parse_commit_subjects(log, 3);
+        assert_eq!(
+            subjects,
+            [
+                "Fix UART overrun on nRF52",
+                "Add west manifest for v3.6",
+                "Bump MSRV"
+            ]
+        );
+
+        let long = "x".repeat(200);
+        assert_eq!(
+            parse_commit_subjects(&long, 5),
+            [format!("{}…", "x".repeat(MAX_COMMIT_SUBJECT_CHARS))]
+        );
+
+        let ctx = GitContext {
+            branch: Some("main".to_string()),
+            commit: Some("abc1234".to_string()),
+            recent_commits: subjects,
+            ..GitContext::default()
+        };
+        assert_eq!(
+            ctx.to_prompt_section().unwrap(),
+            "## Git Context\nBranch: main\nCommit: abc1234\nWorking tree: clean\n\
+             Recent commits:\n- Fix UART overrun on nRF52\n- Add west manifest for v3.6\n\
+             - Bump MSRV"
+        );
+    }
+
+    #[test]
+    fn recent_commits_are_capped() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(collect_recent_commits(dir.path(), 0).is_empty());
+        let log: String = (0..50).map(|i| format!("commit {i}\n")).collect();
+        assert_eq!(
+            parse_commit_subjects(&log, MAX_RECENT_COMMITS).len(),
+            MAX_RECENT_COMMITS
+        );
+    }
+
+    #[test]
+    fn cached_git_context_is_reused_until_head_changes() {
+        let repo = tempfile::tempdir().unwrap();
+        std::fs::create_dir(repo.path().join(".git")).unwrap();
+        std::fs::write(repo.path().join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+
+        let cache = GitContextCache::default();
+        let spawned = std::cell::Cell::new(0);
+        let collect = |_: &Path| {
+            spawned.set(spawned.get() + 1);
+            GitContext {
+                commit: Some(format!("c{}", spawned.get())),
+                ..GitContext::default()
+            }
+        };
+        let ttl = Duration::from_secs(60);
+
+        let first = cached_git_context(&cache, repo.path(), ttl, collect);
+        let second = cached_git_context(&cache, repo.path(), ttl, collect);
+        assert_eq!(
+            spawned.get(),
+            1,
+            "second call within the TTL must not re-collect"
+        );
+        assert_eq!(first, second);
+
+        std::fs::write(repo.path().join(".git/HEAD"), "ref: refs/heads/dev\n").unwrap();
+        let after_checkout = cached_git_context(&cache, repo.path(), ttl, collect);
+        assert_eq!(spawned.get(), 2);
+        assert_eq!(after_checkout.commit.as_deref(), Some("c2"));
+    }
+
+    #[test]
+    fn cached_git_context_expires_after_the_ttl() {
+        let repo = tempfile::tempdir().unwrap();
+        let cache = GitContextCache::default();
+        let spawned = std::cell::Cell::new(0);
+        let collect = |_: &Path| {
+            spawned.set(spawned.get() + 1);
+            GitContext::default()
+        };
+        cached_git_context(&cache, repo.path(), Duration::ZERO, collect);
+        cached_git_context(&cache, repo.path(), Duration::ZERO, collect);
+        assert_eq!(spawned.get(), 2);
+    }
+
+    #[test]
+    fn git_dir_follows_a_worktree_link() {
+        let repo = tempfile::tempdir().unwrap();
+        assert_eq!(git_dir(repo.path()), None);
+        std::fs::write(
+            repo.path().join(".git"),
+            "gitdir: /srv/main/.git/worktrees/wt\n",
+        )
+        .unwrap();
+        assert_eq!(
+            git_dir(repo.path()).as_deref(),
+            Some(Path::new("/srv/main/.git/worktrees/wt"))
+        );
+    }
+
+    #[test]
+    fn file_recency_uses_commit_time_and_mtime_for_dirty_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+                .args(args)
+                .env("GIT_COMMITTER_DATE", "2001-01-01T00:00:00Z")
+                .current_dir(dir.path())
+                .output()
+                .unwrap()
+        };
+        if !git(&["init", "-q"]).status.success() {
+            return; // git not installed
+        }
+        std::fs::write(dir.path().join("old.rs"), "").unwrap();
+        git(&["add", "old.rs"]);
+        git(&["commit", "-q", "-m", "init"]);
+        std::fs::write(dir.path().join("new.rs"), "").unwrap();
+
+        let recency = collect_file_recency(dir.path()).unwrap();
+        let top = dir.path().canonicalize().unwrap();
+        let old = recency.modified(&top.join("old.rs")).unwrap();
+        let new = recency.modified(&top.join("new.rs")).unwrap();
+        assert!(new > old, "untracked file should be newer than the commit");
+        assert!(recency.modified(&top.join("missing.rs")).is_none());
     }
 }
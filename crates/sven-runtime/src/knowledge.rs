@@ -16,6 +16,7 @@
 //! files:
 //!   - crates/sven-p2p/**
 //!   - crates/sven-node/**
+//! tags: [networking, relay]
 //! updated: 2026-03-01
 //! ---
 //!
@@ -30,6 +31,7 @@
 //! |:------------|:---------|:------------------------------------------------------|
 //! | `subsystem` | Yes      | Human-readable name shown in tool output              |
 //! | `files`     | No       | Glob patterns for files this doc covers               |
+//! | `tags`      | No       | Labels for filtering in `list_knowledge`              |
 //! | `updated`   | No       | ISO date (YYYY-MM-DD) when doc was last reviewed      |
 //!
 //! ## Drift detection
@@ -55,6 +57,8 @@ pub struct KnowledgeInfo {
     pub subsystem: String,
     /// Glob patterns for source files this document covers (from `files:`).
     pub files: Vec<String>,
+    /// Free-form labels used to filter `list_knowledge` (from `tags:`).
+    pub tags: Vec<String>,
     /// ISO-date string when the document was last reviewed (from `updated:`).
     /// `None` when the `updated:` field is absent — no drift detection for
     /// that document.
@@ -97,6 +101,8 @@ struct KnowledgeFrontmatter {
     #[serde(default)]
     files: Vec<String>,
     #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
     updated: Option<String>,
 }
 
@@ -147,6 +153,12 @@ fn parse_knowledge_file(raw: &str, path: &Path) -> Option<KnowledgeInfo> {
     Some(KnowledgeInfo {
         subsystem: fm.subsystem.trim().to_string(),
         files: fm.files,
+        tags: fm
+            .tags
+            .iter()
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect(),
         updated: fm.updated,
         path: path.to_path_buf(),
         body,
@@ -354,11 +366,12 @@ mod tests {
 
     #[test]
     fn parse_knowledge_file_valid() {
-        let raw = "---\nsubsystem: P2P Networking\nfiles:\n  - crates/sven-p2p/**\nupdated: 2026-01-15\n---\n\n## Core Architecture\n\nDetail here.";
+        let raw = "---\nsubsystem: P2P Networking\nfiles:\n  - crates/sven-p2p/**\ntags: [net, relay]\nupdated: 2026-01-15\n---\n\n## Core Architecture\n\nDetail here.";
         let path = PathBuf::from("/tmp/sven-p2p.md");
         let info = parse_knowledge_file(raw, &path).expect("should parse");
         assert_eq!(info.subsystem, "P2P Networking");
         assert_eq!(info.files, vec!["crates/sven-p2p/**"]);
+        assert_eq!(info.tags, vec!["net", "relay"]);
         assert_eq!(info.updated.as_deref(), Some("2026-01-15"));
         assert!(info.body.contains("Core Architecture"));
     }
@@ -384,6 +397,7 @@ mod tests {
         let info = parse_knowledge_file(raw, &path).expect("should parse with minimal fields");
         assert_eq!(info.subsystem, "Config");
         assert!(info.files.is_empty());
+        assert!(info.tags.is_empty());
         assert!(info.updated.is_none());
     }
 
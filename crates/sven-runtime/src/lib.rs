@@ -32,12 +32,15 @@ pub use knowledge::{
 
 pub mod project;
 pub use project::{
-    find_project_root, find_workspace_root, load_project_context_file,
-    load_project_context_file_with_path, resolve_auto_log_path,
+    build_system_hint, detect_build_systems, find_project_root, find_workspace_root,
+    load_project_context_file, load_project_context_file_with_path, resolve_auto_log_path,
 };
 
 pub mod git;
-pub use git::{collect_git_context, GitContext};
+pub use git::{
+    collect_file_recency, collect_git_context, collect_git_context_cached, collect_recent_commits,
+    FileRecency, GitContext, MAX_RECENT_COMMITS,
+};
 
 pub mod ci;
 pub use ci::{ci_template_vars, detect_ci_context, CiContext};
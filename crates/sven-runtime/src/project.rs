@@ -123,6 +123,67 @@ pub fn find_workspace_root(project_root: &Path) -> PathBuf {
     project_root.to_path_buf()
 }
 
+// ─── Build system detection ───────────────────────────────────────────────────
+
+/// Marker files in the project root and the build system each one implies,
+/// with the commands the agent should reach for.  Reported in this order.
+const BUILD_SYSTEMS: &[(&str, &str, &str)] = &[
+    (
+        "west.yml",
+        "west",
+        "Zephyr/west workspace — `west build -b <board>`, `west flash`, `west update`",
+    ),
+    (
+        "Cargo.toml",
+        "cargo",
+        "Rust — `cargo build`, `cargo test`, `cargo clippy`",
+    ),
+    (
+        "CMakeLists.txt",
+        "cmake",
+        "CMake — `cmake -B build && cmake --build build`, `ctest --test-dir build`",
+    ),
+    (
+        "go.mod",
+        "go",
+        "Go — `go build ./...`, `go test ./...`, `go vet ./...`",
+    ),
+    (
+        "package.json",
+        "npm",
+        "JavaScript/TypeScript — the `scripts` in package.json (`npm run build`, `npm test`)",
+    ),
+    (
+        "pyproject.toml",
+        "python",
+        "Python — the tools configured in pyproject.toml (e.g. `pytest`, `ruff`)",
+    ),
+];
+
+/// Detect the build systems of a project from marker files in
+/// `project_root`.  A `.west` directory in `workspace_root` also counts as
+/// west: Zephyr applications usually live in a repository below the west
+/// workspace rather than next to `west.yml`.
+pub fn detect_build_systems(project_root: &Path, workspace_root: Option<&Path>) -> Vec<String> {
+    BUILD_SYSTEMS
+        .iter()
+        .filter(|(marker, id, _)| {
+            project_root.join(marker).is_file()
+                || (*id == "west" && workspace_root.is_some_and(|ws| ws.join(".west").is_dir()))
+        })
+        .map(|(_, id, _)| id.to_string())
+        .collect()
+}
+
+/// What a build system reported by [`detect_build_systems`] means for the
+/// agent, for the system prompt.  `None` for an unknown id.
+pub fn build_system_hint(id: &str) -> Option<&'static str> {
+    BUILD_SYSTEMS
+        .iter()
+        .find(|(_, known, _)| *known == id)
+        .map(|(_, _, hint)| *hint)
+}
+
 // ─── Project context file ─────────────────────────────────────────────────────
 
 /// Maximum bytes loaded from a project context file.
@@ -193,6 +254,52 @@ mod tests {
         assert!(root.is_dir(), "project root should be a directory");
     }
 
+    #[test]
+    fn each_marker_file_is_detected() {
+        for (marker, id) in [
+            ("Cargo.toml", "cargo"),
+            ("package.json", "npm"),
+            ("CMakeLists.txt", "cmake"),
+            ("west.yml", "west"),
+            ("go.mod", "go"),
+            ("pyproject.toml", "python"),
+        ] {
+            let tmp = tempfile::tempdir().unwrap();
+            std::fs::write(tmp.path().join(marker), "").unwrap();
+            assert_eq!(detect_build_systems(tmp.path(), None), [id], "{marker}");
+            assert!(build_system_hint(id).is_some());
+        }
+    }
+
+    #[test]
+    fn several_build_systems_are_reported_in_order() {
+        let tmp = tempfile::tempdir().unwrap();
+        for marker in ["pyproject.toml", "Cargo.toml", "CMakeLists.txt"] {
+            std::fs::write(tmp.path().join(marker), "").unwrap();
+        }
+        // A directory named like a marker is not a marker.
+        std::fs::create_dir(tmp.path().join("go.mod")).unwrap();
+        assert_eq!(
+            detect_build_systems(tmp.path(), None),
+            ["cargo", "cmake", "python"]
+        );
+        assert!(detect_build_systems(&tmp.path().join("go.mod"), None).is_empty());
+    }
+
+    #[test]
+    fn zephyr_app_below_a_west_workspace_is_west() {
+        let ws = tempfile::tempdir().unwrap();
+        std::fs::create_dir(ws.path().join(".west")).unwrap();
+        let app = ws.path().join("app");
+        std::fs::create_dir(&app).unwrap();
+        std::fs::write(app.join("CMakeLists.txt"), "").unwrap();
+        assert_eq!(
+            detect_build_systems(&app, Some(ws.path())),
+            ["west", "cmake"]
+        );
+        assert_eq!(detect_build_systems(&app, Some(&app)), ["cmake"]);
+    }
+
     #[test]
     fn find_workspace_root_detects_west_workspace() {
         let tmp = std::env::temp_dir().join("sven_wsroot_west_test");
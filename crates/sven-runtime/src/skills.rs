@@ -74,6 +74,7 @@
 //!   This skill should be used when the user asks to "do X", "configure Y".
 //! name: My Skill       # optional — falls back to directory name
 //! version: 0.1.0       # optional
+//! requires: [git-basics]  # optional — skills loaded before this one
 //! sven:                # optional sven-specific block
 //!   always: false
 //!   requires_bins: [ffmpeg]
@@ -142,6 +143,9 @@ pub struct SkillInfo {
     pub content: String,
     /// Optional sven-specific metadata.
     pub sven_meta: Option<SvenSkillMeta>,
+    /// Commands of prerequisite skills (from `requires:`), loaded before this
+    /// one by the `skill` tool.
+    pub requires: Vec<String>,
 }
 
 /// A shared, live-refreshable collection of discovered skills.
@@ -174,6 +178,8 @@ struct RawFrontmatter {
     version: Option<String>,
     #[serde(default)]
     sven: Option<SvenSkillMeta>,
+    #[serde(default)]
+    requires: Vec<String>,
 }
 
 // ── Parsing ───────────────────────────────────────────────────────────────────
@@ -185,6 +191,8 @@ pub struct ParsedSkill {
     pub description: String,
     pub version: Option<String>,
     pub sven_meta: Option<SvenSkillMeta>,
+    /// Commands of prerequisite skills from `requires:`.
+    pub requires: Vec<String>,
     /// Everything after the closing `---` fence, with leading whitespace trimmed.
     pub body: String,
 }
@@ -233,6 +241,12 @@ pub fn parse_skill_file(raw: &str) -> Option<ParsedSkill> {
             description: fm.description,
             version: fm.version,
             sven_meta: fm.sven,
+            requires: fm
+                .requires
+                .into_iter()
+                .map(|r| r.trim().trim_matches('/').to_string())
+                .filter(|r| !r.is_empty())
+                .collect(),
             body,
         });
     }
@@ -268,6 +282,7 @@ pub fn parse_skill_file(raw: &str) -> Option<ParsedSkill> {
         description,
         version: None,
         sven_meta: None,
+        requires: Vec::new(),
         body: rest.to_string(),
     })
 }
@@ -380,6 +395,7 @@ fn try_load_skill(
         skill_dir: skill_dir.to_path_buf(),
         content: parsed.body,
         sven_meta: parsed.sven_meta,
+        requires: parsed.requires,
     })
 }
 
@@ -704,6 +720,7 @@ fn try_load_command(md_path: &Path, command: &str, source: &str) -> Option<Skill
         skill_dir: md_path.parent().unwrap_or(md_path).to_path_buf(),
         content: parsed.body,
         sven_meta: parsed.sven_meta,
+        requires: parsed.requires,
     })
 }
 
@@ -860,6 +877,14 @@ mod tests {
         assert!(!meta.user_invocable_only);
     }
 
+    #[test]
+    fn parse_skill_file_reads_requires() {
+        let raw =
+            "---\ndescription: Release.\nrequires: [git-basics, \" sven/plan/ \"]\n---\n\nBody.";
+        let parsed = parse_skill_file(raw).expect("should parse");
+        assert_eq!(parsed.requires, vec!["git-basics", "sven/plan"]);
+    }
+
     #[test]
     fn parse_skill_file_missing_description_returns_none() {
         let raw = "---\nname: Something\n---\n\nBody.";
@@ -75,6 +75,9 @@ impl Tool for ContextOpenTool {
     fn default_policy(&self) -> ApprovalPolicy {
         ApprovalPolicy::Auto
     }
+    fn path_args(&self) -> &[&str] {
+        &["path"]
+    }
 
     fn output_category(&self) -> OutputCategory {
         OutputCategory::Generic
@@ -40,6 +40,9 @@ impl Tool for DeleteFileTool {
     fn default_policy(&self) -> ApprovalPolicy {
         ApprovalPolicy::Ask
     }
+    fn path_args(&self) -> &[&str] {
+        &["path"]
+    }
 
     fn modes(&self) -> &[AgentMode] {
         &[AgentMode::Agent]
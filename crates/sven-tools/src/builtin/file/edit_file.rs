@@ -9,9 +9,17 @@ use tracing::debug;
 
 use sven_config::AgentMode;
 
+use crate::params::opt_u64;
 use crate::policy::ApprovalPolicy;
 use crate::tool::{Tool, ToolCall, ToolDisplay, ToolOutput};
 
+/// Default number of unchanged lines shown around each change in the
+/// result diff.
+const DEFAULT_RESULT_CONTEXT: usize = 3;
+
+/// Upper bound for the `context` parameter.
+const MAX_RESULT_CONTEXT: usize = 20;
+
 /// Minimum similarity ratio (0–1) for a fuzzy window to be accepted.
 const FUZZY_THRESHOLD: f64 = 0.85;
 
@@ -327,10 +335,38 @@ fn find_hunk_position(
             msg.push_str(&format!("  |{l}|\n"));
         }
     }
+    if let Some((missing, ratio, line_no, line)) =
+        nearest_line_for_missing(&file_refs, search_lines)
+    {
+        msg.push_str(&format!(
+            "Line |{missing}| is not in the file; nearest similar line is {line_no} ({:.0}%):\n  |{line}|\n",
+            ratio * 100.0
+        ));
+    }
     msg.push_str("Re-read the file, fix the context lines, and retry.");
     Err(msg)
 }
 
+/// For the first search line that appears nowhere in the file (ignoring
+/// surrounding whitespace), return it with the most similar file line:
+/// `(missing, ratio, 1-based line number, line)`.
+fn nearest_line_for_missing<'a>(
+    file_lines: &[&'a str],
+    search_lines: &[&'a str],
+) -> Option<(&'a str, f64, usize, &'a str)> {
+    let missing = search_lines
+        .iter()
+        .find(|s| !s.trim().is_empty() && !file_lines.iter().any(|l| l.trim() == s.trim()))?;
+    file_lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| (similarity_ratio(missing.trim(), l.trim()), i + 1, *l))
+        .filter(|(r, _, _)| *r > 0.3)
+        .max_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(r, n, l)| (*missing, r, n, l))
+}
+
 /// When several windows match at equal quality, pick the one closest to `hint`
 /// (1-based old-file line).  Falls back to the first match when hint is absent.
 fn pick_best(matches: &[usize], hint: Option<usize>) -> usize {
@@ -460,7 +496,10 @@ impl Tool for EditFileTool {
          +    x * 2\n\
           }\n\
          \n\
-         Re-read the file after any previous edit before writing new context."
+         Re-read the file after any previous edit before writing new context.\n\
+         \n\
+         The result shows the applied change as a unified diff of the file \
+         (`context` unchanged lines around each change, default 3)."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -474,6 +513,10 @@ impl Tool for EditFileTool {
                 "diff": {
                     "type": "string",
                     "description": "Unified diff with hunk markers. REQUIRED: must contain at least one line starting with @@ (e.g. @@ -1,3 +1,4 @@). Without @@ hunk markers the call will fail. Each hunk starts with @@; include 2–3 context lines around every change."
+                },
+                "context": {
+                    "type": "integer",
+                    "description": "Unchanged lines around each change in the result diff (default 3, max 20)"
                 }
             },
             "required": ["path", "diff"],
@@ -484,6 +527,9 @@ impl Tool for EditFileTool {
     fn default_policy(&self) -> ApprovalPolicy {
         ApprovalPolicy::Ask
     }
+    fn path_args(&self) -> &[&str] {
+        &["path"]
+    }
 
     fn modes(&self) -> &[AgentMode] {
         &[AgentMode::Agent]
@@ -499,6 +545,10 @@ impl Tool for EditFileTool {
             None => return ToolOutput::err(&call.id, "Missing required parameter: diff"),
         };
 
+        let context = opt_u64(call, "context")
+            .map_or(DEFAULT_RESULT_CONTEXT, |n| n as usize)
+            .min(MAX_RESULT_CONTEXT);
+
         debug!(path = %path, "edit_file tool");
 
         let hunks = match parse_hunks(&diff_str) {
@@ -543,12 +593,45 @@ impl Tool for EditFileTool {
         }
 
         match tokio::fs::write(&path, &new_content).await {
-            Ok(_) => ToolOutput::ok(&call.id, "Edit successfully applied"),
+            Ok(_) => ToolOutput::ok(
+                &call.id,
+                edit_summary(&path, &content, &new_content, hunks.len(), context),
+            ),
             Err(e) => ToolOutput::err(&call.id, format!("Write failed: {e}")),
         }
     }
 }
 
+/// Success message: `Edit successfully applied`, then the hunk and line counts
+/// and a unified diff of the file before and after the edit.
+fn edit_summary(path: &str, before: &str, after: &str, hunks: usize, context: usize) -> String {
+    let diff = TextDiff::from_lines(before, after);
+    let (mut removed, mut added) = (0, 0);
+    for change in diff.iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Delete => removed += 1,
+            ChangeTag::Insert => added += 1,
+            ChangeTag::Equal => {}
+        }
+    }
+    let mut out = format!(
+        "Edit successfully applied\n{hunks} hunk{}, -{removed}/+{added} lines",
+        if hunks == 1 { "" } else { "s" }
+    );
+    if removed + added == 0 {
+        out.push_str(" (file unchanged)");
+        return out;
+    }
+    let unified = diff
+        .unified_diff()
+        .context_radius(context)
+        .header(&format!("a/{path}"), &format!("b/{path}"))
+        .to_string();
+    out.push_str("\n\n");
+    out.push_str(unified.trim_end());
+    out
+}
+
 impl ToolDisplay for EditFileTool {
     fn display_name(&self) -> &str {
         "Edit"
@@ -685,6 +768,40 @@ mod tests {
         let _ = std::fs::remove_file(&path);
     }
 
+    #[tokio::test]
+    async fn no_match_names_nearest_similar_line() {
+        let original = "fn foo() {\n    let total = compute(items);\n    total\n}\n";
+        let path = tmp_file(original);
+        let t = EditFileTool;
+        let out = t
+            .execute(&call(json!({
+                "path": path,
+                "diff": "@@ @@\n-    let sum = compute_all(item_list);\n+    let sum = 0;\n"
+            })))
+            .await;
+        assert!(out.is_error, "{}", out.content);
+        assert!(
+            out.content.starts_with("Context not found."),
+            "{}",
+            out.content
+        );
+        assert!(
+            out.content.contains(
+                "Line |    let sum = compute_all(item_list);| is not in the file; \
+                 nearest similar line is 2"
+            ),
+            "{}",
+            out.content
+        );
+        assert!(
+            out.content.contains("  |    let total = compute(items);|"),
+            "{}",
+            out.content
+        );
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+        let _ = std::fs::remove_file(&path);
+    }
+
     #[tokio::test]
     async fn surrounding_content_is_preserved() {
         let path = tmp_file("// header\nfn target() { old(); }\n// footer\n");
@@ -1090,7 +1207,39 @@ mod tests {
             })))
             .await;
         assert!(!out.is_error, "{}", out.content);
-        assert_eq!(out.content, "Edit successfully applied");
+        assert_eq!(
+            out.content.lines().next(),
+            Some("Edit successfully applied"),
+            "{}",
+            out.content
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn success_message_includes_unified_diff_of_change() {
+        let before: String = (1..=10).map(|i| format!("line {i}\n")).collect();
+        let path = tmp_file(&before);
+        let t = EditFileTool;
+        let out = t
+            .execute(&call(json!({
+                "path": path,
+                "diff": "@@ -5,3 +5,3 @@\n line 4\n-line 5\n+LINE FIVE\n line 6\n",
+                "context": 1
+            })))
+            .await;
+        assert!(!out.is_error, "{}", out.content);
+        let expected = format!(
+            "Edit successfully applied\n1 hunk, -1/+1 lines\n\n\
+             --- a/{path}\n\
+             +++ b/{path}\n\
+             @@ -4,3 +4,3 @@\n\
+             \x20line 4\n\
+             -line 5\n\
+             +LINE FIVE\n\
+             \x20line 6"
+        );
+        assert_eq!(out.content, expected);
         let _ = std::fs::remove_file(&path);
     }
 
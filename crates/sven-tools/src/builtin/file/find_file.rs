@@ -1,6 +1,9 @@
 // Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
 //
 // SPDX-License-Identifier: Apache-2.0
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
 use async_trait::async_trait;
 use serde_json::{json, Value};
 use tracing::debug;
@@ -14,11 +17,18 @@ pub struct FindFileTool;
 // Directories that are always excluded from search results.
 const EXCLUDED_DIRS: &[&str] = &[".git", "target", "node_modules", ".cargo"];
 
+/// Matches collected before ranking, so `max_results` keeps the best ones
+/// rather than the first ones walked.
+const RANK_CANDIDATES: usize = 5000;
+
+/// Age at which the recency score halves.
+const RECENCY_HALF_LIFE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
 /// Match a file path (relative to root, using `/` separators) against a glob
 /// pattern.  Supports `*` (any chars within a segment), `**` (any segments),
 /// and `?` (any single char).  Matching is done on the full relative path so
 /// patterns like `**/sven-team/**/*.rs` work correctly.
-fn glob_matches(pattern: &str, path: &str, case_insensitive: bool) -> bool {
+pub(crate) fn glob_matches(pattern: &str, path: &str, case_insensitive: bool) -> bool {
     // Normalise separators to `/` so patterns work on all platforms.
     let path_norm = path.replace(std::path::MAIN_SEPARATOR, "/");
     let path_str = if case_insensitive {
@@ -107,7 +117,7 @@ fn find_files_walkdir(
     case_insensitive: bool,
     max: usize,
     deadline: std::time::Instant,
-) -> anyhow::Result<Vec<String>> {
+) -> anyhow::Result<Vec<PathBuf>> {
     let has_path_sep = pattern.contains('/');
 
     let mut results = Vec::new();
@@ -154,7 +164,7 @@ fn find_files_walkdir(
         };
 
         if glob_matches(pattern, match_target, case_insensitive) {
-            results.push(entry.path().display().to_string());
+            results.push(entry.into_path());
             if results.len() >= max {
                 break;
             }
@@ -164,6 +174,67 @@ fn find_files_walkdir(
     Ok(results)
 }
 
+/// Order `files` (found under `root`) best first, returning each with its
+/// score in `0.0..=1.0`.
+///
+/// The score is the recency of the file's last change — from git when `root`
+/// is inside a repository, otherwise the file's mtime — halving every
+/// [`RECENCY_HALF_LIFE`].  With `near`, it is averaged with how few directory
+/// hops separate the file from `near`.
+fn rank_files(root: &str, files: Vec<PathBuf>, near: Option<&str>) -> Vec<(PathBuf, f64)> {
+    let abs_root = Path::new(root)
+        .canonicalize()
+        .unwrap_or_else(|_| PathBuf::from(root));
+    let git = sven_runtime::collect_file_recency(&abs_root);
+    let near = near.and_then(|n| std::path::absolute(n).ok());
+    let now = SystemTime::now();
+
+    let mut ranked: Vec<(PathBuf, f64)> = files
+        .into_iter()
+        .map(|file| {
+            let abs = abs_root.join(file.strip_prefix(root).unwrap_or(&file));
+            let modified = match &git {
+                Some(git) => git.modified(&abs),
+                None => std::fs::metadata(&abs).and_then(|m| m.modified()).ok(),
+            };
+            let recency = recency_score(modified, now);
+            let score = match &near {
+                Some(near) => (recency + proximity_score(&abs, near)) / 2.0,
+                None => recency,
+            };
+            (file, score)
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked
+}
+
+/// `1.0` for a change right now, halving every [`RECENCY_HALF_LIFE`]; `0.0`
+/// when the time is unknown.
+fn recency_score(modified: Option<SystemTime>, now: SystemTime) -> f64 {
+    let Some(modified) = modified else {
+        return 0.0;
+    };
+    let age = now.duration_since(modified).unwrap_or_default();
+    0.5f64.powf(age.as_secs_f64() / RECENCY_HALF_LIFE.as_secs_f64())
+}
+
+/// `1.0` for a file in the same directory as `near` (or inside `near` when it
+/// is a directory), `1 / (1 + hops)` otherwise.
+fn proximity_score(file: &Path, near: &Path) -> f64 {
+    let near_dir = if near.is_dir() {
+        near
+    } else {
+        near.parent().unwrap_or(near)
+    };
+    let file_dir = file.parent().unwrap_or(file);
+    let a: Vec<_> = near_dir.components().collect();
+    let b: Vec<_> = file_dir.components().collect();
+    let common = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let hops = (a.len() - common) + (b.len() - common);
+    1.0 / (1.0 + hops as f64)
+}
+
 #[async_trait]
 impl Tool for FindFileTool {
     fn name(&self) -> &str {
@@ -181,6 +252,9 @@ impl Tool for FindFileTool {
            '**/sven-team/**/*.rs' — .rs files inside any 'sven-team' directory\n\
            'Cargo.toml'         — exact filename anywhere under root\n\
            '*lint*'             — filenames containing 'lint'\n\
+         rank: true sorts results best first with a [score] — recently changed files \
+         (git history, or mtime outside git) rank higher. near: a path; files closer to it \
+         rank higher (implies rank). Use these to pick the right one of many mod.rs.\n\
          For content search use grep or search_codebase instead."
     }
 
@@ -207,6 +281,14 @@ impl Tool for FindFileTool {
                 "timeout_secs": {
                     "type": "integer",
                     "description": "Hard timeout in seconds (default: 10)"
+                },
+                "rank": {
+                    "type": "boolean",
+                    "description": "Sort by recency (and proximity to 'near') and show each score (default: false)"
+                },
+                "near": {
+                    "type": "string",
+                    "description": "File or directory the results should be close to; implies rank"
                 }
             },
             "required": ["pattern"],
@@ -217,6 +299,13 @@ impl Tool for FindFileTool {
     fn default_policy(&self) -> ApprovalPolicy {
         ApprovalPolicy::Auto
     }
+    fn path_args(&self) -> &[&str] {
+        &["root"]
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
 
     async fn execute(&self, call: &ToolCall) -> ToolOutput {
         let raw_pattern = match call.args.get("pattern").and_then(|v| v.as_str()) {
@@ -244,8 +333,19 @@ impl Tool for FindFileTool {
             .get("timeout_secs")
             .and_then(|v| v.as_u64())
             .unwrap_or(10);
+        let near = call
+            .args
+            .get("near")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        let rank = near.is_some()
+            || call
+                .args
+                .get("rank")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
 
-        debug!(pattern = %raw_pattern, root = %root, "find_file tool");
+        debug!(pattern = %raw_pattern, root = %root, rank, "find_file tool");
 
         let pattern = raw_pattern.clone();
         let root_path = root.clone();
@@ -254,8 +354,24 @@ impl Tool for FindFileTool {
         // the async executor.
         let deadline = std::time::Instant::now() + std::time::Duration::from_secs(timeout_secs);
 
-        let result = tokio::task::spawn_blocking(move || {
-            find_files_walkdir(&root_path, &pattern, case_insensitive, max, deadline)
+        let result = tokio::task::spawn_blocking(move || -> anyhow::Result<Vec<String>> {
+            if !rank {
+                let found =
+                    find_files_walkdir(&root_path, &pattern, case_insensitive, max, deadline)?;
+                return Ok(found.iter().map(|p| p.display().to_string()).collect());
+            }
+            let found = find_files_walkdir(
+                &root_path,
+                &pattern,
+                case_insensitive,
+                max.max(RANK_CANDIDATES),
+                deadline,
+            )?;
+            Ok(rank_files(&root_path, found, near.as_deref())
+                .into_iter()
+                .take(max)
+                .map(|(p, score)| format!("{}  [score {score:.2}]", p.display()))
+                .collect::<Vec<_>>())
         })
         .await;
 
@@ -510,4 +626,75 @@ mod tests {
         assert!(out.content.contains("lib.rs"), "{}", out.content);
         assert!(!out.content.contains("README.md"), "{}", out.content);
     }
+
+    // ── Ranking ───────────────────────────────────────────────────────────────
+
+    fn touch(path: &Path, age: Duration) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, b"").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(SystemTime::now() - age)
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn rank_puts_recently_modified_file_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let day = Duration::from_secs(24 * 60 * 60);
+        touch(&dir.path().join("old/mod.rs"), 30 * day);
+        touch(&dir.path().join("new/mod.rs"), Duration::from_secs(60));
+
+        let out = FindFileTool
+            .execute(&call(json!({
+                "pattern": "mod.rs",
+                "root": dir.path().to_str().unwrap(),
+                "rank": true
+            })))
+            .await;
+        assert!(!out.is_error, "{}", out.content);
+        let lines: Vec<&str> = out.content.lines().collect();
+        assert_eq!(lines.len(), 2, "{}", out.content);
+        assert!(
+            lines[0].contains("new/mod.rs  [score 1.00]"),
+            "{}",
+            out.content
+        );
+        assert!(
+            lines[1].contains("old/mod.rs  [score 0.05]"),
+            "{}",
+            out.content
+        );
+    }
+
+    #[tokio::test]
+    async fn near_prefers_files_close_to_the_given_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let age = Duration::from_secs(60);
+        touch(&dir.path().join("a/mod.rs"), age);
+        touch(&dir.path().join("b/c/mod.rs"), age);
+        touch(&dir.path().join("b/c/lib.rs"), age);
+
+        let out = FindFileTool
+            .execute(&call(json!({
+                "pattern": "mod.rs",
+                "root": dir.path().to_str().unwrap(),
+                "near": dir.path().join("b/c/lib.rs").to_str().unwrap()
+            })))
+            .await;
+        assert!(!out.is_error, "{}", out.content);
+        let lines: Vec<&str> = out.content.lines().collect();
+        assert!(lines[0].contains("b/c/mod.rs"), "{}", out.content);
+        assert!(lines[1].contains("a/mod.rs"), "{}", out.content);
+    }
+
+    #[test]
+    fn proximity_counts_directory_hops() {
+        let near = Path::new("/r/b/c/lib.rs");
+        assert_eq!(proximity_score(Path::new("/r/b/c/mod.rs"), near), 1.0);
+        assert_eq!(proximity_score(Path::new("/r/b/mod.rs"), near), 0.5);
+        assert_eq!(proximity_score(Path::new("/r/a/mod.rs"), near), 1.0 / 4.0);
+    }
 }
@@ -64,10 +64,17 @@ impl Tool for ReadFileTool {
     fn default_policy(&self) -> ApprovalPolicy {
         ApprovalPolicy::Auto
     }
+    fn path_args(&self) -> &[&str] {
+        &["path"]
+    }
     fn output_category(&self) -> OutputCategory {
         OutputCategory::FileContent
     }
 
+    fn read_only(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, call: &ToolCall) -> ToolOutput {
         let path = match require_str(call, "path") {
             Ok(p) => p.to_string(),
@@ -51,6 +51,9 @@ impl Tool for WriteTool {
     fn default_policy(&self) -> ApprovalPolicy {
         ApprovalPolicy::Ask
     }
+    fn path_args(&self) -> &[&str] {
+        &["path"]
+    }
 
     fn modes(&self) -> &[AgentMode] {
         &[AgentMode::Agent]
@@ -5,13 +5,24 @@
 
 use async_trait::async_trait;
 use serde_json::{json, Value};
-use sven_runtime::SharedKnowledge;
+use sven_runtime::{KnowledgeInfo, SharedKnowledge};
 
+use crate::params::{opt_str, opt_u64};
 use crate::policy::ApprovalPolicy;
 use crate::tool::{OutputCategory, Tool, ToolCall, ToolOutput};
 
-/// List all project knowledge documents with their subsystem names, covered
-/// file patterns, and last-updated dates.
+/// Documents listed per page when `limit` is not given.
+const DEFAULT_LIMIT: usize = 20;
+
+/// Longest snippet shown per document, in characters.
+const SNIPPET_CHARS: usize = 120;
+
+/// List project knowledge documents with their subsystem names, tags, covered
+/// file patterns, last-updated dates and a short snippet.
+///
+/// Documents can be narrowed by `tag` and `query` and paged with
+/// `limit`/`offset`; they are always ordered by subsystem name, then file
+/// name, so pages are stable between calls.
 ///
 /// Call this first to discover which subsystems have knowledge specs, then
 /// use `search_knowledge` to find relevant content or `read_file` to load
@@ -27,8 +38,10 @@ impl Tool for ListKnowledgeTool {
     }
 
     fn description(&self) -> &str {
-        "List all project knowledge documents in `.sven/knowledge/`.\n\
-         Returns: subsystem name, covered file patterns, last-updated date, filename.\n\
+        "List project knowledge documents in `.sven/knowledge/`.\n\
+         Returns per doc: subsystem, tags, file, last-updated date, covered file patterns, snippet.\n\
+         Narrow with tag= (exact, case-insensitive) and query= (substring of subsystem, tags, \
+         file patterns or body); page with limit= (default 20) and offset=.\n\
          Use `search_knowledge` to search content, or `read_file` to load a full doc.\n\
          Knowledge docs contain subsystem architecture, invariants, and failure-mode tables."
     }
@@ -36,7 +49,24 @@ impl Tool for ListKnowledgeTool {
     fn parameters_schema(&self) -> Value {
         json!({
             "type": "object",
-            "properties": {},
+            "properties": {
+                "tag": {
+                    "type": "string",
+                    "description": "Only documents with this tag (case-insensitive)"
+                },
+                "query": {
+                    "type": "string",
+                    "description": "Only documents whose subsystem, tags, file patterns or body contain this text (case-insensitive)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum documents to return (default 20)"
+                },
+                "offset": {
+                    "type": "integer",
+                    "description": "Number of matching documents to skip (default 0)"
+                }
+            },
             "additionalProperties": false
         })
     }
@@ -49,6 +79,10 @@ impl Tool for ListKnowledgeTool {
         OutputCategory::MatchList
     }
 
+    fn read_only(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, call: &ToolCall) -> ToolOutput {
         let docs = self.knowledge.get();
 
@@ -62,46 +96,101 @@ impl Tool for ListKnowledgeTool {
                  subsystem: My Subsystem\n\
                  files:\n\
                    - crates/my-crate/**\n\
+                 tags: [core]\n\
                  updated: 2026-01-01\n\
                  ---\n\
                  ```",
             );
         }
 
-        let mut lines = vec![
-            format!("Found {} knowledge document(s):\n", docs.len()),
-            format!(
-                "{:<30} {:<40} {:<12} {}",
-                "Subsystem", "Covers", "Updated", "File"
-            ),
-            format!("{}", "-".repeat(100)),
-        ];
+        let tag = opt_str(call, "tag")
+            .map(str::trim)
+            .filter(|t| !t.is_empty());
+        let query = opt_str(call, "query")
+            .map(|q| q.trim().to_lowercase())
+            .filter(|q| !q.is_empty());
+        let limit = opt_u64(call, "limit")
+            .map_or(DEFAULT_LIMIT, |n| n as usize)
+            .max(1);
+        let offset = opt_u64(call, "offset").unwrap_or(0) as usize;
 
-        for doc in docs.iter() {
-            let covers = if doc.files.is_empty() {
-                "(no file patterns)".to_string()
+        let mut matching: Vec<&KnowledgeInfo> = docs
+            .iter()
+            .filter(|doc| tag.is_none_or(|t| doc.tags.iter().any(|d| d.eq_ignore_ascii_case(t))))
+            .filter(|doc| query.as_deref().is_none_or(|q| doc_matches(doc, q)))
+            .collect();
+        matching.sort_by(|a, b| {
+            a.subsystem
+                .cmp(&b.subsystem)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+
+        let mut filters = Vec::new();
+        if let Some(tag) = tag {
+            filters.push(format!("tag={tag}"));
+        }
+        if let Some(query) = opt_str(call, "query")
+            .map(str::trim)
+            .filter(|q| !q.is_empty())
+        {
+            filters.push(format!("query={query:?}"));
+        }
+        let filters = if filters.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", filters.join(", "))
+        };
+
+        let total = matching.len();
+        let page: Vec<&KnowledgeInfo> = matching.into_iter().skip(offset).take(limit).collect();
+        if page.is_empty() {
+            let msg = if total == 0 {
+                format!(
+                    "No knowledge documents match{filters}; {} document(s) in total.",
+                    docs.len()
+                )
             } else {
-                doc.files.join(", ")
+                format!("No documents at offset {offset}; {total} match{filters}.")
             };
-            let covers_display = if covers.len() > 38 {
-                format!("{}…", &covers[..37])
+            return ToolOutput::ok(&call.id, msg);
+        }
+
+        let mut lines = vec![format!(
+            "Knowledge documents {}-{} of {total}{filters}:",
+            offset + 1,
+            offset + page.len()
+        )];
+        for (i, doc) in page.iter().enumerate() {
+            lines.push(String::new());
+            let tags = if doc.tags.is_empty() {
+                String::new()
             } else {
-                covers
+                format!("  [{}]", doc.tags.join(", "))
             };
-
-            let updated = doc.updated.as_deref().unwrap_or("—");
+            lines.push(format!("{}. {}{tags}", offset + i + 1, doc.subsystem));
             let filename = doc.path.file_name().and_then(|n| n.to_str()).unwrap_or("?");
-
+            let updated = doc.updated.as_deref().unwrap_or("—");
+            let covers = if doc.files.is_empty() {
+                "(no file patterns)".to_string()
+            } else {
+                doc.files.join(", ")
+            };
             lines.push(format!(
-                "{:<30} {:<40} {:<12} {}",
-                &doc.subsystem[..doc.subsystem.len().min(29)],
-                covers_display,
-                updated,
-                filename
+                "   file: {filename} · updated: {updated} · covers: {covers}"
             ));
+            if let Some(snippet) = snippet(&doc.body, query.as_deref()) {
+                lines.push(format!("   {snippet}"));
+            }
         }
 
         lines.push(String::new());
+        if offset + page.len() < total {
+            lines.push(format!(
+                "{} more: call again with offset={}.",
+                total - offset - page.len(),
+                offset + page.len()
+            ));
+        }
         lines.push(
             "Use `search_knowledge \"<query>\"` to find relevant content across all docs."
                 .to_string(),
@@ -111,6 +200,30 @@ impl Tool for ListKnowledgeTool {
     }
 }
 
+/// Whether `doc` contains the lowercase `query` in its subsystem, tags, file
+/// patterns, file name or body.
+fn doc_matches(doc: &KnowledgeInfo, query: &str) -> bool {
+    let filename = doc.path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    std::iter::once(doc.subsystem.as_str())
+        .chain(doc.tags.iter().map(String::as_str))
+        .chain(doc.files.iter().map(String::as_str))
+        .chain([filename, doc.body.as_str()])
+        .any(|text| text.to_lowercase().contains(query))
+}
+
+/// One line of `body`: the first one containing `query` if any, otherwise
+/// the first line of prose (skipping headings, fences and tables).
+fn snippet(body: &str, query: Option<&str>) -> Option<String> {
+    let mut lines = body.lines().map(str::trim);
+    let hit = query.and_then(|q| lines.clone().find(|l| l.to_lowercase().contains(q)));
+    let line =
+        hit.or_else(|| lines.find(|l| !l.is_empty() && !l.starts_with(['#', '`', '|', '~'])))?;
+    Some(match line.char_indices().nth(SNIPPET_CHARS) {
+        Some((cut, _)) => format!("{}…", &line[..cut]),
+        None => line.to_string(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -119,13 +232,72 @@ mod tests {
     use sven_runtime::KnowledgeInfo;
 
     fn call() -> ToolCall {
+        call_with(json!({}))
+    }
+
+    fn call_with(args: Value) -> ToolCall {
         ToolCall {
             id: "lk1".into(),
             name: "list_knowledge".into(),
-            args: json!({}),
+            args,
+        }
+    }
+
+    fn doc(subsystem: &str, file: &str, tags: &[&str], body: &str) -> KnowledgeInfo {
+        KnowledgeInfo {
+            subsystem: subsystem.to_string(),
+            files: vec![],
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            updated: None,
+            path: std::path::PathBuf::from(format!(".sven/knowledge/{file}")),
+            body: body.to_string(),
+        }
+    }
+
+    /// Five documents, deliberately out of order.
+    fn fixture() -> ListKnowledgeTool {
+        ListKnowledgeTool {
+            knowledge: SharedKnowledge::new(vec![
+                doc(
+                    "Tool System",
+                    "tools.md",
+                    &["core"],
+                    "## Overview\nTools are registered at startup.",
+                ),
+                doc(
+                    "P2P Networking",
+                    "p2p.md",
+                    &["net", "Relay"],
+                    "## Overview\nRelay circuits and mDNS.",
+                ),
+                doc(
+                    "Agent Loop",
+                    "agent.md",
+                    &["core"],
+                    "# Agent\nThe loop drives tool calls.",
+                ),
+                doc(
+                    "Channels",
+                    "channels.md",
+                    &["net"],
+                    "Telegram and Discord bridges.",
+                ),
+                doc("Config", "config.md", &[], "Layered YAML config files."),
+            ]),
         }
     }
 
+    fn titles(content: &str) -> Vec<String> {
+        content
+            .lines()
+            .filter_map(|l| {
+                l.split_once(". ")
+                    .filter(|(n, _)| n.parse::<usize>().is_ok())
+            })
+            .map(|(_, rest)| rest.split("  [").next().unwrap().to_string())
+            .collect()
+    }
+
     #[tokio::test]
     async fn empty_knowledge_base_returns_helpful_message() {
         let t = ListKnowledgeTool {
@@ -143,6 +315,7 @@ mod tests {
             KnowledgeInfo {
                 subsystem: "P2P Networking".to_string(),
                 files: vec!["crates/sven-p2p/**".to_string()],
+                tags: vec![],
                 updated: Some("2026-01-15".to_string()),
                 path: std::path::PathBuf::from(".sven/knowledge/sven-p2p.md"),
                 body: "P2P body.".to_string(),
@@ -150,6 +323,7 @@ mod tests {
             KnowledgeInfo {
                 subsystem: "Tool System".to_string(),
                 files: vec!["crates/sven-tools/**".to_string()],
+                tags: vec![],
                 updated: None,
                 path: std::path::PathBuf::from(".sven/knowledge/sven-tools.md"),
                 body: "Tools body.".to_string(),
@@ -167,4 +341,116 @@ mod tests {
         assert!(out.content.contains("sven-p2p.md"));
         assert!(out.content.contains("search_knowledge"));
     }
+
+    #[tokio::test]
+    async fn lists_all_in_subsystem_order_with_tags_and_snippets() {
+        let out = fixture().execute(&call()).await;
+        assert!(!out.is_error, "{}", out.content);
+        assert_eq!(
+            titles(&out.content),
+            [
+                "Agent Loop",
+                "Channels",
+                "Config",
+                "P2P Networking",
+                "Tool System"
+            ]
+        );
+        assert!(
+            out.content.starts_with("Knowledge documents 1-5 of 5:"),
+            "{}",
+            out.content
+        );
+        assert!(
+            out.content.contains("4. P2P Networking  [net, Relay]"),
+            "{}",
+            out.content
+        );
+        // Snippets skip headings.
+        assert!(
+            out.content.contains("   The loop drives tool calls."),
+            "{}",
+            out.content
+        );
+        assert!(!out.content.contains("more: call again"), "{}", out.content);
+    }
+
+    #[tokio::test]
+    async fn tag_and_query_filter_documents() {
+        let t = fixture();
+        let out = t.execute(&call_with(json!({"tag": "NET"}))).await;
+        assert_eq!(titles(&out.content), ["Channels", "P2P Networking"]);
+        assert!(out.content.contains("(tag=NET)"), "{}", out.content);
+
+        let out = t.execute(&call_with(json!({"tag": "relay"}))).await;
+        assert_eq!(titles(&out.content), ["P2P Networking"]);
+
+        // query matches subsystem, tags and body, case-insensitively.
+        let out = t.execute(&call_with(json!({"query": "TOOL"}))).await;
+        assert_eq!(titles(&out.content), ["Agent Loop", "Tool System"]);
+        assert!(
+            out.content.contains("   The loop drives tool calls."),
+            "{}",
+            out.content
+        );
+
+        let out = t
+            .execute(&call_with(json!({"tag": "core", "query": "startup"})))
+            .await;
+        assert_eq!(titles(&out.content), ["Tool System"]);
+
+        let out = t.execute(&call_with(json!({"tag": "missing"}))).await;
+        assert!(!out.is_error);
+        assert!(
+            out.content
+                .starts_with("No knowledge documents match (tag=missing)"),
+            "{}",
+            out.content
+        );
+    }
+
+    #[tokio::test]
+    async fn limit_and_offset_page_through_results() {
+        let t = fixture();
+        let first = t.execute(&call_with(json!({"limit": 2}))).await;
+        assert_eq!(titles(&first.content), ["Agent Loop", "Channels"]);
+        assert!(
+            first.content.contains("3 more: call again with offset=2."),
+            "{}",
+            first.content
+        );
+
+        let second = t
+            .execute(&call_with(json!({"limit": 2, "offset": 2})))
+            .await;
+        assert!(
+            second.content.starts_with("Knowledge documents 3-4 of 5:"),
+            "{}",
+            second.content
+        );
+        assert_eq!(titles(&second.content), ["Config", "P2P Networking"]);
+
+        let last = t
+            .execute(&call_with(json!({"limit": 2, "offset": 4})))
+            .await;
+        assert_eq!(titles(&last.content), ["Tool System"]);
+        assert!(
+            !last.content.contains("more: call again"),
+            "{}",
+            last.content
+        );
+
+        let past = t.execute(&call_with(json!({"offset": 9}))).await;
+        assert!(
+            past.content.contains("No documents at offset 9; 5 match."),
+            "{}",
+            past.content
+        );
+
+        // Pages of a filtered list are stable between calls.
+        let again = t
+            .execute(&call_with(json!({"limit": 2, "offset": 2})))
+            .await;
+        assert_eq!(again.content, second.content);
+    }
 }
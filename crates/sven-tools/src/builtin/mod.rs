@@ -45,13 +45,13 @@ mod output_category_tests {
 
     #[test]
     fn shell_tool_is_headtail() {
-        let t = super::shell::ShellTool { timeout_secs: 30 };
+        let t = super::shell::ShellTool::default();
         assert_eq!(t.output_category(), OutputCategory::HeadTail);
     }
 
     #[test]
     fn run_terminal_command_is_headtail() {
-        let t = super::terminal::run_terminal_command::RunTerminalCommandTool { timeout_secs: 30 };
+        let t = super::terminal::run_terminal_command::RunTerminalCommandTool::default();
         assert_eq!(t.output_category(), OutputCategory::HeadTail);
     }
 
@@ -126,13 +126,13 @@ mod output_category_tests {
 
     #[test]
     fn web_fetch_is_generic() {
-        let t = super::web::web_fetch::WebFetchTool;
+        let t = super::web::web_fetch::WebFetchTool::default();
         assert_eq!(t.output_category(), OutputCategory::Generic);
     }
 
     #[test]
     fn web_search_is_generic() {
-        let t = super::web::web_search::WebSearchTool { api_key: None };
+        let t = super::web::web_search::WebSearchTool::default();
         assert_eq!(t.output_category(), OutputCategory::Generic);
     }
 
@@ -39,6 +39,13 @@ impl Tool for ReadImageTool {
     fn default_policy(&self) -> ApprovalPolicy {
         ApprovalPolicy::Auto
     }
+    fn path_args(&self) -> &[&str] {
+        &["path"]
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
 
     async fn execute(&self, call: &ToolCall) -> ToolOutput {
         let path_str = match call.args.get("path").and_then(|v| v.as_str()) {
@@ -1,6 +1,7 @@
 // Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
 //
 // SPDX-License-Identifier: Apache-2.0
+use std::path::Path;
 use std::sync::OnceLock;
 
 use async_trait::async_trait;
@@ -48,8 +49,12 @@ impl Tool for GrepTool {
          pattern: full regex (escape literal braces: \\{\\}).\n\
          include: glob filter (*.rs, **/*.{ts,tsx}).\n\
          whole_project: true → auto-exclude .git/ target/ node_modules/ dist/ __pycache__/ *.lock\n\
-         case_sensitive: default true. limit: 100. context_lines: 0.\n\
-         output_mode: content (default, file:line:col:text) | files_with_matches | count\n\
+         case_sensitive: default true. limit: 100.\n\
+         before / after / context: lines of context around each match (like -B/-A/-C);\n\
+         context lines print as file-line-text, groups are separated by --.\n\
+         multiline: true → the pattern may span lines (use \\n), e.g. \"fn main\\(\\)\\s*\\{\\n\\s*let\".\n\
+         output_mode: content (default, file:line:col:text; file:line:text with context or multiline) \
+         | files_with_matches | count\n\
          Use files_with_matches for discovery, then read_file for details."
     }
 
@@ -86,12 +91,24 @@ impl Tool for GrepTool {
                     "enum": ["content", "files_with_matches", "count"],
                     "description": "Output format: content (default), files_with_matches, or count"
                 },
-                "context_lines": {
+                "context": {
                     "type": "integer",
-                    "description": "Lines of context before and after each match (default 0)"
+                    "description": "Lines of context before and after each match, like grep -C (default 0)"
+                },
+                "before": {
+                    "type": "integer",
+                    "description": "Lines of context before each match, like grep -B (at least context)"
+                },
+                "after": {
+                    "type": "integer",
+                    "description": "Lines of context after each match, like grep -A (at least context)"
+                },
+                "multiline": {
+                    "type": "boolean",
+                    "description": "Let the pattern match across line boundaries (default false)"
                 }
             },
-            "required": ["pattern", "path", "include", "case_sensitive", "limit", "output_mode", "context_lines"],
+            "required": ["pattern", "path", "include", "case_sensitive", "limit", "output_mode", "context", "before", "after", "multiline"],
             "additionalProperties": false
         })
     }
@@ -99,10 +116,17 @@ impl Tool for GrepTool {
     fn default_policy(&self) -> ApprovalPolicy {
         ApprovalPolicy::Auto
     }
+    fn path_args(&self) -> &[&str] {
+        &["path"]
+    }
     fn output_category(&self) -> OutputCategory {
         OutputCategory::MatchList
     }
 
+    fn read_only(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, call: &ToolCall) -> ToolOutput {
         let pattern = match require_str(call, "pattern") {
             Ok(p) => p.to_string(),
@@ -114,23 +138,27 @@ impl Tool for GrepTool {
         let case_sensitive = opt_bool(call, "case_sensitive").unwrap_or(true);
         let limit = opt_u64(call, "limit").unwrap_or(100) as usize;
         let output_mode = opt_str(call, "output_mode").unwrap_or("content");
-        let context_lines = opt_u64(call, "context_lines").unwrap_or(0) as usize;
-
-        debug!(pattern = %pattern, path = %path, output_mode = %output_mode, whole_project, "grep tool");
-
-        let result = run_rg(
-            &pattern,
-            &path,
-            include.as_deref(),
+        // `context_lines` is the older name of `context`.
+        let context = opt_u64(call, "context")
+            .or_else(|| opt_u64(call, "context_lines"))
+            .unwrap_or(0) as usize;
+        let query = GrepQuery {
+            pattern: &pattern,
+            path: &path,
+            include: include.as_deref(),
             whole_project,
             case_sensitive,
-            limit,
             output_mode,
-            context_lines,
-        )
-        .await;
+            // `before`/`after` only widen `context`: a model filling in every
+            // field with 0 must not cancel the context it asked for.
+            before: opt_u64(call, "before").map_or(context, |n| context.max(n as usize)),
+            after: opt_u64(call, "after").map_or(context, |n| context.max(n as usize)),
+            multiline: opt_bool(call, "multiline").unwrap_or(false),
+        };
 
-        match result {
+        debug!(pattern = %pattern, path = %path, output_mode = %output_mode, whole_project, multiline = query.multiline, "grep tool");
+
+        match run_grep(&query).await.map(|out| limit_lines(&out, limit)) {
             Ok(output) if output.trim().is_empty() => ToolOutput::ok(&call.id, "(no matches)"),
             Ok(output) => ToolOutput::ok(&call.id, output),
             Err(e) => ToolOutput::err(&call.id, format!("grep error: {e}")),
@@ -138,27 +166,64 @@ impl Tool for GrepTool {
     }
 }
 
-#[allow(clippy::too_many_arguments)]
-async fn run_rg(
-    pattern: &str,
-    path: &str,
-    include: Option<&str>,
+/// Directories skipped by `whole_project` and by `search_codebase`.
+pub(super) const PROJECT_EXCLUDES: &[&str] =
+    &[".git", "target", "node_modules", "dist", "__pycache__"];
+
+/// One grep request, with defaults already applied.
+struct GrepQuery<'a> {
+    pattern: &'a str,
+    path: &'a str,
+    include: Option<&'a str>,
     whole_project: bool,
     case_sensitive: bool,
-    limit: usize,
-    output_mode: &str,
-    context_lines: usize,
-) -> anyhow::Result<String> {
+    output_mode: &'a str,
+    before: usize,
+    after: usize,
+    multiline: bool,
+}
+
+impl GrepQuery<'_> {
+    fn is_content(&self) -> bool {
+        !matches!(self.output_mode, "files_with_matches" | "count")
+    }
+
+    /// Context or multiline output uses `file:line:text` lines (and
+    /// `file-line-text` for context) instead of vimgrep's `file:line:col:text`.
+    fn wants_grep_format(&self) -> bool {
+        self.before > 0 || self.after > 0 || self.multiline
+    }
+}
+
+/// Keep the first `limit` lines of `output`, noting how many were dropped.
+pub(super) fn limit_lines(output: &str, limit: usize) -> String {
+    let mut result = output.lines().take(limit).collect::<Vec<_>>().join("\n");
+    let total_lines = output.lines().count();
+    if total_lines > limit {
+        result.push_str(&format!(
+            "\n...[{} more matches not shown — narrow with path= or include= to see all results]",
+            total_lines - limit
+        ));
+    }
+    result
+}
+
+async fn run_grep(q: &GrepQuery<'_>) -> anyhow::Result<String> {
     let output = if has_rg().await {
         let mut args = vec!["--color".to_string(), "never".to_string()];
 
-        match output_mode {
+        match q.output_mode {
             "files_with_matches" => {
                 args.push("-l".to_string());
             }
             "count" => {
                 args.push("-c".to_string());
             }
+            _ if q.wants_grep_format() => {
+                args.push("--line-number".to_string());
+                args.push("--with-filename".to_string());
+                args.push("--no-heading".to_string());
+            }
             _ => {
                 // content mode: vimgrep format for unambiguous file:line:col:text output
                 args.push("--vimgrep".to_string());
@@ -166,44 +231,50 @@ async fn run_rg(
             }
         }
 
-        if !case_sensitive {
+        if !q.case_sensitive {
             args.push("--ignore-case".to_string());
         }
-        if context_lines > 0 && output_mode == "content" {
-            args.push(format!("-C{}", context_lines));
+        if q.multiline {
+            args.push("--multiline".to_string());
+        }
+        if q.is_content() {
+            if q.before > 0 {
+                args.push(format!("-B{}", q.before));
+            }
+            if q.after > 0 {
+                args.push(format!("-A{}", q.after));
+            }
         }
-        if whole_project {
-            for excl in &[
-                ".git/**",
-                "target/**",
-                "node_modules/**",
-                "dist/**",
-                "__pycache__/**",
-                "*.lock",
-            ] {
+        if q.whole_project {
+            for excl in PROJECT_EXCLUDES {
                 args.push("--glob".to_string());
-                args.push(format!("!{excl}"));
+                args.push(format!("!{excl}/**"));
             }
+            args.push("--glob".to_string());
+            args.push("!*.lock".to_string());
         }
-        if let Some(glob) = include {
+        if let Some(glob) = q.include {
             args.push("-g".to_string());
             args.push(glob.to_string());
         }
-        args.push(pattern.to_string());
-        args.push(path.to_string());
+        args.push(q.pattern.to_string());
+        args.push(q.path.to_string());
 
         tokio::process::Command::new("rg")
             .args(&args)
             .stdin(std::process::Stdio::null())
             .output()
             .await?
+    } else if q.multiline {
+        // grep cannot match across lines; search in-process instead.
+        return multiline_search(q);
     } else {
         // Fallback to the system grep (Unix/macOS).  On Windows, grep is not
         // available by default; users should install ripgrep (`winget install BurntSushi.ripgrep`).
         #[cfg(not(windows))]
         {
             let mut args = vec!["-ran".to_string()];
-            match output_mode {
+            match q.output_mode {
                 "files_with_matches" => {
                     args.push("-l".to_string());
                 }
@@ -212,24 +283,29 @@ async fn run_rg(
                 }
                 _ => {}
             }
-            if !case_sensitive {
+            if !q.case_sensitive {
                 args.push("-i".to_string());
             }
-            if context_lines > 0 && output_mode == "content" {
-                args.push(format!("-C{}", context_lines));
+            if q.is_content() {
+                if q.before > 0 {
+                    args.push(format!("-B{}", q.before));
+                }
+                if q.after > 0 {
+                    args.push(format!("-A{}", q.after));
+                }
             }
-            if whole_project {
-                for excl in &[".git", "target", "node_modules", "dist", "__pycache__"] {
+            if q.whole_project {
+                for excl in PROJECT_EXCLUDES {
                     args.push("--exclude-dir".to_string());
                     args.push(excl.to_string());
                 }
             }
-            if let Some(glob) = include {
+            if let Some(glob) = q.include {
                 args.push("--include".to_string());
                 args.push(glob.to_string());
             }
-            args.push(pattern.to_string());
-            args.push(path.to_string());
+            args.push(q.pattern.to_string());
+            args.push(q.path.to_string());
 
             tokio::process::Command::new("grep")
                 .args(&args)
@@ -242,15 +318,6 @@ async fn run_rg(
             // On Windows, grep is not available. Return an empty output so the
             // caller surfaces a "no matches / rg not found" message.
             // Users should install ripgrep: winget install BurntSushi.ripgrep
-            let _ = (
-                output_mode,
-                context_lines,
-                whole_project,
-                include,
-                case_sensitive,
-                pattern,
-                path,
-            );
             return Err(anyhow::anyhow!(
                 "ripgrep (rg) is required for search on Windows. \
                  Install it with: winget install BurntSushi.ripgrep"
@@ -258,17 +325,122 @@ async fn run_rg(
         }
     };
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let lines: Vec<&str> = stdout.lines().take(limit).collect();
-    let mut result = lines.join("\n");
-    let total_lines = stdout.lines().count();
-    if total_lines > limit {
-        result.push_str(&format!(
-            "\n...[{} more matches not shown — narrow with path= or include= to see all results]",
-            total_lines - limit
-        ));
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Multiline search without ripgrep, printing the same `file:line:text` /
+/// `file-line-text` / `--` format as `rg --multiline -n`.
+fn multiline_search(q: &GrepQuery<'_>) -> anyhow::Result<String> {
+    let re = regex::RegexBuilder::new(q.pattern)
+        .case_insensitive(!q.case_sensitive)
+        .multi_line(true)
+        .build()?;
+    let root = Path::new(q.path);
+    if !root.exists() {
+        anyhow::bail!("{}: No such file or directory", q.path);
+    }
+
+    let mut out: Vec<String> = Vec::new();
+    for entry in walkdir::WalkDir::new(root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| {
+            !(q.whole_project
+                && e.depth() > 0
+                && e.file_type().is_dir()
+                && PROJECT_EXCLUDES.contains(&e.file_name().to_string_lossy().as_ref()))
+        })
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+    {
+        let file = entry.path();
+        if !include_matches(q, root, file) {
+            continue;
+        }
+        // Unreadable and binary (non-UTF-8) files are skipped, like grep -I.
+        let Ok(text) = std::fs::read_to_string(file) else {
+            continue;
+        };
+        let name = file.display().to_string();
+        let spans = match_line_spans(&re, &text);
+        if spans.is_empty() {
+            continue;
+        }
+        match q.output_mode {
+            "files_with_matches" => out.push(name),
+            "count" => out.push(format!("{name}:{}", spans.len())),
+            _ => format_spans(&mut out, &name, &text, &spans, q.before, q.after),
+        }
+    }
+    Ok(out.join("\n"))
+}
+
+fn include_matches(q: &GrepQuery<'_>, root: &Path, file: &Path) -> bool {
+    if q.whole_project && file.extension().is_some_and(|ext| ext == "lock") {
+        return false;
+    }
+    let Some(glob) = q.include else {
+        return true;
+    };
+    // Like ripgrep, a glob without `/` is matched against the file name.
+    let target = if glob.contains('/') {
+        file.strip_prefix(root)
+            .unwrap_or(file)
+            .to_string_lossy()
+            .into_owned()
+    } else {
+        file.file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+    crate::builtin::file::find_file::glob_matches(glob, &target, false)
+}
+
+/// 0-based `(first, last)` line of every non-empty match of `re` in `text`.
+fn match_line_spans(re: &regex::Regex, text: &str) -> Vec<(usize, usize)> {
+    let line_of = |offset: usize| text[..offset].matches('\n').count();
+    re.find_iter(text)
+        .filter(|m| !m.is_empty())
+        .map(|m| {
+            // A match ending in a newline does not extend onto the next line.
+            let last = m.end() - 1 - usize::from(m.as_str().ends_with('\n'));
+            (line_of(m.start()), line_of(last.max(m.start())))
+        })
+        .collect()
+}
+
+/// Append the matched lines of one file, with `before`/`after` lines of
+/// context, merging overlapping groups and separating the rest with `--`.
+fn format_spans(
+    out: &mut Vec<String>,
+    name: &str,
+    text: &str,
+    spans: &[(usize, usize)],
+    before: usize,
+    after: usize,
+) {
+    let lines: Vec<&str> = text.lines().collect();
+    let last_line = lines.len().saturating_sub(1);
+    let mut matched = vec![false; lines.len()];
+    let mut shown = vec![false; lines.len()];
+    for &(first, last) in spans {
+        let last = last.min(last_line);
+        matched[first..=last].iter_mut().for_each(|m| *m = true);
+        let from = first.saturating_sub(before);
+        let to = (last + after).min(last_line);
+        shown[from..=to].iter_mut().for_each(|s| *s = true);
+    }
+    let context = before > 0 || after > 0;
+    let mut prev: Option<usize> = None;
+    for (i, line) in lines.iter().enumerate().filter(|(i, _)| shown[*i]) {
+        let gap = prev.map_or(!out.is_empty(), |p| i > p + 1);
+        if context && gap {
+            out.push("--".to_string());
+        }
+        let sep = if matched[i] { ':' } else { '-' };
+        out.push(format!("{name}{sep}{}{sep}{line}", i + 1));
+        prev = Some(i);
     }
-    Ok(result)
 }
 
 impl ToolDisplay for GrepTool {
@@ -389,4 +561,102 @@ mod tests {
             out.content
         );
     }
+
+    #[tokio::test]
+    async fn context_lines_surround_each_match() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "a\nb\nneedle one\nc\nd\ne\nf\nneedle two\ng\n").unwrap();
+
+        let out = GrepTool
+            .execute(&call(json!({
+                "pattern": "needle",
+                "path": path.to_str().unwrap(),
+                "before": 1,
+                "after": 1
+            })))
+            .await;
+        assert!(!out.is_error, "{}", out.content);
+        let lines: Vec<&str> = out.content.lines().collect();
+        assert_eq!(lines.len(), 7, "{}", out.content);
+        assert!(lines[0].ends_with("2-b"), "{}", out.content);
+        assert!(lines[1].ends_with("3:needle one"), "{}", out.content);
+        assert!(lines[2].ends_with("4-c"), "{}", out.content);
+        assert_eq!(lines[3], "--");
+        assert!(lines[4].ends_with("7-f"), "{}", out.content);
+        assert!(lines[6].ends_with("9-g"), "{}", out.content);
+    }
+
+    #[tokio::test]
+    async fn zero_before_and_after_keep_the_context() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(
+            &path,
+            "a
+b
+c
+needle
+d
+e
+f
+",
+        )
+        .unwrap();
+
+        let out = GrepTool
+            .execute(&call(json!({
+                "pattern": "needle",
+                "path": path.to_str().unwrap(),
+                "context": 3,
+                "before": 0,
+                "after": 0
+            })))
+            .await;
+        assert!(!out.is_error, "{}", out.content);
+        let lines: Vec<&str> = out.content.lines().collect();
+        assert_eq!(lines.len(), 7, "{}", out.content);
+        assert!(lines[0].ends_with("1-a"), "{}", out.content);
+        assert!(lines[6].ends_with("7-f"), "{}", out.content);
+    }
+
+    #[tokio::test]
+    async fn multiline_pattern_spans_two_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("main.rs"),
+            "fn main() {\n    let x = 1;\n}\nfn other() {}\n",
+        )
+        .unwrap();
+
+        let out = GrepTool
+            .execute(&call(json!({
+                "pattern": "fn main\\(\\) \\{\\n\\s*let",
+                "path": dir.path().to_str().unwrap(),
+                "multiline": true
+            })))
+            .await;
+        assert!(!out.is_error, "{}", out.content);
+        let lines: Vec<&str> = out.content.lines().collect();
+        assert_eq!(lines.len(), 2, "{}", out.content);
+        assert!(
+            lines[0].ends_with("main.rs:1:fn main() {"),
+            "{}",
+            out.content
+        );
+        assert!(
+            lines[1].ends_with("main.rs:2:    let x = 1;"),
+            "{}",
+            out.content
+        );
+
+        // Without multiline the pattern cannot cross the newline.
+        let out = GrepTool
+            .execute(&call(json!({
+                "pattern": "fn main\\(\\) \\{\\n\\s*let",
+                "path": dir.path().to_str().unwrap()
+            })))
+            .await;
+        assert!(out.content.contains("no matches"), "{}", out.content);
+    }
 }
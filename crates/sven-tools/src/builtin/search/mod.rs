@@ -6,6 +6,7 @@
 pub mod grep;
 pub mod search_codebase;
 pub mod search_knowledge;
+pub mod symbols;
 
 pub use grep::GrepTool;
 pub use search_codebase::SearchCodebaseTool;
@@ -1,6 +1,8 @@
 // Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
 //
 // SPDX-License-Identifier: Apache-2.0
+use std::path::Path;
+
 use async_trait::async_trait;
 use serde_json::{json, Value};
 use tracing::debug;
@@ -9,8 +11,15 @@ use crate::params::{opt_bool, opt_str, opt_u64, require_str};
 use crate::policy::ApprovalPolicy;
 use crate::tool::{OutputCategory, Tool, ToolCall, ToolDisplay, ToolOutput};
 
+use super::grep::{limit_lines, PROJECT_EXCLUDES};
+use super::symbols::{self, Symbol};
+
 /// Thin wrapper over `grep` / ripgrep with sensible codebase defaults:
 /// always excludes .git/, target/, node_modules/, dist/, __pycache__/.
+///
+/// `mode: "symbols"` instead matches `query` against the names of
+/// function/type/class definitions parsed with tree-sitter (when built with
+/// the `tree-sitter` feature), falling back to text search otherwise.
 pub struct SearchCodebaseTool;
 
 #[async_trait]
@@ -23,9 +32,12 @@ impl Tool for SearchCodebaseTool {
         "Ripgrep across the codebase with standard exclusions: \
          .git/ target/ node_modules/ dist/ __pycache__/ *.lock\n\
          Same regex syntax as grep. Use for broad whole-repo exploration.\n\
-         Use grep (not this) when you need output_mode, context_lines, or targeted search.\n\
+         Use grep (not this) when you need output_mode, context lines, multiline patterns, or targeted search.\n\
          Use glob when searching by filename. query: regex. include: glob file filter. \
-         case_sensitive: true. limit: 100."
+         case_sensitive: true. limit: 100.\n\
+         mode: text (default) | symbols — symbols matches query against function/struct/class \
+         definition names and returns file:start-end: signature; use it to find where X is defined. \
+         Falls back to text search when no definition matches or no parser is available."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -51,9 +63,14 @@ impl Tool for SearchCodebaseTool {
                 "limit": {
                     "type": "integer",
                     "description": "Maximum number of matches to return (default 100)"
+                },
+                "mode": {
+                    "type": "string",
+                    "enum": ["text", "symbols"],
+                    "description": "text (default): regex over file contents; symbols: regex over definition names"
                 }
             },
-            "required": ["query", "path", "include", "case_sensitive", "limit"],
+            "required": ["query", "path", "include", "case_sensitive", "limit", "mode"],
             "additionalProperties": false
         })
     }
@@ -61,10 +78,17 @@ impl Tool for SearchCodebaseTool {
     fn default_policy(&self) -> ApprovalPolicy {
         ApprovalPolicy::Auto
     }
+    fn path_args(&self) -> &[&str] {
+        &["path"]
+    }
     fn output_category(&self) -> OutputCategory {
         OutputCategory::MatchList
     }
 
+    fn read_only(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, call: &ToolCall) -> ToolOutput {
         let query = match require_str(call, "query") {
             Ok(q) => q.to_string(),
@@ -75,101 +99,51 @@ impl Tool for SearchCodebaseTool {
         let case_sensitive = opt_bool(call, "case_sensitive").unwrap_or(true);
         let limit = opt_u64(call, "limit").unwrap_or(100) as usize;
 
-        debug!(query = %query, path = %path, "search_codebase tool");
+        let mode = opt_str(call, "mode").unwrap_or("text");
 
-        // Detect rg availability by probing its --version flag (cross-platform;
-        // avoids `which` which is Unix-only and `where` which is Windows-only).
-        let has_rg = tokio::process::Command::new("rg")
-            .arg("--version")
-            .stdin(std::process::Stdio::null())
-            .output()
-            .await
-            .map(|o| o.status.success())
-            .unwrap_or(false);
-
-        let output = if has_rg {
-            let mut args = vec![
-                "--vimgrep".to_string(),
-                "--color".to_string(),
-                "never".to_string(),
-                "--no-heading".to_string(),
-                // Exclude build artifacts
-                "--glob".to_string(),
-                "!.git/**".to_string(),
-                "--glob".to_string(),
-                "!target/**".to_string(),
-                "--glob".to_string(),
-                "!node_modules/**".to_string(),
-                "--glob".to_string(),
-                "!dist/**".to_string(),
-                "--glob".to_string(),
-                "!__pycache__/**".to_string(),
-                "--glob".to_string(),
-                "!*.lock".to_string(),
-            ];
-            if !case_sensitive {
-                args.push("--ignore-case".to_string());
-            }
-            if let Some(glob) = &include {
-                args.push("-g".to_string());
-                args.push(glob.clone());
-            }
-            args.push(query.clone());
-            args.push(path.clone());
+        debug!(query = %query, path = %path, mode = %mode, "search_codebase tool");
 
-            tokio::process::Command::new("rg")
-                .args(&args)
-                .stdin(std::process::Stdio::null())
-                .output()
+        let mut note = None;
+        if mode == "symbols" {
+            if symbols::available() {
+                let (q, p, inc) = (query.clone(), path.clone(), include.clone());
+                let found = tokio::task::spawn_blocking(move || {
+                    symbol_search(&q, Path::new(&p), inc.as_deref(), case_sensitive)
+                })
                 .await
-        } else {
-            // Fallback to system grep on Unix/macOS.  On Windows, ripgrep is
-            // required: install with `winget install BurntSushi.ripgrep`.
-            #[cfg(not(windows))]
-            {
-                let mut cmd_parts = vec!["grep -rn".to_string()];
-                if !case_sensitive {
-                    cmd_parts.push("-i".to_string());
-                }
-                cmd_parts.push("--exclude-dir=.git --exclude-dir=target --exclude-dir=node_modules --exclude-dir=dist".to_string());
-                if let Some(glob) = &include {
-                    cmd_parts.push(format!("--include={glob}"));
+                .map_err(anyhow::Error::from)
+                .and_then(|r| r);
+                match found {
+                    Ok(lines) if !lines.is_empty() => {
+                        return ToolOutput::ok(&call.id, limit_lines(&lines.join("\n"), limit));
+                    }
+                    Ok(_) => {
+                        note = Some(format!(
+                            "(no definitions named /{query}/; showing text matches)"
+                        ));
+                    }
+                    Err(e) => {
+                        return ToolOutput::err(&call.id, format!("search_codebase error: {e}"))
+                    }
                 }
-                cmd_parts.push(shell_escape(&query));
-                cmd_parts.push(shell_escape(&path));
-
-                tokio::process::Command::new("sh")
-                    .arg("-c")
-                    .arg(cmd_parts.join(" "))
-                    .stdin(std::process::Stdio::null())
-                    .output()
-                    .await
-            }
-            #[cfg(windows)]
-            {
-                let _ = (case_sensitive, &include, &query, &path);
-                return ToolOutput::err(
-                    &call.id,
-                    "ripgrep (rg) is required for search_codebase on Windows. \
-                     Install it with: winget install BurntSushi.ripgrep",
+            } else {
+                note = Some(
+                    "(symbol search is not built in (tree-sitter feature); showing text matches)"
+                        .to_string(),
                 );
             }
-        };
+        }
 
-        match output {
-            Ok(out) => {
-                let text = String::from_utf8_lossy(&out.stdout);
-                let lines: Vec<&str> = text.lines().take(limit).collect();
-                if lines.is_empty() {
-                    ToolOutput::ok(&call.id, "(no matches)")
+        match text_search(&query, &path, include.as_deref(), case_sensitive).await {
+            Ok(text) => {
+                let result = if text.lines().next().is_none() {
+                    "(no matches)".to_string()
                 } else {
-                    let total = text.lines().count();
-                    let mut result = lines.join("\n");
-                    if total > limit {
-                        result
-                            .push_str(&format!("\n...[{} more matches not shown]", total - limit));
-                    }
-                    ToolOutput::ok(&call.id, result)
+                    limit_lines(&text, limit)
+                };
+                match note {
+                    Some(note) => ToolOutput::ok(&call.id, format!("{note}\n{result}")),
+                    None => ToolOutput::ok(&call.id, result),
                 }
             }
             Err(e) => ToolOutput::err(&call.id, format!("search_codebase error: {e}")),
@@ -177,6 +151,147 @@ impl Tool for SearchCodebaseTool {
     }
 }
 
+/// Run ripgrep (or grep) for `query` and return its raw output.
+async fn text_search(
+    query: &str,
+    path: &str,
+    include: Option<&str>,
+    case_sensitive: bool,
+) -> anyhow::Result<String> {
+    // Detect rg availability by probing its --version flag (cross-platform;
+    // avoids `which` which is Unix-only and `where` which is Windows-only).
+    let has_rg = tokio::process::Command::new("rg")
+        .arg("--version")
+        .stdin(std::process::Stdio::null())
+        .output()
+        .await
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+
+    let output = if has_rg {
+        let mut args = vec![
+            "--vimgrep".to_string(),
+            "--color".to_string(),
+            "never".to_string(),
+            "--no-heading".to_string(),
+        ];
+        // Exclude build artifacts
+        for dir in PROJECT_EXCLUDES {
+            args.push("--glob".to_string());
+            args.push(format!("!{dir}/**"));
+        }
+        args.push("--glob".to_string());
+        args.push("!*.lock".to_string());
+        if !case_sensitive {
+            args.push("--ignore-case".to_string());
+        }
+        if let Some(glob) = include {
+            args.push("-g".to_string());
+            args.push(glob.to_string());
+        }
+        args.push(query.to_string());
+        args.push(path.to_string());
+
+        tokio::process::Command::new("rg")
+            .args(&args)
+            .stdin(std::process::Stdio::null())
+            .output()
+            .await?
+    } else {
+        // Fallback to system grep on Unix/macOS.  On Windows, ripgrep is
+        // required: install with `winget install BurntSushi.ripgrep`.
+        #[cfg(not(windows))]
+        {
+            let mut cmd_parts = vec!["grep -rn".to_string()];
+            if !case_sensitive {
+                cmd_parts.push("-i".to_string());
+            }
+            cmd_parts.push("--exclude-dir=.git --exclude-dir=target --exclude-dir=node_modules --exclude-dir=dist".to_string());
+            if let Some(glob) = include {
+                cmd_parts.push(format!("--include={glob}"));
+            }
+            cmd_parts.push(shell_escape(query));
+            cmd_parts.push(shell_escape(path));
+
+            tokio::process::Command::new("sh")
+                .arg("-c")
+                .arg(cmd_parts.join(" "))
+                .stdin(std::process::Stdio::null())
+                .output()
+                .await?
+        }
+        #[cfg(windows)]
+        {
+            let _ = (case_sensitive, include, query, path);
+            anyhow::bail!(
+                "ripgrep (rg) is required for search_codebase on Windows. \
+                 Install it with: winget install BurntSushi.ripgrep"
+            );
+        }
+    };
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Definitions under `root` whose name matches `query`, formatted as
+/// `file:start-end: signature`.
+fn symbol_search(
+    query: &str,
+    root: &Path,
+    include: Option<&str>,
+    case_sensitive: bool,
+) -> anyhow::Result<Vec<String>> {
+    let re = regex::RegexBuilder::new(query)
+        .case_insensitive(!case_sensitive)
+        .build()?;
+    let mut out = Vec::new();
+    for entry in walkdir::WalkDir::new(root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| {
+            !(e.depth() > 0
+                && e.file_type().is_dir()
+                && PROJECT_EXCLUDES.contains(&e.file_name().to_string_lossy().as_ref()))
+        })
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+    {
+        let file = entry.path();
+        if let Some(glob) = include {
+            let name = entry.file_name().to_string_lossy();
+            if !crate::builtin::file::find_file::glob_matches(glob, &name, false) {
+                continue;
+            }
+        }
+        if !symbols::supports(file) {
+            continue;
+        }
+        let Ok(source) = std::fs::read_to_string(file) else {
+            continue;
+        };
+        let Some(found) = symbols::extract_symbols(file, &source) else {
+            continue;
+        };
+        out.extend(
+            found
+                .iter()
+                .filter(|s| re.is_match(&s.name))
+                .map(|s| format_symbol(file, s)),
+        );
+    }
+    Ok(out)
+}
+
+fn format_symbol(file: &Path, s: &Symbol) -> String {
+    format!(
+        "{}:{}-{}: {}",
+        file.display(),
+        s.start_line,
+        s.end_line,
+        s.signature
+    )
+}
+
 fn shell_escape(s: &str) -> String {
     format!("'{}'", s.replace('\'', "'\\''"))
 }
@@ -271,4 +386,38 @@ mod tests {
             "expected case-insensitive match for TOOLREGISTRY"
         );
     }
+
+    #[tokio::test]
+    async fn symbol_mode_finds_definitions_or_falls_back_to_text() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("lib.rs"),
+            "pub fn parse_config(raw: &str) -> u8 {\n    0\n}\n\nfn run() {\n    parse_config(\"x\");\n}\n",
+        )
+        .unwrap();
+
+        let out = SearchCodebaseTool
+            .execute(&call(json!({
+                "query": "^parse_config$",
+                "path": dir.path().to_str().unwrap(),
+                "mode": "symbols"
+            })))
+            .await;
+        assert!(!out.is_error, "{}", out.content);
+        if symbols::available() {
+            assert!(
+                out.content
+                    .ends_with("lib.rs:1-3: pub fn parse_config(raw: &str) -> u8"),
+                "{}",
+                out.content
+            );
+            assert_eq!(out.content.lines().count(), 1, "{}", out.content);
+        } else {
+            assert!(
+                out.content.contains("showing text matches"),
+                "{}",
+                out.content
+            );
+        }
+    }
 }
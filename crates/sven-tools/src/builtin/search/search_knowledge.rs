@@ -6,7 +6,7 @@
 use async_trait::async_trait;
 use serde_json::{json, Value};
 use sven_config::AgentMode;
-use sven_runtime::SharedKnowledge;
+use sven_runtime::{KnowledgeInfo, SharedKnowledge};
 
 use crate::policy::ApprovalPolicy;
 use crate::tool::{OutputCategory, Tool, ToolCall, ToolOutput};
@@ -17,12 +17,16 @@ const CONTEXT_LINES: usize = 3;
 const MAX_MATCHES_PER_DOC: usize = 5;
 /// Maximum number of documents shown in results.
 const MAX_DOCS_IN_RESULTS: usize = 8;
+/// Share of the score given to the query appearing in the subsystem name or tags.
+const TITLE_WEIGHT: f64 = 0.2;
 
 /// Search project knowledge documents with keyword/substring matching.
 ///
 /// Searches the body of every `.sven/knowledge/*.md` file (skipping YAML
-/// frontmatter) for the given query string.  Results are sorted by match
-/// count descending so the most relevant document appears first.
+/// frontmatter) for the given query string.  Each matching document gets a
+/// relevance score in `0.0..=1.0` (see [`relevance`]); results are sorted by
+/// score descending so the most relevant document appears first, and
+/// `min_score` drops weak matches.
 pub struct SearchKnowledgeTool {
     pub knowledge: SharedKnowledge,
 }
@@ -35,7 +39,8 @@ impl Tool for SearchKnowledgeTool {
 
     fn description(&self) -> &str {
         "Search project knowledge documents with a keyword query.\n\
-         Returns: matching excerpts (with context lines) sorted by relevance.\n\
+         Returns: matching excerpts (with context lines) per document, each with a relevance\n\
+         score (0-1) and its source path, sorted by score. min_score drops weaker documents.\n\
          Use before modifying a subsystem to retrieve architecture notes,\n\
          correctness invariants, and known failure-mode tables.\n\
          Use `list_knowledge` to see all available documents."
@@ -48,6 +53,10 @@ impl Tool for SearchKnowledgeTool {
                 "query": {
                     "type": "string",
                     "description": "Keyword or phrase to search for (case-insensitive substring match)"
+                },
+                "min_score": {
+                    "type": "number",
+                    "description": "Drop documents scoring below this relevance, 0.0-1.0 (default 0)"
                 }
             },
             "required": ["query"],
@@ -67,11 +76,23 @@ impl Tool for SearchKnowledgeTool {
         OutputCategory::MatchList
     }
 
+    fn read_only(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, call: &ToolCall) -> ToolOutput {
         let query = match call.args.get("query").and_then(|v| v.as_str()) {
             Some(q) if !q.trim().is_empty() => q.trim().to_string(),
             _ => return ToolOutput::err(&call.id, "missing or empty 'query'"),
         };
+        let min_score = call
+            .args
+            .get("min_score")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0);
+        if !(0.0..=1.0).contains(&min_score) {
+            return ToolOutput::err(&call.id, "min_score must be between 0 and 1");
+        }
 
         let docs = self.knowledge.get();
 
@@ -85,8 +106,8 @@ impl Tool for SearchKnowledgeTool {
 
         let query_lower = query.to_lowercase();
 
-        // Search each doc and collect (match_count, formatted_result).
-        let mut results: Vec<(usize, String)> = docs
+        // Search each doc and collect (score, subsystem, match_count, formatted_result).
+        let mut results: Vec<(f64, &str, usize, String)> = docs
             .iter()
             .filter_map(|doc| {
                 // Count actual matching lines (not excerpts, which merge close matches).
@@ -98,6 +119,7 @@ impl Tool for SearchKnowledgeTool {
                 if match_count == 0 {
                     return None;
                 }
+                let score = relevance(doc, &query_lower, match_count);
                 let excerpts =
                     extract_excerpts(&doc.body, &query_lower, CONTEXT_LINES, MAX_MATCHES_PER_DOC);
                 if excerpts.is_empty() {
@@ -114,19 +136,37 @@ impl Tool for SearchKnowledgeTool {
                     .map(|d| format!(" (updated {d})"))
                     .unwrap_or_default();
 
-                let mut block = vec![format!(
-                    "### {} — `{}`{}  [{} match(es)]",
-                    doc.subsystem, filename, updated_hint, match_count
-                )];
+                let mut block = vec![
+                    format!(
+                        "### {} — `{}`{}  [score {score:.2}, {} match(es)]",
+                        doc.subsystem, filename, updated_hint, match_count
+                    ),
+                    format!("source: {}", doc.path.display()),
+                ];
                 for excerpt in &excerpts {
                     block.push(String::new());
                     block.push(excerpt.clone());
                 }
 
-                Some((match_count, block.join("\n")))
+                Some((score, doc.subsystem.as_str(), match_count, block.join("\n")))
             })
             .collect();
 
+        // Sort by descending score so the most relevant doc comes first; ties
+        // by subsystem name keep the order reproducible.
+        results.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+        let best = results.first().map(|r| r.0);
+        results.retain(|r| r.0 >= min_score);
+
+        if let (true, Some(best)) = (results.is_empty(), best) {
+            return ToolOutput::ok(
+                &call.id,
+                format!(
+                    "No matches for `{query}` with score >= {min_score:.2} \
+                     (best was {best:.2}). Lower min_score to see weaker matches."
+                ),
+            );
+        }
         if results.is_empty() {
             return ToolOutput::ok(
                 &call.id,
@@ -138,12 +178,10 @@ impl Tool for SearchKnowledgeTool {
             );
         }
 
-        // Sort by descending match count so most relevant doc comes first.
-        results.sort_by(|a, b| b.0.cmp(&a.0));
         results.truncate(MAX_DOCS_IN_RESULTS);
 
         let total_docs_searched = docs.len();
-        let total_matches: usize = results.iter().map(|(c, _)| c).sum();
+        let total_matches: usize = results.iter().map(|r| r.2).sum();
         let header = format!(
             "## Knowledge Search: `{query}`\n\
              Found {total_matches} match(es) in {} of {total_docs_searched} document(s):\n",
@@ -151,7 +189,7 @@ impl Tool for SearchKnowledgeTool {
         );
 
         let mut lines = vec![header];
-        lines.extend(results.into_iter().map(|(_, block)| block));
+        lines.extend(results.into_iter().map(|r| r.3));
         lines.push(String::new());
         lines.push(
             "Use `read_file <path>` to load the full document, or `list_knowledge` to see all docs."
@@ -162,6 +200,21 @@ impl Tool for SearchKnowledgeTool {
     }
 }
 
+/// Relevance of `doc` to `query_lower`, in `0.0..=1.0`.
+///
+/// Each matching body line halves the distance to a full body score, so one
+/// match scores 0.4, two 0.6 and four 0.75; the query also appearing in the
+/// subsystem name or a tag adds [`TITLE_WEIGHT`].  Scores are absolute, not
+/// relative to the other documents, so `min_score` means the same thing for
+/// every query.
+fn relevance(doc: &KnowledgeInfo, query_lower: &str, match_count: usize) -> f64 {
+    let body = 1.0 - 0.5f64.powi(match_count.min(64) as i32);
+    let in_title = std::iter::once(&doc.subsystem)
+        .chain(&doc.tags)
+        .any(|t| t.to_lowercase().contains(query_lower));
+    (1.0 - TITLE_WEIGHT) * body + if in_title { TITLE_WEIGHT } else { 0.0 }
+}
+
 /// Extract matching excerpts from `body` for the given `query_lower`.
 ///
 /// Returns up to `max_matches` excerpts, each consisting of `context_lines`
@@ -217,7 +270,6 @@ mod tests {
     use super::*;
     use crate::tool::{Tool, ToolCall};
     use serde_json::json;
-    use sven_runtime::KnowledgeInfo;
 
     fn call(query: &str) -> ToolCall {
         ToolCall {
@@ -231,6 +283,7 @@ mod tests {
         KnowledgeInfo {
             subsystem: subsystem.to_string(),
             files: vec![],
+            tags: vec![],
             updated: None,
             path: std::path::PathBuf::from(format!(
                 ".sven/knowledge/{}.md",
@@ -325,6 +378,80 @@ mod tests {
         );
     }
 
+    fn scores(content: &str) -> Vec<f64> {
+        content
+            .lines()
+            .filter_map(|l| l.split_once("[score ")?.1.split_once(',')?.0.parse().ok())
+            .collect()
+    }
+
+    fn scored_fixture() -> SearchKnowledgeTool {
+        let mut relay = make_doc("Relay", "relay once");
+        relay.tags = vec!["net".into()];
+        SearchKnowledgeTool {
+            knowledge: SharedKnowledge::new(vec![
+                make_doc("Alpha", "one relay mention"),
+                make_doc("Beta", "relay\nrelay\nrelay\nrelay"),
+                relay,
+                make_doc("Gamma", "relay and\nanother relay"),
+            ]),
+        }
+    }
+
+    #[tokio::test]
+    async fn results_carry_scores_and_sources_sorted_descending() {
+        let out = scored_fixture().execute(&call("relay")).await;
+        assert!(!out.is_error, "{}", out.content);
+        let scores = scores(&out.content);
+        assert_eq!(scores, [0.75, 0.6, 0.6, 0.4], "{}", out.content);
+        assert!(scores.windows(2).all(|w| w[0] >= w[1]));
+        // Equal scores are ordered by subsystem name; the title match lifts Relay.
+        let order: Vec<usize> = ["Beta", "Gamma", "Relay", "Alpha"]
+            .iter()
+            .map(|s| out.content.find(&format!("### {s} ")).unwrap())
+            .collect();
+        assert!(order.windows(2).all(|w| w[0] < w[1]), "{}", out.content);
+        assert!(
+            out.content.contains("source: .sven/knowledge/beta.md"),
+            "{}",
+            out.content
+        );
+    }
+
+    #[tokio::test]
+    async fn min_score_drops_weak_matches() {
+        let t = scored_fixture();
+        let out = t
+            .execute(&ToolCall {
+                id: "sk2".into(),
+                name: "search_knowledge".into(),
+                args: json!({ "query": "relay", "min_score": 0.6 }),
+            })
+            .await;
+        assert!(!out.is_error, "{}", out.content);
+        assert_eq!(scores(&out.content), [0.75, 0.6, 0.6]);
+        assert!(!out.content.contains("### Alpha"), "{}", out.content);
+
+        let out = t
+            .execute(&ToolCall {
+                id: "sk3".into(),
+                name: "search_knowledge".into(),
+                args: json!({ "query": "relay", "min_score": 0.9 }),
+            })
+            .await;
+        assert!(!out.is_error);
+        assert!(out.content.contains("best was 0.75"), "{}", out.content);
+
+        let out = t
+            .execute(&ToolCall {
+                id: "sk4".into(),
+                name: "search_knowledge".into(),
+                args: json!({ "query": "relay", "min_score": 2 }),
+            })
+            .await;
+        assert!(out.is_error);
+    }
+
     #[test]
     fn extract_excerpts_returns_context_window() {
         let body = "a\nb\nc\nmatch line\nd\ne\nf";
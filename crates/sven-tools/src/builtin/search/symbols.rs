@@ -0,0 +1,327 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! Definition extraction for `search_codebase` symbol mode.
+//!
+//! With the `tree-sitter` feature, source files in a supported language are
+//! parsed and their function, type and class definitions are returned with
+//! signature and line range.  Without the feature (or for other languages)
+//! [`extract_symbols`] returns `None` and the caller falls back to text search.
+
+use std::path::Path;
+
+/// A definition found in a source file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    /// Short kind label: `fn`, `struct`, `enum`, `trait`, `impl`, `class`, …
+    pub kind: &'static str,
+    pub name: String,
+    /// Declaration up to the body, on one line.
+    pub signature: String,
+    /// 1-based first line.
+    pub start_line: usize,
+    /// 1-based last line (inclusive).
+    pub end_line: usize,
+}
+
+/// Whether symbol extraction was compiled in.
+pub const fn available() -> bool {
+    cfg!(feature = "tree-sitter")
+}
+
+/// Whether a grammar is available for `path`'s extension, so callers can
+/// skip reading files [`extract_symbols`] would reject.
+pub fn supports(path: &Path) -> bool {
+    #[cfg(feature = "tree-sitter")]
+    {
+        path.extension()
+            .and_then(|e| e.to_str())
+            .and_then(Lang::from_extension)
+            .is_some()
+    }
+    #[cfg(not(feature = "tree-sitter"))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+/// Extract the definitions in `source`, choosing the grammar from `path`'s
+/// extension.  Returns `None` when no grammar is available for the file.
+pub fn extract_symbols(path: &Path, source: &str) -> Option<Vec<Symbol>> {
+    let ext = path.extension()?.to_str()?;
+    #[cfg(feature = "tree-sitter")]
+    {
+        grammar::extract(Lang::from_extension(ext)?, source)
+    }
+    #[cfg(not(feature = "tree-sitter"))]
+    {
+        let _ = (ext, source);
+        None
+    }
+}
+
+#[cfg(feature = "tree-sitter")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lang {
+    Rust,
+    Python,
+    Go,
+    C,
+    JavaScript,
+    TypeScript,
+    Tsx,
+}
+
+#[cfg(feature = "tree-sitter")]
+impl Lang {
+    fn from_extension(ext: &str) -> Option<Self> {
+        Some(match ext {
+            "rs" => Self::Rust,
+            "py" | "pyi" => Self::Python,
+            "go" => Self::Go,
+            "c" | "h" => Self::C,
+            "js" | "mjs" | "cjs" | "jsx" => Self::JavaScript,
+            "ts" | "mts" | "cts" => Self::TypeScript,
+            "tsx" => Self::Tsx,
+            _ => return None,
+        })
+    }
+
+    fn language(self) -> tree_sitter::Language {
+        match self {
+            Self::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Self::Python => tree_sitter_python::LANGUAGE.into(),
+            Self::Go => tree_sitter_go::LANGUAGE.into(),
+            Self::C => tree_sitter_c::LANGUAGE.into(),
+            Self::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Self::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Self::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+        }
+    }
+
+    /// Symbol kind for a definition node, or `None` for other nodes.
+    fn kind_of(self, node_kind: &str) -> Option<&'static str> {
+        Some(match (self, node_kind) {
+            (Self::Rust, "function_item" | "function_signature_item") => "fn",
+            (Self::Rust, "struct_item") => "struct",
+            (Self::Rust, "enum_item") => "enum",
+            (Self::Rust, "union_item") => "union",
+            (Self::Rust, "trait_item") => "trait",
+            (Self::Rust, "impl_item") => "impl",
+            (Self::Rust, "type_item") => "type",
+            (Self::Rust, "mod_item") => "mod",
+            (Self::Rust, "macro_definition") => "macro",
+            (Self::Rust, "const_item") => "const",
+            (Self::Rust, "static_item") => "static",
+            (Self::Python, "function_definition") => "def",
+            (Self::Python, "class_definition") => "class",
+            (Self::Go, "function_declaration") => "func",
+            (Self::Go, "method_declaration") => "method",
+            (Self::Go, "type_spec") => "type",
+            (Self::C, "function_definition") => "fn",
+            (Self::C, "struct_specifier") => "struct",
+            (Self::C, "enum_specifier") => "enum",
+            (Self::C, "union_specifier") => "union",
+            (
+                Self::JavaScript | Self::TypeScript | Self::Tsx,
+                "function_declaration" | "generator_function_declaration",
+            ) => "function",
+            (Self::JavaScript | Self::TypeScript | Self::Tsx, "class_declaration") => "class",
+            (Self::JavaScript | Self::TypeScript | Self::Tsx, "method_definition") => "method",
+            (Self::TypeScript | Self::Tsx, "interface_declaration") => "interface",
+            (Self::TypeScript | Self::Tsx, "type_alias_declaration") => "type",
+            (Self::TypeScript | Self::Tsx, "enum_declaration") => "enum",
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(feature = "tree-sitter")]
+mod grammar {
+    use tree_sitter::{Node, Parser};
+
+    use super::{Lang, Symbol};
+
+    /// Longest signature kept, in characters.
+    pub(super) const MAX_SIGNATURE_CHARS: usize = 200;
+
+    pub(super) fn extract(lang: Lang, source: &str) -> Option<Vec<Symbol>> {
+        let mut parser = Parser::new();
+        parser.set_language(&lang.language()).ok()?;
+        let tree = parser.parse(source, None)?;
+        let mut symbols = Vec::new();
+        collect(lang, tree.root_node(), source, &mut symbols);
+        Some(symbols)
+    }
+
+    fn collect(lang: Lang, node: Node<'_>, source: &str, out: &mut Vec<Symbol>) {
+        if let Some(kind) = lang.kind_of(node.kind()) {
+            if let Some(name) = name_of(lang, node, source) {
+                out.push(Symbol {
+                    kind,
+                    name,
+                    signature: signature_of(node, source),
+                    start_line: node.start_position().row + 1,
+                    end_line: node.end_position().row + 1,
+                });
+            }
+        }
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            collect(lang, child, source, out);
+        }
+    }
+
+    fn name_of(lang: Lang, node: Node<'_>, source: &str) -> Option<String> {
+        let name = match (lang, node.kind()) {
+            (Lang::Rust, "impl_item") => {
+                // `impl Trait for Type` is named after both.
+                let ty = text(node.child_by_field_name("type")?, source);
+                return Some(match node.child_by_field_name("trait") {
+                    Some(tr) => format!("{} for {ty}", text(tr, source)),
+                    None => ty.to_string(),
+                });
+            }
+            (Lang::C, "function_definition") => {
+                // The name sits at the bottom of a chain of declarators
+                // (pointer, function, parenthesized).
+                let mut decl = node.child_by_field_name("declarator")?;
+                while let Some(inner) = decl.child_by_field_name("declarator") {
+                    decl = inner;
+                }
+                decl
+            }
+            // A struct/enum reference without a body is a use, not a definition.
+            (Lang::C, _) if node.child_by_field_name("body").is_none() => return None,
+            _ => node.child_by_field_name("name")?,
+        };
+        Some(text(name, source).to_string())
+    }
+
+    /// Text from the start of `node` to the start of its body, or its first
+    /// line when it has no body.
+    fn signature_of(node: Node<'_>, source: &str) -> String {
+        let start = node.start_byte();
+        let end = match node.child_by_field_name("body") {
+            Some(body) => body.start_byte(),
+            None => {
+                let rest = &source[start..node.end_byte()];
+                start + rest.find('\n').unwrap_or(rest.len())
+            }
+        };
+        let sig = source[start..end].trim_end();
+        one_line(sig.strip_suffix(':').unwrap_or(sig))
+    }
+
+    /// Collapse whitespace runs and cap the length of a signature.
+    pub(super) fn one_line(text: &str) -> String {
+        let mut sig = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if let Some((cut, _)) = sig.char_indices().nth(MAX_SIGNATURE_CHARS) {
+            sig.truncate(cut);
+            sig.push('…');
+        }
+        sig
+    }
+
+    fn text<'s>(node: Node<'_>, source: &'s str) -> &'s str {
+        &source[node.byte_range()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "tree-sitter")]
+    #[test]
+    fn one_line_collapses_whitespace_and_caps_length() {
+        use grammar::{one_line, MAX_SIGNATURE_CHARS};
+        assert_eq!(one_line("fn  a(\n    x: u8,\n)"), "fn a( x: u8, )");
+        let long = one_line(&"x".repeat(500));
+        assert_eq!(long.chars().count(), MAX_SIGNATURE_CHARS + 1);
+        assert!(long.ends_with('…'));
+    }
+
+    #[test]
+    fn unknown_extension_has_no_grammar() {
+        assert_eq!(
+            extract_symbols(Path::new("notes.txt"), "fn main() {}"),
+            None
+        );
+        assert_eq!(extract_symbols(Path::new("Makefile"), "all:"), None);
+        assert!(!supports(Path::new("notes.txt")));
+        assert!(!supports(Path::new("Makefile")));
+        assert_eq!(supports(Path::new("geo.rs")), available());
+    }
+
+    #[cfg(feature = "tree-sitter")]
+    const RUST_SAMPLE: &str = "\
+/// A point.
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+}
+
+pub fn distance(a: &Point,
+                b: &Point) -> f64 {
+    let dx = (a.x - b.x) as f64;
+    let dy = (a.y - b.y) as f64;
+    (dx * dx + dy * dy).sqrt()
+}
+
+struct Unit;
+";
+
+    #[cfg(feature = "tree-sitter")]
+    #[test]
+    fn extracts_rust_functions_and_structs() {
+        let symbols = extract_symbols(Path::new("geo.rs"), RUST_SAMPLE).unwrap();
+        let summary: Vec<_> = symbols
+            .iter()
+            .map(|s| (s.kind, s.name.as_str(), s.start_line, s.end_line))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("struct", "Point", 2, 5),
+                ("impl", "Point", 7, 11),
+                ("fn", "new", 8, 10),
+                ("fn", "distance", 13, 18),
+                ("struct", "Unit", 20, 20),
+            ]
+        );
+        assert_eq!(symbols[0].signature, "pub struct Point");
+        assert_eq!(symbols[2].signature, "pub fn new(x: i32, y: i32) -> Self");
+        assert_eq!(
+            symbols[3].signature,
+            "pub fn distance(a: &Point, b: &Point) -> f64"
+        );
+        assert_eq!(symbols[4].signature, "struct Unit;");
+    }
+
+    #[cfg(feature = "tree-sitter")]
+    #[test]
+    fn extracts_python_defs_and_classes() {
+        let source = "class Greeter:\n    def hello(self, name):\n        return name\n";
+        let symbols = extract_symbols(Path::new("greet.py"), source).unwrap();
+        let names: Vec<_> = symbols.iter().map(|s| (s.kind, s.name.as_str())).collect();
+        assert_eq!(names, [("class", "Greeter"), ("def", "hello")]);
+        assert_eq!(symbols[1].signature, "def hello(self, name)");
+    }
+
+    #[cfg(feature = "tree-sitter")]
+    #[test]
+    fn c_prototypes_are_not_definitions() {
+        let source = "struct s;\nint add(int a, int b);\nstatic int *twice(int a) { return 0; }\n";
+        let symbols = extract_symbols(Path::new("m.c"), source).unwrap();
+        let names: Vec<_> = symbols.iter().map(|s| (s.kind, s.name.as_str())).collect();
+        assert_eq!(names, [("fn", "twice")]);
+    }
+}
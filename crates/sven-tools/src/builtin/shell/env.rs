@@ -0,0 +1,189 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! Environment passed to commands run by the shell tools.
+
+use std::ffi::OsString;
+
+use sven_config::ToolsConfig;
+use tokio::process::Command;
+
+/// Variables every scrubbed environment keeps: enough to find programs and
+/// to pick a locale.
+#[cfg(not(windows))]
+const BASE_ALLOWLIST: &[&str] = &["PATH", "HOME", "LANG"];
+
+/// On Windows `cmd.exe` additionally needs its own location and the system
+/// directories to start at all.
+#[cfg(windows)]
+const BASE_ALLOWLIST: &[&str] = &[
+    "PATH",
+    "HOME",
+    "LANG",
+    "SystemRoot",
+    "ComSpec",
+    "PATHEXT",
+    "TEMP",
+    "TMP",
+];
+
+/// Environment a spawned command runs with.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CommandEnv {
+    /// The full environment of the sven process.
+    #[default]
+    Inherit,
+    /// Only the base variables (`PATH`, `HOME`, `LANG`) and the listed extras.
+    AllowList(Vec<String>),
+}
+
+impl CommandEnv {
+    /// Environment selected by `tools.inherit_env` and `tools.env_allowlist`.
+    /// An unset `inherit_env` inherits.
+    pub fn from_config(cfg: &ToolsConfig) -> Self {
+        if cfg.inherit_env.unwrap_or(true) {
+            Self::Inherit
+        } else {
+            Self::AllowList(cfg.env_allowlist.clone())
+        }
+    }
+
+    /// Whether the variable `name` reaches the command.
+    pub fn allows(&self, name: &str) -> bool {
+        match self {
+            Self::Inherit => true,
+            Self::AllowList(extra) => BASE_ALLOWLIST
+                .iter()
+                .copied()
+                .chain(extra.iter().map(String::as_str))
+                .any(|allowed| same_name(allowed, name)),
+        }
+    }
+
+    /// Restrict `cmd` to the allowed variables of the current process
+    /// environment.  A no-op for [`CommandEnv::Inherit`].
+    pub(crate) fn apply(&self, cmd: &mut Command) {
+        self.apply_from(cmd, std::env::vars_os());
+    }
+
+    /// [`Self::apply`] with `vars` standing in for the process environment.
+    fn apply_from(&self, cmd: &mut Command, vars: impl IntoIterator<Item = (OsString, OsString)>) {
+        if *self == Self::Inherit {
+            return;
+        }
+        cmd.env_clear();
+        for (name, value) in vars {
+            if name.to_str().is_some_and(|n| self.allows(n)) {
+                cmd.env(name, value);
+            }
+        }
+    }
+}
+
+/// Whether two variable names are the same.  Windows variable names are
+/// case-insensitive and reported with varying case (`Path`, `windir`).
+#[cfg(windows)]
+fn same_name(a: &str, b: &str) -> bool {
+    a.eq_ignore_ascii_case(b)
+}
+
+#[cfg(not(windows))]
+fn same_name(a: &str, b: &str) -> bool {
+    a == b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unset_inherit_env_inherits() {
+        assert_eq!(
+            CommandEnv::from_config(&ToolsConfig::default()),
+            CommandEnv::Inherit
+        );
+    }
+
+    #[test]
+    fn allowlist_keeps_base_and_configured_variables() {
+        let cfg = ToolsConfig {
+            inherit_env: Some(false),
+            env_allowlist: vec!["CARGO_HOME".into()],
+            ..ToolsConfig::default()
+        };
+        let env = CommandEnv::from_config(&cfg);
+        assert!(env.allows("PATH"));
+        assert!(env.allows("HOME"));
+        assert!(env.allows("CARGO_HOME"));
+        assert!(!env.allows("OPENAI_API_KEY"));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn windows_names_match_in_any_case() {
+        let env = CommandEnv::AllowList(vec!["CARGO_HOME".into()]);
+        assert!(env.allows("Path"));
+        assert!(env.allows("SYSTEMROOT"));
+        assert!(env.allows("cargo_home"));
+        assert!(!env.allows("OPENAI_API_KEY"));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn unix_names_are_case_sensitive() {
+        let env = CommandEnv::AllowList(vec!["CARGO_HOME".into()]);
+        assert!(!env.allows("Path"));
+        assert!(!env.allows("cargo_home"));
+    }
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(OsString, OsString)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (OsString::from(k), OsString::from(v)))
+            .collect()
+    }
+
+    /// The variables `cmd` sets explicitly, sorted by name.
+    fn explicit_env(cmd: &Command) -> Vec<(String, Option<String>)> {
+        let mut env: Vec<_> = cmd
+            .as_std()
+            .get_envs()
+            .map(|(k, v)| {
+                (
+                    k.to_string_lossy().into_owned(),
+                    v.map(|v| v.to_string_lossy().into_owned()),
+                )
+            })
+            .collect();
+        env.sort();
+        env
+    }
+
+    #[test]
+    fn allowlist_passes_only_allowed_variables() {
+        let env = CommandEnv::AllowList(vec!["SVEN_ALLOWED".into()]);
+        let mut cmd = Command::new("true");
+        env.apply_from(
+            &mut cmd,
+            vars(&[
+                ("PATH", "/bin"),
+                ("SVEN_ALLOWED", "visible"),
+                ("SVEN_HIDDEN", "leaked"),
+            ]),
+        );
+        assert_eq!(
+            explicit_env(&cmd),
+            [
+                ("PATH".to_string(), Some("/bin".to_string())),
+                ("SVEN_ALLOWED".to_string(), Some("visible".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn inherit_leaves_the_command_untouched() {
+        let mut cmd = Command::new("true");
+        CommandEnv::Inherit.apply_from(&mut cmd, vars(&[("SVEN_HIDDEN", "x")]));
+        assert!(explicit_env(&cmd).is_empty());
+    }
+}
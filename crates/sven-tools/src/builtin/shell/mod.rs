@@ -3,6 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 //! Shell execution tool.
 
+mod env;
+mod secrets;
 mod tool;
+pub use env::CommandEnv;
+pub use secrets::{SecretMasker, MASK};
 pub(crate) use tool::head_tail_truncate;
 pub use tool::ShellTool;
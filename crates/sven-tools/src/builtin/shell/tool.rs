@@ -9,6 +9,10 @@ use std::process::Stdio;
 use tokio::process::Command;
 use tracing::debug;
 
+use sven_config::ToolsConfig;
+
+use super::env::CommandEnv;
+use super::secrets::SecretMasker;
 use crate::policy::ApprovalPolicy;
 use crate::tool::{OutputCategory, Tool, ToolCall, ToolDisplay, ToolOutput};
 
@@ -27,11 +31,31 @@ const TAIL_LINES: usize = 100;
 /// Built-in tool that runs a shell command.
 pub struct ShellTool {
     pub timeout_secs: u64,
+    /// Applied to the output before it is returned.
+    pub secrets: SecretMasker,
+    /// Environment the command runs with.
+    pub env: CommandEnv,
 }
 
 impl Default for ShellTool {
     fn default() -> Self {
-        Self { timeout_secs: 30 }
+        Self {
+            timeout_secs: 30,
+            secrets: SecretMasker::default(),
+            env: CommandEnv::Inherit,
+        }
+    }
+}
+
+impl ShellTool {
+    /// The tool as `tools` configures it: timeout, secret masking and
+    /// environment.
+    pub fn from_config(tools: &ToolsConfig) -> Self {
+        Self {
+            timeout_secs: tools.timeouts.get_or("shell", tools.timeout_secs),
+            secrets: SecretMasker::new(&tools.secrets),
+            env: CommandEnv::from_config(tools),
+        }
     }
 }
 
@@ -87,6 +111,9 @@ impl Tool for ShellTool {
     fn default_policy(&self) -> ApprovalPolicy {
         ApprovalPolicy::Ask
     }
+    fn path_args(&self) -> &[&str] {
+        &["workdir"]
+    }
     fn output_category(&self) -> OutputCategory {
         OutputCategory::HeadTail
     }
@@ -162,6 +189,7 @@ impl Tool for ShellTool {
         if let Some(wd) = &workdir {
             cmd.current_dir(wd);
         }
+        self.env.apply(&mut cmd);
 
         let result =
             tokio::time::timeout(std::time::Duration::from_secs(timeout), cmd.output()).await;
@@ -185,6 +213,7 @@ impl Tool for ShellTool {
                 if content.is_empty() {
                     content = format!("[exit {}]", output.status.code().unwrap_or(-1));
                 }
+                let content = self.secrets.mask(&content).into_owned();
 
                 let code = output.status.code().unwrap_or(-1);
                 if code == 0 {
@@ -375,7 +404,10 @@ mod tests {
     #[cfg(unix)]
     #[tokio::test]
     async fn timeout_returns_error() {
-        let t = ShellTool { timeout_secs: 1 };
+        let t = ShellTool {
+            timeout_secs: 1,
+            ..Default::default()
+        };
         let out = t
             .execute(&call(
                 "1",
@@ -488,7 +520,10 @@ mod adversarial_tests {
 
     #[tokio::test]
     async fn very_long_command_string_does_not_crash() {
-        let t = ShellTool { timeout_secs: 5 };
+        let t = ShellTool {
+            timeout_secs: 5,
+            ..Default::default()
+        };
         let long_cmd = format!("echo {}", "A".repeat(1_000_000));
         let out = t.execute(&call(json!({"shell_command": long_cmd}))).await;
         // Must complete without panic; output may be truncated
@@ -497,7 +532,10 @@ mod adversarial_tests {
 
     #[tokio::test]
     async fn command_with_shell_metacharacters_does_not_crash() {
-        let t = ShellTool { timeout_secs: 5 };
+        let t = ShellTool {
+            timeout_secs: 5,
+            ..Default::default()
+        };
         // The shell_command runs under sh -c; these special chars must not cause
         // a panic in the Rust layer even if sh reports an error.
         let out = t
@@ -510,7 +548,10 @@ mod adversarial_tests {
 
     #[tokio::test]
     async fn command_producing_large_output_is_truncated_not_oom() {
-        let t = ShellTool { timeout_secs: 10 };
+        let t = ShellTool {
+            timeout_secs: 10,
+            ..Default::default()
+        };
         // Generate ~500 KB of output; the truncation logic must keep memory bounded.
         let out = t
             .execute(&call(json!({"shell_command": "yes | head -c 512000"})))
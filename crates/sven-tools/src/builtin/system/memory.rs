@@ -65,6 +65,9 @@ impl Tool for MemoryTool {
         "Persistent memory and project knowledge access.\n\
          action: set | get | delete | list | search_knowledge | list_knowledge\n\n\
          KV memory (set/get/delete/list) persists across sessions in ~/.config/sven/memory.json.\n\
+         set mode: replace (default) overwrites the value; append adds value on a new line;\n\
+         upsert_section treats the value as markdown and replaces the body of the `## <section>`\n\
+         heading (added at the end if absent), leaving other sections untouched.\n\
          At session start: call action=list to check stored project context.\n\
          Store: project conventions, toolchain quirks, recurring solutions.\n\n\
          Knowledge (search_knowledge/list_knowledge) searches .sven/knowledge/ docs.\n\
@@ -88,6 +91,15 @@ impl Tool for MemoryTool {
                     "type": "string",
                     "description": "[action=set] Value to store"
                 },
+                "mode": {
+                    "type": "string",
+                    "enum": ["replace", "append", "upsert_section"],
+                    "description": "[action=set] How value is combined with the stored one (default replace)"
+                },
+                "section": {
+                    "type": "string",
+                    "description": "[action=set, mode=upsert_section] Section heading, e.g. 'Decisions' for '## Decisions'"
+                },
                 "query": {
                     "type": "string",
                     "description": "[action=search_knowledge] Keyword or phrase to search for"
@@ -126,10 +138,46 @@ impl Tool for MemoryTool {
                     Some(v) => v.to_string(),
                     None => return ToolOutput::err(&call.id, "missing 'value' for set"),
                 };
+                let mode = call
+                    .args
+                    .get("mode")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("replace");
                 let mut store = load_store(&path).await;
-                store.insert(key.clone(), value);
+                let old = store.remove(&key).unwrap_or_default();
+                let (new, done) = match mode {
+                    "replace" => (value, format!("set {key}")),
+                    "append" if old.is_empty() => (value, format!("appended to {key}")),
+                    "append" => (
+                        format!("{}\n{value}", old.trim_end_matches('\n')),
+                        format!("appended to {key}"),
+                    ),
+                    "upsert_section" => {
+                        let Some(section) = call.args.get("section").and_then(|v| v.as_str())
+                        else {
+                            return ToolOutput::err(
+                                &call.id,
+                                "missing 'section' for mode=upsert_section",
+                            );
+                        };
+                        let heading = section.trim_start_matches('#').trim();
+                        (
+                            upsert_section(&old, heading, &value),
+                            format!("updated section '## {heading}' of {key}"),
+                        )
+                    }
+                    other => {
+                        return ToolOutput::err(
+                            &call.id,
+                            format!(
+                                "unknown mode '{other}'. Valid: replace, append, upsert_section"
+                            ),
+                        )
+                    }
+                };
+                store.insert(key.clone(), new);
                 match save_store(&path, &store).await {
-                    Ok(_) => ToolOutput::ok(&call.id, format!("set {key}")),
+                    Ok(_) => ToolOutput::ok(&call.id, done),
                     Err(e) => ToolOutput::err(&call.id, format!("save error: {e}")),
                 }
             }
@@ -204,6 +252,56 @@ impl Tool for MemoryTool {
     }
 }
 
+/// Replace the body of the `## heading` section of the markdown `doc` with
+/// `body`, or append the section when `doc` has no such heading.
+///
+/// A section runs until the next `#` or `##` heading; `###` sub-headings and
+/// lines inside code fences belong to it.
+fn upsert_section(doc: &str, heading: &str, body: &str) -> String {
+    let lines: Vec<&str> = doc.lines().collect();
+    let mut in_fence = false;
+    let mut start = None;
+    let mut end = lines.len();
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        let level = trimmed.chars().take_while(|&c| c == '#').count();
+        if !(1..=2).contains(&level) || !trimmed[level..].starts_with(' ') {
+            continue;
+        }
+        if start.is_some() {
+            end = i;
+            break;
+        }
+        if level == 2 && trimmed[level..].trim() == heading {
+            start = Some(i);
+        }
+    }
+
+    let section = format!("## {heading}\n{}", body.trim_matches('\n'));
+    let mut out = match start {
+        Some(start) => {
+            let mut parts: Vec<String> = lines[..start].iter().map(|l| l.to_string()).collect();
+            parts.push(section);
+            if end < lines.len() {
+                parts.push(String::new());
+                parts.extend(lines[end..].iter().map(|l| l.to_string()));
+            }
+            parts.join("\n")
+        }
+        None if doc.trim().is_empty() => section,
+        None => format!("{}\n\n{section}", doc.trim_end()),
+    };
+    out.push('\n');
+    out
+}
+
 async fn load_store(path: &str) -> HashMap<String, String> {
     match tokio::fs::read_to_string(path).await {
         Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
@@ -348,4 +446,86 @@ mod tests {
         assert!(!out.is_error);
         assert!(out.content.contains("No knowledge documents found"));
     }
+
+    const NOTES: &str = "# Project notes\n\n## Decisions\n- use tokio\n\n### Rejected\n- async-std\n\n## TODO\n- write docs\n";
+
+    #[test]
+    fn upsert_section_replaces_only_the_matching_body() {
+        let out = upsert_section(NOTES, "Decisions", "- use tokio\n- use anyhow\n");
+        assert_eq!(
+            out,
+            "# Project notes\n\n## Decisions\n- use tokio\n- use anyhow\n\n## TODO\n- write docs\n"
+        );
+        // The last section runs to the end of the document.
+        let out = upsert_section(NOTES, "TODO", "- nothing");
+        assert!(out.ends_with("## TODO\n- nothing\n"), "{out}");
+        assert!(out.contains("### Rejected\n- async-std"), "{out}");
+    }
+
+    #[test]
+    fn upsert_section_appends_missing_section() {
+        let out = upsert_section(NOTES, "Build", "cargo build --release");
+        assert!(out.starts_with(NOTES.trim_end()), "{out}");
+        assert!(
+            out.ends_with("- write docs\n\n## Build\ncargo build --release\n"),
+            "{out}"
+        );
+        assert_eq!(upsert_section("", "TODO", "- a"), "## TODO\n- a\n");
+    }
+
+    #[test]
+    fn upsert_section_ignores_headings_in_code_fences() {
+        let doc = "## Snippets\n```md\n## TODO\n```\n";
+        let out = upsert_section(doc, "TODO", "- real");
+        assert!(out.starts_with(doc), "{out}");
+        assert!(out.ends_with("## TODO\n- real\n"), "{out}");
+    }
+
+    #[tokio::test]
+    async fn set_modes_replace_append_and_upsert_section() {
+        let t = make_tool();
+        let path = t.memory_path();
+        let get = || call(json!({"action": "get", "key": "notes"}));
+
+        t.execute(&call(
+            json!({"action": "set", "key": "notes", "value": NOTES}),
+        ))
+        .await;
+        let out = t
+            .execute(&call(json!({
+                "action": "set", "key": "notes", "mode": "upsert_section",
+                "section": "## TODO", "value": "- ship it"
+            })))
+            .await;
+        assert!(!out.is_error, "{}", out.content);
+        let stored = t.execute(&get()).await.content;
+        assert!(stored.contains("## Decisions\n- use tokio"), "{stored}");
+        assert!(stored.ends_with("## TODO\n- ship it\n"), "{stored}");
+
+        t.execute(&call(json!({
+            "action": "set", "key": "notes", "mode": "append", "value": "footer"
+        })))
+        .await;
+        let stored = t.execute(&get()).await.content;
+        assert!(stored.ends_with("- ship it\nfooter"), "{stored}");
+
+        t.execute(&call(json!({
+            "action": "set", "key": "notes", "mode": "replace", "value": "fresh"
+        })))
+        .await;
+        assert_eq!(t.execute(&get()).await.content, "fresh");
+
+        let out = t
+            .execute(&call(json!({
+                "action": "set", "key": "notes", "mode": "upsert_section", "value": "x"
+            })))
+            .await;
+        assert!(
+            out.is_error && out.content.contains("section"),
+            "{}",
+            out.content
+        );
+
+        let _ = std::fs::remove_file(&path);
+    }
 }
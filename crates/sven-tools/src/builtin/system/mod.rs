@@ -16,4 +16,4 @@ pub use memory::MemoryTool;
 pub use read_lints::ReadLintsTool;
 pub use skill::SkillTool;
 pub use system::SystemTool;
-pub use todo::TodoTool;
+pub use todo::{load_todos, TodoTool};
@@ -45,6 +45,9 @@ impl Tool for ReadLintsTool {
     fn default_policy(&self) -> ApprovalPolicy {
         ApprovalPolicy::Auto
     }
+    fn path_args(&self) -> &[&str] {
+        &["paths", "workdir"]
+    }
     fn output_category(&self) -> OutputCategory {
         OutputCategory::MatchList
     }
@@ -18,6 +18,12 @@
 //!   the model knows what resources are available without reading them all.
 //! - A compact navigation hint listing **direct child sub-skills** (name +
 //!   one-line description) when the skill has nested skill packages below it.
+//!
+//! Skills can declare prerequisites with `requires: [other-skill]` in their
+//! frontmatter.  `load` resolves them transitively and returns every
+//! prerequisite's content before the requested skill's, in dependency order
+//! (each skill once).  Cycles, missing prerequisites and chains deeper than
+//! [`MAX_REQUIRES_DEPTH`] are errors.
 
 use async_trait::async_trait;
 use regex::Regex;
@@ -32,6 +38,9 @@ use crate::tool::{Tool, ToolCall, ToolOutput};
 /// Maximum number of bundled file paths to list in the tool response.
 const MAX_BUNDLED_FILES: usize = 20;
 
+/// Maximum length of a `requires:` chain below the requested skill.
+pub const MAX_REQUIRES_DEPTH: usize = 4;
+
 /// Build the static description string for the tool, listing available skills.
 fn build_description(skills: &[SkillInfo]) -> String {
     if skills.is_empty() {
@@ -62,6 +71,8 @@ fn build_description(skills: &[SkillInfo]) -> String {
          Call this when the user's request matches a skill description.\n\
          Returns the skill's SKILL.md body, the absolute base directory, \
          and a listing of bundled files.\n\
+         Prerequisite skills named in its `requires:` frontmatter are loaded first \
+         (set dependencies=false to load only the named skill).\n\
          Pass the <command> value (e.g. \"sven\" or \"sven/plan\") as the `name` argument.\n\n\
          list: List available skills, optionally filtered by a regex against command/name/description.\n\
          Leave `regex` empty to list all skills.\n\n\
@@ -148,6 +159,11 @@ impl Tool for SkillTool {
                     "description": "[action=load] The command of the skill to load \
                                     (e.g. \"sven\" or \"sven/plan\")"
                 },
+                "dependencies": {
+                    "type": "boolean",
+                    "description": "[action=load] Also load the skills it requires, \
+                                    prerequisites first (default true)"
+                },
                 "regex": {
                     "type": "string",
                     "description": "[action=list] Optional regex to filter skills by command, \
@@ -191,80 +207,61 @@ impl SkillTool {
 
         let current_skills = self.skills.get();
 
-        let skill = match current_skills.iter().find(|s| s.command == command) {
-            Some(s) => s,
-            None => {
-                let available = current_skills
-                    .iter()
-                    .map(|s| s.command.as_str())
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                return ToolOutput::err(
-                    &call.id,
-                    format!(
-                        "skill \"{command}\" not found. Available skills: {}",
-                        if available.is_empty() {
-                            "(none)"
-                        } else {
-                            &available
-                        }
-                    ),
-                );
-            }
-        };
-
-        let mut bundled_files: Vec<String> = Vec::new();
-        collect_files_recursive(
-            &skill.skill_dir,
-            &mut bundled_files,
-            &skill.skill_md_path,
-            true,
-        );
-        bundled_files.sort();
-        bundled_files.truncate(MAX_BUNDLED_FILES);
-
-        let files_block = if bundled_files.is_empty() {
-            String::new()
-        } else {
-            let list = bundled_files
+        if !current_skills.iter().any(|s| s.command == command) {
+            let available = current_skills
                 .iter()
-                .map(|p| format!("<file>{p}</file>"))
+                .map(|s| s.command.as_str())
                 .collect::<Vec<_>>()
-                .join("\n");
-            format!("\n\n<skill_files>\n{list}\n</skill_files>")
-        };
+                .join(", ");
+            return ToolOutput::err(
+                &call.id,
+                format!(
+                    "skill \"{command}\" not found. Available skills: {}",
+                    if available.is_empty() {
+                        "(none)"
+                    } else {
+                        &available
+                    }
+                ),
+            );
+        }
 
-        let base_dir = skill.skill_dir.display().to_string();
-        let content = match load_skill_content_from_disk(&skill.skill_md_path) {
-            Some(body) => body,
-            None => {
-                return ToolOutput::err(
-                    &call.id,
-                    format!(
-                        "failed to load skill \"{command}\" from disk (file may be missing, \
-                         unreadable, oversized, or have invalid frontmatter)"
-                    ),
-                );
+        let with_deps = call
+            .args
+            .get("dependencies")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+        let order = if with_deps {
+            match resolve_requires(&command, &current_skills) {
+                Ok(order) => order,
+                Err(e) => return ToolOutput::err(&call.id, e),
             }
+        } else {
+            current_skills
+                .iter()
+                .filter(|s| s.command == command)
+                .collect()
         };
-        let content = content.trim_end();
-        let sub_skills_hint = build_sub_skills_hint(skill, &current_skills);
 
-        ToolOutput::ok(
-            &call.id,
-            format!(
-                "<skill_content command=\"{command}\" name=\"{name}\">\n\
-                 # Skill: {name}\n\n\
-                 {content}\n\n\
-                 Base directory: {base_dir}\n\
-                 Relative paths in this skill (scripts/, references/, assets/) \
-                 are relative to this base directory.\
-                 {files_block}\
-                 {sub_skills_hint}\n\
-                 </skill_content>",
-                name = skill.name
-            ),
-        )
+        let mut blocks = Vec::with_capacity(order.len());
+        for skill in &order {
+            match render_skill(skill, &current_skills, &command) {
+                Ok(block) => blocks.push(block),
+                Err(e) => return ToolOutput::err(&call.id, e),
+            }
+        }
+        if order.len() > 1 {
+            let deps = order[..order.len() - 1]
+                .iter()
+                .map(|s| s.command.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            blocks.insert(
+                0,
+                format!("Loaded \"{command}\" with its prerequisite skills first: {deps}"),
+            );
+        }
+        ToolOutput::ok(&call.id, blocks.join("\n\n"))
     }
 
     async fn exec_list(&self, call: &ToolCall) -> ToolOutput {
@@ -337,6 +334,105 @@ impl SkillTool {
     }
 }
 
+// ── Loading helpers ───────────────────────────────────────────────────────────
+
+/// Return `command` and everything it transitively `requires`, prerequisites
+/// first, each skill once.
+fn resolve_requires<'a>(command: &str, all: &'a [SkillInfo]) -> Result<Vec<&'a SkillInfo>, String> {
+    fn visit<'a>(
+        command: &str,
+        all: &'a [SkillInfo],
+        chain: &mut Vec<String>,
+        order: &mut Vec<&'a SkillInfo>,
+    ) -> Result<(), String> {
+        if order.iter().any(|s| s.command == command) {
+            return Ok(());
+        }
+        if chain.iter().any(|c| c == command) {
+            chain.push(command.to_string());
+            return Err(format!("skill dependency cycle: {}", chain.join(" -> ")));
+        }
+        let Some(skill) = all.iter().find(|s| s.command == command) else {
+            let parent = chain.last().map(String::as_str).unwrap_or("?");
+            return Err(format!(
+                "skill \"{parent}\" requires \"{command}\", which is not available"
+            ));
+        };
+        if chain.len() > MAX_REQUIRES_DEPTH {
+            return Err(format!(
+                "skill requirements nest deeper than {MAX_REQUIRES_DEPTH} levels: {} -> {command}",
+                chain.join(" -> ")
+            ));
+        }
+        chain.push(command.to_string());
+        for dep in &skill.requires {
+            visit(dep, all, chain, order)?;
+        }
+        chain.pop();
+        order.push(skill);
+        Ok(())
+    }
+
+    let mut order = Vec::new();
+    visit(command, all, &mut Vec::new(), &mut order)?;
+    Ok(order)
+}
+
+/// Render one skill as a `<skill_content>` block, re-reading its SKILL.md.
+/// `requested` is the command the model asked for; prerequisites are marked
+/// with `required_by`.
+fn render_skill(skill: &SkillInfo, all: &[SkillInfo], requested: &str) -> Result<String, String> {
+    let command = &skill.command;
+    let mut bundled_files: Vec<String> = Vec::new();
+    collect_files_recursive(
+        &skill.skill_dir,
+        &mut bundled_files,
+        &skill.skill_md_path,
+        true,
+    );
+    bundled_files.sort();
+    bundled_files.truncate(MAX_BUNDLED_FILES);
+
+    let files_block = if bundled_files.is_empty() {
+        String::new()
+    } else {
+        let list = bundled_files
+            .iter()
+            .map(|p| format!("<file>{p}</file>"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!("\n\n<skill_files>\n{list}\n</skill_files>")
+    };
+
+    let base_dir = skill.skill_dir.display().to_string();
+    let Some(content) = load_skill_content_from_disk(&skill.skill_md_path) else {
+        return Err(format!(
+            "failed to load skill \"{command}\" from disk (file may be missing, \
+             unreadable, oversized, or have invalid frontmatter)"
+        ));
+    };
+    let content = content.trim_end();
+    let sub_skills_hint = build_sub_skills_hint(skill, all);
+    let required_by = if command == requested {
+        String::new()
+    } else {
+        format!(" required_by=\"{requested}\"")
+    };
+
+    Ok(format!(
+        "<skill_content command=\"{command}\" name=\"{name}\"{required_by}>\n\
+         # Skill: {name}\n\n\
+         {content}\n\n\
+         Base directory: {base_dir}\n\
+         Relative paths in this skill (scripts/, references/, assets/) \
+         are relative to this base directory.\
+         {files_block}\
+         {sub_skills_hint}\n\
+         </skill_content>",
+        name = skill.name
+    ))
+}
+
 // ── File collection helpers ───────────────────────────────────────────────────
 
 /// Recursively collect file paths under `dir`, excluding `exclude_file`.
@@ -388,6 +484,7 @@ mod tests {
             skill_dir,
             content: content.to_string(),
             sven_meta: None,
+            requires: vec![],
         }
     }
 
@@ -415,6 +512,7 @@ mod tests {
             skill_dir,
             content: body.to_string(),
             sven_meta: None,
+            requires: vec![],
         }
     }
 
@@ -532,6 +630,94 @@ mod tests {
         assert!(out.content.contains("</skill_content>"));
     }
 
+    // ── requires ─────────────────────────────────────────────────────────────
+
+    fn with_requires(mut skill: SkillInfo, requires: &[&str]) -> SkillInfo {
+        skill.requires = requires.iter().map(|r| r.to_string()).collect();
+        skill
+    }
+
+    #[tokio::test]
+    async fn load_resolves_two_level_requires_in_dependency_order() {
+        let tmp = tempfile::tempdir().unwrap();
+        let release = with_requires(
+            make_skill_on_disk("release", "Release.", "RELEASE BODY", &tmp),
+            &["changelog", "git-basics"],
+        );
+        let changelog = with_requires(
+            make_skill_on_disk("changelog", "Changelog.", "CHANGELOG BODY", &tmp),
+            &["git-basics"],
+        );
+        let git = make_skill_on_disk("git-basics", "Git.", "GIT BODY", &tmp);
+        let tool = make_tool(vec![release, changelog, git]);
+
+        let out = tool.execute(&load_call("release")).await;
+        assert!(!out.is_error, "{}", out.content);
+        let pos = |s: &str| out.content.find(s).unwrap();
+        assert!(pos("GIT BODY") < pos("CHANGELOG BODY"), "{}", out.content);
+        assert!(
+            pos("CHANGELOG BODY") < pos("RELEASE BODY"),
+            "{}",
+            out.content
+        );
+        // The shared prerequisite is loaded once.
+        assert_eq!(out.content.matches("GIT BODY").count(), 1);
+        assert!(out.content.contains(
+            "<skill_content command=\"git-basics\" name=\"git-basics\" required_by=\"release\">"
+        ));
+        assert!(out.content.starts_with(
+            "Loaded \"release\" with its prerequisite skills first: git-basics, changelog"
+        ));
+
+        let call = ToolCall {
+            id: "t3".into(),
+            name: "skill".into(),
+            args: json!({ "action": "load", "name": "release", "dependencies": false }),
+        };
+        let out = tool.execute(&call).await;
+        assert!(!out.is_error, "{}", out.content);
+        assert!(!out.content.contains("GIT BODY"), "{}", out.content);
+    }
+
+    #[tokio::test]
+    async fn load_rejects_cyclic_requires() {
+        let tmp = tempfile::tempdir().unwrap();
+        let a = with_requires(make_skill_on_disk("a", "A.", "A", &tmp), &["b"]);
+        let b = with_requires(make_skill_on_disk("b", "B.", "B", &tmp), &["a"]);
+        let tool = make_tool(vec![a, b]);
+        let out = tool.execute(&load_call("a")).await;
+        assert!(out.is_error);
+        assert!(
+            out.content.contains("skill dependency cycle: a -> b -> a"),
+            "{}",
+            out.content
+        );
+    }
+
+    #[test]
+    fn resolve_requires_reports_missing_and_too_deep() {
+        let missing = vec![with_requires(make_skill("a", "A.", ""), &["ghost"])];
+        let err = resolve_requires("a", &missing).unwrap_err();
+        assert!(err.contains("\"a\" requires \"ghost\""), "{err}");
+
+        let chain: Vec<SkillInfo> = (0..=MAX_REQUIRES_DEPTH + 1)
+            .map(|i| {
+                with_requires(
+                    make_skill(&format!("s{i}"), "S.", ""),
+                    &[&format!("s{}", i + 1)],
+                )
+            })
+            .chain(std::iter::once(make_skill(
+                &format!("s{}", MAX_REQUIRES_DEPTH + 2),
+                "S.",
+                "",
+            )))
+            .collect();
+        let err = resolve_requires("s0", &chain).unwrap_err();
+        assert!(err.contains("deeper than"), "{err}");
+        assert!(resolve_requires("s2", &chain).is_ok());
+    }
+
     // ── list action ──────────────────────────────────────────────────────────
 
     #[tokio::test]
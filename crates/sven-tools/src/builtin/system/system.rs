@@ -4,7 +4,8 @@
 //! Compound `system` tool that consolidates agent self-modification capabilities.
 //!
 //! Actions:
-//! - `switch_mode`       — switch the agent's operating mode in any direction.
+//! - `switch_mode`       — switch the agent's operating mode, in any direction
+//!   unless `agent.mode_transitions` restricts it.
 //! - `switch_model`      — change the active LLM using an fzf-style fuzzy search string.
 //! - `add_mcp_server`    — add an MCP server to the nearest config file.
 //! - `remove_mcp_server` — remove an MCP server from the nearest config file.
@@ -17,7 +18,7 @@ use serde_json::{json, Value};
 use tokio::sync::{mpsc, Mutex};
 use tracing::debug;
 
-use sven_config::{AgentMode, McpOAuthConfig, McpServerConfig, McpTransport};
+use sven_config::{AgentMode, McpOAuthConfig, McpServerConfig, McpTransport, ModeTransitions};
 use sven_model::catalog::static_catalog;
 
 use crate::events::ToolEvent;
@@ -28,6 +29,8 @@ use crate::tool::{Tool, ToolCall, ToolOutput};
 pub struct SystemTool {
     current_mode: Arc<Mutex<AgentMode>>,
     event_tx: mpsc::Sender<ToolEvent>,
+    transitions: ModeTransitions,
+    modes: Vec<AgentMode>,
 }
 
 impl SystemTool {
@@ -35,20 +38,33 @@ impl SystemTool {
         Self {
             current_mode,
             event_tx,
+            transitions: ModeTransitions::default(),
+            modes: AgentMode::BUILTIN.to_vec(),
         }
     }
 
+    /// Offer `modes` to `switch_mode` (from [`sven_config::AgentConfig::all_modes`])
+    /// instead of the built-in modes only.
+    pub fn with_modes(mut self, modes: Vec<AgentMode>) -> Self {
+        self.modes = modes;
+        self
+    }
+
+    /// Restrict `switch_mode` to the transitions allowed by `transitions`
+    /// (from `agent.mode_transitions`).  Without this every switch is allowed.
+    pub fn with_mode_transitions(mut self, transitions: ModeTransitions) -> Self {
+        self.transitions = transitions;
+        self
+    }
+
     async fn exec_switch_mode(&self, call: &ToolCall) -> ToolOutput {
         let mode_str = match call.args.get("mode").and_then(|v| v.as_str()) {
             Some(m) => m.to_string(),
             None => return ToolOutput::err(&call.id, "missing 'mode' for action=switch_mode"),
         };
 
-        let target = match mode_str.as_str() {
-            "research" => AgentMode::Research,
-            "plan" => AgentMode::Plan,
-            "agent" => AgentMode::Agent,
-            other => return ToolOutput::err(&call.id, format!("unknown mode: {other}")),
+        let Some(target) = self.modes.iter().copied().find(|m| m.name() == mode_str) else {
+            return ToolOutput::err(&call.id, format!("unknown mode: {mode_str}"));
         };
 
         // Hold the lock for the entire check-then-write to avoid TOCTOU.
@@ -61,6 +77,27 @@ impl SystemTool {
             return ToolOutput::ok(&call.id, format!("already in {mode_str} mode"));
         }
 
+        if !self.transitions.allows(current, target) {
+            let allowed = self
+                .transitions
+                .targets(current, &self.modes)
+                .iter()
+                .map(|m| m.to_string())
+                .collect::<Vec<_>>();
+            return ToolOutput::err(
+                &call.id,
+                format!(
+                    "switching from {current} to {target} mode is not allowed by \
+                     agent.mode_transitions; allowed from {current}: {}",
+                    if allowed.is_empty() {
+                        "(none)".to_string()
+                    } else {
+                        allowed.join(", ")
+                    }
+                ),
+            );
+        }
+
         *mode_guard = target;
         // Release the lock before awaiting on the channel send.
         drop(mode_guard);
@@ -174,7 +211,9 @@ impl Tool for SystemTool {
     fn description(&self) -> &str {
         "Agent system controls: mode/model switching and MCP server management.\n\
          action: switch_mode | switch_model | add_mcp_server | remove_mcp_server\n\n\
-         switch_mode: Switch operating mode freely (research ↔ plan ↔ agent).\n\n\
+         switch_mode: Switch operating mode (research ↔ plan ↔ agent, or a custom mode \
+         from the configuration). \
+         The configuration may forbid some switches; a refused switch leaves the mode unchanged.\n\n\
          switch_model: Switch the active LLM (e.g. \"claude-opus\", \"gpt4o\").\n\n\
          add_mcp_server: Add an external MCP server. Writes to the nearest config file.\n\
            - stdio: provide command + args (e.g. npx -y @modelcontextprotocol/server-github)\n\
@@ -183,6 +222,7 @@ impl Tool for SystemTool {
     }
 
     fn parameters_schema(&self) -> Value {
+        let modes: Vec<&str> = self.modes.iter().map(AgentMode::name).collect();
         json!({
             "type": "object",
             "properties": {
@@ -193,7 +233,7 @@ impl Tool for SystemTool {
                 },
                 "mode": {
                     "type": "string",
-                    "enum": ["research", "plan", "agent"],
+                    "enum": modes,
                     "description": "[switch_mode] Target operating mode"
                 },
                 "model": {
@@ -233,7 +273,7 @@ impl Tool for SystemTool {
     }
 
     // Available in all modes: switch_model has no mode restriction, and
-    // switch_mode enforces the downgrade-only rule internally.
+    // switch_mode enforces agent.mode_transitions internally.
 
     async fn execute(&self, call: &ToolCall) -> ToolOutput {
         let action = match call.args.get("action").and_then(|v| v.as_str()) {
@@ -558,6 +598,29 @@ mod tests {
         matches!(event, ToolEvent::ModeChanged(AgentMode::Plan));
     }
 
+    #[tokio::test]
+    async fn switches_to_a_custom_mode() {
+        let reviewer = AgentMode::parse("system-tool-reviewer").unwrap();
+        let (tool, current, mut rx) = make_tool(AgentMode::Agent);
+        let mut modes = AgentMode::BUILTIN.to_vec();
+        modes.push(reviewer);
+        let tool = tool.with_modes(modes);
+        let modes = tool.parameters_schema()["properties"]["mode"]["enum"].clone();
+        assert!(modes
+            .as_array()
+            .unwrap()
+            .contains(&json!("system-tool-reviewer")));
+
+        let out = tool.execute(&mode_call("system-tool-reviewer")).await;
+        assert!(!out.is_error, "{}", out.content);
+        assert_eq!(*current.lock().await, reviewer);
+        assert!(matches!(rx.try_recv(), Ok(ToolEvent::ModeChanged(m)) if m == reviewer));
+
+        let out = tool.execute(&mode_call("undefined-mode")).await;
+        assert!(out.is_error);
+        assert!(out.content.contains("unknown mode"));
+    }
+
     #[tokio::test]
     async fn missing_mode_param_is_error() {
         let (tool, _current, _rx) = make_tool(AgentMode::Agent);
@@ -584,6 +647,36 @@ mod tests {
         assert!(out.content.contains("missing required parameter 'action'"));
     }
 
+    #[tokio::test]
+    async fn transition_matrix_allows_listed_switch() {
+        let (tool, current, _rx) = make_tool(AgentMode::Research);
+        let tool = tool.with_mode_transitions(ModeTransitions(
+            [(AgentMode::Research, vec![AgentMode::Plan])].into(),
+        ));
+        let out = tool.execute(&mode_call("plan")).await;
+        assert!(!out.is_error, "{}", out.content);
+        assert_eq!(*current.lock().await, AgentMode::Plan);
+    }
+
+    #[tokio::test]
+    async fn transition_matrix_refuses_escalation() {
+        let (tool, current, mut rx) = make_tool(AgentMode::Research);
+        let tool = tool.with_mode_transitions(ModeTransitions(
+            [(AgentMode::Research, vec![AgentMode::Plan])].into(),
+        ));
+        let out = tool.execute(&mode_call("agent")).await;
+        assert!(out.is_error);
+        assert!(
+            out.content
+                .contains("from research to agent mode is not allowed"),
+            "{}",
+            out.content
+        );
+        assert!(out.content.contains("allowed from research: plan"));
+        assert_eq!(*current.lock().await, AgentMode::Research);
+        assert!(rx.try_recv().is_err(), "no ModeChanged event");
+    }
+
     // ── switch_model tests ────────────────────────────────────────────────────
 
     #[tokio::test]
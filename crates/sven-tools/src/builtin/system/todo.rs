@@ -1,12 +1,19 @@
 // Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
 //
 // SPDX-License-Identifier: Apache-2.0
+//! Session todo list.
+//!
+//! The list lives in an `Arc<Mutex<Vec<TodoItem>>>` shared with the frontend.
+//! With [`TodoTool::with_persist_path`] every change is also written to a JSON
+//! file, which [`load_todos`] reads back at startup so a resumed session keeps
+//! its task list.
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use serde_json::{json, Value};
 use tokio::sync::{mpsc, Mutex};
-use tracing::debug;
+use tracing::{debug, warn};
 
 use crate::events::{TodoItem, TodoStatus, ToolEvent};
 use crate::policy::ApprovalPolicy;
@@ -15,11 +22,22 @@ use crate::tool::{Tool, ToolCall, ToolDisplay, ToolOutput};
 pub struct TodoTool {
     todos: Arc<Mutex<Vec<TodoItem>>>,
     event_tx: mpsc::Sender<ToolEvent>,
+    persist_path: Option<PathBuf>,
 }
 
 impl TodoTool {
     pub fn new(todos: Arc<Mutex<Vec<TodoItem>>>, event_tx: mpsc::Sender<ToolEvent>) -> Self {
-        Self { todos, event_tx }
+        Self {
+            todos,
+            event_tx,
+            persist_path: None,
+        }
+    }
+
+    /// Save the list to `path` after every change.
+    pub fn with_persist_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.persist_path = Some(path.into());
+        self
     }
 }
 
@@ -106,8 +124,7 @@ impl Tool for TodoTool {
                 debug!(count = todos.len(), "todo add");
                 let snapshot = todos.clone();
                 drop(todos);
-                self.emit(&snapshot).await;
-                ToolOutput::ok(&call.id, format_todos(&snapshot))
+                self.publish(call, &snapshot).await
             }
 
             "update" => {
@@ -138,8 +155,7 @@ impl Tool for TodoTool {
                 debug!(count = todos.len(), "todo update");
                 let snapshot = todos.clone();
                 drop(todos);
-                self.emit(&snapshot).await;
-                ToolOutput::ok(&call.id, format_todos(&snapshot))
+                self.publish(call, &snapshot).await
             }
 
             "set" => {
@@ -173,8 +189,7 @@ impl Tool for TodoTool {
                 }
                 debug!(count = items.len(), "todo set");
                 *self.todos.lock().await = items.clone();
-                self.emit(&items).await;
-                ToolOutput::ok(&call.id, format_todos(&items))
+                self.publish(call, &items).await
             }
 
             other => ToolOutput::err(
@@ -186,14 +201,50 @@ impl Tool for TodoTool {
 }
 
 impl TodoTool {
-    async fn emit(&self, items: &[TodoItem]) {
+    /// Notify the frontend of a changed list, save it when persistence is
+    /// enabled, and return the list as the tool result.
+    async fn publish(&self, call: &ToolCall, items: &[TodoItem]) -> ToolOutput {
         let _ = self
             .event_tx
             .send(ToolEvent::TodoUpdate(items.to_vec()))
             .await;
+        let mut out = format_todos(items);
+        if let Some(path) = &self.persist_path {
+            if let Err(e) = save_todos(path, items).await {
+                warn!(path = %path.display(), error = %e, "failed to save todos");
+                out.push_str(&format!(
+                    "\n\n(warning: todo list not saved to {}: {e})",
+                    path.display()
+                ));
+            }
+        }
+        ToolOutput::ok(&call.id, out)
+    }
+}
+
+/// Read a todo list saved by a [`TodoTool`] with persistence enabled.
+/// A missing file is an empty list.
+pub async fn load_todos(path: &Path) -> anyhow::Result<Vec<TodoItem>> {
+    match tokio::fs::read_to_string(path).await {
+        Ok(content) => Ok(serde_json::from_str(&content)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
     }
 }
 
+async fn save_todos(path: &Path, items: &[TodoItem]) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+    }
+    // Write-then-rename so a crash never leaves a truncated file behind.
+    let tmp = path.with_extension("json.tmp");
+    tokio::fs::write(&tmp, serde_json::to_string_pretty(items)?).await?;
+    tokio::fs::rename(&tmp, path).await?;
+    Ok(())
+}
+
 // ── Helpers ───────────────────────────────────────────────────────────────────
 
 fn parse_full_items(call: &ToolCall) -> Result<Vec<TodoItem>, String> {
@@ -508,4 +559,66 @@ mod tests {
         .await;
         assert!(matches!(rx.try_recv(), Ok(ToolEvent::TodoUpdate(_))));
     }
+
+    // ── persistence ───────────────────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn persisted_todos_round_trip_with_status() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".sven/todos.json");
+        let (tool, _todos, _rx) = make_tool();
+        let tool = tool.with_persist_path(&path);
+        tool.execute(&call(json!({
+            "action": "add",
+            "todos": [
+                {"id": "1", "content": "write parser", "status": "in_progress"},
+                {"id": "2", "content": "write tests", "status": "pending"},
+                {"id": "3", "content": "old idea", "status": "pending"}
+            ]
+        })))
+        .await;
+        let out = tool
+            .execute(&call(json!({
+                "action": "update",
+                "todos": [
+                    {"id": "1", "status": "completed"},
+                    {"id": "3", "status": "cancelled"}
+                ]
+            })))
+            .await;
+        assert!(!out.is_error, "{}", out.content);
+        assert!(!out.content.contains("warning"), "{}", out.content);
+
+        // A fresh session restores the list, including completion state.
+        let restored = load_todos(&path).await.unwrap();
+        let summary: Vec<_> = restored
+            .iter()
+            .map(|t| (t.id.as_str(), t.content.as_str(), t.status.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("1", "write parser", TodoStatus::Completed),
+                ("2", "write tests", TodoStatus::Pending),
+                ("3", "old idea", TodoStatus::Cancelled),
+            ]
+        );
+
+        let todos = Arc::new(Mutex::new(restored));
+        let (tx, _rx) = mpsc::channel(16);
+        let resumed = TodoTool::new(todos, tx).with_persist_path(&path);
+        let out = resumed.execute(&call(json!({ "action": "read" }))).await;
+        assert!(
+            out.content.contains("✓ [1] write parser"),
+            "{}",
+            out.content
+        );
+    }
+
+    #[tokio::test]
+    async fn load_todos_missing_file_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let todos = load_todos(&dir.path().join("none.json")).await.unwrap();
+        assert!(todos.is_empty());
+    }
 }
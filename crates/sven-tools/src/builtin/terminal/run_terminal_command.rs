@@ -9,19 +9,41 @@ use std::process::Stdio;
 use tokio::process::Command;
 use tracing::debug;
 
-use sven_config::AgentMode;
+use sven_config::{AgentMode, ToolsConfig};
 
-use crate::builtin::shell::head_tail_truncate;
+use crate::builtin::shell::{head_tail_truncate, CommandEnv, SecretMasker};
 use crate::policy::ApprovalPolicy;
 use crate::tool::{OutputCategory, Tool, ToolCall, ToolDisplay, ToolOutput};
 
 pub struct RunTerminalCommandTool {
     pub timeout_secs: u64,
+    /// Applied to the output before it is returned.
+    pub secrets: SecretMasker,
+    /// Environment the command runs with.
+    pub env: CommandEnv,
 }
 
 impl Default for RunTerminalCommandTool {
     fn default() -> Self {
-        Self { timeout_secs: 30 }
+        Self {
+            timeout_secs: 30,
+            secrets: SecretMasker::default(),
+            env: CommandEnv::Inherit,
+        }
+    }
+}
+
+impl RunTerminalCommandTool {
+    /// The tool as `tools` configures it: timeout, secret masking and
+    /// environment.
+    pub fn from_config(tools: &ToolsConfig) -> Self {
+        Self {
+            timeout_secs: tools
+                .timeouts
+                .get_or("run_terminal_command", tools.timeout_secs),
+            secrets: SecretMasker::new(&tools.secrets),
+            env: CommandEnv::from_config(tools),
+        }
     }
 }
 
@@ -80,6 +102,9 @@ impl Tool for RunTerminalCommandTool {
     fn default_policy(&self) -> ApprovalPolicy {
         ApprovalPolicy::Ask
     }
+    fn path_args(&self) -> &[&str] {
+        &["workdir"]
+    }
     fn output_category(&self) -> OutputCategory {
         OutputCategory::HeadTail
     }
@@ -138,6 +163,7 @@ impl Tool for RunTerminalCommandTool {
         if let Some(wd) = &workdir {
             cmd.current_dir(wd);
         }
+        self.env.apply(&mut cmd);
 
         let result =
             tokio::time::timeout(std::time::Duration::from_secs(timeout), cmd.output()).await;
@@ -161,6 +187,7 @@ impl Tool for RunTerminalCommandTool {
                 if content.is_empty() {
                     content = format!("[exit {}]", output.status.code().unwrap_or(-1));
                 }
+                let content = self.secrets.mask(&content).into_owned();
 
                 if output.status.success() {
                     ToolOutput::ok(&call.id, content)
@@ -238,7 +265,10 @@ mod tests {
 
     #[tokio::test]
     async fn timeout_returns_error() {
-        let t = RunTerminalCommandTool { timeout_secs: 1 };
+        let t = RunTerminalCommandTool {
+            timeout_secs: 1,
+            ..Default::default()
+        };
         let out = t
             .execute(&call(json!({"command": "sleep 60", "timeout_secs": 1})))
             .await;
@@ -246,6 +276,43 @@ mod tests {
         assert!(out.content.contains("timeout"));
     }
 
+    #[tokio::test]
+    async fn masks_api_keys_in_output() {
+        let t = RunTerminalCommandTool::default();
+        let out = t
+            .execute(&call(
+                json!({"command": "echo key=sk-test0123456789abcdefXYZ; echo plain text"}),
+            ))
+            .await;
+        assert!(!out.is_error);
+        assert!(out.content.contains("key=****"), "{}", out.content);
+        assert!(!out.content.contains("sk-test"), "{}", out.content);
+        assert!(out.content.contains("plain text"));
+    }
+
+    #[tokio::test]
+    async fn scrubbed_env_passes_only_allowlisted_variables() {
+        // Which variables pass is covered in `shell::env`; here only that the
+        // tool applies the scrubbed environment, without touching our own.
+        let t = RunTerminalCommandTool {
+            env: CommandEnv::AllowList(Vec::new()),
+            ..Default::default()
+        };
+        let out = t
+            .execute(&call(json!({"command": "env | cut -d= -f1"})))
+            .await;
+        assert!(!out.is_error, "{}", out.content);
+        let names: Vec<&str> = out.content.lines().collect();
+        assert!(names.contains(&"PATH"), "{}", out.content);
+        // Besides the base variables, only what the shell sets itself.
+        for name in names {
+            assert!(
+                ["PATH", "HOME", "LANG", "PWD", "OLDPWD", "SHLVL", "_"].contains(&name),
+                "{name} leaked into the scrubbed environment"
+            );
+        }
+    }
+
     #[test]
     fn only_available_in_agent_mode() {
         let t = RunTerminalCommandTool::default();
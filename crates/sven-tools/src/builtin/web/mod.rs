@@ -7,4 +7,4 @@ pub mod web_fetch;
 pub mod web_search;
 
 pub use web_fetch::WebFetchTool;
-pub use web_search::WebSearchTool;
+pub use web_search::{SearchBackend, SearchResult, WebSearchTool};
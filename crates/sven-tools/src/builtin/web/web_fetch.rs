@@ -12,7 +12,22 @@ use crate::tool::{Tool, ToolCall, ToolDisplay, ToolOutput};
 /// 20 K chars ≈ 5,000 tokens — fits comfortably within a 40 K-token context window.
 const DEFAULT_MAX_CHARS: usize = 20_000;
 
-pub struct WebFetchTool;
+pub struct WebFetchTool {
+    /// Request timeout in seconds.
+    pub timeout_secs: u64,
+}
+
+impl WebFetchTool {
+    pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+}
+
+impl Default for WebFetchTool {
+    fn default() -> Self {
+        Self {
+            timeout_secs: Self::DEFAULT_TIMEOUT_SECS,
+        }
+    }
+}
 
 #[async_trait]
 impl Tool for WebFetchTool {
@@ -21,9 +36,10 @@ impl Tool for WebFetchTool {
     }
 
     fn description(&self) -> &str {
-        "Fetch a URL and return content as readable text (HTML → markdown). Read-only.\n\
+        "Fetch a URL and return content as readable text (HTML → markdown, JSON pretty-printed). Read-only.\n\
          Valid http/https only. No auth, no binary, no localhost/private IPs.\n\
-         max_chars: 50,000 by default. For non-webpage URLs use run_terminal_command."
+         max_chars: 50,000 by default. raw: true returns the body without extraction.\n\
+         For non-webpage URLs use run_terminal_command."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -37,9 +53,13 @@ impl Tool for WebFetchTool {
                 "max_chars": {
                     "type": "integer",
                     "description": "Maximum characters to return (default 50000)"
+                },
+                "raw": {
+                    "type": "boolean",
+                    "description": "Return the body as received, without HTML/JSON extraction (default false)"
                 }
             },
-            "required": ["url", "max_chars"],
+            "required": ["url", "max_chars", "raw"],
             "additionalProperties": false
         })
     }
@@ -48,6 +68,10 @@ impl Tool for WebFetchTool {
         ApprovalPolicy::Auto
     }
 
+    fn read_only(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, call: &ToolCall) -> ToolOutput {
         let url = match call.args.get("url").and_then(|v| v.as_str()) {
             Some(u) => u.to_string(),
@@ -58,19 +82,29 @@ impl Tool for WebFetchTool {
             .get("max_chars")
             .and_then(|v| v.as_u64())
             .unwrap_or(DEFAULT_MAX_CHARS as u64) as usize;
+        let raw = call
+            .args
+            .get("raw")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
 
-        debug!(url = %url, "web_fetch tool");
+        debug!(url = %url, raw, "web_fetch tool");
 
-        match fetch_url(&url, max_chars).await {
+        match fetch_url(&url, max_chars, raw, self.timeout_secs).await {
             Ok(content) => ToolOutput::ok(&call.id, content),
             Err(e) => ToolOutput::err(&call.id, format!("fetch error: {e}")),
         }
     }
 }
 
-async fn fetch_url(url: &str, max_chars: usize) -> anyhow::Result<String> {
+async fn fetch_url(
+    url: &str,
+    max_chars: usize,
+    raw: bool,
+    timeout_secs: u64,
+) -> anyhow::Result<String> {
     let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(30))
+        .timeout(std::time::Duration::from_secs(timeout_secs))
         .redirect(reqwest::redirect::Policy::limited(3))
         .user_agent("sven-agent/0.1")
         .build()?;
@@ -84,9 +118,20 @@ async fn fetch_url(url: &str, max_chars: usize) -> anyhow::Result<String> {
         .to_lowercase();
 
     let body = response.text().await?;
+    let content = if raw {
+        body
+    } else {
+        extract(&content_type, body)
+    };
+    Ok(truncate(content, max_chars))
+}
 
-    let content = if content_type.contains("html") {
-        html_to_text(&body)
+/// Turn a fetched body into model-friendly text according to its
+/// `Content-Type`: HTML becomes markdown, JSON is pretty-printed, anything
+/// else is returned unchanged.
+fn extract(content_type: &str, body: String) -> String {
+    if content_type.contains("html") {
+        html_to_markdown(&body)
     } else if content_type.contains("json") {
         match serde_json::from_str::<Value>(&body) {
             Ok(v) => serde_json::to_string_pretty(&v).unwrap_or(body),
@@ -94,22 +139,77 @@ async fn fetch_url(url: &str, max_chars: usize) -> anyhow::Result<String> {
         }
     } else {
         body
-    };
+    }
+}
 
-    if content.len() > max_chars {
-        Ok(format!(
-            "{}...[truncated at {max_chars} chars; total {} chars]",
-            &content[..max_chars],
-            content.len()
-        ))
-    } else {
-        Ok(content)
+/// Cap `content` at `max_bytes`, cutting on a character boundary.
+fn truncate(content: String, max_bytes: usize) -> String {
+    if content.len() <= max_bytes {
+        return content;
+    }
+    let mut end = max_bytes;
+    while !content.is_char_boundary(end) {
+        end -= 1;
     }
+    format!(
+        "{}...[truncated at {max_bytes} bytes; total {} bytes]",
+        &content[..end],
+        content.len()
+    )
 }
 
-/// Convert HTML to plain text using html2text.
-fn html_to_text(html: &str) -> String {
-    html2text::from_read(html.as_bytes(), 100)
+/// Convert HTML to markdown: headings, lists, inline code and reference-style
+/// links come from html2text; `<pre>` blocks are kept verbatim as fenced code
+/// blocks, which html2text would otherwise flatten into inline code.
+fn html_to_markdown(html: &str) -> String {
+    let lower = html.to_ascii_lowercase();
+    let mut input = String::with_capacity(html.len());
+    let mut blocks = Vec::new();
+    let mut pos = 0;
+    while let Some(start) = find_pre_open(&lower, pos) {
+        let Some(end) = lower[start..]
+            .find("</pre>")
+            .map(|i| start + i + "</pre>".len())
+        else {
+            break;
+        };
+        input.push_str(&html[pos..start]);
+        input.push_str(&format!("<p>{}</p>", code_marker(blocks.len())));
+        let code = html2text::from_read_with_decorator(
+            &html.as_bytes()[start..end],
+            usize::MAX / 2,
+            html2text::render::text_renderer::TrivialDecorator::new(),
+        );
+        blocks.push(code.trim_matches('\n').to_string());
+        pos = end;
+    }
+    input.push_str(&html[pos..]);
+
+    let mut text = html2text::from_read(input.as_bytes(), 100);
+    for (i, code) in blocks.iter().enumerate() {
+        text = text.replace(&code_marker(i), &format!("```\n{code}\n```"));
+    }
+    text
+}
+
+/// Offset of the next `<pre>` (or `<pre attr=...>`) tag at or after `from`.
+fn find_pre_open(lower: &str, from: usize) -> Option<usize> {
+    let mut at = from;
+    while let Some(i) = lower[at..].find("<pre") {
+        let start = at + i;
+        match lower[start + 4..].chars().next() {
+            Some(c) if c == '>' || c.is_whitespace() => return Some(start),
+            None => return None,
+            _ => at = start + 4,
+        }
+    }
+    None
+}
+
+/// Placeholder for `<pre>` block `index`.  The `-end` suffix keeps one
+/// marker from being a prefix of another (block 1 of block 10).
+fn code_marker(index: usize) -> String {
+    format!("sven-web-fetch-code-block-{index}-end")
 }
 
 impl ToolDisplay for WebFetchTool {
@@ -132,18 +232,78 @@ mod tests {
     use super::*;
 
     #[test]
-    fn html_to_text_strips_tags() {
+    fn html_to_markdown_strips_tags() {
         let html = "<html><body><h1>Hello</h1><p>World</p></body></html>";
-        let text = html_to_text(html);
+        let text = html_to_markdown(html);
         assert!(text.contains("Hello"));
         assert!(text.contains("World"));
         assert!(!text.contains("<h1>"));
     }
 
+    #[test]
+    fn html_page_is_extracted_to_markdown() {
+        let html = r#"<html><head><title>Doc</title>
+            <style>body { color: red }</style><script>var tracking = 1;</script></head>
+            <body><h1>Install</h1>
+            <p>Read <a href="https://example.com/guide">the guide</a> first.</p>
+            <PRE class="lang-sh"><code>cargo build --release
+cargo test</code></PRE>
+            <h2>Usage</h2><ul><li>run <code>sven</code></li></ul></body></html>"#;
+        let md = extract("text/html; charset=utf-8", html.to_string());
+        assert!(md.contains("# Install"), "{md}");
+        assert!(md.contains("## Usage"), "{md}");
+        assert!(md.contains("[the guide]"), "{md}");
+        assert!(md.contains("https://example.com/guide"), "{md}");
+        assert!(
+            md.contains("```\ncargo build --release\ncargo test\n```"),
+            "{md}"
+        );
+        assert!(md.contains("`sven`"), "{md}");
+        assert!(!md.contains('<'), "{md}");
+        assert!(!md.contains("tracking"), "{md}");
+    }
+
+    #[test]
+    fn json_body_is_pretty_printed() {
+        let out = extract(
+            "application/json",
+            r#"{"name":"sven","tags":["a","b"]}"#.into(),
+        );
+        assert_eq!(
+            out,
+            "{\n  \"name\": \"sven\",\n  \"tags\": [\n    \"a\",\n    \"b\"\n  ]\n}"
+        );
+        // Invalid JSON is passed through untouched.
+        assert_eq!(extract("application/json", "{oops".into()), "{oops");
+    }
+
+    #[test]
+    fn many_pre_blocks_keep_their_own_code() {
+        let html: String = (0..12)
+            .map(|i| format!("<p>block</p><pre>code {i}</pre>"))
+            .collect();
+        let text = html_to_markdown(&html);
+        for i in 0..12 {
+            assert!(text.contains(&format!("```\ncode {i}\n```")), "{text}");
+        }
+        assert!(!text.contains("sven-web-fetch-code-block"), "{text}");
+    }
+
+    #[test]
+    fn other_content_types_pass_through_with_a_byte_cap() {
+        let body = "plain ".repeat(10);
+        assert_eq!(extract("text/plain", body.clone()), body);
+        let capped = truncate(body, 12);
+        assert!(capped.starts_with("plain plain ..."), "{capped}");
+        assert!(capped.contains("total 60 bytes"), "{capped}");
+        // Never split a multi-byte character.
+        assert!(truncate("ééé".into(), 3).starts_with("é..."));
+    }
+
     #[test]
     fn schema_requires_url() {
         use crate::tool::Tool;
-        let t = WebFetchTool;
+        let t = WebFetchTool::default();
         let schema = t.parameters_schema();
         let required = schema["required"].as_array().unwrap();
         assert!(required.iter().any(|v| v.as_str() == Some("url")));
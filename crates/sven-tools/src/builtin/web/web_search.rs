@@ -1,6 +1,8 @@
 // Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
 //
 // SPDX-License-Identifier: Apache-2.0
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use serde_json::{json, Value};
 use tracing::debug;
@@ -8,10 +10,78 @@ use tracing::debug;
 use crate::policy::ApprovalPolicy;
 use crate::tool::{Tool, ToolCall, ToolDisplay, ToolOutput};
 
-#[derive(Default)]
+/// Results per page when `num_results` is not given.
+const DEFAULT_NUM_RESULTS: usize = 5;
+/// Upper bound for `num_results` (the Brave API maximum).
+const MAX_NUM_RESULTS: usize = 20;
+/// Highest page the Brave API can return (its `offset` goes up to 9).
+const MAX_PAGE: usize = 10;
+
+/// One search hit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+}
+
+/// A web search provider.
+#[async_trait]
+pub trait SearchBackend: Send + Sync {
+    /// Return up to `count` results for `query`, skipping the first
+    /// `page - 1` pages of `count` results each.  `page` is 1-based.
+    async fn search(
+        &self,
+        query: &str,
+        count: usize,
+        page: usize,
+    ) -> anyhow::Result<Vec<SearchResult>>;
+}
+
 pub struct WebSearchTool {
     /// Optional API key override (falls back to env BRAVE_API_KEY)
     pub api_key: Option<String>,
+    /// Request timeout in seconds.
+    pub timeout_secs: u64,
+    /// Search provider; `None` uses the Brave Search API with `api_key`.
+    pub backend: Option<Arc<dyn SearchBackend>>,
+}
+
+impl WebSearchTool {
+    pub const DEFAULT_TIMEOUT_SECS: u64 = 15;
+
+    /// A tool that searches through `backend` instead of Brave.
+    pub fn with_backend(backend: Arc<dyn SearchBackend>) -> Self {
+        Self {
+            backend: Some(backend),
+            ..Self::default()
+        }
+    }
+
+    fn resolve_backend(&self) -> Option<Arc<dyn SearchBackend>> {
+        if let Some(backend) = &self.backend {
+            return Some(Arc::clone(backend));
+        }
+        let api_key = self
+            .api_key
+            .clone()
+            .or_else(|| std::env::var("BRAVE_API_KEY").ok())
+            .filter(|k| !k.trim().is_empty())?;
+        Some(Arc::new(BraveSearch {
+            api_key,
+            timeout_secs: self.timeout_secs,
+        }))
+    }
+}
+
+impl Default for WebSearchTool {
+    fn default() -> Self {
+        Self {
+            api_key: None,
+            timeout_secs: Self::DEFAULT_TIMEOUT_SECS,
+            backend: None,
+        }
+    }
 }
 
 #[async_trait]
@@ -21,7 +91,10 @@ impl Tool for WebSearchTool {
     }
 
     fn description(&self) -> &str {
-        "Real-time web search. Requires BRAVE_API_KEY env var. count: 1-10 (default 5).\n\
+        "Real-time web search. Requires BRAVE_API_KEY env var.\n\
+         num_results: 1-20 (default 5). page: 1-10 (default 1) — request the next page \
+         for more results instead of re-searching.\n\
+         Each result has a title, URL, source domain and snippet.\n\
          Include the current year in queries for recent info (e.g., 'React docs 2026').\n\
          Knowledge cutoff: early 2025 — use this for anything that may have changed since.\n\
          ALWAYS cite sources after answering:\n\
@@ -37,12 +110,16 @@ impl Tool for WebSearchTool {
                     "type": "string",
                     "description": "Search query"
                 },
-                "count": {
+                "num_results": {
+                    "type": "integer",
+                    "description": "Results per page (default 5, max 20)"
+                },
+                "page": {
                     "type": "integer",
-                    "description": "Number of results to return (default 5, max 10)"
+                    "description": "1-based page of results (default 1, max 10)"
                 }
             },
-            "required": ["query", "count"],
+            "required": ["query", "num_results", "page"],
             "additionalProperties": false
         })
     }
@@ -51,90 +128,169 @@ impl Tool for WebSearchTool {
         ApprovalPolicy::Auto
     }
 
+    fn read_only(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, call: &ToolCall) -> ToolOutput {
         let query = match call.args.get("query").and_then(|v| v.as_str()) {
             Some(q) => q.to_string(),
             None => return ToolOutput::err(&call.id, "missing 'query'"),
         };
-        let count = call
+        // `count` is the pre-pagination name of `num_results`.
+        let num_results = call
             .args
-            .get("count")
+            .get("num_results")
+            .or_else(|| call.args.get("count"))
             .and_then(|v| v.as_u64())
-            .unwrap_or(5)
-            .min(10) as usize;
-
-        debug!(query = %query, count, "web_search tool");
+            .map_or(DEFAULT_NUM_RESULTS, |n| n as usize)
+            .clamp(1, MAX_NUM_RESULTS);
+        let page = call
+            .args
+            .get("page")
+            .and_then(|v| v.as_u64())
+            .map_or(1, |p| p as usize)
+            .clamp(1, MAX_PAGE);
 
-        // Resolve API key
-        let api_key = self
-            .api_key
-            .clone()
-            .or_else(|| std::env::var("BRAVE_API_KEY").ok());
+        debug!(query = %query, num_results, page, "web_search tool");
 
-        let Some(api_key) = api_key else {
+        let Some(backend) = self.resolve_backend() else {
             return ToolOutput::err(
                 &call.id,
-                "No Brave Search API key configured. Set the BRAVE_API_KEY environment variable \
-                 or configure tools.web.search.api_key in sven.toml.",
+                "web_search is not configured: no Brave Search API key. Get a key at \
+                 https://brave.com/search/api/ and either export BRAVE_API_KEY=<key> or set \
+                 tools.web.search.api_key in ~/.config/sven/config.yaml.",
             );
         };
 
-        match brave_search(&query, count, &api_key).await {
-            Ok(results) => ToolOutput::ok(&call.id, results),
+        match backend.search(&query, num_results, page).await {
+            Ok(mut results) => {
+                results.truncate(num_results);
+                ToolOutput::ok(
+                    &call.id,
+                    format_results(&query, num_results, page, &results),
+                )
+            }
             Err(e) => ToolOutput::err(&call.id, format!("search error: {e}")),
         }
     }
 }
 
-async fn brave_search(query: &str, count: usize, api_key: &str) -> anyhow::Result<String> {
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(15))
-        .user_agent("sven-agent/0.1")
-        .build()?;
-
-    let url = format!(
-        "https://api.search.brave.com/res/v1/web/search?q={}&count={}",
-        urlencoding(query),
-        count
+/// Render one page of results.  Numbering continues across pages so the
+/// model can tell page 2 apart from page 1.
+fn format_results(
+    query: &str,
+    num_results: usize,
+    page: usize,
+    results: &[SearchResult],
+) -> String {
+    if results.is_empty() {
+        return if page > 1 {
+            format!("(no results on page {page} for {query:?})")
+        } else {
+            "(no results)".to_string()
+        };
+    }
+    let first = (page - 1) * num_results + 1;
+    let mut out = format!(
+        "Results {first}-{} for {query:?} (page {page}):\n\n",
+        first + results.len() - 1
     );
+    let entries: Vec<String> = results
+        .iter()
+        .enumerate()
+        .map(|(i, r)| {
+            format!(
+                "{}. **{}**\n   {}\n   source: {}\n   {}",
+                first + i,
+                r.title,
+                r.url,
+                domain_of(&r.url),
+                r.snippet
+            )
+        })
+        .collect();
+    out.push_str(&entries.join("\n\n"));
+    if results.len() == num_results && page < MAX_PAGE {
+        out.push_str(&format!("\n\n(more results: page={})", page + 1));
+    }
+    out
+}
 
-    let resp = client
-        .get(&url)
-        .header("Accept", "application/json")
-        .header("Accept-Encoding", "gzip")
-        .header("X-Subscription-Token", api_key)
-        .send()
-        .await?;
+/// Host part of `url` without a leading `www.`, or `""` when there is none.
+fn domain_of(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, r)| r);
+    let host = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let host = host.rsplit_once('@').map_or(host, |(_, h)| h);
+    let host = host.split(':').next().unwrap_or("");
+    host.strip_prefix("www.").unwrap_or(host)
+}
 
-    if !resp.status().is_success() {
-        anyhow::bail!("Brave API returned status {}", resp.status());
-    }
+/// The Brave Search API backend.
+struct BraveSearch {
+    api_key: String,
+    timeout_secs: u64,
+}
 
-    let json: Value = resp.json().await?;
+#[async_trait]
+impl SearchBackend for BraveSearch {
+    async fn search(
+        &self,
+        query: &str,
+        count: usize,
+        page: usize,
+    ) -> anyhow::Result<Vec<SearchResult>> {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(self.timeout_secs))
+            .user_agent("sven-agent/0.1")
+            .build()?;
 
-    let results = json
-        .get("web")
-        .and_then(|w| w.get("results"))
-        .and_then(|r| r.as_array())
-        .map(|arr| arr.as_slice())
-        .unwrap_or(&[]);
+        let url = format!(
+            "https://api.search.brave.com/res/v1/web/search?q={}&count={}&offset={}",
+            urlencoding(query),
+            count,
+            page - 1
+        );
 
-    if results.is_empty() {
-        return Ok("(no results)".to_string());
-    }
+        let resp = client
+            .get(&url)
+            .header("Accept", "application/json")
+            .header("Accept-Encoding", "gzip")
+            .header("X-Subscription-Token", &self.api_key)
+            .send()
+            .await?;
 
-    let mut output = Vec::new();
-    for (i, r) in results.iter().enumerate().take(count) {
-        let title = r
-            .get("title")
-            .and_then(|v| v.as_str())
-            .unwrap_or("(no title)");
-        let url = r.get("url").and_then(|v| v.as_str()).unwrap_or("");
-        let desc = r.get("description").and_then(|v| v.as_str()).unwrap_or("");
-        output.push(format!("{}. **{}**\n   {}\n   {}", i + 1, title, url, desc));
+        if !resp.status().is_success() {
+            anyhow::bail!("Brave API returned status {}", resp.status());
+        }
+
+        let json: Value = resp.json().await?;
+        Ok(parse_brave_results(&json))
     }
+}
 
-    Ok(output.join("\n\n"))
+fn parse_brave_results(json: &Value) -> Vec<SearchResult> {
+    let field = |r: &Value, key: &str| {
+        r.get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    json.get("web")
+        .and_then(|w| w.get("results"))
+        .and_then(|r| r.as_array())
+        .map(|arr| {
+            arr.iter()
+                .map(|r| SearchResult {
+                    title: Some(field(r, "title"))
+                        .filter(|t| !t.is_empty())
+                        .unwrap_or_else(|| "(no title)".to_string()),
+                    url: field(r, "url"),
+                    snippet: field(r, "description"),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
 fn urlencoding(s: &str) -> String {
@@ -189,7 +345,7 @@ mod tests {
         // Ensure env var is unset for test
         std::env::remove_var("BRAVE_API_KEY");
 
-        let t = WebSearchTool { api_key: None };
+        let t = WebSearchTool::default();
         let call = ToolCall {
             id: "1".into(),
             name: "web_search".into(),
@@ -198,5 +354,108 @@ mod tests {
         let out = t.execute(&call).await;
         assert!(out.is_error);
         assert!(out.content.contains("BRAVE_API_KEY"));
+        assert!(out.content.contains("tools.web.search.api_key"));
+    }
+
+    /// Serves `total` numbered results and records each request.
+    struct MockSearch {
+        total: usize,
+        requests: std::sync::Mutex<Vec<(usize, usize)>>,
+    }
+
+    #[async_trait]
+    impl SearchBackend for MockSearch {
+        async fn search(
+            &self,
+            _query: &str,
+            count: usize,
+            page: usize,
+        ) -> anyhow::Result<Vec<SearchResult>> {
+            self.requests.lock().unwrap().push((count, page));
+            let start = (page - 1) * count;
+            Ok((start..self.total.min(start + count))
+                .map(|i| SearchResult {
+                    title: format!("Result {}", i + 1),
+                    url: format!("https://www.site{}.example/page?q=1", i + 1),
+                    snippet: format!("snippet {}", i + 1),
+                })
+                .collect())
+        }
+    }
+
+    async fn search(total: usize, args: Value) -> (ToolOutput, Vec<(usize, usize)>) {
+        let backend = Arc::new(MockSearch {
+            total,
+            requests: Default::default(),
+        });
+        let t = WebSearchTool::with_backend(backend.clone());
+        let call = ToolCall {
+            id: "1".into(),
+            name: "web_search".into(),
+            args,
+        };
+        let out = t.execute(&call).await;
+        let requests = backend.requests.lock().unwrap().clone();
+        (out, requests)
+    }
+
+    #[tokio::test]
+    async fn num_results_limits_and_clamps_the_count() {
+        let (out, requests) = search(50, json!({"query": "rust", "num_results": 3})).await;
+        assert!(!out.is_error, "{}", out.content);
+        assert_eq!(requests, [(3, 1)]);
+        assert!(out.content.contains("3. **Result 3**"), "{}", out.content);
+        assert!(!out.content.contains("Result 4"));
+        assert!(out.content.contains("source: site1.example"));
+        assert!(out.content.contains("snippet 2"));
+        assert!(out.content.contains("page=2"));
+
+        let (_, requests) = search(50, json!({"query": "rust", "num_results": 500})).await;
+        assert_eq!(requests, [(MAX_NUM_RESULTS, 1)]);
+        let (_, requests) = search(50, json!({"query": "rust"})).await;
+        assert_eq!(requests, [(DEFAULT_NUM_RESULTS, 1)]);
+    }
+
+    #[tokio::test]
+    async fn page_continues_where_the_previous_one_ended() {
+        let (out, requests) =
+            search(12, json!({"query": "rust", "num_results": 5, "page": 2})).await;
+        assert_eq!(requests, [(5, 2)]);
+        assert!(out.content.starts_with("Results 6-10"), "{}", out.content);
+        assert!(out.content.contains("6. **Result 6**"));
+
+        // The last, short page has no "more results" hint.
+        let (out, _) = search(12, json!({"query": "rust", "num_results": 5, "page": 3})).await;
+        assert!(out.content.starts_with("Results 11-12"), "{}", out.content);
+        assert!(!out.content.contains("more results"));
+
+        let (out, _) = search(12, json!({"query": "rust", "num_results": 5, "page": 4})).await;
+        assert!(
+            out.content.contains("no results on page 4"),
+            "{}",
+            out.content
+        );
+    }
+
+    #[test]
+    fn domain_of_strips_scheme_path_port_and_www() {
+        assert_eq!(
+            domain_of("https://www.rust-lang.org/learn"),
+            "rust-lang.org"
+        );
+        assert_eq!(domain_of("http://user@docs.rs:8080/x?y#z"), "docs.rs");
+        assert_eq!(domain_of("example.com"), "example.com");
+    }
+
+    #[test]
+    fn brave_response_is_parsed() {
+        let body = json!({"web": {"results": [
+            {"title": "Rust", "url": "https://rust-lang.org", "description": "A language"},
+            {"url": "https://x.example"}
+        ]}});
+        let results = parse_brave_results(&body);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].snippet, "A language");
+        assert_eq!(results[1].title, "(no title)");
     }
 }
@@ -4,9 +4,11 @@
 pub mod builtin;
 pub mod display;
 pub mod events;
+pub(crate) mod panic_guard;
 pub(crate) mod params;
 pub mod policy;
 pub mod registry;
+pub mod sandbox;
 pub mod tool;
 pub mod tool_summary;
 
@@ -14,6 +16,7 @@ pub use display::format_tools_list;
 pub use events::{TodoItem, TodoStatus, ToolEvent};
 pub use policy::{ApprovalPolicy, PermissionRequester, RolePolicy, ToolPolicy};
 pub use registry::{SharedToolDisplays, SharedTools, ToolRegistry, ToolSchema};
+pub use sandbox::Sandbox;
 pub use tool::{
     OutputCategory, Tool, ToolCall, ToolDisplay, ToolDisplayRegistry, ToolOutput, ToolOutputPart,
 };
@@ -37,20 +40,20 @@ pub use builtin::system::memory::MemoryTool;
 pub use builtin::system::read_lints::ReadLintsTool;
 pub use builtin::system::skill::SkillTool;
 pub use builtin::system::system::SystemTool;
-pub use builtin::system::todo::TodoTool;
+pub use builtin::system::todo::{load_todos, TodoTool};
 
 // Terminal tools
 pub use builtin::terminal::run_terminal_command::RunTerminalCommandTool;
 
 // Web tools
 pub use builtin::web::web_fetch::WebFetchTool;
-pub use builtin::web::web_search::WebSearchTool;
+pub use builtin::web::web_search::{SearchBackend, SearchResult, WebSearchTool};
 
 // Knowledge tools
 pub use builtin::knowledge::list_knowledge::ListKnowledgeTool;
 
 // Shell tool
-pub use builtin::shell::ShellTool;
+pub use builtin::shell::{CommandEnv, SecretMasker, ShellTool};
 
 // GDB debugging tools (Unix only — GDB signal APIs are not available on Windows)
 #[cfg(unix)]
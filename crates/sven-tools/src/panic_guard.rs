@@ -0,0 +1,101 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! Contain panics raised by a tool's `execute` so one buggy tool cannot take
+//! down the session.
+//!
+//! [`catch_panic`] polls a tool future inside `catch_unwind`.  While it does,
+//! a process-wide panic hook (installed on first use) records the panic's
+//! location and backtrace for the dead-letter log instead of running the
+//! previous hook — which, in the TUI, would leave the alternate screen.
+//! Panics anywhere else still go to the previous hook unchanged.
+
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::Once;
+use std::task::{Context, Poll};
+
+thread_local! {
+    /// Set while a tool future is being polled on this thread.
+    static IN_TOOL: Cell<bool> = const { Cell::new(false) };
+    /// Location and backtrace of the last tool panic on this thread.
+    static LAST_REPORT: RefCell<Option<(String, String)>> = const { RefCell::new(None) };
+}
+
+/// A panic caught while executing a tool.
+#[derive(Debug)]
+pub(crate) struct ToolPanic {
+    pub message: String,
+    pub location: String,
+    pub backtrace: String,
+}
+
+/// Run `fut` to completion, turning a panic while polling it into a
+/// [`ToolPanic`].
+pub(crate) fn catch_panic<F: Future>(fut: F) -> CatchPanic<F> {
+    install_hook();
+    CatchPanic {
+        inner: Box::pin(fut),
+    }
+}
+
+pub(crate) struct CatchPanic<F> {
+    inner: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for CatchPanic<F> {
+    type Output = Result<F::Output, ToolPanic>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let inner = self.inner.as_mut();
+        let was_in_tool = IN_TOOL.replace(true);
+        let polled = panic::catch_unwind(AssertUnwindSafe(|| inner.poll(cx)));
+        IN_TOOL.set(was_in_tool);
+        match polled {
+            Ok(Poll::Pending) => Poll::Pending,
+            Ok(Poll::Ready(output)) => Poll::Ready(Ok(output)),
+            Err(payload) => {
+                let (location, backtrace) = LAST_REPORT
+                    .take()
+                    .unwrap_or_else(|| ("unknown".into(), "unavailable".into()));
+                Poll::Ready(Err(ToolPanic {
+                    message: panic_message(payload.as_ref()),
+                    location,
+                    backtrace,
+                }))
+            }
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        (*s).to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "non-string panic payload".to_string()
+    }
+}
+
+fn install_hook() {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !IN_TOOL.get() {
+                previous(info);
+                return;
+            }
+            let location = info
+                .location()
+                .map_or_else(|| "unknown".to_string(), ToString::to_string);
+            let backtrace = Backtrace::force_capture().to_string();
+            LAST_REPORT.set(Some((location, backtrace)));
+        }));
+    });
+}
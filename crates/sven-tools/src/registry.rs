@@ -3,10 +3,12 @@
 // SPDX-License-Identifier: Apache-2.0
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 use sven_config::AgentMode;
 
 use crate::policy::PermissionRequester;
+use crate::sandbox::Sandbox;
 use crate::tool::ToolDisplayRegistry;
 use crate::{ApprovalPolicy, OutputCategory, Tool, ToolCall, ToolOutput};
 
@@ -94,6 +96,31 @@ pub struct ToolRegistry {
     /// When set, tools with `ApprovalPolicy::Ask` are gated behind a
     /// `session/request_permission` round-trip to the IDE before executing.
     permission_requester: Option<Arc<dyn PermissionRequester>>,
+    /// Per-tool wall-clock limits enforced by the agent loop.
+    timeouts: HashMap<String, Duration>,
+    /// Root that path arguments must stay inside; `None` allows any path.
+    sandbox: Option<Sandbox>,
+    /// Allow/deny lists set with [`Self::restrict`]; also applied to MCP
+    /// tools installed later.
+    filter: RwLock<Option<NameFilter>>,
+    /// False for fixed tool sets (presets); see [`Self::disable_mcp_tools`].
+    mcp_enabled: bool,
+}
+
+/// Tool names a [`ToolRegistry`] is restricted to.
+#[derive(Debug, Clone)]
+struct NameFilter {
+    allow: Option<Vec<String>>,
+    deny: Vec<String>,
+}
+
+impl NameFilter {
+    fn permits(&self, name: &str) -> bool {
+        self.allow
+            .as_ref()
+            .is_none_or(|allow| allow.iter().any(|a| a == name))
+            && !self.deny.iter().any(|d| d == name)
+    }
 }
 
 impl ToolRegistry {
@@ -102,6 +129,10 @@ impl ToolRegistry {
             tools: RwLock::new(HashMap::new()),
             display_registry: Arc::new(RwLock::new(ToolDisplayRegistry::new())),
             permission_requester: None,
+            timeouts: HashMap::new(),
+            sandbox: None,
+            filter: RwLock::new(None),
+            mcp_enabled: true,
         }
     }
 
@@ -120,6 +151,20 @@ impl ToolRegistry {
         }
     }
 
+    /// Register `tool` unless a tool with the same name is already registered.
+    pub fn try_register(&mut self, tool: Arc<dyn Tool>) -> anyhow::Result<()> {
+        let name = tool.name().to_string();
+        let mut guard = self
+            .tools
+            .write()
+            .map_err(|_| anyhow::anyhow!("tool registry lock poisoned"))?;
+        if guard.contains_key(&name) {
+            anyhow::bail!("a tool named \"{name}\" is already registered");
+        }
+        guard.insert(name, tool);
+        Ok(())
+    }
+
     /// Register a tool that also provides display metadata. The same instance
     /// is used for execution and for TUI display (collapsed summary, display name).
     pub fn register_with_display(&mut self, tool: impl Tool + crate::tool::ToolDisplay + 'static) {
@@ -135,15 +180,89 @@ impl ToolRegistry {
 
     /// Replace all MCP tools with the given set.  Call when MCP servers connect,
     /// disconnect, or tools are reloaded so the agent uses the updated list.
+    /// Tools excluded by [`Self::restrict`] are left out, and nothing is
+    /// installed after [`Self::disable_mcp_tools`].
     pub fn replace_mcp_tools(&self, new_tools: Vec<Arc<dyn Tool>>) {
+        if !self.mcp_enabled {
+            return;
+        }
+        let filter = self.filter.read().ok().and_then(|f| f.clone());
         if let Ok(mut guard) = self.tools.write() {
             guard.retain(|_, t| !t.is_mcp());
             for tool in new_tools {
-                guard.insert(tool.name().to_string(), tool);
+                if filter.as_ref().is_none_or(|f| f.permits(tool.name())) {
+                    guard.insert(tool.name().to_string(), tool);
+                }
             }
         }
     }
 
+    /// Keep MCP tools out of this registry, including those offered later by
+    /// [`Self::replace_mcp_tools`].  Used for fixed tool sets (presets).
+    pub fn disable_mcp_tools(&mut self) {
+        self.mcp_enabled = false;
+    }
+
+    /// Whether MCP tools may be registered; see [`Self::disable_mcp_tools`].
+    pub fn accepts_mcp_tools(&self) -> bool {
+        self.mcp_enabled
+    }
+
+    /// Keep only the tools named in `allow` (all when `None`) and remove
+    /// those named in `deny`.  MCP tools installed later by
+    /// [`Self::replace_mcp_tools`] are restricted the same way.
+    ///
+    /// Returns the listed names that match no registered tool, sorted, so the
+    /// caller can warn about typos.
+    pub fn restrict(&self, allow: Option<&[String]>, deny: &[String]) -> Vec<String> {
+        let filter = NameFilter {
+            allow: allow.map(<[String]>::to_vec),
+            deny: deny.to_vec(),
+        };
+        let registered = self.names();
+        let mut unknown: Vec<String> = allow
+            .unwrap_or_default()
+            .iter()
+            .chain(deny)
+            .filter(|name| !registered.contains(name))
+            .cloned()
+            .collect();
+        unknown.sort();
+        unknown.dedup();
+
+        if let Ok(mut guard) = self.tools.write() {
+            guard.retain(|name, _| filter.permits(name));
+        }
+        if let Ok(mut slot) = self.filter.write() {
+            *slot = Some(filter);
+        }
+        unknown
+    }
+
+    /// Limit every call of tool `name` to `timeout`.
+    ///
+    /// The registry only records the limit; the agent loop reads it back with
+    /// [`Self::timeout_for`] and cuts off calls that run longer.
+    pub fn set_timeout(&mut self, name: impl Into<String>, timeout: Duration) {
+        self.timeouts.insert(name.into(), timeout);
+    }
+
+    /// Reject calls whose path arguments ([`Tool::path_args`]) resolve to a
+    /// location outside `sandbox`.
+    pub fn set_sandbox(&mut self, sandbox: Sandbox) {
+        self.sandbox = Some(sandbox);
+    }
+
+    /// The sandbox set with [`Self::set_sandbox`], if any.
+    pub fn sandbox(&self) -> Option<&Sandbox> {
+        self.sandbox.as_ref()
+    }
+
+    /// The limit set with [`Self::set_timeout`] for tool `name`, if any.
+    pub fn timeout_for(&self, name: &str) -> Option<Duration> {
+        self.timeouts.get(name).copied()
+    }
+
     /// Shared handle to the display registry for TUI rendering (collapsed preview, etc.).
     pub fn display_registry(&self) -> Arc<RwLock<ToolDisplayRegistry>> {
         Arc::clone(&self.display_registry)
@@ -173,6 +292,11 @@ impl ToolRegistry {
             Some(t) => t,
             None => return ToolOutput::err(&call.id, format!("unknown tool: {}", call.name)),
         };
+        if let Some(sandbox) = &self.sandbox {
+            if let Err(msg) = sandbox.check_args(&call.args, tool.path_args()) {
+                return ToolOutput::err(&call.id, msg);
+            }
+        }
         if let Some(ref requester) = self.permission_requester {
             if matches!(tool.default_policy(), ApprovalPolicy::Ask)
                 && !requester.request_permission(call).await
@@ -183,7 +307,26 @@ impl ToolRegistry {
                 );
             }
         }
-        tool.execute(call).await
+        match crate::panic_guard::catch_panic(tool.execute(call)).await {
+            Ok(output) => output,
+            Err(panic) => {
+                // Dead-letter log: lands in SVEN_LOG_FILE when it is set.
+                tracing::error!(
+                    target: "sven_tools::dead_letter",
+                    tool = %call.name,
+                    call_id = %call.id,
+                    args = %call.args,
+                    location = %panic.location,
+                    "tool panicked: {}\n{}",
+                    panic.message,
+                    panic.backtrace,
+                );
+                ToolOutput::err(
+                    &call.id,
+                    format!("tool '{}' panicked: {}", call.name, panic.message),
+                )
+            }
+        }
     }
 
     pub fn names(&self) -> Vec<String> {
@@ -296,6 +439,33 @@ mod tests {
         }
     }
 
+    /// [`EchoTool`] reporting itself as an MCP tool.
+    struct McpEchoTool {
+        name: &'static str,
+    }
+
+    #[async_trait]
+    impl Tool for McpEchoTool {
+        fn name(&self) -> &str {
+            self.name
+        }
+        fn description(&self) -> &str {
+            "echoes its input"
+        }
+        fn parameters_schema(&self) -> Value {
+            json!({ "type": "object" })
+        }
+        fn default_policy(&self) -> ApprovalPolicy {
+            ApprovalPolicy::Auto
+        }
+        fn is_mcp(&self) -> bool {
+            true
+        }
+        async fn execute(&self, call: &ToolCall) -> ToolOutput {
+            ToolOutput::ok(&call.id, format!("echo:{}", call.args))
+        }
+    }
+
     /// Tool that explicitly declares a non-default output category.
     struct TerminalTool;
 
@@ -392,6 +562,43 @@ mod tests {
         assert_eq!(names, vec!["a", "b"]);
     }
 
+    #[test]
+    fn restrict_applies_allow_then_deny() {
+        let mut reg = ToolRegistry::new();
+        reg.register(EchoTool { name: "a" });
+        reg.register(EchoTool { name: "b" });
+        reg.register(EchoTool { name: "c" });
+        let allow = ["a".to_string(), "b".to_string(), "typo".to_string()];
+        let unknown = reg.restrict(Some(&allow), &["b".to_string()]);
+        assert_eq!(unknown, ["typo"]);
+        assert_eq!(reg.names(), vec!["a"]);
+        assert!(reg.get("c").is_none());
+    }
+
+    #[test]
+    fn restrict_also_filters_mcp_tools_installed_later() {
+        let mut reg = ToolRegistry::new();
+        reg.register(EchoTool { name: "read" });
+        reg.restrict(None, &["mcp_write".to_string()]);
+        reg.replace_mcp_tools(vec![
+            Arc::new(McpEchoTool { name: "mcp_read" }),
+            Arc::new(McpEchoTool { name: "mcp_write" }),
+        ]);
+        let mut names = reg.names();
+        names.sort();
+        assert_eq!(names, vec!["mcp_read", "read"]);
+    }
+
+    #[test]
+    fn disabled_mcp_tools_are_never_installed() {
+        let mut reg = ToolRegistry::new();
+        reg.register(EchoTool { name: "read" });
+        reg.disable_mcp_tools();
+        assert!(!reg.accepts_mcp_tools());
+        reg.replace_mcp_tools(vec![Arc::new(McpEchoTool { name: "mcp_read" })]);
+        assert_eq!(reg.names(), vec!["read"]);
+    }
+
     #[test]
     fn schemas_contains_registered_tool() {
         let mut reg = ToolRegistry::new();
@@ -422,6 +629,128 @@ mod tests {
         assert!(out.content.starts_with("echo:"));
     }
 
+    #[tokio::test]
+    async fn sandbox_rejects_paths_escaping_the_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("notes.txt");
+        std::fs::write(&file, "inside\n").unwrap();
+        let mut reg = ToolRegistry::new();
+        reg.register(crate::ReadFileTool);
+        reg.set_sandbox(Sandbox::new(dir.path()).unwrap());
+        let read = |path: String| ToolCall {
+            id: "r".into(),
+            name: "read_file".into(),
+            args: json!({ "path": path }),
+        };
+
+        let out = reg.execute(&read(file.display().to_string())).await;
+        assert!(!out.is_error, "{}", out.content);
+        assert!(out.content.contains("inside"));
+
+        let escape = format!("{}/../../etc/passwd", dir.path().display());
+        let out = reg.execute(&read(escape)).await;
+        assert!(out.is_error);
+        assert!(
+            out.content.contains("outside the sandbox root"),
+            "{}",
+            out.content
+        );
+    }
+
+    /// Tool whose `execute` panics after an await point.
+    struct PanickingTool;
+
+    #[async_trait]
+    impl Tool for PanickingTool {
+        fn name(&self) -> &str {
+            "boom"
+        }
+        fn description(&self) -> &str {
+            "always panics"
+        }
+        fn parameters_schema(&self) -> Value {
+            json!({ "type": "object" })
+        }
+        fn default_policy(&self) -> ApprovalPolicy {
+            ApprovalPolicy::Auto
+        }
+        async fn execute(&self, _call: &ToolCall) -> ToolOutput {
+            tokio::task::yield_now().await;
+            panic!("index out of range");
+        }
+    }
+
+    fn boom_call() -> ToolCall {
+        ToolCall {
+            id: "1".into(),
+            name: "boom".into(),
+            args: json!({ "n": 7 }),
+        }
+    }
+
+    #[tokio::test]
+    async fn panicking_tool_returns_error_output() {
+        let mut reg = ToolRegistry::new();
+        reg.register(PanickingTool);
+        reg.register(EchoTool { name: "echo" });
+
+        let out = reg.execute(&boom_call()).await;
+        assert!(out.is_error);
+        assert_eq!(out.call_id, "1");
+        assert_eq!(out.content, "tool 'boom' panicked: index out of range");
+
+        let echo = ToolCall {
+            id: "2".into(),
+            name: "echo".into(),
+            args: json!({}),
+        };
+        let out = reg.execute(&echo).await;
+        assert!(!out.is_error, "the registry keeps working after a panic");
+    }
+
+    /// Collects everything a `tracing_subscriber::fmt` layer writes.
+    #[derive(Clone, Default)]
+    struct Captured(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn panic_location_and_backtrace_reach_the_dead_letter_log() {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        // The test runs on a current-thread runtime, so the default
+        // subscriber covers the whole call.
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let mut reg = ToolRegistry::new();
+        reg.register(PanickingTool);
+        reg.execute(&boom_call()).await;
+
+        let log = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert!(log.contains("sven_tools::dead_letter"), "{log}");
+        assert!(log.contains("tool=boom"), "{log}");
+        assert!(log.contains(r#"args={"n":7}"#), "{log}");
+        assert!(
+            log.contains("location=crates/sven-tools/src/registry.rs:"),
+            "{log}"
+        );
+        assert!(log.contains("tool panicked: index out of range"), "{log}");
+        // The backtrace follows the message and names the panicking tool.
+        assert!(log.contains("PanickingTool"), "{log}");
+    }
+
     #[tokio::test]
     async fn execute_unknown_tool_returns_error() {
         let reg = ToolRegistry::new();
@@ -0,0 +1,262 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! Confinement of path arguments to a sandbox root.
+//!
+//! Tools name the arguments that hold filesystem paths
+//! ([`crate::Tool::path_args`]); when the registry has a [`Sandbox`] every
+//! such argument must resolve to a location inside its root, otherwise the
+//! call is rejected before the tool runs.
+
+use std::path::{Component, Path, PathBuf};
+
+use serde_json::Value;
+
+/// Directory that path arguments of tool calls must stay inside.
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    root: PathBuf,
+    /// Why `root` could not be used, for a sandbox that rejects every path.
+    unusable: Option<String>,
+}
+
+impl Sandbox {
+    /// Sandbox rooted at `root`, which must exist.  Symlinks in `root` are
+    /// resolved so the containment check compares real locations.
+    pub fn new(root: impl AsRef<Path>) -> std::io::Result<Self> {
+        Ok(Self {
+            root: root.as_ref().canonicalize()?,
+            unusable: None,
+        })
+    }
+
+    /// Sandbox that rejects every path, for when the intended `root` cannot
+    /// be used (`reason` says why).  Confinement fails closed: a broken
+    /// `tools.sandbox_root` must not leave tool paths unconfined.
+    pub fn deny_all(root: impl Into<PathBuf>, reason: impl Into<String>) -> Self {
+        Self {
+            root: root.into(),
+            unusable: Some(reason.into()),
+        }
+    }
+
+    /// The canonical sandbox root.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Where `path` points once `..` components and symlinks of its existing
+    /// ancestors are resolved.  Relative paths are taken from the current
+    /// working directory, as the tools themselves do.
+    pub fn resolve(&self, path: &str) -> PathBuf {
+        let path = Path::new(path);
+        let absolute = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            std::env::current_dir()
+                .unwrap_or_else(|_| self.root.clone())
+                .join(path)
+        };
+        // Walk the components in order, as the OS does: a `..` steps up from
+        // where the path so far really is, so `link/..` leaves the target of
+        // `link`, not the directory holding it.
+        let mut out = PathBuf::new();
+        for component in absolute.components() {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    out = canonicalize_existing(&out);
+                    out.pop();
+                }
+                other => out.push(other),
+            }
+        }
+        canonicalize_existing(&out)
+    }
+
+    /// Whether `path` stays inside the root.  Always false for a
+    /// [`Self::deny_all`] sandbox.
+    pub fn contains(&self, path: &str) -> bool {
+        self.unusable.is_none() && self.resolve(path).starts_with(&self.root)
+    }
+
+    /// Check the path arguments `keys` of a call.  An argument may be a
+    /// string or an array of strings; absent arguments are skipped.  A dotted
+    /// key such as `tasks.workdir` names a field of an object argument, or of
+    /// every object in an array argument.  Returns the error message for the
+    /// first path outside the root.
+    pub fn check_args(&self, args: &Value, keys: &[&str]) -> Result<(), String> {
+        for key in keys {
+            let paths = path_values(args, key);
+            if let Some(outside) = paths.into_iter().find(|p| !self.contains(p)) {
+                if let Some(reason) = &self.unusable {
+                    return Err(format!(
+                        "'{key}' path {outside} was rejected: the sandbox root {} \
+                         is unusable ({reason}); fix tools.sandbox_root \
+                         (or set tools.sandbox: false to lift this restriction)",
+                        self.root.display()
+                    ));
+                }
+                return Err(format!(
+                    "'{key}' path {outside} is outside the sandbox root {}; \
+                     only paths inside the project may be used \
+                     (set tools.sandbox: false to lift this restriction)",
+                    self.root.display()
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The strings held by argument `key` of `args` (see [`Sandbox::check_args`]).
+fn path_values<'a>(args: &'a Value, key: &str) -> Vec<&'a str> {
+    if let Some((outer, inner)) = key.split_once('.') {
+        return match args.get(outer) {
+            Some(Value::Array(items)) => items
+                .iter()
+                .flat_map(|item| path_values(item, inner))
+                .collect(),
+            Some(item) => path_values(item, inner),
+            None => Vec::new(),
+        };
+    }
+    match args.get(key) {
+        Some(Value::String(s)) => vec![s.as_str()],
+        Some(Value::Array(items)) => items.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// `path` with the symlinks of its longest existing prefix resolved.  The
+/// rest does not exist yet (a file about to be written) and is appended as
+/// is, so a symlink inside the root cannot point outside it.
+fn canonicalize_existing(path: &Path) -> PathBuf {
+    let mut existing = path;
+    let mut rest = Vec::new();
+    loop {
+        if let Ok(real) = existing.canonicalize() {
+            return rest.iter().rev().fold(real, |acc, c| acc.join(c));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn in_root_paths_are_allowed() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        let sandbox = Sandbox::new(dir.path()).unwrap();
+        let root = dir.path().display();
+        assert!(sandbox.contains(&format!("{root}/src")));
+        assert!(sandbox.contains(&format!("{root}/src/new/file.rs")));
+        assert!(sandbox.contains(&format!("{root}/src/../Cargo.toml")));
+    }
+
+    #[test]
+    fn dot_dot_escape_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let sandbox = Sandbox::new(dir.path()).unwrap();
+        let root = dir.path().display();
+        assert!(!sandbox.contains(&format!("{root}/../../etc/passwd")));
+        assert!(!sandbox.contains("/etc/passwd"));
+
+        let err = sandbox
+            .check_args(
+                &json!({"path": format!("{root}/../../etc/passwd")}),
+                &["path"],
+            )
+            .unwrap_err();
+        assert!(err.contains("outside the sandbox root"), "{err}");
+    }
+
+    #[test]
+    fn deny_all_rejects_every_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let sandbox = Sandbox::deny_all(dir.path(), "missing");
+        let inside = dir.path().join("a.rs").display().to_string();
+        assert!(!sandbox.contains(&inside));
+        let err = sandbox
+            .check_args(&json!({"path": inside}), &["path"])
+            .unwrap_err();
+        assert!(err.contains("unusable (missing)"), "{err}");
+        assert!(sandbox.check_args(&json!({}), &["path"]).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_out_of_root_is_rejected() {
+        let outside = tempfile::tempdir().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        std::os::unix::fs::symlink(outside.path(), dir.path().join("link")).unwrap();
+        let sandbox = Sandbox::new(dir.path()).unwrap();
+        assert!(!sandbox.contains(&format!("{}/link/secret", dir.path().display())));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn dot_dot_after_an_outward_symlink_is_rejected() {
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::create_dir(outside.path().join("a")).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        std::os::unix::fs::symlink(outside.path().join("a"), dir.path().join("link")).unwrap();
+        let sandbox = Sandbox::new(dir.path()).unwrap();
+        let root = dir.path().display();
+        // The OS resolves `link/..` to the parent of the link's target.
+        assert!(!sandbox.contains(&format!("{root}/link/../secret")));
+        assert!(!sandbox.contains(&format!("{root}/link/../a/new/../x")));
+        assert_eq!(
+            sandbox.resolve(&format!("{root}/link/../secret")),
+            outside.path().canonicalize().unwrap().join("secret")
+        );
+        // `..` after a real directory still stays inside.
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        assert!(sandbox.contains(&format!("{root}/src/../x")));
+    }
+
+    #[test]
+    fn array_arguments_are_checked() {
+        let dir = tempfile::tempdir().unwrap();
+        let sandbox = Sandbox::new(dir.path()).unwrap();
+        let inside = dir.path().join("a.rs").display().to_string();
+        assert!(sandbox
+            .check_args(&json!({"paths": [inside]}), &["paths"])
+            .is_ok());
+        assert!(sandbox
+            .check_args(&json!({"paths": [inside, "/etc"]}), &["paths"])
+            .is_err());
+        assert!(sandbox.check_args(&json!({}), &["paths"]).is_ok());
+    }
+
+    #[test]
+    fn nested_arguments_are_checked() {
+        let dir = tempfile::tempdir().unwrap();
+        let sandbox = Sandbox::new(dir.path()).unwrap();
+        let inside = dir.path().display().to_string();
+        let keys = &["tasks.workdir"];
+        assert!(sandbox
+            .check_args(&json!({"tasks": [{"workdir": inside}, {}]}), keys)
+            .is_ok());
+        assert!(sandbox
+            .check_args(
+                &json!({"tasks": [{"workdir": inside}, {"workdir": "/etc"}]}),
+                keys
+            )
+            .is_err());
+        assert!(sandbox
+            .check_args(&json!({"tasks": {"workdir": "/etc"}}), keys)
+            .is_err());
+    }
+}
@@ -80,6 +80,17 @@ impl ToolOutput {
         }
     }
 
+    /// Error result for a call that was cut off after `after`.
+    pub fn timed_out(call_id: impl Into<String>, tool: &str, after: std::time::Duration) -> Self {
+        Self::err(
+            call_id,
+            format!(
+                "tool '{tool}' timed out after {}s (tools.timeouts.{tool})",
+                after.as_secs()
+            ),
+        )
+    }
+
     /// Result with arbitrary parts (text and/or images).
     ///
     /// `content` is set to the concatenation of all Text parts.
@@ -166,6 +177,19 @@ pub trait Tool: Send + Sync {
     fn is_mcp(&self) -> bool {
         false
     }
+    /// Names of the arguments that hold filesystem paths (a string or an array
+    /// of strings; `outer.inner` for a field of nested objects).  The registry
+    /// rejects calls whose paths fall outside its sandbox root (see
+    /// [`crate::ToolRegistry::set_sandbox`]).
+    fn path_args(&self) -> &[&str] {
+        &[]
+    }
+    /// Whether running this tool leaves the working tree and external state
+    /// unchanged.  `sven replay` only re-runs such tools unless `--apply` is
+    /// given.  Default: `false`.
+    fn read_only(&self) -> bool {
+        false
+    }
     /// Execute the tool.  Errors should be wrapped in [`ToolOutput::err`].
     async fn execute(&self, call: &ToolCall) -> ToolOutput;
 }
@@ -13,10 +13,25 @@ use sven_tools::QuestionRequest;
 use crate::{
     app::{chat_state::ChatState, App, FocusPane},
     chat::segment::{messages_for_resubmit, ChatSegment},
+    notify,
     overlay::question::QuestionModal,
 };
 
 impl App {
+    /// Ring the bell / raise a desktop notification for a completed turn of
+    /// `session_id` when enabled and the terminal is in the background.
+    fn notify_turn_complete(&self, session_id: &sven_input::SessionId) {
+        let title = self
+            .sessions
+            .get(session_id)
+            .map_or("chat", |e| e.title.as_str());
+        if let Some(seq) =
+            notify::completion_notification(&self.config.tui, self.ui.terminal_focused, title)
+        {
+            notify::emit(&seq);
+        }
+    }
+
     // ── Agent event handler ───────────────────────────────────────────────────
 
     pub(crate) async fn handle_agent_event(
@@ -104,6 +119,7 @@ impl App {
             // Move chat to top of list when the model finishes a response (not on click).
             if matches!(event, AgentEvent::TurnComplete) {
                 self.sessions.promote_to_top(&session_id);
+                self.notify_turn_complete(&session_id);
             }
             return false;
         }
@@ -318,9 +334,16 @@ impl App {
                 }
             }
             AgentEvent::TitleGenerated(title) => {
-                self.sessions.set_title(&session_id, title.clone());
-                if session_id == self.sessions.active_id {
-                    self.chat_title = title;
+                // A title set with /title while generation was in flight wins.
+                let untitled = self
+                    .sessions
+                    .get(&session_id)
+                    .is_some_and(|e| e.title == "New chat" || e.title.is_empty());
+                if untitled {
+                    self.sessions.set_title(&session_id, title.clone());
+                    if session_id == self.sessions.active_id {
+                        self.chat_title = title;
+                    }
                 }
             }
             AgentEvent::TurnComplete => {
@@ -329,19 +352,7 @@ impl App {
                 if self.is_node_proxy
                     && (self.chat_title == "New chat" || self.chat_title.is_empty())
                 {
-                    let first_user_text = self.chat.segments.iter().find_map(|seg| {
-                        use crate::chat::segment::ChatSegment;
-                        use sven_model::{MessageContent, Role};
-                        if let ChatSegment::Message(m) = seg {
-                            if m.role == Role::User {
-                                if let MessageContent::Text(t) = &m.content {
-                                    return Some(t.clone());
-                                }
-                            }
-                        }
-                        None
-                    });
-                    if let Some(user_text) = first_user_text {
+                    if let Some(user_text) = self.first_user_text() {
                         let title = sven_input::make_title(&user_text);
                         self.chat_title = title.clone();
                         let active_id = self.sessions.active_id.clone();
@@ -374,8 +385,10 @@ impl App {
                     }
                 }
                 self.save_history_async();
+                self.flush_history_async();
                 // Move this chat to top of list when the model finishes a response (not on click).
                 self.sessions.promote_to_top(&session_id);
+                self.notify_turn_complete(&session_id);
                 // Only dequeue the next message if no queue item is being edited
                 // and an abort did not explicitly suppress auto-advance.
                 if self.edit.queue_index.is_none() && !self.queue.abort_pending {
@@ -423,6 +436,7 @@ impl App {
                     let _ = bridge.set_modifiable(true).await;
                 }
                 self.save_history_async();
+                self.flush_history_async();
                 self.rerender_chat().await;
                 self.scroll_to_bottom();
                 if let Some(pager) = &mut self.ui.pager {
@@ -514,11 +528,18 @@ impl App {
             }
             AgentEvent::ModeChanged(mode) => {
                 self.session.mode = mode;
+                self.route_model_for_mode();
             }
             AgentEvent::ModelChanged(model_str) => {
                 let resolved = sven_model::resolve_model_from_config(&self.config, &model_str);
                 self.session.stage_model(resolved);
             }
+            AgentEvent::ModelFallback { to, .. } => {
+                // Only the failing completion is rerouted, so this is status,
+                // not a model switch.
+                self.agent.current_tool = Some(format!("model unavailable — using {to}"));
+                self.rerender_chat().await;
+            }
             AgentEvent::CollabEvent(ev) => {
                 self.chat.segments.push(ChatSegment::CollabEvent(ev));
                 self.save_history_async();
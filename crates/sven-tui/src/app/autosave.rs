@@ -0,0 +1,132 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! Debounce for conversation auto-save.
+//!
+//! Streaming and rapid edits request a save far more often than the disk
+//! needs to see one.  A request writes immediately when nothing was written
+//! within the debounce window; otherwise it is coalesced into one pending
+//! write, flushed when the window closes (see [`Autosave::deadline`]), when a
+//! turn completes, and on quit.
+
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub(crate) struct Autosave {
+    debounce: Duration,
+    last_write: Option<Instant>,
+    pending: bool,
+}
+
+impl Autosave {
+    pub(crate) fn new(debounce: Duration) -> Self {
+        Self {
+            debounce,
+            last_write: None,
+            pending: false,
+        }
+    }
+
+    /// A save was requested at `now`.  Returns `true` when the caller should
+    /// write now; otherwise the write is left pending.
+    pub(crate) fn request(&mut self, now: Instant) -> bool {
+        let within_window = self
+            .last_write
+            .is_some_and(|last| now.duration_since(last) < self.debounce);
+        if within_window {
+            self.pending = true;
+            return false;
+        }
+        self.mark_written(now);
+        true
+    }
+
+    /// When a pending write should be flushed, or `None` if nothing is pending.
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        if !self.pending {
+            return None;
+        }
+        Some(
+            self.last_write
+                .map_or_else(Instant::now, |last| last + self.debounce),
+        )
+    }
+
+    /// Returns `true` (and clears the pending flag) when a pending write is
+    /// due at `now`.
+    pub(crate) fn take_due(&mut self, now: Instant) -> bool {
+        if self.deadline().is_some_and(|d| now >= d) {
+            self.mark_written(now);
+            return true;
+        }
+        false
+    }
+
+    /// Returns `true` (and clears the pending flag) when a write is pending,
+    /// regardless of the window.  Used for the forced flush on turn
+    /// completion, session switches and quit.
+    pub(crate) fn take_pending(&mut self, now: Instant) -> bool {
+        if self.pending {
+            self.mark_written(now);
+            return true;
+        }
+        false
+    }
+
+    /// Record a write made at `now` outside [`Self::request`].
+    pub(crate) fn mark_written(&mut self, now: Instant) {
+        self.last_write = Some(now);
+        self.pending = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rapid_requests_coalesce_into_one_write_plus_flush() {
+        let t0 = Instant::now();
+        let ms = Duration::from_millis;
+        let mut autosave = Autosave::new(ms(500));
+
+        let writes: usize = [0, 50, 120, 300, 450]
+            .into_iter()
+            .map(|at| autosave.request(t0 + ms(at)) as usize)
+            .sum();
+        assert_eq!(writes, 1, "only the first edit writes inside the window");
+        assert_eq!(autosave.deadline(), Some(t0 + ms(500)));
+        assert!(!autosave.take_due(t0 + ms(499)));
+
+        // The window closes: the coalesced edits are flushed once.
+        assert!(autosave.take_due(t0 + ms(500)));
+        assert!(!autosave.take_due(t0 + ms(2000)), "nothing left to flush");
+        assert_eq!(autosave.deadline(), None);
+    }
+
+    #[test]
+    fn forced_flush_writes_pending_edits_immediately() {
+        let t0 = Instant::now();
+        let ms = Duration::from_millis;
+        let mut autosave = Autosave::new(ms(500));
+        assert!(autosave.request(t0));
+        assert!(!autosave.request(t0 + ms(10)));
+        assert!(
+            autosave.take_pending(t0 + ms(20)),
+            "turn completion flushes"
+        );
+        assert!(!autosave.take_pending(t0 + ms(30)));
+        // The flush restarts the window.
+        assert!(!autosave.request(t0 + ms(100)));
+        assert!(autosave.request(t0 + ms(600)));
+        assert_eq!(autosave.deadline(), None);
+    }
+
+    #[test]
+    fn zero_debounce_writes_every_request() {
+        let t0 = Instant::now();
+        let mut autosave = Autosave::new(Duration::ZERO);
+        assert!((0..5).all(|i| autosave.request(t0 + Duration::from_millis(i))));
+        assert_eq!(autosave.deadline(), None);
+    }
+}
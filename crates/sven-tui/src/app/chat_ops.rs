@@ -25,10 +25,12 @@ use crate::{
             ChatSegment,
         },
     },
+    clipboard::copy_to_clipboard,
+    commands::CopyTarget,
     history_save, history_save_to,
-    markdown::render_markdown,
+    markdown::{render_markdown, RenderCtx},
     serialize_jsonl_records,
-    ui::theme::{BAR_AGENT, BAR_THINKING},
+    ui::theme::RolePalette,
     ui::tool_renderer,
     ui::width_utils::{col_to_byte_offset, display_width, truncate_to_width},
     ConversationRecord,
@@ -51,6 +53,8 @@ impl App {
         let mut copy_labels: std::collections::HashSet<usize> = Default::default();
         let mut line_start = 0usize;
         let ascii = self.ascii();
+        let md_ctx = self.markdown_ctx();
+        let palette = self.role_palette();
         let bar_char = if ascii { "| " } else { "▌ " };
         let bar_cols: u16 = unicode_width::UnicodeWidthStr::width(bar_char) as u16;
         // Reserve space for action labels: ↻ ✎ ✕ y  = 9 chars (+ 1 spare)
@@ -126,6 +130,7 @@ impl App {
                 tool_display_registry.clone(),
                 render_width,
                 bar_char,
+                palette,
             );
 
             let styled = if let Some(rich) = rich_lines_opt {
@@ -182,8 +187,8 @@ impl App {
                     strip_display_anchors(&raw)
                 };
 
-                let lines = render_markdown(&s, render_width, ascii);
-                let (bar_style, dim) = segment_bar_style(seg);
+                let lines = render_markdown(&s, render_width, md_ctx);
+                let (bar_style, dim) = segment_bar_style(seg, palette);
                 apply_bar_and_dim(lines, bar_style, dim, bar_char)
             };
 
@@ -223,16 +228,19 @@ impl App {
                 // Show "Seasoning" heading with scanning dot, then streaming thought below
                 // (no backticks, no 80-char clip - stream full thought in real-time)
                 let header = format!("{sep}{SYM_THINK} **Seasoning**  {dot}\n");
-                let header_lines = render_markdown(&header, render_width, ascii);
+                let header_lines = render_markdown(&header, render_width, md_ctx);
                 let header_styled = apply_bar_and_dim(
                     header_lines,
-                    Some(Style::default().fg(BAR_THINKING)),
+                    Some(Style::default().fg(palette.thinking)),
                     false,
                     bar_char,
                 );
                 // Render thinking content on next line, styled with DIM modifier
                 let thinking = format!("{}\n", self.chat.streaming_buffer);
-                let thinking_lines = render_markdown(&thinking, render_width, ascii);
+                let thinking_lines =
+                    self.chat
+                        .streaming_markdown
+                        .render(&thinking, render_width, md_ctx);
                 let dim_thinking: Vec<Line> = thinking_lines
                     .into_iter()
                     .map(|mut line| {
@@ -244,7 +252,7 @@ impl App {
                     .collect();
                 let thinking_styled = apply_bar_and_dim(
                     dim_thinking,
-                    Some(Style::default().fg(BAR_THINKING)),
+                    Some(Style::default().fg(palette.thinking)),
                     false,
                     bar_char,
                 );
@@ -260,9 +268,16 @@ impl App {
                     "\n"
                 };
                 let text = format!("{sep}**Agent:** {}{}", self.chat.streaming_buffer, cursor);
-                let lines = render_markdown(&text, render_width, ascii);
-                let styled =
-                    apply_bar_and_dim(lines, Some(Style::default().fg(BAR_AGENT)), false, bar_char);
+                let lines = self
+                    .chat
+                    .streaming_markdown
+                    .render(&text, render_width, md_ctx);
+                let styled = apply_bar_and_dim(
+                    lines,
+                    Some(Style::default().fg(palette.agent)),
+                    false,
+                    bar_char,
+                );
                 all_lines.extend(styled);
             }
         }
@@ -279,6 +294,54 @@ impl App {
         }
     }
 
+    /// Flip segment `seg_idx` between its collapsed one-line preview and its
+    /// full content, rebuild the display and keep the segment in view.
+    /// Expanding a tool call also expands its result.  Returns `false` for
+    /// segments that do not collapse.
+    pub(crate) fn toggle_segment_expand(&mut self, seg_idx: usize) -> bool {
+        let Some(seg) = self.chat.segments.get(seg_idx) else {
+            return false;
+        };
+        let is_collapsible = match seg {
+            ChatSegment::Message(m) => matches!(
+                (&m.role, &m.content),
+                (Role::User, MessageContent::Text(_))
+                    | (Role::Assistant, MessageContent::Text(_))
+                    | (Role::Assistant, MessageContent::ToolCall { .. })
+                    | (Role::Tool, MessageContent::ToolResult { .. })
+            ),
+            ChatSegment::Thinking { .. } => true,
+            _ => false,
+        };
+        if !is_collapsible {
+            return false;
+        }
+        let cur = self.chat.effective_expand_level(seg_idx, seg);
+        let next = if cur == 0 { 2 } else { 0 };
+        self.chat.expand_level.insert(seg_idx, next);
+        // When expanding a tool call, also expand the paired result
+        // so it is visible without an extra click.
+        if next >= 2 {
+            if let Some(result_idx) = self.paired_result_for(seg_idx) {
+                let result_seg = &self.chat.segments[result_idx];
+                if self.chat.effective_expand_level(result_idx, result_seg) == 0 {
+                    self.chat.expand_level.insert(result_idx, 2);
+                }
+            }
+        }
+        self.build_display_from_segments();
+        self.ui.search.update_matches(&self.chat.lines);
+        let max_offset = (self.chat.lines.len() as u16).saturating_sub(self.layout.chat_height);
+        self.chat.scroll_offset = self.chat.scroll_offset.min(max_offset);
+        if let Some(&(seg_start, _)) = self.chat.segment_line_ranges.get(seg_idx) {
+            if (seg_start as u16) < self.chat.scroll_offset {
+                self.chat.scroll_offset = seg_start as u16;
+            }
+        }
+        self.chat.focused_segment = Some(seg_idx);
+        true
+    }
+
     /// If segment `idx` is a ToolCall, return the index of the immediately
     /// following ToolResult with the same call_id (if any).
     pub(crate) fn paired_result_for(&self, idx: usize) -> Option<usize> {
@@ -292,6 +355,15 @@ impl App {
         self.chat.focused_segment = segment_at_line(&self.chat.segment_line_ranges, center);
     }
 
+    /// Erase the conversation of the active session and save the empty
+    /// history, so the agent starts the next turn without it.
+    pub(crate) async fn clear_chat(&mut self) {
+        self.chat.segments.clear();
+        self.chat.tool_args.clear();
+        self.save_history_async();
+        self.rerender_chat().await;
+    }
+
     /// Re-render the chat pane: update the Neovim buffer (if active) and
     /// rebuild the ratatui display lines.
     pub(crate) async fn rerender_chat(&mut self) {
@@ -325,6 +397,16 @@ impl App {
         self.config.tui.ascii_borders
     }
 
+    /// Markdown render settings for the configured borders and theme.
+    pub(crate) fn markdown_ctx(&self) -> RenderCtx {
+        RenderCtx::new(self.ascii(), &self.config.tui.theme)
+    }
+
+    /// Chat bar colors of the conversation roles for the configured theme.
+    pub(crate) fn role_palette(&self) -> RolePalette {
+        RolePalette::for_theme(&self.config.tui.theme)
+    }
+
     // ── Scroll helpers ────────────────────────────────────────────────────────
 
     pub(crate) fn scroll_up(&mut self, n: u16) {
@@ -406,6 +488,25 @@ impl App {
 
     // ── History persistence ───────────────────────────────────────────────────
 
+    /// Text of the first user message in the current chat, if any.
+    pub(crate) fn first_user_text(&self) -> Option<String> {
+        self.chat.segments.iter().find_map(|seg| match seg {
+            ChatSegment::Message(m) if m.role == Role::User => match &m.content {
+                MessageContent::Text(t) => Some(t.clone()),
+                _ => None,
+            },
+            _ => None,
+        })
+    }
+
+    /// Set the active session's title and persist it with the conversation.
+    pub(crate) fn set_chat_title(&mut self, title: String) {
+        let active_id = self.sessions.active_id.clone();
+        self.sessions.set_title(&active_id, title.clone());
+        self.chat_title = title;
+        self.save_history_async();
+    }
+
     /// Synchronous variant of `save_history_async` for use at clean exit.
     ///
     /// Called just before `run()` returns so that any messages typed in the
@@ -440,6 +541,26 @@ impl App {
             return;
         }
 
+        // A debounced save may still be pending; the YAML document is written
+        // below anyway, the JSONL file and history only then.
+        if self.autosave.take_pending(Instant::now()) {
+            if let Some(write_jsonl) = self.jsonl_write_job(&records) {
+                write_jsonl();
+            }
+            if let Some(path) = &self.history_path {
+                let messages: Vec<sven_model::Message> = records
+                    .iter()
+                    .filter_map(|r| match r {
+                        ConversationRecord::Message(m) => Some(m.clone()),
+                        _ => None,
+                    })
+                    .collect();
+                if let Err(e) = history_save_to(path, &messages) {
+                    debug!("failed to update conversation history on exit: {e}");
+                }
+            }
+        }
+
         let yaml_path = self.yaml_path.clone();
         let model = Some(self.session.model_display.clone());
         let mode = Some(self.session.mode.to_string());
@@ -480,7 +601,76 @@ impl App {
         }
     }
 
+    /// A job writing `records` to `jsonl_path` (appending in journal mode), or
+    /// `None` when there is no JSONL output.  With `output_image_dir`, the
+    /// job first moves embedded images into that directory.
+    fn jsonl_write_job(
+        &mut self,
+        records: &[ConversationRecord],
+    ) -> Option<Box<dyn FnOnce() + Send + 'static>> {
+        let mut records = records.to_vec();
+        let image_dir = self.output_image_dir.clone();
+        if let Some(journal) = self.jsonl_journal_for_current_path() {
+            return Some(Box::new(move || {
+                externalize_images_to(&mut records, image_dir.as_deref());
+                let mut journal = journal.lock().unwrap_or_else(|e| e.into_inner());
+                if let Err(e) = journal.write(&records) {
+                    tracing::debug!("failed to append to JSONL conversation file: {e}");
+                }
+            }));
+        }
+        let jsonl_path = self.jsonl_path.clone()?;
+        Some(Box::new(move || {
+            externalize_images_to(&mut records, image_dir.as_deref());
+            let serialized = serialize_jsonl_records(&records);
+            if let Err(e) = sven_input::write_atomic(&jsonl_path, &serialized) {
+                tracing::debug!("failed to update JSONL conversation file: {e}");
+            }
+        }))
+    }
+
+    /// The append-only journal for the current `jsonl_path`, or `None` when
+    /// the file is rewritten on every save.  Switching sessions changes the
+    /// path, which starts a new journal.
+    fn jsonl_journal_for_current_path(
+        &mut self,
+    ) -> Option<std::sync::Arc<std::sync::Mutex<sven_input::JsonlJournal>>> {
+        if !self.jsonl_append {
+            return None;
+        }
+        let path = self.jsonl_path.as_ref()?;
+        let current = self
+            .jsonl_journal
+            .as_ref()
+            .is_some_and(|j| j.lock().unwrap_or_else(|e| e.into_inner()).path() == path.as_path());
+        if !current {
+            self.jsonl_journal = Some(std::sync::Arc::new(std::sync::Mutex::new(
+                sven_input::JsonlJournal::new(path),
+            )));
+        }
+        self.jsonl_journal.clone()
+    }
+
+    /// Save the conversation, debounced by `tui.autosave_debounce_ms`: the
+    /// write happens now unless one happened within the window, in which case
+    /// it is deferred to the window's end (see the run loop).
     pub(crate) fn save_history_async(&mut self) {
+        if self.autosave.request(std::time::Instant::now()) {
+            self.write_history_async();
+        }
+    }
+
+    /// Write a deferred save now, if one is pending.  Called when a turn ends
+    /// and before switching sessions.
+    pub(crate) fn flush_history_async(&mut self) {
+        if self.autosave.take_pending(std::time::Instant::now()) {
+            self.write_history_async();
+        }
+    }
+
+    /// Write the conversation to JSONL, the YAML chat document and history
+    /// without debouncing.
+    pub(crate) fn write_history_async(&mut self) {
         let records: Vec<ConversationRecord> = self
             .chat
             .segments
@@ -524,13 +714,8 @@ impl App {
             })
             .collect();
 
-        if let Some(jsonl_path) = self.jsonl_path.clone() {
-            let serialized = serialize_jsonl_records(&records);
-            tokio::spawn(async move {
-                if let Err(e) = std::fs::write(&jsonl_path, &serialized) {
-                    tracing::debug!("failed to update JSONL conversation file: {e}");
-                }
-            });
+        if let Some(write_jsonl) = self.jsonl_write_job(&records) {
+            tokio::spawn(async move { write_jsonl() });
         }
 
         // Save as YAML chat document, preserving the original created_at timestamp.
@@ -584,7 +769,7 @@ impl App {
 
         let path_opt = self.history_path.clone();
         match path_opt {
-            None => match history_save(&messages) {
+            None => match history_save(&self.config.history, &messages) {
                 Ok(path) => {
                     debug!(path = %path.display(), "conversation saved to history");
                     self.history_path = Some(path);
@@ -647,26 +832,55 @@ impl App {
 
     // ── Clipboard copy ────────────────────────────────────────────────────────
 
-    /// Copy the text content of a segment to the terminal clipboard via OSC 52.
+    /// Copy the text content of a segment to the clipboard.
     pub(crate) fn copy_segment_to_clipboard(&self, seg_idx: usize) -> bool {
         if let Some(seg) = self.chat.segments.get(seg_idx) {
             let text = extract_segment_text(seg, &self.chat.tool_args);
             if !text.is_empty() {
-                osc52_copy(&text);
-                return true;
+                return copy_to_clipboard(&text);
             }
         }
         false
     }
 
-    /// Copy all chat content to the clipboard via OSC 52.
+    /// Copy all chat content to the clipboard.
     pub(crate) fn copy_all_to_clipboard(&self) -> bool {
         if self.chat.segments.is_empty() {
             return false;
         }
         let text = format_conversation(&self.chat.segments, "", &self.chat.tool_args);
-        osc52_copy(&text);
-        true
+        copy_to_clipboard(&text)
+    }
+
+    /// Text of the most recent agent reply.
+    pub(crate) fn last_reply_text(&self) -> Option<String> {
+        self.chat.segments.iter().rev().find_map(|seg| match seg {
+            ChatSegment::Message(m) if m.role == Role::Assistant => match &m.content {
+                MessageContent::Text(t) if !t.trim().is_empty() => Some(t.clone()),
+                _ => None,
+            },
+            _ => None,
+        })
+    }
+
+    /// `/copy`: copy `target` to the clipboard and report the outcome.
+    pub(crate) fn copy_command(&mut self, target: CopyTarget) {
+        use crate::app::ui_state::Toast;
+        let (copied, what) = match target {
+            CopyTarget::LastReply => match self.last_reply_text() {
+                Some(text) => (copy_to_clipboard(&text), "last reply"),
+                None => {
+                    self.ui.push_toast(Toast::info("No agent reply to copy"));
+                    return;
+                }
+            },
+            CopyTarget::All => (self.copy_all_to_clipboard(), "conversation"),
+        };
+        self.ui.push_toast(if copied {
+            Toast::info(format!("Copied {what} to clipboard"))
+        } else {
+            Toast::error(format!("Could not copy {what} to clipboard"))
+        });
     }
 }
 
@@ -834,19 +1048,6 @@ fn make_grouped_preview(
     format!("\n{SYM_TOOL}  {label}{summary_part}  {status_sym}{duration}  {SYM_EXPAND}\n")
 }
 
-// ── Clipboard via OSC 52 ──────────────────────────────────────────────────────
-
-/// Copy `text` to the terminal clipboard using the OSC 52 escape sequence.
-/// Works in most modern terminals (kitty, alacritty, tmux with allow-passthrough,
-/// iTerm2, foot, wezterm) without any native library dependency.
-fn osc52_copy(text: &str) {
-    use std::io::Write;
-    let encoded = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, text);
-    let seq = format!("\x1b]52;c;{encoded}\x07");
-    let _ = std::io::stdout().write_all(seq.as_bytes());
-    let _ = std::io::stdout().flush();
-}
-
 // ── Text extraction for clipboard ────────────────────────────────────────────
 
 fn extract_segment_text(
@@ -884,14 +1085,13 @@ fn extract_segment_text(
 
 impl App {
     /// Copy the currently selected text (from a mouse drag selection) to the
-    /// terminal clipboard via OSC 52.  Shows a toast on success.
+    /// clipboard.  Shows a toast on success.
     pub(crate) fn copy_selection_to_clipboard(&mut self) {
         let Some((s_line, s_col, e_line, e_col)) = self.chat.normalized_selection() else {
             return;
         };
         let text = extract_selection_text(&self.chat.lines, s_line, s_col, e_line, e_col);
-        if !text.is_empty() {
-            osc52_copy(&text);
+        if !text.is_empty() && copy_to_clipboard(&text) {
             self.ui
                 .push_toast(crate::app::ui_state::Toast::info("Selection copied"));
         }
@@ -1038,10 +1238,9 @@ fn render_segment_rich(
     tool_display_registry: ToolDisplayRegistryRef,
     render_width: u16,
     bar_char: &str,
+    palette: RolePalette,
 ) -> Option<crate::markdown::StyledLines> {
-    use crate::ui::theme::BAR_TOOL;
-
-    let bar_style = Style::default().fg(BAR_TOOL);
+    let bar_style = Style::default().fg(palette.tool);
 
     match seg {
         // ── Tool call ─────────────────────────────────────────────────────────
@@ -1350,3 +1549,87 @@ fn tool_renderer_accent(
         _ => crate::ui::theme::BAR_TOOL,
     }
 }
+
+/// Move the embedded images of `records` into `dir` when one is set
+/// (`--output-image-dir`).
+fn externalize_images_to(records: &mut [ConversationRecord], dir: Option<&std::path::Path>) {
+    if let Some(dir) = dir {
+        if let Err(e) = sven_input::externalize_images(records, dir) {
+            debug!("failed to extract images to {}: {e:#}", dir.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sven_model::Message;
+
+    use super::*;
+
+    fn segment_text(app: &App, idx: usize) -> String {
+        let (start, end) = app.chat.segment_line_ranges[idx];
+        app.chat.lines[start..end]
+            .iter()
+            .map(|l| {
+                l.spans
+                    .iter()
+                    .map(|s| s.content.as_ref())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[tokio::test]
+    async fn toggling_thinking_recomputes_segment_ranges() {
+        let (mut app, _rx) = App::for_testing();
+        app.inject_chat_user_message("why does it hang?");
+        app.chat.segments.push(ChatSegment::Thinking {
+            content: "check the lock order\nthen the channel\nthen the timeout".into(),
+        });
+        app.chat
+            .segments
+            .push(ChatSegment::Message(Message::assistant("A lock cycle.")));
+        app.build_display_from_segments();
+        let collapsed = app.chat.segment_line_ranges.clone();
+        assert!(segment_text(&app, 1).contains("reasoning, 3 lines"));
+        assert!(!segment_text(&app, 1).contains("lock order"));
+
+        assert!(app.toggle_segment_expand(1));
+        let expanded = app.chat.segment_line_ranges.clone();
+        assert!(segment_text(&app, 1).contains("then the timeout"));
+        let grown = (expanded[1].1 - expanded[1].0) - (collapsed[1].1 - collapsed[1].0);
+        assert!(grown >= 2, "{collapsed:?} -> {expanded:?}");
+        assert_eq!(expanded[0], collapsed[0]);
+        assert_eq!(expanded[1].0, collapsed[1].0);
+        assert_eq!(
+            expanded[2],
+            (collapsed[2].0 + grown, collapsed[2].1 + grown)
+        );
+        assert!(segment_text(&app, 2).contains("A lock cycle."));
+
+        assert!(app.toggle_segment_expand(1));
+        assert_eq!(app.chat.segment_line_ranges, collapsed);
+        assert!(!app.toggle_segment_expand(99));
+    }
+
+    #[tokio::test]
+    async fn jsonl_output_writes_images_to_the_output_image_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let jsonl = dir.path().join("run.jsonl");
+        let (mut app, _rx) = App::for_testing();
+        app.jsonl_path = Some(jsonl.clone());
+        app.output_image_dir = Some(dir.path().join("images"));
+        let records = vec![ConversationRecord::Message(Message::user_with_parts(vec![
+            sven_model::ContentPart::image("data:image/png;base64,iVBORw0KGgo="),
+        ]))];
+
+        app.jsonl_write_job(&records).unwrap()();
+
+        let written = std::fs::read_to_string(&jsonl).unwrap();
+        assert!(written.contains("file://"), "{written}");
+        assert!(!written.contains("base64"), "{written}");
+        let images = std::fs::read_dir(dir.path().join("images")).unwrap();
+        assert_eq!(images.count(), 1);
+    }
+}
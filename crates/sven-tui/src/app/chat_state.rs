@@ -5,7 +5,10 @@
 
 use std::collections::{HashMap, HashSet};
 
-use crate::{chat::segment::ChatSegment, markdown::StyledLines};
+use crate::{
+    chat::segment::ChatSegment,
+    markdown::{StreamingMarkdown, StyledLines},
+};
 
 /// Expand level for a collapsible segment.
 ///
@@ -25,6 +28,9 @@ pub(crate) struct ChatState {
     pub streaming_buffer: String,
     /// True while receiving `ThinkingDelta` events; controls thinking-block style.
     pub streaming_is_thinking: bool,
+    /// Incremental renderer for `streaming_buffer`, so each delta re-wraps
+    /// only the unfinished tail of the text.
+    pub streaming_markdown: StreamingMarkdown,
     /// `(start_line, end_line)` in `lines` for each segment in `segments`.
     /// Rebuilt whenever `build_display_from_segments` runs.
     pub segment_line_ranges: Vec<(usize, usize)>,
@@ -76,6 +82,7 @@ impl ChatState {
             segments: Vec::new(),
             streaming_buffer: String::new(),
             streaming_is_thinking: false,
+            streaming_markdown: StreamingMarkdown::default(),
             segment_line_ranges: Vec::new(),
             scroll_offset: 0,
             auto_scroll: true,
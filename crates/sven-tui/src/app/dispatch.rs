@@ -41,7 +41,7 @@ Scrolling
 Other
   /           Search
   q           Focus queue panel
-  Space       Toggle delegate summary
+  Space       Expand / collapse segment
   ?           Show this help";
 
 impl App {
@@ -294,6 +294,7 @@ impl App {
                         self.chat.focused_segment = None;
                         let qm = QueuedMessage {
                             content: user_text,
+                            images: Vec::new(),
                             model_transition: None,
                             mode_transition: None,
                         };
@@ -355,22 +356,7 @@ impl App {
                     };
                     match (&seg.role, &seg.content) {
                         (Role::User, MessageContent::Text(_)) => {
-                            let (staged_model, staged_mode) = self.session.consume_staged();
-                            let qm = crate::app::QueuedMessage {
-                                content: new_content.clone(),
-                                model_transition: staged_model
-                                    .map(|c| crate::app::ModelDirective::SwitchTo(Box::new(c))),
-                                mode_transition: staged_mode,
-                            };
-                            self.chat.segments.truncate(i + 1);
-                            self.chat.segments.pop();
-                            self.chat
-                                .segments
-                                .push(ChatSegment::Message(Message::user(&new_content)));
-                            let messages = messages_for_resubmit(&self.chat.segments);
-                            self.rerender_chat().await;
-                            self.scroll_to_bottom();
-                            self.send_resubmit_to_agent(messages, qm).await;
+                            self.resubmit_user_message(i, new_content).await;
                         }
                         (Role::Assistant, MessageContent::Text(_)) => {
                             if let Some(ChatSegment::Message(m)) = self.chat.segments.get_mut(i) {
@@ -590,9 +576,14 @@ impl App {
                 self.scroll_chat_to_show_segment(prev);
             }
             Action::ShowChatHelp => {
+                // Space only expands plain segments when nvim is off.
+                let help: Vec<&str> = CHAT_HELP_MESSAGE
+                    .lines()
+                    .filter(|l| self.nvim.disabled || !l.trim_start().starts_with("Space"))
+                    .collect();
                 self.ui.confirm_modal = Some(ConfirmModal::info_with_border(
                     "Chat shortcuts",
-                    CHAT_HELP_MESSAGE,
+                    help.join("\n"),
                     ratatui::style::Color::Green,
                 ));
             }
@@ -644,15 +635,13 @@ impl App {
             }
 
             Action::SearchOpen => {
-                self.ui.search.query.clear();
-                self.ui.search.current = 0;
-                self.ui.search.update_matches(&self.chat.lines);
-                self.ui.search.active = true;
+                let offset = self.search_view_offset();
+                self.ui.search.begin(offset);
                 self.ui.focus = FocusPane::Chat;
                 self.recompute_focused_segment();
             }
             Action::SearchClose => {
-                self.ui.search.active = false;
+                self.ui.search.accept();
                 if let Some(line) = self.ui.search.current_line() {
                     if self.ui.inspector.is_some() {
                         if let Some(insp) = &mut self.ui.inspector {
@@ -665,50 +654,20 @@ impl App {
                     }
                 }
             }
-            Action::SearchInput(c) => {
-                self.ui.search.query.push(c);
-                if self.ui.inspector.is_some() {
-                    // Search scoped to the inspector's own content.
-                    let lines = self
-                        .ui
-                        .inspector
-                        .as_ref()
-                        .map(|i| i.pager.cloned_lines())
-                        .unwrap_or_default();
-                    self.ui.search.update_matches(&lines);
-                    if let Some(line) = self.ui.search.current_line() {
-                        if let Some(insp) = &mut self.ui.inspector {
-                            insp.pager.scroll_to_line(line);
-                        }
-                    }
-                } else {
-                    self.ui.search.update_matches(&self.chat.lines);
-                    if let Some(line) = self.ui.search.current_line() {
-                        self.chat.scroll_offset = line as u16;
-                        if let Some(pager) = &mut self.ui.pager {
-                            pager.scroll_to_line(line);
-                        }
-                    }
+            Action::SearchCancel => {
+                if let Some(offset) = self.ui.search.cancel() {
+                    self.restore_search_view(offset);
                 }
             }
+            Action::SearchInput(c) => {
+                let mut query = self.ui.search.query.clone();
+                query.push(c);
+                self.search_incremental(query);
+            }
             Action::SearchBackspace => {
-                self.ui.search.query.pop();
-                if self.ui.inspector.is_some() {
-                    let lines = self
-                        .ui
-                        .inspector
-                        .as_ref()
-                        .map(|i| i.pager.cloned_lines())
-                        .unwrap_or_default();
-                    self.ui.search.update_matches(&lines);
-                    if let Some(line) = self.ui.search.current_line() {
-                        if let Some(insp) = &mut self.ui.inspector {
-                            insp.pager.scroll_to_line(line);
-                        }
-                    }
-                } else {
-                    self.ui.search.update_matches(&self.chat.lines);
-                }
+                let mut query = self.ui.search.query.clone();
+                query.pop();
+                self.search_incremental(query);
             }
             Action::SearchNextMatch => {
                 if !self.ui.search.matches.is_empty() {
@@ -765,6 +724,7 @@ impl App {
                 self.input.cursor += 1;
                 self.ui.completion = None;
             }
+            Action::PasteImage => self.paste_clipboard_image(),
             Action::InputBackspace => {
                 if self.input.cursor > 0 {
                     let prev = prev_char_boundary(&self.input.buffer, self.input.cursor);
@@ -921,31 +881,22 @@ impl App {
                     return false;
                 }
                 // Prepend attachment paths to the submitted text.
-                let full_text = if self.input.attachments.is_empty() {
-                    text.clone()
+                let (full_text, mut images) = self.input.take_attachments(&text);
+                let full_text = if full_text.starts_with('/') {
+                    full_text
                 } else {
-                    let att_text: String = self
-                        .input
-                        .attachments
-                        .iter()
-                        .map(|a| a.to_message_text())
-                        .collect::<Vec<_>>()
-                        .join("\n");
-                    if text.is_empty() {
-                        att_text
-                    } else {
-                        format!("{att_text}\n{text}")
-                    }
+                    let (rewritten, inline) = self.attach_inline_image_paths(&full_text);
+                    images.extend(inline);
+                    rewritten
                 };
-                self.input.attachments.clear();
                 // Save to history (only the user-typed text, not the attachment metadata).
                 if !text.is_empty() {
                     self.input.push_history(&text);
                 }
-                if full_text.is_empty() {
+                if full_text.is_empty() && images.is_empty() {
                     return false;
                 }
-                return self.submit_user_input(&full_text).await;
+                return self.submit_user_input(&full_text, images).await;
             }
 
             Action::CompletionNext => {
@@ -975,28 +926,12 @@ impl App {
                             if text.is_empty() && self.input.attachments.is_empty() {
                                 return false;
                             }
-                            let full_text = if self.input.attachments.is_empty() {
-                                text.clone()
-                            } else {
-                                let att_text: String = self
-                                    .input
-                                    .attachments
-                                    .iter()
-                                    .map(|a| a.to_message_text())
-                                    .collect::<Vec<_>>()
-                                    .join("\n");
-                                if text.is_empty() {
-                                    att_text
-                                } else {
-                                    format!("{att_text}\n{text}")
-                                }
-                            };
-                            self.input.attachments.clear();
+                            let (full_text, images) = self.input.take_attachments(&text);
                             if !text.is_empty() {
                                 self.input.push_history(&text);
                             }
                             if !full_text.is_empty() {
-                                return self.submit_user_input(&full_text).await;
+                                return self.submit_user_input(&full_text, images).await;
                             }
                         }
                     }
@@ -1048,7 +983,8 @@ impl App {
 
             Action::CycleMode => {
                 if !self.is_node_proxy {
-                    self.session.cycle_mode();
+                    self.session.cycle_mode(&self.config.agent.all_modes());
+                    self.route_model_for_mode();
                 }
             }
 
@@ -1133,6 +1069,8 @@ impl App {
                     {
                         *expanded = !*expanded;
                         self.rerender_chat().await;
+                    } else if self.nvim.disabled {
+                        self.toggle_segment_expand(seg_idx);
                     }
                 }
             }
@@ -1317,47 +1255,7 @@ impl App {
 
                 // Expand/collapse if the click lands on a collapsible segment.
                 if let Some(seg_idx) = segment_at_line(&self.chat.segment_line_ranges, abs_line) {
-                    let is_collapsible = match self.chat.segments.get(seg_idx) {
-                        Some(ChatSegment::Message(m)) => matches!(
-                            (&m.role, &m.content),
-                            (Role::User, MessageContent::Text(_))
-                                | (Role::Assistant, MessageContent::Text(_))
-                                | (Role::Assistant, MessageContent::ToolCall { .. })
-                                | (Role::Tool, MessageContent::ToolResult { .. })
-                        ),
-                        Some(ChatSegment::Thinking { .. }) => true,
-                        _ => false,
-                    };
-                    if is_collapsible {
-                        if let Some(seg) = self.chat.segments.get(seg_idx) {
-                            let cur = self.chat.effective_expand_level(seg_idx, seg);
-                            let next = if cur == 0 { 2 } else { 0 };
-                            self.chat.expand_level.insert(seg_idx, next);
-                            // When expanding a tool call, also expand the paired result
-                            // so it is visible without an extra click.
-                            if next >= 2 {
-                                if let Some(result_idx) = self.paired_result_for(seg_idx) {
-                                    let result_seg = self.chat.segments[result_idx].clone();
-                                    let cur_result =
-                                        self.chat.effective_expand_level(result_idx, &result_seg);
-                                    if cur_result == 0 {
-                                        self.chat.expand_level.insert(result_idx, 2);
-                                    }
-                                }
-                            }
-                        }
-                        self.build_display_from_segments();
-                        self.ui.search.update_matches(&self.chat.lines);
-                        let max_offset =
-                            (self.chat.lines.len() as u16).saturating_sub(self.layout.chat_height);
-                        self.chat.scroll_offset = self.chat.scroll_offset.min(max_offset);
-                        if let Some(&(seg_start, _)) = self.chat.segment_line_ranges.get(seg_idx) {
-                            if (seg_start as u16) < self.chat.scroll_offset {
-                                self.chat.scroll_offset = seg_start as u16;
-                            }
-                        }
-                        self.chat.focused_segment = Some(seg_idx);
-                    }
+                    self.toggle_segment_expand(seg_idx);
                 }
             }
 
@@ -1466,6 +1364,59 @@ impl App {
 
     // ── Slash command completion ──────────────────────────────────────────────
 
+    /// Scroll offset of the view a search runs over: the inspector, the
+    /// pager or the chat pane.
+    fn search_view_offset(&self) -> usize {
+        if let Some(insp) = &self.ui.inspector {
+            insp.pager.scroll_offset
+        } else if let Some(pager) = &self.ui.pager {
+            pager.scroll_offset
+        } else {
+            usize::from(self.chat.scroll_offset)
+        }
+    }
+
+    /// Scroll the searched view back to `offset` from [`Self::search_view_offset`].
+    fn restore_search_view(&mut self, offset: usize) {
+        if let Some(insp) = &mut self.ui.inspector {
+            insp.pager.scroll_offset = offset;
+        } else if let Some(pager) = &mut self.ui.pager {
+            pager.scroll_offset = offset;
+        } else {
+            self.chat.scroll_offset = u16::try_from(offset).unwrap_or(u16::MAX);
+        }
+    }
+
+    /// Search for `query` as typed so far and show its first match, or the
+    /// position the search started from while nothing matches.
+    fn search_incremental(&mut self, query: String) {
+        let line = match &self.ui.inspector {
+            // Search scoped to the inspector's own content.
+            Some(insp) => {
+                let lines = insp.pager.cloned_lines();
+                self.ui.search.set_query(query, &lines)
+            }
+            None => self.ui.search.set_query(query, &self.chat.lines),
+        };
+        match line {
+            Some(line) => {
+                if let Some(insp) = &mut self.ui.inspector {
+                    insp.pager.scroll_to_line(line);
+                } else {
+                    self.chat.scroll_offset = line as u16;
+                    if let Some(pager) = &mut self.ui.pager {
+                        pager.scroll_to_line(line);
+                    }
+                }
+            }
+            None => {
+                if let Some(offset) = self.ui.search.saved_offset {
+                    self.restore_search_view(offset);
+                }
+            }
+        }
+    }
+
     fn command_line_at_cursor(&self) -> (usize, String) {
         let before_cursor = &self.input.buffer[..self.input.cursor];
         let start = before_cursor.rfind('\n').map(|i| i + 1).unwrap_or(0);
@@ -5,6 +5,8 @@
 
 use std::path::PathBuf;
 
+use sven_image::EncodedImage;
+
 // ── InputAttachment ───────────────────────────────────────────────────────────
 
 /// An image the user attached to the current message via paste.
@@ -14,11 +16,40 @@ use std::path::PathBuf;
 #[derive(Debug, Clone)]
 pub struct InputAttachment {
     pub path: PathBuf,
+    /// Encoded image sent to the model along with the message.  `None` when
+    /// the model only gets the path (it cannot take images).
+    pub image: Option<EncodedImage>,
+    /// Pasted from the clipboard: `path` is only a display name and is not
+    /// mentioned in the message text.
+    pub from_clipboard: bool,
 }
 
 impl InputAttachment {
     pub fn new(path: PathBuf) -> Self {
-        Self { path }
+        Self {
+            path,
+            image: None,
+            from_clipboard: false,
+        }
+    }
+
+    /// Attachment for the image file `path` with its encoded content.
+    pub fn with_image(path: PathBuf, image: EncodedImage) -> Self {
+        Self {
+            path,
+            image: Some(image),
+            from_clipboard: false,
+        }
+    }
+
+    /// Attachment for the `n`-th image pasted from the clipboard.
+    pub fn pasted(n: usize, image: EncodedImage) -> Self {
+        let ext = image.mime_type.strip_prefix("image/").unwrap_or("img");
+        Self {
+            path: PathBuf::from(format!("clipboard-{n}.{ext}")),
+            image: Some(image),
+            from_clipboard: true,
+        }
     }
 
     /// Short display name (just the file name, not the full path).
@@ -96,6 +127,33 @@ impl InputState {
         }
     }
 
+    /// Take the attachments for sending `text`: returns the message text with
+    /// a line per attached file prepended, and the data URLs of the images
+    /// that go to the model as content.
+    pub fn take_attachments(&mut self, text: &str) -> (String, Vec<String>) {
+        if self.attachments.is_empty() {
+            return (text.to_string(), Vec::new());
+        }
+        let attachments = std::mem::take(&mut self.attachments);
+        let att_text = attachments
+            .iter()
+            .filter(|a| !a.from_clipboard)
+            .map(|a| a.to_message_text())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let images = attachments
+            .into_iter()
+            .filter_map(|a| a.image)
+            .map(EncodedImage::into_data_url)
+            .collect();
+        let full_text = match (att_text.is_empty(), text.is_empty()) {
+            (_, true) => att_text,
+            (true, false) => text.to_string(),
+            (false, false) => format!("{att_text}\n{text}"),
+        };
+        (full_text, images)
+    }
+
     /// Push a newly-submitted message into the history ring.
     pub fn push_history(&mut self, text: &str) {
         let text = text.trim().to_string();
@@ -5,12 +5,14 @@
 
 pub(crate) mod agent_conn;
 pub(crate) mod agent_events;
+pub(crate) mod autosave;
 pub(crate) mod chat_ops;
 pub(crate) mod chat_state;
 pub(crate) mod dispatch;
 pub(crate) mod hit_test;
 pub(crate) mod input_state;
 pub(crate) mod layout_cache;
+pub(crate) mod nvim_resize;
 pub(crate) mod nvim_state;
 pub(crate) mod queue_state;
 pub(crate) mod session_manager;
@@ -37,7 +39,7 @@ use crate::{
     agent::{agent_task, AgentRequest},
     chat::segment::ChatSegment,
     commands::{CommandRegistry, CompletionManager},
-    keys::Action,
+    keys::{Action, Keymap},
     layout::AppLayout,
     markdown::StyledLines,
     node_agent::node_agent_task,
@@ -47,6 +49,7 @@ use crate::{
         ConfirmModalView, HelpOverlay, InputEditMode, InputPane, QuestionModalView, QueueItem,
         QueuePanel, SearchBar, StatusBar, ToastStack, WelcomeScreen, WhichKeyOverlay,
     },
+    vi::ViState,
 };
 
 pub(crate) use agent_conn::AgentConn;
@@ -75,6 +78,12 @@ pub struct AppOptions {
     pub model_override: Option<String>,
     pub jsonl_path: Option<PathBuf>,
     pub jsonl_load_path: Option<PathBuf>,
+    /// Append only the new records to `jsonl_path` after each turn instead
+    /// of rewriting the whole file.
+    pub jsonl_append: bool,
+    /// Write images of the JSONL output to files in this directory and
+    /// reference them by path (`--output-image-dir`).
+    pub output_image_dir: Option<PathBuf>,
     pub initial_queue: Vec<QueuedMessage>,
     /// When `Some`, connect the TUI to a running node instead of running a
     /// local agent.  Gives the TUI full access to the node's P2P tools.
@@ -107,6 +116,10 @@ pub struct App {
     pub(crate) session: crate::state::SessionState,
     pub(crate) command_registry: Arc<CommandRegistry>,
     pub(crate) completion_manager: CompletionManager,
+    /// User key bindings from `tui.keymap`.
+    pub(crate) keymap: Keymap,
+    /// Modal editing state; `Some` when `tui.vi_mode` is on.
+    pub(crate) vi: Option<ViState>,
     pub(crate) shared_skills: sven_runtime::SharedSkills,
     pub(crate) shared_agents: sven_runtime::SharedAgents,
     /// Shared tool snapshot — populated by AgentBuilder after the local tool
@@ -130,6 +143,17 @@ pub struct App {
     pub(crate) shared_tool_displays: sven_tools::SharedToolDisplays,
     pub(crate) history_path: Option<PathBuf>,
     pub(crate) jsonl_path: Option<PathBuf>,
+    /// Append new records to `jsonl_path` instead of rewriting it
+    /// (`--jsonl-append`).
+    pub(crate) jsonl_append: bool,
+    /// Directory the images of the JSONL output are written to
+    /// (`--output-image-dir`).
+    pub(crate) output_image_dir: Option<PathBuf>,
+    /// Journal for the current `jsonl_path` in append-only mode, created on
+    /// the first save.
+    pub(crate) jsonl_journal: Option<Arc<std::sync::Mutex<sven_input::JsonlJournal>>>,
+    /// Debounce for `save_history_async` (`tui.autosave_debounce_ms`).
+    pub(crate) autosave: autosave::Autosave,
     /// Set to `true` after a tool call completes — triggers a terminal-state
     /// recovery pass before the next draw.
     pub(crate) needs_terminal_recover: bool,
@@ -164,6 +188,13 @@ pub struct App {
 
 impl App {
     pub fn new(config: Arc<Config>, opts: AppOptions) -> Self {
+        // `sven` validates the keymap before starting the TUI.
+        let keymap = Keymap::from_config(&config.tui.keymap).unwrap_or_else(|e| {
+            tracing::warn!("ignoring tui.keymap: {e:#}");
+            Keymap::default()
+        });
+        let vi = config.tui.vi_mode.then(ViState::default);
+
         let (initial_segments, history_path) = opts
             .initial_history
             .map(|(segs, path)| (segs, Some(path)))
@@ -173,41 +204,12 @@ impl App {
             if jsonl.exists() {
                 match std::fs::read_to_string(jsonl) {
                     Ok(content) => match sven_input::parse_jsonl_full(&content) {
-                        Ok(parsed) => parsed
-                            .records
-                            .into_iter()
-                            .filter_map(|r| match r {
-                                sven_input::ConversationRecord::Message(m) => {
-                                    if m.role != sven_model::Role::System {
-                                        Some(ChatSegment::Message(m))
-                                    } else {
-                                        None
-                                    }
-                                }
-                                sven_input::ConversationRecord::Thinking { content } => {
-                                    Some(ChatSegment::Thinking { content })
-                                }
-                                sven_input::ConversationRecord::ContextCompacted {
-                                    tokens_before,
-                                    tokens_after,
-                                    strategy,
-                                    turn,
-                                } => {
-                                    use sven_core::CompactionStrategyUsed;
-                                    let strategy = match strategy.as_deref() {
-                                        Some("emergency") => CompactionStrategyUsed::Emergency,
-                                        Some("narrative") => CompactionStrategyUsed::Narrative,
-                                        _ => CompactionStrategyUsed::Structured,
-                                    };
-                                    Some(ChatSegment::ContextCompacted {
-                                        tokens_before,
-                                        tokens_after,
-                                        strategy,
-                                        turn: turn.unwrap_or(0),
-                                    })
-                                }
-                            })
-                            .collect(),
+                        Ok(mut parsed) => {
+                            if let Err(e) = parsed.inline_image_files() {
+                                debug!("failed to load images of JSONL conversation file: {e:#}");
+                            }
+                            sven_frontend::segments_from_records(parsed.records)
+                        }
                         Err(e) => {
                             debug!("failed to parse JSONL conversation file: {e}");
                             initial_segments
@@ -228,7 +230,7 @@ impl App {
         let initial_model_cfg = if let Some(ref mo) = opts.model_override {
             sven_model::resolve_model_from_config(&config, mo)
         } else {
-            config.model.clone()
+            sven_model::routed_model(&config, opts.mode).unwrap_or_else(|| config.model.clone())
         };
 
         let project_root = sven_runtime::find_project_root().ok();
@@ -338,6 +340,10 @@ impl App {
             .map(|d| d.title.clone())
             .unwrap_or_else(|| "New chat".to_string());
 
+        let autosave = autosave::Autosave::new(std::time::Duration::from_millis(
+            config.tui.autosave_debounce_ms,
+        ));
+
         let mut app = Self {
             config,
             node_backend: opts.node_backend,
@@ -345,9 +351,17 @@ impl App {
             node_url,
             node_token,
             node_insecure,
-            session: crate::state::SessionState::new(initial_model_cfg, opts.mode),
+            session: {
+                let mut session = crate::state::SessionState::new(initial_model_cfg, opts.mode);
+                if opts.model_override.is_some() {
+                    session.pin_model();
+                }
+                session
+            },
             command_registry: registry,
             completion_manager,
+            keymap,
+            vi,
             shared_skills,
             shared_agents,
             shared_tools,
@@ -356,6 +370,10 @@ impl App {
             mcp_prompt_commands: std::collections::HashMap::new(),
             mcp_refresh_tx: None,
             history_path,
+            jsonl_append: opts.jsonl_append,
+            output_image_dir: opts.output_image_dir,
+            jsonl_journal: None,
+            autosave,
             jsonl_path,
             needs_terminal_recover: false,
             buffer_store,
@@ -548,6 +566,11 @@ impl App {
                 streaming_tokens: self.agent.streaming_tokens,
                 in_edit,
                 in_search: self.ui.search.active,
+                vi_mode: self
+                    .vi
+                    .as_ref()
+                    .filter(|_| self.ui.focus == FocusPane::Input)
+                    .map(|vi| vi.mode),
                 team_name: self.ui.team_name.as_deref(),
                 team_role: None, // TODO: wire from team config
                 team_active_count,
@@ -1169,6 +1192,9 @@ impl App {
                     let _ = terminal.clear();
                 }
                 let _ = execute!(std::io::stdout(), EnableMouseCapture);
+                if self.config.tui.notify_on_complete {
+                    let _ = execute!(std::io::stdout(), crossterm::event::EnableFocusChange);
+                }
                 let _ = execute!(
                     std::io::stdout(),
                     PushKeyboardEnhancementFlags(
@@ -1178,12 +1204,22 @@ impl App {
                             | KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES
                     )
                 );
+                // A tool that took over the terminal may also have raced
+                // Neovim's redraws; rebuild the grid rather than trust it.
+                if let Some(nvim_bridge) = &self.nvim.bridge {
+                    if let Err(e) = nvim_bridge.lock().await.resync().await {
+                        tracing::warn!("Failed to resync Neovim grid: {}", e);
+                    }
+                }
             }
 
             // ── Compute Neovim render data (async, before draw) ───────────────
             let (nvim_lines, nvim_draw_scroll, nvim_cursor) =
                 if let Some(nvim_bridge) = &self.nvim.bridge {
-                    let bridge = nvim_bridge.lock().await;
+                    let mut bridge = nvim_bridge.lock().await;
+                    if let Err(e) = bridge.resync_if_desynced().await {
+                        tracing::warn!("Failed to resync Neovim grid: {}", e);
+                    }
                     let lines = bridge.render_to_lines(0, bridge.height).await;
                     let cursor = bridge.get_cursor_pos().await;
                     (lines, 0u16, Some(cursor))
@@ -1200,6 +1236,8 @@ impl App {
             let flush_notify_clone = self.nvim.flush_notify.clone();
             let submit_notify_clone = self.nvim.submit_notify.clone();
             let quit_notify_clone = self.nvim.quit_notify.clone();
+            let autosave_deadline = self.autosave.deadline();
+            let nvim_resize_deadline = self.nvim.resize.deadline();
             tokio::select! {
                 Some((session_id, agent_event)) = self.recv_agent_event() => {
                     if self.handle_agent_event(session_id, agent_event).await { break; }
@@ -1213,6 +1251,14 @@ impl App {
                 Some(toast) = toast_rx.recv() => {
                     self.ui.push_toast(toast);
                 }
+                _ = Self::deadline_future(autosave_deadline) => {
+                    if self.autosave.take_due(std::time::Instant::now()) {
+                        self.write_history_async();
+                    }
+                }
+                _ = Self::deadline_future(nvim_resize_deadline) => {
+                    self.flush_nvim_resize().await;
+                }
                 _ = anim_tick.tick(), if self.agent.busy || self.sessions.any_background_busy() => {
                     // Advance the clock-driven animation frame and rebuild the
                     // display so animated indicators update at a steady 80ms rate.
@@ -1238,6 +1284,29 @@ impl App {
         Ok(())
     }
 
+    /// Sends the settled chat pane size to Neovim once the resize debounce
+    /// window has passed.
+    async fn flush_nvim_resize(&mut self) {
+        let Some((width, height)) = self.nvim.resize.take_due(std::time::Instant::now()) else {
+            return;
+        };
+        if let Some(nvim_bridge) = &self.nvim.bridge {
+            let mut bridge = nvim_bridge.lock().await;
+            if let Err(e) = bridge.resize(width, height).await {
+                tracing::error!("Failed to resize Neovim UI: {}", e);
+            }
+        }
+    }
+
+    /// Resolves at a debounce deadline (auto-save, Neovim resize); never
+    /// when nothing is pending.
+    async fn deadline_future(deadline: Option<std::time::Instant>) {
+        match deadline {
+            Some(at) => tokio::time::sleep_until(at.into()).await,
+            None => std::future::pending().await,
+        }
+    }
+
     pub(crate) async fn recv_agent_event(&mut self) -> Option<(sven_input::SessionId, AgentEvent)> {
         self.sessions.multi_event_rx.recv().await
     }
@@ -1248,6 +1317,8 @@ impl App {
     pub(crate) async fn new_session(&mut self) {
         // Snapshot active chat into its session entry before creating the new one.
         self.save_active_to_session_entry();
+        // A debounced save belongs to the chat being left.
+        self.flush_history_async();
 
         // Create the session entry and make it active.
         let new_id = self.sessions.create_session("New chat");
@@ -1283,6 +1354,7 @@ impl App {
     pub(crate) async fn switch_session(&mut self, target_id: sven_input::SessionId) {
         // Save active state.
         self.save_active_to_session_entry();
+        self.flush_history_async();
 
         // Swap in the target session's stored state.
         let target_chat = self
@@ -1603,6 +1675,17 @@ impl App {
         }
     }
 
+    /// Follow `agent.routing` after a mode change: switch to the model routed
+    /// for the new mode unless the user pinned one.  The node owns model
+    /// selection in node-proxy mode.
+    pub(crate) fn route_model_for_mode(&mut self) {
+        if self.is_node_proxy {
+            return;
+        }
+        let routed = sven_model::routed_model(&self.config, self.session.mode);
+        self.session.route_model(routed);
+    }
+
     /// Compute scroll offset for the peers pane.
     pub(crate) fn peers_scroll_offset(&self) -> usize {
         let pp = self.layout.peers_pane;
@@ -1632,6 +1715,8 @@ impl App {
             model_override: None,
             jsonl_path: None,
             jsonl_load_path: None,
+            jsonl_append: false,
+            output_image_dir: None,
             initial_queue: Vec::new(),
             node_backend: None,
             chat_path: None,
@@ -0,0 +1,88 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! Debounce for resizing the embedded Neovim UI.
+//!
+//! Dragging the terminal edge produces a burst of resize events.  The ratatui
+//! layout follows every one of them immediately, but Neovim only receives the
+//! last size, once the burst has been quiet for the debounce window (see
+//! [`NvimResize::deadline`]).  Forwarding each intermediate size makes Neovim
+//! redraw grids that are already stale and can leave the grid corrupted.
+
+use std::time::{Duration, Instant};
+
+/// Quiet period after the last terminal resize before Neovim is resized.
+pub(crate) const NVIM_RESIZE_DEBOUNCE: Duration = Duration::from_millis(75);
+
+#[derive(Debug)]
+pub(crate) struct NvimResize {
+    debounce: Duration,
+    /// Latest requested `(width, height)` and when it was requested.
+    pending: Option<((u16, u16), Instant)>,
+}
+
+impl NvimResize {
+    pub(crate) fn new(debounce: Duration) -> Self {
+        Self {
+            debounce,
+            pending: None,
+        }
+    }
+
+    /// The chat pane became `width`×`height` at `now`.  Replaces any size
+    /// still waiting and restarts the window.
+    pub(crate) fn request(&mut self, width: u16, height: u16, now: Instant) {
+        self.pending = Some(((width, height), now));
+    }
+
+    /// When the pending size should be sent, or `None` if nothing is pending.
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        self.pending.map(|(_, at)| at + self.debounce)
+    }
+
+    /// The size to send to Neovim when it is due at `now`; clears it.
+    pub(crate) fn take_due(&mut self, now: Instant) -> Option<(u16, u16)> {
+        if self.deadline().is_some_and(|d| now >= d) {
+            return self.pending.take().map(|(size, _)| size);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn burst_of_sizes_sends_only_the_last_once() {
+        let t0 = Instant::now();
+        let ms = Duration::from_millis;
+        let mut resize = NvimResize::new(ms(75));
+
+        let mut sent = Vec::new();
+        for (i, at) in [0, 16, 33, 50, 66, 83].into_iter().enumerate() {
+            let now = t0 + ms(at);
+            resize.request(80 + i as u16, 24 + i as u16, now);
+            sent.extend(resize.take_due(now));
+        }
+        assert!(sent.is_empty(), "nothing is sent while the drag continues");
+        assert_eq!(resize.deadline(), Some(t0 + ms(83 + 75)));
+        assert_eq!(resize.take_due(t0 + ms(157)), None);
+
+        sent.extend(resize.take_due(t0 + ms(158)));
+        sent.extend(resize.take_due(t0 + ms(400)));
+        assert_eq!(sent, vec![(85, 29)]);
+        assert_eq!(resize.deadline(), None);
+    }
+
+    #[test]
+    fn separate_resizes_are_each_sent() {
+        let t0 = Instant::now();
+        let ms = Duration::from_millis;
+        let mut resize = NvimResize::new(ms(75));
+        resize.request(100, 30, t0);
+        assert_eq!(resize.take_due(t0 + ms(100)), Some((100, 30)));
+        resize.request(120, 40, t0 + ms(1000));
+        assert_eq!(resize.take_due(t0 + ms(1075)), Some((120, 40)));
+    }
+}
@@ -5,6 +5,7 @@
 
 use std::sync::Arc;
 
+use super::nvim_resize::{NvimResize, NVIM_RESIZE_DEBOUNCE};
 use crate::nvim::NvimBridge;
 
 /// State for the optional embedded Neovim process.
@@ -20,6 +21,8 @@ pub(crate) struct NvimState {
     pub quit_notify: Option<Arc<tokio::sync::Notify>>,
     /// True when Neovim is disabled (`--no-nvim`).
     pub disabled: bool,
+    /// Chat pane size waiting to be sent to Neovim.
+    pub resize: NvimResize,
 }
 
 impl NvimState {
@@ -30,6 +33,7 @@ impl NvimState {
             submit_notify: None,
             quit_notify: None,
             disabled,
+            resize: NvimResize::new(NVIM_RESIZE_DEBOUNCE),
         }
     }
 }
@@ -14,6 +14,8 @@
 //!  2. `self.layout.resize_drag` and `self.prefs.*` — border-drag state machine
 //!     that spans multiple events and cannot be expressed as a single `Action`.
 //!  3. `self.ui.pending_nav` — transient key-prefix flag.
+//!  4. The input and edit buffers in vi mode — normal-mode commands are
+//!     edits of their own, not `Action`s (`handle_vi_key`).
 //!
 //! Everything else goes through `mouse_to_action()` → `dispatch()`.
 
@@ -24,10 +26,14 @@ use crate::{
     app::hit_test::{hit_test, HitArea},
     app::input_state::{is_image_path, InputAttachment},
     app::layout_cache::ResizeDrag,
+    app::ui_state::Toast,
     app::{App, FocusPane},
     input::{is_reserved_key, to_nvim_notation},
     keys::{map_key, Action},
     layout::AppLayout,
+    markdown::StyledLines,
+    pager::PagerAction,
+    vi::ViOutcome,
 };
 
 use super::dispatch::prev_char_boundary;
@@ -123,20 +129,37 @@ impl App {
                     }
                 }
 
+                if in_input && !in_search && !self.ui.pending_nav && self.handle_vi_key(&k).await {
+                    return false;
+                }
+
                 let in_edit_mode =
                     self.edit.message_index.is_some() || self.edit.queue_index.is_some();
                 let in_chat_list = self.ui.focus == FocusPane::ChatList;
                 let in_chat_pane = self.ui.focus == FocusPane::Chat;
-                if let Some(action) = map_key(
-                    k,
-                    in_search,
-                    in_input,
-                    self.ui.pending_nav,
-                    in_edit_mode,
-                    in_queue,
-                    in_chat_list,
-                    in_chat_pane,
-                ) {
+                let custom = if (in_input || in_chat_pane)
+                    && !in_search
+                    && !in_edit_mode
+                    && !self.ui.pending_nav
+                {
+                    self.keymap.lookup(&k, in_input)
+                } else {
+                    None
+                };
+                let action = match custom {
+                    Some(binding) => binding,
+                    None => map_key(
+                        k,
+                        in_search,
+                        in_input,
+                        self.ui.pending_nav,
+                        in_edit_mode,
+                        in_queue,
+                        in_chat_list,
+                        in_chat_pane,
+                    ),
+                };
+                if let Some(action) = action {
                     if action == Action::NavPrefix {
                         self.ui.pending_nav = true;
                         return false;
@@ -290,13 +313,13 @@ impl App {
                 self.layout.queue_pane = layout.queue_pane;
                 self.layout.chat_list_pane = layout.chat_list_pane;
                 self.layout.peers_pane = layout.peers_pane;
-                if let Some(nvim_bridge) = &self.nvim.bridge {
+                if self.nvim.bridge.is_some() {
+                    // Neovim follows once the drag settles (see `flush_nvim_resize`).
                     let chat_width = layout.chat_pane.width.saturating_sub(2);
                     let chat_height = layout.chat_inner_height();
-                    let mut bridge = nvim_bridge.lock().await;
-                    if let Err(e) = bridge.resize(chat_width, chat_height).await {
-                        tracing::error!("Failed to resize Neovim UI: {}", e);
-                    }
+                    self.nvim
+                        .resize
+                        .request(chat_width, chat_height, std::time::Instant::now());
                 }
                 self.rerender_chat().await;
                 if let Some(pager) = &mut self.ui.pager {
@@ -307,47 +330,16 @@ impl App {
 
             // ── Bracketed paste ───────────────────────────────────────────────
             Event::Paste(text) => {
-                // Normalise line endings first.
-                let normalised: String = text.replace("\r\n", "\n").replace('\r', "\n");
-
-                // ── Per-line path / image detection ───────────────────────────
-                // Only image files (png, jpg, etc.) are attached as context
-                // objects; all other paths (directories, code files, text files)
-                // are inserted inline so the model sees them as plain text and
-                // the user can edit them freely.
-                //
-                // Multi-line pastes are checked line by line.  A line that
-                // resolves as an image becomes an attachment (consumed, not
-                // inserted).  Every other line — including resolved non-image
-                // paths — is inserted into the buffer as-is.
-                let lines: Vec<&str> = normalised.split('\n').collect();
-                let single_line = lines.len() == 1;
-                let mut any_inserted = false;
-                for (idx, line) in lines.iter().enumerate() {
-                    let candidate = line.trim();
-                    if let Some(path_buf) = Self::resolve_paste_path(candidate) {
-                        if is_image_path(&path_buf) {
-                            self.input.attachments.push(InputAttachment::new(path_buf));
-                            // Don't insert image paths into the buffer.
-                            continue;
-                        }
-                        // Non-image path: insert as inline text.
-                    }
-                    // Insert the line text.  Add a newline between lines (but
-                    // not after the final segment of a multi-line paste).
-                    if any_inserted || (!single_line && idx > 0) {
-                        self.input.buffer.insert(self.input.cursor, '\n');
-                        self.input.cursor += 1;
-                    }
-                    for ch in line.chars() {
-                        self.input.buffer.insert(self.input.cursor, ch);
-                        self.input.cursor += ch.len_utf8();
-                    }
-                    any_inserted = true;
-                }
-                if self.should_show_completion() {
-                    self.update_completion_overlay();
-                }
+                self.insert_paste(&text);
+                false
+            }
+
+            Event::FocusGained => {
+                self.ui.terminal_focused = true;
+                false
+            }
+            Event::FocusLost => {
+                self.ui.terminal_focused = false;
                 false
             }
 
@@ -476,6 +468,179 @@ impl App {
         }
     }
 
+    // ── Bracketed paste ───────────────────────────────────────────────────────
+
+    /// Insert a bracketed paste at the input cursor.
+    ///
+    /// The text arrives as one event, so embedded newlines are inserted into
+    /// the buffer rather than acting as Enter; nothing is submitted.  A
+    /// multi-line paste is acknowledged with a "Pasted N lines" toast.
+    pub(crate) fn insert_paste(&mut self, text: &str) {
+        // Normalise line endings first.
+        let normalised: String = text.replace("\r\n", "\n").replace('\r', "\n");
+
+        // ── Per-line path / image detection ───────────────────────────────────
+        // Only image files (png, jpg, etc.) are attached as context
+        // objects; all other paths (directories, code files, text files)
+        // are inserted inline so the model sees them as plain text and
+        // the user can edit them freely.
+        //
+        // Multi-line pastes are checked line by line.  A line that
+        // resolves as an image becomes an attachment (consumed, not
+        // inserted).  Every other line — including resolved non-image
+        // paths — is inserted into the buffer as-is.
+        let lines: Vec<&str> = normalised.split('\n').collect();
+        let single_line = lines.len() == 1;
+        let mut any_inserted = false;
+        let mut inserted_lines = 0usize;
+        for (idx, line) in lines.iter().enumerate() {
+            let candidate = line.trim();
+            if let Some(path_buf) = Self::resolve_paste_path(candidate) {
+                if is_image_path(&path_buf) {
+                    self.attach_image_file(path_buf);
+                    // Don't insert image paths into the buffer.
+                    continue;
+                }
+                // Non-image path: insert as inline text.
+            }
+            // Insert the line text.  Add a newline between lines (but
+            // not after the final segment of a multi-line paste).
+            if any_inserted || (!single_line && idx > 0) {
+                self.input.buffer.insert(self.input.cursor, '\n');
+                self.input.cursor += 1;
+            }
+            self.input.buffer.insert_str(self.input.cursor, line);
+            self.input.cursor += line.len();
+            any_inserted = true;
+            inserted_lines += 1;
+        }
+        if inserted_lines > 1 {
+            self.ui
+                .push_toast(Toast::info(format!("Pasted {inserted_lines} lines")));
+        }
+        if self.should_show_completion() {
+            self.update_completion_overlay();
+        }
+    }
+
+    // ── Image attachments ─────────────────────────────────────────────────────
+
+    /// Why images cannot be sent to the model right now, if they cannot.
+    fn image_input_unavailable(&self) -> Option<String> {
+        let model = &self.session.model_cfg;
+        if self.is_node_proxy {
+            Some("Images cannot be sent in node-proxy mode".into())
+        } else if !sven_model::catalog::supports_images(&model.provider, &model.name) {
+            Some(format!(
+                "{}/{} does not accept images",
+                model.provider, model.name
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Ctrl+v: attach the image on the system clipboard.
+    pub(crate) fn paste_clipboard_image(&mut self) {
+        match crate::clipboard::read_image() {
+            Some(bytes) => {
+                self.attach_image_bytes(&bytes);
+            }
+            None => self.ui.push_toast(Toast::info("No image on the clipboard")),
+        }
+    }
+
+    /// Encode clipboard image `bytes` and attach them to the message being
+    /// composed, shown as `clipboard-N.png` above the input.  Refused with a
+    /// toast when the model cannot take images or the bytes are no image.
+    pub(crate) fn attach_image_bytes(&mut self, bytes: &[u8]) -> bool {
+        if let Some(reason) = self.image_input_unavailable() {
+            self.ui.push_toast(Toast::warning(reason));
+            return false;
+        }
+        match sven_image::load_image_bytes(bytes) {
+            Ok(image) => {
+                let n = self
+                    .input
+                    .attachments
+                    .iter()
+                    .filter(|a| a.from_clipboard)
+                    .count()
+                    + 1;
+                self.input
+                    .attachments
+                    .push(InputAttachment::pasted(n, image));
+                true
+            }
+            Err(e) => {
+                self.ui
+                    .push_toast(Toast::error(format!("Clipboard image: {e}")));
+                false
+            }
+        }
+    }
+
+    /// Attach a pasted or dropped image file.  Its content goes to the model
+    /// when the model takes images; otherwise only the path is mentioned.
+    fn attach_image_file(&mut self, path: std::path::PathBuf) {
+        let image = match self.image_input_unavailable() {
+            Some(_) => None,
+            None => sven_image::load_image(&path)
+                .map_err(|e| tracing::debug!("not sending image content: {e}"))
+                .ok(),
+        };
+        let attachment = match image {
+            Some(image) => InputAttachment::with_image(path, image),
+            None => InputAttachment::new(path),
+        };
+        self.input.attachments.push(attachment);
+    }
+
+    /// Attach the existing image files named in `text` when the model takes
+    /// images.  Each path is replaced by an `[image: name]` reference; the
+    /// result is the rewritten text and the data URLs of the images.  Other
+    /// paths, and all paths for text-only models, are left alone.
+    pub(crate) fn attach_inline_image_paths(&self, text: &str) -> (String, Vec<String>) {
+        if self.image_input_unavailable().is_some() {
+            return (text.to_string(), Vec::new());
+        }
+        let mut out = String::with_capacity(text.len());
+        let mut images = Vec::new();
+        let mut rest = text;
+        while let Some(start) = rest.find(|c: char| !c.is_whitespace()) {
+            out.push_str(&rest[..start]);
+            let token_len = rest[start..]
+                .find(char::is_whitespace)
+                .unwrap_or(rest.len() - start);
+            let token = &rest[start..start + token_len];
+            rest = &rest[start + token_len..];
+
+            // Sentence punctuation around the path is kept outside the reference.
+            let core = token.trim_start_matches('(');
+            let core = core.trim_end_matches([',', '.', ';', ':', ')', '!', '?']);
+            let lead = &token[..token.len() - token.trim_start_matches('(').len()];
+            let trail = &token[lead.len() + core.len()..];
+            let image = Self::resolve_paste_path(core)
+                .filter(|p| p.is_file())
+                .filter(|p| {
+                    p.extension()
+                        .and_then(|e| e.to_str())
+                        .is_some_and(sven_image::is_image_extension)
+                })
+                .and_then(|p| Some((sven_image::load_image(&p).ok()?, p)));
+            match image {
+                Some((image, path)) => {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    out.push_str(&format!("{lead}[image: {name}]{trail}"));
+                    images.push(image.into_data_url());
+                }
+                None => out.push_str(token),
+            }
+        }
+        out.push_str(rest);
+        (out, images)
+    }
+
     // ── Paste path resolution ─────────────────────────────────────────────────
 
     /// Try to resolve a paste candidate to an existing filesystem path.
@@ -490,7 +655,7 @@ impl App {
     ///
     /// Returns `Some(PathBuf)` only when the resolved path actually **exists**
     /// on the filesystem.
-    fn resolve_paste_path(candidate: &str) -> Option<std::path::PathBuf> {
+    pub(crate) fn resolve_paste_path(candidate: &str) -> Option<std::path::PathBuf> {
         // Strip file:// URI prefix.
         let s = if let Some(rest) = candidate.strip_prefix("file://") {
             rest
@@ -680,6 +845,7 @@ impl App {
                                     self.chat.focused_segment = saved;
                                 }
                             }
+                            ConfirmedAction::ClearChat => self.clear_chat().await,
                             ConfirmedAction::DeleteChat(id) => {
                                 if id == self.sessions.active_id {
                                     let other = self
@@ -713,7 +879,6 @@ impl App {
 
     pub(crate) async fn handle_pager_key(&mut self, k: crossterm::event::KeyEvent) -> bool {
         use crate::keys::map_search_key;
-        use crate::pager::PagerAction;
 
         if self.ui.search.active {
             if let Some(action) = map_search_key(k) {
@@ -732,10 +897,8 @@ impl App {
                 self.ui.pager = None;
             }
             PagerAction::OpenSearch | PagerAction::OpenSearchBackward => {
-                self.ui.search.query.clear();
-                self.ui.search.current = 0;
-                self.ui.search.update_matches(&self.chat.lines);
-                self.ui.search.active = true;
+                let offset = pager.scroll_offset;
+                self.ui.search.begin(offset);
             }
             PagerAction::SearchNext => {
                 if !self.ui.search.matches.is_empty() {
@@ -763,6 +926,15 @@ impl App {
                     }
                 }
             }
+            action @ (PagerAction::ToggleSearchCase | PagerAction::ToggleSearchWholeWord) => {
+                let lines = pager.cloned_lines();
+                self.toggle_search_mode(&action, &lines);
+                if let (Some(line), Some(pager)) =
+                    (self.ui.search.current_line(), &mut self.ui.pager)
+                {
+                    pager.scroll_to_line(line);
+                }
+            }
             PagerAction::Handled => {}
         }
         false
@@ -774,7 +946,6 @@ impl App {
     /// scopes matches to the inspector's own lines.
     pub(crate) async fn handle_inspector_key(&mut self, k: crossterm::event::KeyEvent) -> bool {
         use crate::keys::map_search_key;
-        use crate::pager::PagerAction;
 
         if self.ui.search.active {
             if let Some(action) = map_search_key(k) {
@@ -794,33 +965,12 @@ impl App {
                 self.ui.search.active = false;
             }
             PagerAction::OpenSearch => {
-                self.ui.search.query.clear();
-                self.ui.search.current = 0;
-                let lines = inspector.pager.cloned_lines();
-                self.ui.search.update_matches(&lines);
-                // Jump to first match immediately.
-                if let Some(line) = self.ui.search.current_line() {
-                    if let Some(insp) = &mut self.ui.inspector {
-                        insp.pager.scroll_to_line(line);
-                    }
-                }
-                self.ui.search.active = true;
+                let offset = inspector.pager.scroll_offset;
+                self.ui.search.begin(offset);
             }
             PagerAction::OpenSearchBackward => {
-                self.ui.search.query.clear();
-                self.ui.search.current = 0;
-                let lines = inspector.pager.cloned_lines();
-                self.ui.search.update_matches(&lines);
-                // Start from the last match for backward search.
-                if !self.ui.search.matches.is_empty() {
-                    self.ui.search.current = self.ui.search.matches.len() - 1;
-                    if let Some(line) = self.ui.search.current_line() {
-                        if let Some(insp) = &mut self.ui.inspector {
-                            insp.pager.scroll_to_line(line);
-                        }
-                    }
-                }
-                self.ui.search.active = true;
+                let offset = inspector.pager.scroll_offset;
+                self.ui.search.begin_backward(offset);
             }
             PagerAction::SearchNext => {
                 if !self.ui.search.matches.is_empty() {
@@ -848,8 +998,236 @@ impl App {
                     }
                 }
             }
+            action @ (PagerAction::ToggleSearchCase | PagerAction::ToggleSearchWholeWord) => {
+                let lines = inspector.pager.cloned_lines();
+                self.toggle_search_mode(&action, &lines);
+                if let (Some(line), Some(inspector)) =
+                    (self.ui.search.current_line(), &mut self.ui.inspector)
+                {
+                    inspector.pager.scroll_to_line(line);
+                }
+            }
             PagerAction::Handled => {}
         }
         false
     }
+
+    /// Apply a search-mode toggle from a pager, recomputing the matches
+    /// against the pager's `lines`, and announce the new modes.
+    fn toggle_search_mode(&mut self, action: &PagerAction, lines: &StyledLines) {
+        if matches!(action, PagerAction::ToggleSearchCase) {
+            self.ui.search.toggle_case_sensitive(lines);
+        } else {
+            self.ui.search.toggle_whole_word(lines);
+        }
+        let label = self.ui.search.mode_label();
+        self.ui.push_toast(Toast::info(format!("Search: {label}")));
+    }
+
+    /// Offer `k` to vi mode, applying it to the edit buffer while a message
+    /// is being edited and to the input buffer otherwise.  Returns `true`
+    /// when vi mode consumed the key.
+    async fn handle_vi_key(&mut self, k: &crossterm::event::KeyEvent) -> bool {
+        let Some(vi) = &mut self.vi else {
+            return false;
+        };
+        let editing = self.edit.active();
+        vi.select_buffer(editing);
+        let (buf, cursor) = if editing {
+            (&mut self.edit.buffer, &mut self.edit.cursor)
+        } else {
+            (&mut self.input.buffer, &mut self.input.cursor)
+        };
+        let before = buf.clone();
+        if vi.handle_key(k, buf, cursor) == ViOutcome::Passthrough {
+            return false;
+        }
+        let changed = *buf != before;
+        if !editing {
+            self.ui.completion = None;
+        } else if changed && self.edit.message_index.is_some() {
+            self.update_editing_segment_live();
+            self.rerender_chat().await;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn multi_line_paste_is_inserted_without_submitting() {
+        let (mut app, mut rx) = App::for_testing();
+        app.inject_input("see: ");
+        let pasted = "error[E0308]: mismatched types\r\n  --> src/main.rs:4:5\n\n    x + 1";
+        app.handle_term_event(Event::Paste(pasted.into())).await;
+
+        assert_eq!(
+            app.input.buffer,
+            "see: error[E0308]: mismatched types\n  --> src/main.rs:4:5\n\n    x + 1"
+        );
+        assert_eq!(app.input.cursor, app.input.buffer.len());
+        assert!(rx.try_recv().is_err(), "paste must not submit");
+        assert!(!app.is_agent_busy());
+        assert!(app.ui.toasts.iter().any(|t| t.message == "Pasted 4 lines"));
+    }
+
+    #[tokio::test]
+    async fn search_is_incremental_and_esc_restores_the_scroll_position() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+        use ratatui::text::Line;
+
+        let (mut app, _rx) = App::for_testing();
+        app.chat.lines = (0..40)
+            .map(|i| match i {
+                12 => Line::from("fn parse_args()"),
+                30 => Line::from("fn parse_config()"),
+                _ => Line::from(format!("line {i}")),
+            })
+            .collect();
+        app.chat.scroll_offset = 20;
+
+        app.dispatch_action(Action::SearchOpen).await;
+        let mut counts = Vec::new();
+        for c in "parse_c".chars() {
+            app.dispatch_action(Action::SearchInput(c)).await;
+            counts.push(app.ui.search.matches.len());
+        }
+        assert_eq!(counts, vec![2, 2, 2, 2, 2, 2, 1]);
+        assert_eq!(app.chat.scroll_offset, 30);
+
+        app.dispatch_action(Action::SearchBackspace).await;
+        assert_eq!(app.ui.search.matches, vec![12, 30]);
+
+        let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+        app.handle_term_event(Event::Key(esc)).await;
+        assert!(!app.ui.search.active);
+        assert_eq!(app.chat.scroll_offset, 20);
+    }
+
+    /// A 1x1 PNG.
+    const PNG: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNkYPhfDwAChwGA60e6kgAAAABJRU5ErkJggg==";
+
+    fn png_bytes() -> Vec<u8> {
+        use base64::Engine as _;
+        base64::engine::general_purpose::STANDARD
+            .decode(PNG)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn clipboard_image_is_attached_and_sent_as_content() {
+        use crate::agent::AgentRequest;
+        use crate::chat::segment::ChatSegment;
+        use sven_model::MessageContent;
+
+        let (mut app, mut rx) = App::for_testing();
+        app.session.model_cfg.provider = "openai".into();
+        app.session.model_cfg.name = "gpt-4o".into();
+
+        assert!(app.attach_image_bytes(&png_bytes()));
+        let att = &app.input.attachments[0];
+        assert_eq!(att.display_name(), "clipboard-1.png");
+        assert_eq!(att.image.as_ref().unwrap().mime_type, "image/png");
+
+        app.inject_input("what is this?");
+        app.dispatch_action(Action::Submit).await;
+
+        let Ok(AgentRequest::Resubmit {
+            new_user_content,
+            images,
+            ..
+        }) = rx.try_recv()
+        else {
+            panic!("expected a resubmit");
+        };
+        assert_eq!(new_user_content, "what is this?");
+        assert_eq!(images.len(), 1);
+        assert!(images[0].starts_with("data:image/png;base64,"));
+        assert!(app.input.attachments.is_empty());
+
+        let Some(ChatSegment::Message(m)) = app.chat.segments.last() else {
+            panic!("expected the user message");
+        };
+        assert!(matches!(&m.content, MessageContent::ContentParts(p) if p.len() == 2));
+        let shown: String = app
+            .chat
+            .lines
+            .iter()
+            .flat_map(|l| l.spans.iter().map(|s| s.content.to_string()))
+            .collect();
+        assert!(shown.contains("[image 1]"), "{shown}");
+    }
+
+    #[tokio::test]
+    async fn clipboard_image_needs_a_vision_model() {
+        let (mut app, _rx) = App::for_testing();
+        app.session.model_cfg.provider = "openai".into();
+        app.session.model_cfg.name = "no-such-model".into();
+        assert!(!app.attach_image_bytes(&png_bytes()));
+        assert!(app.input.attachments.is_empty());
+        assert!(app
+            .ui
+            .toasts
+            .iter()
+            .any(|t| t.message.contains("does not accept images")));
+    }
+
+    #[tokio::test]
+    async fn image_paths_in_the_message_are_attached() {
+        use crate::agent::AgentRequest;
+
+        let dir = tempfile::tempdir().unwrap();
+        let png = dir.path().join("shot.png");
+        std::fs::write(&png, png_bytes()).unwrap();
+        let src = dir.path().join("main.rs");
+        std::fs::write(&src, "fn main() {}").unwrap();
+        let text = format!("compare ({}) with {}, please", png.display(), src.display());
+
+        let (mut app, mut rx) = App::for_testing();
+        app.session.model_cfg.provider = "openai".into();
+        app.session.model_cfg.name = "gpt-4o".into();
+        let (rewritten, images) = app.attach_inline_image_paths(&text);
+        assert_eq!(
+            rewritten,
+            format!("compare ([image: shot.png]) with {}, please", src.display())
+        );
+        assert_eq!(images.len(), 1);
+        assert!(images[0].starts_with("data:image/png;base64,"));
+
+        app.inject_input(&text);
+        app.dispatch_action(Action::Submit).await;
+        let Ok(AgentRequest::Resubmit { images, .. }) = rx.try_recv() else {
+            panic!("expected a resubmit");
+        };
+        assert_eq!(images.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn image_paths_stay_text_for_text_only_models() {
+        let dir = tempfile::tempdir().unwrap();
+        let png = dir.path().join("shot.png");
+        std::fs::write(&png, png_bytes()).unwrap();
+        let text = format!("look at {}", png.display());
+
+        let (mut app, _rx) = App::for_testing();
+        app.session.model_cfg.provider = "openai".into();
+        app.session.model_cfg.name = "no-such-model".into();
+        let (rewritten, images) = app.attach_inline_image_paths(&text);
+        assert_eq!(rewritten, text);
+        assert!(images.is_empty());
+    }
+
+    #[tokio::test]
+    async fn single_line_paste_inserts_at_cursor_without_hint() {
+        let (mut app, _rx) = App::for_testing();
+        app.inject_input("ab");
+        app.input.cursor = 1;
+        app.handle_term_event(Event::Paste("XY".into())).await;
+        assert_eq!(app.input.buffer, "aXYb");
+        assert_eq!(app.input.cursor, 3);
+        assert!(app.ui.toasts.is_empty());
+    }
 }
@@ -109,6 +109,9 @@ pub(crate) struct UiState {
     pub peers: Vec<PeerInfo>,
     /// Selected index in the peers list.
     pub peers_selected: usize,
+    /// Whether the terminal window has focus, as last reported by the
+    /// terminal (assumed true until it reports otherwise).
+    pub terminal_focused: bool,
 }
 
 #[allow(dead_code)]
@@ -132,6 +135,7 @@ impl UiState {
             active_session_peer: None,
             peers: Vec::new(),
             peers_selected: 0,
+            terminal_focused: true,
         }
     }
 
@@ -10,12 +10,12 @@ use std::collections::HashMap;
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use std::sync::{Arc, RwLock};
-use sven_model::{FunctionCall, Message, MessageContent, Role};
+use sven_model::{ContentPart, FunctionCall, Message, MessageContent, Role};
 use sven_tools::{TodoItem, ToolDisplayRegistry};
 
 use crate::chat::segment::ChatSegment;
 use crate::markdown::StyledLines;
-use crate::ui::theme::{BAR_AGENT, BAR_COMPACT, BAR_ERROR, BAR_THINKING, BAR_TOOL, BAR_USER};
+use crate::ui::theme::{RolePalette, BAR_COMPACT, BAR_ERROR};
 use crate::ui::width_utils::truncate_to_width;
 
 // ── Symbols ────────────────────────────────────────────────────────────────────
@@ -146,7 +146,7 @@ pub type ToolDisplayRegistryRef = Option<Arc<RwLock<ToolDisplayRegistry>>>;
 /// The preview is a compact, information-dense one-liner:
 /// - Tool call:   `⚙  tool_name  smart_description  duration  ▶`
 /// - Tool result: `✓/✗  tool_name  duration  ▶`
-/// - Thinking:    `◆  Reasoning  ~N words  ▶`
+/// - Thinking:    `◆ reasoning, N lines  ▶`
 /// - User:        `You  first_line…  ▶`
 /// - Agent:       `first_line…  ▶`
 pub fn collapsed_preview(
@@ -233,14 +233,58 @@ pub fn collapsed_preview(
             _ => segment_to_markdown(seg, tool_args_cache),
         },
         ChatSegment::Thinking { content } => {
-            let normalized: String = content.split_whitespace().collect::<Vec<_>>().join(" ");
-            let preview = truncate_to_width(&normalized, 80);
-            format!("\n{SYM_THINK} **Thought**  {preview}  {SYM_EXPAND}")
+            format!("\n{}  {SYM_EXPAND}", thinking_summary(content))
         }
         _ => segment_to_markdown(seg, tool_args_cache),
     }
 }
 
+// ── Neovim fold structure ─────────────────────────────────────────────────────
+
+/// A line that opens a fold in the Neovim conversation buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoldHeader {
+    /// Level of the fold the line opens.
+    pub level: u8,
+    /// Text the line starts with.
+    pub prefix: String,
+    /// The line must be exactly `prefix` rather than merely start with it.
+    pub whole_line: bool,
+}
+
+/// Fold headers for the markdown written by [`segment_to_markdown`]: level 1
+/// at every role anchor, level 2 at the visible heading of a tool call, tool
+/// result or thought.  Built from the renderer's own symbols so the folds
+/// follow changes to the format.
+pub fn fold_headers() -> Vec<FoldHeader> {
+    let header = |level, prefix: &str, whole_line| FoldHeader {
+        level,
+        prefix: prefix.to_string(),
+        whole_line,
+    };
+    vec![
+        header(1, "---", true),
+        header(1, "**You:**", false),
+        header(1, "**Agent:**", false),
+        header(1, "**Agent:tool_call:", false),
+        header(1, "**Agent:thinking**", true),
+        header(1, "**Tool:", false),
+        header(1, "**System:**", false),
+        header(1, "## ", false),
+        header(2, &format!("{SYM_TOOL} **"), false),
+        header(2, &format!("{SYM_OK} **"), false),
+        header(2, &format!("{SYM_ERR} **"), false),
+        header(2, &format!("{SYM_THINK} **Thought**"), false),
+    ]
+}
+
+/// One-line stand-in for a collapsed thinking block: `◆ reasoning, N lines`.
+pub fn thinking_summary(content: &str) -> String {
+    let lines = content.trim().lines().count();
+    let unit = if lines == 1 { "line" } else { "lines" };
+    format!("{SYM_THINK} *reasoning, {lines} {unit}*")
+}
+
 /// Format the full conversation as a single markdown string (used for the
 /// Neovim buffer content).
 pub fn format_conversation(
@@ -265,28 +309,25 @@ pub fn format_conversation(
 }
 
 /// Return `(bar_style, dim)` for a segment used to draw the per-segment colour
-/// bar in the ratatui-only chat pane.
-pub fn segment_bar_style(seg: &ChatSegment) -> (Option<Style>, bool) {
+/// bar in the ratatui-only chat pane.  Each role has its own color from the
+/// theme's `palette`; thinking is also dimmed so it reads as an aside.
+pub fn segment_bar_style(seg: &ChatSegment, palette: RolePalette) -> (Option<Style>, bool) {
+    let bar = |color| Some(Style::default().fg(color));
     match seg {
         ChatSegment::Message(m) => match (&m.role, &m.content) {
-            (Role::User, MessageContent::Text(_)) => (Some(Style::default().fg(BAR_USER)), false),
-            (Role::Assistant, MessageContent::Text(_)) => {
-                (Some(Style::default().fg(BAR_AGENT)), false)
-            }
-            (Role::Assistant, MessageContent::ToolCall { .. }) => {
-                (Some(Style::default().fg(BAR_TOOL)), false)
-            }
-            (Role::Tool, MessageContent::ToolResult { .. }) => {
-                (Some(Style::default().fg(BAR_TOOL)), false)
+            (Role::User, _) => (bar(palette.user), false),
+            (Role::Assistant, MessageContent::ToolCall { .. }) | (Role::Tool, _) => {
+                (bar(palette.tool), false)
             }
-            _ => (None, false),
+            (Role::Assistant, _) => (bar(palette.agent), false),
+            (Role::System, _) => (None, false),
         },
-        ChatSegment::Thinking { .. } => (Some(Style::default().fg(BAR_THINKING)), false),
+        ChatSegment::Thinking { .. } => (bar(palette.thinking), true),
         ChatSegment::Error(_) => (Some(Style::default().fg(BAR_ERROR)), false),
         ChatSegment::ContextCompacted { .. } => (Some(Style::default().fg(BAR_COMPACT)), false),
         ChatSegment::TodoUpdate(_) => (Some(Style::default().fg(BAR_COMPACT)), true),
         ChatSegment::CollabEvent(_) => (Some(Style::default().fg(BAR_COMPACT)), true),
-        ChatSegment::DelegateSummary { .. } => (Some(Style::default().fg(BAR_TOOL)), false),
+        ChatSegment::DelegateSummary { .. } => (bar(palette.tool), false),
     }
 }
 
@@ -364,17 +405,21 @@ pub(crate) fn message_to_markdown(
     tool_args_cache: &HashMap<String, String>,
 ) -> String {
     match (&m.role, &m.content) {
-        (Role::User, MessageContent::Text(t)) => {
-            // Convert single newlines to Markdown hard line breaks (`  \n`) so
-            // they render as visual line breaks in the TUI chat pane.  Double
-            // newlines (paragraph separators) are kept intact: split on `\n\n`,
-            // apply hard-break conversion within each paragraph, then rejoin.
-            let display = t
-                .split("\n\n")
-                .map(|para| para.replace('\n', "  \n"))
+        (Role::User, MessageContent::Text(t)) => user_markdown(t, 0),
+        (Role::User, MessageContent::ContentParts(parts)) => {
+            let text = parts
+                .iter()
+                .filter_map(|p| match p {
+                    ContentPart::Text { text } => Some(text.as_str()),
+                    ContentPart::Image { .. } => None,
+                })
                 .collect::<Vec<_>>()
-                .join("\n\n");
-            format!("---\n\n**You:** {display}\n")
+                .join("\n");
+            let images = parts
+                .iter()
+                .filter(|p| matches!(p, ContentPart::Image { .. }))
+                .count();
+            user_markdown(&text, images)
         }
         (Role::Assistant, MessageContent::Text(t)) => format!("\n**Agent:** {}\n", t),
         (
@@ -412,6 +457,30 @@ pub(crate) fn message_to_markdown(
     }
 }
 
+/// A user message with an `*[image N]*` placeholder line per attached image.
+fn user_markdown(text: &str, images: usize) -> String {
+    // Convert single newlines to Markdown hard line breaks (`  \n`) so
+    // they render as visual line breaks in the TUI chat pane.  Double
+    // newlines (paragraph separators) are kept intact: split on `\n\n`,
+    // apply hard-break conversion within each paragraph, then rejoin.
+    let display = text
+        .split("\n\n")
+        .map(|para| para.replace('\n', "  \n"))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let placeholders: String = (1..=images)
+        .map(|n| {
+            let sep = if n == 1 && display.is_empty() {
+                ""
+            } else {
+                "  \n"
+            };
+            format!("{sep}*[image {n}]*")
+        })
+        .collect();
+    format!("---\n\n**You:** {display}{placeholders}\n")
+}
+
 // ── Helpers ───────────────────────────────────────────────────────────────────
 
 /// Return the last `n` non-empty path components joined by `/`.
@@ -1182,4 +1251,55 @@ mod tests {
         assert_eq!(parsed[0].as_text(), Some("keep this"));
         assert_eq!(parsed[1].as_text(), Some("keep this too"));
     }
+
+    // ── Collapsed thinking ────────────────────────────────────────────────────
+
+    #[test]
+    fn thinking_summary_counts_reasoning_lines() {
+        assert_eq!(
+            thinking_summary("\nfirst check the lock\nthen the queue\n\nanswer\n"),
+            "◆ *reasoning, 4 lines*"
+        );
+        assert_eq!(thinking_summary("just one"), "◆ *reasoning, 1 line*");
+    }
+
+    #[test]
+    fn collapsed_thinking_is_a_single_summary_line() {
+        let seg = ChatSegment::Thinking {
+            content: "a\nb\nc".into(),
+        };
+        let preview = collapsed_preview(&seg, &HashMap::new(), &HashMap::new(), None);
+        assert_eq!(preview, "\n◆ *reasoning, 3 lines*  ▶");
+        let rendered = crate::markdown::render_markdown(&preview, 80, Default::default());
+        let text: Vec<String> = rendered
+            .iter()
+            .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+            .filter(|l: &String| !l.trim().is_empty())
+            .collect();
+        assert_eq!(text, vec!["◆ reasoning, 3 lines  ▶"]);
+    }
+
+    #[test]
+    fn roles_have_distinct_bar_colors() {
+        let palette = RolePalette::for_theme("dark");
+        let color = |seg: &ChatSegment| segment_bar_style(seg, palette).0.and_then(|s| s.fg);
+        let thinking = ChatSegment::Thinking {
+            content: "hm".into(),
+        };
+        let colors = [
+            color(&user_seg("hi")),
+            color(&agent_seg("hello")),
+            color(&tool_call_seg("c1", "read_file")),
+            color(&thinking),
+        ];
+        for (i, a) in colors.iter().enumerate() {
+            assert!(a.is_some());
+            assert!(colors[i + 1..].iter().all(|b| b != a), "{colors:?}");
+        }
+        assert_eq!(color(&tool_result_seg("c1", "ok")), colors[2]);
+        assert!(
+            segment_bar_style(&thinking, palette).1,
+            "thinking is dimmed"
+        );
+    }
 }
@@ -3,6 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0
 //! In-pane search state: query, regex, match list and current-match tracking.
 
+use regex::{Regex, RegexBuilder};
+
 use crate::markdown::StyledLines;
 
 /// All state needed to track an active text search across rendered chat lines.
@@ -12,13 +14,23 @@ pub struct SearchState {
     pub query: String,
     pub matches: Vec<usize>,
     pub current: usize,
-    /// Compiled regex (when the query is valid regex syntax).
+    /// Compiled search for `query` with the mode flags applied.  A query
+    /// that is not valid regex syntax is searched for literally.
     pub regex: Option<regex::Regex>,
+    /// Match letter case exactly instead of ignoring it.
+    pub case_sensitive: bool,
+    /// Only match whole words.
+    pub whole_word: bool,
+    /// Scroll offset of the searched view when the search bar opened;
+    /// cancelling the search returns there.
+    pub saved_offset: Option<usize>,
+    /// Select the nearest match above the starting line instead of below.
+    pub backward: bool,
 }
 
 impl SearchState {
     /// Recompute the list of matching line indices against the given rendered
-    /// lines.  The regex is rebuilt whenever `query` changes.
+    /// lines.  The regex is rebuilt from `query` and the mode flags.
     pub fn update_matches(&mut self, lines: &StyledLines) {
         if self.query.is_empty() {
             self.matches.clear();
@@ -26,22 +38,16 @@ impl SearchState {
             return;
         }
 
-        let re = regex::Regex::new(&format!("(?i){}", &self.query)).ok();
-        self.regex = re.clone();
+        self.regex = self.compile();
+        let Some(re) = &self.regex else {
+            self.matches.clear();
+            return;
+        };
 
         self.matches = lines
             .iter()
             .enumerate()
-            .filter(|(_, l)| {
-                if let Some(re) = &re {
-                    l.spans.iter().any(|s| re.is_match(&s.content))
-                } else {
-                    let q = self.query.to_lowercase();
-                    l.spans
-                        .iter()
-                        .any(|s| s.content.to_lowercase().contains(&q))
-                }
-            })
+            .filter(|(_, l)| l.spans.iter().any(|s| re.is_match(&s.content)))
             .map(|(i, _)| i)
             .collect();
 
@@ -54,4 +60,245 @@ impl SearchState {
     pub fn current_line(&self) -> Option<usize> {
         self.matches.get(self.current).copied()
     }
+
+    /// Open the search bar over a view scrolled to `offset`, starting with an
+    /// empty query.
+    pub fn begin(&mut self, offset: usize) {
+        self.active = true;
+        self.query.clear();
+        self.matches.clear();
+        self.regex = None;
+        self.current = 0;
+        self.saved_offset = Some(offset);
+        self.backward = false;
+    }
+
+    /// Like [`begin`](Self::begin), but matches are looked for above `offset`.
+    pub fn begin_backward(&mut self, offset: usize) {
+        self.begin(offset);
+        self.backward = true;
+    }
+
+    /// Set the query to `query` as typed so far and select the first match
+    /// at or below the line the search started from (wrapping to the first
+    /// match overall), or for a backward search the last match above it
+    /// (wrapping to the last match overall).  Returns the line to show, if
+    /// anything matches.
+    pub fn set_query(&mut self, query: String, lines: &StyledLines) -> Option<usize> {
+        self.query = query;
+        self.update_matches(lines);
+        let origin = self.saved_offset.unwrap_or(0);
+        self.current = if self.backward {
+            self.matches
+                .iter()
+                .rposition(|&l| l < origin)
+                .unwrap_or(self.matches.len().saturating_sub(1))
+        } else {
+            self.matches.iter().position(|&l| l >= origin).unwrap_or(0)
+        };
+        self.current_line()
+    }
+
+    /// Close the search bar keeping the current match selected for `n`/`N`.
+    pub fn accept(&mut self) {
+        self.active = false;
+        self.saved_offset = None;
+    }
+
+    /// Close the search bar and drop the query.  Returns the offset to
+    /// scroll back to.
+    pub fn cancel(&mut self) -> Option<usize> {
+        self.active = false;
+        self.query.clear();
+        self.matches.clear();
+        self.regex = None;
+        self.current = 0;
+        self.saved_offset.take()
+    }
+
+    /// Flip case sensitivity and recompute the matches against `lines`.
+    pub fn toggle_case_sensitive(&mut self, lines: &StyledLines) {
+        self.case_sensitive = !self.case_sensitive;
+        self.update_matches(lines);
+    }
+
+    /// Flip whole-word matching and recompute the matches against `lines`.
+    pub fn toggle_whole_word(&mut self, lines: &StyledLines) {
+        self.whole_word = !self.whole_word;
+        self.update_matches(lines);
+    }
+
+    /// Short description of the active modes, e.g. `"case-sensitive, whole word"`.
+    pub fn mode_label(&self) -> String {
+        let case = if self.case_sensitive {
+            "case-sensitive"
+        } else {
+            "ignore case"
+        };
+        if self.whole_word {
+            format!("{case}, whole word")
+        } else {
+            case.to_string()
+        }
+    }
+
+    fn compile(&self) -> Option<Regex> {
+        let build = |pattern: &str| {
+            let pattern = if self.whole_word {
+                format!(r"\b(?:{pattern})\b")
+            } else {
+                pattern.to_string()
+            };
+            RegexBuilder::new(&pattern)
+                .case_insensitive(!self.case_sensitive)
+                .build()
+                .ok()
+        };
+        build(&self.query).or_else(|| build(&regex::escape(&self.query)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::text::Line;
+
+    use super::*;
+
+    fn lines() -> StyledLines {
+        [
+            "Error: build failed",
+            "an error occurred",
+            "terrorist detection",
+            "ERRORS: 3",
+            "all good",
+        ]
+        .into_iter()
+        .map(Line::from)
+        .collect()
+    }
+
+    fn matches(query: &str, case_sensitive: bool, whole_word: bool) -> Vec<usize> {
+        let mut search = SearchState {
+            query: query.into(),
+            case_sensitive,
+            whole_word,
+            ..SearchState::default()
+        };
+        search.update_matches(&lines());
+        search.matches
+    }
+
+    #[test]
+    fn ignore_case_substring() {
+        assert_eq!(matches("error", false, false), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn case_sensitive_substring() {
+        assert_eq!(matches("error", true, false), vec![1, 2]);
+        assert_eq!(matches("ERROR", true, false), vec![3]);
+    }
+
+    #[test]
+    fn ignore_case_whole_word() {
+        assert_eq!(matches("error", false, true), vec![0, 1]);
+    }
+
+    #[test]
+    fn case_sensitive_whole_word() {
+        assert_eq!(matches("error", true, true), vec![1]);
+        assert_eq!(matches("Error", true, true), vec![0]);
+    }
+
+    #[test]
+    fn regex_alternation_respects_whole_word() {
+        assert_eq!(matches("good|error", false, true), vec![0, 1, 4]);
+    }
+
+    #[test]
+    fn invalid_regex_is_searched_literally() {
+        let lines: StyledLines = vec![Line::from("call f(x"), Line::from("F(X) twice")];
+        let mut search = SearchState {
+            query: "f(x".into(),
+            ..SearchState::default()
+        };
+        search.update_matches(&lines);
+        assert_eq!(search.matches, vec![0, 1]);
+        search.toggle_case_sensitive(&lines);
+        assert_eq!(search.matches, vec![0]);
+    }
+
+    #[test]
+    fn typing_recomputes_matches_per_keystroke() {
+        let lines = lines();
+        let mut search = SearchState::default();
+        search.begin(0);
+        let mut counts = Vec::new();
+        for c in "errors".chars() {
+            let mut query = search.query.clone();
+            query.push(c);
+            search.set_query(query, &lines);
+            counts.push(search.matches.len());
+        }
+        // Every line but "all good" matches until the final "s".
+        assert_eq!(counts, vec![4, 4, 4, 4, 4, 1]);
+        assert_eq!(search.current_line(), Some(3));
+    }
+
+    #[test]
+    fn first_match_is_taken_from_the_starting_line() {
+        let lines = lines();
+        let mut search = SearchState::default();
+        search.begin(2);
+        assert_eq!(search.set_query("error".into(), &lines), Some(2));
+        search.begin(4);
+        assert_eq!(search.set_query("error".into(), &lines), Some(0));
+    }
+
+    #[test]
+    fn backward_search_takes_the_match_above_the_starting_line() {
+        let lines = lines();
+        let mut search = SearchState::default();
+        search.begin_backward(2);
+        assert_eq!(search.set_query("error".into(), &lines), Some(1));
+        search.begin_backward(0);
+        assert_eq!(search.set_query("error".into(), &lines), Some(3));
+        search.begin(0);
+        assert_eq!(search.set_query("error".into(), &lines), Some(0));
+    }
+
+    #[test]
+    fn cancel_returns_saved_offset_and_clears_query() {
+        let lines = lines();
+        let mut search = SearchState::default();
+        search.begin(17);
+        search.set_query("error".into(), &lines);
+        assert_eq!(search.cancel(), Some(17));
+        assert!(!search.active);
+        assert!(search.query.is_empty() && search.matches.is_empty());
+
+        search.begin(3);
+        search.set_query("good".into(), &lines);
+        search.accept();
+        assert_eq!(search.current_line(), Some(4));
+        assert_eq!(search.saved_offset, None);
+    }
+
+    #[test]
+    fn toggles_recompute_matches() {
+        let lines = lines();
+        let mut search = SearchState {
+            query: "error".into(),
+            ..SearchState::default()
+        };
+        search.update_matches(&lines);
+        search.toggle_whole_word(&lines);
+        assert_eq!(search.matches, vec![0, 1]);
+        assert_eq!(search.mode_label(), "ignore case, whole word");
+        search.toggle_case_sensitive(&lines);
+        assert_eq!(search.matches, vec![1]);
+        search.toggle_whole_word(&lines);
+        assert_eq!(search.matches, vec![1, 2]);
+        assert_eq!(search.mode_label(), "case-sensitive");
+    }
 }
@@ -0,0 +1,199 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! Reading images from and copying text to the system clipboard.
+//!
+//! Terminals only deliver text pastes, so an image is fetched directly with
+//! the platform clipboard tool: `wl-paste` (Wayland), `xclip` (X11) or
+//! `pngpaste` (macOS).
+//!
+//! Text is copied with the OSC 52 escape sequence, which the terminal itself
+//! applies to its clipboard — so it works over SSH.  Locally the platform
+//! tool is used as well, for terminals that ignore OSC 52.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use base64::Engine as _;
+
+/// Commands printing the clipboard image as PNG on stdout, tried in order.
+const IMAGE_COMMANDS: &[&[&str]] = &[
+    &["wl-paste", "--no-newline", "--type", "image/png"],
+    &[
+        "xclip",
+        "-selection",
+        "clipboard",
+        "-target",
+        "image/png",
+        "-out",
+    ],
+    &["pngpaste", "-"],
+];
+
+/// Commands reading the text to copy from stdin, tried in order.
+const COPY_COMMANDS: &[&[&str]] = &[
+    &["wl-copy"],
+    &["xclip", "-selection", "clipboard", "-in"],
+    &["pbcopy"],
+];
+
+/// Longest base64 payload sent in one OSC 52 sequence.  xterm and most
+/// terminals derived from it drop anything longer.
+const OSC52_MAX_ENCODED: usize = 100_000;
+
+/// GNU screen truncates a DCS string after 768 bytes, so the payload is split
+/// into chunks of this size, each in its own DCS.
+const SCREEN_CHUNK: usize = 76;
+
+/// Terminal multiplexer between sven and the terminal, which has to be told
+/// to pass OSC 52 through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Multiplexer {
+    None,
+    Tmux,
+    Screen,
+}
+
+impl Multiplexer {
+    fn detect() -> Self {
+        if std::env::var_os("TMUX").is_some() {
+            Multiplexer::Tmux
+        } else if std::env::var("TERM").is_ok_and(|t| t.starts_with("screen")) {
+            Multiplexer::Screen
+        } else {
+            Multiplexer::None
+        }
+    }
+}
+
+/// Copy `text` to the clipboard.  Returns `false` when nothing could take it:
+/// the text is too long for OSC 52 and no local clipboard tool accepted it.
+pub(crate) fn copy_to_clipboard(text: &str) -> bool {
+    let sent = osc52_sequence(text, Multiplexer::detect()).is_some_and(|seq| {
+        let mut out = std::io::stdout();
+        out.write_all(seq.as_bytes()).is_ok() && out.flush().is_ok()
+    });
+    let over_ssh =
+        std::env::var_os("SSH_CONNECTION").is_some() || std::env::var_os("SSH_TTY").is_some();
+    // Over SSH the local tool would fill the remote host's clipboard.
+    let copied_locally = !over_ssh && write_text(text);
+    sent || copied_locally
+}
+
+/// The OSC 52 sequence setting the clipboard to `text`, wrapped for `mux`.
+/// `None` when the encoded text exceeds [`OSC52_MAX_ENCODED`].
+pub(crate) fn osc52_sequence(text: &str, mux: Multiplexer) -> Option<String> {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    if encoded.len() > OSC52_MAX_ENCODED {
+        return None;
+    }
+    Some(match mux {
+        Multiplexer::None => format!("\x1b]52;c;{encoded}\x07"),
+        Multiplexer::Tmux => format!("\x1bPtmux;\x1b\x1b]52;c;{encoded}\x07\x1b\\"),
+        Multiplexer::Screen => {
+            // base64 is ASCII, so byte chunks are valid strings.
+            let chunks: Vec<&str> = encoded
+                .as_bytes()
+                .chunks(SCREEN_CHUNK)
+                .map(|c| std::str::from_utf8(c).unwrap_or_default())
+                .collect();
+            format!("\x1bP\x1b]52;c;{}\x07\x1b\\", chunks.join("\x1b\\\x1bP"))
+        }
+    })
+}
+
+/// Pipe `text` into the first local clipboard tool that accepts it.
+fn write_text(text: &str) -> bool {
+    COPY_COMMANDS.iter().any(|cmd| {
+        let Ok(mut child) = Command::new(cmd[0])
+            .args(&cmd[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            return false;
+        };
+        let written = child
+            .stdin
+            .take()
+            .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+        child.wait().is_ok_and(|s| s.success()) && written
+    })
+}
+
+/// Bytes of the image on the clipboard, or `None` when the clipboard holds
+/// no image or none of the clipboard tools is installed.
+pub(crate) fn read_image() -> Option<Vec<u8>> {
+    IMAGE_COMMANDS.iter().find_map(|cmd| {
+        let out = Command::new(cmd[0])
+            .args(&cmd[1..])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        (out.status.success() && !out.stdout.is_empty()).then_some(out.stdout)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(payload: &str) -> String {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(payload)
+            .unwrap();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn osc52_encodes_text_as_base64() {
+        assert_eq!(
+            osc52_sequence("hello", Multiplexer::None).unwrap(),
+            "\x1b]52;c;aGVsbG8=\x07"
+        );
+        let seq = osc52_sequence("héllo ✓\nline two", Multiplexer::None).unwrap();
+        let payload = seq
+            .strip_prefix("\x1b]52;c;")
+            .and_then(|s| s.strip_suffix('\x07'))
+            .unwrap();
+        assert_eq!(decode(payload), "héllo ✓\nline two");
+    }
+
+    #[test]
+    fn osc52_is_wrapped_for_tmux_passthrough() {
+        assert_eq!(
+            osc52_sequence("hello", Multiplexer::Tmux).unwrap(),
+            "\x1bPtmux;\x1b\x1b]52;c;aGVsbG8=\x07\x1b\\"
+        );
+    }
+
+    #[test]
+    fn large_payload_is_chunked_for_screen() {
+        let text = "fn main() { println!(\"hi\"); }\n".repeat(40);
+        let seq = osc52_sequence(&text, Multiplexer::Screen).unwrap();
+
+        let body = seq
+            .strip_prefix("\x1bP")
+            .and_then(|s| s.strip_suffix("\x1b\\"))
+            .unwrap();
+        let chunks: Vec<&str> = body.split("\x1b\\\x1bP").collect();
+        assert!(chunks.len() > 10, "{} chunks", chunks.len());
+        assert!(chunks.iter().all(|c| c.len() <= SCREEN_CHUNK + 8));
+
+        let payload: String = chunks.concat();
+        let payload = payload
+            .strip_prefix("\x1b]52;c;")
+            .and_then(|s| s.strip_suffix('\x07'))
+            .unwrap();
+        assert_eq!(decode(payload), text);
+    }
+
+    #[test]
+    fn payload_over_the_terminal_limit_is_refused() {
+        let limit = OSC52_MAX_ENCODED / 4 * 3;
+        assert!(osc52_sequence(&"x".repeat(limit), Multiplexer::None).is_some());
+        assert!(osc52_sequence(&"x".repeat(limit + 1), Multiplexer::None).is_none());
+    }
+}
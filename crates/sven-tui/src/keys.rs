@@ -1,7 +1,11 @@
 // Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
 //
 // SPDX-License-Identifier: Apache-2.0
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Context as _;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use sven_config::KeymapConfig;
 
 /// All logical actions the TUI can perform, independent of key binding.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -36,6 +40,7 @@ pub enum Action {
     // Search
     SearchOpen,
     SearchClose,
+    SearchCancel,
     SearchNextMatch,
     SearchPrevMatch,
     SearchInput(char),
@@ -44,6 +49,8 @@ pub enum Action {
     // Input
     InputChar(char),
     InputNewline,
+    /// Attach the image on the system clipboard to the message (Ctrl+v).
+    PasteImage,
     /// ESC while focused on the input pane (not in a completion overlay).
     /// Cancels an ongoing edit when one is active; otherwise clears the input
     /// buffer, attachments, and resets scroll/history navigation state.
@@ -141,7 +148,9 @@ pub enum Action {
     CycleTeammateBackward,
     /// Toggle the task list overlay (Ctrl+t when in team mode).
     ToggleTaskList,
-    /// Expand or collapse a DelegateSummary segment at cursor (Space / Enter).
+    /// Expand or collapse the segment at cursor (Space / Enter): a
+    /// DelegateSummary, or in ratatui-only mode a message, tool call or
+    /// thinking block.
     ToggleDelegateSummary,
 
     // Chat list (multi-session sidebar)
@@ -341,6 +350,8 @@ pub fn map_key(
         KeyCode::Char('c') if ctrl && in_input => Some(Action::InterruptAgent),
         KeyCode::Char('u') if ctrl && in_input => Some(Action::InputDeleteToStart),
         KeyCode::Char('k') if ctrl && in_input => Some(Action::InputDeleteToEnd),
+        // Text pastes arrive as bracketed paste; Ctrl+v fetches an image.
+        KeyCode::Char('v') if ctrl && in_input => Some(Action::PasteImage),
         // Ctrl+Up/Down: explicit history navigation (always jumps, regardless of cursor row).
         // Plain Up/Down also trigger history when the cursor is already on the first/last row
         // (shell-style), which is handled inside the InputMoveLineUp/Down dispatch handlers.
@@ -438,20 +449,219 @@ pub fn map_key(
     }
 }
 
+/// Keys of the open search bar: every character extends the query, so `n`
+/// and `N` only navigate once `Enter` has closed the bar; `Esc` abandons the
+/// search and scrolls back to where it started.
 pub(crate) fn map_search_key(event: KeyEvent) -> Option<Action> {
-    let shift = event.modifiers.contains(KeyModifiers::SHIFT);
+    let ctrl = event.modifiers.contains(KeyModifiers::CONTROL);
 
     match event.code {
-        KeyCode::Esc => Some(Action::SearchClose),
+        KeyCode::Esc => Some(Action::SearchCancel),
         KeyCode::Enter => Some(Action::SearchClose),
         KeyCode::Backspace => Some(Action::SearchBackspace),
-        KeyCode::Char('n') if !shift => Some(Action::SearchNextMatch),
-        KeyCode::Char('N') | KeyCode::Char('n') if shift => Some(Action::SearchPrevMatch),
-        KeyCode::Char(c) => Some(Action::SearchInput(c)),
+        KeyCode::Down => Some(Action::SearchNextMatch),
+        KeyCode::Up => Some(Action::SearchPrevMatch),
+        KeyCode::Char('n') if ctrl => Some(Action::SearchNextMatch),
+        KeyCode::Char('p') if ctrl => Some(Action::SearchPrevMatch),
+        KeyCode::Char(c) if !ctrl => Some(Action::SearchInput(c)),
         _ => None,
     }
 }
 
+// ─── Configurable keymap ──────────────────────────────────────────────────────
+
+/// Actions that `tui.keymap` can bind, by configuration name.
+const BINDABLE_ACTIONS: &[(&str, Action)] = &[
+    ("focus_input", Action::FocusInput),
+    ("nav_prefix", Action::NavPrefix),
+    ("nav_up", Action::NavUp),
+    ("nav_down", Action::NavDown),
+    ("nav_left", Action::NavLeft),
+    ("nav_right", Action::NavRight),
+    ("scroll_up", Action::ScrollUp),
+    ("scroll_down", Action::ScrollDown),
+    ("chat_highlight_up", Action::ChatHighlightUp),
+    ("chat_highlight_down", Action::ChatHighlightDown),
+    ("show_chat_help", Action::ShowChatHelp),
+    ("scroll_page_up", Action::ScrollPageUp),
+    ("scroll_page_down", Action::ScrollPageDown),
+    ("scroll_top", Action::ScrollTop),
+    ("scroll_bottom", Action::ScrollBottom),
+    ("search_open", Action::SearchOpen),
+    ("search_next_match", Action::SearchNextMatch),
+    ("search_prev_match", Action::SearchPrevMatch),
+    ("input_newline", Action::InputNewline),
+    ("paste_image", Action::PasteImage),
+    ("input_escape", Action::InputEscape),
+    ("input_backspace", Action::InputBackspace),
+    ("input_delete", Action::InputDelete),
+    ("input_move_cursor_left", Action::InputMoveCursorLeft),
+    ("input_move_cursor_right", Action::InputMoveCursorRight),
+    ("input_move_word_left", Action::InputMoveWordLeft),
+    ("input_move_word_right", Action::InputMoveWordRight),
+    ("input_move_line_start", Action::InputMoveLineStart),
+    ("input_move_line_end", Action::InputMoveLineEnd),
+    ("input_move_line_up", Action::InputMoveLineUp),
+    ("input_move_line_down", Action::InputMoveLineDown),
+    ("input_page_up", Action::InputPageUp),
+    ("input_page_down", Action::InputPageDown),
+    ("input_delete_to_end", Action::InputDeleteToEnd),
+    ("input_delete_to_start", Action::InputDeleteToStart),
+    ("input_history_up", Action::InputHistoryUp),
+    ("input_history_down", Action::InputHistoryDown),
+    ("submit", Action::Submit),
+    ("interrupt_agent", Action::InterruptAgent),
+    ("cycle_mode", Action::CycleMode),
+    ("edit_message_at_cursor", Action::EditMessageAtCursor),
+    ("delete_chat_segment", Action::DeleteChatSegment),
+    ("remove_chat_segment", Action::RemoveChatSegment),
+    ("rerun_from_segment", Action::RerunFromSegment),
+    ("focus_queue", Action::FocusQueue),
+    ("resize_input_grow", Action::ResizeInputGrow),
+    ("resize_input_shrink", Action::ResizeInputShrink),
+    ("submit_buffer_to_agent", Action::SubmitBufferToAgent),
+    ("completion_next", Action::CompletionNext),
+    ("completion_prev", Action::CompletionPrev),
+    ("help", Action::Help),
+    ("open_pager", Action::OpenPager),
+    ("copy_segment", Action::CopySegment),
+    ("copy_all", Action::CopyAll),
+    ("open_team_picker", Action::OpenTeamPicker),
+    ("cycle_teammate_forward", Action::CycleTeammateForward),
+    ("cycle_teammate_backward", Action::CycleTeammateBackward),
+    ("toggle_task_list", Action::ToggleTaskList),
+    ("toggle_delegate_summary", Action::ToggleDelegateSummary),
+    ("toggle_chat_list", Action::ToggleChatList),
+    ("focus_chat_list", Action::FocusChatList),
+    ("new_chat", Action::NewChat),
+];
+
+/// Action name that removes a built-in binding.
+const UNBOUND: &str = "none";
+
+/// A key with the modifiers that distinguish bindings.
+type KeyChord = (KeyCode, KeyModifiers);
+
+/// User key bindings from `tui.keymap`, consulted before [`map_key`] while
+/// the input or chat pane has focus.  `None` entries unbind a key.
+#[derive(Debug, Clone, Default)]
+pub struct Keymap {
+    global: HashMap<KeyChord, Option<Action>>,
+    input: HashMap<KeyChord, Option<Action>>,
+    chat: HashMap<KeyChord, Option<Action>>,
+}
+
+impl Keymap {
+    /// Build the keymap, rejecting unknown key names and action names.
+    pub fn from_config(cfg: &KeymapConfig) -> anyhow::Result<Self> {
+        let table = |name: &str, entries: &BTreeMap<String, String>| {
+            entries
+                .iter()
+                .map(|(key, action)| {
+                    let chord = parse_key(key)
+                        .with_context(|| format!("tui.keymap.{name}: invalid key '{key}'"))?;
+                    let action = parse_action(action)
+                        .with_context(|| format!("tui.keymap.{name}: key '{key}'"))?;
+                    Ok((chord, action))
+                })
+                .collect::<anyhow::Result<HashMap<_, _>>>()
+        };
+        Ok(Self {
+            global: table("global", &cfg.global)?,
+            input: table("input", &cfg.input)?,
+            chat: table("chat", &cfg.chat)?,
+        })
+    }
+
+    /// The user binding of `event` in the input pane (`in_input`) or the chat
+    /// pane: `Some(None)` when the key is unbound, `None` when the keymap
+    /// does not mention it and the built-in mapping applies.
+    pub fn lookup(&self, event: &KeyEvent, in_input: bool) -> Option<Option<Action>> {
+        let chord = normalize(event.code, event.modifiers);
+        let pane = if in_input { &self.input } else { &self.chat };
+        pane.get(&chord)
+            .or_else(|| self.global.get(&chord))
+            .cloned()
+    }
+}
+
+fn parse_action(name: &str) -> anyhow::Result<Option<Action>> {
+    if name == UNBOUND {
+        return Ok(None);
+    }
+    BINDABLE_ACTIONS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, action)| Some(action.clone()))
+        .ok_or_else(|| anyhow::anyhow!("unknown action '{name}'"))
+}
+
+/// Parse a key such as `ctrl+e`, `alt+shift+up`, `G`, `f5` or `ctrl++`.
+fn parse_key(spec: &str) -> anyhow::Result<KeyChord> {
+    let (mods, key) = if spec.len() > 1 && spec.ends_with("++") {
+        (&spec[..spec.len() - 2], "+")
+    } else {
+        match spec.rsplit_once('+') {
+            Some((mods, key)) if !key.is_empty() => (mods, key),
+            _ => ("", spec),
+        }
+    };
+    let mut modifiers = KeyModifiers::NONE;
+    for m in mods.split('+').filter(|m| !m.is_empty()) {
+        modifiers |= match m.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => KeyModifiers::CONTROL,
+            "alt" | "meta" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            other => anyhow::bail!("unknown modifier '{other}'"),
+        };
+    }
+    let mut chars = key.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match key.to_ascii_lowercase().as_str() {
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "space" => KeyCode::Char(' '),
+            name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                Some(n @ 1..=24) => KeyCode::F(n),
+                _ => anyhow::bail!("unknown key '{key}'"),
+            },
+        },
+    };
+    Ok(normalize(code, modifiers))
+}
+
+/// Canonical form of a key so that a configured key and the event the
+/// terminal reports for it compare equal: Shift is folded into the character
+/// (`shift+g` is `G`) and Shift+Tab is `BackTab`.
+fn normalize(code: KeyCode, modifiers: KeyModifiers) -> KeyChord {
+    let mut modifiers =
+        modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+    let shift = modifiers.contains(KeyModifiers::SHIFT);
+    let code = match code {
+        KeyCode::Char(c) if shift => KeyCode::Char(c.to_ascii_uppercase()),
+        KeyCode::Tab if shift => KeyCode::BackTab,
+        other => other,
+    };
+    if matches!(code, KeyCode::Char(_) | KeyCode::BackTab) {
+        modifiers.remove(KeyModifiers::SHIFT);
+    }
+    (code, modifiers)
+}
+
 // ─── Unit tests ───────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -807,4 +1017,80 @@ mod tests {
             Some(Action::ShowChatHelp)
         );
     }
+
+    fn keymap(json: serde_json::Value) -> anyhow::Result<Keymap> {
+        let cfg: KeymapConfig = serde_json::from_value(json).unwrap();
+        Keymap::from_config(&cfg)
+    }
+
+    #[test]
+    fn keymap_override_dispatches_configured_action() {
+        let km = keymap(serde_json::json!({
+            "chat": { "ctrl+e": "scroll_down", "ctrl+y": "scroll_up", "G": "scroll_top" },
+            "input": { "ctrl+s": "submit", "enter": "input_newline" },
+            "global": { "f5": "open_pager", "alt+shift+up": "scroll_page_up" }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            km.lookup(&ctrl_key('e'), false),
+            Some(Some(Action::ScrollDown))
+        );
+        assert_eq!(
+            km.lookup(&ctrl_key('y'), false),
+            Some(Some(Action::ScrollUp))
+        );
+        // The terminal reports `G` with Shift held.
+        let shift_g = key(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        assert_eq!(km.lookup(&shift_g, false), Some(Some(Action::ScrollTop)));
+
+        assert_eq!(km.lookup(&ctrl_key('s'), true), Some(Some(Action::Submit)));
+        let enter = key(KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(km.lookup(&enter, true), Some(Some(Action::InputNewline)));
+        // Pane tables only apply in their pane.
+        assert_eq!(km.lookup(&ctrl_key('e'), true), None);
+        assert_eq!(km.lookup(&enter, false), None);
+
+        let f5 = key(KeyCode::F(5), KeyModifiers::NONE);
+        assert_eq!(km.lookup(&f5, true), Some(Some(Action::OpenPager)));
+        assert_eq!(km.lookup(&f5, false), Some(Some(Action::OpenPager)));
+        let up = key(KeyCode::Up, KeyModifiers::ALT | KeyModifiers::SHIFT);
+        assert_eq!(km.lookup(&up, false), Some(Some(Action::ScrollPageUp)));
+    }
+
+    #[test]
+    fn keymap_pane_binding_wins_over_global_and_none_unbinds() {
+        let km = keymap(serde_json::json!({
+            "global": { "ctrl+t": "help", "ctrl++": "resize_input_grow" },
+            "chat": { "ctrl+t": "none" }
+        }))
+        .unwrap();
+        assert_eq!(km.lookup(&ctrl_key('t'), false), Some(None));
+        assert_eq!(km.lookup(&ctrl_key('t'), true), Some(Some(Action::Help)));
+        assert_eq!(
+            km.lookup(&ctrl_key('+'), true),
+            Some(Some(Action::ResizeInputGrow))
+        );
+        // Unmentioned keys fall through to the built-in mapping.
+        assert_eq!(km.lookup(&plain_key('j'), false), None);
+    }
+
+    #[test]
+    fn keymap_rejects_unknown_actions_and_keys() {
+        let err =
+            keymap(serde_json::json!({ "chat": { "ctrl+e": "scroll_sideways" } })).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("unknown action 'scroll_sideways'"),
+            "{err:#}"
+        );
+
+        let err = keymap(serde_json::json!({ "input": { "hyper+x": "submit" } })).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("unknown modifier 'hyper'"),
+            "{err:#}"
+        );
+
+        assert!(keymap(serde_json::json!({ "input": { "f99": "submit" } })).is_err());
+        assert!(keymap(serde_json::json!({ "input": { "pgup": "submit" } })).is_err());
+    }
 }
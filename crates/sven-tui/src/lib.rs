@@ -4,6 +4,7 @@
 mod agent;
 mod app;
 mod chat;
+mod clipboard;
 mod commands;
 mod input;
 mod input_wrap;
@@ -11,15 +12,18 @@ mod keys;
 mod layout;
 mod markdown;
 pub mod node_agent;
+mod notify;
 mod nvim;
 mod overlay;
 mod pager;
 mod state;
 mod submit;
 mod ui;
+mod vi;
 
 pub use app::{App, AppOptions, ModelDirective, NodeBackend, QueuedMessage};
 pub use chat::segment::ChatSegment;
+pub use keys::Keymap;
 // Re-export sven-frontend types for downstream crates that depend on sven-tui.
 pub use sven_frontend::{AgentRequest, FrontendOptions, SessionMeta};
 pub use sven_input::history::{save as history_save, save_to as history_save_to};
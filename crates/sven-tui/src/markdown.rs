@@ -4,13 +4,50 @@
 use pulldown_cmark::{Alignment, Event, Options, Parser, Tag, TagEnd};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
+use std::sync::OnceLock;
 use sven_frontend::markdown::{parse_markdown_blocks, MarkdownBlock};
+use syntect::easy::HighlightLines;
+use syntect::highlighting::ThemeSet;
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
 
 use crate::ui::theme::{md_blockquote, md_bullet, md_rule_char};
 
 /// A styled line ready for Ratatui rendering.
 pub type StyledLines = Vec<Line<'static>>;
 
+/// Settings that affect how every block is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderCtx {
+    /// Use plain-ASCII box chars instead of Unicode; also disables code
+    /// highlighting.
+    pub ascii: bool,
+    /// Syntect theme used to color fenced code blocks.
+    pub code_theme: &'static str,
+}
+
+impl RenderCtx {
+    /// Context for the `tui.theme` setting (`dark`, `light` or `solarized`).
+    /// Unknown names use the dark code theme.
+    pub fn new(ascii: bool, tui_theme: &str) -> Self {
+        let code_theme = match tui_theme {
+            "light" => "InspiredGitHub",
+            "solarized" => "Solarized (dark)",
+            _ => DEFAULT_CODE_THEME,
+        };
+        Self { ascii, code_theme }
+    }
+}
+
+impl Default for RenderCtx {
+    fn default() -> Self {
+        Self {
+            ascii: false,
+            code_theme: DEFAULT_CODE_THEME,
+        }
+    }
+}
+
 // ── Public API ────────────────────────────────────────────────────────────────
 
 /// Convert a markdown string into a list of styled [`Line`]s for Ratatui.
@@ -20,30 +57,144 @@ pub type StyledLines = Vec<Line<'static>>;
 /// item, block quote, etc.) is rendered correctly without cross-contamination.
 ///
 /// `wrap_width` — wrap long text at this column (0 → 80).
-/// `ascii`      — use plain-ASCII box chars instead of Unicode.
-pub fn render_markdown(md: &str, wrap_width: u16, ascii: bool) -> StyledLines {
-    let width = if wrap_width == 0 {
+/// `ctx`        — box characters and code colors, see [`RenderCtx`].
+pub fn render_markdown(md: &str, wrap_width: u16, ctx: RenderCtx) -> StyledLines {
+    let blocks = parse_markdown_blocks(md);
+    render_blocks_to_lines(&blocks, effective_width(wrap_width), ctx, &mut 1)
+}
+
+fn effective_width(wrap_width: u16) -> usize {
+    if wrap_width == 0 {
         80
     } else {
         wrap_width as usize
-    };
-    let blocks = parse_markdown_blocks(md);
-    render_blocks_to_lines(&blocks, width, ascii)
+    }
+}
+
+// ── Incremental rendering for streaming ───────────────────────────────────────
+
+/// Renderer for markdown that grows at the end, such as a streaming response.
+///
+/// Text up to the last blank line that safely ends a block is rendered once
+/// and kept; each call parses and wraps only what follows it.  The result is
+/// the same as [`render_markdown`] on the whole text.
+#[derive(Debug, Clone, Default)]
+pub struct StreamingMarkdown {
+    /// Source whose rendering is cached in `settled_lines`.
+    settled_src: String,
+    settled_lines: StyledLines,
+    /// Ordered-list counter after the settled blocks.
+    counter: u64,
+    width: usize,
+    ctx: RenderCtx,
+}
+
+impl StreamingMarkdown {
+    /// Styled lines of `md`.  When `md` extends the text of the previous
+    /// call only the tail is rendered; other text, a new width or a new
+    /// context starts over.
+    pub fn render(&mut self, md: &str, wrap_width: u16, ctx: RenderCtx) -> StyledLines {
+        let width = effective_width(wrap_width);
+        if width != self.width || ctx != self.ctx || !md.starts_with(&self.settled_src) {
+            *self = Self {
+                width,
+                ctx,
+                counter: 1,
+                ..Self::default()
+            };
+        }
+        let settled = self.settled_src.len();
+        let point = settle_point(md, settled);
+        if point > settled {
+            let chunk = &md[settled..point];
+            let blocks = parse_markdown_blocks(chunk);
+            let lines = render_blocks_to_lines(&blocks, width, ctx, &mut self.counter);
+            self.settled_lines.extend(lines);
+            self.settled_src.push_str(chunk);
+        }
+
+        let tail = parse_markdown_blocks(&md[point..]);
+        let mut lines = self.settled_lines.clone();
+        lines.extend(render_blocks_to_lines(
+            &tail,
+            width,
+            ctx,
+            &mut self.counter.clone(),
+        ));
+        lines
+    }
+}
+
+/// Byte offset of the last block boundary in `md` at or after `from` (a line
+/// start outside any code fence) where the text can be split without
+/// changing how either side renders, or `from` when there is none.
+///
+/// A boundary is the start of a line that follows a blank line and begins
+/// with a character that cannot continue the previous block: indentation
+/// continues list items, a list marker would make the list loose, a table
+/// row would merge with the table above, and a digit may still turn into an
+/// ordered-list marker.
+fn settle_point(md: &str, from: usize) -> usize {
+    let mut point = from;
+    let mut fence: Option<(char, usize)> = None;
+    let mut after_blank = false;
+    let mut pos = from;
+    for line in md[from..].split_inclusive('\n') {
+        let start = pos;
+        pos += line.len();
+        if fence.is_none()
+            && after_blank
+            && line
+                .chars()
+                .next()
+                .is_some_and(|c| !c.is_whitespace() && !"-*+|>0123456789".contains(c))
+        {
+            point = start;
+        }
+
+        let trimmed = line.trim_start_matches(' ');
+        let indent = line.len() - trimmed.len();
+        let marker = trimmed.chars().next().filter(|c| matches!(c, '`' | '~'));
+        match (fence, marker) {
+            (None, Some(c)) if indent < 4 => {
+                let run = trimmed.chars().take_while(|&x| x == c).count();
+                if run >= 3 {
+                    fence = Some((c, run));
+                }
+            }
+            (Some((c, len)), Some(m)) if m == c && indent < 4 => {
+                let run = trimmed.chars().take_while(|&x| x == c).count();
+                if run >= len && trimmed[run..].trim().is_empty() {
+                    fence = None;
+                }
+            }
+            _ => {}
+        }
+        after_blank = line.trim().is_empty();
+    }
+    point
 }
 
 // ── Blocks-based renderer (matches GUI parsing) ───────────────────────────────
 
 /// Render parsed markdown blocks to styled lines. Uses the same block structure
 /// as the GUI so paragraphs, block quotes, list items, etc. are never confused.
-fn render_blocks_to_lines(blocks: &[MarkdownBlock], width: usize, ascii: bool) -> StyledLines {
+/// `ordered_counter` is the number of the next ordered-list item, carried
+/// between calls when a document is rendered in pieces.
+fn render_blocks_to_lines(
+    blocks: &[MarkdownBlock],
+    width: usize,
+    ctx: RenderCtx,
+    ordered_counter: &mut u64,
+) -> StyledLines {
+    let ascii = ctx.ascii;
     let mut lines = Vec::new();
-    let mut ordered_counter: u64 = 1;
     let mut i = 0;
 
     while i < blocks.len() {
         // Collect consecutive TableRow blocks and render as a single table.
         if let MarkdownBlock::TableRow(_) = &blocks[i] {
-            ordered_counter = 1;
+            *ordered_counter = 1;
             let mut table_rows: Vec<(Vec<String>, bool)> = Vec::new();
             while i < blocks.len() {
                 if let MarkdownBlock::TableRow(cells) = &blocks[i] {
@@ -83,8 +234,13 @@ fn render_blocks_to_lines(blocks: &[MarkdownBlock], width: usize, ascii: bool) -
                 block_lines.push(Line::default());
                 block_lines
             }
-            MarkdownBlock::CodeBlock { language: _, code } => {
-                let mut block_lines = plain_code_lines(code, width);
+            MarkdownBlock::CodeBlock { language, code } => {
+                let highlighted = if ascii {
+                    None
+                } else {
+                    highlighted_code_lines(language, code, width, ctx.code_theme)
+                };
+                let mut block_lines = highlighted.unwrap_or_else(|| plain_code_lines(code, width));
                 block_lines.push(Line::default());
                 block_lines
             }
@@ -95,12 +251,12 @@ fn render_blocks_to_lines(blocks: &[MarkdownBlock], width: usize, ascii: bool) -
                 task_checked,
             } => {
                 let (prefix, display_text) = if *ordered {
-                    let num = ordered_counter;
-                    ordered_counter += 1;
+                    let num = *ordered_counter;
+                    *ordered_counter += 1;
                     let indent = "  ".repeat(*depth as usize);
                     (format!("{indent}  {num}. "), text.as_str())
                 } else {
-                    ordered_counter = 1;
+                    *ordered_counter = 1;
                     let indent = "  ".repeat(*depth as usize);
                     let (bullet, display_text) = if let Some(checked) = task_checked {
                         (
@@ -132,7 +288,7 @@ fn render_blocks_to_lines(blocks: &[MarkdownBlock], width: usize, ascii: bool) -
                 )
             }
             MarkdownBlock::BlockQuote(text) => {
-                ordered_counter = 1;
+                *ordered_counter = 1;
                 let prefix = md_blockquote(ascii).to_string();
                 let spans = parse_inline_to_spans(text);
                 let mut block_lines = word_wrap_spans_to_lines_with_prefix(
@@ -146,7 +302,7 @@ fn render_blocks_to_lines(blocks: &[MarkdownBlock], width: usize, ascii: bool) -
                 block_lines
             }
             MarkdownBlock::Separator => {
-                ordered_counter = 1;
+                *ordered_counter = 1;
                 let rc = md_rule_char(ascii);
                 let rc_w = unicode_width::UnicodeWidthChar::width(rc)
                     .unwrap_or(1)
@@ -161,7 +317,7 @@ fn render_blocks_to_lines(blocks: &[MarkdownBlock], width: usize, ascii: bool) -
                 ]
             }
             MarkdownBlock::InlineCode(text) => {
-                ordered_counter = 1;
+                *ordered_counter = 1;
                 vec![Line::from(Span::styled(
                     format!("`{text}`"),
                     Style::default().fg(Color::Yellow),
@@ -331,43 +487,107 @@ fn heading_style_from_level(level: u8) -> Style {
 }
 
 /// Plain (no highlighting) code fallback — cyan text.
+fn plain_code_lines(code: &str, max_width: usize) -> Vec<Line<'static>> {
+    let style = Style::default().fg(Color::Cyan);
+    code.lines()
+        .flat_map(|raw| wrap_code_line(vec![(raw.to_string(), style)], max_width))
+        .collect()
+}
+
+/// Hard-wrap one line of styled code fragments at `max_width` columns.
 ///
 /// Lines wider than `max_width` are hard-wrapped so that `chat_lines` never
 /// contains spans that exceed the visible chat pane width.  Without this,
 /// long lines produce styled cells in Ratatui's buffer that persist as visual
 /// ghost artefacts when the viewport is scrolled.
-fn plain_code_lines(code: &str, max_width: usize) -> Vec<Line<'static>> {
-    let style = Style::default().fg(Color::Cyan);
+fn wrap_code_line(fragments: Vec<(String, Style)>, max_width: usize) -> Vec<Line<'static>> {
     let mut out = Vec::new();
-    for raw in code.lines() {
-        let mut remaining = raw;
-        loop {
-            let mut col = 0usize;
-            let mut byte_end = remaining.len();
-            for (i, ch) in remaining.char_indices() {
-                // CJK-conservative width: ambiguous chars count as 2 so the
-                // hard-wrapped code line never overflows the terminal column limit.
-                let cw = unicode_width::UnicodeWidthChar::width(ch).unwrap_or(0);
-                if col + cw > max_width {
-                    byte_end = i;
-                    break;
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut col = 0usize;
+    for (text, style) in fragments {
+        let mut start = 0;
+        for (i, ch) in text.char_indices() {
+            // CJK-conservative width: ambiguous chars count as 2 so the
+            // hard-wrapped code line never overflows the terminal column limit.
+            let cw = unicode_width::UnicodeWidthChar::width(ch).unwrap_or(0);
+            if col + cw > max_width && col > 0 {
+                if i > start {
+                    spans.push(Span::styled(text[start..i].to_string(), style));
                 }
-                col += cw;
-            }
-            if byte_end == remaining.len() {
-                out.push(Line::from(Span::styled(remaining.to_string(), style)));
-                break;
+                out.push(Line::from(std::mem::take(&mut spans)));
+                start = i;
+                col = 0;
             }
-            out.push(Line::from(Span::styled(
-                remaining[..byte_end].to_string(),
-                style,
-            )));
-            remaining = &remaining[byte_end..];
+            col += cw;
+        }
+        if start < text.len() {
+            spans.push(Span::styled(text[start..].to_string(), style));
         }
     }
+    out.push(Line::from(spans));
     out
 }
 
+// ── Syntax highlighting ───────────────────────────────────────────────────────
+
+static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+static THEME_SET: OnceLock<ThemeSet> = OnceLock::new();
+
+const DEFAULT_CODE_THEME: &str = "base16-ocean.dark";
+
+/// Syntax for the info string of a fence (`rust`, `py`, `sh title="x"`), or
+/// `None` when the language is missing or not known.
+fn fence_syntax<'a>(syntaxes: &'a SyntaxSet, language: &str) -> Option<&'a SyntaxReference> {
+    let token = language
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .next()
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    let token = match token.as_str() {
+        "shell" | "console" | "zsh" => "bash",
+        other => other,
+    };
+    if token.is_empty() {
+        return None;
+    }
+    syntaxes
+        .find_syntax_by_token(token)
+        .filter(|s| s.name != syntaxes.find_syntax_plain_text().name)
+}
+
+/// Code colored token by token, or `None` when the fence language is
+/// unknown so the caller falls back to [`plain_code_lines`].
+fn highlighted_code_lines(
+    language: &str,
+    code: &str,
+    max_width: usize,
+    code_theme: &str,
+) -> Option<Vec<Line<'static>>> {
+    let syntaxes = SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines);
+    let syntax = fence_syntax(syntaxes, language)?;
+    let themes = THEME_SET.get_or_init(ThemeSet::load_defaults);
+    let theme = themes.themes.get(code_theme)?;
+
+    let mut highlighter = HighlightLines::new(syntax, theme);
+    let mut out = Vec::new();
+    for line in LinesWithEndings::from(code) {
+        let ranges = highlighter.highlight_line(line, syntaxes).ok()?;
+        let fragments = ranges
+            .into_iter()
+            .map(|(style, text)| {
+                let fg = style.foreground;
+                (
+                    text.trim_end_matches(['\n', '\r']).to_string(),
+                    Style::default().fg(Color::Rgb(fg.r, fg.g, fg.b)),
+                )
+            })
+            .filter(|(text, _)| !text.is_empty())
+            .collect();
+        out.extend(wrap_code_line(fragments, max_width));
+    }
+    Some(out)
+}
+
 // ── Table rendering ───────────────────────────────────────────────────────────
 
 /// Render a buffered GFM table to a list of styled [`Line`]s.
@@ -519,11 +739,22 @@ fn render_table(
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use super::*;
 
+    const DARK: RenderCtx = RenderCtx {
+        ascii: false,
+        code_theme: DEFAULT_CODE_THEME,
+    };
+    const ASCII: RenderCtx = RenderCtx {
+        ascii: true,
+        ..DARK
+    };
+
     #[test]
     fn render_empty_returns_some_lines() {
-        let lines = render_markdown("", 80, false);
+        let lines = render_markdown("", 80, DARK);
         assert!(
             lines.len() <= 1,
             "empty input should yield at most one line"
@@ -535,7 +766,7 @@ mod tests {
         // TaskListMarker arrives after Start(Item) in pulldown-cmark. The first
         // item must show ☑ (not •) when completed.
         let md = "- [x] First task done\n- [ ] Second pending\n";
-        let lines = render_markdown(md, 80, false);
+        let lines = render_markdown(md, 80, DARK);
         let text: String = lines
             .iter()
             .flat_map(|l| l.spans.iter().map(|s| s.content.as_ref()))
@@ -553,14 +784,14 @@ mod tests {
     #[test]
     fn render_unclosed_link_does_not_panic() {
         let md = "[unclosed link\n\nnormal text";
-        let lines = render_markdown(md, 80, false);
+        let lines = render_markdown(md, 80, DARK);
         assert!(!lines.is_empty(), "should produce some lines");
     }
 
     #[test]
     fn style_stack_cleanup_after_unclosed_tag() {
         let md = "**bold [link\n\nplain text";
-        let lines = render_markdown(md, 80, false);
+        let lines = render_markdown(md, 80, DARK);
         assert!(!lines.is_empty());
     }
 
@@ -582,7 +813,7 @@ mod tests {
     #[test]
     fn table_is_not_compacted_into_single_line() {
         let md = "| A | B |\n|---|---|\n| 1 | 2 |\n";
-        let lines = render_markdown(md, 80, false);
+        let lines = render_markdown(md, 80, DARK);
         // Must produce more than one non-empty line.
         let non_empty: Vec<_> = lines.iter().filter(|l| !l.spans.is_empty()).collect();
         assert!(
@@ -594,7 +825,7 @@ mod tests {
     #[test]
     fn table_headers_appear_in_output() {
         let md = "| Name | Age |\n|------|-----|\n| Alice | 30 |\n";
-        let lines = render_markdown(md, 80, false);
+        let lines = render_markdown(md, 80, DARK);
         let text = lines_to_text(&lines);
         assert!(text.contains("Name"), "header 'Name' missing: {text}");
         assert!(text.contains("Age"), "header 'Age' missing: {text}");
@@ -603,7 +834,7 @@ mod tests {
     #[test]
     fn table_body_cells_appear_in_output() {
         let md = "| Name | Age |\n|------|-----|\n| Alice | 30 |\n| Bob | 25 |\n";
-        let lines = render_markdown(md, 80, false);
+        let lines = render_markdown(md, 80, DARK);
         let text = lines_to_text(&lines);
         assert!(text.contains("Alice"), "cell 'Alice' missing: {text}");
         assert!(text.contains("Bob"), "cell 'Bob' missing: {text}");
@@ -614,7 +845,7 @@ mod tests {
     #[test]
     fn table_has_vertical_separators() {
         let md = "| A | B |\n|---|---|\n| 1 | 2 |\n";
-        let lines = render_markdown(md, 80, false);
+        let lines = render_markdown(md, 80, DARK);
         let text = lines_to_text(&lines);
         // Non-ASCII mode should have │ separators.
         assert!(
@@ -626,7 +857,7 @@ mod tests {
     #[test]
     fn table_ascii_mode_uses_pipe_separators() {
         let md = "| A | B |\n|---|---|\n| 1 | 2 |\n";
-        let lines = render_markdown(md, 80, true); // ascii=true
+        let lines = render_markdown(md, 80, ASCII);
         let text = lines_to_text(&lines);
         assert!(
             text.contains('|'),
@@ -641,7 +872,7 @@ mod tests {
         // The header separator row (├─┼─┤ or +---+) must appear between
         // the header and the first body row.
         let md = "| Col |\n|-----|\n| val |\n";
-        let lines = render_markdown(md, 80, false);
+        let lines = render_markdown(md, 80, DARK);
         let text = lines_to_text(&lines);
         // The separator between header and body uses ├ or + in ASCII mode.
         assert!(
@@ -653,7 +884,7 @@ mod tests {
     #[test]
     fn table_preceded_by_text_renders_both() {
         let md = "Some text.\n\n| X | Y |\n|---|---|\n| a | b |\n";
-        let lines = render_markdown(md, 80, false);
+        let lines = render_markdown(md, 80, DARK);
         let text = lines_to_text(&lines);
         assert!(text.contains("Some text"), "preceding text present: {text}");
         assert!(text.contains('X'), "table header X present: {text}");
@@ -669,7 +900,7 @@ mod tests {
     #[test]
     fn block_quote_renders_without_list_bullet() {
         let md = "> This is a block quote\n\nNormal paragraph";
-        let lines = render_markdown(md, 80, false);
+        let lines = render_markdown(md, 80, DARK);
         let text = lines_to_text(&lines);
         assert!(
             text.contains("This is a block quote"),
@@ -699,10 +930,130 @@ mod tests {
         );
     }
 
+    // ── Streaming ─────────────────────────────────────────────────────────────
+
+    const STREAM_FIXTURE: &str = "**Agent:** Here is the plan for the \
+        refactor, wrapped over several lines of text so the wrapper has work.\n\n\
+        ## Steps\n\n\
+        1. Split the parser\n\
+        2. Move the tests\n\n\
+        3. A loose item after a blank line\n\n\
+        Some text with `code` and **bold** words.\n\n\
+        ```rust\nfn main() {\n\n    println!(\"hi\");\n}\n```\n\n\
+        - bullet one\n\n  continued under the bullet\n\n\
+        | A | B |\n|---|---|\n| 1 | 2 |\n\n\
+        | C |\n|---|\n| 3 |\n\n\
+        > quoted\n\n\
+        ---\n\n\
+        Final paragraph that keeps going ";
+
+    #[test]
+    fn streaming_matches_full_render_at_every_step() {
+        for step in [1, 3, 17] {
+            let mut stream = StreamingMarkdown::default();
+            let mut end = 0;
+            while end < STREAM_FIXTURE.len() {
+                end = (end + step).min(STREAM_FIXTURE.len());
+                while !STREAM_FIXTURE.is_char_boundary(end) {
+                    end += 1;
+                }
+                let prefix = &STREAM_FIXTURE[..end];
+                assert_eq!(
+                    stream.render(prefix, 40, DARK),
+                    render_markdown(prefix, 40, DARK),
+                    "step {step}, prefix {prefix:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn streaming_caches_settled_blocks() {
+        let mut stream = StreamingMarkdown::default();
+        stream.render(STREAM_FIXTURE, 40, DARK);
+        assert!(stream.settled_src.len() > STREAM_FIXTURE.len() / 2);
+        assert!(STREAM_FIXTURE[stream.settled_src.len()..].starts_with("Final paragraph"));
+    }
+
+    #[test]
+    fn streaming_starts_over_on_new_text_or_width() {
+        let mut stream = StreamingMarkdown::default();
+        stream.render(STREAM_FIXTURE, 40, DARK);
+        let other = "Different text.\n\nAnd more.";
+        assert_eq!(
+            stream.render(other, 40, DARK),
+            render_markdown(other, 40, DARK)
+        );
+        assert_eq!(
+            stream.render(STREAM_FIXTURE, 72, ASCII),
+            render_markdown(STREAM_FIXTURE, 72, ASCII)
+        );
+    }
+
+    // ── Code highlighting ─────────────────────────────────────────────────────
+
+    fn code_colors(lines: &StyledLines) -> HashSet<Option<Color>> {
+        lines
+            .iter()
+            .flat_map(|l| l.spans.iter().map(|s| s.style.fg))
+            .collect()
+    }
+
+    #[test]
+    fn rust_fence_is_highlighted() {
+        let md = "```rust\nfn main() {\n    let x: u32 = 42; // answer\n}\n```";
+        let lines = render_markdown(md, 80, DARK);
+        assert!(lines_to_text(&lines).contains("let x: u32 = 42;"));
+        assert!(code_colors(&lines).len() > 2, "{lines:?}");
+    }
+
+    #[test]
+    fn common_languages_are_detected() {
+        let syntaxes = SyntaxSet::load_defaults_newlines();
+        for lang in [
+            "rust", "rs", "c", "python", "py", "json", "sh", "bash", "shell",
+        ] {
+            assert!(fence_syntax(&syntaxes, lang).is_some(), "{lang}");
+        }
+        assert!(fence_syntax(&syntaxes, "rust,ignore").is_some());
+        assert!(fence_syntax(&syntaxes, "").is_none());
+        assert!(fence_syntax(&syntaxes, "text").is_none());
+    }
+
+    #[test]
+    fn unknown_language_and_ascii_fall_back_to_plain() {
+        let plain = |md: &str, ascii| {
+            let lines = render_markdown(md, 80, RenderCtx { ascii, ..DARK });
+            code_colors(&lines)
+                .into_iter()
+                .all(|fg| fg == Some(Color::Cyan) || fg.is_none())
+        };
+        assert!(plain("```zzzlang\nfn main() {}\n```", false));
+        assert!(plain("```\nfn main() {}\n```", false));
+        assert!(plain("```rust\nfn main() {}\n```", true));
+    }
+
+    #[test]
+    fn code_colors_follow_the_tui_theme() {
+        let md = "```rust\nfn main() { let x = 1; }\n```";
+        let colors = |theme| code_colors(&render_markdown(md, 80, RenderCtx::new(false, theme)));
+        assert_eq!(colors("dark"), colors("unknown"));
+        assert_ne!(colors("dark"), colors("light"));
+        assert_ne!(colors("dark"), colors("solarized"));
+    }
+
+    #[test]
+    fn highlighted_code_is_hard_wrapped() {
+        let md = format!("```rust\nlet s = \"{}\";\n```", "a".repeat(50));
+        let lines = render_markdown(&md, 20, DARK);
+        assert!(lines.iter().all(|l| l.width() <= 20));
+        assert!(lines_to_text(&lines).contains(&"a".repeat(10)));
+    }
+
     #[test]
     fn paragraph_and_list_rendered_distinctly() {
         let md = "A paragraph.\n\n- List item 1\n- List item 2";
-        let lines = render_markdown(md, 80, false);
+        let lines = render_markdown(md, 80, DARK);
         let text = lines_to_text(&lines);
         assert!(text.contains("A paragraph"), "paragraph present: {text}");
         assert!(text.contains("List item 1"), "list item 1 present: {text}");
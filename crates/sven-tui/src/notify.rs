@@ -0,0 +1,91 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! Turn-completion notification for when the terminal is in the background.
+//!
+//! The terminal reports focus changes (crossterm `FocusGained` /
+//! `FocusLost`) once focus reporting is enabled; when a turn completes while
+//! unfocused the bell and/or an OSC 9 desktop notification is written to the
+//! terminal, as selected by `tui.notify_style`.
+
+use std::io::Write;
+
+use sven_config::{NotifyStyle, TuiConfig};
+
+/// Escape sequence announcing that a turn of the chat `title` completed, or
+/// `None` when no notification is due: notifications are off or the user is
+/// looking at the terminal.
+pub(crate) fn completion_notification(
+    cfg: &TuiConfig,
+    focused: bool,
+    title: &str,
+) -> Option<String> {
+    if !cfg.notify_on_complete || focused {
+        return None;
+    }
+    let bell = "\x07";
+    let osc9 = || {
+        // Control characters would end the sequence early.
+        let text: String = format!("sven: {title} finished")
+            .chars()
+            .filter(|c| !c.is_control())
+            .collect();
+        format!("\x1b]9;{text}\x07")
+    };
+    Some(match cfg.notify_style {
+        NotifyStyle::Bell => bell.to_string(),
+        NotifyStyle::Osc9 => osc9(),
+        NotifyStyle::Both => format!("{}{bell}", osc9()),
+    })
+}
+
+/// Write `sequence` straight to the terminal.
+pub(crate) fn emit(sequence: &str) {
+    let mut out = std::io::stdout();
+    let _ = out.write_all(sequence.as_bytes());
+    let _ = out.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cfg(enabled: bool, style: NotifyStyle) -> TuiConfig {
+        TuiConfig {
+            notify_on_complete: enabled,
+            notify_style: style,
+            ..TuiConfig::default()
+        }
+    }
+
+    #[test]
+    fn notifies_only_when_enabled_and_unfocused() {
+        let on = cfg(true, NotifyStyle::Both);
+        assert!(completion_notification(&on, false, "chat").is_some());
+        assert!(completion_notification(&on, true, "chat").is_none());
+
+        let off = cfg(false, NotifyStyle::Both);
+        assert!(completion_notification(&off, false, "chat").is_none());
+        assert!(completion_notification(&off, true, "chat").is_none());
+    }
+
+    #[test]
+    fn style_selects_bell_or_osc9() {
+        let bell = completion_notification(&cfg(true, NotifyStyle::Bell), false, "x").unwrap();
+        assert_eq!(bell, "\x07");
+
+        let osc =
+            completion_notification(&cfg(true, NotifyStyle::Osc9), false, "Fix build").unwrap();
+        assert_eq!(osc, "\x1b]9;sven: Fix build finished\x07");
+
+        let both = completion_notification(&cfg(true, NotifyStyle::Both), false, "x").unwrap();
+        assert!(both.starts_with("\x1b]9;") && both.ends_with("\x07\x07"));
+    }
+
+    #[test]
+    fn control_characters_in_the_title_are_dropped() {
+        let osc =
+            completion_notification(&cfg(true, NotifyStyle::Osc9), false, "a\x07b\x1b]c").unwrap();
+        assert_eq!(osc, "\x1b]9;sven: ab]c finished\x07");
+    }
+}
@@ -21,6 +21,8 @@ use tokio::{
 };
 use tracing::debug;
 
+use crate::chat::markdown::{fold_headers, FoldHeader};
+
 use super::{
     grid::{Grid, HlAttr},
     handler::NvimHandler,
@@ -48,6 +50,10 @@ pub struct NvimBridge {
     pub submit_notify: Arc<Notify>,
     /// Fired when Neovim sends `sven_quit` (triggered by `:q` / `:qa`).
     pub quit_notify: Arc<Notify>,
+    /// Dimensions of the last [`NvimBridge::resync`], so a size Neovim
+    /// refuses to adopt does not trigger a redraw on every frame.  Cleared
+    /// once the grid is seen in sync and on every resize.
+    last_resync: Option<(u16, u16)>,
 }
 
 impl NvimBridge {
@@ -109,6 +115,7 @@ impl NvimBridge {
             flush_notify,
             submit_notify,
             quit_notify,
+            last_resync: None,
         })
     }
 
@@ -149,6 +156,7 @@ impl NvimBridge {
     pub async fn resize(&mut self, width: u16, height: u16) -> Result<()> {
         self.width = width;
         self.height = height;
+        self.last_resync = None;
         self.neovim
             .ui_try_resize(width as i64, height as i64)
             .await
@@ -156,6 +164,45 @@ impl NvimBridge {
         Ok(())
     }
 
+    /// Discard the cached grid and have Neovim redraw the whole screen.
+    ///
+    /// Recovers from a grid that no longer matches what Neovim shows (a
+    /// missed `grid_line`, a scroll racing a resize, …).  Neovim answers with
+    /// a full `grid_clear` + `grid_line` sequence followed by `flush`.
+    pub async fn resync(&mut self) -> Result<()> {
+        debug!("Resyncing Neovim grid at {}x{}", self.width, self.height);
+        self.grid
+            .lock()
+            .await
+            .resize(self.width as usize, self.height as usize);
+        self.last_resync = Some((self.width, self.height));
+        self.neovim
+            .command("redraw!")
+            .await
+            .context("Failed to request a full redraw")?;
+        Ok(())
+    }
+
+    /// [`resync`](Self::resync) when the cached grid's dimensions differ
+    /// from the size the UI was attached or resized to.  Returns whether a
+    /// resync was requested.
+    pub async fn resync_if_desynced(&mut self) -> Result<bool> {
+        let size = (self.width, self.height);
+        let desynced = {
+            let grid = self.grid.lock().await;
+            (grid.width, grid.height) != (size.0 as usize, size.1 as usize)
+        };
+        if !desynced {
+            self.last_resync = None;
+            return Ok(false);
+        }
+        if self.last_resync == Some(size) {
+            return Ok(false);
+        }
+        self.resync().await?;
+        Ok(true)
+    }
+
     /// Apply markdown filetype, fold expression, wrap settings, and register
     /// the `:w` / `:q` custom commands for this buffer.
     pub async fn configure_buffer(&mut self) -> Result<()> {
@@ -180,39 +227,8 @@ vim.api.nvim_set_hl(0, 'Folded', { fg = '#606060', bg = 'NONE', italic = false }
             debug!("Could not set basic settings: {:?}", e);
         }
 
-        let fold_config = r#"
--- Fold expression for sven conversation structure.
-function _G.sven_fold_expr(lnum)
-  local ok, line = pcall(vim.fn.getline, lnum)
-  if not ok then return '=' end
-
-  -- Level-1 fold headers: user message separators, agent response lines,
-  -- and metadata headers for tool calls/results/thinking
-  if line:match('^%-%-%-$')              then return '>1' end
-  if line:match('^%*%*Agent:%*%*')       then return '>1' end
-  if line:match('^%*%*Agent:tool_call:') then return '>1' end
-  if line:match('^%*%*Agent:thinking%*%*') then return '>1' end
-  if line:match('^%*%*Tool:')            then return '>1' end
-  if line:match('^%*%*You:%*%*')         then return '>1' end
-  if line:match('^%*%*System:%*%*')      then return '>1' end
-  if line:match('^## ')                  then return '>1' end
-
-  -- Level-2 fold headers: visual display lines for tool calls/responses/thinking
-  if line:match('^🔧 %*%*Tool Call:')     then return '>2' end
-  if line:match('^✅ %*%*Tool Response:') then return '>2' end
-  if line:match('^💭 %*%*Thought')        then return '>2' end
-
-  -- Body lines inherit the fold level of the previous line.
-  return '='
-end
-
-vim.cmd('setlocal foldmethod=expr')
-vim.cmd('setlocal foldexpr=v:lua.sven_fold_expr(v:lnum)')
-vim.cmd('setlocal foldlevel=1')
-vim.cmd('setlocal foldenable')
-vim.cmd('setlocal foldminlines=0')
-"#;
-        if let Err(e) = self.neovim.exec_lua(fold_config, vec![]).await {
+        let fold_config = fold_expr_lua(&fold_headers());
+        if let Err(e) = self.neovim.exec_lua(&fold_config, vec![]).await {
             debug!("Could not configure folding: {:?}", e);
             let _ = self
                 .neovim
@@ -340,10 +356,96 @@ end, { buffer = buf, silent = true, desc = 'Toggle fold under cursor' })
     }
 }
 
+/// Lua defining `sven_fold_expr` over `headers` and enabling it as the
+/// buffer's fold expression.
+pub(crate) fn fold_expr_lua(headers: &[FoldHeader]) -> String {
+    let table: String = headers
+        .iter()
+        .map(|h| {
+            format!(
+                "  {{ '>{}', {}, {} }},\n",
+                h.level,
+                lua_string(&h.prefix),
+                h.whole_line
+            )
+        })
+        .collect();
+    format!(
+        r#"
+-- Fold expression for sven conversation structure: each header line opens a
+-- fold of its level; entries are {{ level, prefix, whole_line }}.
+local headers = {{
+{table}}}
+
+function _G.sven_fold_expr(lnum)
+  local ok, line = pcall(vim.fn.getline, lnum)
+  if not ok then return '=' end
+  for _, h in ipairs(headers) do
+    if (h[3] and line == h[2]) or (not h[3] and vim.startswith(line, h[2])) then
+      return h[1]
+    end
+  end
+  -- Body lines inherit the fold level of the previous line.
+  return '='
+end
+
+vim.cmd('setlocal foldmethod=expr')
+vim.cmd('setlocal foldexpr=v:lua.sven_fold_expr(v:lnum)')
+vim.cmd('setlocal foldlevel=1')
+vim.cmd('setlocal foldenable')
+vim.cmd('setlocal foldminlines=0')
+"#
+    )
+}
+
+/// `s` as a single-quoted Lua string literal.
+fn lua_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('\'');
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\'' => out.push_str("\\'"),
+            '\n' => out.push_str("\\n"),
+            c => out.push(c),
+        }
+    }
+    out.push('\'');
+    out
+}
+
 // ── Integration tests ─────────────────────────────────────────────────────────
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    #[test]
+    fn fold_lua_contains_the_configured_headers() {
+        let lua = fold_expr_lua(&fold_headers());
+        for header in fold_headers() {
+            let entry = format!(
+                "{{ '>{}', {}, {} }}",
+                header.level,
+                lua_string(&header.prefix),
+                header.whole_line
+            );
+            assert!(lua.contains(&entry), "missing {entry} in:\n{lua}");
+        }
+        assert!(lua.contains("{ '>2', '⚙ **', false }"));
+        assert!(lua.contains("{ '>1', '---', true }"));
+    }
+
+    #[test]
+    fn fold_lua_escapes_prefixes() {
+        let lua = fold_expr_lua(&[FoldHeader {
+            level: 3,
+            prefix: r"it's \ odd".into(),
+            whole_line: false,
+        }]);
+        assert!(lua.contains(r"{ '>3', 'it\'s \\ odd', false }"), "{lua}");
+    }
+
     mod nvim_integration {
         use tokio::time::{sleep, Duration};
 
@@ -564,6 +666,63 @@ mod tests {
             assert_eq!(bridge.height, 40);
         }
 
+        #[tokio::test]
+        async fn resync_repopulates_a_corrupted_grid() {
+            if !nvim_available() {
+                return;
+            }
+            let mut bridge = spawn_bridge().await;
+            bridge
+                .set_buffer_content("alpha line\nbeta line")
+                .await
+                .unwrap();
+            sleep(Duration::from_millis(200)).await;
+
+            // Simulate missed redraw events: the cache loses its content and
+            // even its dimensions.
+            bridge.grid.lock().await.resize(10, 3);
+            assert!(
+                !lines_text(&bridge.render_to_lines(0, bridge.height).await).contains("alpha line")
+            );
+
+            assert!(bridge.resync_if_desynced().await.unwrap());
+            sleep(Duration::from_millis(200)).await;
+
+            {
+                let grid = bridge.grid.lock().await;
+                assert_eq!((grid.width, grid.height), (80, 24));
+            }
+            let text = lines_text(&bridge.render_to_lines(0, bridge.height).await);
+            let content = bridge.get_buffer_content().await.unwrap();
+            for line in content.lines() {
+                assert!(
+                    text.contains(line),
+                    "{line:?} missing after resync: {text:?}"
+                );
+            }
+            assert!(
+                !bridge.resync_if_desynced().await.unwrap(),
+                "an in-sync grid must not be resynced again"
+            );
+        }
+
+        #[tokio::test]
+        async fn each_desync_at_the_same_size_is_resynced() {
+            if !nvim_available() {
+                return;
+            }
+            let mut bridge = spawn_bridge().await;
+            for round in 0..2 {
+                bridge.grid.lock().await.resize(10, 3);
+                assert!(
+                    bridge.resync_if_desynced().await.unwrap(),
+                    "desync {round} must be resynced"
+                );
+                sleep(Duration::from_millis(200)).await;
+                assert!(!bridge.resync_if_desynced().await.unwrap());
+            }
+        }
+
         #[tokio::test]
         async fn render_to_lines_reflects_buffer_content() {
             if !nvim_available() {
@@ -1549,6 +1708,82 @@ mod tests {
                 "G after content update must reach NEW last line; cursor={line_after_second_g}, total={second_last}");
         }
 
+        #[tokio::test]
+        async fn rendered_conversation_folds_by_role_and_heading() {
+            use std::collections::HashMap;
+
+            use sven_model::{FunctionCall, Message, MessageContent, Role};
+
+            use crate::chat::{markdown::format_conversation, segment::ChatSegment};
+
+            if !nvim_available() {
+                return;
+            }
+            let call = ChatSegment::Message(Message {
+                role: Role::Assistant,
+                content: MessageContent::ToolCall {
+                    tool_call_id: "c1".into(),
+                    function: FunctionCall {
+                        name: "read_file".into(),
+                        arguments: r#"{"path":"a.rs"}"#.into(),
+                    },
+                },
+            });
+            let result = ChatSegment::Message(Message {
+                role: Role::Tool,
+                content: MessageContent::ToolResult {
+                    tool_call_id: "c1".into(),
+                    content: "fn main() {}".into(),
+                },
+            });
+            let segments = vec![
+                ChatSegment::Message(Message::user("read a.rs")),
+                ChatSegment::Thinking {
+                    content: "look at the file".into(),
+                },
+                call,
+                result,
+                ChatSegment::Message(Message::assistant("It is empty.")),
+            ];
+            let tool_names = HashMap::from([("c1".to_string(), "read_file".to_string())]);
+            let content = format_conversation(&segments, "", &tool_names);
+
+            let mut bridge = spawn_configured_bridge().await;
+            bridge.set_buffer_content(&content).await.unwrap();
+            sleep(Duration::from_millis(200)).await;
+
+            let mut expected = Vec::new();
+            for (i, line) in content.lines().enumerate() {
+                let level = if line == "---"
+                    || line.starts_with("**You:**")
+                    || line.starts_with("**Agent:")
+                    || line.starts_with("**Tool:")
+                {
+                    1
+                } else if line.starts_with("⚙ **")
+                    || line.starts_with("✓ **")
+                    || line.starts_with("◆ **Thought**")
+                {
+                    2
+                } else {
+                    continue;
+                };
+                expected.push((i + 1, line.to_string(), level));
+            }
+            assert_eq!(
+                expected.iter().filter(|(_, _, l)| *l == 2).count(),
+                3,
+                "sample must contain tool call, result and thought headings:\n{content}"
+            );
+            for (lnum, line, level) in expected {
+                let got = bridge
+                    .eval_vim(&format!("foldlevel({lnum})"))
+                    .await
+                    .unwrap();
+                assert_eq!(got.as_u64(), Some(level), "foldlevel of {line:?}");
+            }
+        }
+
         #[tokio::test]
         async fn flush_notify_fires_after_input_is_processed() {
             if !nvim_available() {
@@ -33,6 +33,8 @@ pub enum ConfirmedAction {
     /// Delete a chat session (and its YAML file). When it is the active session,
     /// the app will switch to another session first.
     DeleteChat(sven_input::SessionId),
+    /// Erase the conversation of the active session (`/clear`).
+    ClearChat,
 }
 
 /// A generic centred modal dialog with a title, a message, and two buttons.
@@ -27,6 +27,10 @@ pub enum PagerAction {
     SearchNext,
     /// Navigate to previous search match.
     SearchPrev,
+    /// Toggle case-sensitive search and recompute the matches.
+    ToggleSearchCase,
+    /// Toggle whole-word search and recompute the matches.
+    ToggleSearchWholeWord,
     /// Key was handled internally; nothing else needed.
     Handled,
 }
@@ -36,7 +40,8 @@ pub enum PagerAction {
 /// The pager displays the current chat lines in a full-screen buffer.
 /// `j`/`k`, `Ctrl+u`/`d`, `Ctrl+b`/`f`, `g`/`G`, page-keys, `Home`/`End`
 /// all work as in vim/less.  `/`, `n`, `N` are forwarded to the app's
-/// search machinery.  `q`/`Esc` closes the overlay.
+/// search machinery; `c` and `w` toggle case-sensitive and whole-word
+/// matching.  `q`/`Esc` closes the overlay.
 pub struct PagerOverlay {
     lines: StyledLines,
     pub scroll_offset: usize,
@@ -167,6 +172,8 @@ impl PagerOverlay {
             KeyCode::Char('?') => return PagerAction::OpenSearchBackward,
             KeyCode::Char('n') => return PagerAction::SearchNext,
             KeyCode::Char('N') => return PagerAction::SearchPrev,
+            KeyCode::Char('c') if !ctrl => return PagerAction::ToggleSearchCase,
+            KeyCode::Char('w') if !ctrl => return PagerAction::ToggleSearchWholeWord,
 
             _ => {}
         }
@@ -286,6 +293,8 @@ impl PagerOverlay {
             Span::styled(":search  ", d),
             Span::styled("n/N", k),
             Span::styled(":match  ", d),
+            Span::styled("c/w", k),
+            Span::styled(":case/word  ", d),
             Span::styled("q/Esc", k),
             Span::styled(":close", d),
         ]);
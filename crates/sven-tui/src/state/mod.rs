@@ -28,6 +28,9 @@ pub struct SessionState {
     pub staged_mode: Option<AgentMode>,
     /// Currently active agent mode.
     pub mode: AgentMode,
+    /// Set once the user picks a model (`--model`, `/model`); from then on
+    /// `agent.routing` no longer switches the model with the mode.
+    pub model_pinned: bool,
 }
 
 impl SessionState {
@@ -39,9 +42,15 @@ impl SessionState {
             staged_model: None,
             staged_mode: None,
             mode,
+            model_pinned: false,
         }
     }
 
+    /// Keep the current model regardless of `agent.routing`.
+    pub fn pin_model(&mut self) {
+        self.model_pinned = true;
+    }
+
     /// Switch the active model.
     ///
     /// Updates `model_display` and `model_cfg` immediately so the status bar
@@ -49,11 +58,29 @@ impl SessionState {
     /// also stored in `staged_model` so `consume_staged()` can forward it to
     /// the background agent task with the next submitted message.
     pub fn stage_model(&mut self, cfg: ModelConfig) {
+        self.model_pinned = true;
+        self.stage_unpinned_model(cfg);
+    }
+
+    fn stage_unpinned_model(&mut self, cfg: ModelConfig) {
         self.model_display = format!("{}/{}", cfg.provider, cfg.name);
         self.model_cfg = cfg.clone();
         self.staged_model = Some(cfg);
     }
 
+    /// Switch to `routed`, the model `agent.routing` assigns to the current
+    /// mode, unless the model is pinned.  Staged like [`Self::stage_model`]
+    /// but does not pin, so the next mode change can route again.
+    pub fn route_model(&mut self, routed: Option<ModelConfig>) {
+        let Some(cfg) = routed else { return };
+        if self.model_pinned
+            || (cfg.provider == self.model_cfg.provider && cfg.name == self.model_cfg.name)
+        {
+            return;
+        }
+        self.stage_unpinned_model(cfg);
+    }
+
     /// Switch the active mode.
     ///
     /// Updates `mode` immediately for display and stores in `staged_mode` so
@@ -68,6 +95,7 @@ impl SessionState {
     /// Used by the `SubmitBufferToAgent` path where a `/model` command inside
     /// the Neovim buffer takes effect right away.
     pub fn apply_model(&mut self, cfg: ModelConfig) {
+        self.model_pinned = true;
         self.model_display = format!("{}/{}", cfg.provider, cfg.name);
         self.model_cfg = cfg;
         self.staged_model = None;
@@ -79,13 +107,15 @@ impl SessionState {
         self.staged_mode = None;
     }
 
-    /// Cycle the mode: Research → Plan → Agent → Research.
-    pub fn cycle_mode(&mut self) {
-        self.mode = match self.mode {
-            AgentMode::Research => AgentMode::Plan,
-            AgentMode::Plan => AgentMode::Agent,
-            AgentMode::Agent => AgentMode::Research,
-        };
+    /// Cycle the mode through `modes` (see
+    /// [`sven_config::AgentConfig::all_modes`]): Research → Plan → Agent →
+    /// custom modes → Research.
+    pub fn cycle_mode(&mut self, modes: &[AgentMode]) {
+        let next = modes
+            .iter()
+            .position(|&m| m == self.mode)
+            .map_or(0, |i| (i + 1) % modes.len());
+        self.mode = modes[next];
     }
 
     /// Consume any staged overrides and return them for embedding into the
@@ -184,11 +214,45 @@ mod tests {
     #[test]
     fn cycle_mode_wraps_correctly() {
         let mut s = SessionState::new(mock_cfg("openai", "gpt-4o"), AgentMode::Research);
-        s.cycle_mode();
+        s.cycle_mode(&AgentMode::BUILTIN);
         assert_eq!(s.mode, AgentMode::Plan);
-        s.cycle_mode();
+        s.cycle_mode(&AgentMode::BUILTIN);
         assert_eq!(s.mode, AgentMode::Agent);
-        s.cycle_mode();
+        s.cycle_mode(&AgentMode::BUILTIN);
         assert_eq!(s.mode, AgentMode::Research);
     }
+
+    #[test]
+    fn mode_change_switches_to_the_routed_model() {
+        let mut s = SessionState::new(mock_cfg("openai", "gpt-4o"), AgentMode::Agent);
+        s.stage_mode(AgentMode::Research);
+        s.route_model(Some(mock_cfg("openai", "gpt-4o-mini")));
+        assert_eq!(s.model_display, "openai/gpt-4o-mini");
+        let (model, mode) = s.consume_staged();
+        assert_eq!(model.unwrap().name, "gpt-4o-mini");
+        assert_eq!(mode, Some(AgentMode::Research));
+        assert!(!s.model_pinned, "routing must not pin the model");
+
+        // Routing again to the same model stages nothing.
+        s.route_model(Some(mock_cfg("openai", "gpt-4o-mini")));
+        assert!(s.staged_model.is_none());
+        // A mode without a route keeps the current model.
+        s.route_model(None);
+        assert_eq!(s.model_display, "openai/gpt-4o-mini");
+    }
+
+    #[test]
+    fn explicit_model_wins_over_routing() {
+        let mut s = SessionState::new(mock_cfg("openai", "gpt-4o"), AgentMode::Agent);
+        s.stage_model(mock_cfg("anthropic", "claude-opus-4-6"));
+        s.stage_mode(AgentMode::Research);
+        s.route_model(Some(mock_cfg("openai", "gpt-4o-mini")));
+        assert_eq!(s.model_display, "anthropic/claude-opus-4-6");
+        assert_eq!(s.staged_model.unwrap().provider, "anthropic");
+
+        let mut s = SessionState::new(mock_cfg("openai", "gpt-4o"), AgentMode::Agent);
+        s.pin_model();
+        s.route_model(Some(mock_cfg("openai", "gpt-4o-mini")));
+        assert_eq!(s.model_display, "openai/gpt-4o");
+    }
 }
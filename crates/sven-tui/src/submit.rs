@@ -30,8 +30,9 @@
 //!   If the agent is idle, `App::send_resubmit_to_agent()` is called directly.
 //!
 //! **Step 5** — `App::send_resubmit_to_agent()`
-//!   Sends `AgentRequest::Resubmit { messages, new_user_content, model_override,
-//!   mode_override }` to the background agent task via `agent.tx`.
+//!   Sends `AgentRequest::Resubmit { messages, new_user_content, images,
+//!   model_override, mode_override }` to the background agent task via
+//!   `agent.tx`.  `images` holds the data URLs of images pasted with Ctrl+v.
 //!
 //! **Step 6** — `agent_task` loop
 //!   Receives `AgentRequest::Resubmit`.  Resolves `model_override` string to a
@@ -43,12 +44,12 @@
 
 use std::sync::Arc;
 
-use sven_model::Message;
+use sven_model::{Message, MessageContent, Role};
 
 use crate::{
     agent::AgentRequest,
     app::{App, FocusPane, ModelDirective, QueuedMessage},
-    chat::segment::{messages_for_resubmit, ChatSegment},
+    chat::segment::{messages_for_resubmit, user_message, ChatSegment},
     commands::{dispatch_command, CommandContext, ImmediateAction},
 };
 
@@ -56,7 +57,8 @@ impl App {
     // ── Submit path ───────────────────────────────────────────────────────────
 
     /// Process user input text: dispatch slash commands or send as a message.
-    pub(crate) async fn submit_user_input(&mut self, text: &str) -> bool {
+    /// `images` are data URLs of pasted images sent along with the message.
+    pub(crate) async fn submit_user_input(&mut self, text: &str, images: Vec<String>) -> bool {
         if text.starts_with('/') {
             let ctx = CommandContext {
                 config: self.config.clone(),
@@ -94,11 +96,34 @@ impl App {
                         return false;
                     }
 
-                    if matches!(result.immediate_action, Some(ImmediateAction::ClearChat)) {
-                        self.chat.segments.clear();
-                        self.chat.tool_args.clear();
-                        self.save_history_async();
-                        self.rerender_chat().await;
+                    if let Some(ImmediateAction::ClearChat { confirm }) = result.immediate_action {
+                        if confirm && !self.chat.segments.is_empty() {
+                            use crate::overlay::confirm::{ConfirmModal, ConfirmedAction};
+                            let n = self.chat.segments.len();
+                            self.ui.confirm_modal = Some(
+                                ConfirmModal::new(
+                                    "Clear chat",
+                                    format!(
+                                        "Erase all {n} entries of this conversation? \
+                                         (/clear! skips this prompt)"
+                                    ),
+                                    ConfirmedAction::ClearChat,
+                                )
+                                .labels(" Clear ", " Cancel "),
+                            );
+                        } else {
+                            self.clear_chat().await;
+                        }
+                        return false;
+                    }
+
+                    if matches!(result.immediate_action, Some(ImmediateAction::Retry)) {
+                        if let Some(resolved) =
+                            result.resolve_model(&self.config, &self.session.model_cfg)
+                        {
+                            self.session.stage_model(resolved);
+                        }
+                        self.retry_last_user_message().await;
                         return false;
                     }
 
@@ -110,6 +135,31 @@ impl App {
                         return false;
                     }
 
+                    if let Some(ImmediateAction::Copy { target }) = result.immediate_action {
+                        self.copy_command(target);
+                        return false;
+                    }
+
+                    if let Some(ImmediateAction::ShowUsage { ref usage }) = result.immediate_action
+                    {
+                        self.ui
+                            .push_toast(crate::app::ui_state::Toast::warning(usage.clone()));
+                        return false;
+                    }
+
+                    if let Some(ImmediateAction::SetTitle { ref title }) = result.immediate_action {
+                        let title = title
+                            .clone()
+                            .or_else(|| self.first_user_text().map(|t| sven_input::make_title(&t)))
+                            .unwrap_or_else(|| "New chat".to_string());
+                        self.ui
+                            .push_toast(crate::app::ui_state::Toast::info(format!(
+                                "Title set to \"{title}\""
+                            )));
+                        self.set_chat_title(title);
+                        return false;
+                    }
+
                     if matches!(
                         result.immediate_action,
                         Some(ImmediateAction::RefreshSkills)
@@ -201,7 +251,7 @@ impl App {
                         result.immediate_action
                     {
                         use crate::ui::{InspectorKind, InspectorOverlay};
-                        let ascii = self.ascii();
+                        let ctx = self.markdown_ctx();
                         let skills = self.shared_skills.get();
                         let agents = self.shared_agents.get();
                         let buffer_store = std::sync::Arc::clone(&self.buffer_store);
@@ -209,22 +259,22 @@ impl App {
                         let is_node = self.is_node_proxy;
                         let inspector = match kind {
                             InspectorKind::Skills => {
-                                InspectorOverlay::for_skills(&skills, is_node, ascii)
+                                InspectorOverlay::for_skills(&skills, is_node, ctx)
                             }
                             InspectorKind::Subagents => {
-                                InspectorOverlay::for_subagents(&agents, is_node, ascii)
+                                InspectorOverlay::for_subagents(&agents, is_node, ctx)
                             }
                             InspectorKind::Peers => InspectorOverlay::for_peers(
                                 &agents,
                                 Some(buffer_store),
                                 is_node,
-                                ascii,
+                                ctx,
                             ),
                             InspectorKind::Context => InspectorOverlay::for_context(
                                 project_root.as_deref(),
                                 Some(buffer_store),
                                 is_node,
-                                ascii,
+                                ctx,
                             ),
                             InspectorKind::Tools => {
                                 let tools = if is_node {
@@ -237,7 +287,7 @@ impl App {
                                 } else {
                                     self.shared_tools.get().to_vec()
                                 };
-                                InspectorOverlay::for_tools(&tools, is_node, ascii)
+                                InspectorOverlay::for_tools(&tools, is_node, ctx)
                             }
                             InspectorKind::Mcp => {
                                 let statuses = if let Some(ref mgr) = self.mcp_manager {
@@ -245,7 +295,7 @@ impl App {
                                 } else {
                                     vec![]
                                 };
-                                InspectorOverlay::for_mcp(&statuses, ascii)
+                                InspectorOverlay::for_mcp(&statuses, ctx)
                             }
                         };
                         self.ui.inspector = Some(inspector);
@@ -288,21 +338,22 @@ impl App {
                     // In node-proxy mode the node owns model/mode selection;
                     // silently ignore /model and /mode commands.
                     if !self.is_node_proxy {
-                        if let Some(model_str) = result.model_override {
-                            let resolved =
-                                sven_model::resolve_model_from_config(&self.config, &model_str);
+                        if let Some(resolved) =
+                            result.resolve_model(&self.config, &self.session.model_cfg)
+                        {
                             self.session.stage_model(resolved);
                         }
 
                         if let Some(mode) = result.mode_override {
                             self.session.stage_mode(mode);
+                            self.route_model_for_mode();
                         }
                     }
 
                     match result.message_to_send {
                         None => return false,
                         Some(msg) => {
-                            return self.enqueue_or_send_text(&msg).await;
+                            return self.enqueue_or_send_text(&msg, images).await;
                         }
                     }
                 }
@@ -310,15 +361,17 @@ impl App {
             }
         }
 
-        self.enqueue_or_send_text(text).await
+        self.enqueue_or_send_text(text, images).await
     }
 
-    /// Consume staged overrides and either enqueue or send `text` to the agent.
-    pub(crate) async fn enqueue_or_send_text(&mut self, text: &str) -> bool {
+    /// Consume staged overrides and either enqueue or send `text` (with the
+    /// data URLs `images`) to the agent.
+    pub(crate) async fn enqueue_or_send_text(&mut self, text: &str, images: Vec<String>) -> bool {
         self.chat.auto_scroll = true;
         let (staged_model, staged_mode) = self.session.consume_staged();
         let qm = QueuedMessage {
             content: text.to_string(),
+            images,
             model_transition: staged_model.map(|c| ModelDirective::SwitchTo(Box::new(c))),
             mode_transition: staged_mode,
         };
@@ -330,7 +383,7 @@ impl App {
             let history = messages_for_resubmit(&self.chat.segments);
             self.chat
                 .segments
-                .push(ChatSegment::Message(Message::user(text)));
+                .push(ChatSegment::Message(user_message(text, &qm.images)));
             self.save_history_async();
             self.rerender_chat().await;
             self.scroll_to_bottom();
@@ -351,12 +404,13 @@ impl App {
                 return true;
             }
             if !self.is_node_proxy {
-                if let Some(model_str) = result.model_override {
-                    let resolved = sven_model::resolve_model_from_config(&self.config, &model_str);
+                if let Some(resolved) = result.resolve_model(&self.config, &self.session.model_cfg)
+                {
                     self.session.apply_model(resolved);
                 }
                 if let Some(mode) = result.mode_override {
                     self.session.apply_mode(mode);
+                    self.route_model_for_mode();
                 }
             }
         }
@@ -398,6 +452,57 @@ impl App {
         }
     }
 
+    /// Replace the user message at segment `i` with `content`, drop every
+    /// later segment and resubmit the truncated conversation, applying any
+    /// staged model / mode.
+    pub(crate) async fn resubmit_user_message(&mut self, i: usize, content: String) {
+        let (staged_model, staged_mode) = self.session.consume_staged();
+        let qm = QueuedMessage {
+            content: content.clone(),
+            images: Vec::new(),
+            model_transition: staged_model.map(|c| ModelDirective::SwitchTo(Box::new(c))),
+            mode_transition: staged_mode,
+        };
+        self.chat.segments.truncate(i);
+        self.chat
+            .segments
+            .push(ChatSegment::Message(Message::user(&content)));
+        let messages = messages_for_resubmit(&self.chat.segments);
+        self.rerender_chat().await;
+        self.scroll_to_bottom();
+        self.send_resubmit_to_agent(messages, qm).await;
+    }
+
+    /// `/retry`: resend the most recent text message of the user.
+    async fn retry_last_user_message(&mut self) {
+        if self.agent.busy {
+            self.ui.push_toast(crate::app::ui_state::Toast::info(
+                "Wait for the current turn to finish (or /abort) before /retry",
+            ));
+            return;
+        }
+        let last_user =
+            self.chat
+                .segments
+                .iter()
+                .enumerate()
+                .rev()
+                .find_map(|(i, seg)| match seg {
+                    ChatSegment::Message(Message {
+                        role: Role::User,
+                        content: MessageContent::Text(text),
+                        ..
+                    }) => Some((i, text.clone())),
+                    _ => None,
+                });
+        match last_user {
+            Some((i, content)) => self.resubmit_user_message(i, content).await,
+            None => self.ui.push_toast(crate::app::ui_state::Toast::info(
+                "Nothing to retry: no previous message in this chat",
+            )),
+        }
+    }
+
     pub(crate) async fn send_resubmit_to_agent(
         &mut self,
         messages: Vec<Message>,
@@ -419,6 +524,7 @@ impl App {
                 .send(AgentRequest::Resubmit {
                     messages,
                     new_user_content: qm.content.clone(),
+                    images: qm.images,
                     model_override,
                     mode_override,
                 })
@@ -449,13 +555,21 @@ impl App {
                 if self.queue.messages.is_empty() && self.ui.focus == FocusPane::Queue {
                     self.ui.focus = FocusPane::Input;
                 }
-                self.chat
-                    .segments
-                    .push(ChatSegment::Message(Message::user(&next.content)));
+                // `Submit` carries text only; a message with images is sent
+                // with the history like an idle submit.
+                let history =
+                    (!next.images.is_empty()).then(|| messages_for_resubmit(&self.chat.segments));
+                self.chat.segments.push(ChatSegment::Message(user_message(
+                    &next.content,
+                    &next.images,
+                )));
                 self.rerender_chat().await;
                 self.chat.auto_scroll = true;
                 self.scroll_to_bottom();
-                self.send_to_agent(next).await;
+                match history {
+                    Some(history) => self.send_resubmit_to_agent(history, next).await,
+                    None => self.send_to_agent(next).await,
+                }
             }
         }
     }
@@ -637,6 +751,102 @@ mod submit_integration_tests {
         assert_eq!(resubmit_mode(&req), Some(AgentMode::Research));
     }
 
+    #[tokio::test]
+    async fn clear_command_asks_for_confirmation_first() {
+        let (mut app, _rx) = App::for_testing();
+        app.inject_chat_user_message("keep me");
+
+        app.inject_input("/clear");
+        app.dispatch_action(Action::Submit).await;
+        assert!(app.ui.confirm_modal.is_some(), "/clear must ask first");
+        assert_eq!(app.chat.segments.len(), 1);
+
+        let enter = crossterm::event::KeyEvent::from(crossterm::event::KeyCode::Enter);
+        app.handle_confirm_modal_key(enter).await;
+        assert!(app.ui.confirm_modal.is_none());
+        assert!(app.chat.segments.is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancelled_clear_keeps_the_conversation() {
+        let (mut app, _rx) = App::for_testing();
+        app.inject_chat_user_message("keep me");
+
+        app.inject_input("/clear");
+        app.dispatch_action(Action::Submit).await;
+        let esc = crossterm::event::KeyEvent::from(crossterm::event::KeyCode::Esc);
+        app.handle_confirm_modal_key(esc).await;
+        assert!(app.ui.confirm_modal.is_none());
+        assert_eq!(app.chat.segments.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn force_clear_command_clears_immediately() {
+        let (mut app, _rx) = App::for_testing();
+        app.inject_chat_user_message("gone");
+
+        app.inject_input("/clear!");
+        app.dispatch_action(Action::Submit).await;
+        assert!(app.ui.confirm_modal.is_none());
+        assert!(app.chat.segments.is_empty());
+    }
+
+    #[tokio::test]
+    async fn retry_resends_last_user_message_without_the_old_reply() {
+        let (mut app, mut rx) = App::for_testing();
+        app.inject_chat_user_message("first question");
+        app.chat
+            .segments
+            .push(crate::chat::segment::ChatSegment::Message(
+                sven_model::Message::assistant("first answer"),
+            ));
+        app.inject_chat_user_message("second question");
+        app.chat
+            .segments
+            .push(crate::chat::segment::ChatSegment::Message(
+                sven_model::Message::assistant("poor answer"),
+            ));
+
+        app.inject_input("/retry");
+        app.dispatch_action(Action::Submit).await;
+
+        let req = recv_resubmit_for_test(&mut rx);
+        assert_eq!(resubmit_content(&req), "second question");
+        let AgentRequest::Resubmit { messages, .. } = &req else {
+            unreachable!()
+        };
+        assert!(
+            messages.iter().all(|m| m.as_text() != Some("poor answer")),
+            "the retried reply must be dropped: {messages:?}"
+        );
+        assert_eq!(app.chat.segments.len(), 3);
+        assert!(app.is_agent_busy());
+    }
+
+    #[tokio::test]
+    async fn retry_with_model_stages_it_for_the_retried_turn() {
+        let (mut app, mut rx) = App::for_testing();
+        app.inject_chat_user_message("question");
+
+        app.inject_input("/retry openai/gpt-4o");
+        app.dispatch_action(Action::Submit).await;
+
+        let req = recv_resubmit_for_test(&mut rx);
+        assert_eq!(resubmit_content(&req), "question");
+        assert_eq!(resubmit_model(&req).as_deref(), Some("openai/gpt-4o"));
+    }
+
+    #[tokio::test]
+    async fn retry_without_history_sends_nothing() {
+        let (mut app, mut rx) = App::for_testing();
+        app.inject_input("/retry");
+        app.dispatch_action(Action::Submit).await;
+
+        assert!(rx.try_recv().is_err(), "nothing to retry");
+        assert!(!app.is_agent_busy());
+        assert!(!app.ui.toasts.is_empty(), "the user is told why");
+    }
+
     #[tokio::test]
     async fn quit_command_returns_true() {
         let (mut app, _rx) = App::for_testing();
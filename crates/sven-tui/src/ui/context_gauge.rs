@@ -0,0 +1,92 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! Inline context-window gauge for the status bar: `[█████▍  ] 68%`.
+
+use ratatui::text::Span;
+
+use super::theme::ctx_style;
+
+/// Number of character cells inside the brackets.
+const GAUGE_CELLS: usize = 8;
+
+/// Partial blocks for one to seven eighths of a cell.
+const EIGHTHS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// The bar of a gauge `pct` percent full, e.g. `[████▌░░░]`.
+///
+/// The unicode bar resolves eighths of a cell; the ascii bar (`[####....]`)
+/// only whole cells.  Values above 100 show a full bar.
+pub(crate) fn gauge_bar(pct: u8, ascii: bool) -> String {
+    let pct = usize::from(pct.min(100));
+    let (full, partial, fill, empty) = if ascii {
+        (pct * GAUGE_CELLS / 100, None, '#', '.')
+    } else {
+        let eighths = pct * GAUGE_CELLS * 8 / 100;
+        let partial = match eighths % 8 {
+            0 => None,
+            rem => Some(EIGHTHS[rem - 1]),
+        };
+        (eighths / 8, partial, '█', '░')
+    };
+    let mut bar = String::with_capacity(GAUGE_CELLS * 3 + 2);
+    bar.push('[');
+    bar.extend(std::iter::repeat_n(fill, full));
+    bar.extend(partial);
+    let used = full + usize::from(partial.is_some());
+    bar.extend(std::iter::repeat_n(empty, GAUGE_CELLS - used));
+    bar.push(']');
+    bar
+}
+
+/// Bar and percentage spans of the context gauge, colored green, yellow or
+/// red by how full the context window is.
+pub(crate) fn draw_context_gauge(pct: u8, ascii: bool) -> [Span<'static>; 2] {
+    let style = ctx_style(pct);
+    [
+        Span::styled(gauge_bar(pct, ascii), style),
+        Span::styled(format!(" {pct}%"), style),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    #[test]
+    fn unicode_bar_at_several_percentages() {
+        assert_eq!(gauge_bar(0, false), "[░░░░░░░░]");
+        assert_eq!(gauge_bar(50, false), "[████░░░░]");
+        assert_eq!(gauge_bar(55, false), "[████▍░░░]");
+        assert_eq!(gauge_bar(99, false), "[███████▉]");
+        assert_eq!(gauge_bar(100, false), "[████████]");
+        assert_eq!(gauge_bar(180, false), "[████████]");
+    }
+
+    #[test]
+    fn ascii_bar_uses_whole_cells() {
+        assert_eq!(gauge_bar(0, true), "[........]");
+        assert_eq!(gauge_bar(55, true), "[####....]");
+        assert_eq!(gauge_bar(100, true), "[########]");
+        assert!(gauge_bar(73, true).is_ascii());
+    }
+
+    #[test]
+    fn bar_width_is_constant() {
+        for pct in 0..=100 {
+            assert_eq!(gauge_bar(pct, false).chars().count(), GAUGE_CELLS + 2);
+        }
+    }
+
+    #[test]
+    fn color_follows_thresholds() {
+        let fg = |pct| draw_context_gauge(pct, false)[0].style.fg;
+        assert_eq!(fg(10), Some(Color::Rgb(80, 180, 100)));
+        assert_eq!(fg(75), Some(Color::Rgb(220, 180, 60)));
+        assert_eq!(fg(95), Some(Color::Rgb(220, 80, 80)));
+        let [bar, pct] = draw_context_gauge(42, true);
+        assert_eq!(bar.content, "[###.....]");
+        assert_eq!(pct.content, " 42%");
+    }
+}
@@ -39,6 +39,7 @@ const BINDINGS: &[(&str, &str, bool)] = &[
     ("── Input pane ──", "", true),
     ("Enter", "Send message", false),
     ("Alt+Enter", "New line", false),
+    ("^v", "Attach clipboard image", false),
     ("^c", "Interrupt agent", false),
     ("^k / ^u", "Delete to end/start", false),
     ("^Up / ^Dn", "History older/newer", false),
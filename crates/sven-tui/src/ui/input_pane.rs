@@ -75,7 +75,7 @@ impl Widget for InputPane<'_> {
 
         // Character count shown next to the hint.
         let char_count = self.content.chars().count();
-        let token_est = char_count / 4;
+        let token_est = sven_core::estimate_tokens(self.content);
         let counter_str = if char_count > 0 {
             if self.ascii {
                 format!("  {char_count}c ~{token_est}t  ")
@@ -29,7 +29,7 @@ use sven_runtime::{
 };
 use sven_tools::{format_tools_list, OutputBufferStore, ToolSchema};
 
-use crate::markdown::render_markdown;
+use crate::markdown::{render_markdown, RenderCtx};
 use crate::pager::PagerOverlay;
 use crate::ui::width_utils::truncate_to_width_exact;
 
@@ -55,7 +55,7 @@ impl InspectorOverlay {
     ///
     /// `is_node_proxy` adds a note that skills are locally discovered and may
     /// differ from the connected node's skill set.
-    pub fn for_skills(skills: &[SkillInfo], is_node_proxy: bool, ascii: bool) -> Self {
+    pub fn for_skills(skills: &[SkillInfo], is_node_proxy: bool, ctx: RenderCtx) -> Self {
         let mut md = format_skills_tree(skills);
         if is_node_proxy {
             md = format!(
@@ -63,7 +63,7 @@ impl InspectorOverlay {
                  filesystem and may differ from the node's skill set.\n\n{md}"
             );
         }
-        let lines = render_markdown(&md, 0, ascii);
+        let lines = render_markdown(&md, 0, ctx);
         Self {
             pager: PagerOverlay::with_title(lines, InspectorKind::Skills.title()),
         }
@@ -72,7 +72,7 @@ impl InspectorOverlay {
     /// Build the subagents inspector from a slice of discovered agents.
     ///
     /// `is_node_proxy` adds a note that subagents are locally discovered.
-    pub fn for_subagents(agents: &[AgentInfo], is_node_proxy: bool, ascii: bool) -> Self {
+    pub fn for_subagents(agents: &[AgentInfo], is_node_proxy: bool, ctx: RenderCtx) -> Self {
         let mut md = format_agents_list(agents);
         if is_node_proxy {
             md = format!(
@@ -80,7 +80,7 @@ impl InspectorOverlay {
                  filesystem and may differ from the node's subagent set.\n\n{md}"
             );
         }
-        let lines = render_markdown(&md, 0, ascii);
+        let lines = render_markdown(&md, 0, ctx);
         Self {
             pager: PagerOverlay::with_title(lines, InspectorKind::Subagents.title()),
         }
@@ -96,10 +96,10 @@ impl InspectorOverlay {
         configured_agents: &[AgentInfo],
         buffer_store: Option<Arc<Mutex<OutputBufferStore>>>,
         is_node_proxy: bool,
-        ascii: bool,
+        ctx: RenderCtx,
     ) -> Self {
         let md = format_peers_markdown(configured_agents, buffer_store, is_node_proxy);
-        let lines = render_markdown(&md, 0, ascii);
+        let lines = render_markdown(&md, 0, ctx);
         Self {
             pager: PagerOverlay::with_title(lines, InspectorKind::Peers.title()),
         }
@@ -114,10 +114,10 @@ impl InspectorOverlay {
         project_root: Option<&std::path::Path>,
         buffer_store: Option<Arc<Mutex<OutputBufferStore>>>,
         is_node_proxy: bool,
-        ascii: bool,
+        ctx: RenderCtx,
     ) -> Self {
         let md = format_context_markdown(project_root, buffer_store, is_node_proxy);
-        let lines = render_markdown(&md, 0, ascii);
+        let lines = render_markdown(&md, 0, ctx);
         Self {
             pager: PagerOverlay::with_title(lines, InspectorKind::Context.title()),
         }
@@ -128,23 +128,23 @@ impl InspectorOverlay {
     /// In local mode `tools` comes from the shared registry snapshot.
     /// In node-proxy mode the caller fetches the list from the node first via
     /// [`crate::node_agent::fetch_node_tools`] and passes it here.
-    pub fn for_tools(tools: &[ToolSchema], is_node_proxy: bool, ascii: bool) -> Self {
+    pub fn for_tools(tools: &[ToolSchema], is_node_proxy: bool, ctx: RenderCtx) -> Self {
         let source_note = if is_node_proxy {
             "> **Connected to node** — showing tools registered on the node.\n\n"
         } else {
             ""
         };
         let md = format!("{}{}", source_note, format_tools_list(tools));
-        let lines = render_markdown(&md, 0, ascii);
+        let lines = render_markdown(&md, 0, ctx);
         Self {
             pager: PagerOverlay::with_title(lines, InspectorKind::Tools.title()),
         }
     }
 
     /// Build the MCP servers inspector from a list of server status summaries.
-    pub fn for_mcp(statuses: &[ServerStatusSummary], ascii: bool) -> Self {
+    pub fn for_mcp(statuses: &[ServerStatusSummary], ctx: RenderCtx) -> Self {
         let md = format_mcp_markdown(statuses);
-        let lines = render_markdown(&md, 0, ascii);
+        let lines = render_markdown(&md, 0, ctx);
         Self {
             pager: PagerOverlay::with_title(lines, InspectorKind::Mcp.title()),
         }
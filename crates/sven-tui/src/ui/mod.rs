@@ -15,6 +15,7 @@ use ratatui::{
 pub(crate) mod chat_list_pane;
 pub(crate) mod chat_pane;
 pub(crate) mod completion_menu;
+pub(crate) mod context_gauge;
 pub(crate) mod help_overlay;
 pub(crate) mod input_pane;
 pub(crate) mod inspector;
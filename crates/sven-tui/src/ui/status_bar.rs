@@ -13,11 +13,13 @@ use ratatui::{
 };
 use sven_config::AgentMode;
 
+use super::context_gauge::draw_context_gauge;
 use super::theme::{
-    ctx_bar, ctx_style, mode_style, sep, spinner_char, BAR_AGENT, BAR_THINKING, BAR_TOOL,
-    BG_ELEVATED, BORDER_DIM, SE_YELLOW, TEXT_DIM,
+    mode_style, sep, spinner_char, BAR_AGENT, BAR_THINKING, BAR_TOOL, BG_ELEVATED, BORDER_DIM,
+    SE_YELLOW, TEXT_DIM,
 };
 use crate::app::ui_state::FocusPane;
+use crate::vi::ViMode;
 
 // ── StatusBar widget ──────────────────────────────────────────────────────────
 
@@ -49,6 +51,9 @@ pub struct StatusBar<'a> {
     pub in_edit: bool,
     /// True when the search bar is active.
     pub in_search: bool,
+    /// Vi editing mode of the input box; `None` unless vi mode is on and
+    /// the input has focus.
+    pub vi_mode: Option<ViMode>,
     // ── Team info (all `None` when not in a team) ──────────────────────────
     /// Active team name (e.g. `"auth-refactor"`).
     pub team_name: Option<&'a str>,
@@ -98,8 +103,7 @@ impl Widget for StatusBar<'_> {
         } else {
             self.context_pct
         };
-        let ctx_bar_str = ctx_bar(display_ctx_pct, self.ascii);
-        let ctx_pct_str = format!(" {}%", display_ctx_pct);
+        let [ctx_bar, ctx_pct] = draw_context_gauge(display_ctx_pct, self.ascii);
 
         // Tool in progress — only shown when a tool is actually running.
         let tool_sym = if self.ascii { "*" } else { "⚙" };
@@ -151,7 +155,7 @@ impl Widget for StatusBar<'_> {
         // ── Context-sensitive hint (right side) ───────────────────────────────
         // Show only the most relevant hint for the current state.
         let hint: &str = if self.in_search {
-            "n/N match · Esc close"
+            "↑/↓ match · Enter keep · Esc cancel"
         } else if self.in_edit {
             "Enter confirm · Esc cancel"
         } else {
@@ -170,6 +174,14 @@ impl Widget for StatusBar<'_> {
             }
         };
 
+        let vi_span: Span<'static> = match self.vi_mode {
+            Some(mode) => Span::styled(
+                format!("  {}", mode.label()),
+                Style::default().fg(SE_YELLOW).add_modifier(Modifier::BOLD),
+            ),
+            None => Span::raw(""),
+        };
+
         // ── Team info ─────────────────────────────────────────────────────────
         // Shows: "⬡ auth-refactor [lead] 3/7 tasks | viewing: security-reviewer"
         let team_span: Span<'static> = if let Some(team) = self.team_name {
@@ -214,10 +226,11 @@ impl Widget for StatusBar<'_> {
             Span::styled(self.model_name.to_string(), Style::default().fg(BAR_AGENT)),
             Span::styled(separator, Style::default().fg(BORDER_DIM)),
             Span::styled(format!(" {mode_str} "), mode_style(self.mode)),
+            vi_span,
             Span::styled(separator, Style::default().fg(BORDER_DIM)),
             Span::styled(" ctx ", Style::default().fg(TEXT_DIM)),
-            Span::styled(ctx_bar_str.to_string(), ctx_style(self.context_pct)),
-            Span::styled(ctx_pct_str, ctx_style(self.context_pct)),
+            ctx_bar,
+            ctx_pct,
             token_span,
             tool_span,
             team_span,
@@ -9,6 +9,7 @@ use ratatui::{
     text::Span,
     widgets::{Block, BorderType, Borders},
 };
+
 use sven_config::AgentMode;
 
 // ── Brand palette ─────────────────────────────────────────────────────────────
@@ -45,6 +46,53 @@ pub(crate) const BAR_COMPACT: Color = Color::Rgb(80, 120, 160);
 
 /// Swedish Embedded yellow (chip body color).
 pub const SE_YELLOW: Color = Color::Rgb(230, 180, 40);
+
+// ── Role palette ──────────────────────────────────────────────────────────────
+
+/// Chat bar colors of the conversation roles for one `tui.theme`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RolePalette {
+    pub user: Color,
+    pub agent: Color,
+    pub tool: Color,
+    pub thinking: Color,
+}
+
+const DARK_ROLES: RolePalette = RolePalette {
+    user: BAR_USER,
+    agent: BAR_AGENT,
+    tool: BAR_TOOL,
+    thinking: BAR_THINKING,
+};
+
+/// Deeper tones that keep their contrast on a light background.
+const LIGHT_ROLES: RolePalette = RolePalette {
+    user: Color::Rgb(30, 130, 70),
+    agent: Color::Rgb(40, 90, 190),
+    tool: Color::Rgb(160, 105, 0),
+    thinking: Color::Rgb(120, 70, 170),
+};
+
+/// Solarized green, blue, yellow and violet.
+const SOLARIZED_ROLES: RolePalette = RolePalette {
+    user: Color::Rgb(133, 153, 0),
+    agent: Color::Rgb(38, 139, 210),
+    tool: Color::Rgb(181, 137, 0),
+    thinking: Color::Rgb(108, 113, 196),
+};
+
+impl RolePalette {
+    /// The role colors for `tui.theme` (`dark`, `light` or `solarized`).
+    /// Unknown names use the dark palette.
+    pub(crate) fn for_theme(tui_theme: &str) -> Self {
+        match tui_theme {
+            "light" => LIGHT_ROLES,
+            "solarized" => SOLARIZED_ROLES,
+            _ => DARK_ROLES,
+        }
+    }
+}
+
 // ── Animation sequences ───────────────────────────────────────────────────────
 
 /// Braille spinner frame sequence (10 frames).  Event-driven: advances on each
@@ -169,9 +217,11 @@ pub(crate) fn mode_style(mode: AgentMode) -> Style {
         AgentMode::Research => Style::default().fg(Color::Rgb(100, 200, 130)),
         AgentMode::Plan => Style::default().fg(Color::Rgb(220, 190, 80)),
         AgentMode::Agent => Style::default().fg(Color::Rgb(180, 130, 220)),
+        AgentMode::Custom(_) => Style::default().fg(Color::Rgb(100, 180, 220)),
     }
 }
 
+/// Context gauge color: green, yellow from 70 %, bold red from 90 %.
 pub(crate) fn ctx_style(pct: u8) -> Style {
     if pct >= 90 {
         Style::default()
@@ -184,15 +234,6 @@ pub(crate) fn ctx_style(pct: u8) -> Style {
     }
 }
 
-/// Render a compact 8-character context usage bar: `[████░░░░]`.
-/// Returned as a styled String for embedding in a status line.
-pub(crate) fn ctx_bar(pct: u8, ascii: bool) -> String {
-    let filled = (pct as usize * 8 / 100).min(8);
-    let empty = 8 - filled;
-    let (fill_ch, empty_ch) = if ascii { ("#", ".") } else { ("█", "░") };
-    format!("[{}{}]", fill_ch.repeat(filled), empty_ch.repeat(empty))
-}
-
 // ── Shared block builders ─────────────────────────────────────────────────────
 
 /// Build a titled pane block with ALL borders and focus-aware style.
@@ -0,0 +1,794 @@
+// Copyright (c) 2024-2026 Martin Schröder <info@swedishembedded.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+//! Optional vi-style modal editing for the input and edit buffers.
+//!
+//! With `tui.vi_mode` enabled a buffer starts in insert mode, where every key
+//! behaves as usual.  Esc switches to normal mode, where letters are motions
+//! and operators instead of text.  Keys normal mode has no use for (Enter,
+//! arrows, anything with Ctrl or Alt) fall through to the regular bindings,
+//! so submitting, pane switching and the rest keep working.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Undo snapshots kept per buffer.
+const UNDO_LIMIT: usize = 100;
+
+/// Current editing mode, shown in the status bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ViMode {
+    #[default]
+    Insert,
+    Normal,
+}
+
+impl ViMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            ViMode::Insert => "INSERT",
+            ViMode::Normal => "NORMAL",
+        }
+    }
+}
+
+/// What became of a key passed to [`ViState::handle_key`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViOutcome {
+    /// The key was consumed; the buffer or cursor may have changed.
+    Handled,
+    /// Not a vi command: handle the key with the regular bindings.
+    Passthrough,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Delete,
+    Change,
+    Yank,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Motion {
+    Left,
+    Right,
+    Down,
+    Up,
+    WordForward,
+    WordBackward,
+    WordEnd,
+    LineStart,
+    FirstNonBlank,
+    LineEnd,
+    BufferStart,
+    BufferEnd,
+}
+
+impl Motion {
+    fn from_char(c: char) -> Option<Self> {
+        Some(match c {
+            'h' => Motion::Left,
+            'l' | ' ' => Motion::Right,
+            'j' => Motion::Down,
+            'k' => Motion::Up,
+            'w' => Motion::WordForward,
+            'b' => Motion::WordBackward,
+            'e' => Motion::WordEnd,
+            '0' => Motion::LineStart,
+            '^' => Motion::FirstNonBlank,
+            '$' => Motion::LineEnd,
+            'G' => Motion::BufferEnd,
+            _ => return None,
+        })
+    }
+
+    /// Operators over these motions act on whole lines.
+    fn linewise(self) -> bool {
+        matches!(
+            self,
+            Motion::Down | Motion::Up | Motion::BufferStart | Motion::BufferEnd
+        )
+    }
+}
+
+/// Modal editing state shared by the input buffer and the inline edit
+/// buffer.  Operates on a `String` and a byte cursor, the representation
+/// the rest of the TUI uses for both.
+#[derive(Debug, Default)]
+pub struct ViState {
+    pub mode: ViMode,
+    operator: Option<Operator>,
+    /// `g` was typed and waits for its second key.
+    pending_g: bool,
+    count: Option<usize>,
+    register: String,
+    /// The register holds whole lines (from `dd`, `yy`, ...).
+    register_linewise: bool,
+    undo: Vec<(String, usize)>,
+    /// Whether keys currently go to the edit buffer rather than the input.
+    editing: bool,
+}
+
+impl ViState {
+    /// Start over in insert mode when keys move between the input buffer
+    /// and an edit buffer: undo history belongs to the buffer it came from.
+    pub fn select_buffer(&mut self, editing: bool) {
+        if self.editing != editing {
+            *self = ViState {
+                register: std::mem::take(&mut self.register),
+                register_linewise: self.register_linewise,
+                editing,
+                ..ViState::default()
+            };
+        }
+    }
+
+    /// Apply `key` to `buf` with the cursor at byte offset `cursor`.
+    pub fn handle_key(
+        &mut self,
+        key: &KeyEvent,
+        buf: &mut String,
+        cursor: &mut usize,
+    ) -> ViOutcome {
+        match self.mode {
+            ViMode::Insert => {
+                if key.code != KeyCode::Esc || !key.modifiers.is_empty() {
+                    return ViOutcome::Passthrough;
+                }
+                self.mode = ViMode::Normal;
+                // An insert that changed nothing leaves nothing to undo.
+                if self.undo.last().is_some_and(|(text, _)| text == buf) {
+                    self.undo.pop();
+                }
+                if *cursor > line_start(buf, *cursor) {
+                    *cursor = prev_char(buf, *cursor);
+                }
+                *cursor = clamp(buf, *cursor);
+                ViOutcome::Handled
+            }
+            ViMode::Normal => self.normal_key(key, buf, cursor),
+        }
+    }
+
+    fn normal_key(&mut self, key: &KeyEvent, buf: &mut String, cursor: &mut usize) -> ViOutcome {
+        if key
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        {
+            return ViOutcome::Passthrough;
+        }
+        let c = match key.code {
+            KeyCode::Char(c) => c,
+            KeyCode::Backspace => 'h',
+            KeyCode::Delete => 'x',
+            KeyCode::Esc if self.pending() => {
+                self.clear_pending();
+                return ViOutcome::Handled;
+            }
+            KeyCode::Enter => {
+                // Submitting or confirming starts the next message fresh.
+                self.clear_pending();
+                self.mode = ViMode::Insert;
+                return ViOutcome::Passthrough;
+            }
+            _ => return ViOutcome::Passthrough,
+        };
+
+        if let Some(d) = c.to_digit(10) {
+            if d != 0 || self.count.is_some() {
+                let count = self.count.unwrap_or(0);
+                self.count = Some(count.saturating_mul(10).saturating_add(d as usize));
+                return ViOutcome::Handled;
+            }
+        }
+        if std::mem::take(&mut self.pending_g) {
+            if c == 'g' {
+                self.motion(Motion::BufferStart, buf, cursor);
+            } else {
+                self.clear_pending();
+            }
+            return ViOutcome::Handled;
+        }
+
+        let operator = match c {
+            'd' => Some(Operator::Delete),
+            'c' => Some(Operator::Change),
+            'y' => Some(Operator::Yank),
+            _ => None,
+        };
+        if let Some(op) = operator {
+            match self.operator {
+                None => self.operator = Some(op),
+                // `dd`, `cc`, `yy`: the current line and count - 1 below it.
+                Some(pending) if pending == op => {
+                    self.operator = None;
+                    let n = self.take_count();
+                    let last = vertical(buf, *cursor, true, n - 1);
+                    self.apply_lines(op, *cursor, last, buf, cursor);
+                }
+                Some(_) => self.clear_pending(),
+            }
+            return ViOutcome::Handled;
+        }
+        if c == 'g' {
+            self.pending_g = true;
+            return ViOutcome::Handled;
+        }
+        if let Some(motion) = Motion::from_char(c) {
+            self.motion(motion, buf, cursor);
+            return ViOutcome::Handled;
+        }
+        if self.operator.is_some() {
+            self.clear_pending();
+            return ViOutcome::Handled;
+        }
+
+        let n = self.take_count();
+        match c {
+            'i' => self.insert_at(*cursor, buf, cursor),
+            'a' => {
+                let at = if *cursor < line_end(buf, *cursor) {
+                    next_char(buf, *cursor)
+                } else {
+                    *cursor
+                };
+                self.insert_at(at, buf, cursor);
+            }
+            'I' => self.insert_at(first_non_blank(buf, *cursor), buf, cursor),
+            'A' => self.insert_at(line_end(buf, *cursor), buf, cursor),
+            'o' | 'O' => {
+                let at = if c == 'o' {
+                    line_end(buf, *cursor)
+                } else {
+                    line_start(buf, *cursor)
+                };
+                self.snapshot(buf, *cursor);
+                buf.insert(at, '\n');
+                *cursor = if c == 'o' { at + 1 } else { at };
+                self.mode = ViMode::Insert;
+            }
+            'x' | 's' => {
+                let to = repeat(n, *cursor, |p| right(buf, p, true));
+                let op = if c == 'x' {
+                    Operator::Delete
+                } else {
+                    Operator::Change
+                };
+                self.apply_range(op, *cursor, to, buf, cursor);
+            }
+            'X' => {
+                let from = repeat(n, *cursor, |p| left(buf, p));
+                self.apply_range(Operator::Delete, from, *cursor, buf, cursor);
+            }
+            'D' | 'C' => {
+                let op = if c == 'D' {
+                    Operator::Delete
+                } else {
+                    Operator::Change
+                };
+                let to = line_end(buf, *cursor);
+                self.apply_range(op, *cursor, to, buf, cursor);
+                if op == Operator::Delete {
+                    *cursor = clamp(buf, *cursor);
+                }
+            }
+            'S' => {
+                let last = vertical(buf, *cursor, true, n - 1);
+                self.apply_lines(Operator::Change, *cursor, last, buf, cursor);
+            }
+            'p' | 'P' => self.put(c == 'p', n, buf, cursor),
+            'u' => {
+                for _ in 0..n {
+                    if let Some((text, pos)) = self.undo.pop() {
+                        *buf = text;
+                        *cursor = clamp(buf, pos);
+                    }
+                }
+            }
+            // Normal mode never types text.
+            _ => {}
+        }
+        ViOutcome::Handled
+    }
+
+    fn pending(&self) -> bool {
+        self.operator.is_some() || self.pending_g || self.count.is_some()
+    }
+
+    fn clear_pending(&mut self) {
+        self.operator = None;
+        self.pending_g = false;
+        self.count = None;
+    }
+
+    fn take_count(&mut self) -> usize {
+        self.count.take().unwrap_or(1).max(1)
+    }
+
+    fn snapshot(&mut self, buf: &str, cursor: usize) {
+        if self.undo.len() == UNDO_LIMIT {
+            self.undo.remove(0);
+        }
+        self.undo.push((buf.to_string(), cursor));
+    }
+
+    fn insert_at(&mut self, at: usize, buf: &str, cursor: &mut usize) {
+        self.snapshot(buf, *cursor);
+        *cursor = at;
+        self.mode = ViMode::Insert;
+    }
+
+    /// Move by `motion`, or apply the pending operator over it.
+    fn motion(&mut self, motion: Motion, buf: &mut String, cursor: &mut usize) {
+        let n = self.take_count();
+        let Some(op) = self.operator.take() else {
+            *cursor = clamp(buf, target(motion, n, buf, *cursor, false));
+            return;
+        };
+        let start = *cursor;
+        if motion.linewise() {
+            let end = target(motion, n, buf, start, true);
+            self.apply_lines(op, start, end, buf, cursor);
+            return;
+        }
+        // `cw` on a word changes to its end, like `ce`.
+        let on_word = char_at(buf, start).is_some_and(|c| !c.is_whitespace());
+        let motion = if op == Operator::Change && motion == Motion::WordForward && on_word {
+            Motion::WordEnd
+        } else {
+            motion
+        };
+        let end = target(motion, n, buf, start, true);
+        let (from, mut to) = if end < start {
+            (end, start)
+        } else {
+            (start, end)
+        };
+        match motion {
+            Motion::WordEnd => to = next_char(buf, to),
+            // A word motion does not take the line break along.
+            Motion::WordForward if line_end(buf, from) > from => to = to.min(line_end(buf, from)),
+            _ => {}
+        }
+        self.apply_range(op, from, to, buf, cursor);
+    }
+
+    fn apply_range(
+        &mut self,
+        op: Operator,
+        from: usize,
+        to: usize,
+        buf: &mut String,
+        cursor: &mut usize,
+    ) {
+        if from < to {
+            self.register = buf[from..to].to_string();
+            self.register_linewise = false;
+        }
+        match op {
+            Operator::Yank => *cursor = clamp(buf, from),
+            Operator::Delete if from < to => {
+                self.snapshot(buf, *cursor);
+                buf.replace_range(from..to, "");
+                *cursor = clamp(buf, from);
+            }
+            Operator::Delete => {}
+            Operator::Change => {
+                self.snapshot(buf, *cursor);
+                buf.replace_range(from..to, "");
+                *cursor = from;
+                self.mode = ViMode::Insert;
+            }
+        }
+    }
+
+    /// Apply `op` to every line from the one holding `a` to the one holding `b`.
+    fn apply_lines(
+        &mut self,
+        op: Operator,
+        a: usize,
+        b: usize,
+        buf: &mut String,
+        cursor: &mut usize,
+    ) {
+        let start = line_start(buf, a.min(b));
+        let end = line_end(buf, a.max(b));
+        self.register = buf[start..end].to_string();
+        self.register_linewise = true;
+        match op {
+            Operator::Yank => {
+                if b < a {
+                    *cursor = clamp(buf, b);
+                }
+            }
+            Operator::Delete => {
+                self.snapshot(buf, *cursor);
+                let line = if end < buf.len() {
+                    buf.replace_range(start..=end, "");
+                    start
+                } else if start > 0 {
+                    buf.replace_range(start - 1..end, "");
+                    line_start(buf, start - 1)
+                } else {
+                    buf.clear();
+                    0
+                };
+                *cursor = clamp(buf, first_non_blank(buf, line));
+            }
+            Operator::Change => {
+                self.snapshot(buf, *cursor);
+                buf.replace_range(start..end, "");
+                *cursor = start;
+                self.mode = ViMode::Insert;
+            }
+        }
+    }
+
+    /// `p` (`after`) and `P`: put the register `n` times.
+    fn put(&mut self, after: bool, n: usize, buf: &mut String, cursor: &mut usize) {
+        if self.register.is_empty() && !self.register_linewise {
+            return;
+        }
+        self.snapshot(buf, *cursor);
+        if self.register_linewise {
+            let text = vec![self.register.as_str(); n].join("\n");
+            let line = if after {
+                let at = line_end(buf, *cursor);
+                buf.insert_str(at, &format!("\n{text}"));
+                at + 1
+            } else {
+                let at = line_start(buf, *cursor);
+                buf.insert_str(at, &format!("{text}\n"));
+                at
+            };
+            *cursor = clamp(buf, first_non_blank(buf, line));
+        } else {
+            let text = self.register.repeat(n);
+            let at = if after && *cursor < line_end(buf, *cursor) {
+                next_char(buf, *cursor)
+            } else {
+                *cursor
+            };
+            buf.insert_str(at, &text);
+            *cursor = clamp(buf, prev_char(buf, at + text.len()));
+        }
+    }
+}
+
+// ── Buffer geometry ───────────────────────────────────────────────────────────
+
+fn repeat(n: usize, pos: usize, step: impl Fn(usize) -> usize) -> usize {
+    (0..n).fold(pos, |p, _| step(p))
+}
+
+/// Where `motion` repeated `n` times leads from `pos`.  With `operator` set,
+/// `l` may reach the end of the line so that the last character can be
+/// operated on.
+fn target(motion: Motion, n: usize, buf: &str, pos: usize, operator: bool) -> usize {
+    match motion {
+        Motion::Left => repeat(n, pos, |p| left(buf, p)),
+        Motion::Right => repeat(n, pos, |p| right(buf, p, operator)),
+        Motion::Down => vertical(buf, pos, true, n),
+        Motion::Up => vertical(buf, pos, false, n),
+        Motion::WordForward => repeat(n, pos, |p| word_forward(buf, p)),
+        Motion::WordBackward => repeat(n, pos, |p| word_backward(buf, p)),
+        Motion::WordEnd => repeat(n, pos, |p| word_end(buf, p)),
+        Motion::LineStart => line_start(buf, pos),
+        Motion::FirstNonBlank => first_non_blank(buf, pos),
+        Motion::LineEnd => line_end(buf, vertical(buf, pos, true, n - 1)),
+        Motion::BufferStart => first_non_blank(buf, 0),
+        Motion::BufferEnd => first_non_blank(buf, line_start(buf, buf.len())),
+    }
+}
+
+fn char_at(buf: &str, pos: usize) -> Option<char> {
+    buf[pos..].chars().next()
+}
+
+fn next_char(buf: &str, pos: usize) -> usize {
+    char_at(buf, pos).map_or(pos, |c| pos + c.len_utf8())
+}
+
+fn prev_char(buf: &str, pos: usize) -> usize {
+    buf[..pos]
+        .chars()
+        .next_back()
+        .map_or(pos, |c| pos - c.len_utf8())
+}
+
+fn line_start(buf: &str, pos: usize) -> usize {
+    buf[..pos].rfind('\n').map_or(0, |i| i + 1)
+}
+
+fn line_end(buf: &str, pos: usize) -> usize {
+    buf[pos..].find('\n').map_or(buf.len(), |i| pos + i)
+}
+
+fn first_non_blank(buf: &str, pos: usize) -> usize {
+    let (start, end) = (line_start(buf, pos), line_end(buf, pos));
+    buf[start..end]
+        .find(|c: char| !c.is_whitespace())
+        .map_or(end, |i| start + i)
+}
+
+/// Keep a normal-mode cursor on a character: never past the last one of
+/// a non-empty line.
+fn clamp(buf: &str, pos: usize) -> usize {
+    let pos = pos.min(buf.len());
+    let (start, end) = (line_start(buf, pos), line_end(buf, pos));
+    if pos >= end && end > start {
+        prev_char(buf, end)
+    } else {
+        pos
+    }
+}
+
+fn left(buf: &str, pos: usize) -> usize {
+    if pos > line_start(buf, pos) {
+        prev_char(buf, pos)
+    } else {
+        pos
+    }
+}
+
+fn right(buf: &str, pos: usize, operator: bool) -> usize {
+    let end = line_end(buf, pos);
+    let next = next_char(buf, pos);
+    if next < end || (operator && next == end && pos < end) {
+        next
+    } else {
+        pos
+    }
+}
+
+/// The same column `n` lines down (or up), or the end of a shorter line.
+fn vertical(buf: &str, pos: usize, down: bool, n: usize) -> usize {
+    let mut start = line_start(buf, pos);
+    let col = buf[start..pos].chars().count();
+    for _ in 0..n {
+        if down {
+            let end = line_end(buf, start);
+            if end == buf.len() {
+                break;
+            }
+            start = end + 1;
+        } else {
+            if start == 0 {
+                break;
+            }
+            start = line_start(buf, start - 1);
+        }
+    }
+    let end = line_end(buf, start);
+    buf[start..end]
+        .char_indices()
+        .nth(col)
+        .map_or(end, |(i, _)| start + i)
+}
+
+#[derive(PartialEq)]
+enum CharClass {
+    Blank,
+    Word,
+    Punct,
+}
+
+fn class(c: char) -> CharClass {
+    if c.is_whitespace() {
+        CharClass::Blank
+    } else if c.is_alphanumeric() || c == '_' {
+        CharClass::Word
+    } else {
+        CharClass::Punct
+    }
+}
+
+/// `w`: the start of the next word.
+fn word_forward(buf: &str, pos: usize) -> usize {
+    let mut p = pos;
+    if let Some(cls) = char_at(buf, p).map(class) {
+        while cls != CharClass::Blank && char_at(buf, p).is_some_and(|c| class(c) == cls) {
+            p = next_char(buf, p);
+        }
+    }
+    while char_at(buf, p).is_some_and(char::is_whitespace) {
+        p = next_char(buf, p);
+    }
+    p
+}
+
+/// `b`: the start of this word, or of the previous one when already there.
+fn word_backward(buf: &str, pos: usize) -> usize {
+    let before = |p: usize| buf[..p].chars().next_back();
+    let mut p = pos;
+    while before(p).is_some_and(char::is_whitespace) {
+        p = prev_char(buf, p);
+    }
+    let Some(cls) = before(p).map(class) else {
+        return p;
+    };
+    while before(p).is_some_and(|c| class(c) == cls) {
+        p = prev_char(buf, p);
+    }
+    p
+}
+
+/// `e`: the last character of this word, or of the next one when already there.
+fn word_end(buf: &str, pos: usize) -> usize {
+    let mut p = next_char(buf, pos);
+    while char_at(buf, p).is_some_and(char::is_whitespace) {
+        p = next_char(buf, p);
+    }
+    let Some(cls) = char_at(buf, p).map(class) else {
+        return prev_char(buf, p).max(pos);
+    };
+    while char_at(buf, next_char(buf, p)).is_some_and(|c| class(c) == cls) {
+        p = next_char(buf, p);
+    }
+    p
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run `keys` in normal mode over `text` with the cursor at the `|`
+    /// marker; returns the text with the marker at the new cursor.
+    fn run(text: &str, keys: &str) -> (String, ViMode) {
+        let mut cursor = text.find('|').unwrap();
+        let mut buf = text.replacen('|', "", 1);
+        let mut vi = ViState {
+            mode: ViMode::Normal,
+            ..ViState::default()
+        };
+        for c in keys.chars() {
+            let code = match c {
+                '\x1b' => KeyCode::Esc,
+                c => KeyCode::Char(c),
+            };
+            vi.handle_key(
+                &KeyEvent::new(code, KeyModifiers::NONE),
+                &mut buf,
+                &mut cursor,
+            );
+        }
+        buf.insert(cursor, '|');
+        (buf, vi.mode)
+    }
+
+    fn normal(text: &str, keys: &str) -> String {
+        let (out, mode) = run(text, keys);
+        assert_eq!(mode, ViMode::Normal, "{keys:?} left normal mode");
+        out
+    }
+
+    #[test]
+    fn character_and_line_motions() {
+        assert_eq!(normal("ab|cd", "h"), "a|bcd");
+        assert_eq!(normal("ab|cd", "ll"), "abc|d");
+        assert_eq!(normal("|abcd", "h"), "|abcd");
+        assert_eq!(normal("ab|cd", "$"), "abc|d");
+        assert_eq!(normal("  ab|cd", "0"), "|  abcd");
+        assert_eq!(normal("  ab|cd", "^"), "  |abcd");
+        assert_eq!(normal("ab|cd\nx\nefgh", "j"), "abcd\n|x\nefgh");
+        assert_eq!(normal("ab|cd\nx\nefgh", "2j"), "abcd\nx\nef|gh");
+        assert_eq!(normal("abcd\nx\nef|gh", "k"), "abcd\n|x\nefgh");
+        assert_eq!(normal("ab\ncd\nef|gh", "gg"), "|ab\ncd\nefgh");
+        assert_eq!(normal("a|b\ncd\nefgh", "G"), "ab\ncd\n|efgh");
+    }
+
+    #[test]
+    fn word_motions() {
+        let text = "|let x = foo.bar(1);";
+        assert_eq!(normal(text, "w"), "let |x = foo.bar(1);");
+        assert_eq!(normal(text, "4w"), "let x = foo|.bar(1);");
+        assert_eq!(normal(text, "e"), "le|t x = foo.bar(1);");
+        assert_eq!(normal(text, "3e"), "let x |= foo.bar(1);");
+        assert_eq!(normal("let x = foo.b|ar(1);", "b"), "let x = foo.|bar(1);");
+        assert_eq!(normal("let x = foo.b|ar(1);", "3b"), "let x = |foo.bar(1);");
+        assert_eq!(normal("one\n  |two", "b"), "|one\n  two");
+    }
+
+    #[test]
+    fn delete_operators() {
+        assert_eq!(normal("one |two three", "dw"), "one |three");
+        assert_eq!(normal("one |two three", "d2w"), "one| ");
+        assert_eq!(normal("one tw|o\nthree", "dw"), "one t|w\nthree");
+        assert_eq!(normal("one t|wo three", "de"), "one t| three");
+        assert_eq!(normal("one two t|hree", "db"), "one two |hree");
+        assert_eq!(normal("one |two three", "D"), "one| ");
+        assert_eq!(normal("ab|cd", "x"), "ab|d");
+        assert_eq!(normal("ab|cd", "3x"), "a|b");
+        assert_eq!(normal("ab|cd", "X"), "a|cd");
+    }
+
+    #[test]
+    fn linewise_operators_and_put() {
+        assert_eq!(normal("one\nt|wo\nthree", "dd"), "one\n|three");
+        assert_eq!(normal("one\ntwo\nth|ree", "dd"), "one\n|two");
+        assert_eq!(normal("o|ne\ntwo\nthree", "2dd"), "|three");
+        assert_eq!(normal("one\nt|wo\nthree", "dk"), "|three");
+        assert_eq!(normal("o|ne", "dd"), "|");
+        assert_eq!(normal("o|ne\ntwo", "ddp"), "two\n|one");
+        assert_eq!(normal("o|ne\ntwo", "yyjp"), "one\ntwo\n|one");
+        assert_eq!(normal("o|ne\ntwo", "yyP"), "|one\none\ntwo");
+        assert_eq!(normal("|ab cd", "dwp"), "cab| d");
+    }
+
+    #[test]
+    fn change_enters_insert_mode() {
+        assert_eq!(
+            run("one |two three", "cw"),
+            ("one | three".into(), ViMode::Insert)
+        );
+        assert_eq!(
+            run("one\n  t|wo\n", "cc"),
+            ("one\n|\n".into(), ViMode::Insert)
+        );
+        assert_eq!(run("ab|cd", "C"), ("ab|".into(), ViMode::Insert));
+        assert_eq!(run("ab|cd", "A"), ("abcd|".into(), ViMode::Insert));
+        assert_eq!(run("ab|cd", "a"), ("abc|d".into(), ViMode::Insert));
+        assert_eq!(
+            run("one|\ntwo", "o"),
+            ("one\n|\ntwo".into(), ViMode::Insert)
+        );
+        assert_eq!(
+            run("one\nt|wo", "O"),
+            ("one\n|\ntwo".into(), ViMode::Insert)
+        );
+        // Esc returns to normal mode one character to the left.
+        assert_eq!(normal("ab|cd", "a\x1b"), "ab|cd");
+    }
+
+    #[test]
+    fn undo_restores_previous_text() {
+        assert_eq!(normal("one |two three", "dwu"), "one |two three");
+        assert_eq!(normal("one |two three", "dwdwuu"), "one |two three");
+        assert_eq!(normal("a|b\ncd", "ddPu"), "|cd");
+        // An insert that typed nothing is not an undo step.
+        assert_eq!(normal("ab|cd", "xi\x1bu"), "ab|cd");
+    }
+
+    #[test]
+    fn esc_cancels_pending_operator() {
+        assert_eq!(normal("one |two", "d\x1bw"), "one tw|o");
+        assert_eq!(normal("one |two", "2\x1bx"), "one |wo");
+    }
+
+    #[test]
+    fn other_keys_pass_through() {
+        let mut vi = ViState::default();
+        let (mut buf, mut cursor) = (String::from("abc"), 3);
+        let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        let ctrl_w = KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL);
+        let typed = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE);
+
+        assert_eq!(
+            vi.handle_key(&typed, &mut buf, &mut cursor),
+            ViOutcome::Passthrough
+        );
+        assert_eq!(
+            vi.handle_key(&esc, &mut buf, &mut cursor),
+            ViOutcome::Handled
+        );
+        assert_eq!((vi.mode, cursor), (ViMode::Normal, 2));
+        assert_eq!(
+            vi.handle_key(&esc, &mut buf, &mut cursor),
+            ViOutcome::Passthrough
+        );
+        assert_eq!(
+            vi.handle_key(&ctrl_w, &mut buf, &mut cursor),
+            ViOutcome::Passthrough
+        );
+        assert_eq!(
+            vi.handle_key(&typed, &mut buf, &mut cursor),
+            ViOutcome::Handled
+        );
+        assert_eq!(buf, "ab");
+        assert_eq!(
+            vi.handle_key(&enter, &mut buf, &mut cursor),
+            ViOutcome::Passthrough
+        );
+        assert_eq!(vi.mode, ViMode::Insert);
+    }
+}
@@ -4,7 +4,7 @@
 use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use clap_complete::{generate, Shell};
 use std::path::PathBuf;
-use sven_config::AgentMode;
+use sven_config::{AgentMode, ToolPreset};
 
 // ── Tool subcommand ───────────────────────────────────────────────────────────
 
@@ -181,6 +181,25 @@ pub enum AcpCommands {
         /// May also be set via the SVEN_PROVIDER environment variable.
         #[arg(long, env = "SVEN_PROVIDER", value_name = "PROVIDER")]
         provider: Option<String>,
+
+        /// Restrict the agent to a preset tool set (minimal | review).
+        ///
+        /// The task tool passes the `tool_profile` requested for a sub-agent
+        /// here.
+        #[arg(long, value_enum, value_name = "PRESET")]
+        tool_profile: Option<ToolPreset>,
+
+        /// Offer only these tools (comma-separated); sets `tools.allow`.
+        ///
+        /// The task tool passes the parent's `tools.allow` here.
+        #[arg(long, value_delimiter = ',', value_name = "TOOLS")]
+        tools_allow: Option<Vec<String>>,
+
+        /// Remove these tools (comma-separated); sets `tools.deny`.
+        ///
+        /// The task tool passes the parent's `tools.deny` here.
+        #[arg(long, value_delimiter = ',', value_name = "TOOLS")]
+        tools_deny: Vec<String>,
     },
 }
 
@@ -450,6 +469,44 @@ pub enum PeerCommands {
     },
 }
 
+// ── Config subcommand ─────────────────────────────────────────────────────────
+
+/// `sven config` subcommands — read and edit config values by dotted key.
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommands {
+    /// Print the effective value of a config key.
+    ///
+    /// The value is read from the merged configuration (all config files plus
+    /// `--config`).  Nothing is printed when the key is unset.
+    ///
+    /// Example:
+    ///   sven config get model.temperature
+    Get {
+        /// Dotted key, e.g. `model.name` or `tools.web.fetch_max_chars`.
+        key: String,
+    },
+
+    /// Set a config key in a config file.
+    ///
+    /// Writes to the `--config` file if given, otherwise to the
+    /// highest-priority config file that exists (the user config when there
+    /// is none).  Numbers, booleans and `null` are stored as such; comments
+    /// in the file are kept where possible.
+    ///
+    /// Example:
+    ///   sven config set model.temperature 0.2
+    Set {
+        /// Dotted key, e.g. `model.name` or `tools.web.fetch_max_chars`.
+        key: String,
+        /// New value.
+        value: String,
+        /// Write to the user config (~/.config/sven/config.yaml) even when a
+        /// project config exists.
+        #[arg(long)]
+        global: bool,
+    },
+}
+
 // ── Index subcommand ──────────────────────────────────────────────────────────
 
 /// `sven index` subcommands — manage the repository context index.
@@ -682,6 +739,14 @@ pub enum OutputFormatArg {
     Jsonl,
 }
 
+/// File format for `sven export`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ExportFormatArg {
+    /// Self-contained HTML page.
+    #[default]
+    Html,
+}
+
 #[derive(Parser, Debug)]
 #[command(
     name = "sven",
@@ -715,6 +780,11 @@ pub struct Cli {
     #[arg(long, short = 'M', env = "SVEN_MODEL")]
     pub model: Option<String>,
 
+    /// Restrict the agent to a preset tool set (minimal | review).
+    /// Overrides `tools.profile` from the config.
+    #[arg(long, value_enum, value_name = "PRESET")]
+    pub tool_profile: Option<ToolPreset>,
+
     /// Path to a markdown workflow file (CI mode).
     /// Workflow structure (H1, preamble, `##` steps) is only applied when using
     /// this flag; stdin is never parsed as a workflow.
@@ -729,6 +799,22 @@ pub struct Cli {
     #[arg(long, value_name = "ID", num_args = 0..=1, default_missing_value = "")]
     pub resume: Option<String>,
 
+    /// Resume the most recent saved conversation.
+    /// Works in both TUI and headless mode; in headless mode the prompt is
+    /// appended as a new user turn, as with --resume.
+    #[arg(long = "continue", conflicts_with = "resume")]
+    pub continue_last: bool,
+
+    /// With --resume/--continue in headless mode: compact the loaded history
+    /// before the new turn when it holds more than N user turns.
+    #[arg(long, value_name = "N")]
+    pub max_history_turns: Option<usize>,
+
+    /// With --resume/--continue in headless mode: compact the loaded history
+    /// before the new turn when it is estimated at more than N tokens.
+    #[arg(long, value_name = "N")]
+    pub max_context_tokens: Option<usize>,
+
     /// Path to config file (overrides auto-discovery)
     #[arg(long, short = 'c')]
     pub config: Option<PathBuf>,
@@ -742,7 +828,8 @@ pub struct Cli {
     pub output_format: OutputFormatArg,
 
     /// Directory to write run artifacts (full conversation, per-step files).
-    /// Created if it does not exist.
+    /// Created if it does not exist.  Each run writes into its own
+    /// `run-<id>` subdirectory and `latest` points at the newest.
     #[arg(long)]
     pub artifacts_dir: Option<PathBuf>,
 
@@ -775,6 +862,22 @@ pub struct Cli {
     #[arg(long, value_name = "TEXT")]
     pub append_system_prompt: Option<String>,
 
+    /// Print the system prompt sven would send for --mode and exit.
+    ///
+    /// Includes the detected project, git and CI context, the project
+    /// context file, --system-prompt-file / --append-system-prompt and, with
+    /// --file, the workflow preamble.
+    #[arg(long)]
+    pub print_system_prompt: bool,
+
+    /// Request each completion in one piece instead of streaming it.
+    ///
+    /// Same as setting `driver_options.stream: false` on every configured
+    /// model.  Useful behind proxies that buffer or break server-sent events.
+    /// Only the OpenAI-compatible drivers honour it.
+    #[arg(long)]
+    pub no_stream: bool,
+
     /// Write the final agent response to a file after the run completes.
     /// The file is created (and intermediate directories) if needed.
     #[arg(long, short = 'o', value_name = "PATH")]
@@ -793,6 +896,12 @@ pub struct Cli {
     #[arg(long, value_name = "PATH", conflicts_with = "jsonl")]
     pub output_jsonl: Option<PathBuf>,
 
+    /// Write images embedded in the output JSONL to files in DIR, named
+    /// `<hash>.<ext>`, and store `file://` references to them instead of
+    /// base64 data URLs.  Loading such a file inlines the images again.
+    #[arg(long, value_name = "DIR")]
+    pub output_image_dir: Option<PathBuf>,
+
     /// Combined load + output JSONL: equivalent to --load-jsonl PATH --output-jsonl PATH.
     /// Loads an existing conversation from PATH, runs, and writes back to the same file.
     /// In TUI mode the file is kept in sync after every turn.
@@ -800,6 +909,13 @@ pub struct Cli {
     #[arg(long, value_name = "PATH")]
     pub jsonl: Option<PathBuf>,
 
+    /// TUI only: after each turn append just the new records to the output
+    /// JSONL file instead of rewriting it.  The file is still rewritten in
+    /// full when earlier messages change (edit, rewind) or it was modified
+    /// by something else.
+    #[arg(long)]
+    pub jsonl_append: bool,
+
     /// Load (and save) a YAML chat document.
     /// The file is parsed as a ChatDocument; the conversation history seeds the agent
     /// and new turns are appended.  In TUI mode the file is kept in sync after every turn.
@@ -840,6 +956,13 @@ pub struct Cli {
     #[arg(long, value_name = "TOKENS")]
     pub max_tokens: Option<u64>,
 
+    /// On failure, also print a single-line JSON object to stderr:
+    ///   {"code":2,"kind":"validation_error","message":"...","step":null,"elapsed_secs":0.01}
+    /// `kind` is one of agent_error, validation_error, tool_warnings,
+    /// budget_exhausted, timeout, interrupt.  Headless mode only.
+    #[arg(long)]
+    pub json_errors: bool,
+
     /// Increase verbosity (-v = debug, -vv = trace)
     #[arg(long, short = 'v', action = clap::ArgAction::Count)]
     pub verbose: u8,
@@ -950,6 +1073,24 @@ pub enum Commands {
     },
     /// Print the effective configuration and exit
     ShowConfig,
+    /// Check the environment: config, API key, provider endpoint and the
+    /// external programs sven uses.
+    ///
+    /// Prints a checklist and exits non-zero when any check fails.
+    Doctor {
+        /// Skip the provider health check (no network access).
+        #[arg(long)]
+        offline: bool,
+    },
+    /// Read or change individual config values.
+    ///
+    /// Subcommands:
+    ///   sven config get <KEY>             — print the effective value
+    ///   sven config set <KEY> <VALUE>     — write the value to a config file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
     /// Handle OAuth callback from sven:// protocol (used by OS protocol handler).
     ///
     /// When the OAuth server redirects to sven://sven.mcp/callback?code=...&state=...,
@@ -969,6 +1110,49 @@ pub enum Commands {
         /// Maximum number of conversations to show (default: 20)
         #[arg(long, short = 'n', default_value = "20")]
         limit: usize,
+        /// Only list conversations whose content contains this text
+        /// (case-insensitive), with the matching turn and a snippet
+        #[arg(long, value_name = "QUERY", conflicts_with = "prune")]
+        grep: Option<String>,
+        /// Delete the oldest saved conversations beyond --keep and/or
+        /// --max-age-days instead of listing
+        #[arg(long)]
+        prune: bool,
+        /// With --prune: number of most recent conversations to keep
+        #[arg(long, value_name = "N", requires = "prune")]
+        keep: Option<usize>,
+        /// With --prune: delete conversations older than this many days
+        #[arg(long, value_name = "DAYS", requires = "prune")]
+        max_age_days: Option<u64>,
+    },
+    /// Export a saved conversation to a standalone file for sharing.
+    ///
+    /// The HTML export is a single page with embedded CSS, collapsible tool
+    /// sections, highlighted code blocks and inline images.
+    Export {
+        /// Conversation ID, unique prefix, or path to a .md / .jsonl file
+        #[arg(value_name = "ID")]
+        id: String,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ExportFormatArg::Html)]
+        format: ExportFormatArg,
+        /// File to write (default: stdout)
+        #[arg(long, short = 'o')]
+        out: Option<PathBuf>,
+    },
+    /// Re-run the tool calls of a saved conversation against the current
+    /// working tree and report where the results differ from the recorded
+    /// ones.  Exits non-zero when any result differs.
+    ///
+    /// Only read-only tools (file reads, searches, web lookups) run unless
+    /// --apply is given.
+    Replay {
+        /// Conversation ID, unique prefix, or path to a .md / .jsonl file
+        #[arg(value_name = "ID")]
+        id: String,
+        /// Also re-run tools that may modify files or run commands
+        #[arg(long)]
+        apply: bool,
     },
     /// Validate a workflow file: parse frontmatter, count steps, check syntax.
     /// Exits 0 if valid, non-zero with an error description otherwise.
@@ -976,6 +1160,24 @@ pub enum Commands {
         /// Path to the workflow markdown file to validate
         #[arg(long, short = 'f', required = true)]
         file: PathBuf,
+        /// Fail when a provider/model reference does not resolve against the
+        /// driver registry and model catalog (otherwise only a warning)
+        #[arg(long)]
+        strict: bool,
+    },
+    /// Follow a JSONL conversation file and print each turn as it is written.
+    ///
+    /// Works like `tail -f` on a file written by `--output-jsonl` or `--jsonl`,
+    /// printing records as conversation markdown.  Press Ctrl-C to stop.
+    ///
+    ///   sven tail .sven/logs/run.jsonl
+    ///   sven tail --no-follow run.jsonl   — print the file once and exit
+    Tail {
+        /// JSONL conversation file to follow (may not exist yet)
+        file: PathBuf,
+        /// Print the current contents and exit instead of following
+        #[arg(long)]
+        no_follow: bool,
     },
     /// Build and query a repository context index.
     ///
@@ -1100,6 +1302,14 @@ pub enum Commands {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+        /// Only show providers whose id, name, description or category
+        /// contains TERM (case-insensitive)
+        #[arg(long, value_name = "TERM")]
+        filter: Option<String>,
+        /// Only show providers in this category: cloud, gateway, fast, open,
+        /// specialized, regional, local or testing
+        #[arg(long, value_name = "CATEGORY")]
+        category: Option<String>,
     },
 }
 
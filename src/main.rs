@@ -13,15 +13,14 @@ use tracing_subscriber::{filter::EnvFilter, fmt, prelude::*};
 
 use clap::Parser;
 use cli::{
-    AcpCommands, Cli, Commands, IndexCommands, McpCommands, NodeCommands, OutputFormatArg,
-    PeerCommands, TeamCommands, ToolCommands, WebDevicesCommands,
+    AcpCommands, Cli, Commands, ConfigCommands, ExportFormatArg, IndexCommands, McpCommands,
+    NodeCommands, OutputFormatArg, PeerCommands, TeamCommands, ToolCommands, WebDevicesCommands,
 };
 use sven_bootstrap::build_cli_tool_registry;
 use sven_ci::{find_project_root, CiOptions, CiRunner, OutputFormat};
 use sven_ci::{MapOptions, ReduceOptions, TeeOptions};
-use sven_config::AgentMode;
 use sven_gui::bridge::{SvenApp, SvenAppOptions};
-use sven_input::{history, parse_frontmatter, parse_workflow};
+use sven_input::{expand_includes, history, parse_frontmatter, parse_workflow};
 use sven_model::catalog::ModelCatalogEntry;
 use sven_tui::{App, AppOptions, ModelDirective, NodeBackend, QueuedMessage};
 
@@ -66,7 +65,11 @@ async fn main() -> anyhow::Result<()> {
                 return run_tool_command(command, &config).await;
             }
             Commands::Mcp { command } => {
-                return run_mcp_command(command).await;
+                let mut config = sven_config::load(cli.config.as_deref())?;
+                // The MCP server is headless: scrub the shell environment
+                // unless the config says otherwise.
+                config.tools.inherit_env.get_or_insert(false);
+                return run_mcp_command(command, &config).await;
             }
             Commands::Acp { command } => {
                 return run_acp_command(command).await;
@@ -89,12 +92,42 @@ async fn main() -> anyhow::Result<()> {
             Commands::OauthCallback { url } => {
                 return run_oauth_callback(url).await;
             }
-            Commands::Chats { limit } => {
-                print_chats(*limit);
+            Commands::Chats {
+                limit,
+                grep,
+                prune,
+                keep,
+                max_age_days,
+            } => {
+                let mut config = sven_config::load(cli.config.as_deref())?;
+                resolve_history_dir(&mut config);
+                if *prune {
+                    return prune_chats(&config.history, *keep, *max_age_days);
+                }
+                match grep {
+                    Some(query) => print_chat_matches(&config.history, query, *limit),
+                    None => print_chats(&config.history, *limit),
+                }
                 return Ok(());
             }
-            Commands::Validate { file } => {
-                return validate_workflow(file);
+            Commands::Export { id, format, out } => {
+                let mut config = sven_config::load(cli.config.as_deref())?;
+                resolve_history_dir(&mut config);
+                return export_conversation(&config.history, id, *format, out.as_deref());
+            }
+            Commands::Replay { id, apply } => {
+                let mut config = sven_config::load(cli.config.as_deref())?;
+                resolve_history_dir(&mut config);
+                return replay_conversation(id, *apply, &config).await;
+            }
+            Commands::Validate { file, strict } => {
+                let config = sven_model::load_config(cli.config.as_deref(), *strict);
+                // Lenient validation only checks references when the config loads.
+                let config = if *strict { Some(config?) } else { config.ok() };
+                return validate_workflow(file, config.as_ref(), *strict);
+            }
+            Commands::Tail { file, no_follow } => {
+                return sven_ci::tail::tail_jsonl(file, !*no_follow).await;
             }
             Commands::Map {
                 template,
@@ -139,6 +172,20 @@ async fn main() -> anyhow::Result<()> {
             Commands::Index { command } => {
                 return run_index_command(command);
             }
+            Commands::Config { command } => {
+                return run_config_command(command, cli.config.as_deref());
+            }
+            Commands::Doctor { offline } => {
+                let checks = sven_ci::doctor::run(cli.config.as_deref(), *offline).await;
+                print!("{}", sven_ci::doctor::render(&checks));
+                if checks
+                    .iter()
+                    .any(|c| c.status == sven_ci::doctor::CheckStatus::Fail)
+                {
+                    std::process::exit(1);
+                }
+                return Ok(());
+            }
             Commands::ListModels {
                 provider,
                 refresh,
@@ -147,13 +194,32 @@ async fn main() -> anyhow::Result<()> {
                 let config = sven_config::load(cli.config.as_deref())?;
                 return list_models_cmd(&config, provider.as_deref(), *refresh, *json).await;
             }
-            Commands::ListProviders { verbose, json } => {
-                return list_providers_cmd(*verbose, *json);
+            Commands::ListProviders {
+                verbose,
+                json,
+                filter,
+                category,
+            } => {
+                return list_providers_cmd(*verbose, *json, filter.as_deref(), category.as_deref());
             }
         }
     }
 
-    let config = Arc::new(sven_config::load(cli.config.as_deref())?);
+    let mut config = sven_model::load_config(cli.config.as_deref(), false)?;
+    if let Some(preset) = cli.tool_profile {
+        config.tools.profile = Some(preset);
+    }
+    if cli.no_stream {
+        disable_streaming(&mut config);
+    }
+    // Headless runs scrub the shell environment unless the config says otherwise.
+    config.tools.inherit_env.get_or_insert(!cli.is_headless());
+    resolve_history_dir(&mut config);
+    let config = Arc::new(config);
+
+    if cli.print_system_prompt {
+        return print_system_prompt(&cli, &config);
+    }
 
     // ── Teammate mode ─────────────────────────────────────────────────────────
     // When --team-name is set (injected by spawn_teammate), skip the normal CI
@@ -170,6 +236,8 @@ async fn main() -> anyhow::Result<()> {
         return run_as_teammate(agent_name, team_name, role, config).await;
     }
 
+    let cli = resolve_continue(cli, &config.history)?;
+
     if cli.gui {
         run_gui(cli, config).await
     } else if cli.is_headless() {
@@ -644,6 +712,9 @@ async fn run_acp_command(cmd: &AcpCommands) -> anyhow::Result<()> {
             token,
             model,
             provider,
+            tool_profile,
+            tools_allow,
+            tools_deny,
         } => {
             if let Some(url) = node_url {
                 let tok = token.clone().ok_or_else(|| {
@@ -660,13 +731,22 @@ async fn run_acp_command(cmd: &AcpCommands) -> anyhow::Result<()> {
                 if let Some(ref prov) = provider {
                     config.model.provider = prov.clone();
                 }
+                if let Some(preset) = tool_profile {
+                    config.tools.profile = Some(*preset);
+                }
+                if let Some(allow) = tools_allow {
+                    config.tools.allow = Some(allow.clone());
+                }
+                if !tools_deny.is_empty() {
+                    config.tools.deny = tools_deny.clone();
+                }
                 sven_acp::serve_stdio(std::sync::Arc::new(config)).await
             }
         }
     }
 }
 
-async fn run_mcp_command(cmd: &McpCommands) -> anyhow::Result<()> {
+async fn run_mcp_command(cmd: &McpCommands, config: &sven_config::Config) -> anyhow::Result<()> {
     match cmd {
         McpCommands::Serve {
             tools,
@@ -683,6 +763,7 @@ async fn run_mcp_command(cmd: &McpCommands) -> anyhow::Result<()> {
                 sven_mcp::serve_stdio_node_proxy(url.clone(), tok).await
             } else {
                 let registry = std::sync::Arc::new(sven_mcp::build_mcp_registry(
+                    &config.tools,
                     brave_api_key.clone(),
                     tools.as_deref(),
                 ));
@@ -693,9 +774,18 @@ async fn run_mcp_command(cmd: &McpCommands) -> anyhow::Result<()> {
 }
 
 /// Validate a workflow file: parse frontmatter, count steps, report to stdout.
-fn validate_workflow(file: &std::path::Path) -> anyhow::Result<()> {
+///
+/// Provider/model references are checked against `config` when one is given.
+/// With `strict` (`--strict`) unresolvable references fail validation;
+/// otherwise they are reported as warnings.
+fn validate_workflow(
+    file: &std::path::Path,
+    config: Option<&sven_config::Config>,
+    strict: bool,
+) -> anyhow::Result<()> {
     let content = std::fs::read_to_string(file)
         .with_context(|| format!("reading workflow file {}", file.display()))?;
+    let content = expand_includes(&content, file)?;
 
     let (frontmatter, markdown_body) = parse_frontmatter(&content);
 
@@ -739,6 +829,10 @@ fn validate_workflow(file: &std::path::Path) -> anyhow::Result<()> {
         );
     }
 
+    let issues = config
+        .map(|config| sven_ci::check_model_refs(config, frontmatter.as_ref(), &workflow))
+        .unwrap_or_default();
+
     let mut queue = workflow.steps;
     let total = queue.len();
     println!("Steps: {total}");
@@ -767,6 +861,21 @@ fn validate_workflow(file: &std::path::Path) -> anyhow::Result<()> {
         }
     }
 
+    if !issues.is_empty() {
+        eprintln!();
+        let level = if strict { "error" } else { "warning" };
+        for issue in &issues {
+            eprintln!("{level}: {issue}");
+        }
+    }
+    if strict && !issues.is_empty() {
+        anyhow::bail!(
+            "{} unresolvable model reference(s) in {}",
+            issues.len(),
+            file.display()
+        );
+    }
+
     println!("\nWorkflow is valid.");
     Ok(())
 }
@@ -876,14 +985,38 @@ async fn list_models_cmd(
     Ok(())
 }
 
-/// List all registered model providers.
-fn list_providers_cmd(verbose: bool, as_json: bool) -> anyhow::Result<()> {
-    let drivers = sven_model::list_drivers();
+/// List the registered model providers, optionally narrowed by a search term
+/// and a category.
+fn list_providers_cmd(
+    verbose: bool,
+    as_json: bool,
+    term: Option<&str>,
+    category: Option<&str>,
+) -> anyhow::Result<()> {
+    use sven_model::{DriverCategory, DriverFilter};
+
+    let category = match category {
+        None => None,
+        Some(id) => Some(DriverCategory::from_id(id).ok_or_else(|| {
+            let known: Vec<&str> = DriverCategory::ALL.iter().map(|c| c.id()).collect();
+            anyhow::anyhow!(
+                "unknown category {id:?} (expected one of: {})",
+                known.join(", ")
+            )
+        })?),
+    };
+    let filter = DriverFilter {
+        term: term.map(str::to_string),
+        category,
+    };
+    let drivers = sven_model::filter_drivers(&filter);
+    let total = sven_model::list_drivers().len();
 
     if as_json {
         #[derive(serde::Serialize)]
         struct ProviderJson {
             id: &'static str,
+            category: &'static str,
             name: &'static str,
             description: &'static str,
             default_api_key_env: Option<&'static str>,
@@ -894,6 +1027,7 @@ fn list_providers_cmd(verbose: bool, as_json: bool) -> anyhow::Result<()> {
             .iter()
             .map(|d| ProviderJson {
                 id: d.id,
+                category: d.category.id(),
                 name: d.name,
                 description: d.description,
                 default_api_key_env: d.default_api_key_env,
@@ -905,41 +1039,20 @@ fn list_providers_cmd(verbose: bool, as_json: bool) -> anyhow::Result<()> {
         return Ok(());
     }
 
-    println!("Supported Model Providers ({} total)\n", drivers.len());
-
-    if verbose {
-        for d in drivers {
-            println!("  {} — {}", d.id, d.name);
-            println!("    {}", d.description);
-            if let Some(env) = d.default_api_key_env {
-                println!("    API key env : {env}");
-            }
-            if let Some(url) = d.default_base_url {
-                println!("    Default URL : {url}");
-            }
-            if !d.requires_api_key {
-                println!("    Auth        : none required");
-            }
-            println!();
-        }
+    if drivers.len() == total {
+        println!("Supported Model Providers ({total} total)");
+    } else if drivers.is_empty() {
+        println!("No providers match the filter ({total} total).");
+        return Ok(());
     } else {
-        let id_w = drivers
-            .iter()
-            .map(|d| d.id.len())
-            .max()
-            .unwrap_or(10)
-            .max(10);
-        let name_w = drivers
-            .iter()
-            .map(|d| d.name.len())
-            .max()
-            .unwrap_or(8)
-            .max(8);
-        println!("{:<id_w$}  {:<name_w$}  DESCRIPTION", "ID", "NAME");
-        println!("{}", "-".repeat(id_w + name_w + 40));
-        for d in drivers {
-            println!("{:<id_w$}  {:<name_w$}  {}", d.id, d.name, d.description);
-        }
+        println!("Model Providers ({} of {total} match)", drivers.len());
+    }
+
+    print!(
+        "{}",
+        sven_model::registry::render_driver_list(&drivers, verbose)
+    );
+    if !verbose {
         println!("\nUse `sven list-providers --verbose` for API key and URL details.");
         println!("Use `sven list-models --provider <ID>` to see models for a specific provider.");
     }
@@ -1017,6 +1130,92 @@ fn read_stdin_to_string() -> anyhow::Result<String> {
     Ok(buf)
 }
 
+// ── --no-stream ───────────────────────────────────────────────────────────────
+
+/// Set `driver_options.stream: false` on the active model, every named
+/// provider and every per-model override that would replace the provider's
+/// options.
+fn disable_streaming(config: &mut sven_config::Config) {
+    fn set(opts: &mut serde_json::Value) {
+        if !opts.is_object() {
+            *opts = serde_json::json!({});
+        }
+        opts["stream"] = serde_json::Value::Bool(false);
+    }
+    set(&mut config.model.driver_options);
+    for entry in config.providers.values_mut() {
+        set(&mut entry.driver_options);
+        for params in entry.models.values_mut() {
+            if !params.driver_options.is_null() {
+                set(&mut params.driver_options);
+            }
+        }
+    }
+}
+
+// ── --print-system-prompt ─────────────────────────────────────────────────────
+
+/// Assemble the system prompt the way a session started with the same flags
+/// would, print it and return.
+fn print_system_prompt(cli: &Cli, config: &sven_config::Config) -> anyhow::Result<()> {
+    let mut runtime = sven_bootstrap::RuntimeContext::auto_detect().to_agent_runtime();
+
+    // A workflow preamble is appended before --append-system-prompt, as in
+    // the CI runner.
+    let preamble = match &cli.file {
+        Some(path) if path.extension().is_none_or(|e| e != "jsonl") => {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("reading {}", path.display()))?;
+            let content = expand_includes(&content, path)?;
+            let (_, body) = parse_frontmatter(&content);
+            parse_workflow(body).system_prompt_append
+        }
+        _ => None,
+    };
+    runtime.append_system_prompt = match (preamble, cli.append_system_prompt.clone()) {
+        (Some(p), Some(a)) => Some(format!("{p}\n\n{a}")),
+        (p, a) => p.or(a),
+    };
+    if let Some(path) = &cli.system_prompt_file {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("reading --system-prompt-file {}", path.display()))?;
+        runtime.system_prompt_override = Some(content.trim().to_string());
+    }
+
+    let prompt = runtime.render_system_prompt(cli.mode, config.agent.system_prompt.as_deref());
+    println!("{prompt}");
+    Ok(())
+}
+
+// ── Config command handler ────────────────────────────────────────────────────
+
+fn run_config_command(
+    cmd: &ConfigCommands,
+    explicit: Option<&std::path::Path>,
+) -> anyhow::Result<()> {
+    match cmd {
+        ConfigCommands::Get { key } => {
+            let config = sven_config::load(explicit)?;
+            match sven_config::get_key(&config, key)? {
+                Some(serde_yaml::Value::String(s)) => println!("{s}"),
+                Some(value) => print!("{}", serde_yaml::to_string(&value)?),
+                None => {}
+            }
+            Ok(())
+        }
+        ConfigCommands::Set { key, value, global } => {
+            let path = match explicit {
+                Some(p) => p.to_path_buf(),
+                None => sven_config::edit_target(*global),
+            };
+            let stored = sven_config::set_key(&path, key, value)?;
+            let shown = serde_yaml::to_string(&stored)?;
+            println!("{key} = {} ({})", shown.trim_end(), path.display());
+            Ok(())
+        }
+    }
+}
+
 // ── Index command handler ─────────────────────────────────────────────────────
 
 fn run_index_command(cmd: &IndexCommands) -> anyhow::Result<()> {
@@ -1075,13 +1274,13 @@ fn run_team_command(cmd: &TeamCommands) -> anyhow::Result<()> {
 }
 
 /// Print the list of saved conversations to stdout.
-fn print_chats(limit: usize) {
-    match history::list(Some(limit)) {
+fn print_chats(cfg: &sven_config::HistoryConfig, limit: usize) {
+    match history::list(cfg, Some(limit)) {
         Ok(entries) if entries.is_empty() => {
             println!("No saved conversations found.");
             println!(
                 "Conversations are stored in: {}",
-                history::history_dir().display()
+                history::history_dir(cfg).display()
             );
         }
         Ok(entries) => {
@@ -1109,7 +1308,7 @@ fn print_chats(limit: usize) {
                 );
             }
             println!("\nTotal: {} conversation(s)", entries.len());
-            println!("History dir: {}", history::history_dir().display());
+            println!("History dir: {}", history::history_dir(cfg).display());
         }
         Err(e) => {
             eprintln!("Error listing conversations: {e}");
@@ -1118,9 +1317,183 @@ fn print_chats(limit: usize) {
     }
 }
 
+/// Re-run the tool calls of a saved conversation and report differences
+/// from the recorded results.
+async fn replay_conversation(
+    id: &str,
+    apply: bool,
+    config: &sven_config::Config,
+) -> anyhow::Result<()> {
+    let path = history::resolve(&config.history, id)
+        .with_context(|| format!("resolving conversation id '{id}'"))?;
+    let content =
+        std::fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+    let mut records = if path.extension().is_some_and(|e| e == "jsonl") {
+        sven_input::parse_jsonl_full(&content)
+            .with_context(|| format!("parsing {}", path.display()))?
+            .records
+    } else {
+        sven_input::parse_conversation_records(&content)
+            .with_context(|| format!("parsing {}", path.display()))?
+            .records
+    };
+    sven_input::inline_image_files(&mut records)
+        .with_context(|| format!("loading images of {}", path.display()))?;
+
+    let reg = build_cli_tool_registry(config);
+    let calls = sven_ci::verify_tool_calls(&records, &reg, apply).await;
+    if calls.is_empty() {
+        println!("No tool calls in {}", path.display());
+        return Ok(());
+    }
+
+    let (mut reproduced, mut differ, mut skipped) = (0, 0, 0);
+    for (i, call) in calls.iter().enumerate() {
+        let status = if call.skipped() {
+            skipped += 1;
+            "skipped (not read-only; pass --apply to run)"
+        } else if call.reproduces() {
+            reproduced += 1;
+            "reproduces"
+        } else {
+            differ += 1;
+            "DIFFERS"
+        };
+        println!(
+            "[{}] {} ({}): {status}",
+            i + 1,
+            call.name,
+            call.tool_call_id
+        );
+        let diff = call.diff();
+        if !diff.is_empty() {
+            println!("{}", diff.trim_end());
+        }
+    }
+    println!(
+        "\n{} tool call(s): {reproduced} reproduce, {differ} differ, {skipped} skipped",
+        calls.len()
+    );
+    if differ > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Make a relative `history.dir` absolute: it is taken from the project
+/// root, or the current directory outside a project.
+fn resolve_history_dir(config: &mut sven_config::Config) {
+    if let Some(dir) = config.history.dir.as_mut().filter(|d| d.is_relative()) {
+        let root = find_project_root().unwrap_or_else(|_| std::path::PathBuf::from("."));
+        *dir = root.join(&*dir);
+    }
+}
+
+/// Delete saved conversations beyond the `--keep` / `--max-age-days` limits.
+fn prune_chats(
+    cfg: &sven_config::HistoryConfig,
+    keep: Option<usize>,
+    max_age_days: Option<u64>,
+) -> anyhow::Result<()> {
+    if keep.is_none() && max_age_days.is_none() {
+        anyhow::bail!("--prune needs --keep <N> and/or --max-age-days <DAYS>");
+    }
+    let removed =
+        history::vacuum(cfg, keep, max_age_days).context("pruning saved conversations")?;
+    for e in &removed {
+        println!("removed {}", e.id);
+    }
+    println!("\nPruned {} conversation(s)", removed.len());
+    Ok(())
+}
+
+/// Print the saved conversations containing `query` to stdout.
+fn print_chat_matches(cfg: &sven_config::HistoryConfig, query: &str, limit: usize) {
+    match history::search(cfg, query, Some(limit)) {
+        Ok(hits) if hits.is_empty() => {
+            println!("No saved conversations contain \"{query}\".");
+        }
+        Ok(hits) => {
+            println!(
+                "{:<45}  {:<16}  {:<5}  MATCH",
+                "ID (use with --resume)", "DATE", "TURN"
+            );
+            println!("{}", "-".repeat(95));
+            for hit in &hits {
+                let e = &hit.entry;
+                let display_id = if e.id.len() > 44 {
+                    format!("{}…", &e.id[..43])
+                } else {
+                    e.id.clone()
+                };
+                let date = e.timestamp.replace('T', " ");
+                let date = &date[..16.min(date.len())];
+                let turn = if hit.turn == 0 {
+                    "-".to_string()
+                } else {
+                    hit.turn.to_string()
+                };
+                println!(
+                    "{:<45}  {:<16}  {:<5}  {}",
+                    display_id, date, turn, hit.snippet
+                );
+            }
+            println!("\nTotal: {} matching conversation(s)", hits.len());
+        }
+        Err(e) => {
+            eprintln!("Error searching conversations: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Render a saved conversation in `format` and write it to `out` (or stdout).
+fn export_conversation(
+    cfg: &sven_config::HistoryConfig,
+    id: &str,
+    format: ExportFormatArg,
+    out: Option<&std::path::Path>,
+) -> anyhow::Result<()> {
+    let path =
+        history::resolve(cfg, id).with_context(|| format!("resolving conversation id '{id}'"))?;
+    let content =
+        std::fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+    let is_jsonl = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("jsonl"));
+    let (title, mut records) = if is_jsonl {
+        let parsed = sven_input::parse_jsonl_full(&content)
+            .with_context(|| format!("parsing {}", path.display()))?;
+        (None, parsed.records)
+    } else {
+        let parsed = sven_input::parse_conversation_records(&content)
+            .with_context(|| format!("parsing {}", path.display()))?;
+        (parsed.title, parsed.records)
+    };
+    sven_input::inline_image_files(&mut records)
+        .with_context(|| format!("loading images of {}", path.display()))?;
+    let title = title.unwrap_or_else(|| {
+        path.file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "Conversation".to_string())
+    });
+    let segments = sven_frontend::segments_from_records(records);
+
+    let rendered = match format {
+        ExportFormatArg::Html => sven_frontend::export::render_html(&title, &segments),
+    };
+    match out {
+        Some(out) => {
+            std::fs::write(out, rendered).with_context(|| format!("writing {}", out.display()))?
+        }
+        None => io::stdout().write_all(rendered.as_bytes())?,
+    }
+    Ok(())
+}
+
 /// Launch `fzf` and let the user pick a conversation to resume.
-fn pick_chat_with_fzf() -> anyhow::Result<Option<String>> {
-    let entries = history::list(None).context("listing saved conversations")?;
+fn pick_chat_with_fzf(cfg: &sven_config::HistoryConfig) -> anyhow::Result<Option<String>> {
+    let entries = history::list(cfg, None).context("listing saved conversations")?;
     if entries.is_empty() {
         anyhow::bail!(
             "No saved conversations found.\n\
@@ -1321,11 +1694,13 @@ async fn run_as_teammate(
                     trace_level: 0,
                     load_jsonl: None,
                     output_jsonl: None,
+                    output_image_dir: None,
                     rerun_toolcalls: false,
                     regen_system_prompt: false,
                     max_tokens_budget: None,
                     load_chat: None,
                     output_chat: None,
+                    json_errors: false,
                 };
 
                 let run_result = CiRunner::new(config.clone()).run(ci_opts).await;
@@ -1426,6 +1801,23 @@ async fn run_gui(cli: Cli, config: Arc<sven_config::Config>) -> anyhow::Result<(
     })
 }
 
+/// Turn `--continue` into `--resume <newest conversation id>`.
+fn resolve_continue(mut cli: Cli, cfg: &sven_config::HistoryConfig) -> anyhow::Result<Cli> {
+    if !cli.continue_last {
+        return Ok(cli);
+    }
+    let entry = history::latest(cfg)
+        .context("listing saved conversations")?
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "--continue: no saved conversations in {}",
+                history::history_dir(cfg).display()
+            )
+        })?;
+    cli.resume = Some(entry.id);
+    Ok(cli)
+}
+
 async fn run_ci(cli: Cli, config: Arc<sven_config::Config>) -> anyhow::Result<()> {
     // ── Detect project root ──────────────────────────────────────────────────
     let project_root = find_project_root().ok();
@@ -1438,8 +1830,8 @@ async fn run_ci(cli: Cli, config: Arc<sven_config::Config>) -> anyhow::Result<()
                  Use 'sven chats' to list available conversations."
             );
         }
-        let file_path =
-            history::resolve(id).with_context(|| format!("resolving conversation id '{id}'"))?;
+        let file_path = history::resolve(&config.history, id)
+            .with_context(|| format!("resolving conversation id '{id}'"))?;
 
         if let Some(prompt) = &cli.prompt {
             use std::fmt::Write as _;
@@ -1460,6 +1852,8 @@ async fn run_ci(cli: Cli, config: Arc<sven_config::Config>) -> anyhow::Result<()
             model_override: cli.model,
             file_path,
             content,
+            max_history_turns: cli.max_history_turns,
+            max_context_tokens: cli.max_context_tokens,
         };
         return ConversationRunner::new(config).run(opts).await;
     }
@@ -1504,6 +1898,7 @@ async fn run_ci(cli: Cli, config: Arc<sven_config::Config>) -> anyhow::Result<()
     } else if let Some(path) = &cli.file {
         let content = std::fs::read_to_string(path)
             .with_context(|| format!("reading input file {}", path.display()))?;
+        let content = expand_includes(&content, path)?;
         (content, cli.prompt.clone())
     } else if !is_stdin_tty() {
         let mut buf = String::new();
@@ -1564,11 +1959,13 @@ async fn run_ci(cli: Cli, config: Arc<sven_config::Config>) -> anyhow::Result<()
         trace_level: cli.verbose,
         load_jsonl,
         output_jsonl,
+        output_image_dir: cli.output_image_dir,
         rerun_toolcalls: cli.rerun_toolcalls,
         regen_system_prompt: cli.regen_system_prompt,
         max_tokens_budget: cli.max_tokens,
         load_chat,
         output_chat,
+        json_errors: cli.json_errors,
     };
 
     CiRunner::new(config).run(opts).await
@@ -1577,17 +1974,20 @@ async fn run_ci(cli: Cli, config: Arc<sven_config::Config>) -> anyhow::Result<()
 async fn run_tui(cli: Cli, config: Arc<sven_config::Config>) -> anyhow::Result<()> {
     use ratatui::crossterm::{
         event::{
-            DisableMouseCapture, EnableMouseCapture, KeyboardEnhancementFlags,
-            PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+            DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture,
+            KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
         },
         execute,
     };
 
+    // Reject a broken keymap before the terminal switches to raw mode.
+    sven_tui::Keymap::from_config(&config.tui.keymap).context("invalid tui.keymap in config")?;
+
     let initial_history = match &cli.resume {
         None => None,
         Some(id) => {
             let actual_id = if id.is_empty() {
-                match pick_chat_with_fzf()? {
+                match pick_chat_with_fzf(&config.history)? {
                     Some(picked) => picked,
                     None => return Ok(()),
                 }
@@ -1595,7 +1995,7 @@ async fn run_tui(cli: Cli, config: Arc<sven_config::Config>) -> anyhow::Result<(
                 id.clone()
             };
 
-            let (parsed, path) = history::load(&actual_id)
+            let (parsed, path) = history::load(&config.history, &actual_id)
                 .with_context(|| format!("loading conversation '{actual_id}'"))?;
 
             let segments: Vec<sven_tui::ChatSegment> = parsed
@@ -1632,6 +2032,12 @@ async fn run_tui(cli: Cli, config: Arc<sven_config::Config>) -> anyhow::Result<(
     // stderr fd avoids that.  Stderr still points to the real terminal here
     // because the dup2 redirect below has not happened yet.
     let _ = execute!(std::io::stderr(), EnableMouseCapture);
+    // Focus reports let the TUI notify on turn completion only while the
+    // terminal is in the background.
+    let notify_on_complete = config.tui.notify_on_complete;
+    if notify_on_complete {
+        let _ = execute!(std::io::stderr(), EnableFocusChange);
+    }
     let _ = execute!(
         std::io::stderr(),
         PushKeyboardEnhancementFlags(
@@ -1725,7 +2131,10 @@ async fn run_tui(cli: Cli, config: Arc<sven_config::Config>) -> anyhow::Result<(
 
     let initial_queue: Vec<QueuedMessage> = if let Some(path) = &cli.file {
         if !file_is_jsonl {
-            match std::fs::read_to_string(path) {
+            let content = std::fs::read_to_string(path)
+                .map_err(anyhow::Error::from)
+                .and_then(|c| expand_includes(&c, path).map_err(anyhow::Error::from));
+            match content {
                 Ok(content) => {
                     let (fm, body) = parse_frontmatter(&content);
                     let _ = fm; // Frontmatter used by runner, not TUI queue loader
@@ -1739,14 +2148,14 @@ async fn run_tui(cli: Cli, config: Arc<sven_config::Config>) -> anyhow::Result<(
                             ModelDirective::SwitchTo(Box::new(cfg))
                         });
                         // Resolve per-step mode string into an AgentMode
-                        let mode_transition = step.options.mode.as_deref().and_then(|m| match m {
-                            "research" => Some(AgentMode::Research),
-                            "plan" => Some(AgentMode::Plan),
-                            "agent" => Some(AgentMode::Agent),
-                            _ => None,
-                        });
+                        let mode_transition = step
+                            .options
+                            .mode
+                            .as_deref()
+                            .and_then(|name| config_ref.agent.mode(name));
                         q.push(QueuedMessage {
                             content: step.content,
+                            images: Vec::new(),
                             model_transition,
                             mode_transition,
                         });
@@ -1808,6 +2217,8 @@ async fn run_tui(cli: Cli, config: Arc<sven_config::Config>) -> anyhow::Result<(
         model_override: cli.model,
         jsonl_path: jsonl_save_path,
         jsonl_load_path,
+        jsonl_append: cli.jsonl_append,
+        output_image_dir: cli.output_image_dir,
         initial_queue,
         node_backend,
         chat_path: chat_load_path,
@@ -1819,6 +2230,9 @@ async fn run_tui(cli: Cli, config: Arc<sven_config::Config>) -> anyhow::Result<(
 
     let _ = execute!(std::io::stdout(), PopKeyboardEnhancementFlags);
     let _ = execute!(std::io::stdout(), DisableMouseCapture);
+    if notify_on_complete {
+        let _ = execute!(std::io::stdout(), DisableFocusChange);
+    }
     ratatui::restore();
 
     result
@@ -617,3 +617,90 @@ EOF
     assert_output_contains "timeout=60s"
     rm -f "${wf}"
 }
+
+# ── Conditional steps (when=) ─────────────────────────────────────────────────
+
+@test "06.56 when= condition that is false skips the step" {
+    local wf
+    wf="$(tmp_file)"
+    cat > "${wf}" << 'EOF2'
+## Check
+ping
+
+## Fix
+<!-- sven: when="contains(last_output, 'FAIL')" -->
+hello
+EOF2
+    run_split_output bash -c '"$BIN" --headless --model mock --file "$1"' -- "${wf}"
+    [ "${EXIT_CODE}" -eq 0 ]
+    [[ "${STDERR_OUT}" == *"[sven:step:skip] 2/2"* ]]
+    [[ "${STDOUT_OUT}" != *"hello from sven mock agent"* ]]
+    rm -f "${wf}"
+}
+
+@test "06.57 when= condition that is true runs the step" {
+    local wf
+    wf="$(tmp_file)"
+    cat > "${wf}" << 'EOF2'
+## Check
+ping
+
+## Greet
+<!-- sven: when="exit_code == 0 && contains(last_output, 'pong')" -->
+hello
+EOF2
+    run_split_output bash -c '"$BIN" --headless --model mock --file "$1"' -- "${wf}"
+    [ "${EXIT_CODE}" -eq 0 ]
+    [[ "${STDERR_OUT}" != *"[sven:step:skip]"* ]]
+    [[ "${STDOUT_OUT}" == *"hello from sven mock agent"* ]]
+    rm -f "${wf}"
+}
+
+@test "06.58 skipped step is marked skipped in json output" {
+    local wf
+    wf="$(tmp_file)"
+    cat > "${wf}" << 'EOF2'
+## Check
+ping
+
+## Fix
+<!-- sven: when="exit_code != 0" -->
+hello
+EOF2
+    run_split_output bash -c \
+        '"$BIN" --headless --model mock --output-format json --file "$1"' -- "${wf}"
+    [ "${EXIT_CODE}" -eq 0 ]
+    echo "${STDOUT_OUT}" | python3 -c \
+        'import json,sys; s=json.load(sys.stdin)["steps"]; assert [x["skipped"] for x in s] == [False, True]'
+    rm -f "${wf}"
+}
+
+@test "06.59 invalid when= condition exits with code 2" {
+    local wf
+    wf="$(tmp_file)"
+    cat > "${wf}" << 'EOF2'
+## Check
+<!-- sven: when="exitcode == 0" -->
+ping
+EOF2
+    run bash -c '"$BIN" --headless --model mock --file "$1" 2>/dev/null' -- "${wf}"
+    [ "${status}" -eq 2 ]
+    rm -f "${wf}"
+}
+
+@test "06.60 tail --no-follow prints a JSONL run as conversation markdown" {
+    local out
+    out="$(tmp_file)"
+    run bash -c 'echo "ping" | "$BIN" --headless --model mock --output-jsonl "$1" >/dev/null 2>&1' -- "${out}"
+    [ "${status}" -eq 0 ]
+    run_split_output "${BIN}" tail --no-follow "${out}"
+    [ "${EXIT_CODE}" -eq 0 ]
+    [[ "${STDOUT_OUT}" == *"## User"*"ping"* ]]
+    [[ "${STDOUT_OUT}" == *"## Sven"*"pong"* ]]
+    rm -f "${out}"
+}
+
+@test "06.61 tail --no-follow on a missing file fails" {
+    run "${BIN}" tail --no-follow /nonexistent/sven_tail.jsonl
+    [ "${status}" -ne 0 ]
+}
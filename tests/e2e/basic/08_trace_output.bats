@@ -7,7 +7,7 @@
 #   • [sven:tokens] always emitted (input/output tokens)
 #   • [sven:thinking] emitted at default verbosity (full content)
 #   • [sven:thinking] emits full content at all verbosity levels
-#   • Thinking does NOT appear in stdout (only stderr)
+#   • Thinking reaches stdout only as a ```sven-thinking block
 #   • Multi-tier pipe chains: history seeded correctly
 #   • Conversation format detection: workflow headings not re-processed
 #   • Pipe chain: each stage adds a new sven turn
@@ -83,10 +83,10 @@ load helpers
     [[ "${STDERR_OUT}" == *"Let me carefully reason"* ]]
 }
 
-@test "08.13 thinking content does NOT appear in stdout" {
+@test "08.13 thinking reaches stdout only as a sven-thinking block" {
     run_split_output bash -c 'echo "think deeply about this" | "$BIN" --headless --model mock'
     [[ "${STDOUT_OUT}" != *"[sven:thinking]"* ]]
-    [[ "${STDOUT_OUT}" != *"Let me carefully reason"* ]]
+    [[ "${STDOUT_OUT}" == *'```sven-thinking'*"Let me carefully reason"* ]]
 }
 
 @test "08.14 model reply appears in stdout even when thinking emitted" {
@@ -222,3 +222,21 @@ load helpers
     tool_result_line=$(echo "${STDERR_OUT}" | grep '\[sven:tool:result\]' | head -1)
     [[ "${tool_result_line}" == *"output="* ]]
 }
+
+# ── Thinking round-trip through conversation markdown ─────────────────────────
+
+@test "08.28 compact output omits thinking" {
+    run_split_output bash -c 'echo "think deeply about this" | "$BIN" --headless --model mock --output-format compact'
+    [[ "${STDOUT_OUT}" == *"forty-two"* ]]
+    [[ "${STDOUT_OUT}" != *"Let me carefully reason"* ]]
+}
+
+@test "08.29 conversation with a thinking block pipes into the next stage" {
+    run_split_output bash -c \
+        'echo "think deeply about this" \
+           | "$BIN" --headless --model mock 2>/dev/null \
+           | "$BIN" --headless --model mock "ping"'
+    [ "${EXIT_CODE}" -eq 0 ]
+    [[ "${STDOUT_OUT}" == *"pong"* ]]
+    [[ "${STDERR_OUT}" != *"skipping unknown"* ]]
+}
@@ -10,6 +10,7 @@
 #   • Exit code is non-zero on all hard failures
 #   • Missing --file path exits non-zero with a descriptive message
 #   • Unknown --model provider exits non-zero
+#   • --json-errors ends stderr with a JSON object matching the exit code
 #
 # NOTE: These tests deliberately do NOT load helpers.bash for the API-key tests
 # so that the dummy keys exported there do not interfere.  Each test creates its
@@ -137,3 +138,56 @@ load helpers
     # status 124 means timeout killed the process (hang detected).
     [ "${status}" -ne 124 ]
 }
+
+# ── --json-errors ─────────────────────────────────────────────────────────────
+
+# assert_json_error CODE KIND STEP — the last stderr line is the --json-errors
+# object with the given code, kind and step ("None" for no step).
+assert_json_error() {
+    [ "${EXIT_CODE}" -eq "$1" ]
+    echo "${STDERR_OUT}" | tail -n 1 | python3 -c '
+import json, sys
+code, kind, step = sys.argv[1:]
+e = json.load(sys.stdin)
+assert sorted(e) == ["code", "elapsed_secs", "kind", "message", "step"], e
+assert e["code"] == int(code) and e["kind"] == kind, e
+assert str(e["step"]) == step, e
+assert e["message"] and e["elapsed_secs"] >= 0, e
+' "$1" "$2" "$3"
+}
+
+@test "11.16 --json-errors reports an invalid when= condition" {
+    local wf
+    wf="$(tmp_file)"
+    printf '## Check\n<!-- sven: when="bogus(" -->\nping\n' > "${wf}"
+    run_split_output bash -c \
+        '"$BIN" --headless --model mock --json-errors --file "$1"' -- "${wf}"
+    assert_json_error 2 validation_error 1
+    rm -f "${wf}"
+}
+
+@test "11.17 --json-errors reports a piped conversation with no pending task" {
+    run_split_output bash -c \
+        'printf "## User\nping\n\n## Sven\npong\n" | "$BIN" --headless --model mock --json-errors'
+    assert_json_error 2 validation_error None
+}
+
+@test "11.18 --json-errors reports an unreadable --load-jsonl" {
+    run_split_output bash -c \
+        'echo "ping" | "$BIN" --headless --model mock --json-errors --load-jsonl /tmp/sven_bats_no_such_file.jsonl'
+    assert_json_error 2 validation_error None
+    [[ "${STDERR_OUT}" == *"sven_bats_no_such_file.jsonl"* ]]
+}
+
+@test "11.19 --json-errors reports an exhausted token budget" {
+    run_split_output bash -c \
+        'echo "ping" | "$BIN" --headless --model mock --json-errors --max-tokens 1'
+    assert_json_error 4 budget_exhausted 1
+}
+
+@test "11.20 failures print no JSON without --json-errors" {
+    run_split_output bash -c \
+        'echo "ping" | "$BIN" --headless --model mock --load-jsonl /tmp/sven_bats_no_such_file.jsonl'
+    [ "${EXIT_CODE}" -eq 2 ]
+    [[ "${STDERR_OUT}" != *'"kind"'* ]]
+}